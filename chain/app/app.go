@@ -102,6 +102,9 @@ import (
 
 const appName = "app"
 
+// peggyParamsUpgrade is the name of the upgrade plan that stores the peggy and minter params added since launch
+const peggyParamsUpgrade = "peggy-params"
+
 var (
 	// DefaultNodeHome sets the folder where the applcation data and configuration will be stored
 	DefaultNodeHome string
@@ -417,6 +420,11 @@ func NewMhubApp(
 
 	app.minterKeeper.SetPeggyKeeper(app.peggyKeeper)
 
+	app.upgradeKeeper.SetUpgradeHandler(peggyParamsUpgrade, func(ctx sdk.Context, plan upgradetypes.Plan) {
+		app.peggyKeeper.SetMissingParams(ctx)
+		app.minterKeeper.SetMissingParams(ctx)
+	})
+
 	var skipGenesisInvariants = cast.ToBool(appOpts.Get(crisis.FlagSkipGenesisInvariants))
 
 	app.mm = module.NewManager(
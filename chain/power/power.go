@@ -0,0 +1,77 @@
+// Package power converts validator power between the representations the bridges use. The Hub's valsets carry
+// each validator's consensus power normalized to a total of ContractTotal, which is what the Peggy contract
+// checks signatures against. The Minter multisig can't hold weights that large, it gets the same shares out of
+// MultisigTotal. Both sides truncate with integer math only, floating point could make validators disagree.
+//
+// The thresholds here have to match the contract's powerThreshold and the multisig's threshold, so the Hub, the
+// orchestrator and the relayers agree on when a set of signatures is enough.
+package power
+
+import (
+	"math"
+
+	sdk "github.com/cosmos/cosmos-sdk/types"
+)
+
+const (
+	// ContractTotal is the total power of a valset, the powers the Peggy contract checks signatures against
+	ContractTotal = uint64(math.MaxUint32)
+
+	// ContractThreshold is the Peggy contract's powerThreshold, 66% of ContractTotal. Signatures need strictly
+	// more power than this
+	ContractThreshold = uint64(2834678415)
+
+	// MultisigTotal is the total weight of a Minter multisig's members
+	MultisigTotal = 1000
+
+	// MultisigThreshold is the weight the Minter multisig needs to send, out of MultisigTotal
+	MultisigThreshold = 667
+
+	// MaxMultisigWeight is the most weight Minter lets a multisig member have
+	MaxMultisigWeight = 1023
+)
+
+// ContractPower is a validator's share of the total power, out of ContractTotal. The truncation loses less
+// than one unit per validator, so a valset's powers add up to a bit less than ContractTotal
+func ContractPower(power uint64, total uint64) uint64 {
+	return scale(power, ContractTotal, total)
+}
+
+// MultisigWeight is a member's share of the valset's total power, as a Minter multisig weight out of MultisigTotal
+func MultisigWeight(power uint64, total uint64) uint32 {
+	return uint32(scale(power, MultisigTotal, total))
+}
+
+// MultisigWeights are the weights of a valset's members by their powers, in the same order
+func MultisigWeights(powers []uint64) []uint32 {
+	var total uint64
+	for _, power := range powers {
+		total += power
+	}
+
+	weights := make([]uint32, len(powers))
+	for i, power := range powers {
+		weights[i] = MultisigWeight(power, total)
+	}
+
+	return weights
+}
+
+// PassesContractThreshold is true if signatures with this much contract power are enough for the Peggy contract
+func PassesContractThreshold(power uint64) bool {
+	return power > ContractThreshold
+}
+
+// PassesMultisigThreshold is true if signatures with this much weight are enough for the Minter multisig
+func PassesMultisigThreshold(weight uint64) bool {
+	return weight >= MultisigThreshold
+}
+
+// scale is power / total * to, in integer math wide enough not to overflow. A total of 0 has no shares
+func scale(power uint64, to uint64, total uint64) uint64 {
+	if total == 0 {
+		return 0
+	}
+
+	return sdk.NewUint(power).MulUint64(to).QuoUint64(total).Uint64()
+}
@@ -0,0 +1,146 @@
+package power
+
+import (
+	"encoding/json"
+	"io/ioutil"
+	"math"
+	mrand "math/rand"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestContractPower(t *testing.T) {
+	assert.Equal(t, ContractTotal, ContractPower(10, 10))
+	assert.Equal(t, ContractTotal/2, ContractPower(5, 10))
+	assert.Equal(t, uint64(1431655765), ContractPower(1, 3))
+	assert.Equal(t, uint64(0), ContractPower(0, 10))
+	assert.Equal(t, uint64(0), ContractPower(10, 0))
+
+	// consensus powers times ContractTotal overflow uint64
+	assert.Equal(t, ContractTotal/2, ContractPower(math.MaxUint64/2, math.MaxUint64-1))
+}
+
+func TestContractThreshold(t *testing.T) {
+	// the deployed contracts' powerThreshold is 66% of the total, rounded up
+	assert.Equal(t, (ContractTotal*66+99)/100, ContractThreshold)
+
+	assert.False(t, PassesContractThreshold(ContractThreshold))
+	assert.True(t, PassesContractThreshold(ContractThreshold+1))
+	assert.True(t, PassesContractThreshold(ContractPower(2, 3)))
+	assert.False(t, PassesContractThreshold(ContractPower(65, 100)))
+}
+
+func TestMultisigWeights(t *testing.T) {
+	assert.Equal(t, []uint32{750, 250}, MultisigWeights([]uint64{3, 1}))
+	assert.Equal(t, []uint32{333, 333, 333}, MultisigWeights([]uint64{1, 1, 1}))
+	assert.Equal(t, []uint32{1000}, MultisigWeights([]uint64{ContractTotal}))
+	assert.Equal(t, []uint32{0, 0}, MultisigWeights([]uint64{0, 0}))
+	assert.Empty(t, MultisigWeights(nil))
+
+	// truncating to contract powers is far below a weight's resolution, the weights stay the same
+	consensus := []uint64{120, 80, 45, 30, 3}
+	var total uint64
+	for _, power := range consensus {
+		total += power
+	}
+
+	contract := make([]uint64, len(consensus))
+	for i, power := range consensus {
+		contract[i] = ContractPower(power, total)
+	}
+
+	assert.Equal(t, MultisigWeights(consensus), MultisigWeights(contract))
+}
+
+func TestMultisigThreshold(t *testing.T) {
+	assert.True(t, MultisigThreshold <= MaxMultisigWeight)
+	assert.True(t, uint32(MultisigTotal) <= MaxMultisigWeight)
+	assert.False(t, PassesMultisigThreshold(uint64(MultisigThreshold)-1))
+	assert.True(t, PassesMultisigThreshold(uint64(MultisigThreshold)))
+
+	// two of three equal members can't send, their rounded down weights fall short
+	weights := MultisigWeights([]uint64{1, 1, 1})
+	assert.False(t, PassesMultisigThreshold(uint64(weights[0]+weights[1])))
+}
+
+// Random valsets, normalized like the Hub does, keep within the bounds both sides rely on
+func TestNormalizationBounds(t *testing.T) {
+	rand := mrand.New(mrand.NewSource(1))
+	for i := 0; i < 1000; i++ {
+		consensus := make([]uint64, 1+rand.Intn(100))
+		var total uint64
+		for j := range consensus {
+			consensus[j] = 1 + uint64(rand.Int63n(1<<40))
+			total += consensus[j]
+		}
+
+		var contractTotal uint64
+		contract := make([]uint64, len(consensus))
+		for j, power := range consensus {
+			contract[j] = ContractPower(power, total)
+			contractTotal += contract[j]
+		}
+
+		// every member loses less than one unit to truncation
+		assert.True(t, contractTotal <= ContractTotal)
+		assert.True(t, contractTotal > ContractTotal-uint64(len(contract)))
+
+		var weightTotal uint64
+		for _, weight := range MultisigWeights(contract) {
+			assert.True(t, weight <= MaxMultisigWeight)
+			weightTotal += uint64(weight)
+		}
+
+		assert.True(t, weightTotal <= MultisigTotal)
+		assert.True(t, weightTotal+uint64(len(contract)) > MultisigTotal)
+	}
+}
+
+type vectors struct {
+	ContractTotal     uint64 `json:"contract_total"`
+	ContractThreshold uint64 `json:"contract_threshold"`
+	Valsets           []struct {
+		ConsensusPowers []uint64 `json:"consensus_powers"`
+		ContractPowers  []uint64 `json:"contract_powers"`
+		MultisigWeights []uint32 `json:"multisig_weights"`
+		Signers         []int    `json:"signers"`
+		PassesContract  bool     `json:"passes_contract"`
+		PassesMultisig  bool     `json:"passes_multisig"`
+	} `json:"valsets"`
+}
+
+// testdata/vectors.json is checked by the orchestrator's tests too, so the Hub and the relayers agree on them
+func TestVectors(t *testing.T) {
+	data, err := ioutil.ReadFile("testdata/vectors.json")
+	require.NoError(t, err)
+
+	var v vectors
+	require.NoError(t, json.Unmarshal(data, &v))
+	assert.Equal(t, ContractTotal, v.ContractTotal)
+	assert.Equal(t, ContractThreshold, v.ContractThreshold)
+
+	for _, valset := range v.Valsets {
+		var total uint64
+		for _, power := range valset.ConsensusPowers {
+			total += power
+		}
+
+		contract := make([]uint64, len(valset.ConsensusPowers))
+		for i, power := range valset.ConsensusPowers {
+			contract[i] = ContractPower(power, total)
+		}
+		assert.Equal(t, valset.ContractPowers, contract, valset.ConsensusPowers)
+		weights := MultisigWeights(valset.ConsensusPowers)
+		assert.Equal(t, valset.MultisigWeights, weights, valset.ConsensusPowers)
+
+		var signedPower, signedWeight uint64
+		for _, signer := range valset.Signers {
+			signedPower += contract[signer]
+			signedWeight += uint64(weights[signer])
+		}
+		assert.Equal(t, valset.PassesContract, PassesContractThreshold(signedPower), valset.ConsensusPowers)
+		assert.Equal(t, valset.PassesMultisig, PassesMultisigThreshold(signedWeight), valset.ConsensusPowers)
+	}
+}
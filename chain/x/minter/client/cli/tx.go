@@ -165,9 +165,9 @@ func CmdUnsafeMinterAddr() *cobra.Command {
 
 func CmdWithdrawToMinter() *cobra.Command {
 	return &cobra.Command{
-		Use:   "withdraw [from_key_or_cosmos_address] [to_minter_address] [amount]",
-		Short: "Adds a new entry to the transaction pool to withdraw an amount from the Minter multisig",
-		Args:  cobra.ExactArgs(3),
+		Use:   "withdraw [from_key_or_cosmos_address] [to_minter_address] [amount] [memo]",
+		Short: "Adds a new entry to the transaction pool to withdraw an amount from the Minter multisig, the memo is optional",
+		Args:  cobra.RangeArgs(3, 4),
 		RunE: func(cmd *cobra.Command, args []string) error {
 			cliCtx, err := client.GetClientTxContext(cmd)
 			if err != nil {
@@ -186,6 +186,9 @@ func CmdWithdrawToMinter() *cobra.Command {
 				MinterDest: args[1],
 				Amount:     amount,
 			}
+			if len(args) == 4 {
+				msg.Memo = args[3]
+			}
 			if err := msg.ValidateBasic(); err != nil {
 				return err
 			}
@@ -194,3 +194,16 @@ func txStatusHandler(cliCtx client.Context, _ string) http.HandlerFunc {
 		rest.PostProcessResponse(w, cliCtx.WithHeight(height), res)
 	}
 }
+
+func txProgressHandler(cliCtx client.Context, _ string) http.HandlerFunc {
+	return func(w http.ResponseWriter, r *http.Request) {
+		vars := mux.Vars(r)
+
+		res, height, err := cliCtx.Query(fmt.Sprintf("custom/peggy/attestationProgress/%s", vars[txHash]))
+		if err != nil {
+			rest.WriteErrorResponse(w, http.StatusInternalServerError, err.Error())
+			return
+		}
+		rest.PostProcessResponse(w, cliCtx.WithHeight(height), res)
+	}
+}
@@ -59,4 +59,6 @@ func RegisterRoutes(cliCtx client.Context, r *mux.Router, storeName string) {
 	r.HandleFunc(fmt.Sprintf("/%s/batch_confirm/{%s}", storeName, nonce), allBatchConfirmsHandler(cliCtx, storeName)).Methods("GET")
 
 	r.HandleFunc(fmt.Sprintf("/%s/tx_status/{%s}", storeName, txHash), txStatusHandler(cliCtx, storeName)).Methods("GET")
+	// Gets which validators the claim of an Ethereum deposit is still waiting on
+	r.HandleFunc(fmt.Sprintf("/%s/tx_progress/{%s}", storeName, txHash), txProgressHandler(cliCtx, storeName)).Methods("GET")
 }
@@ -6,6 +6,7 @@ import (
 
 	"github.com/MinterTeam/mhub/chain/x/minter/keeper"
 	"github.com/MinterTeam/mhub/chain/x/minter/types"
+	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
 )
@@ -277,10 +278,14 @@ func handleMsgSendToMinter(ctx sdk.Context, keeper keeper.Keeper, msg *types.Msg
 	}
 
 	sender, _ := sdk.AccAddressFromBech32(msg.Sender)
-	txID, err := keeper.AddToOutgoingPool(ctx, sender, msg.MinterDest, "todo", msg.Amount) // todo: txhash
+	txHash := oracletypes.CurrentTxHash(ctx)
+	txID, err := keeper.AddToOutgoingPool(ctx, sender, msg.MinterDest, txHash, msg.Memo, msg.Amount)
 	if err != nil {
 		return &sdk.Result{}, nil // todo log
 	}
+	if msg.Memo != "" {
+		keeper.OracleKeeper().SetTxMemo(ctx, txHash, msg.Memo)
+	}
 	return &sdk.Result{
 		Data:   sdk.Uint64ToBigEndian(txID),
 		Events: ctx.EventManager().Events().ToABCIEvents(),
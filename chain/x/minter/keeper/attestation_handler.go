@@ -66,7 +66,7 @@ func (a *AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, clai
 
 				for _, val := range valset.Members {
 					amount := commission.Amount.Mul(sdk.NewIntFromUint64(val.Power)).Quo(sdk.NewIntFromUint64(totalPower))
-					_, err := a.keeper.AddToOutgoingPool(ctx, sdk.AccAddress{}, val.MinterAddress, "#commission", sdk.NewCoin(commission.Denom, amount))
+					_, err := a.keeper.AddToOutgoingPool(ctx, sdk.AccAddress{}, val.MinterAddress, "#commission", "", sdk.NewCoin(commission.Denom, amount))
 					if err != nil {
 						return sdkerrors.Wrap(err, "commission withdrawal")
 					}
@@ -147,7 +147,7 @@ func (a *AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, clai
 		}
 
 		if feeIsOk {
-			_, err := a.peggyKeeper.AddToOutgoingPool(ctx, receiver, claim.EthReceiver, claim.MinterSender, claim.TxHash, sdk.NewCoin(denom, claim.Amount).Sub(commission).Sub(fee), fee)
+			_, err := a.peggyKeeper.AddToOutgoingPool(ctx, receiver, claim.EthReceiver, claim.MinterSender, claim.TxHash, "", sdk.NewCoin(denom, claim.Amount).Sub(commission).Sub(fee), fee)
 			if err != nil {
 				return sdkerrors.Wrap(err, "withdraw")
 			}
@@ -161,7 +161,7 @@ func (a *AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, clai
 
 			a.keeper.oracleKeeper.SetTxStatus(ctx, claim.TxHash, oracletypes.TX_STATUS_REFUNDED, "")
 
-			_, err := a.keeper.AddToOutgoingPool(ctx, receiver, claim.MinterSender, claim.TxHash, sdk.NewCoin(denom, claim.Amount).Sub(commission))
+			_, err := a.keeper.AddToOutgoingPool(ctx, receiver, claim.MinterSender, claim.TxHash, "", sdk.NewCoin(denom, claim.Amount).Sub(commission))
 			if err != nil {
 				return sdkerrors.Wrap(err, "refund")
 			}
@@ -34,6 +34,7 @@ func (k Keeper) BuildOutgoingTXBatch(ctx sdk.Context, maxElements int) (*types.O
 		BatchNonce:   nextID,
 		MinterNonce:  minterNonce,
 		Transactions: selectedTx,
+		FoldOutputs:  k.GetParams(ctx).FoldBatchOutputs,
 	}
 	k.storeBatch(ctx, batch)
 
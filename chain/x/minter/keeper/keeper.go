@@ -3,10 +3,10 @@ package keeper
 import (
 	"fmt"
 	oraclekeeper "github.com/MinterTeam/mhub/chain/x/oracle/keeper"
-	"math"
 	"sort"
 	"strconv"
 
+	"github.com/MinterTeam/mhub/chain/power"
 	"github.com/MinterTeam/mhub/chain/x/minter/types"
 	"github.com/cosmos/cosmos-sdk/codec"
 	"github.com/cosmos/cosmos-sdk/store/prefix"
@@ -294,7 +294,7 @@ func (k Keeper) GetCurrentValset(ctx sdk.Context) *types.Valset {
 	}
 	// normalize power values
 	for i := range bridgeValidators {
-		bridgeValidators[i].Power = sdk.NewUint(bridgeValidators[i].Power).MulUint64(math.MaxUint32).QuoUint64(totalPower).Uint64()
+		bridgeValidators[i].Power = power.ContractPower(bridgeValidators[i].Power, totalPower)
 	}
 	return types.NewValset(uint64(ctx.BlockHeight()), bridgeValidators)
 }
@@ -314,6 +314,14 @@ func (k Keeper) setParams(ctx sdk.Context, ps *types.Params) {
 	k.paramSpace.SetParamSet(ctx, ps)
 }
 
+// SetMissingParams stores the defaults of params added after the chain started, GetParams panics on a missing key
+func (k Keeper) SetMissingParams(ctx sdk.Context) {
+	defaults := types.DefaultParams()
+	if !k.paramSpace.Has(ctx, types.ParamsFoldBatchOutputs) {
+		k.paramSpace.Set(ctx, types.ParamsFoldBatchOutputs, &defaults.FoldBatchOutputs)
+	}
+}
+
 // GetBridgeContractAddress returns the bridge contract address on ETH
 func (k Keeper) GetBridgeContractAddress(ctx sdk.Context) string {
 	var a string
@@ -16,7 +16,7 @@ import (
 // - burns the voucher for transfer amount and fees
 // - persists an OutgoingTx
 // - adds the TX to the `available` TX pool via a second index
-func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counterpartReceiver string, txHash string, amount sdk.Coin) (uint64, error) {
+func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counterpartReceiver string, txHash string, memo string, amount sdk.Coin) (uint64, error) {
 	totalInVouchers := sdk.Coins{amount}
 
 	// Ensure that the coin is a peggy voucher
@@ -43,6 +43,7 @@ func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counte
 		DestAddr: counterpartReceiver,
 		Amount:   amount,
 		TxHash:   txHash,
+		Memo:     memo,
 	}
 
 	// set the outgoing tx in the pool index
@@ -64,6 +65,7 @@ func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counte
 		sdk.NewAttribute(types.AttributeKeyOutgoingTXID, strconv.Itoa(int(nextID))),
 		sdk.NewAttribute(types.AttributeKeyNonce, fmt.Sprint(nextID)),
 		sdk.NewAttribute(types.AttributeKeyTxHash, txHash),
+		sdk.NewAttribute(types.AttributeKeyMemo, memo),
 	)
 	ctx.EventManager().EmitEvent(poolEvent)
 
@@ -26,6 +26,7 @@ type OutgoingTxBatch struct {
 	BatchNonce   uint64                `protobuf:"varint,1,opt,name=batch_nonce,json=batchNonce,proto3" json:"batch_nonce,omitempty"`
 	MinterNonce  uint64                `protobuf:"varint,2,opt,name=minter_nonce,json=minterNonce,proto3" json:"minter_nonce,omitempty"`
 	Transactions []*OutgoingTransferTx `protobuf:"bytes,3,rep,name=transactions,proto3" json:"transactions,omitempty"`
+	FoldOutputs  bool                  `protobuf:"varint,4,opt,name=fold_outputs,json=foldOutputs,proto3" json:"fold_outputs,omitempty"`
 }
 
 func (m *OutgoingTxBatch) Reset()         { *m = OutgoingTxBatch{} }
@@ -82,6 +83,13 @@ func (m *OutgoingTxBatch) GetTransactions() []*OutgoingTransferTx {
 	return nil
 }
 
+func (m *OutgoingTxBatch) GetFoldOutputs() bool {
+	if m != nil {
+		return m.FoldOutputs
+	}
+	return false
+}
+
 type OutgoingTransferTx struct {
 	Id          uint64      `protobuf:"varint,1,opt,name=id,proto3" json:"id,omitempty"`
 	Sender      string      `protobuf:"bytes,2,opt,name=sender,proto3" json:"sender,omitempty"`
@@ -166,29 +174,29 @@ func init() {
 func init() { proto.RegisterFile("minter/v1/batch.proto", fileDescriptor_bfc5e5d918be8aee) }
 
 var fileDescriptor_bfc5e5d918be8aee = []byte{
-	// 339 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x64, 0x91, 0xb1, 0x4e, 0xf3, 0x30,
-	0x14, 0x85, 0xeb, 0xb6, 0x7f, 0x7f, 0xd5, 0xa9, 0x40, 0xb2, 0x54, 0x88, 0x40, 0x84, 0xd2, 0xa9,
-	0x53, 0xac, 0x96, 0x85, 0xb5, 0x65, 0x81, 0x01, 0x90, 0xaa, 0x4c, 0x2c, 0x95, 0x93, 0x98, 0xda,
-	0x42, 0xb1, 0xab, 0xf8, 0xb6, 0x0a, 0x6f, 0xc1, 0xc8, 0x73, 0xf0, 0x14, 0x8c, 0x1d, 0x19, 0x51,
-	0xfb, 0x22, 0xc8, 0x71, 0x28, 0x20, 0x36, 0xfb, 0xf8, 0xf3, 0x39, 0xe7, 0xea, 0xe2, 0x6e, 0x26,
-	0x15, 0xf0, 0x9c, 0xae, 0x86, 0x34, 0x66, 0x90, 0x88, 0x70, 0x91, 0x6b, 0xd0, 0xa4, 0xed, 0xe4,
-	0x70, 0x35, 0x3c, 0x3a, 0xfe, 0x26, 0x18, 0x00, 0x37, 0xc0, 0x40, 0x6a, 0xe5, 0xb8, 0xfe, 0x0b,
-	0xc2, 0xfb, 0x77, 0x4b, 0x98, 0x6b, 0xa9, 0xe6, 0x51, 0x31, 0xb1, 0x0e, 0xe4, 0x14, 0x7b, 0xa5,
-	0xd5, 0x4c, 0x69, 0x95, 0x70, 0x1f, 0xf5, 0xd0, 0xa0, 0x39, 0xc5, 0xa5, 0x74, 0x6b, 0x15, 0x72,
-	0x86, 0x3b, 0xce, 0xb3, 0x22, 0xea, 0x25, 0xe1, 0x39, 0xcd, 0x21, 0x63, 0xdc, 0x81, 0x9c, 0x29,
-	0xc3, 0x12, 0x1b, 0x66, 0xfc, 0x46, 0xaf, 0x31, 0xf0, 0x46, 0x27, 0xe1, 0xae, 0x56, 0xb8, 0x4b,
-	0xb5, 0xd8, 0x03, 0xcf, 0xa3, 0x62, 0xfa, 0xeb, 0x4b, 0xff, 0x15, 0x61, 0xf2, 0x17, 0x22, 0x7b,
-	0xb8, 0x2e, 0xd3, 0xaa, 0x54, 0x5d, 0xa6, 0xe4, 0x00, 0xb7, 0x0c, 0x57, 0x29, 0xcf, 0xcb, 0x1a,
-	0xed, 0x69, 0x75, 0xb3, 0x25, 0x53, 0x6e, 0x60, 0xc6, 0xd2, 0x34, 0xe7, 0xc6, 0x36, 0xb0, 0xaf,
-	0x9e, 0xd5, 0xc6, 0x4e, 0x22, 0x17, 0xbb, 0x39, 0x40, 0x3f, 0x72, 0xe5, 0x37, 0x7b, 0x68, 0xe0,
-	0x8d, 0xba, 0x3f, 0x4a, 0xde, 0x94, 0xa7, 0x4b, 0x2d, 0xd5, 0xd7, 0x78, 0x91, 0x25, 0xc9, 0x21,
-	0xfe, 0x0f, 0xc5, 0x4c, 0x30, 0x23, 0xfc, 0x7f, 0x2e, 0x15, 0x8a, 0x2b, 0x66, 0xc4, 0xe4, 0xfa,
-	0x6d, 0x13, 0xa0, 0xf5, 0x26, 0x40, 0x1f, 0x9b, 0x00, 0x3d, 0x6f, 0x83, 0xda, 0x7a, 0x1b, 0xd4,
-	0xde, 0xb7, 0x41, 0xed, 0x9e, 0xce, 0x25, 0x88, 0x65, 0x1c, 0x26, 0x3a, 0xa3, 0xce, 0x36, 0xe2,
-	0x2c, 0xa3, 0x99, 0x58, 0xc6, 0x34, 0x11, 0x4c, 0x2a, 0x5a, 0xd0, 0x6a, 0x53, 0xf0, 0xb4, 0xe0,
-	0x26, 0x6e, 0x95, 0x1b, 0x3a, 0xff, 0x0c, 0x00, 0x00, 0xff, 0xff, 0x64, 0xe1, 0x7e, 0x3e, 0xe2,
-	0x01, 0x00, 0x00,
+	// 347 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x65, 0x91, 0x31, 0x4e, 0xc3, 0x30,
+	0x18, 0x85, 0x49, 0x5b, 0x0a, 0x75, 0x2a, 0x90, 0x2c, 0x15, 0x22, 0x10, 0xa5, 0x74, 0xea, 0x14,
+	0xab, 0x65, 0x61, 0x6d, 0x59, 0x60, 0x80, 0x4a, 0x51, 0x26, 0x96, 0xc8, 0x4d, 0xdc, 0x26, 0x82,
+	0xd8, 0x55, 0xfc, 0xa7, 0x0a, 0xb7, 0xe0, 0x2c, 0xac, 0x5c, 0x80, 0xb1, 0x23, 0x23, 0x82, 0x8b,
+	0x60, 0xc7, 0xa1, 0x80, 0x18, 0x7e, 0xc9, 0xfe, 0xf2, 0xf2, 0xde, 0xb3, 0x8d, 0x3a, 0x69, 0xc2,
+	0x81, 0x65, 0x64, 0x35, 0x24, 0x33, 0x0a, 0x61, 0xec, 0x2e, 0x33, 0x01, 0x02, 0xb7, 0x0c, 0x76,
+	0x57, 0xc3, 0xa3, 0xe3, 0x1f, 0x05, 0x05, 0x60, 0x12, 0x28, 0x24, 0x82, 0x1b, 0x5d, 0xff, 0xc5,
+	0x42, 0xfb, 0xd3, 0x1c, 0x16, 0x22, 0xe1, 0x0b, 0xbf, 0x98, 0x68, 0x07, 0x7c, 0x8a, 0xec, 0xd2,
+	0x2a, 0xe0, 0x82, 0x87, 0xcc, 0xb1, 0x7a, 0xd6, 0xa0, 0xe1, 0xa1, 0x12, 0xdd, 0x6a, 0x82, 0xcf,
+	0x50, 0xdb, 0x78, 0x56, 0x8a, 0x5a, 0xa9, 0xb0, 0x0d, 0x33, 0x92, 0x31, 0x6a, 0x43, 0x46, 0xb9,
+	0xa4, 0xa1, 0x0e, 0x93, 0x4e, 0xbd, 0x57, 0x1f, 0xd8, 0xa3, 0x13, 0x77, 0x53, 0xcb, 0xdd, 0xa4,
+	0x6a, 0xd9, 0x9c, 0x65, 0x7e, 0xe1, 0xfd, 0xf9, 0x45, 0xa7, 0xcc, 0xc5, 0x43, 0x14, 0x88, 0x1c,
+	0x96, 0x39, 0x48, 0xa7, 0xa1, 0x52, 0x76, 0x3d, 0x5b, 0xb3, 0xa9, 0x41, 0xfd, 0x67, 0x0b, 0xe1,
+	0xff, 0x3e, 0x78, 0x0f, 0xd5, 0x92, 0xa8, 0xea, 0xad, 0x56, 0xf8, 0x00, 0x35, 0x25, 0xe3, 0x11,
+	0xcb, 0xca, 0xa6, 0x2d, 0xaf, 0xda, 0xe9, 0x84, 0x48, 0xdd, 0x47, 0x40, 0xa3, 0x28, 0x63, 0x52,
+	0x97, 0xd4, 0x5f, 0x6d, 0xcd, 0xc6, 0x06, 0xe1, 0x8b, 0xcd, 0x51, 0x41, 0xdc, 0x33, 0x5e, 0x96,
+	0xb0, 0x47, 0x9d, 0x5f, 0xe7, 0xb8, 0x29, 0x57, 0x97, 0xaa, 0xc2, 0xf7, 0x0d, 0xf8, 0x5a, 0x89,
+	0x0f, 0xd1, 0x0e, 0x14, 0x41, 0x4c, 0x65, 0xec, 0x6c, 0x9b, 0x54, 0x28, 0xae, 0xd4, 0x6e, 0x72,
+	0xfd, 0xfa, 0xd1, 0xb5, 0xd6, 0x6a, 0xde, 0xd5, 0x3c, 0x7d, 0x76, 0xb7, 0xd6, 0x6a, 0xde, 0xd4,
+	0xdc, 0x91, 0x45, 0x02, 0x71, 0x3e, 0x73, 0x43, 0x91, 0x12, 0x63, 0xeb, 0x33, 0x9a, 0x92, 0x54,
+	0x31, 0x12, 0xc6, 0x34, 0xe1, 0xa4, 0x20, 0xd5, 0x63, 0xc2, 0xe3, 0x92, 0xc9, 0x59, 0xb3, 0x7c,
+	0xc4, 0xf3, 0x2f, 0x83, 0xbb, 0xdf, 0x32, 0x05, 0x02, 0x00, 0x00,
 }
 
 func (m *OutgoingTxBatch) Marshal() (dAtA []byte, err error) {
@@ -211,6 +219,16 @@ func (m *OutgoingTxBatch) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if m.FoldOutputs {
+		i--
+		if m.FoldOutputs {
+			dAtA[i] = 1
+		} else {
+			dAtA[i] = 0
+		}
+		i--
+		dAtA[i] = 0x20
+	}
 	if len(m.Transactions) > 0 {
 		for iNdEx := len(m.Transactions) - 1; iNdEx >= 0; iNdEx-- {
 			{
@@ -328,6 +346,9 @@ func (m *OutgoingTxBatch) Size() (n int) {
 			n += 1 + l + sovBatch(uint64(l))
 		}
 	}
+	if m.FoldOutputs {
+		n += 2
+	}
 	return n
 }
 
@@ -466,6 +487,26 @@ func (m *OutgoingTxBatch) Unmarshal(dAtA []byte) error {
 				return err
 			}
 			iNdEx = postIndex
+		case 4:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field FoldOutputs", wireType)
+			}
+			var v int
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowBatch
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				v |= int(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			m.FoldOutputs = bool(v != 0)
 		default:
 			iNdEx = preIndex
 			skippy, err := skipBatch(dAtA[iNdEx:])
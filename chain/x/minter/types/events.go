@@ -22,4 +22,5 @@ const (
 	AttributeKeyNonce           = "nonce"
 	AttributeKeyBridgeChainID   = "bridge_chain_id"
 	AttributeKeyTxHash          = "tx_hash"
+	AttributeKeyMemo            = "memo"
 )
@@ -57,6 +57,9 @@ var (
 
 	ParamsStopped = []byte("Stopped")
 
+	// ParamsFoldBatchOutputs stores whether batch multisends pay each destination once
+	ParamsFoldBatchOutputs = []byte("FoldBatchOutputs")
+
 	// Ensure that params implements the proper interface
 	_ paramtypes.ParamSet = &Params{}
 )
@@ -90,6 +93,7 @@ func DefaultParams() *Params {
 		SlashFractionClaim:            sdk.NewDec(1).Quo(sdk.NewDec(1000)),
 		SlashFractionConflictingClaim: sdk.NewDec(1).Quo(sdk.NewDec(1000)),
 		Stopped:                       false,
+		FoldBatchOutputs:              false,
 	}
 }
 
@@ -128,6 +132,7 @@ func (p *Params) ParamSetPairs() paramtypes.ParamSetPairs {
 		paramtypes.NewParamSetPair(ParamsStoreSlashFractionClaim, &p.SlashFractionClaim, validateSlashFractionClaim),
 		paramtypes.NewParamSetPair(ParamsStoreSlashFractionConflictingClaim, &p.SlashFractionConflictingClaim, validateSlashFractionConflictingClaim),
 		paramtypes.NewParamSetPair(ParamsStopped, &p.Stopped, validateStopped),
+		paramtypes.NewParamSetPair(ParamsFoldBatchOutputs, &p.FoldBatchOutputs, validateFoldBatchOutputs),
 	}
 }
 
@@ -255,3 +260,10 @@ func validateStopped(i interface{}) error {
 	}
 	return nil
 }
+
+func validateFoldBatchOutputs(i interface{}) error {
+	if _, ok := i.(bool); !ok {
+		return fmt.Errorf("invalid parameter type: %T", i)
+	}
+	return nil
+}
@@ -36,6 +36,7 @@ type Params struct {
 	SlashFractionClaim            github_com_cosmos_cosmos_sdk_types.Dec `protobuf:"bytes,9,opt,name=slash_fraction_claim,json=slashFractionClaim,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Dec" json:"slash_fraction_claim"`
 	SlashFractionConflictingClaim github_com_cosmos_cosmos_sdk_types.Dec `protobuf:"bytes,10,opt,name=slash_fraction_conflicting_claim,json=slashFractionConflictingClaim,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Dec" json:"slash_fraction_conflicting_claim"`
 	Stopped                       bool                                   `protobuf:"varint,11,opt,name=stopped,proto3" json:"stopped,omitempty"`
+	FoldBatchOutputs              bool                                   `protobuf:"varint,12,opt,name=fold_batch_outputs,json=foldBatchOutputs,proto3" json:"fold_batch_outputs,omitempty"`
 }
 
 func (m *Params) Reset()         { *m = Params{} }
@@ -120,6 +121,13 @@ func (m *Params) GetStopped() bool {
 	return false
 }
 
+func (m *Params) GetFoldBatchOutputs() bool {
+	if m != nil {
+		return m.FoldBatchOutputs
+	}
+	return false
+}
+
 // GenesisState struct
 type GenesisState struct {
 	Params           *Params `protobuf:"bytes,1,opt,name=params,proto3" json:"params,omitempty"`
@@ -181,39 +189,39 @@ func init() {
 func init() { proto.RegisterFile("minter/v1/genesis.proto", fileDescriptor_43fc00fc33749c12) }
 
 var fileDescriptor_43fc00fc33749c12 = []byte{
-	// 501 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xac, 0x53, 0x4d, 0x6f, 0xd3, 0x40,
-	0x14, 0x8c, 0x21, 0x4d, 0x9b, 0xed, 0x17, 0x6c, 0x53, 0x61, 0x21, 0xe1, 0x5a, 0x95, 0x28, 0x41,
-	0x02, 0x2f, 0x2d, 0x37, 0x6e, 0xa4, 0x08, 0xd4, 0x03, 0x08, 0x99, 0x0a, 0x24, 0x2e, 0x66, 0xed,
-	0xdd, 0xda, 0x2b, 0xec, 0x5d, 0xcb, 0xbb, 0x4d, 0xe0, 0xc6, 0x4f, 0xe0, 0x67, 0xf5, 0xd8, 0x23,
-	0x02, 0x54, 0xa1, 0xe4, 0x8f, 0xa0, 0xbc, 0xdd, 0x86, 0xb4, 0xe2, 0x54, 0xf5, 0x94, 0x78, 0xe6,
-	0xcd, 0x1b, 0x8f, 0xf5, 0x06, 0xdd, 0xa9, 0x84, 0x34, 0xbc, 0x21, 0xc3, 0x5d, 0x92, 0x73, 0xc9,
-	0xb5, 0xd0, 0x51, 0xdd, 0x28, 0xa3, 0x70, 0xd7, 0x12, 0xd1, 0x70, 0xf7, 0x6e, 0x2f, 0x57, 0xb9,
-	0x02, 0x94, 0x4c, 0xff, 0xd9, 0x81, 0xed, 0x5f, 0x0b, 0xa8, 0xf3, 0x96, 0x36, 0xb4, 0xd2, 0xf8,
-	0x01, 0x5a, 0xd7, 0x86, 0x36, 0x26, 0x31, 0x45, 0xc3, 0x75, 0xa1, 0x4a, 0xe6, 0x7b, 0xa1, 0xd7,
-	0x6f, 0xc7, 0x6b, 0x00, 0x1f, 0x9e, 0xa3, 0xf8, 0x3e, 0x5a, 0xb3, 0x6b, 0x13, 0xca, 0x58, 0xc3,
-	0xb5, 0xf6, 0x6f, 0x84, 0x5e, 0xbf, 0x1b, 0xaf, 0x5a, 0xf4, 0xb9, 0x05, 0xf1, 0x0e, 0x5a, 0x4f,
-	0x1b, 0xc1, 0x72, 0x9e, 0x64, 0x05, 0x15, 0x32, 0x11, 0xcc, 0xbf, 0x09, 0xfb, 0x56, 0x2d, 0xbc,
-	0x3f, 0x45, 0x0f, 0x18, 0xde, 0x43, 0x9b, 0x5a, 0xe4, 0x92, 0xb3, 0x64, 0x48, 0x4b, 0xcd, 0x8d,
-	0x4e, 0x46, 0x42, 0x32, 0x35, 0xf2, 0xdb, 0x30, 0xbd, 0x61, 0xc9, 0xf7, 0x96, 0xfb, 0x00, 0xd4,
-	0x9c, 0x26, 0xa5, 0x26, 0x2b, 0xf8, 0x4c, 0xb3, 0x30, 0xaf, 0x19, 0x58, 0xce, 0x69, 0x9e, 0xa0,
-	0x9e, 0xd3, 0x64, 0x25, 0x15, 0xd5, 0x4c, 0xd2, 0x01, 0x09, 0xb6, 0xdc, 0x3e, 0x50, 0x4e, 0x91,
-	0xa2, 0x4d, 0x5d, 0x52, 0x5d, 0x24, 0x47, 0x0d, 0xcd, 0x8c, 0x50, 0xd2, 0xbd, 0xa1, 0xbf, 0x18,
-	0x7a, 0xfd, 0x95, 0x41, 0x74, 0x72, 0xb6, 0xd5, 0xfa, 0x79, 0xb6, 0xb5, 0x93, 0x0b, 0x53, 0x1c,
-	0xa7, 0x51, 0xa6, 0x2a, 0x92, 0x29, 0x5d, 0x29, 0xed, 0x7e, 0x1e, 0x6b, 0xf6, 0x99, 0x98, 0xaf,
-	0x35, 0xd7, 0xd1, 0x0b, 0x9e, 0xc5, 0x1b, 0xb0, 0xec, 0xa5, 0xdb, 0x65, 0x03, 0xe1, 0x4f, 0xa8,
-	0x77, 0xc9, 0x03, 0x12, 0xf9, 0x4b, 0x57, 0xb2, 0xc0, 0x17, 0x2c, 0x20, 0xff, 0x7f, 0x1c, 0x20,
-	0xbf, 0xdf, 0xbd, 0x06, 0x07, 0xf8, 0x5c, 0x78, 0x84, 0xc2, 0xcb, 0x0e, 0x4a, 0x1e, 0x95, 0x22,
-	0x33, 0x42, 0xe6, 0xce, 0x0d, 0x5d, 0xc9, 0xed, 0xde, 0x45, 0xb7, 0x7f, 0x5b, 0xad, 0xb1, 0x8f,
-	0x16, 0xb5, 0x51, 0x75, 0xcd, 0x99, 0xbf, 0x1c, 0x7a, 0xfd, 0xa5, 0xf8, 0xfc, 0xf1, 0x59, 0xfb,
-	0xdb, 0xef, 0xb0, 0xb5, 0x9d, 0xa3, 0x95, 0x57, 0xb6, 0x0f, 0xef, 0x0c, 0x35, 0x1c, 0x3f, 0x44,
-	0x9d, 0x1a, 0x8e, 0x1d, 0x2e, 0x7b, 0x79, 0xef, 0x76, 0x34, 0xeb, 0x47, 0x64, 0x5b, 0x10, 0xbb,
-	0x01, 0xfc, 0x08, 0x61, 0xdb, 0x06, 0x77, 0xea, 0x52, 0xc9, 0x8c, 0xc3, 0xa1, 0xb7, 0xe3, 0x5b,
-	0xc0, 0xbc, 0x06, 0xe2, 0xcd, 0x14, 0x1f, 0x1c, 0x9c, 0x8c, 0x03, 0xef, 0x74, 0x1c, 0x78, 0x7f,
-	0xc6, 0x81, 0xf7, 0x7d, 0x12, 0xb4, 0x4e, 0x27, 0x41, 0xeb, 0xc7, 0x24, 0x68, 0x7d, 0x24, 0x73,
-	0x49, 0xad, 0xe2, 0x90, 0xd3, 0x8a, 0x54, 0xc5, 0x71, 0x4a, 0xa0, 0x17, 0xe4, 0x0b, 0x71, 0xed,
-	0x85, 0xd8, 0x69, 0x07, 0x8a, 0xf9, 0xf4, 0x6f, 0x00, 0x00, 0x00, 0xff, 0xff, 0xee, 0x5c, 0xb7,
-	0x47, 0xd4, 0x03, 0x00, 0x00,
+	// 512 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xad, 0x53, 0xcf, 0x6f, 0xd3, 0x30,
+	0x18, 0x6d, 0xa1, 0x74, 0xab, 0xd7, 0xfd, 0xc0, 0x0c, 0x61, 0x21, 0xb1, 0x55, 0x93, 0x18, 0x20,
+	0x41, 0xcc, 0xc6, 0x8d, 0x1b, 0x1d, 0x02, 0xed, 0xc0, 0x0f, 0x85, 0x09, 0x24, 0x2e, 0xc1, 0x89,
+	0xdd, 0xc4, 0x22, 0xb1, 0xa3, 0xd8, 0x6d, 0xe1, 0xc6, 0x95, 0x1b, 0x7f, 0xd6, 0x8e, 0x3b, 0x22,
+	0x84, 0x26, 0x04, 0xff, 0x08, 0xce, 0xe7, 0xac, 0xb4, 0x88, 0xd3, 0xc4, 0xc1, 0x4a, 0xf2, 0xde,
+	0xf7, 0xbe, 0xe7, 0xef, 0x53, 0x1e, 0xba, 0x56, 0x48, 0x65, 0x45, 0x45, 0x27, 0x7b, 0x34, 0x15,
+	0x4a, 0x18, 0x69, 0x82, 0xb2, 0xd2, 0x56, 0xe3, 0x9e, 0x27, 0x82, 0xc9, 0xde, 0xf5, 0xcd, 0x54,
+	0xa7, 0x1a, 0x50, 0x5a, 0xbf, 0xf9, 0x82, 0x9d, 0xcf, 0x5d, 0xd4, 0x7d, 0xc9, 0x2a, 0x56, 0x18,
+	0x7c, 0x0b, 0xad, 0x1b, 0xcb, 0x2a, 0x1b, 0xd9, 0xac, 0x12, 0x26, 0xd3, 0x39, 0x27, 0xed, 0x41,
+	0xfb, 0x76, 0x27, 0x5c, 0x03, 0xf8, 0xe8, 0x0c, 0xc5, 0x37, 0xd1, 0x9a, 0x6f, 0x1b, 0x31, 0xce,
+	0x1d, 0x68, 0xc8, 0x05, 0x57, 0xd7, 0x0b, 0x57, 0x3d, 0xfa, 0xc8, 0x83, 0x78, 0x17, 0xad, 0xc7,
+	0x95, 0xe4, 0xa9, 0x88, 0x92, 0x8c, 0x49, 0x15, 0x49, 0x4e, 0x2e, 0x42, 0xbf, 0x55, 0x0f, 0x1f,
+	0xd4, 0xe8, 0x21, 0xc7, 0xfb, 0xe8, 0xaa, 0x91, 0xa9, 0x12, 0x3c, 0x9a, 0xb0, 0xdc, 0x08, 0x6b,
+	0xa2, 0xa9, 0x54, 0x5c, 0x4f, 0x49, 0x07, 0xaa, 0xaf, 0x78, 0xf2, 0xb5, 0xe7, 0xde, 0x00, 0x35,
+	0xa7, 0x89, 0x99, 0x4d, 0x32, 0x31, 0xd3, 0x5c, 0x9a, 0xd7, 0x0c, 0x3d, 0xd7, 0x68, 0xee, 0xa3,
+	0xcd, 0x46, 0x93, 0xe4, 0x4c, 0x16, 0x33, 0x49, 0x17, 0x24, 0xd8, 0x73, 0x07, 0x40, 0x35, 0x8a,
+	0xd8, 0xb9, 0xe4, 0xcc, 0x64, 0xd1, 0xa8, 0x62, 0x89, 0x95, 0x5a, 0x35, 0x37, 0x24, 0x4b, 0x4e,
+	0xd2, 0x1f, 0x06, 0xc7, 0xa7, 0xdb, 0xad, 0x6f, 0xa7, 0xdb, 0xbb, 0xa9, 0xb4, 0xd9, 0x38, 0x0e,
+	0x12, 0x5d, 0xd0, 0x44, 0x9b, 0x42, 0x9b, 0xe6, 0x71, 0xcf, 0xf0, 0xf7, 0xd4, 0x7e, 0x2c, 0x85,
+	0x09, 0x1e, 0x8b, 0xc4, 0xdd, 0xaa, 0x6e, 0xf6, 0xa4, 0xe9, 0xe5, 0x07, 0xc2, 0xef, 0xdc, 0xad,
+	0x16, 0x3d, 0x60, 0x22, 0xb2, 0x7c, 0x2e, 0x0b, 0xbc, 0x60, 0x01, 0xf3, 0xff, 0xc3, 0x01, 0xe6,
+	0x27, 0xbd, 0xff, 0xe0, 0x00, 0xeb, 0xc2, 0x53, 0x34, 0xf8, 0xdb, 0x41, 0xab, 0x51, 0x2e, 0xdd,
+	0xbb, 0x4a, 0x1b, 0x37, 0x74, 0x2e, 0xb7, 0x1b, 0x8b, 0x6e, 0x7f, 0xba, 0x7a, 0x63, 0x82, 0x96,
+	0x8c, 0xd5, 0x65, 0x29, 0x38, 0x59, 0x71, 0xfd, 0x97, 0xc3, 0xb3, 0x4f, 0x7c, 0x17, 0xe1, 0x91,
+	0xfb, 0x57, 0xfd, 0x32, 0x23, 0x3d, 0xb6, 0xe5, 0xd8, 0x1a, 0xd2, 0x87, 0xa2, 0x8d, 0x9a, 0x81,
+	0xdd, 0xbc, 0xf0, 0xf8, 0xc3, 0xce, 0xa7, 0xef, 0x83, 0xd6, 0x4e, 0x8a, 0xfa, 0x4f, 0x7d, 0x7a,
+	0x5e, 0x59, 0x66, 0x05, 0xbe, 0x83, 0xba, 0x25, 0x44, 0x03, 0x72, 0xb0, 0xb2, 0x7f, 0x39, 0x98,
+	0xa5, 0x29, 0xf0, 0x99, 0x09, 0x9b, 0x82, 0xda, 0xce, 0x67, 0xa7, 0x09, 0x86, 0xd2, 0x2a, 0x11,
+	0x10, 0x8b, 0x4e, 0xb8, 0x01, 0xcc, 0x33, 0x20, 0x9e, 0xd7, 0xf8, 0xf0, 0xf0, 0xf8, 0xe7, 0x56,
+	0xfb, 0xc4, 0x9d, 0x1f, 0xee, 0x7c, 0xf9, 0xb5, 0xd5, 0x3a, 0x71, 0xe7, 0xab, 0x3b, 0x6f, 0xe9,
+	0xdc, 0x5e, 0xbc, 0xe2, 0x48, 0xb0, 0x82, 0x16, 0x0e, 0xa3, 0x90, 0x22, 0xfa, 0x81, 0x36, 0x59,
+	0x87, 0x25, 0xc5, 0x5d, 0x88, 0xf1, 0x83, 0xdf, 0x86, 0xc6, 0xdc, 0x61, 0x02, 0x04, 0x00, 0x00,
 }
 
 func (m *Params) Marshal() (dAtA []byte, err error) {
@@ -236,6 +244,16 @@ func (m *Params) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if m.FoldBatchOutputs {
+		i--
+		if m.FoldBatchOutputs {
+			dAtA[i] = 1
+		} else {
+			dAtA[i] = 0
+		}
+		i--
+		dAtA[i] = 0x60
+	}
 	if m.Stopped {
 		i--
 		if m.Stopped {
@@ -408,6 +426,9 @@ func (m *Params) Size() (n int) {
 	if m.Stopped {
 		n += 2
 	}
+	if m.FoldBatchOutputs {
+		n += 2
+	}
 	return n
 }
 
@@ -741,6 +762,26 @@ func (m *Params) Unmarshal(dAtA []byte) error {
 				}
 			}
 			m.Stopped = bool(v != 0)
+		case 12:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field FoldBatchOutputs", wireType)
+			}
+			var v int
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowGenesis
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				v |= int(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			m.FoldBatchOutputs = bool(v != 0)
 		default:
 			iNdEx = preIndex
 			skippy, err := skipGenesis(dAtA[iNdEx:])
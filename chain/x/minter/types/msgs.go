@@ -5,6 +5,7 @@ import (
 	"fmt"
 	"regexp"
 
+	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
 	"github.com/ethereum/go-ethereum/crypto"
@@ -176,6 +177,9 @@ func (msg MsgSendToMinter) ValidateBasic() error {
 	if err := ValidateMinterAddress(msg.MinterDest); err != nil {
 		return sdkerrors.Wrap(err, "ethereum address")
 	}
+	if err := oracletypes.ValidateMemo(msg.Memo); err != nil {
+		return sdkerrors.Wrap(ErrInvalid, err.Error())
+	}
 	// TODO validate fee is sufficient, fixed fee to start
 	return nil
 }
@@ -362,6 +362,7 @@ type MsgSendToMinter struct {
 	Sender     string     `protobuf:"bytes,1,opt,name=sender,proto3" json:"sender,omitempty"`
 	MinterDest string     `protobuf:"bytes,2,opt,name=minter_dest,json=minterDest,proto3" json:"minter_dest,omitempty"`
 	Amount     types.Coin `protobuf:"bytes,3,opt,name=amount,proto3" json:"amount"`
+	Memo       string     `protobuf:"bytes,4,opt,name=memo,proto3" json:"memo,omitempty"`
 }
 
 func (m *MsgSendToMinter) Reset()         { *m = MsgSendToMinter{} }
@@ -418,6 +419,13 @@ func (m *MsgSendToMinter) GetAmount() types.Coin {
 	return types.Coin{}
 }
 
+func (m *MsgSendToMinter) GetMemo() string {
+	if m != nil {
+		return m.Memo
+	}
+	return ""
+}
+
 // TODO: write response data here
 type MsgSendToMinterResponse struct {
 }
@@ -1139,72 +1147,72 @@ func init() {
 func init() { proto.RegisterFile("minter/v1/msgs.proto", fileDescriptor_74a1bf1de956ed0f) }
 
 var fileDescriptor_74a1bf1de956ed0f = []byte{
-	// 1031 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xcc, 0x57, 0xcf, 0x6f, 0xe3, 0x44,
-	0x14, 0x6e, 0x9a, 0x34, 0x25, 0x2f, 0x69, 0x77, 0x31, 0x55, 0xeb, 0xa4, 0x5d, 0xa7, 0xf5, 0xfe,
-	0x60, 0x2f, 0xd8, 0x74, 0x39, 0x70, 0x85, 0x76, 0x41, 0xf4, 0x50, 0x0e, 0xd9, 0x15, 0x48, 0x5c,
-	0xa2, 0x89, 0x3d, 0x6b, 0x5b, 0x1b, 0xcf, 0x04, 0xcf, 0x24, 0x5b, 0xb8, 0x01, 0x17, 0x24, 0x2e,
-	0x20, 0x8e, 0x88, 0xff, 0x67, 0x8f, 0x2b, 0x71, 0x41, 0x20, 0xad, 0x50, 0xcb, 0x1f, 0x82, 0x3c,
-	0x33, 0x99, 0x78, 0x1c, 0x37, 0xbb, 0xdc, 0xf6, 0x54, 0xfb, 0xbd, 0xe7, 0xf7, 0x7d, 0xf3, 0xde,
-	0x37, 0xef, 0x35, 0xb0, 0x93, 0x26, 0x84, 0xe3, 0xcc, 0x9f, 0x1d, 0xfb, 0x29, 0x8b, 0x98, 0x37,
-	0xc9, 0x28, 0xa7, 0x56, 0x4b, 0x5a, 0xbd, 0xd9, 0x71, 0xcf, 0x09, 0x28, 0x4b, 0x29, 0xf3, 0x47,
-	0x88, 0x61, 0x7f, 0x76, 0x3c, 0xc2, 0x1c, 0x1d, 0xfb, 0x01, 0x4d, 0x88, 0x0c, 0xed, 0xed, 0x44,
-	0x34, 0xa2, 0xe2, 0xd1, 0xcf, 0x9f, 0x94, 0xf5, 0x20, 0xa2, 0x34, 0x1a, 0x63, 0x1f, 0x4d, 0x12,
-	0x1f, 0x11, 0x42, 0x39, 0xe2, 0x09, 0x25, 0x2a, 0xbd, 0xfb, 0x53, 0x0d, 0x6e, 0x9e, 0xb3, 0xe8,
-	0x0b, 0x34, 0x66, 0x98, 0x9f, 0x52, 0xf2, 0x24, 0xc9, 0x52, 0x6b, 0x07, 0x36, 0x08, 0x25, 0x01,
-	0xb6, 0x6b, 0x87, 0xb5, 0xfb, 0x8d, 0x81, 0x7c, 0xb1, 0x0e, 0xa0, 0x35, 0x43, 0xe3, 0x24, 0x44,
-	0x9c, 0x66, 0xf6, 0xfa, 0x61, 0xed, 0x7e, 0x6b, 0xb0, 0x30, 0x58, 0x77, 0x61, 0x5b, 0x32, 0x1d,
-	0xa2, 0x30, 0xcc, 0x30, 0x63, 0x76, 0x5d, 0x84, 0x6c, 0x49, 0xeb, 0xc7, 0xd2, 0x98, 0x27, 0x61,
-	0x49, 0x44, 0x10, 0x9f, 0x66, 0xd8, 0x6e, 0xc8, 0x24, 0xda, 0xe0, 0xf6, 0xc0, 0x2e, 0x93, 0x19,
-	0x60, 0x36, 0xa1, 0x84, 0x61, 0xf7, 0xfd, 0x02, 0xd1, 0x01, 0xfe, 0x7a, 0x8a, 0x19, 0xcf, 0xb3,
-	0x65, 0xf2, 0x11, 0x67, 0x82, 0x6c, 0x6b, 0xb0, 0x30, 0x18, 0xd9, 0xd4, 0x17, 0x3a, 0xdb, 0x53,
-	0x78, 0xe7, 0x9c, 0x45, 0x8f, 0x30, 0x3f, 0x37, 0xe8, 0xd9, 0xb0, 0x39, 0xa7, 0x2f, 0xd3, 0xcd,
-	0x5f, 0x5f, 0x71, 0x7a, 0xe3, 0x58, 0xf5, 0xf2, 0xb1, 0x6e, 0xc1, 0x7e, 0x05, 0x98, 0xe6, 0xf2,
-	0x43, 0x0d, 0x6e, 0x08, 0x3f, 0x09, 0x1f, 0x53, 0x19, 0x62, 0xed, 0x42, 0x93, 0x61, 0x12, 0xea,
-	0x63, 0xa9, 0x37, 0xab, 0x0f, 0x6d, 0x55, 0xe6, 0x10, 0x33, 0xae, 0x88, 0x80, 0x34, 0x3d, 0xcc,
-	0x4b, 0xf2, 0x21, 0x34, 0x51, 0x4a, 0xa7, 0x84, 0x0b, 0x1a, 0xed, 0x07, 0x5d, 0x4f, 0xaa, 0xc6,
-	0xcb, 0x55, 0xe3, 0x29, 0xd5, 0x78, 0xa7, 0x34, 0x21, 0x27, 0x8d, 0xe7, 0x2f, 0xfb, 0x6b, 0x03,
-	0x15, 0xee, 0x76, 0x61, 0xaf, 0x44, 0x42, 0x13, 0xf4, 0x05, 0x3f, 0x55, 0xc2, 0x13, 0xc4, 0x83,
-	0xf8, 0x15, 0x95, 0x97, 0xb9, 0x8a, 0x1f, 0xe8, 0x5c, 0x3f, 0xca, 0xc3, 0xaa, 0xee, 0xca, 0x64,
-	0xd5, 0x7a, 0xbb, 0x0d, 0x4a, 0x3b, 0xc3, 0xbc, 0x92, 0x78, 0x5e, 0xf5, 0x8e, 0x34, 0x3e, 0x12,
-	0x36, 0xb3, 0x2d, 0xf5, 0x95, 0x6d, 0x59, 0x52, 0x9b, 0x64, 0x59, 0x64, 0xa2, 0x59, 0xfe, 0xbe,
-	0x2e, 0x58, 0x3e, 0xc4, 0x13, 0xca, 0x12, 0x7e, 0x3a, 0x46, 0x49, 0x9a, 0x97, 0x1e, 0xcf, 0x30,
-	0xe1, 0xc3, 0x22, 0x57, 0x10, 0xa6, 0xcf, 0x05, 0xe1, 0x5d, 0x68, 0xe6, 0xb7, 0xf1, 0x2c, 0x14,
-	0x4c, 0x1b, 0x03, 0xf5, 0x66, 0x7d, 0x6a, 0xb4, 0xa4, 0x75, 0xe2, 0xe5, 0x75, 0xff, 0xeb, 0x65,
-	0xff, 0x5e, 0x94, 0xf0, 0x78, 0x3a, 0xf2, 0x02, 0x9a, 0xfa, 0xea, 0x6a, 0xcb, 0x3f, 0xef, 0xb1,
-	0xf0, 0xa9, 0xcf, 0xbf, 0x99, 0x60, 0xe6, 0x9d, 0x11, 0x3e, 0xef, 0x50, 0xb1, 0x20, 0x52, 0x1a,
-	0x0d, 0xa3, 0x20, 0x52, 0x20, 0xef, 0xc2, 0x0d, 0x99, 0x64, 0x98, 0xe1, 0x00, 0x27, 0x33, 0x9c,
-	0xd9, 0x1b, 0x22, 0x6c, 0x5b, 0x9a, 0x07, 0xca, 0x6a, 0xb9, 0xd0, 0xa1, 0x59, 0x10, 0x63, 0xc6,
-	0x33, 0x51, 0xbc, 0xa6, 0x4c, 0x56, 0xb4, 0x59, 0x7b, 0xb0, 0xc9, 0x2f, 0x86, 0x31, 0x62, 0xb1,
-	0xbd, 0x29, 0x65, 0xc8, 0x2f, 0x3e, 0x43, 0x2c, 0x76, 0x7f, 0x91, 0x63, 0xe3, 0xcb, 0x84, 0xc7,
-	0x61, 0x86, 0x9e, 0xbd, 0x66, 0x81, 0xfa, 0xd0, 0x1e, 0xe5, 0x65, 0x56, 0x01, 0xb2, 0x4a, 0x20,
-	0x4c, 0x32, 0xa0, 0xcc, 0xa9, 0xb1, 0x9a, 0xd3, 0x86, 0xc1, 0x49, 0x5e, 0x77, 0x83, 0x92, 0xee,
-	0xa7, 0x6c, 0x75, 0xb1, 0x9d, 0xda, 0x75, 0x01, 0xdb, 0x8b, 0x99, 0xf3, 0x7a, 0xe7, 0x38, 0x82,
-	0xce, 0x4c, 0xc4, 0x1b, 0x07, 0x69, 0x4b, 0x5b, 0xf5, 0x49, 0xea, 0xcb, 0x27, 0x71, 0x6d, 0xd8,
-	0x35, 0x91, 0x35, 0xa7, 0xbf, 0xd7, 0xe1, 0x6d, 0x7d, 0x19, 0x3f, 0xe1, 0xf1, 0x1b, 0x22, 0xc0,
-	0x8f, 0xa0, 0xfe, 0x04, 0xab, 0x8b, 0xf4, 0xbf, 0x93, 0xe4, 0x9f, 0x2e, 0x4b, 0x78, 0xa3, 0x42,
-	0xc2, 0x47, 0xd0, 0xc1, 0x3c, 0x5e, 0xe8, 0x57, 0x2a, 0xb3, 0x8d, 0x79, 0x7c, 0xad, 0x78, 0x37,
-	0x57, 0x0b, 0xe5, 0x2d, 0x43, 0x28, 0xfb, 0xd0, 0x5d, 0x2a, 0xee, 0xbc, 0xf4, 0x0f, 0x7e, 0x6b,
-	0x41, 0xfd, 0x9c, 0x45, 0x16, 0x87, 0x2d, 0x73, 0x29, 0xee, 0x7b, 0x7a, 0x13, 0x7b, 0xe5, 0x25,
-	0xd5, 0xbb, 0xbd, 0xc2, 0xa9, 0xbb, 0x7a, 0xf4, 0xfd, 0x1f, 0xff, 0xfe, 0xba, 0xbe, 0xef, 0x76,
-	0xfd, 0xc5, 0xa6, 0x57, 0x3a, 0x0a, 0x14, 0x88, 0x46, 0x9d, 0x6f, 0xb8, 0x4a, 0x54, 0xe5, 0xac,
-	0x46, 0x2d, 0x6f, 0xba, 0x15, 0xa8, 0x6a, 0x60, 0x5b, 0xdf, 0xd5, 0xe0, 0xe6, 0xd2, 0x2a, 0x74,
-	0xcc, 0xe4, 0x65, 0x7f, 0xef, 0xde, 0x6a, 0xbf, 0xc6, 0xbf, 0x2b, 0xf0, 0xfb, 0xee, 0xad, 0x02,
-	0x7e, 0x0e, 0x6e, 0xfe, 0xb7, 0x60, 0x11, 0x68, 0xe9, 0x8e, 0x58, 0xbd, 0x72, 0xee, 0xc5, 0x52,
-	0xea, 0xb9, 0xd7, 0xfb, 0x56, 0x9e, 0x39, 0x17, 0xdc, 0x90, 0x53, 0x85, 0x6b, 0x4d, 0xa0, 0x63,
-	0x2c, 0xb4, 0x12, 0x64, 0xd1, 0x57, 0x86, 0xac, 0xdc, 0x6b, 0x87, 0x02, 0xb2, 0xe7, 0xda, 0x05,
-	0x48, 0x55, 0xdf, 0xa1, 0x98, 0x70, 0x39, 0xa2, 0xb1, 0xf5, 0x4a, 0x88, 0x45, 0x5f, 0x19, 0xb1,
-	0x72, 0x47, 0x55, 0x21, 0x2a, 0x1d, 0x2d, 0x10, 0x8d, 0x0d, 0x56, 0x42, 0x2c, 0xfa, 0xca, 0x88,
-	0x95, 0xa3, 0xb2, 0x0a, 0x31, 0x94, 0x81, 0xc3, 0x40, 0x20, 0x70, 0xd8, 0x32, 0x77, 0x42, 0x49,
-	0xbf, 0x86, 0xb3, 0xac, 0xdf, 0xea, 0xd1, 0x5d, 0xd5, 0xcb, 0x67, 0x2a, 0x52, 0xa1, 0x7e, 0x0b,
-	0xdb, 0xa5, 0x51, 0x79, 0x50, 0x25, 0x92, 0xb9, 0xb7, 0x77, 0x67, 0x95, 0x57, 0x03, 0xdf, 0x11,
-	0xc0, 0x8e, 0x7b, 0x50, 0x21, 0xa2, 0x7c, 0x3e, 0x49, 0xec, 0x31, 0xb4, 0x8b, 0xbb, 0xa3, 0x5b,
-	0x39, 0x08, 0x04, 0xea, 0xd1, 0xb5, 0x2e, 0x0d, 0xd9, 0x17, 0x90, 0x5d, 0x77, 0xaf, 0x62, 0x42,
-	0xe4, 0x81, 0x27, 0x67, 0xcf, 0x2f, 0x9d, 0xda, 0x8b, 0x4b, 0xa7, 0xf6, 0xcf, 0xa5, 0x53, 0xfb,
-	0xf9, 0xca, 0x59, 0x7b, 0x71, 0xe5, 0xac, 0xfd, 0x79, 0xe5, 0xac, 0x7d, 0xe5, 0x17, 0xc6, 0xb0,
-	0xbc, 0x0d, 0x8f, 0x31, 0x4a, 0xfd, 0x34, 0x9e, 0x8e, 0xfc, 0x20, 0x46, 0x09, 0xf1, 0x2f, 0xe6,
-	0x49, 0xc5, 0x4c, 0x1e, 0x35, 0xc5, 0x0f, 0x80, 0x0f, 0xfe, 0x0b, 0x00, 0x00, 0xff, 0xff, 0x01,
-	0x3b, 0xeb, 0x44, 0x77, 0x0c, 0x00, 0x00,
+	// 1027 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xcd, 0x57, 0x4d, 0x6f, 0xdb, 0x46,
+	0x10, 0x8d, 0x2c, 0xd9, 0x8e, 0x56, 0x8e, 0x93, 0x30, 0x46, 0x4c, 0xd1, 0x8e, 0x14, 0x33, 0x9f,
+	0x97, 0x92, 0x71, 0x7a, 0xe8, 0x35, 0xb5, 0xd3, 0xa2, 0x3e, 0xa4, 0x07, 0x25, 0x68, 0x80, 0x5e,
+	0x84, 0x15, 0xb9, 0x91, 0x88, 0x88, 0x5c, 0x95, 0x5c, 0x2b, 0x6e, 0x6f, 0xc9, 0x29, 0x40, 0x2e,
+	0x2d, 0x7a, 0xe8, 0x21, 0xe8, 0xff, 0xc9, 0x31, 0x40, 0x2f, 0x45, 0x0b, 0x04, 0x45, 0xdb, 0x1f,
+	0xd2, 0xe5, 0xce, 0x6a, 0xc5, 0x5d, 0xad, 0x95, 0xf4, 0x96, 0x03, 0x61, 0x72, 0x66, 0x38, 0xef,
+	0xed, 0xcc, 0xe3, 0x8c, 0x8c, 0xb6, 0xd2, 0x24, 0x63, 0x24, 0x0f, 0xa7, 0xfb, 0x61, 0x5a, 0x0c,
+	0x8b, 0x60, 0x92, 0x53, 0x46, 0x9d, 0x26, 0x58, 0x83, 0xe9, 0xbe, 0xd7, 0x89, 0x68, 0x91, 0xd2,
+	0x22, 0x1c, 0xe0, 0x82, 0xf0, 0xa8, 0x01, 0x61, 0x78, 0x3f, 0x8c, 0x68, 0x92, 0x41, 0xa8, 0xb7,
+	0x35, 0xa4, 0x43, 0x2a, 0x6e, 0xc3, 0xf2, 0x4e, 0x5a, 0x77, 0x87, 0x94, 0x0e, 0xc7, 0x24, 0xc4,
+	0x93, 0x24, 0xc4, 0x59, 0x46, 0x19, 0x66, 0x09, 0xcd, 0x64, 0x7a, 0xff, 0x55, 0x0d, 0x5d, 0x78,
+	0x50, 0x0c, 0xbf, 0xc1, 0xe3, 0x82, 0xb0, 0x43, 0x9a, 0x3d, 0x49, 0xf2, 0xd4, 0xd9, 0x42, 0xab,
+	0x19, 0xcd, 0x22, 0xe2, 0xd6, 0xae, 0xd6, 0x6e, 0x37, 0x7a, 0xf0, 0xe0, 0xec, 0xa2, 0xe6, 0x14,
+	0x8f, 0x93, 0x18, 0x33, 0x9a, 0xbb, 0x2b, 0xdc, 0xd3, 0xec, 0xcd, 0x0d, 0xce, 0x0d, 0xb4, 0x09,
+	0x4c, 0xfb, 0x38, 0x8e, 0x73, 0x52, 0x14, 0x6e, 0x5d, 0x84, 0x9c, 0x03, 0xeb, 0xe7, 0x60, 0x2c,
+	0x93, 0x14, 0xc9, 0x30, 0xc3, 0xec, 0x38, 0x27, 0x6e, 0x03, 0x92, 0x28, 0x83, 0xef, 0x21, 0xd7,
+	0x24, 0xd3, 0x23, 0xc5, 0x84, 0xd3, 0x25, 0xfe, 0x9d, 0x0a, 0xd1, 0x1e, 0xf9, 0xee, 0x98, 0x14,
+	0xac, 0xcc, 0x96, 0xc3, 0x2d, 0xc9, 0x05, 0x59, 0x9e, 0x4d, 0x19, 0xb4, 0x6c, 0xf2, 0x0d, 0x95,
+	0xed, 0x29, 0xba, 0xc4, 0x7d, 0x0f, 0x09, 0x7b, 0xa0, 0xd1, 0x73, 0xd1, 0xfa, 0x8c, 0x3e, 0xa4,
+	0x9b, 0x3d, 0xbe, 0xe7, 0xf4, 0xda, 0xb1, 0xea, 0xe6, 0xb1, 0xae, 0xa0, 0x1d, 0x0b, 0x98, 0xe2,
+	0xf2, 0x4b, 0x0d, 0x9d, 0x17, 0xfe, 0x2c, 0x7e, 0x44, 0x21, 0xc4, 0xb9, 0x8c, 0xd6, 0x0a, 0xfe,
+	0xac, 0x8e, 0x25, 0x9f, 0x9c, 0x2e, 0x6a, 0xc9, 0x32, 0xc7, 0xfc, 0x38, 0x92, 0x08, 0x02, 0xd3,
+	0xfd, 0xb2, 0x24, 0x9f, 0xa1, 0x35, 0x9c, 0xd2, 0xe3, 0x8c, 0x09, 0x1a, 0xad, 0xbb, 0xed, 0x00,
+	0x54, 0x13, 0x94, 0xaa, 0x09, 0xa4, 0x6a, 0x82, 0x43, 0xae, 0x9a, 0x83, 0xc6, 0x9b, 0x77, 0xdd,
+	0x33, 0x3d, 0x19, 0xee, 0x38, 0xa8, 0x91, 0x92, 0x94, 0xca, 0xa6, 0x88, 0x7b, 0xbf, 0x8d, 0xb6,
+	0x0d, 0x62, 0x8a, 0x74, 0x28, 0x38, 0xcb, 0xb2, 0x1e, 0x60, 0x16, 0x8d, 0xde, 0xd3, 0x0d, 0xc8,
+	0x55, 0x7d, 0x41, 0xe5, 0x7a, 0x09, 0x05, 0x90, 0x1d, 0x87, 0x64, 0x76, 0x0d, 0x5e, 0x43, 0x52,
+	0x4f, 0xfd, 0xb2, 0xba, 0x64, 0xd6, 0x89, 0x0d, 0x30, 0x3e, 0x14, 0x36, 0xbd, 0x55, 0xf5, 0xa5,
+	0xad, 0x5a, 0x50, 0x20, 0xb0, 0xac, 0x32, 0x51, 0x2c, 0x7f, 0x5d, 0x11, 0x2c, 0xef, 0x93, 0x09,
+	0x2d, 0x12, 0x76, 0x38, 0xc6, 0x49, 0x5a, 0xb6, 0x83, 0x4c, 0x49, 0xc6, 0xfa, 0x55, 0xae, 0x48,
+	0x98, 0xbe, 0x16, 0x84, 0x79, 0x1f, 0xcb, 0x2f, 0xf4, 0x28, 0x16, 0x4c, 0x1b, 0x3d, 0xf9, 0xe4,
+	0x7c, 0xa9, 0xb5, 0xa9, 0x79, 0x10, 0x94, 0xbd, 0xf8, 0xe3, 0x5d, 0xf7, 0xe6, 0x30, 0x61, 0xa3,
+	0xe3, 0x01, 0x6f, 0x5a, 0x1a, 0xca, 0xcf, 0x1d, 0xfe, 0x7c, 0x52, 0xc4, 0x4f, 0x43, 0xf6, 0xfd,
+	0x84, 0x14, 0xc1, 0x51, 0xc6, 0x54, 0xd7, 0x2a, 0x05, 0x01, 0xb9, 0x34, 0xb4, 0x82, 0x80, 0x68,
+	0x6e, 0xa1, 0xf3, 0x90, 0xa4, 0x9f, 0x93, 0x88, 0x24, 0x53, 0x1e, 0xb6, 0x2a, 0xc2, 0x36, 0xc1,
+	0xdc, 0x93, 0x56, 0xc7, 0x47, 0x1b, 0x34, 0x8f, 0x46, 0xbc, 0x41, 0xb9, 0x28, 0xde, 0x1a, 0x24,
+	0xab, 0xda, 0x9c, 0x6d, 0xb4, 0xce, 0x4e, 0xfa, 0x23, 0x5c, 0x8c, 0xdc, 0x75, 0x90, 0x26, 0x3b,
+	0xf9, 0x8a, 0x3f, 0xf9, 0x3f, 0xc1, 0x28, 0x79, 0xcc, 0x0f, 0x10, 0xe7, 0xf8, 0xd9, 0x07, 0x16,
+	0x88, 0x07, 0x0c, 0xca, 0x32, 0xcb, 0x00, 0xa8, 0x12, 0x12, 0x26, 0x08, 0x30, 0x39, 0x35, 0x96,
+	0x73, 0x5a, 0xd5, 0x38, 0xc1, 0x08, 0xd0, 0x28, 0xa9, 0x7e, 0x42, 0xab, 0xab, 0xed, 0x54, 0xae,
+	0x13, 0xb4, 0x39, 0x9f, 0x43, 0x1f, 0x76, 0x8e, 0x3d, 0xb4, 0x31, 0x15, 0xf1, 0xda, 0x41, 0x5a,
+	0x60, 0xb3, 0x9f, 0xa4, 0xbe, 0x78, 0x12, 0xdf, 0x45, 0x97, 0x75, 0x64, 0xc5, 0xe9, 0xcf, 0x15,
+	0x74, 0x51, 0x7d, 0x8c, 0x5f, 0xb0, 0xd1, 0x47, 0x22, 0xc0, 0x7b, 0xa8, 0xfe, 0x84, 0xc8, 0x0f,
+	0xe9, 0x7f, 0x27, 0x29, 0x5f, 0x5d, 0x94, 0xf0, 0xaa, 0x45, 0xc2, 0xbc, 0xbc, 0x84, 0x8d, 0xe6,
+	0xfa, 0x05, 0x65, 0xb6, 0xb8, 0xed, 0x54, 0xf1, 0xae, 0x2f, 0x17, 0xca, 0x59, 0x4d, 0x28, 0x3b,
+	0xa8, 0xbd, 0x50, 0xdc, 0x59, 0xe9, 0xef, 0xbe, 0x6e, 0xa2, 0x3a, 0xf7, 0x3a, 0x0c, 0x9d, 0xd3,
+	0x17, 0xe5, 0x4e, 0xa0, 0xb6, 0x73, 0x60, 0x2e, 0x2e, 0xef, 0xda, 0x12, 0xa7, 0xea, 0xea, 0xde,
+	0x8b, 0xdf, 0xfe, 0xfd, 0x79, 0x65, 0xc7, 0x6f, 0x87, 0xf3, 0xed, 0x2f, 0x75, 0x14, 0x49, 0x10,
+	0x85, 0x3a, 0xdb, 0x7a, 0x56, 0x54, 0xe9, 0xb4, 0xa3, 0x9a, 0xdb, 0x6f, 0x09, 0xaa, 0x1c, 0xd8,
+	0xce, 0x73, 0xfe, 0x35, 0x2f, 0xac, 0xc7, 0x8e, 0x9e, 0xdc, 0xf4, 0x7b, 0x37, 0x97, 0xfb, 0x15,
+	0xfe, 0x0d, 0x81, 0xdf, 0xf5, 0xaf, 0x54, 0xf0, 0x4b, 0x70, 0xfd, 0x17, 0x84, 0x93, 0xa1, 0xa6,
+	0xea, 0x88, 0xe3, 0x99, 0xb9, 0xe7, 0x4b, 0xc9, 0xf3, 0x4f, 0xf7, 0x2d, 0x3d, 0x73, 0x29, 0xb8,
+	0x3e, 0xa3, 0x12, 0xd7, 0x99, 0xa0, 0x0d, 0x6d, 0xa1, 0x19, 0x90, 0x55, 0x9f, 0x09, 0x69, 0xdd,
+	0x6b, 0x57, 0x05, 0xa4, 0xe7, 0xbb, 0x15, 0x48, 0x59, 0xdf, 0xbe, 0x98, 0x70, 0x25, 0xa2, 0xb6,
+	0xf5, 0x0c, 0xc4, 0xaa, 0xcf, 0x44, 0xb4, 0xee, 0x28, 0x1b, 0xa2, 0xd4, 0xd1, 0x1c, 0x51, 0xdb,
+	0x60, 0x06, 0x62, 0xd5, 0x67, 0x22, 0x5a, 0x47, 0xa5, 0x0d, 0x31, 0x86, 0xc0, 0x7e, 0x24, 0x10,
+	0xb8, 0x7e, 0xf5, 0x9d, 0x60, 0xe8, 0x57, 0x73, 0x9a, 0xfa, 0xb5, 0x8f, 0x6e, 0x5b, 0x2f, 0x9f,
+	0xc9, 0x48, 0x89, 0xfa, 0x03, 0xda, 0x34, 0x46, 0xe5, 0xae, 0x4d, 0x24, 0x33, 0xaf, 0x77, 0x7d,
+	0x99, 0x57, 0x01, 0x5f, 0x17, 0xc0, 0x1d, 0x7f, 0xd7, 0x22, 0xa2, 0x72, 0x3e, 0x01, 0xf6, 0x18,
+	0xb5, 0xaa, 0xbb, 0xa3, 0x6d, 0x1d, 0x04, 0x02, 0x75, 0xef, 0x54, 0x97, 0x82, 0xec, 0x0a, 0xc8,
+	0xb6, 0xbf, 0x6d, 0x99, 0x10, 0x65, 0xe0, 0xc1, 0xd1, 0x9b, 0xbf, 0x3b, 0xb5, 0xb7, 0xfc, 0xfa,
+	0x8b, 0x5f, 0x3f, 0xfe, 0xd3, 0x39, 0xf3, 0x96, 0x5f, 0xbf, 0xf3, 0xeb, 0xdb, 0xb0, 0x32, 0x86,
+	0xe1, 0x6b, 0x78, 0x44, 0x70, 0x1a, 0xa6, 0xdc, 0x16, 0x46, 0x23, 0x9c, 0x64, 0xe1, 0xc9, 0x2c,
+	0xa9, 0x98, 0xc9, 0x83, 0x35, 0xf1, 0x4f, 0xc1, 0xa7, 0xff, 0x01, 0x6a, 0x01, 0x32, 0xb1, 0x8b,
+	0x0c, 0x00, 0x00,
 }
 
 // Reference imports to suppress errors if they are not otherwise used.
@@ -1823,6 +1831,13 @@ func (m *MsgSendToMinter) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if len(m.Memo) > 0 {
+		i -= len(m.Memo)
+		copy(dAtA[i:], m.Memo)
+		i = encodeVarintMsgs(dAtA, i, uint64(len(m.Memo)))
+		i--
+		dAtA[i] = 0x22
+	}
 	{
 		size, err := m.Amount.MarshalToSizedBuffer(dAtA[:i])
 		if err != nil {
@@ -2441,6 +2456,10 @@ func (m *MsgSendToMinter) Size() (n int) {
 	}
 	l = m.Amount.Size()
 	n += 1 + l + sovMsgs(uint64(l))
+	l = len(m.Memo)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
 	return n
 }
 
@@ -3347,6 +3366,38 @@ func (m *MsgSendToMinter) Unmarshal(dAtA []byte) error {
 				return err
 			}
 			iNdEx = postIndex
+		case 4:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Memo", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Memo = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
 		default:
 			iNdEx = preIndex
 			skippy, err := skipMsgs(dAtA[iNdEx:])
@@ -31,6 +31,7 @@ type OutgoingTx struct {
 	DestAddr string     `protobuf:"bytes,2,opt,name=dest_addr,json=destAddr,proto3" json:"dest_addr,omitempty"`
 	Amount   types.Coin `protobuf:"bytes,3,opt,name=amount,proto3" json:"amount"`
 	TxHash   string     `protobuf:"bytes,7,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	Memo     string     `protobuf:"bytes,8,opt,name=memo,proto3" json:"memo,omitempty"`
 }
 
 func (m *OutgoingTx) Reset()         { *m = OutgoingTx{} }
@@ -94,6 +95,13 @@ func (m *OutgoingTx) GetTxHash() string {
 	return ""
 }
 
+func (m *OutgoingTx) GetMemo() string {
+	if m != nil {
+		return m.Memo
+	}
+	return ""
+}
+
 // IDSet represents a set of IDs
 type IDSet struct {
 	Ids []uint64 `protobuf:"varint,1,rep,packed,name=ids,proto3" json:"ids,omitempty"`
@@ -147,26 +155,26 @@ func init() {
 func init() { proto.RegisterFile("minter/v1/pool.proto", fileDescriptor_86823eeec411a049) }
 
 var fileDescriptor_86823eeec411a049 = []byte{
-	// 300 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x2c, 0x90, 0xbd, 0x4e, 0xc3, 0x30,
-	0x14, 0x85, 0x63, 0x5a, 0x5a, 0x6a, 0x16, 0x14, 0x55, 0x90, 0x16, 0xc9, 0x54, 0x9d, 0x3a, 0xf9,
-	0x2a, 0x30, 0x30, 0x53, 0x18, 0xe8, 0x80, 0x90, 0x4a, 0x27, 0x96, 0xca, 0x89, 0xad, 0xc4, 0x12,
-	0xf1, 0xad, 0x62, 0xa7, 0x0a, 0x6f, 0x81, 0xc4, 0x4b, 0x75, 0xec, 0xc8, 0x84, 0x50, 0xfb, 0x22,
-	0x28, 0x3f, 0xdb, 0xf1, 0xf1, 0x91, 0xee, 0xa7, 0x8f, 0x0e, 0x33, 0x6d, 0x9c, 0xca, 0x61, 0x1b,
-	0xc2, 0x06, 0xf1, 0x83, 0x6f, 0x72, 0x74, 0xe8, 0x0f, 0x9a, 0x96, 0x6f, 0xc3, 0x31, 0x8b, 0xd1,
-	0x66, 0x68, 0x21, 0x12, 0x56, 0xc1, 0x36, 0x8c, 0x94, 0x13, 0x21, 0xc4, 0xa8, 0x4d, 0x33, 0x1d,
-	0x0f, 0x13, 0x4c, 0xb0, 0x8e, 0x50, 0xa5, 0xa6, 0x9d, 0x7e, 0x13, 0x4a, 0x5f, 0x0b, 0x97, 0xa0,
-	0x36, 0xc9, 0xaa, 0xf4, 0x2f, 0x69, 0xcf, 0x2a, 0x23, 0x55, 0x1e, 0x90, 0x09, 0x99, 0x0d, 0x96,
-	0xed, 0xcb, 0xbf, 0xa6, 0x03, 0xa9, 0xac, 0x5b, 0x0b, 0x29, 0xf3, 0xe0, 0xa4, 0xfe, 0x3a, 0xab,
-	0x8a, 0x07, 0x29, 0x73, 0xff, 0x9e, 0xf6, 0x44, 0x86, 0x85, 0x71, 0x41, 0x67, 0x42, 0x66, 0xe7,
-	0xb7, 0x23, 0xde, 0xa0, 0xf0, 0x0a, 0x85, 0xb7, 0x28, 0xfc, 0x11, 0xb5, 0x99, 0x77, 0x77, 0xbf,
-	0x37, 0xde, 0xb2, 0x9d, 0xfb, 0x57, 0xb4, 0xef, 0xca, 0x75, 0x2a, 0x6c, 0x1a, 0xf4, 0x9b, 0x73,
-	0xae, 0x7c, 0x16, 0x36, 0x9d, 0x8e, 0xe8, 0xe9, 0xe2, 0xe9, 0x4d, 0x39, 0xff, 0x82, 0x76, 0xb4,
-	0xb4, 0x01, 0x99, 0x74, 0x66, 0xdd, 0x65, 0x15, 0xe7, 0x8b, 0xdd, 0x81, 0x91, 0xfd, 0x81, 0x91,
-	0xbf, 0x03, 0x23, 0x5f, 0x47, 0xe6, 0xed, 0x8f, 0xcc, 0xfb, 0x39, 0x32, 0xef, 0x1d, 0x12, 0xed,
-	0xd2, 0x22, 0xe2, 0x31, 0x66, 0xf0, 0x52, 0x6b, 0x59, 0x29, 0x91, 0x41, 0x96, 0x16, 0x11, 0xc4,
-	0xa9, 0xd0, 0x06, 0x4a, 0x68, 0x25, 0xba, 0xcf, 0x8d, 0xb2, 0x51, 0xaf, 0x56, 0x70, 0xf7, 0x1f,
-	0x00, 0x00, 0xff, 0xff, 0x9e, 0xe1, 0xf9, 0xd6, 0x5b, 0x01, 0x00, 0x00,
+	// 297 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x2d, 0x50, 0xb1, 0x4e, 0xc3, 0x30,
+	0x14, 0x6c, 0x48, 0x48, 0x1b, 0xb3, 0x20, 0xab, 0x82, 0xb4, 0x48, 0x01, 0x75, 0xea, 0x64, 0x2b,
+	0x30, 0x30, 0x53, 0x18, 0xe8, 0x80, 0x90, 0x42, 0x27, 0x96, 0xca, 0x49, 0xac, 0x24, 0x12, 0x8e,
+	0xa3, 0xd8, 0xa9, 0xc2, 0x5f, 0xf0, 0x17, 0xfc, 0x4a, 0xc7, 0x8e, 0x4c, 0x08, 0xc1, 0x8f, 0xf0,
+	0xe2, 0x64, 0x38, 0xe9, 0xee, 0xde, 0x49, 0xef, 0x74, 0x68, 0x2a, 0x8a, 0x52, 0xf3, 0x9a, 0xee,
+	0x42, 0x5a, 0x49, 0xf9, 0x46, 0xaa, 0x5a, 0x6a, 0x89, 0xbd, 0xde, 0x25, 0xbb, 0x70, 0x1e, 0x24,
+	0x52, 0x09, 0xa9, 0x68, 0xcc, 0x14, 0x87, 0x54, 0xcc, 0x35, 0x0b, 0x69, 0x22, 0x8b, 0xb2, 0x8f,
+	0xce, 0xa7, 0x99, 0xcc, 0xa4, 0xa1, 0xb4, 0x63, 0xbd, 0xbb, 0xf8, 0xb4, 0x10, 0x7a, 0x6e, 0x74,
+	0x06, 0xb9, 0x6c, 0xd3, 0xe2, 0x33, 0xe4, 0x2a, 0x5e, 0xa6, 0xbc, 0xf6, 0xad, 0x2b, 0x6b, 0xe9,
+	0x45, 0x83, 0xc2, 0x17, 0xc8, 0x4b, 0xb9, 0xd2, 0x5b, 0x96, 0xa6, 0xb5, 0x7f, 0x64, 0x4e, 0x93,
+	0xce, 0xb8, 0x03, 0x8d, 0x6f, 0x91, 0xcb, 0x84, 0x6c, 0x4a, 0xed, 0xdb, 0x70, 0x39, 0xb9, 0x9e,
+	0x91, 0xbe, 0x0a, 0xe9, 0xaa, 0x90, 0xa1, 0x0a, 0xb9, 0x87, 0x17, 0x2b, 0x67, 0xff, 0x7d, 0x39,
+	0x8a, 0x86, 0x38, 0x3e, 0x47, 0x63, 0xdd, 0x6e, 0x73, 0xa6, 0x72, 0x7f, 0xdc, 0xbf, 0xd3, 0xed,
+	0x23, 0x28, 0x8c, 0x91, 0x23, 0xb8, 0x90, 0xfe, 0xc4, 0xb8, 0x86, 0x2f, 0x66, 0xe8, 0x78, 0xfd,
+	0xf0, 0xc2, 0x35, 0x3e, 0x45, 0x76, 0x91, 0x2a, 0x28, 0x68, 0x2f, 0x9d, 0xa8, 0xa3, 0xab, 0xf5,
+	0xfe, 0x37, 0xb0, 0x0e, 0x80, 0x1f, 0xc0, 0xc7, 0x5f, 0x30, 0x3a, 0x00, 0xbe, 0x00, 0xaf, 0x34,
+	0x2b, 0x74, 0xde, 0xc4, 0x50, 0x48, 0xd0, 0x27, 0x33, 0xd5, 0x86, 0x33, 0x41, 0x05, 0x78, 0x34,
+	0xc9, 0x59, 0x51, 0xd2, 0x96, 0x0e, 0xc3, 0xea, 0xf7, 0x8a, 0xab, 0xd8, 0x35, 0xb3, 0xdc, 0xfc,
+	0x03, 0x12, 0x51, 0xbc, 0x3d, 0x6f, 0x01, 0x00, 0x00,
 }
 
 func (m *OutgoingTx) Marshal() (dAtA []byte, err error) {
@@ -189,6 +197,13 @@ func (m *OutgoingTx) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if len(m.Memo) > 0 {
+		i -= len(m.Memo)
+		copy(dAtA[i:], m.Memo)
+		i = encodeVarintPool(dAtA, i, uint64(len(m.Memo)))
+		i--
+		dAtA[i] = 0x42
+	}
 	if len(m.TxHash) > 0 {
 		i -= len(m.TxHash)
 		copy(dAtA[i:], m.TxHash)
@@ -295,6 +310,10 @@ func (m *OutgoingTx) Size() (n int) {
 	if l > 0 {
 		n += 1 + l + sovPool(uint64(l))
 	}
+	l = len(m.Memo)
+	if l > 0 {
+		n += 1 + l + sovPool(uint64(l))
+	}
 	return n
 }
 
@@ -478,6 +497,38 @@ func (m *OutgoingTx) Unmarshal(dAtA []byte) error {
 			}
 			m.TxHash = string(dAtA[iNdEx:postIndex])
 			iNdEx = postIndex
+		case 8:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Memo", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowPool
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthPool
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthPool
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Memo = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
 		default:
 			iNdEx = preIndex
 			skippy, err := skipPool(dAtA[iNdEx:])
@@ -71,14 +71,23 @@ func (k Keeper) GetPipInBip() sdk.Int {
 	return t
 }
 
+// SetTxStatus records the progress of a transfer, the memo set with SetTxMemo is kept
 func (k Keeper) SetTxStatus(ctx sdk.Context, inTxHash string, status types.TxStatusType, outTxHash string) {
 	ctx.KVStore(k.storeKey).Set(types.GetTxStatusKey(inTxHash), k.cdc.MustMarshalBinaryBare(&types.TxStatus{
 		InTxHash:  inTxHash,
 		OutTxHash: outTxHash,
 		Status:    status,
+		Memo:      k.GetTxStatus(ctx, inTxHash).Memo,
 	}))
 }
 
+// SetTxMemo attaches the sender's memo to a transfer so it is returned with the transfer's status
+func (k Keeper) SetTxMemo(ctx sdk.Context, inTxHash string, memo string) {
+	status := k.GetTxStatus(ctx, inTxHash)
+	status.Memo = memo
+	ctx.KVStore(k.storeKey).Set(types.GetTxStatusKey(inTxHash), k.cdc.MustMarshalBinaryBare(status))
+}
+
 func (k Keeper) GetTxStatus(ctx sdk.Context, inTxHash string) *types.TxStatus {
 	store := ctx.KVStore(k.storeKey)
 	bytes := store.Get(types.GetTxStatusKey(inTxHash))
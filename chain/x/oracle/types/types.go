@@ -3,7 +3,9 @@ package types
 import (
 	"encoding/binary"
 	"errors"
+	"fmt"
 	sdk "github.com/cosmos/cosmos-sdk/types"
+	"github.com/tendermint/tendermint/crypto/tmhash"
 	"strconv"
 )
 
@@ -82,3 +84,19 @@ func (c Coins) GetEthereumAddressByDenom(denom string) (string, error) {
 
 	return "", errors.New("coin not found")
 }
+
+// MaxMemoLength is the longest memo a transfer to Ethereum or Minter may carry
+const MaxMemoLength = 256
+
+// ValidateMemo checks the optional memo of a transfer
+func ValidateMemo(memo string) error {
+	if len(memo) > MaxMemoLength {
+		return fmt.Errorf("memo is %d bytes, at most %d are allowed", len(memo), MaxMemoLength)
+	}
+	return nil
+}
+
+// CurrentTxHash returns the hash of the Hub tx being executed, transfers it makes are tracked by this hash
+func CurrentTxHash(ctx sdk.Context) string {
+	return fmt.Sprintf("%X", tmhash.Sum(ctx.TxBytes()))
+}
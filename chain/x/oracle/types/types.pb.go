@@ -344,6 +344,7 @@ type TxStatus struct {
 	InTxHash  string       `protobuf:"bytes,1,opt,name=in_tx_hash,json=inTxHash,proto3" json:"in_tx_hash"`
 	OutTxHash string       `protobuf:"bytes,2,opt,name=out_tx_hash,json=outTxHash,proto3" json:"out_tx_hash"`
 	Status    TxStatusType `protobuf:"varint,3,opt,name=status,proto3,enum=oracle.v1.TxStatusType" json:"status"`
+	Memo      string       `protobuf:"bytes,4,opt,name=memo,proto3" json:"memo"`
 }
 
 func (m *TxStatus) Reset()         { *m = TxStatus{} }
@@ -400,6 +401,13 @@ func (m *TxStatus) GetStatus() TxStatusType {
 	return TX_STATUS_NOT_FOUND
 }
 
+func (m *TxStatus) GetMemo() string {
+	if m != nil {
+		return m.Memo
+	}
+	return ""
+}
+
 func init() {
 	proto.RegisterEnum("oracle.v1.TxStatusType", TxStatusType_name, TxStatusType_value)
 	proto.RegisterType((*GenericClaim)(nil), "oracle.v1.GenericClaim")
@@ -412,53 +420,52 @@ func init() {
 func init() { proto.RegisterFile("oracle/v1/types.proto", fileDescriptor_b54af2de77c923e3) }
 
 var fileDescriptor_b54af2de77c923e3 = []byte{
-	// 727 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x7c, 0x54, 0x4f, 0x8f, 0xdb, 0x44,
-	0x14, 0x8f, 0x37, 0xce, 0x36, 0x79, 0x49, 0xdb, 0x30, 0x84, 0xae, 0x31, 0xe0, 0x98, 0x20, 0x50,
-	0x54, 0x81, 0x4d, 0x97, 0x0b, 0xa2, 0x12, 0x52, 0xfc, 0xa7, 0x6c, 0x0e, 0xcd, 0x56, 0x13, 0xa7,
-	0xaa, 0xe0, 0x60, 0x79, 0xed, 0x51, 0x6c, 0xb1, 0xf6, 0x44, 0xf6, 0x24, 0x4a, 0xbf, 0x01, 0xca,
-	0x89, 0x2f, 0x90, 0x13, 0x12, 0x27, 0x3e, 0x05, 0xa7, 0x1e, 0x7b, 0x44, 0x1c, 0x22, 0xb4, 0xb9,
-	0xe5, 0x53, 0x20, 0x8f, 0xdd, 0x24, 0xaa, 0xda, 0x3d, 0xf9, 0xbd, 0xdf, 0xef, 0xf7, 0xde, 0xbc,
-	0x79, 0xfe, 0x69, 0xe0, 0x23, 0x9a, 0x7a, 0xfe, 0x35, 0xd1, 0x17, 0x8f, 0x74, 0xf6, 0x72, 0x46,
-	0x32, 0x6d, 0x96, 0x52, 0x46, 0x51, 0xa3, 0x80, 0xb5, 0xc5, 0x23, 0xb9, 0x73, 0x50, 0xc4, 0xd9,
-	0xb4, 0x14, 0xc8, 0x9d, 0x29, 0x9d, 0x52, 0x1e, 0xea, 0x79, 0x54, 0xa0, 0xbd, 0xbf, 0x05, 0x68,
-	0xfd, 0x44, 0x12, 0x92, 0x46, 0xbe, 0x79, 0xed, 0x45, 0x31, 0xea, 0x40, 0x8d, 0xcc, 0xa8, 0x1f,
-	0x4a, 0x82, 0x2a, 0xf4, 0x45, 0x5c, 0x24, 0xe8, 0x33, 0x00, 0x3f, 0xa7, 0xdd, 0xfc, 0x48, 0xe9,
-	0x44, 0x15, 0xfa, 0x35, 0xdc, 0xe0, 0x88, 0xf3, 0x72, 0x46, 0x10, 0x02, 0x31, 0xf4, 0xb2, 0x50,
-	0xaa, 0xaa, 0x42, 0xbf, 0x85, 0x79, 0x8c, 0xbe, 0x80, 0xbb, 0x64, 0x41, 0x12, 0xe6, 0x72, 0x19,
-	0x49, 0x25, 0x51, 0x15, 0xfa, 0x0d, 0xdc, 0xe2, 0xa0, 0x59, 0x60, 0xe8, 0x31, 0x34, 0x67, 0x69,
-	0xe4, 0x93, 0x42, 0x24, 0xd5, 0x54, 0xa1, 0xdf, 0x3c, 0x97, 0xb4, 0xfd, 0x5d, 0xb4, 0xa7, 0xd9,
-	0xf4, 0x59, 0x2e, 0xe0, 0x05, 0x17, 0x15, 0x0c, 0xb3, 0x7d, 0x66, 0xdc, 0x81, 0x1a, 0x2f, 0xeb,
-	0x59, 0x50, 0xb3, 0xf9, 0x98, 0x1d, 0xa8, 0x25, 0x34, 0xf1, 0xc9, 0x9b, 0xe1, 0x79, 0x82, 0xbe,
-	0x84, 0xda, 0x82, 0x32, 0x92, 0x49, 0x27, 0x6a, 0xb5, 0xdf, 0x3c, 0xbf, 0x7f, 0xd4, 0xfe, 0x39,
-	0x65, 0x04, 0x17, 0x6c, 0x6f, 0x04, 0x62, 0x9e, 0xa2, 0x07, 0x70, 0x5a, 0x08, 0x78, 0x97, 0x06,
-	0x2e, 0x33, 0xa4, 0x95, 0xc7, 0xf1, 0xeb, 0xdf, 0x32, 0x25, 0x2e, 0xa7, 0xda, 0x0a, 0x20, 0x9a,
-	0x34, 0x4a, 0xf2, 0xa9, 0x02, 0x92, 0xd0, 0xb8, 0xec, 0x57, 0x24, 0xe8, 0x63, 0xa8, 0x13, 0x16,
-	0xba, 0x5e, 0x10, 0xa4, 0xbc, 0x63, 0x03, 0xdf, 0x21, 0x2c, 0x1c, 0x04, 0x41, 0x8a, 0x1e, 0x42,
-	0x23, 0x8e, 0x12, 0x46, 0x52, 0x37, 0x0a, 0xf8, 0x4e, 0xab, 0xc6, 0xdd, 0xdd, 0xa6, 0x7b, 0x00,
-	0x71, 0xbd, 0x08, 0x87, 0x01, 0xfa, 0x1c, 0x5a, 0x79, 0x9b, 0x80, 0xf8, 0x51, 0xec, 0x5d, 0x67,
-	0x7c, 0xcb, 0x22, 0x6e, 0x12, 0x16, 0x5a, 0x25, 0x84, 0x7e, 0x81, 0x0f, 0xfc, 0x79, 0xc6, 0x68,
-	0xec, 0xfa, 0x34, 0x8e, 0xa3, 0x2c, 0x8b, 0x68, 0xc2, 0x57, 0xdd, 0x32, 0xb4, 0x57, 0x9b, 0xae,
-	0xf0, 0xef, 0xa6, 0xfb, 0xd5, 0x34, 0x62, 0xe1, 0xfc, 0x4a, 0xf3, 0x69, 0xac, 0xfb, 0x34, 0x8b,
-	0x69, 0x56, 0x7e, 0xbe, 0xc9, 0x82, 0x5f, 0x4b, 0x9f, 0x59, 0xc4, 0xc7, 0xed, 0xa2, 0x91, 0xb9,
-	0xef, 0xd3, 0xfb, 0x53, 0x80, 0xba, 0xb3, 0x1c, 0x33, 0x8f, 0xcd, 0x33, 0xf4, 0x35, 0x40, 0x94,
-	0xb8, 0x6c, 0xe9, 0x72, 0x37, 0xf0, 0xeb, 0x1a, 0xf7, 0x76, 0x9b, 0xee, 0x11, 0x8a, 0xeb, 0x51,
-	0xe2, 0x2c, 0x2f, 0x72, 0x87, 0xe8, 0xd0, 0xa4, 0x73, 0xb6, 0x97, 0xf3, 0x25, 0x18, 0xf7, 0x77,
-	0x9b, 0xee, 0x31, 0x8c, 0x1b, 0x74, 0xce, 0xca, 0x82, 0xc7, 0x70, 0x9a, 0xf1, 0x83, 0xf8, 0x52,
-	0xee, 0x9d, 0x9f, 0x1d, 0xfd, 0x82, 0x37, 0x33, 0xe4, 0x7e, 0x34, 0x60, 0xb7, 0xe9, 0x96, 0x52,
-	0x5c, 0x7e, 0x1f, 0xfe, 0x75, 0x02, 0xad, 0x63, 0x11, 0xfa, 0x16, 0x3e, 0x74, 0x5e, 0xb8, 0x63,
-	0x67, 0xe0, 0x4c, 0xc6, 0xee, 0xe8, 0xd2, 0x71, 0x9f, 0x5c, 0x4e, 0x46, 0x56, 0xbb, 0x22, 0x9f,
-	0xad, 0xd6, 0xea, 0xbb, 0x28, 0xf4, 0x23, 0xc8, 0x07, 0xd8, 0xb2, 0x9f, 0x5d, 0x8e, 0x87, 0x8e,
-	0x8b, 0x6d, 0xd3, 0x1e, 0x3e, 0xb7, 0xad, 0xb6, 0x20, 0x2b, 0xab, 0xb5, 0x7a, 0x8b, 0x02, 0x7d,
-	0x0f, 0x67, 0x07, 0xd6, 0x18, 0x38, 0xe6, 0x85, 0x6b, 0x62, 0x7b, 0xe0, 0xd8, 0x56, 0xfb, 0x44,
-	0xfe, 0x64, 0xb5, 0x56, 0xdf, 0x47, 0xa3, 0x1f, 0x40, 0x7a, 0x9b, 0xb2, 0x5f, 0xd8, 0xe6, 0x24,
-	0x2f, 0xad, 0xca, 0x9f, 0xae, 0xd6, 0xea, 0x7b, 0x79, 0xa4, 0x01, 0x3a, 0x70, 0xd8, 0x7e, 0x32,
-	0x19, 0x59, 0xb6, 0xd5, 0x16, 0xe5, 0x07, 0xab, 0xb5, 0xfa, 0x0e, 0x46, 0x16, 0x7f, 0xfb, 0x43,
-	0xa9, 0x18, 0xc3, 0x57, 0x37, 0x8a, 0xf0, 0xfa, 0x46, 0x11, 0xfe, 0xbb, 0x51, 0x84, 0xdf, 0xb7,
-	0x4a, 0xe5, 0xf5, 0x56, 0xa9, 0xfc, 0xb3, 0x55, 0x2a, 0x3f, 0xeb, 0x47, 0x5e, 0x79, 0xca, 0x6d,
-	0xe8, 0x10, 0x2f, 0xd6, 0xe3, 0x70, 0x7e, 0xa5, 0xfb, 0xa1, 0x17, 0x25, 0xfa, 0x52, 0x2f, 0x5f,
-	0x20, 0x6e, 0x9c, 0xab, 0x53, 0xfe, 0xd4, 0x7c, 0xf7, 0x7f, 0x00, 0x00, 0x00, 0xff, 0xff, 0x33,
-	0x7a, 0x6a, 0x5b, 0xba, 0x04, 0x00, 0x00,
+	// 705 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x7d, 0x54, 0x4d, 0x6f, 0xd3, 0x40,
+	0x10, 0x8d, 0x1b, 0xa7, 0x4d, 0x26, 0xe9, 0x07, 0x4b, 0x69, 0x83, 0x29, 0x69, 0x09, 0x02, 0xa1,
+	0x0a, 0x62, 0x5a, 0x2e, 0x88, 0x4a, 0x48, 0xb1, 0xe3, 0xd2, 0x1c, 0xda, 0x54, 0x1b, 0xb7, 0xaa,
+	0xe0, 0x60, 0xb9, 0xce, 0x2a, 0xb1, 0xa8, 0xbd, 0x51, 0xec, 0x44, 0xe1, 0x1f, 0x20, 0x4e, 0xfc,
+	0x01, 0x4e, 0x5c, 0xf9, 0x15, 0x88, 0x43, 0x8f, 0x3d, 0x22, 0x0e, 0x15, 0xa2, 0x37, 0x7e, 0x05,
+	0xe3, 0xb5, 0x9b, 0x44, 0xa8, 0xed, 0x61, 0xed, 0x9d, 0xf7, 0xde, 0xbc, 0x9d, 0x1d, 0x8f, 0x0c,
+	0x77, 0x78, 0xcf, 0x76, 0x4e, 0x98, 0x3a, 0xd8, 0x50, 0xc3, 0x0f, 0x5d, 0x16, 0x54, 0xba, 0x3d,
+	0x1e, 0x72, 0x92, 0x8b, 0xe1, 0xca, 0x60, 0x43, 0x59, 0x1c, 0x2b, 0xbc, 0xa0, 0x9d, 0x08, 0x94,
+	0xc5, 0x36, 0x6f, 0x73, 0xb1, 0x55, 0xa3, 0x5d, 0x8c, 0x96, 0xbf, 0x4b, 0x50, 0x78, 0xc3, 0x7c,
+	0xd6, 0x73, 0x1d, 0xfd, 0xc4, 0x76, 0x3d, 0xb2, 0x08, 0x19, 0xd6, 0xe5, 0x4e, 0xa7, 0x28, 0xad,
+	0x49, 0x4f, 0x64, 0x1a, 0x07, 0xe4, 0x3e, 0x80, 0x13, 0xd1, 0x56, 0x74, 0x64, 0x71, 0x0a, 0xa9,
+	0x0c, 0xcd, 0x09, 0xc4, 0x44, 0x80, 0x10, 0x90, 0x3b, 0x76, 0xd0, 0x29, 0xa6, 0x91, 0x28, 0x50,
+	0xb1, 0x27, 0x0f, 0x61, 0x96, 0x0d, 0x98, 0x1f, 0x5a, 0x42, 0xc6, 0x7a, 0x45, 0x19, 0xc9, 0x1c,
+	0x2d, 0x08, 0x50, 0x8f, 0x31, 0xb2, 0x05, 0xf9, 0x2e, 0x1e, 0xcd, 0x62, 0x51, 0x31, 0x83, 0x92,
+	0xfc, 0x66, 0xb1, 0x32, 0xba, 0x4b, 0x65, 0x37, 0x68, 0xef, 0x47, 0x02, 0x91, 0xb0, 0x93, 0xa2,
+	0xd0, 0x1d, 0x45, 0xda, 0x0c, 0x64, 0x44, 0x5a, 0xb9, 0x06, 0x19, 0x43, 0x94, 0x89, 0xc5, 0xfb,
+	0xdc, 0x77, 0xd8, 0x65, 0xf1, 0x22, 0x20, 0x8f, 0x20, 0x33, 0xe0, 0x21, 0x0b, 0xb0, 0xee, 0x34,
+	0xda, 0xcf, 0x4f, 0xd8, 0x1f, 0x22, 0x4e, 0x63, 0xb6, 0xbc, 0x07, 0x72, 0x14, 0x92, 0x25, 0x98,
+	0x8e, 0x05, 0xc2, 0x25, 0x47, 0x93, 0x88, 0x54, 0x92, 0xe3, 0xc4, 0xf5, 0x6f, 0xa8, 0x92, 0x26,
+	0x55, 0x5d, 0x48, 0x20, 0xeb, 0xdc, 0xf5, 0xa3, 0xaa, 0x5a, 0xcc, 0xe7, 0x5e, 0xe2, 0x17, 0x07,
+	0xe4, 0x2e, 0x64, 0x59, 0xd8, 0xb1, 0xec, 0x56, 0xab, 0x27, 0x1c, 0x73, 0x74, 0x06, 0xe3, 0x2a,
+	0x86, 0x64, 0x1d, 0x72, 0x9e, 0xeb, 0x87, 0xac, 0x67, 0xb9, 0x2d, 0xd1, 0xd3, 0xb4, 0x36, 0xfb,
+	0xf7, 0x7c, 0x75, 0x0c, 0xd2, 0x6c, 0xbc, 0xad, 0xb7, 0xc8, 0x03, 0x28, 0x44, 0x36, 0x2d, 0xe6,
+	0xb8, 0x9e, 0x7d, 0x12, 0x88, 0x2e, 0xcb, 0x34, 0x8f, 0x58, 0x2d, 0x81, 0xc8, 0x3b, 0xb8, 0xe5,
+	0xf4, 0x83, 0x90, 0x7b, 0x96, 0xc3, 0x3d, 0xcf, 0x0d, 0x02, 0x97, 0xfb, 0xa2, 0xd5, 0x05, 0xad,
+	0x72, 0x7a, 0xbe, 0x2a, 0xfd, 0x3a, 0x5f, 0x7d, 0xdc, 0x76, 0xc3, 0x4e, 0xff, 0xb8, 0x82, 0x02,
+	0xd5, 0xe1, 0x81, 0xc7, 0x83, 0xe4, 0xf5, 0x2c, 0x68, 0xbd, 0x4f, 0xe6, 0x0c, 0xfd, 0xe8, 0x42,
+	0x6c, 0xa4, 0x8f, 0x7c, 0xca, 0x3f, 0x24, 0xc8, 0x9a, 0xc3, 0x66, 0x68, 0x87, 0xfd, 0x80, 0x3c,
+	0x05, 0x70, 0x7d, 0x2b, 0x1c, 0x5a, 0x62, 0x1a, 0xc4, 0x75, 0xb5, 0x39, 0xac, 0x7c, 0x02, 0xa5,
+	0x59, 0xd7, 0x37, 0x87, 0x3b, 0xd1, 0x84, 0xa8, 0x90, 0xe7, 0xfd, 0x70, 0x24, 0x17, 0x4d, 0xd0,
+	0xe6, 0x51, 0x3e, 0x09, 0xd3, 0x1c, 0x06, 0x49, 0xc2, 0x16, 0x4c, 0x07, 0xe2, 0x20, 0xd1, 0x94,
+	0xb9, 0xcd, 0xe5, 0x89, 0x4f, 0x70, 0x59, 0x43, 0x34, 0x8f, 0x1a, 0xa0, 0x49, 0x22, 0xa5, 0xc9,
+	0x9b, 0xac, 0x80, 0xec, 0x31, 0x8f, 0xc7, 0x63, 0xa8, 0x65, 0x51, 0x21, 0x62, 0x2a, 0x9e, 0xeb,
+	0xdf, 0xa6, 0xa0, 0x30, 0x69, 0x41, 0x9e, 0xc3, 0x6d, 0xf3, 0xc8, 0x6a, 0x9a, 0x55, 0xf3, 0xa0,
+	0x69, 0xed, 0x35, 0x4c, 0x6b, 0xbb, 0x71, 0xb0, 0x57, 0x5b, 0x48, 0x29, 0xcb, 0x9f, 0xbe, 0xac,
+	0x5d, 0x45, 0x91, 0xd7, 0xa0, 0x8c, 0xe1, 0x9a, 0xb1, 0xdf, 0x68, 0xd6, 0x4d, 0x8b, 0x1a, 0xba,
+	0x51, 0x3f, 0x34, 0x6a, 0x0b, 0x92, 0x52, 0xc2, 0xc4, 0x1b, 0x14, 0xe4, 0x25, 0x2c, 0x8f, 0x59,
+	0xad, 0x6a, 0xea, 0x3b, 0x96, 0x4e, 0x8d, 0xaa, 0x89, 0xc9, 0x53, 0xca, 0x3d, 0x4c, 0xbe, 0x8e,
+	0x26, 0xaf, 0xa0, 0xf8, 0x3f, 0x65, 0x1c, 0x19, 0xfa, 0x41, 0x94, 0x9a, 0x56, 0x56, 0x30, 0xf5,
+	0x5a, 0x1e, 0xa7, 0x9a, 0x8c, 0x39, 0x6a, 0x6c, 0xe3, 0x4d, 0x30, 0x4b, 0x56, 0x96, 0x30, 0xeb,
+	0x0a, 0x46, 0x91, 0x3f, 0x7e, 0x2d, 0xa5, 0xb4, 0xfa, 0xe9, 0x9f, 0x92, 0x74, 0x86, 0xeb, 0x37,
+	0xae, 0xcf, 0x17, 0xa5, 0xd4, 0x19, 0xae, 0x9f, 0xb8, 0xde, 0xaa, 0x13, 0x93, 0xb4, 0x2b, 0x86,
+	0xd4, 0x64, 0xb6, 0xa7, 0x7a, 0x88, 0xa9, 0x4e, 0xc7, 0x76, 0x7d, 0x75, 0xa8, 0x26, 0xff, 0x27,
+	0x31, 0x56, 0xc7, 0xd3, 0xe2, 0x47, 0xf4, 0xe2, 0x1f, 0x93, 0x63, 0xcd, 0xbb, 0xd8, 0x04, 0x00,
+	0x00,
 }
 
 func (m *GenericClaim) Marshal() (dAtA []byte, err error) {
@@ -701,6 +708,13 @@ func (m *TxStatus) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if len(m.Memo) > 0 {
+		i -= len(m.Memo)
+		copy(dAtA[i:], m.Memo)
+		i = encodeVarintTypes(dAtA, i, uint64(len(m.Memo)))
+		i--
+		dAtA[i] = 0x22
+	}
 	if m.Status != 0 {
 		i = encodeVarintTypes(dAtA, i, uint64(m.Status))
 		i--
@@ -851,6 +865,10 @@ func (m *TxStatus) Size() (n int) {
 	if m.Status != 0 {
 		n += 1 + sovTypes(uint64(m.Status))
 	}
+	l = len(m.Memo)
+	if l > 0 {
+		n += 1 + l + sovTypes(uint64(l))
+	}
 	return n
 }
 
@@ -1581,6 +1599,38 @@ func (m *TxStatus) Unmarshal(dAtA []byte) error {
 					break
 				}
 			}
+		case 4:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Memo", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowTypes
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthTypes
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthTypes
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Memo = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
 		default:
 			iNdEx = preIndex
 			skippy, err := skipTypes(dAtA[iNdEx:])
@@ -186,3 +186,10 @@ func shuffled(v BridgeValidators) BridgeValidators {
 	})
 	return v
 }
+
+func TestValidateMemo(t *testing.T) {
+	assert.NoError(t, ValidateMemo(""))
+	assert.NoError(t, ValidateMemo("withdrawal 4411"))
+	assert.NoError(t, ValidateMemo(string(bytes.Repeat([]byte("a"), MaxMemoLength))))
+	assert.Error(t, ValidateMemo(string(bytes.Repeat([]byte("a"), MaxMemoLength+1))))
+}
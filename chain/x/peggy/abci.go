@@ -15,6 +15,10 @@ func EndBlocker(ctx sdk.Context, k keeper.Keeper) {
 	coinsList := k.OracleKeeper().GetCoins(ctx).List()
 	for _, coin := range coinsList {
 		k.IterateOutgoingPoolByFee(ctx, coin.EthAddr, func(id uint64, tx *types.OutgoingTx) bool {
+			// refunds of deposits with an invalid destination have nowhere else to go
+			if types.ValidateEthAddress(tx.RefundAddr) == nil {
+				return false
+			}
 			if ctx.BlockTime().After(time.Unix(tx.ExpirationTime, 0)) {
 				k.RefundOutgoingTx(ctx, id, tx)
 				hasRefunds = true
@@ -28,6 +32,8 @@ func EndBlocker(ctx sdk.Context, k keeper.Keeper) {
 		k.MinterKeeper().BuildOutgoingTXBatch(ctx, minterkeeper.OutgoingTxBatchSize)
 	}
 
+	k.SupersedeWithheldBatches(ctx)
+
 	// valsets are sorted so the most recent one is first
 	valsets := k.GetValsets(ctx)
 	if len(valsets) == 0 || types.BridgeValidators(k.GetCurrentValset(ctx).Members).PowerDiff(valsets[0].Members) > 0.01 {
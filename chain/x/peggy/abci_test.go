@@ -110,3 +110,58 @@ func TestValsetSetting(t *testing.T) {
 	valsets := pk.GetValsets(ctx)
 	require.True(t, len(valsets) == 1)
 }
+
+func TestWithheldBatchReplacement(t *testing.T) {
+	input, ctx := keeper.SetupFiveValChain(t)
+	pk := input.PeggyKeeper
+	params := pk.GetParams(ctx)
+	params.BatchTimeoutBlocks = 100
+	pk.SetParams(ctx, params)
+	pk.SetValsetRequest(ctx)
+
+	storeBatch := func(nonce uint64, tokenContract string, signers int) *types.OutgoingTxBatch {
+		batch := &types.OutgoingTxBatch{
+			BatchNonce:    nonce,
+			Transactions:  []*types.OutgoingTransferTx{},
+			TokenContract: tokenContract,
+			Block:         uint64(ctx.BlockHeight()) - 100,
+		}
+		pk.StoreBatchUnsafe(ctx, batch)
+		for i, val := range keeper.AccAddrs[:signers] {
+			pk.SetBatchConfirm(ctx, &types.MsgConfirmBatch{
+				Nonce:         batch.BatchNonce,
+				TokenContract: batch.TokenContract,
+				EthSigner:     keeper.EthAddrs[i].String(),
+				Orchestrator:  val.String(),
+			})
+		}
+		return batch
+	}
+
+	// two of five validators withhold their signatures, the batch can't reach the contract's 66% of the power
+	withheld := storeBatch(1, keeper.TokenContractAddrs[0], 3)
+	// signed well enough to be relayed, only waiting for a relayer
+	signed := storeBatch(2, keeper.TokenContractAddrs[1], 4)
+
+	EndBlocker(ctx, pk)
+
+	// the withheld batch's signatures stay valid on Ethereum, it is only reported and never canceled here
+	require.NotNil(t, pk.GetOutgoingTXBatch(ctx, withheld.TokenContract, withheld.BatchNonce))
+	require.NotNil(t, pk.GetOutgoingTXBatch(ctx, signed.TokenContract, signed.BatchNonce))
+
+	var withheldBy []string
+	for _, event := range ctx.EventManager().Events() {
+		if event.Type != types.EventTypeOutgoingBatchWithheld {
+			continue
+		}
+		for _, attr := range event.Attributes {
+			if string(attr.Key) == types.AttributeKeyWithheldBy {
+				withheldBy = append(withheldBy, string(attr.Value))
+			}
+		}
+	}
+	require.Len(t, withheldBy, 1)
+	require.Contains(t, withheldBy[0], keeper.EthAddrs[3].String())
+	require.Contains(t, withheldBy[0], keeper.EthAddrs[4].String())
+	require.NotContains(t, withheldBy[0], keeper.EthAddrs[0].String())
+}
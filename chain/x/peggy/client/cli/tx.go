@@ -5,6 +5,7 @@ import (
 	"encoding/hex"
 	"fmt"
 	"log"
+	"strconv"
 
 	"github.com/cosmos/cosmos-sdk/types/errors"
 	ethCrypto "github.com/ethereum/go-ethereum/crypto"
@@ -28,6 +29,7 @@ func GetTxCmd(storeKey string) *cobra.Command {
 
 	peggyTxCmd.AddCommand([]*cobra.Command{
 		CmdWithdrawToETH(),
+		CmdCancelWithdrawToETH(),
 		CmdRequestBatch(),
 		GetUnsafeTestingCmd(),
 	}...)
@@ -93,9 +95,9 @@ func CmdUnsafeETHAddr() *cobra.Command {
 
 func CmdWithdrawToETH() *cobra.Command {
 	return &cobra.Command{
-		Use:   "withdraw [from_key_or_cosmos_address] [to_eth_address] [amount] [bridge_fee]",
-		Short: "Adds a new entry to the transaction pool to withdraw an amount from the Ethereum bridge contract",
-		Args:  cobra.ExactArgs(4),
+		Use:   "withdraw [from_key_or_cosmos_address] [to_eth_address] [amount] [bridge_fee] [memo]",
+		Short: "Adds a new entry to the transaction pool to withdraw an amount from the Ethereum bridge contract, the memo is optional",
+		Args:  cobra.RangeArgs(4, 5),
 		RunE: func(cmd *cobra.Command, args []string) error {
 			cliCtx, err := client.GetClientTxContext(cmd)
 			if err != nil {
@@ -123,6 +125,9 @@ func CmdWithdrawToETH() *cobra.Command {
 				Amount:    amount[0],
 				BridgeFee: bridgeFee[0],
 			}
+			if len(args) == 5 {
+				msg.Memo = args[4]
+			}
 			if err := msg.ValidateBasic(); err != nil {
 				return err
 			}
@@ -132,6 +137,32 @@ func CmdWithdrawToETH() *cobra.Command {
 	}
 }
 
+func CmdCancelWithdrawToETH() *cobra.Command {
+	return &cobra.Command{
+		Use:   "cancel-withdraw [from_key_or_cosmos_address] [transaction_id]",
+		Short: "Removes a withdrawal that is not in a batch yet from the transaction pool and refunds the amount and bridge fee",
+		Args:  cobra.ExactArgs(2),
+		RunE: func(cmd *cobra.Command, args []string) error {
+			cliCtx, err := client.GetClientTxContext(cmd)
+			if err != nil {
+				return err
+			}
+			cosmosAddr := cliCtx.GetFromAddress()
+
+			txID, err := strconv.ParseUint(args[1], 10, 64)
+			if err != nil {
+				return sdkerrors.Wrap(err, "transaction id")
+			}
+
+			msg := types.NewMsgCancelSendToEth(cosmosAddr, txID)
+			if err := msg.ValidateBasic(); err != nil {
+				return err
+			}
+			return tx.GenerateOrBroadcastTxCLI(cliCtx, cmd.Flags(), msg)
+		},
+	}
+}
+
 func CmdRequestBatch() *cobra.Command {
 	return &cobra.Command{
 		Use:   "build-batch [token_contract_address]",
@@ -14,6 +14,7 @@ const (
 	bech32ValidatorAddress = "bech32ValidatorAddress"
 	claimType              = "claimType"
 	signType               = "signType"
+	txHash                 = "tx_hash"
 )
 
 // Here are the routes that are actually queried by the rust
@@ -57,4 +58,9 @@ func RegisterRoutes(cliCtx client.Context, r *mux.Router, storeName string) {
 	// This endpoint gets all of the batch confirmations for a given nonce and denom In order to determine if a batch is complete
 	// the relayer will compare the valset power on the contract to the number of signatures
 	r.HandleFunc(fmt.Sprintf("/%s/batch_confirm/{%s}/{%s}", storeName, nonce, tokenAddress), allBatchConfirmsHandler(cliCtx, storeName)).Methods("GET")
+
+	/// Attestations
+
+	// Gets which validators have voted for the claim of an Ethereum transaction and how much power it still needs to be observed
+	r.HandleFunc(fmt.Sprintf("/%s/attestation_progress/{%s}", storeName, txHash), attestationProgressHandler(cliCtx, storeName)).Methods("GET")
 }
@@ -18,6 +18,9 @@ import (
 //   consensus state (e.g. minting tokens for a deposit event)
 // - If so, marks it "Observed" and emits an event
 func (k Keeper) AddClaim(ctx sdk.Context, details types.EthereumClaim) (*types.Attestation, error) {
+	if haltAt := k.GetHaltAtEventNonce(ctx); haltAt != 0 && details.GetEventNonce() > haltAt {
+		return nil, sdkerrors.Wrapf(types.ErrBridgeHalted, "at event nonce %d, got %d", haltAt, details.GetEventNonce())
+	}
 	if err := k.storeClaim(ctx, details); err != nil {
 		return nil, sdkerrors.Wrap(err, "claim")
 	}
@@ -28,9 +31,84 @@ func (k Keeper) AddClaim(ctx sdk.Context, details types.EthereumClaim) (*types.A
 
 	k.SetAttestation(ctx, att, details)
 
+	if claim, ok := details.(interface{ GetTxHash() string }); ok && claim.GetTxHash() != "" {
+		store := ctx.KVStore(k.storeKey)
+		store.Set(
+			types.GetAttestationByTxHashKey(claim.GetTxHash(), att.EventNonce, att.ClaimHash),
+			types.GetAttestationKey(att.EventNonce, details),
+		)
+	}
+
 	return att, nil
 }
 
+// GetAttestationByTxHash returns the attestation of the claims of an Ethereum transaction. When validators
+// claimed conflicting details for it, that is the attestation which was observed, or until one is, the one
+// with the most votes, the one with the lowest event nonce on a tie
+func (k Keeper) GetAttestationByTxHash(ctx sdk.Context, txHash string) *types.Attestation {
+	store := ctx.KVStore(k.storeKey)
+	prefixStore := prefix.NewStore(store, types.GetAttestationByTxHashPrefix(txHash))
+	iter := prefixStore.Iterator(nil, nil)
+	defer iter.Close()
+
+	var found *types.Attestation
+	for ; iter.Valid(); iter.Next() {
+		bz := store.Get(iter.Value())
+		if len(bz) == 0 {
+			continue
+		}
+		var att types.Attestation
+		k.cdc.MustUnmarshalBinaryBare(bz, &att)
+		if att.Observed {
+			return &att
+		}
+		if found == nil || len(att.Votes) > len(found.Votes) {
+			found = &att
+		}
+	}
+	return found
+}
+
+// GetAttestationProgress returns which bonded validators have voted for the attestation of the claim of an
+// Ethereum transaction and how much power it still needs to be observed
+func (k Keeper) GetAttestationProgress(ctx sdk.Context, txHash string) *types.AttestationProgress {
+	att := k.GetAttestationByTxHash(ctx, txHash)
+	if att == nil {
+		return nil
+	}
+	voted := make(map[string]bool, len(att.Votes))
+	for _, validator := range att.Votes {
+		voted[validator] = true
+	}
+
+	totalPower := k.StakingKeeper.GetLastTotalPower(ctx)
+	progress := &types.AttestationProgress{
+		EventNonce:    att.EventNonce,
+		Observed:      att.Observed,
+		Height:        att.Height,
+		VotedPower:    sdk.NewInt(0),
+		RequiredPower: types.AttestationVotesPowerThreshold.Mul(totalPower).Quo(sdk.NewInt(100)),
+		TotalPower:    totalPower,
+		MissingPower:  sdk.NewInt(0),
+	}
+	for _, validator := range k.StakingKeeper.GetBondedValidatorsByPower(ctx) {
+		val := validator.GetOperator()
+		power := k.StakingKeeper.GetLastValidatorPower(ctx, val)
+		if voted[val.String()] {
+			progress.VotedPower = progress.VotedPower.Add(sdk.NewInt(power))
+		}
+		progress.Validators = append(progress.Validators, types.AttestationValidator{
+			Validator: val.String(),
+			Power:     power,
+			Voted:     voted[val.String()],
+		})
+	}
+	if !att.Observed && progress.VotedPower.LT(progress.RequiredPower) {
+		progress.MissingPower = progress.RequiredPower.Sub(progress.VotedPower)
+	}
+	return progress
+}
+
 // storeClaim persists a claim. Fails when a claim submitted by an Eth signer does not increment the event nonce by exactly 1.
 func (k Keeper) storeClaim(ctx sdk.Context, details types.EthereumClaim) error {
 	// Check that the nonce of this event is exactly one higher than the last nonce stored by this validator.
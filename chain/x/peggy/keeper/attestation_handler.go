@@ -10,6 +10,11 @@ import (
 
 var minDepositAmount = sdk.NewInt(100)
 
+// refundClaim is a deposit claim that can be refunded for an invalid destination
+type refundClaim interface {
+	ValidateDestination() error
+}
+
 // AttestationHandler processes `observed` Attestations
 type AttestationHandler struct {
 	keeper       Keeper
@@ -21,6 +26,9 @@ type AttestationHandler struct {
 func (a AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, claim types.EthereumClaim) error {
 	switch claim := claim.(type) {
 	case *types.MsgDepositClaim:
+		if claim.InvalidDestination {
+			return a.refundDeposit(ctx, claim, claim.TokenContract, claim.Amount, claim.EthereumSender, claim.TxHash)
+		}
 		amount := a.keeper.oracleKeeper.ConvertFromEthValue(ctx, claim.TokenContract, claim.Amount)
 		if amount.LT(minDepositAmount) {
 			return sdkerrors.Wrap(sdkerrors.ErrInvalidCoins, "amount is too small to be deposited")
@@ -63,7 +71,7 @@ func (a AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, claim
 
 				for _, val := range valset.Members {
 					amount := commission.Amount.Mul(sdk.NewIntFromUint64(val.Power)).Quo(sdk.NewIntFromUint64(totalPower))
-					_, err := a.minterKeeper.AddToOutgoingPool(ctx, sdk.AccAddress{}, val.MinterAddress, "#commission", sdk.NewCoin(commission.Denom, amount))
+					_, err := a.minterKeeper.AddToOutgoingPool(ctx, sdk.AccAddress{}, val.MinterAddress, "#commission", "", sdk.NewCoin(commission.Denom, amount))
 					if err != nil {
 						return sdkerrors.Wrap(err, "commission withdrawal")
 					}
@@ -87,6 +95,9 @@ func (a AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, claim
 		a.keeper.oracleKeeper.SetTxStatus(ctx, claim.TxHash, oracletypes.TX_STATUS_DEPOSIT_RECEIVED, "")
 
 	case *types.MsgSendToMinterClaim:
+		if claim.InvalidDestination {
+			return a.refundDeposit(ctx, claim, claim.TokenContract, claim.Amount, claim.EthereumSender, claim.TxHash)
+		}
 		amount := a.keeper.oracleKeeper.ConvertFromEthValue(ctx, claim.TokenContract, claim.Amount)
 		if amount.LT(minDepositAmount) {
 			return sdkerrors.Wrap(sdkerrors.ErrInvalidCoins, "amount is too small to be deposited")
@@ -111,7 +122,7 @@ func (a AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, claim
 		}
 
 		commission := sdk.NewCoin(denom, amount.ToDec().Mul(a.keeper.oracleKeeper.GetCommissionForDemon(ctx, denom)).RoundInt())
-		_, err = a.minterKeeper.AddToOutgoingPool(ctx, receiver, claim.MinterReceiver, claim.TxHash, sdk.NewCoin(denom, amount).Sub(commission))
+		_, err = a.minterKeeper.AddToOutgoingPool(ctx, receiver, claim.MinterReceiver, claim.TxHash, "", sdk.NewCoin(denom, amount).Sub(commission))
 		if err != nil {
 			return sdkerrors.Wrap(err, "withdraw")
 		}
@@ -123,3 +134,46 @@ func (a AttestationHandler) Handle(ctx sdk.Context, att types.Attestation, claim
 	}
 	return nil
 }
+
+// refundDeposit returns a deposit the orchestrators found to have an invalid destination to its sender on
+// Ethereum. The tokens are still held by the contract, so nothing is minted, the refund is queued like a
+// withdrawal whose bridge fee, the gas of a single withdrawal, is taken out of the deposit. Deposits too small
+// to pay for their refund are rejected like deposits below the minimum, and so is a claim whose destination
+// is valid after all since it could be delivered
+func (a AttestationHandler) refundDeposit(ctx sdk.Context, claim refundClaim, tokenContract string, ethAmount sdk.Int, ethereumSender string, txHash string) error {
+	if err := claim.ValidateDestination(); err != nil {
+		return sdkerrors.Wrap(err, "refund")
+	}
+
+	token := types.ERC20Token{
+		Amount:   a.keeper.oracleKeeper.ConvertFromEthValue(ctx, tokenContract, ethAmount),
+		Contract: tokenContract,
+	}
+	coin := token.PeggyCoin(ctx, a.keeper.oracleKeeper)
+	if _, err := types.ValidatePeggyCoin(coin, ctx, a.keeper.oracleKeeper); err != nil {
+		return sdkerrors.Wrap(err, "invalid coin")
+	}
+
+	fee, err := a.keeper.singleWithdrawalFee(ctx, coin.Denom)
+	if err != nil {
+		return sdkerrors.Wrap(err, "refund fee")
+	}
+	if coin.Amount.LTE(fee.Amount) {
+		return sdkerrors.Wrap(sdkerrors.ErrInvalidCoins, "amount is too small to be refunded")
+	}
+
+	// the refund address is the sender too, a refund that isn't batched in time stays in the pool
+	if _, err := a.keeper.addRefundToOutgoingPool(ctx, ethereumSender, txHash, coin.Sub(fee), fee); err != nil {
+		return sdkerrors.Wrap(err, "refund")
+	}
+
+	refundEvent := sdk.NewEvent(
+		types.EventTypeRefund,
+		sdk.NewAttribute(sdk.AttributeKeyModule, types.ModuleName),
+		sdk.NewAttribute(types.AttributeKeyTxHash, txHash),
+	)
+	ctx.EventManager().EmitEvent(refundEvent)
+
+	a.keeper.oracleKeeper.SetTxStatus(ctx, txHash, oracletypes.TX_STATUS_REFUNDED, "")
+	return nil
+}
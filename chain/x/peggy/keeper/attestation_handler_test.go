@@ -0,0 +1,133 @@
+package keeper
+
+import (
+	"testing"
+	"time"
+
+	"github.com/MinterTeam/mhub/chain/x/peggy/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestRefundDeposit(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	k := input.PeggyKeeper
+
+	// a single withdrawal costs one dollar at the testing prices
+	params := input.OracleKeeper.GetParams(ctx)
+	params.MinSingleWithdrawGas = 10 * gweiInEth
+	input.OracleKeeper.SetParams(ctx, params)
+
+	var (
+		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5" // Pickle
+		ethSender           = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
+		fee                 = sdk.NewInt(1e18)
+		supply              = input.BankKeeper.GetSupply(ctx).GetTotal()
+	)
+
+	claims := []types.EthereumClaim{
+		&types.MsgDepositClaim{
+			EventNonce:         1,
+			TokenContract:      myTokenContractAddr,
+			Amount:             sdk.NewInt(5e18),
+			EthereumSender:     ethSender,
+			CosmosReceiver:     "cosmos1invalid",
+			Orchestrator:       AccAddrs[0].String(),
+			TxHash:             "0x01",
+			InvalidDestination: true,
+		},
+		&types.MsgSendToMinterClaim{
+			EventNonce:         2,
+			TokenContract:      myTokenContractAddr,
+			Amount:             sdk.NewInt(3e18),
+			EthereumSender:     ethSender,
+			MinterReceiver:     "Mxinvalid",
+			Orchestrator:       AccAddrs[0].String(),
+			TxHash:             "0x02",
+			InvalidDestination: true,
+		},
+	}
+	for _, claim := range claims {
+		require.NoError(t, k.AttestationHandler.Handle(ctx, types.Attestation{}, claim))
+	}
+
+	// the tokens never left the contract, nothing is minted for the refunds
+	assert.Equal(t, supply, input.BankKeeper.GetSupply(ctx).GetTotal())
+
+	var ids []uint64
+	var gotRefunds []*types.OutgoingTx
+	k.IterateOutgoingPoolByFee(ctx, myTokenContractAddr, func(id uint64, tx *types.OutgoingTx) bool {
+		ids = append(ids, id)
+		gotRefunds = append(gotRefunds, tx)
+		return false
+	})
+	expRefund := func(amount int64, txHash string) *types.OutgoingTx {
+		return &types.OutgoingTx{
+			DestAddr:       ethSender,
+			RefundAddr:     ethSender,
+			ExpirationTime: ctx.BlockTime().Add(time.Hour).Unix(),
+			Amount:         types.NewERC20Token(sdk.NewInt(amount), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			BridgeFee:      types.NewERC20Token(fee, myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			TxHash:         txHash,
+		}
+	}
+	// the fee of a single withdrawal is taken out of each deposit
+	assert.Equal(t, []*types.OutgoingTx{expRefund(4e18, "0x01"), expRefund(2e18, "0x02")}, gotRefunds)
+
+	// nobody on the Hub owns a refund
+	for _, sender := range []sdk.AccAddress{AccAddrs[0], {}} {
+		err := k.CancelOutgoingTx(ctx, ids[0], sender)
+		require.True(t, sdkerrors.ErrUnauthorized.Is(err), err)
+	}
+
+	// a deposit that can't pay for its refund is rejected
+	tooSmall := &types.MsgDepositClaim{
+		EventNonce:         3,
+		TokenContract:      myTokenContractAddr,
+		Amount:             fee,
+		EthereumSender:     ethSender,
+		CosmosReceiver:     "cosmos1invalid",
+		Orchestrator:       AccAddrs[0].String(),
+		TxHash:             "0x03",
+		InvalidDestination: true,
+	}
+	err := k.AttestationHandler.Handle(ctx, types.Attestation{}, tooSmall)
+	require.True(t, sdkerrors.ErrInvalidCoins.Is(err), err)
+
+	// and so is a refund of a deposit that can be delivered
+	validReceivers := []types.EthereumClaim{
+		&types.MsgDepositClaim{
+			EventNonce:         4,
+			TokenContract:      myTokenContractAddr,
+			Amount:             sdk.NewInt(5e18),
+			EthereumSender:     ethSender,
+			CosmosReceiver:     AccAddrs[1].String(),
+			Orchestrator:       AccAddrs[0].String(),
+			TxHash:             "0x04",
+			InvalidDestination: true,
+		},
+		&types.MsgSendToMinterClaim{
+			EventNonce:         5,
+			TokenContract:      myTokenContractAddr,
+			Amount:             sdk.NewInt(5e18),
+			EthereumSender:     ethSender,
+			MinterReceiver:     "Mx7633980c000139dd3bd24a3f54e06474fa941e16",
+			Orchestrator:       AccAddrs[0].String(),
+			TxHash:             "0x05",
+			InvalidDestination: true,
+		},
+	}
+	for _, claim := range validReceivers {
+		err := k.AttestationHandler.Handle(ctx, types.Attestation{}, claim)
+		require.True(t, types.ErrInvalid.Is(err), err)
+	}
+	var pooled int
+	k.IterateOutgoingPoolByFee(ctx, myTokenContractAddr, func(_ uint64, _ *types.OutgoingTx) bool {
+		pooled++
+		return false
+	})
+	assert.Equal(t, 2, pooled)
+}
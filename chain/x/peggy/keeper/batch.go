@@ -2,9 +2,12 @@ package keeper
 
 import (
 	"fmt"
+	"github.com/MinterTeam/mhub/chain/power"
 	minterkeeper "github.com/MinterTeam/mhub/chain/x/minter/keeper"
 	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	"strconv"
+	"strings"
+	"time"
 
 	"github.com/MinterTeam/mhub/chain/x/peggy/types"
 	"github.com/cosmos/cosmos-sdk/store/prefix"
@@ -67,6 +70,11 @@ func (k Keeper) BuildOutgoingTXBatch(ctx sdk.Context, contractAddress string, ma
 		}
 	}
 
+	return k.storeNewBatch(ctx, contractAddress, selectedTx), nil
+}
+
+// storeNewBatch persists a batch of the given transactions under the next batch nonce and emits an event
+func (k Keeper) storeNewBatch(ctx sdk.Context, contractAddress string, selectedTx []*types.OutgoingTransferTx) *types.OutgoingTxBatch {
 	nextID := k.autoIncrementID(ctx, types.KeyLastOutgoingBatchID)
 	batch := &types.OutgoingTxBatch{
 		BatchNonce:    nextID,
@@ -90,7 +98,7 @@ func (k Keeper) BuildOutgoingTXBatch(ctx sdk.Context, contractAddress string, ma
 	}
 
 	ctx.EventManager().EmitEvent(batchEvent)
-	return batch, nil
+	return batch
 }
 
 // OutgoingTxBatchExecuted is run when the Cosmos chain detects that a batch has been executed on Ethereum
@@ -101,24 +109,27 @@ func (k Keeper) OutgoingTxBatchExecuted(ctx sdk.Context, tokenContract string, n
 		return sdkerrors.Wrap(types.ErrUnknown, "nonce")
 	}
 
-	totalFee := sdk.NewInt64Coin(b.Transactions[0].Erc20Fee.PeggyCoin(ctx, k.oracleKeeper).Denom, 0)
-	// cleanup outgoing TX pool
-	for _, tx := range b.Transactions {
-		totalFee = totalFee.Add(tx.Erc20Fee.PeggyCoin(ctx, k.oracleKeeper))
-		k.removePoolEntry(ctx, tx.Id)
-	}
-	commissionKeeperAddress := sdk.AccAddress{0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0}
-	vouchers := sdk.Coins{totalFee}
-	if err := k.bankKeeper.MintCoins(ctx, types.ModuleName, vouchers); err != nil {
-		return sdkerrors.Wrapf(err, "mint vouchers coins: %s", vouchers)
-	}
+	// an empty batch only superseded a withheld one, there are no fees to pay the relayer
+	if len(b.Transactions) > 0 {
+		totalFee := sdk.NewInt64Coin(b.Transactions[0].Erc20Fee.PeggyCoin(ctx, k.oracleKeeper).Denom, 0)
+		// cleanup outgoing TX pool
+		for _, tx := range b.Transactions {
+			totalFee = totalFee.Add(tx.Erc20Fee.PeggyCoin(ctx, k.oracleKeeper))
+			k.removePoolEntry(ctx, tx.Id)
+		}
+		commissionKeeperAddress := sdk.AccAddress{0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0}
+		vouchers := sdk.Coins{totalFee}
+		if err := k.bankKeeper.MintCoins(ctx, types.ModuleName, vouchers); err != nil {
+			return sdkerrors.Wrapf(err, "mint vouchers coins: %s", vouchers)
+		}
 
-	if err := k.bankKeeper.SendCoinsFromModuleToAccount(ctx, types.ModuleName, commissionKeeperAddress, vouchers); err != nil {
-		return sdkerrors.Wrap(err, "transfer vouchers")
-	}
+		if err := k.bankKeeper.SendCoinsFromModuleToAccount(ctx, types.ModuleName, commissionKeeperAddress, vouchers); err != nil {
+			return sdkerrors.Wrap(err, "transfer vouchers")
+		}
 
-	k.minterKeeper.AddToOutgoingPool(ctx, commissionKeeperAddress, "Mx"+txSender[2:], txHash, totalFee)
-	k.minterKeeper.BuildOutgoingTXBatch(ctx, minterkeeper.OutgoingTxBatchSize)
+		k.minterKeeper.AddToOutgoingPool(ctx, commissionKeeperAddress, "Mx"+txSender[2:], txHash, "", totalFee)
+		k.minterKeeper.BuildOutgoingTXBatch(ctx, minterkeeper.OutgoingTxBatchSize)
+	}
 
 	// Iterate through remaining batches
 	k.IterateOutgoingTXBatches(ctx, func(key []byte, iterBatch *types.OutgoingTxBatch) bool {
@@ -150,6 +161,85 @@ func (k Keeper) OutgoingTxBatchExecuted(ctx sdk.Context, tokenContract string, n
 	return nil
 }
 
+// SupersedeWithheldBatches reports batches that waited BatchTimeoutBlocks without enough signatures for the Peggy
+// contract and supersedes them with a newer batch, so validators withholding their signatures can't stall a token's
+// withdrawals. The newer batch is built from the token's pool, or is empty when the pool can't be batched. The
+// withheld batch stays, its signatures remain valid on Ethereum until the contract's batch nonce moves past it, so
+// it is only canceled, and its transactions released to the pool, once the newer batch is executed. The validators
+// that didn't sign are listed in the event, which is emitted once, in the block the batch times out.
+func (k Keeper) SupersedeWithheldBatches(ctx sdk.Context) {
+	timeout := k.GetParams(ctx).BatchTimeoutBlocks
+	if timeout == 0 {
+		return
+	}
+
+	// batches are iterated newest first, a batch that has a newer one for its token is already superseded
+	var timedOut []*types.OutgoingTxBatch
+	newest := map[string]bool{}
+	k.IterateOutgoingTXBatches(ctx, func(_ []byte, batch *types.OutgoingTxBatch) bool {
+		if !newest[batch.TokenContract] && uint64(ctx.BlockHeight()) == batch.Block+timeout {
+			timedOut = append(timedOut, batch)
+		}
+		newest[batch.TokenContract] = true
+		return false
+	})
+	// the signatures are checked against the newest valset, the one the contract holds once it is relayed
+	valsets := k.GetValsets(ctx)
+	if len(timedOut) == 0 || len(valsets) == 0 {
+		return
+	}
+
+	for _, batch := range timedOut {
+		signers := k.batchSigners(ctx, batch)
+		var signedPower uint64
+		var withheldBy []string
+		for _, member := range valsets[0].Members {
+			if signers[strings.ToLower(member.EthereumAddress)] {
+				signedPower += member.Power
+			} else {
+				withheldBy = append(withheldBy, member.EthereumAddress)
+			}
+		}
+		if power.PassesContractThreshold(signedPower) {
+			continue
+		}
+
+		ctx.EventManager().EmitEvent(sdk.NewEvent(
+			types.EventTypeOutgoingBatchWithheld,
+			sdk.NewAttribute(sdk.AttributeKeyModule, types.ModuleName),
+			sdk.NewAttribute(types.AttributeKeyContract, batch.TokenContract),
+			sdk.NewAttribute(types.AttributeKeyNonce, fmt.Sprint(batch.BatchNonce)),
+			sdk.NewAttribute(types.AttributeKeyWithheldBy, strings.Join(withheldBy, ",")),
+		))
+		k.logger(ctx).Info("batch withheld", "contract", batch.TokenContract, "nonce", batch.BatchNonce, "withheld_by", strings.Join(withheldBy, ","))
+
+		// fails when the pool is empty or its fees don't cover the gas, an empty batch still moves the contract's
+		// nonce past the withheld one
+		if _, err := k.BuildOutgoingTXBatch(ctx, batch.TokenContract, OutgoingTxBatchSize); err != nil {
+			k.storeNewBatch(ctx, batch.TokenContract, nil)
+		}
+	}
+}
+
+// batchSigners returns the lowercased Ethereum addresses of the validators that confirmed the batch
+func (k Keeper) batchSigners(ctx sdk.Context, batch *types.OutgoingTxBatch) map[string]bool {
+	signers := map[string]bool{}
+	for _, confirm := range k.GetBatchConfirmByNonceAndTokenContract(ctx, batch.BatchNonce, batch.TokenContract) {
+		orchestrator, err := sdk.AccAddressFromBech32(confirm.Orchestrator)
+		if err != nil {
+			continue
+		}
+		validator := k.GetOrchestratorValidator(ctx, orchestrator)
+		if validator == nil {
+			validator = sdk.ValAddress(orchestrator)
+		}
+		if ethAddress := k.GetEthAddress(ctx, validator); ethAddress != "" {
+			signers[strings.ToLower(ethAddress)] = true
+		}
+	}
+	return signers
+}
+
 // StoreBatch stores a transaction batch
 func (k Keeper) StoreBatch(ctx sdk.Context, batch *types.OutgoingTxBatch) {
 	store := ctx.KVStore(k.storeKey)
@@ -183,6 +273,7 @@ func (k Keeper) pickUnbatchedTX(ctx sdk.Context, contractAddress string, maxElem
 			Erc20Token:  types.NewERC20Token(tx.Amount.Amount, contractAddress),
 			Erc20Fee:    types.NewERC20Token(tx.BridgeFee.Amount, contractAddress),
 			TxHash:      tx.TxHash,
+			Memo:        tx.Memo,
 		}
 		selectedTx = append(selectedTx, txOut)
 		return len(selectedTx) == maxElements
@@ -217,6 +308,13 @@ func (k Keeper) CancelOutgoingTXBatch(ctx sdk.Context, tokenContract string, non
 	for _, tx := range batch.Transactions {
 		tx.Erc20Fee.Contract = tokenContract
 		k.prependToUnbatchedTXIndex(ctx, tx.Erc20Fee.PeggyCoin(ctx, k.oracleKeeper), tx.Id)
+		// the tx waited on the batch rather than in the pool, it gets a full pool period before it is refunded
+		if entry, err := k.getPoolEntry(ctx, tx.Id); err == nil {
+			entry.ExpirationTime = ctx.BlockTime().Add(time.Hour).Unix()
+			if err := k.setPoolEntry(ctx, tx.Id, entry); err != nil {
+				return err
+			}
+		}
 	}
 
 	// Delete batch since it is finished
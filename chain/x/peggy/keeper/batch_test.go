@@ -18,8 +18,9 @@ func TestBatches(t *testing.T) {
 		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
 		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
 		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5" // Pickle
+		expiration          = ctx.BlockTime().Add(time.Hour).Unix()
 		allVouchers         = sdk.NewCoins(
-			types.NewERC20Token(99999, myTokenContractAddr).PeggyCoin(),
+			types.NewERC20Token(sdk.NewInt(99999), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		)
 	)
 
@@ -34,9 +35,9 @@ func TestBatches(t *testing.T) {
 
 	// add some TX to the pool
 	for i, v := range []uint64{2, 3, 2, 1} {
-		amount := types.NewERC20Token(uint64(i+100), myTokenContractAddr).PeggyCoin()
-		fee := types.NewERC20Token(v, myTokenContractAddr).PeggyCoin()
-		_, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, amount, fee)
+		amount := types.NewERC20Token(sdk.NewIntFromUint64(uint64(i+100)), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		fee := types.NewERC20Token(sdk.NewIntFromUint64(v), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		_, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", amount, fee)
 		require.NoError(t, err)
 	}
 
@@ -56,17 +57,17 @@ func TestBatches(t *testing.T) {
 		Transactions: []*types.OutgoingTransferTx{
 			{
 				Id:          2,
-				Erc20Fee:    types.NewERC20Token(3, myTokenContractAddr),
+				Erc20Fee:    types.NewERC20Token(sdk.NewInt(3), myTokenContractAddr),
 				Sender:      mySender.String(),
 				DestAddress: myReceiver,
-				Erc20Token:  types.NewERC20Token(101, myTokenContractAddr),
+				Erc20Token:  types.NewERC20Token(sdk.NewInt(101), myTokenContractAddr),
 			},
 			{
 				Id:          1,
-				Erc20Fee:    types.NewERC20Token(2, myTokenContractAddr),
+				Erc20Fee:    types.NewERC20Token(sdk.NewInt(2), myTokenContractAddr),
 				Sender:      mySender.String(),
 				DestAddress: myReceiver,
-				Erc20Token:  types.NewERC20Token(100, myTokenContractAddr),
+				Erc20Token:  types.NewERC20Token(sdk.NewInt(100), myTokenContractAddr),
 			},
 		},
 		TokenContract: myTokenContractAddr,
@@ -82,16 +83,18 @@ func TestBatches(t *testing.T) {
 	})
 	expUnbatchedTx := []*types.OutgoingTx{
 		{
-			BridgeFee: types.NewERC20Token(2, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(102, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(2), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(102), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(1, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(103, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(1), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(103), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 	}
 	assert.Equal(t, expUnbatchedTx, gotUnbatchedTx)
@@ -102,9 +105,9 @@ func TestBatches(t *testing.T) {
 	// add some more TX to the pool to create a more profitable batch
 	for i, v := range []uint64{4, 5} {
 
-		amount := types.NewERC20Token(uint64(i+100), myTokenContractAddr).PeggyCoin()
-		fee := types.NewERC20Token(v, myTokenContractAddr).PeggyCoin()
-		_, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, amount, fee)
+		amount := types.NewERC20Token(sdk.NewIntFromUint64(uint64(i+100)), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		fee := types.NewERC20Token(sdk.NewIntFromUint64(v), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		_, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", amount, fee)
 		require.NoError(t, err)
 	}
 
@@ -120,17 +123,17 @@ func TestBatches(t *testing.T) {
 		Transactions: []*types.OutgoingTransferTx{
 			{
 				Id:          6,
-				Erc20Fee:    types.NewERC20Token(5, myTokenContractAddr),
+				Erc20Fee:    types.NewERC20Token(sdk.NewInt(5), myTokenContractAddr),
 				Sender:      mySender.String(),
 				DestAddress: myReceiver,
-				Erc20Token:  types.NewERC20Token(101, myTokenContractAddr),
+				Erc20Token:  types.NewERC20Token(sdk.NewInt(101), myTokenContractAddr),
 			},
 			{
 				Id:          5,
-				Erc20Fee:    types.NewERC20Token(4, myTokenContractAddr),
+				Erc20Fee:    types.NewERC20Token(sdk.NewInt(4), myTokenContractAddr),
 				Sender:      mySender.String(),
 				DestAddress: myReceiver,
-				Erc20Token:  types.NewERC20Token(100, myTokenContractAddr),
+				Erc20Token:  types.NewERC20Token(sdk.NewInt(100), myTokenContractAddr),
 			},
 		},
 		TokenContract: myTokenContractAddr,
@@ -143,7 +146,8 @@ func TestBatches(t *testing.T) {
 	// =================================
 
 	// Execute the batch
-	input.PeggyKeeper.OutgoingTxBatchExecuted(ctx, secondBatch.TokenContract, secondBatch.BatchNonce)
+	err = input.PeggyKeeper.OutgoingTxBatchExecuted(ctx, secondBatch.TokenContract, secondBatch.BatchNonce, EthAddrs[0].String(), "0x01")
+	require.NoError(t, err)
 
 	// check batch has been deleted
 	gotSecondBatch := input.PeggyKeeper.GetOutgoingTXBatch(ctx, secondBatch.TokenContract, secondBatch.BatchNonce)
@@ -155,31 +159,189 @@ func TestBatches(t *testing.T) {
 		gotUnbatchedTx = append(gotUnbatchedTx, tx)
 		return false
 	})
+	// the freed txs waited on the batch, they get a full pool period again
+	returnedExpiration := now.Add(time.Hour).Unix()
 	expUnbatchedTx = []*types.OutgoingTx{
 		{
-			BridgeFee: types.NewERC20Token(3, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(101, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(3), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: returnedExpiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(101), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(2, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(100, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(2), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: returnedExpiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(100), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(2, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(102, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(2), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(102), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(1, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(103, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(1), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(103), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 	}
 	assert.Equal(t, expUnbatchedTx, gotUnbatchedTx)
 }
+
+func TestSupersedeWithheldBatches(t *testing.T) {
+	input, ctx := SetupFiveValChain(t)
+	pk := input.PeggyKeeper
+	params := pk.GetParams(ctx)
+	params.BatchTimeoutBlocks = 100
+	pk.SetParams(ctx, params)
+	pk.SetValsetRequest(ctx)
+
+	var (
+		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
+		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
+		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5" // Pickle
+		allVouchers         = sdk.NewCoins(
+			types.NewERC20Token(sdk.NewInt(99999), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+		)
+	)
+
+	require.NoError(t, input.BankKeeper.MintCoins(ctx, types.ModuleName, allVouchers))
+	input.AccountKeeper.NewAccountWithAddress(ctx, mySender)
+	require.NoError(t, input.BankKeeper.SetBalances(ctx, mySender, allVouchers))
+
+	for _, v := range []int64{3, 2} {
+		amount := types.NewERC20Token(sdk.NewInt(100), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		fee := types.NewERC20Token(sdk.NewInt(v), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		_, err := pk.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", amount, fee)
+		require.NoError(t, err)
+	}
+
+	// only the most profitable tx is batched, three of five validators sign it, less than the contract's 66%
+	withheld, err := pk.BuildOutgoingTXBatch(ctx, myTokenContractAddr, 1)
+	require.NoError(t, err)
+	for i, val := range AccAddrs[:3] {
+		pk.SetBatchConfirm(ctx, &types.MsgConfirmBatch{
+			Nonce:         withheld.BatchNonce,
+			TokenContract: myTokenContractAddr,
+			EthSigner:     EthAddrs[i].String(),
+			Orchestrator:  val.String(),
+		})
+	}
+
+	// nothing happens before the timeout
+	pk.SupersedeWithheldBatches(ctx.WithBlockHeight(ctx.BlockHeight() + 99))
+	require.Len(t, pk.GetOutgoingTxBatches(ctx), 1)
+
+	// RE-BATCH THE POOL
+	// =================
+
+	ctx = ctx.WithBlockHeight(ctx.BlockHeight() + 100)
+	pk.SupersedeWithheldBatches(ctx)
+
+	// the withheld batch stays, its signatures are still valid on Ethereum
+	require.NotNil(t, pk.GetOutgoingTXBatch(ctx, myTokenContractAddr, withheld.BatchNonce))
+	batches := pk.GetOutgoingTxBatches(ctx)
+	require.Len(t, batches, 2)
+	superseding := batches[0]
+	assert.Greater(t, superseding.BatchNonce, withheld.BatchNonce)
+	require.Len(t, superseding.Transactions, 1)
+	assert.NotEqual(t, withheld.Transactions[0].Id, superseding.Transactions[0].Id)
+
+	// the timeout fires in one block only, the pool isn't batched again in the next
+	pk.SupersedeWithheldBatches(ctx.WithBlockHeight(ctx.BlockHeight() + 1))
+	require.Len(t, pk.GetOutgoingTxBatches(ctx), 2)
+
+	// CANCEL THE WITHHELD BATCH
+	// =========================
+
+	// executing the newer batch moves the contract's nonce past the withheld one, which can never execute now
+	ctx = ctx.WithBlockTime(ctx.BlockTime().Add(2 * time.Hour))
+	err = pk.OutgoingTxBatchExecuted(ctx, myTokenContractAddr, superseding.BatchNonce, EthAddrs[0].String(), "0x01")
+	require.NoError(t, err)
+	require.Nil(t, pk.GetOutgoingTXBatch(ctx, myTokenContractAddr, withheld.BatchNonce))
+	require.Empty(t, pk.GetOutgoingTxBatches(ctx))
+
+	// its tx is back in the pool with a fresh expiration instead of the one it had when it was first added
+	var ids []uint64
+	var pool []*types.OutgoingTx
+	pk.IterateOutgoingPoolByFee(ctx, myTokenContractAddr, func(id uint64, tx *types.OutgoingTx) bool {
+		ids = append(ids, id)
+		pool = append(pool, tx)
+		return false
+	})
+	require.Equal(t, []uint64{withheld.Transactions[0].Id}, ids)
+	assert.Equal(t, ctx.BlockTime().Add(time.Hour).Unix(), pool[0].ExpirationTime)
+}
+
+func TestSupersedeWithheldBatchesWithEmptyPool(t *testing.T) {
+	input, ctx := SetupFiveValChain(t)
+	pk := input.PeggyKeeper
+	params := pk.GetParams(ctx)
+	params.BatchTimeoutBlocks = 100
+	pk.SetParams(ctx, params)
+	pk.SetValsetRequest(ctx)
+
+	var (
+		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
+		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
+		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5" // Pickle
+		allVouchers         = sdk.NewCoins(
+			types.NewERC20Token(sdk.NewInt(99999), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+		)
+	)
+
+	require.NoError(t, input.BankKeeper.MintCoins(ctx, types.ModuleName, allVouchers))
+	input.AccountKeeper.NewAccountWithAddress(ctx, mySender)
+	require.NoError(t, input.BankKeeper.SetBalances(ctx, mySender, allVouchers))
+
+	amount := types.NewERC20Token(sdk.NewInt(100), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+	fee := types.NewERC20Token(sdk.NewInt(3), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+	_, err := pk.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", amount, fee)
+	require.NoError(t, err)
+
+	// the batch takes the whole pool, three of five validators sign it, less than the contract's 66%
+	withheld, err := pk.BuildOutgoingTXBatch(ctx, myTokenContractAddr, 1)
+	require.NoError(t, err)
+	for i, val := range AccAddrs[:3] {
+		pk.SetBatchConfirm(ctx, &types.MsgConfirmBatch{
+			Nonce:         withheld.BatchNonce,
+			TokenContract: myTokenContractAddr,
+			EthSigner:     EthAddrs[i].String(),
+			Orchestrator:  val.String(),
+		})
+	}
+
+	// SUPERSEDE WITH AN EMPTY BATCH
+	// =============================
+
+	ctx = ctx.WithBlockHeight(ctx.BlockHeight() + 100)
+	pk.SupersedeWithheldBatches(ctx)
+
+	batches := pk.GetOutgoingTxBatches(ctx)
+	require.Len(t, batches, 2)
+	superseding := batches[0]
+	assert.Greater(t, superseding.BatchNonce, withheld.BatchNonce)
+	assert.Equal(t, myTokenContractAddr, superseding.TokenContract)
+	assert.Empty(t, superseding.Transactions)
+
+	// RELEASE THE WITHHELD TRANSACTIONS
+	// =================================
+
+	ctx = ctx.WithBlockTime(ctx.BlockTime().Add(2 * time.Hour))
+	err = pk.OutgoingTxBatchExecuted(ctx, myTokenContractAddr, superseding.BatchNonce, EthAddrs[0].String(), "0x01")
+	require.NoError(t, err)
+	require.Empty(t, pk.GetOutgoingTxBatches(ctx))
+
+	var ids []uint64
+	pk.IterateOutgoingPoolByFee(ctx, myTokenContractAddr, func(id uint64, _ *types.OutgoingTx) bool {
+		ids = append(ids, id)
+		return false
+	})
+	require.Equal(t, []uint64{withheld.Transactions[0].Id}, ids)
+}
@@ -2,6 +2,7 @@ package keeper
 
 import (
 	"fmt"
+	"github.com/MinterTeam/mhub/chain/power"
 	minterkeeper "github.com/MinterTeam/mhub/chain/x/minter/keeper"
 	oraclekeeper "github.com/MinterTeam/mhub/chain/x/oracle/keeper"
 	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
@@ -12,7 +13,6 @@ import (
 	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
 	paramtypes "github.com/cosmos/cosmos-sdk/x/params/types"
 	"github.com/tendermint/tendermint/libs/log"
-	"math"
 	"sort"
 	"strconv"
 )
@@ -73,6 +73,13 @@ func (k Keeper) IsStopped(ctx sdk.Context) bool {
 	return params.GetStopped()
 }
 
+// GetHaltAtEventNonce returns the event nonce past which claims are rejected, zero if the bridge is not halted
+func (k Keeper) GetHaltAtEventNonce(ctx sdk.Context) uint64 {
+	var a uint64
+	k.paramSpace.GetIfExists(ctx, types.ParamsStoreHaltAtEventNonce, &a)
+	return a
+}
+
 /////////////////////////////
 //     VALSET REQUESTS     //
 /////////////////////////////
@@ -325,7 +332,7 @@ func (k Keeper) GetCurrentValset(ctx sdk.Context) *types.Valset {
 	}
 	// normalize power values
 	for i := range bridgeValidators {
-		bridgeValidators[i].Power = sdk.NewUint(bridgeValidators[i].Power).MulUint64(math.MaxUint32).QuoUint64(totalPower).Uint64()
+		bridgeValidators[i].Power = power.ContractPower(bridgeValidators[i].Power, totalPower)
 	}
 
 	// TODO: make the nonce an incrementing one (i.e. fetch last nonce from state, increment, set here)
@@ -363,6 +370,21 @@ func (k Keeper) SetParams(ctx sdk.Context, ps types.Params) {
 	k.paramSpace.SetParamSet(ctx, &ps)
 }
 
+// SetMissingParams stores the defaults of params added after the chain started. GetParams panics on a key the
+// store doesn't have, so the upgrade that ships a new param has to call this before anything reads the params.
+func (k Keeper) SetMissingParams(ctx sdk.Context) {
+	defaults := types.DefaultParams()
+	k.setParamIfMissing(ctx, types.ParamsStoreHaltAtEventNonce, &defaults.HaltAtEventNonce)
+	k.setParamIfMissing(ctx, types.ParamsStoreMinOrchestratorVersion, &defaults.MinOrchestratorVersion)
+	k.setParamIfMissing(ctx, types.ParamsStoreBatchTimeoutBlocks, &defaults.BatchTimeoutBlocks)
+}
+
+func (k Keeper) setParamIfMissing(ctx sdk.Context, key []byte, value interface{}) {
+	if !k.paramSpace.Has(ctx, key) {
+		k.paramSpace.Set(ctx, key, value)
+	}
+}
+
 // GetBridgeContractAddress returns the bridge contract address on ETH
 func (k Keeper) GetBridgeContractAddress(ctx sdk.Context) string {
 	var a string
@@ -428,7 +450,7 @@ func (k Keeper) RefundOutgoingTx(ctx sdk.Context, id uint64, tx *types.OutgoingT
 		if err := k.bankKeeper.SendCoinsFromModuleToAccount(ctx, types.ModuleName, receiver, vouchers); err != nil {
 			panic(err)
 		}
-		if _, err := k.minterKeeper.AddToOutgoingPool(ctx, receiver, tx.RefundAddr, tx.TxHash, vouchers[0]); err != nil {
+		if _, err := k.minterKeeper.AddToOutgoingPool(ctx, receiver, tx.RefundAddr, tx.TxHash, tx.Memo, vouchers[0]); err != nil {
 			panic(err)
 		}
 	} else {
@@ -116,3 +116,91 @@ func TestAttestationIterator(t *testing.T) {
 
 	require.Len(t, atts, 2)
 }
+
+func TestAttestationProgress(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	powers := []int64{50, 30, 20}
+	operators := make([]MockStakingValidatorData, len(powers))
+	for i, power := range powers {
+		operators[i] = MockStakingValidatorData{Operator: sdk.ValAddress(AccAddrs[i]), Power: power}
+		input.PeggyKeeper.SetOrchestratorValidator(ctx, sdk.ValAddress(AccAddrs[i]), AccAddrs[i])
+	}
+	input.PeggyKeeper.StakingKeeper = NewStakingKeeperWeightedMock(operators...)
+
+	claim := &types.MsgDepositClaim{
+		EventNonce:     1,
+		TokenContract:  TokenContractAddrs[0],
+		Amount:         sdk.NewInt(100),
+		EthereumSender: EthAddrs[0].String(),
+		CosmosReceiver: AccAddrs[0].String(),
+		Orchestrator:   AccAddrs[0].String(),
+		TxHash:         "0xABCD",
+	}
+	_, err := input.PeggyKeeper.AddClaim(ctx, claim)
+	require.NoError(t, err)
+
+	assert.Nil(t, input.PeggyKeeper.GetAttestationProgress(ctx, "0x1234"))
+	progress := input.PeggyKeeper.GetAttestationProgress(ctx, "0xabcd")
+	require.NotNil(t, progress)
+	assert.Equal(t, uint64(1), progress.EventNonce)
+	assert.False(t, progress.Observed)
+	assert.Equal(t, sdk.NewInt(50), progress.VotedPower)
+	assert.Equal(t, sdk.NewInt(66), progress.RequiredPower)
+	assert.Equal(t, sdk.NewInt(16), progress.MissingPower)
+	pending := progress.Pending()
+	require.Len(t, pending, 2)
+	assert.Equal(t, sdk.ValAddress(AccAddrs[1]).String(), pending[0].Validator)
+	assert.Equal(t, sdk.ValAddress(AccAddrs[2]).String(), pending[1].Validator)
+}
+
+func TestAttestationByTxHashConflictingClaims(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	operators := make([]MockStakingValidatorData, 4)
+	for i := range operators {
+		operators[i] = MockStakingValidatorData{Operator: sdk.ValAddress(AccAddrs[i]), Power: 25}
+		input.PeggyKeeper.SetOrchestratorValidator(ctx, sdk.ValAddress(AccAddrs[i]), AccAddrs[i])
+	}
+	input.PeggyKeeper.StakingKeeper = NewStakingKeeperWeightedMock(operators...)
+
+	claim := func(orchestrator int, amount int64) *types.MsgDepositClaim {
+		return &types.MsgDepositClaim{
+			EventNonce:     1,
+			TokenContract:  TokenContractAddrs[0],
+			Amount:         sdk.NewInt(amount),
+			EthereumSender: EthAddrs[0].String(),
+			CosmosReceiver: AccAddrs[0].String(),
+			Orchestrator:   AccAddrs[orchestrator].String(),
+			TxHash:         "0xabcd",
+		}
+	}
+
+	// two validators claim one amount, then a third claims another for the same tx hash
+	for _, orchestrator := range []int{0, 1} {
+		_, err := input.PeggyKeeper.AddClaim(ctx, claim(orchestrator, 100))
+		require.NoError(t, err)
+	}
+	_, err := input.PeggyKeeper.AddClaim(ctx, claim(2, 999))
+	require.NoError(t, err)
+
+	att := input.PeggyKeeper.GetAttestationByTxHash(ctx, "0xabcd")
+	require.NotNil(t, att)
+	assert.Equal(t, claim(0, 100).ClaimHash(), att.ClaimHash)
+	assert.False(t, att.Observed)
+	assert.Len(t, att.Votes, 2)
+
+	// the last validator brings the first claim to quorum, the conflicting one does not replace it
+	_, err = input.PeggyKeeper.AddClaim(ctx, claim(3, 100))
+	require.NoError(t, err)
+
+	att = input.PeggyKeeper.GetAttestationByTxHash(ctx, "0xABCD")
+	require.NotNil(t, att)
+	assert.Equal(t, claim(0, 100).ClaimHash(), att.ClaimHash)
+	assert.True(t, att.Observed)
+	progress := input.PeggyKeeper.GetAttestationProgress(ctx, "0xabcd")
+	require.NotNil(t, progress)
+	assert.True(t, progress.Observed)
+	assert.Equal(t, sdk.NewInt(75), progress.VotedPower)
+	assert.Equal(t, sdk.NewInt(0), progress.MissingPower)
+}
@@ -5,6 +5,7 @@ import (
 	"encoding/hex"
 	"fmt"
 
+	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	"github.com/MinterTeam/mhub/chain/x/peggy/types"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
@@ -161,22 +162,91 @@ func (k msgServer) SendToEth(c context.Context, msg *types.MsgSendToEth) (*types
 		return nil, sdkerrors.Wrap(sdkerrors.ErrInvalidRequest, "bridge fee is not sufficient")
 	}
 
-	txID, err := k.AddToOutgoingPool(ctx, sender, msg.EthDest, sender.String(), "todo", msg.Amount, msg.BridgeFee) // todo
+	txHash := oracletypes.CurrentTxHash(ctx)
+	txID, err := k.AddToOutgoingPool(ctx, sender, msg.EthDest, sender.String(), txHash, msg.Memo, msg.Amount, msg.BridgeFee)
 	if err != nil {
 		return nil, err
 	}
+	if msg.Memo != "" {
+		k.oracleKeeper.SetTxMemo(ctx, txHash, msg.Memo)
+	}
 
 	ctx.EventManager().EmitEvent(
 		sdk.NewEvent(
 			sdk.EventTypeMessage,
 			sdk.NewAttribute(sdk.AttributeKeyModule, msg.Type()),
 			sdk.NewAttribute(types.AttributeKeyOutgoingTXID, fmt.Sprint(txID)),
+			sdk.NewAttribute(types.AttributeKeyMemo, msg.Memo),
 		),
 	)
 
 	return &types.MsgSendToEthResponse{}, nil
 }
 
+// CancelSendToEth handles MsgCancelSendToEth
+func (k msgServer) CancelSendToEth(c context.Context, msg *types.MsgCancelSendToEth) (*types.MsgCancelSendToEthResponse, error) {
+	ctx := sdk.UnwrapSDKContext(c)
+	if k.Keeper.IsStopped(ctx) {
+		return nil, types.ErrServiceStopped
+	}
+
+	sender, err := sdk.AccAddressFromBech32(msg.Sender)
+	if err != nil {
+		return nil, sdkerrors.Wrap(sdkerrors.ErrInvalidAddress, msg.Sender)
+	}
+
+	if err := k.CancelOutgoingTx(ctx, msg.TransactionId, sender); err != nil {
+		return nil, err
+	}
+
+	ctx.EventManager().EmitEvent(
+		sdk.NewEvent(
+			sdk.EventTypeMessage,
+			sdk.NewAttribute(sdk.AttributeKeyModule, msg.Type()),
+			sdk.NewAttribute(types.AttributeKeyOutgoingTXID, fmt.Sprint(msg.TransactionId)),
+		),
+	)
+
+	return &types.MsgCancelSendToEthResponse{}, nil
+}
+
+// RelayReceipt handles MsgRelayReceipt, the receipt is only emitted as an event for accounting. Only orchestrators
+// send receipts, for a valset or batch the Hub still has, so the events can't be flooded with made up relays
+func (k msgServer) RelayReceipt(c context.Context, msg *types.MsgRelayReceipt) (*types.MsgRelayReceiptResponse, error) {
+	ctx := sdk.UnwrapSDKContext(c)
+	if k.Keeper.IsStopped(ctx) {
+		return nil, types.ErrServiceStopped
+	}
+
+	relayer, _ := sdk.AccAddressFromBech32(msg.Relayer)
+	if k.GetOrchestratorValidator(ctx, relayer) == nil && k.StakingKeeper.Validator(ctx, sdk.ValAddress(relayer)) == nil {
+		return nil, sdkerrors.Wrap(sdkerrors.ErrUnauthorized, "relayer is not an orchestrator")
+	}
+
+	nonceKey := types.AttributeKeyValsetNonce
+	if msg.TokenContract != "" {
+		nonceKey = types.AttributeKeyBatchNonce
+		if k.GetOutgoingTXBatch(ctx, msg.TokenContract, msg.Nonce) == nil {
+			return nil, sdkerrors.Wrap(types.ErrUnknown, "batch")
+		}
+	} else if !k.HasValsetRequest(ctx, msg.Nonce) {
+		return nil, sdkerrors.Wrap(types.ErrUnknown, "valset")
+	}
+
+	ctx.EventManager().EmitEvent(
+		sdk.NewEvent(
+			types.EventTypeRelayReceipt,
+			sdk.NewAttribute(sdk.AttributeKeyModule, types.ModuleName),
+			sdk.NewAttribute(types.AttributeKeyRelayer, msg.Relayer),
+			sdk.NewAttribute(nonceKey, fmt.Sprint(msg.Nonce)),
+			sdk.NewAttribute(types.AttributeKeyTokenContract, msg.TokenContract),
+			sdk.NewAttribute(types.AttributeKeyTxHash, msg.EthTxHash),
+		),
+	)
+
+	return &types.MsgRelayReceiptResponse{}, nil
+}
+
 // RequestBatch handles MsgRequestBatch
 func (k msgServer) RequestBatch(c context.Context, msg *types.MsgRequestBatch) (*types.MsgRequestBatchResponse, error) {
 	ctx := sdk.UnwrapSDKContext(c)
@@ -0,0 +1,116 @@
+package keeper
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/MinterTeam/mhub/chain/x/peggy/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
+	"github.com/stretchr/testify/assert"
+	"github.com/stretchr/testify/require"
+)
+
+func TestMsgCancelSendToEth(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	var (
+		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
+		notMySender, _      = sdk.AccAddressFromBech32("cosmos1mgamdcs9dah0vn0gqupl05up7pedg2mvupe6hh")
+		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
+		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5"
+		voucher             = func(amount int64) sdk.Coin {
+			return types.NewERC20Token(sdk.NewInt(amount), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		}
+		msgServer = NewMsgServerImpl(input.PeggyKeeper)
+	)
+	allVouchers := sdk.Coins{voucher(1000)}
+	require.NoError(t, input.BankKeeper.MintCoins(ctx, types.ModuleName, allVouchers))
+	input.AccountKeeper.NewAccountWithAddress(ctx, mySender)
+	require.NoError(t, input.BankKeeper.SetBalances(ctx, mySender, allVouchers))
+
+	firstID, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", voucher(100), voucher(10))
+	require.NoError(t, err)
+	secondID, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", voucher(200), voucher(20))
+	require.NoError(t, err)
+
+	// someone else can't cancel the transfer
+	_, err = msgServer.CancelSendToEth(sdk.WrapSDKContext(ctx), &types.MsgCancelSendToEth{TransactionId: firstID, Sender: notMySender.String()})
+	require.True(t, sdkerrors.ErrUnauthorized.Is(err), err)
+	assert.Equal(t, voucher(1000-110-220), input.BankKeeper.GetBalance(ctx, mySender, voucher(0).Denom))
+
+	// the sender is refunded
+	_, err = msgServer.CancelSendToEth(sdk.WrapSDKContext(ctx), &types.MsgCancelSendToEth{TransactionId: firstID, Sender: mySender.String()})
+	require.NoError(t, err)
+	assert.Equal(t, voucher(1000-220), input.BankKeeper.GetBalance(ctx, mySender, voucher(0).Denom))
+
+	// a transfer in a batch stays there
+	_, err = input.PeggyKeeper.BuildOutgoingTXBatch(ctx, myTokenContractAddr, 10)
+	require.NoError(t, err)
+	_, err = msgServer.CancelSendToEth(sdk.WrapSDKContext(ctx), &types.MsgCancelSendToEth{TransactionId: secondID, Sender: mySender.String()})
+	require.True(t, types.ErrInvalid.Is(err), err)
+	assert.Equal(t, voucher(1000-220), input.BankKeeper.GetBalance(ctx, mySender, voucher(0).Denom))
+}
+
+func TestMsgRelayReceipt(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	pk := input.PeggyKeeper
+	var (
+		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
+		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
+		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5"
+		otherTokenContract  = "0xb462864E395d88d6bc7C5dd5F3F5eb4cc2599255"
+		txHash              = "0x" + strings.Repeat("ab", 32)
+		voucher             = func(amount int64) sdk.Coin {
+			return types.NewERC20Token(sdk.NewInt(amount), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		}
+	)
+	// the first validator orchestrates with its own key, the second with a delegate key
+	pk.StakingKeeper = NewStakingKeeperMock(ValAddrs[0], ValAddrs[1])
+	pk.SetOrchestratorValidator(ctx, ValAddrs[1], AccAddrs[2])
+	for i, val := range ValAddrs[:2] {
+		pk.SetEthAddress(ctx, val, EthAddrs[i].String())
+	}
+	valset := pk.SetValsetRequest(ctx)
+	require.True(t, pk.HasValsetRequest(ctx, valset.Nonce))
+
+	allVouchers := sdk.Coins{voucher(1000)}
+	require.NoError(t, input.BankKeeper.MintCoins(ctx, types.ModuleName, allVouchers))
+	input.AccountKeeper.NewAccountWithAddress(ctx, mySender)
+	require.NoError(t, input.BankKeeper.SetBalances(ctx, mySender, allVouchers))
+	_, err := pk.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", voucher(100), voucher(10))
+	require.NoError(t, err)
+	batch, err := pk.BuildOutgoingTXBatch(ctx, myTokenContractAddr, 10)
+	require.NoError(t, err)
+
+	msgServer := NewMsgServerImpl(pk)
+	specs := map[string]struct {
+		msg    *types.MsgRelayReceipt
+		expErr *sdkerrors.Error
+	}{
+		"valset by a validator":   {msg: types.NewMsgRelayReceipt(AccAddrs[0], valset.Nonce, "", txHash)},
+		"batch by a delegate key": {msg: types.NewMsgRelayReceipt(AccAddrs[2], batch.BatchNonce, myTokenContractAddr, txHash)},
+		"not an orchestrator":     {msg: types.NewMsgRelayReceipt(AccAddrs[3], valset.Nonce, "", txHash), expErr: sdkerrors.ErrUnauthorized},
+		"unknown valset":          {msg: types.NewMsgRelayReceipt(AccAddrs[0], valset.Nonce+1, "", txHash), expErr: types.ErrUnknown},
+		"unknown batch":           {msg: types.NewMsgRelayReceipt(AccAddrs[0], batch.BatchNonce+1, myTokenContractAddr, txHash), expErr: types.ErrUnknown},
+		"batch of another token":  {msg: types.NewMsgRelayReceipt(AccAddrs[0], batch.BatchNonce, otherTokenContract, txHash), expErr: types.ErrUnknown},
+	}
+	for name, spec := range specs {
+		t.Run(name, func(t *testing.T) {
+			_, err := msgServer.RelayReceipt(sdk.WrapSDKContext(ctx), spec.msg)
+			if spec.expErr != nil {
+				require.True(t, spec.expErr.Is(err), err)
+				return
+			}
+			require.NoError(t, err)
+		})
+	}
+
+	// nothing is accepted while the bridge is stopped
+	params := pk.GetParams(ctx)
+	params.Stopped = true
+	pk.SetParams(ctx, params)
+	_, err = msgServer.RelayReceipt(sdk.WrapSDKContext(ctx), types.NewMsgRelayReceipt(AccAddrs[0], valset.Nonce, "", txHash))
+	require.True(t, types.ErrServiceStopped.Is(err), err)
+}
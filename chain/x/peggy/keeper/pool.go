@@ -17,7 +17,7 @@ import (
 // - burns the voucher for transfer amount and fees
 // - persists an OutgoingTx
 // - adds the TX to the `available` TX pool via a second index
-func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counterpartReceiver string, refundAddr string, txHash string, amount sdk.Coin, fee sdk.Coin) (uint64, error) {
+func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counterpartReceiver string, refundAddr string, txHash string, memo string, amount sdk.Coin, fee sdk.Coin) (uint64, error) {
 	totalAmount := amount.Add(fee)
 	totalInVouchers := sdk.Coins{totalAmount}
 
@@ -37,18 +37,35 @@ func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counte
 		panic(err)
 	}
 
+	return k.addToOutgoingPool(ctx, sender.String(), counterpartReceiver, refundAddr, txHash, memo, contractAddr, amount, fee)
+}
+
+// addRefundToOutgoingPool queues the refund of a deposit to its sender on Ethereum. The deposited tokens never
+// left the contract, so there are no vouchers to burn. The refund has no sender on the Hub, nobody can cancel it
+func (k Keeper) addRefundToOutgoingPool(ctx sdk.Context, ethereumSender string, txHash string, amount sdk.Coin, fee sdk.Coin) (uint64, error) {
+	contractAddr, err := types.ValidatePeggyCoin(amount.Add(fee), ctx, k.oracleKeeper)
+	if err != nil {
+		return 0, fmt.Errorf("amount not a peggy voucher coin: %s", err)
+	}
+
+	return k.addToOutgoingPool(ctx, "", ethereumSender, ethereumSender, txHash, "", contractAddr, amount, fee)
+}
+
+// addToOutgoingPool persists an OutgoingTx whose vouchers are already taken care of and indexes it by fee
+func (k Keeper) addToOutgoingPool(ctx sdk.Context, sender string, counterpartReceiver string, refundAddr string, txHash string, memo string, contractAddr string, amount sdk.Coin, fee sdk.Coin) (uint64, error) {
 	// get next tx id from keeper
 	nextID := k.autoIncrementID(ctx, types.KeyLastTXPoolID)
 
 	// construct outgoing tx
 	outgoing := &types.OutgoingTx{
-		Sender:         sender.String(),
+		Sender:         sender,
 		DestAddr:       counterpartReceiver,
 		RefundAddr:     refundAddr,
 		ExpirationTime: ctx.BlockTime().Add(time.Hour).Unix(),
 		Amount:         sdk.NewCoin(amount.Denom, k.oracleKeeper.ConvertToEthValue(ctx, contractAddr, amount.Amount)),
 		BridgeFee:      fee,
 		TxHash:         txHash,
+		Memo:           memo,
 	}
 
 	// set the outgoing tx in the pool index
@@ -70,12 +87,91 @@ func (k Keeper) AddToOutgoingPool(ctx sdk.Context, sender sdk.AccAddress, counte
 		sdk.NewAttribute(types.AttributeKeyOutgoingTXID, strconv.Itoa(int(nextID))),
 		sdk.NewAttribute(types.AttributeKeyNonce, fmt.Sprint(nextID)),
 		sdk.NewAttribute(types.AttributeKeyTxHash, txHash),
+		sdk.NewAttribute(types.AttributeKeyMemo, memo),
 	)
 	ctx.EventManager().EmitEvent(poolEvent)
 
 	return nextID, nil
 }
 
+// CancelOutgoingTx
+// - checks the tx is still in the pool and was sent by sender
+// - removes it from the pool and the `available` index, batched txs can't be cancelled
+// - mints the vouchers for the transfer amount and fee back to the sender
+func (k Keeper) CancelOutgoingTx(ctx sdk.Context, id uint64, sender sdk.AccAddress) error {
+	tx, err := k.getPoolEntry(ctx, id)
+	if err != nil {
+		return sdkerrors.Wrap(types.ErrUnknown, "tx id")
+	}
+	// refunds of deposits have no sender on the Hub
+	if tx.Sender == "" || tx.Sender != sender.String() {
+		return sdkerrors.Wrapf(sdkerrors.ErrUnauthorized, "tx %d was not sent by %s", id, sender)
+	}
+	// txs taken into a batch are no longer in the index and are refunded when the batch times out
+	if err := k.removeFromUnbatchedTXIndex(ctx, tx.BridgeFee, id); err != nil {
+		return sdkerrors.Wrap(types.ErrInvalid, "tx is already in a batch")
+	}
+	k.removePoolEntry(ctx, id)
+
+	contractAddr, err := types.ValidatePeggyCoin(tx.Amount, ctx, k.oracleKeeper)
+	if err != nil {
+		return err
+	}
+	// the pool keeps the amount in the ethereum denomination and the fee as it was paid
+	vouchers := sdk.NewCoins(
+		sdk.NewCoin(tx.Amount.Denom, k.oracleKeeper.ConvertFromEthValue(ctx, contractAddr, tx.Amount.Amount)),
+	).Add(tx.BridgeFee)
+
+	if err := k.bankKeeper.MintCoins(ctx, types.ModuleName, vouchers); err != nil {
+		panic(sdkerrors.Wrapf(err, "mint vouchers coins: %s", vouchers))
+	}
+	if err := k.bankKeeper.SendCoinsFromModuleToAccount(ctx, types.ModuleName, sender, vouchers); err != nil {
+		panic(err)
+	}
+
+	cancelEvent := sdk.NewEvent(
+		types.EventTypeRefund,
+		sdk.NewAttribute(sdk.AttributeKeyModule, types.ModuleName),
+		sdk.NewAttribute(types.AttributeKeyOutgoingTXID, strconv.Itoa(int(id))),
+		sdk.NewAttribute(types.AttributeKeyTxHash, tx.TxHash),
+	)
+	ctx.EventManager().EmitEvent(cancelEvent)
+
+	return nil
+}
+
+// singleWithdrawalFee is the bridge fee in denom that pays the gas of a withdrawal batched on its own, rounded
+// up so it passes the same check MsgSendToEth fees do
+func (k Keeper) singleWithdrawalFee(ctx sdk.Context, denom string) (sdk.Coin, error) {
+	minterCoinId, err := k.oracleKeeper.GetCoins(ctx).GetMinterIdByDenom(denom)
+	if err != nil {
+		return sdk.Coin{}, sdkerrors.Wrap(err, "coin")
+	}
+
+	coinPrice, err := k.oracleKeeper.GetMinterPrice(ctx, minterCoinId)
+	if err != nil {
+		return sdk.Coin{}, sdkerrors.Wrap(err, "fee")
+	}
+	if !coinPrice.IsPositive() {
+		return sdk.Coin{}, sdkerrors.Wrap(types.ErrInvalid, "coin price")
+	}
+
+	gasPrice, err := k.oracleKeeper.GetEthGasPrice(ctx)
+	if err != nil {
+		return sdk.Coin{}, sdkerrors.Wrap(err, "gas price")
+	}
+
+	ethPrice, err := k.oracleKeeper.GetEthPrice(ctx)
+	if err != nil {
+		return sdk.Coin{}, sdkerrors.Wrap(err, "eth price")
+	}
+
+	totalUsdGas := gasPrice.Mul(ethPrice).MulRaw(int64(k.oracleKeeper.GetMinSingleWithdrawGas(ctx))).QuoRaw(gweiInEth).QuoRaw(k.oracleKeeper.GetGasUnits())
+	fee := totalUsdGas.Mul(k.oracleKeeper.GetPipInBip()).Add(coinPrice).SubRaw(1).Quo(coinPrice)
+
+	return sdk.NewCoin(denom, fee), nil
+}
+
 // appendToUnbatchedTXIndex add at the end when tx with same fee exists
 func (k Keeper) appendToUnbatchedTXIndex(ctx sdk.Context, fee sdk.Coin, txID uint64) {
 	store := ctx.KVStore(k.storeKey)
@@ -2,9 +2,11 @@ package keeper
 
 import (
 	"testing"
+	"time"
 
 	"github.com/MinterTeam/mhub/chain/x/peggy/types"
 	sdk "github.com/cosmos/cosmos-sdk/types"
+	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
 	"github.com/stretchr/testify/assert"
 	"github.com/stretchr/testify/require"
 )
@@ -16,9 +18,10 @@ func TestAddToOutgoingPool(t *testing.T) {
 		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
 		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
 		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5"
+		expiration          = ctx.BlockTime().Add(time.Hour).Unix()
 	)
 	// mint some voucher first
-	allVouchers := sdk.Coins{types.NewERC20Token(99999, myTokenContractAddr).PeggyCoin()}
+	allVouchers := sdk.Coins{types.NewERC20Token(sdk.NewInt(99999), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)}
 	err := input.BankKeeper.MintCoins(ctx, types.ModuleName, allVouchers)
 	require.NoError(t, err)
 
@@ -29,9 +32,9 @@ func TestAddToOutgoingPool(t *testing.T) {
 
 	// when
 	for i, v := range []uint64{2, 3, 2, 1} {
-		amount := types.NewERC20Token(uint64(i+100), myTokenContractAddr).PeggyCoin()
-		fee := types.NewERC20Token(v, myTokenContractAddr).PeggyCoin()
-		r, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, amount, fee)
+		amount := types.NewERC20Token(sdk.NewIntFromUint64(uint64(i+100)), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		fee := types.NewERC20Token(sdk.NewIntFromUint64(v), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		r, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", amount, fee)
 		require.NoError(t, err)
 		t.Logf("___ response: %#v", r)
 	}
@@ -43,29 +46,83 @@ func TestAddToOutgoingPool(t *testing.T) {
 	})
 	exp := []*types.OutgoingTx{
 		{
-			BridgeFee: types.NewERC20Token(3, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(101, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(3), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(101), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(2, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(100, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(2), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(100), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(2, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(102, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(2), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(102), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 		{
-			BridgeFee: types.NewERC20Token(1, myTokenContractAddr).PeggyCoin(),
-			Sender:    mySender.String(),
-			DestAddr:  myReceiver,
-			Amount:    types.NewERC20Token(103, myTokenContractAddr).PeggyCoin(),
+			BridgeFee:      types.NewERC20Token(sdk.NewInt(1), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
+			Sender:         mySender.String(),
+			DestAddr:       myReceiver,
+			ExpirationTime: expiration,
+			Amount:         types.NewERC20Token(sdk.NewInt(103), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper),
 		},
 	}
 	assert.Equal(t, exp, got)
 }
+
+func TestCancelOutgoingTx(t *testing.T) {
+	input := CreateTestEnv(t)
+	ctx := input.Context
+	var (
+		mySender, _         = sdk.AccAddressFromBech32("cosmos1ahx7f8wyertuus9r20284ej0asrs085case3kn")
+		notMySender, _      = sdk.AccAddressFromBech32("cosmos1mgamdcs9dah0vn0gqupl05up7pedg2mvupe6hh")
+		myReceiver          = "0xd041c41EA1bf0F006ADBb6d2c9ef9D425dE5eaD7"
+		myTokenContractAddr = "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5"
+		voucher             = func(amount int64) sdk.Coin {
+			return types.NewERC20Token(sdk.NewInt(amount), myTokenContractAddr).PeggyCoin(ctx, input.OracleKeeper)
+		}
+	)
+	// mint some voucher first
+	allVouchers := sdk.Coins{voucher(99999)}
+	require.NoError(t, input.BankKeeper.MintCoins(ctx, types.ModuleName, allVouchers))
+	input.AccountKeeper.NewAccountWithAddress(ctx, mySender)
+	require.NoError(t, input.BankKeeper.SetBalances(ctx, mySender, allVouchers))
+
+	firstID, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", voucher(100), voucher(2))
+	require.NoError(t, err)
+	secondID, err := input.PeggyKeeper.AddToOutgoingPool(ctx, mySender, myReceiver, "", "", "", voucher(101), voucher(3))
+	require.NoError(t, err)
+	assert.Equal(t, voucher(99999-102-104), input.BankKeeper.GetBalance(ctx, mySender, voucher(0).Denom))
+
+	// only the sender can cancel
+	err = input.PeggyKeeper.CancelOutgoingTx(ctx, firstID, notMySender)
+	require.True(t, sdkerrors.ErrUnauthorized.Is(err), err)
+
+	// the sender gets the amount and the fee back and the tx leaves the pool
+	require.NoError(t, input.PeggyKeeper.CancelOutgoingTx(ctx, firstID, mySender))
+	assert.Equal(t, voucher(99999-104), input.BankKeeper.GetBalance(ctx, mySender, voucher(0).Denom))
+	var got []uint64
+	input.PeggyKeeper.IterateOutgoingPoolByFee(ctx, myTokenContractAddr, func(id uint64, _ *types.OutgoingTx) bool {
+		got = append(got, id)
+		return false
+	})
+	assert.Equal(t, []uint64{secondID}, got)
+
+	// a canceled tx is gone
+	err = input.PeggyKeeper.CancelOutgoingTx(ctx, firstID, mySender)
+	require.True(t, types.ErrUnknown.Is(err), err)
+
+	// a batched tx can't be canceled, it is refunded when the batch is canceled
+	_, err = input.PeggyKeeper.BuildOutgoingTXBatch(ctx, myTokenContractAddr, 10)
+	require.NoError(t, err)
+	err = input.PeggyKeeper.CancelOutgoingTx(ctx, secondID, mySender)
+	require.True(t, types.ErrInvalid.Is(err), err)
+	assert.Equal(t, voucher(99999-104), input.BankKeeper.GetBalance(ctx, mySender, voucher(0).Denom))
+}
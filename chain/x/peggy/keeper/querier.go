@@ -35,6 +35,10 @@ const (
 	QueryLastPendingBatchRequestByAddr = "lastPendingBatchRequest"
 	QueryOutgoingTxBatches             = "lastBatches"
 	QueryBatchConfirms                 = "batchConfirms"
+
+	// Attestations
+
+	QueryAttestationProgress = "attestationProgress"
 )
 
 // NewQuerier is the module level router for state queries
@@ -66,6 +70,10 @@ func NewQuerier(keeper Keeper) sdk.Querier {
 		case QueryOutgoingTxBatches:
 			return lastBatchesRequest(ctx, keeper) // Tested (lightly)
 
+		// Attestations
+		case QueryAttestationProgress:
+			return queryAttestationProgress(ctx, path[1], keeper)
+
 		case QueryPeggyID:
 			return queryPeggyID(ctx, keeper)
 
@@ -323,6 +331,19 @@ func queryBatch(ctx sdk.Context, nonce string, tokenContract string, keeper Keep
 	return res, nil
 }
 
+// queryAttestationProgress gets the voting progress of the attestation of a claim by its Ethereum tx hash
+func queryAttestationProgress(ctx sdk.Context, txHash string, keeper Keeper) ([]byte, error) {
+	progress := keeper.GetAttestationProgress(ctx, txHash)
+	if progress == nil {
+		return nil, nil
+	}
+	res, err := codec.MarshalJSONIndent(types.ModuleCdc, progress)
+	if err != nil {
+		return nil, sdkerrors.Wrap(sdkerrors.ErrJSONMarshal, err.Error())
+	}
+	return res, nil
+}
+
 func queryPeggyID(ctx sdk.Context, keeper Keeper) ([]byte, error) {
 	peggyID := keeper.GetPeggyID(ctx)
 	res, err := codec.MarshalJSONIndent(types.ModuleCdc, peggyID)
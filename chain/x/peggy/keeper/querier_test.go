@@ -429,7 +429,7 @@ func createTestBatch(t *testing.T, input TestInput) {
 		now                 = time.Now().UTC()
 	)
 	// mint some voucher first
-	allVouchers := sdk.Coins{types.NewERC20Token(99999, myTokenContractAddr).PeggyCoin()}
+	allVouchers := sdk.Coins{types.NewERC20Token(sdk.NewInt(99999), myTokenContractAddr).PeggyCoin(input.Context, input.OracleKeeper)}
 	err := input.BankKeeper.MintCoins(input.Context, types.ModuleName, allVouchers)
 	require.NoError(t, err)
 
@@ -440,9 +440,9 @@ func createTestBatch(t *testing.T, input TestInput) {
 
 	// add some TX to the pool
 	for i, v := range []uint64{2, 3, 2, 1} {
-		amount := types.NewERC20Token(uint64(i+100), myTokenContractAddr).PeggyCoin()
-		fee := types.NewERC20Token(v, myTokenContractAddr).PeggyCoin()
-		_, err := input.PeggyKeeper.AddToOutgoingPool(input.Context, mySender, myReceiver, amount, fee)
+		amount := types.NewERC20Token(sdk.NewIntFromUint64(uint64(i+100)), myTokenContractAddr).PeggyCoin(input.Context, input.OracleKeeper)
+		fee := types.NewERC20Token(sdk.NewIntFromUint64(v), myTokenContractAddr).PeggyCoin(input.Context, input.OracleKeeper)
+		_, err := input.PeggyKeeper.AddToOutgoingPool(input.Context, mySender, myReceiver, "", "", "", amount, fee)
 		require.NoError(t, err)
 	}
 	// when
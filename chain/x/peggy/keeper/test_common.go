@@ -3,7 +3,9 @@ package keeper
 import (
 	"bytes"
 	minterkeeper "github.com/MinterTeam/mhub/chain/x/minter/keeper"
+	mintertypes "github.com/MinterTeam/mhub/chain/x/minter/types"
 	oraclekeeper "github.com/MinterTeam/mhub/chain/x/oracle/keeper"
+	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	"testing"
 	"time"
 
@@ -193,6 +195,41 @@ var (
 		SlashFractionClaim:            sdk.NewDecWithPrec(1, 2),
 		SlashFractionConflictingClaim: sdk.NewDecWithPrec(1, 2),
 	}
+
+	// TestingOracleParams is a set of oracle params for testing, the coins have 18 decimals on both sides so
+	// amounts aren't converted and a zero batch gas lets any fee pay for a batch
+	TestingOracleParams = oracletypes.Params{
+		SignedClaimsWindow:            10,
+		SlashFractionClaim:            sdk.NewDecWithPrec(1, 2),
+		SlashFractionConflictingClaim: sdk.NewDecWithPrec(1, 2),
+		Coins: []*oracletypes.Coin{
+			{
+				Denom:       "pickle",
+				EthAddr:     "0x429881672B9AE42b8EbA0E26cD9C73711b891Ca5",
+				MinterId:    1,
+				EthDecimals: 18,
+			},
+			{
+				Denom:       "testcoin",
+				EthAddr:     "0xAb5801a7D398351b8bE11C439e05C5B3259aeC9B",
+				MinterId:    2,
+				EthDecimals: 18,
+			},
+		},
+		MinBatchGas:          0,
+		MinSingleWithdrawGas: 0,
+		Commission:           sdk.NewDecWithPrec(1, 2),
+	}
+
+	// TestingOraclePrices are the oracle prices batches are priced with in tests
+	TestingOraclePrices = oracletypes.Prices{
+		List: []*oracletypes.Price{
+			{Name: "minter/1", Value: sdk.NewInt(1)},
+			{Name: "minter/2", Value: sdk.NewInt(1)},
+			{Name: "eth/gas", Value: sdk.NewInt(1)},
+			{Name: "eth/0", Value: sdk.NewInt(1)},
+		},
+	}
 )
 
 // TestInput stores the various keepers required to test peggy
@@ -203,6 +240,7 @@ type TestInput struct {
 	DistKeeper    distrkeeper.Keeper
 	BankKeeper    bankkeeper.BaseKeeper
 	GovKeeper     govkeeper.Keeper
+	OracleKeeper  oraclekeeper.Keeper
 	Context       sdk.Context
 	Marshaler     codec.Marshaler
 	LegacyAmino   *codec.LegacyAmino
@@ -268,6 +306,8 @@ func CreateTestEnv(t *testing.T) TestInput {
 	keyParams := sdk.NewKVStoreKey(paramstypes.StoreKey)
 	tkeyParams := sdk.NewTransientStoreKey(paramstypes.TStoreKey)
 	keyGov := sdk.NewKVStoreKey(govtypes.StoreKey)
+	keyOracle := sdk.NewKVStoreKey(oracletypes.StoreKey)
+	keyMinter := sdk.NewKVStoreKey(mintertypes.StoreKey)
 
 	// Initialize memory database and mount stores on it
 	db := dbm.NewMemDB()
@@ -280,6 +320,8 @@ func CreateTestEnv(t *testing.T) TestInput {
 	ms.MountStoreWithDB(keyDistro, sdk.StoreTypeIAVL, db)
 	ms.MountStoreWithDB(tkeyParams, sdk.StoreTypeTransient, db)
 	ms.MountStoreWithDB(keyGov, sdk.StoreTypeIAVL, db)
+	ms.MountStoreWithDB(keyOracle, sdk.StoreTypeIAVL, db)
+	ms.MountStoreWithDB(keyMinter, sdk.StoreTypeIAVL, db)
 	err := ms.LoadLatestVersion()
 	require.Nil(t, err)
 
@@ -299,6 +341,8 @@ func CreateTestEnv(t *testing.T) TestInput {
 	paramsKeeper.Subspace(distrtypes.ModuleName)
 	paramsKeeper.Subspace(govtypes.ModuleName)
 	paramsKeeper.Subspace(types.DefaultParamspace)
+	paramsKeeper.Subspace(oracletypes.ModuleName)
+	paramsKeeper.Subspace(mintertypes.ModuleName)
 
 	// this is also used to initialize module accounts for all the map keys
 	maccPerms := map[string][]string{
@@ -308,6 +352,7 @@ func CreateTestEnv(t *testing.T) TestInput {
 		stakingtypes.NotBondedPoolName: {authtypes.Burner, authtypes.Staking},
 		govtypes.ModuleName:            {authtypes.Burner},
 		types.ModuleName:               {authtypes.Minter, authtypes.Burner},
+		mintertypes.ModuleName:         {authtypes.Minter, authtypes.Burner},
 	}
 
 	accountKeeper := authkeeper.NewAccountKeeper(
@@ -383,7 +428,16 @@ func CreateTestEnv(t *testing.T) TestInput {
 	govKeeper.SetVotingParams(ctx, govtypes.DefaultVotingParams())
 	govKeeper.SetTallyParams(ctx, govtypes.DefaultTallyParams())
 
-	k := NewKeeper(marshaler, peggyKey, getSubspace(paramsKeeper, types.DefaultParamspace), stakingKeeper, bankKeeper, minterkeeper.Keeper{}, oraclekeeper.Keeper{})
+	oracleKeeper := oraclekeeper.NewKeeper(marshaler, keyOracle, getSubspace(paramsKeeper, oracletypes.ModuleName), stakingKeeper, bankKeeper)
+	oracleKeeper.SetParams(ctx, TestingOracleParams)
+	// the oracle only stores prices once validators agree on them, tests set them directly
+	ctx.KVStore(keyOracle).Set(oracletypes.CurrentPricesKey, marshaler.MustMarshalBinaryBare(&TestingOraclePrices))
+
+	minterKeeper := minterkeeper.NewKeeper(marshaler, keyMinter, getSubspace(paramsKeeper, mintertypes.ModuleName), stakingKeeper, bankKeeper, oracleKeeper)
+	minterkeeper.InitGenesis(ctx, minterKeeper, *mintertypes.DefaultGenesisState())
+
+	k := NewKeeper(marshaler, peggyKey, getSubspace(paramsKeeper, types.DefaultParamspace), stakingKeeper, bankKeeper, minterKeeper, oracleKeeper)
+	minterKeeper.SetPeggyKeeper(k)
 
 	k.SetParams(ctx, TestingPeggyParams)
 
@@ -394,6 +448,7 @@ func CreateTestEnv(t *testing.T) TestInput {
 		StakingKeeper: stakingKeeper,
 		DistKeeper:    distKeeper,
 		GovKeeper:     govKeeper,
+		OracleKeeper:  oracleKeeper,
 		Context:       ctx,
 		Marshaler:     marshaler,
 		LegacyAmino:   cdc,
@@ -0,0 +1,36 @@
+package types
+
+import sdk "github.com/cosmos/cosmos-sdk/types"
+
+// AttestationProgress shows how far the attestation of an Ethereum event is from being observed, which
+// validators have already submitted the same claim and whose claims it is still waiting on. Powers are
+// the last validator powers, the attestation is observed once VotedPower reaches RequiredPower and
+// MissingPower is how much more has to vote. Height is the height of the last vote.
+type AttestationProgress struct {
+	EventNonce    uint64                 `json:"event_nonce"`
+	Observed      bool                   `json:"observed"`
+	Height        uint64                 `json:"height"`
+	VotedPower    sdk.Int                `json:"voted_power"`
+	RequiredPower sdk.Int                `json:"required_power"`
+	TotalPower    sdk.Int                `json:"total_power"`
+	MissingPower  sdk.Int                `json:"missing_power"`
+	Validators    []AttestationValidator `json:"validators"`
+}
+
+// AttestationValidator is a bonded validator and whether it has voted for an attestation
+type AttestationValidator struct {
+	Validator string `json:"validator"`
+	Power     int64  `json:"power"`
+	Voted     bool   `json:"voted"`
+}
+
+// Pending returns the validators the attestation is still waiting on
+func (p AttestationProgress) Pending() []AttestationValidator {
+	var out []AttestationValidator
+	for _, v := range p.Validators {
+		if !v.Voted {
+			out = append(out, v)
+		}
+	}
+	return out
+}
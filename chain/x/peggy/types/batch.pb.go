@@ -99,6 +99,7 @@ type OutgoingTransferTx struct {
 	Erc20Token  *ERC20Token `protobuf:"bytes,4,opt,name=erc20_token,json=erc20Token,proto3" json:"erc20_token,omitempty"`
 	Erc20Fee    *ERC20Token `protobuf:"bytes,5,opt,name=erc20_fee,json=erc20Fee,proto3" json:"erc20_fee,omitempty"`
 	TxHash      string      `protobuf:"bytes,6,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	Memo        string      `protobuf:"bytes,7,opt,name=memo,proto3" json:"memo,omitempty"`
 }
 
 func (m *OutgoingTransferTx) Reset()         { *m = OutgoingTransferTx{} }
@@ -176,6 +177,13 @@ func (m *OutgoingTransferTx) GetTxHash() string {
 	return ""
 }
 
+func (m *OutgoingTransferTx) GetMemo() string {
+	if m != nil {
+		return m.Memo
+	}
+	return ""
+}
+
 func init() {
 	proto.RegisterType((*OutgoingTxBatch)(nil), "peggy.v1.OutgoingTxBatch")
 	proto.RegisterType((*OutgoingTransferTx)(nil), "peggy.v1.OutgoingTransferTx")
@@ -185,30 +193,30 @@ func init() { proto.RegisterFile("peggy/v1/batch.proto", fileDescriptor_398e85e0
 
 var fileDescriptor_398e85e0d69cec73 = []byte{
 	// 384 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x7c, 0x51, 0x4f, 0x8b, 0xd3, 0x40,
-	0x14, 0xef, 0x64, 0x77, 0xeb, 0xee, 0x64, 0x5d, 0x61, 0x28, 0x1a, 0x16, 0x89, 0x75, 0x41, 0xe8,
-	0x29, 0xd9, 0x46, 0xbc, 0x6b, 0x8b, 0xd2, 0x8b, 0x0a, 0x21, 0x27, 0x2f, 0x61, 0x32, 0x79, 0x4d,
-	0x42, 0xed, 0x4c, 0x99, 0x79, 0x2d, 0xe9, 0xb7, 0xf0, 0xab, 0xf8, 0x2d, 0x3c, 0xf6, 0xe8, 0x51,
-	0xd2, 0x2f, 0x22, 0x99, 0xc4, 0x8a, 0x08, 0x7b, 0x9b, 0xf7, 0x7b, 0xbf, 0x7f, 0xc3, 0xa3, 0xa3,
-	0x0d, 0x14, 0xc5, 0x3e, 0xdc, 0x4d, 0xc3, 0x8c, 0xa3, 0x28, 0x83, 0x8d, 0x56, 0xa8, 0xd8, 0xa5,
-	0x45, 0x83, 0xdd, 0xf4, 0xf6, 0xf6, 0xb4, 0xe7, 0x88, 0x60, 0x90, 0x63, 0xa5, 0x64, 0xc7, 0xba,
-	0xfb, 0x4e, 0xe8, 0x93, 0xcf, 0x5b, 0x2c, 0x54, 0x25, 0x8b, 0xa4, 0x9e, 0xb5, 0x7a, 0xf6, 0x82,
-	0xba, 0xd6, 0x28, 0x95, 0x4a, 0x0a, 0xf0, 0xc8, 0x98, 0x4c, 0xce, 0x63, 0x6a, 0xa1, 0x4f, 0x2d,
-	0xc2, 0xde, 0xd2, 0x6b, 0xd4, 0x5c, 0x1a, 0x2e, 0x5a, 0x27, 0xe3, 0x39, 0xe3, 0xb3, 0x89, 0x1b,
-	0x3d, 0x0f, 0xfe, 0x24, 0x06, 0x27, 0xc7, 0x96, 0xb5, 0x04, 0x9d, 0xd4, 0xf1, 0x3f, 0x0a, 0xf6,
-	0x8a, 0xde, 0xa0, 0x5a, 0x81, 0x4c, 0x85, 0x92, 0xa8, 0xb9, 0x40, 0xef, 0x6c, 0x4c, 0x26, 0x57,
-	0xf1, 0x63, 0x8b, 0xce, 0x7b, 0x90, 0x8d, 0xe8, 0x45, 0xf6, 0x55, 0x89, 0x95, 0x77, 0x6e, 0x3b,
-	0x74, 0xc3, 0x5d, 0x43, 0x28, 0xfb, 0x3f, 0x81, 0xdd, 0x50, 0xa7, 0xca, 0xfb, 0xb6, 0x4e, 0x95,
-	0xb3, 0xa7, 0x74, 0x68, 0x40, 0xe6, 0xa0, 0x3d, 0xc7, 0x7a, 0xf7, 0x13, 0x7b, 0x49, 0xaf, 0x73,
-	0x30, 0x98, 0xf2, 0x3c, 0xd7, 0x60, 0x4c, 0x9f, 0xec, 0xb6, 0xd8, 0xbb, 0x0e, 0x62, 0x6f, 0xa8,
-	0x0b, 0x5a, 0x44, 0xf7, 0xa9, 0xad, 0x63, 0xd3, 0xdd, 0x68, 0xf4, 0xf7, 0x7f, 0xef, 0xe3, 0x79,
-	0x74, 0x9f, 0xb4, 0xbb, 0x98, 0x5a, 0xa2, 0x7d, 0xb3, 0x29, 0xbd, 0xea, 0x64, 0x4b, 0x00, 0xef,
-	0xe2, 0x01, 0xd1, 0xa5, 0xa5, 0x7d, 0x00, 0x60, 0xcf, 0xe8, 0x23, 0xac, 0xd3, 0x92, 0x9b, 0xd2,
-	0x1b, 0x76, 0x2d, 0xb1, 0x5e, 0x70, 0x53, 0xce, 0x16, 0x3f, 0x1a, 0x9f, 0x1c, 0x1a, 0x9f, 0xfc,
-	0x6a, 0x7c, 0xf2, 0xed, 0xe8, 0x0f, 0x0e, 0x47, 0x7f, 0xf0, 0xf3, 0xe8, 0x0f, 0xbe, 0x04, 0x45,
-	0x85, 0xe5, 0x36, 0x0b, 0x84, 0x5a, 0x87, 0x1f, 0x2b, 0x89, 0xa0, 0x13, 0xe0, 0xeb, 0x70, 0x5d,
-	0x6e, 0xb3, 0x50, 0x94, 0xbc, 0x92, 0x61, 0x1d, 0x76, 0x17, 0xc7, 0xfd, 0x06, 0x4c, 0x36, 0xb4,
-	0x97, 0x7e, 0xfd, 0x3b, 0x00, 0x00, 0xff, 0xff, 0x5b, 0xa6, 0xdd, 0x0a, 0x27, 0x02, 0x00, 0x00,
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x7d, 0x52, 0xcb, 0x4a, 0xc3, 0x40,
+	0x14, 0x35, 0x7d, 0x77, 0x52, 0x2b, 0x0c, 0x45, 0x43, 0x91, 0x5a, 0x0b, 0x42, 0x57, 0x49, 0x1b,
+	0x71, 0xaf, 0x2d, 0x4a, 0x37, 0x2a, 0x84, 0xae, 0xdc, 0x84, 0xe9, 0x64, 0x9a, 0x84, 0x9a, 0x99,
+	0x92, 0x99, 0x96, 0xf4, 0x2f, 0xfc, 0x15, 0xff, 0xc2, 0x65, 0x97, 0x2e, 0x45, 0xff, 0xc1, 0xb5,
+	0x93, 0x49, 0xac, 0x88, 0xe0, 0xe2, 0xc2, 0xbd, 0xe7, 0xde, 0x7b, 0xce, 0x99, 0x07, 0x68, 0x2d,
+	0x89, 0xef, 0x6f, 0xac, 0xf5, 0xd0, 0x9a, 0x21, 0x81, 0x03, 0x73, 0x19, 0x33, 0xc1, 0x60, 0x4d,
+	0xa1, 0xe6, 0x7a, 0xd8, 0x6e, 0xef, 0xfa, 0x48, 0x08, 0xc2, 0x05, 0x12, 0x21, 0xa3, 0xd9, 0x54,
+	0xef, 0x59, 0x03, 0x07, 0xf7, 0x2b, 0xe1, 0xb3, 0x90, 0xfa, 0xd3, 0x64, 0x94, 0xee, 0xc3, 0x13,
+	0xa0, 0x2b, 0x22, 0x97, 0x32, 0x8a, 0x89, 0xa1, 0x75, 0xb5, 0x7e, 0xc9, 0x01, 0x0a, 0xba, 0x4b,
+	0x11, 0x78, 0x09, 0x1a, 0x22, 0x46, 0x94, 0x23, 0x9c, 0x32, 0x71, 0xa3, 0xd0, 0x2d, 0xf6, 0x75,
+	0xfb, 0xd8, 0xfc, 0x56, 0x34, 0x77, 0x8c, 0xe9, 0xd4, 0x9c, 0xc4, 0xd3, 0xc4, 0xf9, 0xb5, 0x01,
+	0xcf, 0x40, 0x53, 0xb0, 0x05, 0xa1, 0x2e, 0x66, 0x54, 0x36, 0xb0, 0x30, 0x8a, 0x52, 0xa5, 0xee,
+	0xec, 0x2b, 0x74, 0x9c, 0x83, 0xb0, 0x05, 0xca, 0xb3, 0x47, 0x86, 0x17, 0x46, 0x49, 0x79, 0xc8,
+	0x8a, 0xde, 0xa7, 0x06, 0xe0, 0x5f, 0x05, 0xd8, 0x04, 0x85, 0xd0, 0xcb, 0xdd, 0xca, 0x0c, 0x1e,
+	0x82, 0x0a, 0x27, 0xd4, 0x23, 0xb1, 0xf4, 0x97, 0x72, 0xe7, 0x15, 0x3c, 0x05, 0x0d, 0x4f, 0xde,
+	0x82, 0x8b, 0x3c, 0x2f, 0x26, 0x9c, 0xe7, 0xca, 0x7a, 0x8a, 0x5d, 0x65, 0x10, 0xbc, 0x00, 0x3a,
+	0x89, 0xb1, 0x3d, 0x70, 0x95, 0x1d, 0xa5, 0xae, 0xdb, 0xad, 0x9f, 0xf3, 0x5d, 0x3b, 0x63, 0x7b,
+	0x30, 0x4d, 0x7b, 0x0e, 0x50, 0x83, 0x2a, 0x87, 0x43, 0x50, 0xcf, 0xd6, 0xe6, 0x84, 0x18, 0xe5,
+	0x7f, 0x96, 0x6a, 0x6a, 0xec, 0x86, 0x10, 0x78, 0x04, 0xaa, 0x22, 0x71, 0x03, 0xc4, 0x03, 0xa3,
+	0x92, 0xb9, 0x14, 0xc9, 0x44, 0x56, 0x10, 0x82, 0x52, 0x44, 0x22, 0x66, 0x54, 0x15, 0xaa, 0xf2,
+	0xd1, 0xe4, 0xe5, 0xbd, 0xa3, 0x6d, 0x65, 0xbc, 0xc9, 0x78, 0xfa, 0xe8, 0xec, 0x6d, 0x65, 0xbc,
+	0xca, 0x78, 0x30, 0xfd, 0x50, 0x04, 0xab, 0x99, 0x89, 0x59, 0x64, 0xdd, 0x86, 0x54, 0xc8, 0x0b,
+	0x21, 0x28, 0xb2, 0x22, 0x89, 0x59, 0x38, 0x40, 0x21, 0xb5, 0x12, 0x2b, 0xfb, 0x05, 0x62, 0xb3,
+	0x24, 0x7c, 0x56, 0x51, 0xaf, 0x7f, 0xfe, 0x05, 0x07, 0xc8, 0x1f, 0xda, 0x3b, 0x02, 0x00, 0x00,
 }
 
 func (m *OutgoingTxBatch) Marshal() (dAtA []byte, err error) {
@@ -285,6 +293,13 @@ func (m *OutgoingTransferTx) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if len(m.Memo) > 0 {
+		i -= len(m.Memo)
+		copy(dAtA[i:], m.Memo)
+		i = encodeVarintBatch(dAtA, i, uint64(len(m.Memo)))
+		i--
+		dAtA[i] = 0x3a
+	}
 	if len(m.TxHash) > 0 {
 		i -= len(m.TxHash)
 		copy(dAtA[i:], m.TxHash)
@@ -403,6 +418,10 @@ func (m *OutgoingTransferTx) Size() (n int) {
 	if l > 0 {
 		n += 1 + l + sovBatch(uint64(l))
 	}
+	l = len(m.Memo)
+	if l > 0 {
+		n += 1 + l + sovBatch(uint64(l))
+	}
 	return n
 }
 
@@ -785,6 +804,38 @@ func (m *OutgoingTransferTx) Unmarshal(dAtA []byte) error {
 			}
 			m.TxHash = string(dAtA[iNdEx:postIndex])
 			iNdEx = postIndex
+		case 7:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Memo", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowBatch
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthBatch
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthBatch
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Memo = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
 		default:
 			iNdEx = preIndex
 			skippy, err := skipBatch(dAtA[iNdEx:])
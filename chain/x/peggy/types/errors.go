@@ -15,4 +15,5 @@ var (
 	ErrUnsupported             = sdkerrors.Register(ModuleName, 8, "unsupported")
 	ErrNonContiguousEventNonce = sdkerrors.Register(ModuleName, 9, "non contiguous event nonce")
 	ErrServiceStopped          = sdkerrors.Register(ModuleName, 10, "service stopped")
+	ErrBridgeHalted            = sdkerrors.Register(ModuleName, 11, "bridge halted")
 )
@@ -9,6 +9,10 @@ import (
 
 const (
 	signaturePrefix = "\x19Ethereum Signed Message:\n32"
+
+	// compactSignatureLen is the length of an EIP-2098 compact signature, r followed by s with the
+	// parity of v in the top bit of s
+	compactSignatureLen = 64
 )
 
 // NewEthereumSignature creates a new signuature over a given byte array
@@ -20,9 +24,21 @@ func NewEthereumSignature(hash []byte, privateKey *ecdsa.PrivateKey) ([]byte, er
 	return crypto.Sign(protectedHash.Bytes(), privateKey)
 }
 
+// ExpandCompactSignature turns an EIP-2098 compact signature into the 65 byte r, s, v form
+func ExpandCompactSignature(signature []byte) []byte {
+	expanded := make([]byte, 65)
+	copy(expanded, signature[:compactSignatureLen])
+	expanded[32] &= 0x7f
+	expanded[64] = 27 + signature[32]>>7
+	return expanded
+}
+
 // ValidateEthereumSignature takes a message, an associated signature and public key and
-// returns an error if the signature isn't valid
+// returns an error if the signature isn't valid. Both 65 byte and compact signatures are accepted.
 func ValidateEthereumSignature(hash []byte, signature []byte, ethAddress string) error {
+	if len(signature) == compactSignatureLen {
+		signature = ExpandCompactSignature(signature)
+	}
 	if len(signature) < 65 {
 		return sdkerrors.Wrap(ErrInvalid, "signature too short")
 	}
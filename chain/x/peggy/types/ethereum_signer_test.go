@@ -11,6 +11,7 @@ import (
 func TestValsetConfirmSig(t *testing.T) {
 	const (
 		correctSig = "e108a7776de6b87183b0690484a74daef44aa6daf907e91abaf7bbfa426ae7706b12e0bd44ef7b0634710d99c2d81087a2f39e075158212343a3b2948ecf33d01c"
+		compactSig = "e108a7776de6b87183b0690484a74daef44aa6daf907e91abaf7bbfa426ae770eb12e0bd44ef7b0634710d99c2d81087a2f39e075158212343a3b2948ecf33d0"
 		invalidSig = "fffff7776de6b87183b0690484a74daef44aa6daf907e91abaf7bbfa426ae7706b12e0bd44ef7b0634710d99c2d81087a2f39e075158212343a3b2948ecf33d01c"
 		ethAddress = "0xc783df8a850f42e7F7e57013759C285caa701eB6"
 		hash       = "88165860d955aee7dc3e83d9d1156a5864b708841965585d206dbef6e9e1a499"
@@ -27,6 +28,11 @@ func TestValsetConfirmSig(t *testing.T) {
 			srcSignature: correctSig,
 			srcETHAddr:   ethAddress,
 		},
+		"compact signature": {
+			srcHash:      hash,
+			srcSignature: compactSig,
+			srcETHAddr:   ethAddress,
+		},
 		"invalid signature": {
 			srcHash:      hash,
 			srcSignature: invalidSig,
@@ -7,9 +7,11 @@ const (
 	EventTypeMultisigBootstrap        = "eth_multisig_bootstrap"
 	EventTypeMultisigUpdateRequest    = "eth_multisig_update_request"
 	EventTypeOutgoingBatchCanceled    = "eth_outgoing_batch_canceled"
+	EventTypeOutgoingBatchWithheld    = "eth_outgoing_batch_withheld"
 	EventTypeBridgeWithdrawalReceived = "eth_withdrawal_received"
 	EventTypeBridgeDepositReceived    = "eth_deposit_received"
 	EventTypeRefund                   = "eth_refund"
+	EventTypeRelayReceipt             = "eth_relay_receipt"
 
 	AttributeKeyAttestationID    = "attestation_id"
 	AttributeKeyAttestationIDs   = "attestation_ids"
@@ -27,4 +29,8 @@ const (
 	AttributeKeySetOperatorAddr  = "set_operator_address"
 	AttributeKeyTxHash           = "tx_hash"
 	AttributeKeyBatchTxHash      = "batch_tx_hash"
+	AttributeKeyMemo             = "memo"
+	AttributeKeyWithheldBy       = "withheld_by"
+	AttributeKeyRelayer          = "relayer"
+	AttributeKeyTokenContract    = "token_contract"
 )
@@ -3,6 +3,7 @@ package types
 import (
 	"bytes"
 	"fmt"
+	"strconv"
 	"strings"
 	"time"
 
@@ -60,6 +61,15 @@ var (
 
 	ParamsStoreStopped = []byte("Stopped")
 
+	// ParamsStoreHaltAtEventNonce stores the event nonce past which claims are rejected
+	ParamsStoreHaltAtEventNonce = []byte("HaltAtEventNonce")
+
+	// ParamsStoreMinOrchestratorVersion stores the lowest orchestrator version validators should run
+	ParamsStoreMinOrchestratorVersion = []byte("MinOrchestratorVersion")
+
+	// ParamsStoreBatchTimeoutBlocks stores the blocks a batch may wait for signatures before it is superseded
+	ParamsStoreBatchTimeoutBlocks = []byte("BatchTimeoutBlocks")
+
 	// Ensure that params implements the proper interface
 	_ paramtypes.ParamSet = &Params{}
 )
@@ -93,6 +103,9 @@ func DefaultParams() *Params {
 		SlashFractionClaim:            sdk.NewDec(1).Quo(sdk.NewDec(1000)),
 		SlashFractionConflictingClaim: sdk.NewDec(1).Quo(sdk.NewDec(1000)),
 		Stopped:                       false,
+		HaltAtEventNonce:              0,
+		MinOrchestratorVersion:        "",
+		BatchTimeoutBlocks:            0,
 	}
 }
 
@@ -159,6 +172,9 @@ func (p *Params) ParamSetPairs() paramtypes.ParamSetPairs {
 		paramtypes.NewParamSetPair(ParamsStoreSlashFractionClaim, &p.SlashFractionClaim, validateSlashFractionClaim),
 		paramtypes.NewParamSetPair(ParamsStoreSlashFractionConflictingClaim, &p.SlashFractionConflictingClaim, validateSlashFractionConflictingClaim),
 		paramtypes.NewParamSetPair(ParamsStoreStopped, &p.Stopped, validateStopped),
+		paramtypes.NewParamSetPair(ParamsStoreHaltAtEventNonce, &p.HaltAtEventNonce, validateHaltAtEventNonce),
+		paramtypes.NewParamSetPair(ParamsStoreMinOrchestratorVersion, &p.MinOrchestratorVersion, validateMinOrchestratorVersion),
+		paramtypes.NewParamSetPair(ParamsStoreBatchTimeoutBlocks, &p.BatchTimeoutBlocks, validateBatchTimeoutBlocks),
 	}
 }
 
@@ -281,6 +297,40 @@ func validateStopped(i interface{}) error {
 	return nil
 }
 
+func validateHaltAtEventNonce(i interface{}) error {
+	if _, ok := i.(uint64); !ok {
+		return fmt.Errorf("invalid parameter type: %T", i)
+	}
+	return nil
+}
+
+func validateMinOrchestratorVersion(i interface{}) error {
+	v, ok := i.(string)
+	if !ok {
+		return fmt.Errorf("invalid parameter type: %T", i)
+	}
+	if v == "" {
+		return nil
+	}
+	parts := strings.Split(v, ".")
+	if len(parts) != 3 {
+		return fmt.Errorf("min orchestrator version %q is not major.minor.patch", v)
+	}
+	for _, part := range parts {
+		if _, err := strconv.ParseUint(part, 10, 64); err != nil {
+			return fmt.Errorf("min orchestrator version %q is not major.minor.patch", v)
+		}
+	}
+	return nil
+}
+
+func validateBatchTimeoutBlocks(i interface{}) error {
+	if _, ok := i.(uint64); !ok {
+		return fmt.Errorf("invalid parameter type: %T", i)
+	}
+	return nil
+}
+
 func strToFixByteArray(s string) ([32]byte, error) {
 	var out [32]byte
 	if len([]byte(s)) > 32 {
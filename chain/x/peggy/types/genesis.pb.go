@@ -52,6 +52,9 @@ type Params struct {
 	SlashFractionClaim            github_com_cosmos_cosmos_sdk_types.Dec `protobuf:"bytes,11,opt,name=slash_fraction_claim,json=slashFractionClaim,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Dec" json:"slash_fraction_claim"`
 	SlashFractionConflictingClaim github_com_cosmos_cosmos_sdk_types.Dec `protobuf:"bytes,12,opt,name=slash_fraction_conflicting_claim,json=slashFractionConflictingClaim,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Dec" json:"slash_fraction_conflicting_claim"`
 	Stopped                       bool                                   `protobuf:"varint,13,opt,name=stopped,proto3" json:"stopped,omitempty"`
+	HaltAtEventNonce              uint64                                 `protobuf:"varint,14,opt,name=halt_at_event_nonce,json=haltAtEventNonce,proto3" json:"halt_at_event_nonce,omitempty"`
+	MinOrchestratorVersion        string                                 `protobuf:"bytes,15,opt,name=min_orchestrator_version,json=minOrchestratorVersion,proto3" json:"min_orchestrator_version,omitempty"`
+	BatchTimeoutBlocks            uint64                                 `protobuf:"varint,16,opt,name=batch_timeout_blocks,json=batchTimeoutBlocks,proto3" json:"batch_timeout_blocks,omitempty"`
 }
 
 func (m *Params) Reset()         { *m = Params{} }
@@ -150,6 +153,27 @@ func (m *Params) GetStopped() bool {
 	return false
 }
 
+func (m *Params) GetHaltAtEventNonce() uint64 {
+	if m != nil {
+		return m.HaltAtEventNonce
+	}
+	return 0
+}
+
+func (m *Params) GetMinOrchestratorVersion() string {
+	if m != nil {
+		return m.MinOrchestratorVersion
+	}
+	return ""
+}
+
+func (m *Params) GetBatchTimeoutBlocks() uint64 {
+	if m != nil {
+		return m.BatchTimeoutBlocks
+	}
+	return 0
+}
+
 // GenesisState struct
 type GenesisState struct {
 	Params         *Params             `protobuf:"bytes,1,opt,name=params,proto3" json:"params,omitempty"`
@@ -243,50 +267,54 @@ func init() {
 func init() { proto.RegisterFile("peggy/v1/genesis.proto", fileDescriptor_84231c3b3f050761) }
 
 var fileDescriptor_84231c3b3f050761 = []byte{
-	// 674 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xac, 0x54, 0xcd, 0x4e, 0x1b, 0x3b,
-	0x14, 0xce, 0x40, 0x48, 0xc0, 0x04, 0x82, 0x4c, 0xb8, 0x32, 0x91, 0x6e, 0x88, 0x58, 0x70, 0x73,
-	0xaf, 0x74, 0x67, 0xf8, 0xd9, 0x75, 0x53, 0x91, 0x54, 0x14, 0x16, 0xa8, 0xd5, 0x80, 0x5a, 0xa9,
-	0x9b, 0xa9, 0x33, 0x63, 0x3c, 0x56, 0x33, 0xe3, 0x68, 0xec, 0x04, 0xd8, 0xf5, 0x11, 0xfa, 0x20,
-	0x7d, 0x10, 0x96, 0x2c, 0xab, 0xaa, 0x42, 0x15, 0x2c, 0xfa, 0x1a, 0x55, 0x8e, 0x3d, 0xf9, 0xa1,
-	0x5d, 0xa1, 0xae, 0x66, 0xfc, 0x7d, 0xe7, 0x3b, 0x9f, 0x8f, 0x8f, 0x8f, 0xd1, 0x5f, 0x7d, 0xc6,
-	0xf9, 0xb5, 0x37, 0xdc, 0xf3, 0x38, 0x4b, 0x99, 0x12, 0xca, 0xed, 0x67, 0x52, 0x4b, 0xbc, 0x08,
-	0xb8, 0x3b, 0xdc, 0xab, 0xd7, 0xb8, 0xe4, 0x12, 0x40, 0x6f, 0xf4, 0x67, 0xf8, 0x7a, 0x6d, 0xac,
-	0xd3, 0xd7, 0x7d, 0x66, 0x55, 0xf5, 0xf5, 0x31, 0x9a, 0x28, 0xae, 0x7e, 0x09, 0xed, 0x52, 0x1d,
-	0xc6, 0x16, 0xad, 0x8f, 0x51, 0xaa, 0x35, 0x53, 0x9a, 0x6a, 0x21, 0x53, 0xc3, 0x6d, 0x7f, 0x2e,
-	0xa1, 0xd2, 0x6b, 0x9a, 0xd1, 0x44, 0xe1, 0x4d, 0x64, 0x76, 0x12, 0x88, 0x88, 0x38, 0x4d, 0xa7,
-	0xb5, 0xe4, 0x97, 0x61, 0x7d, 0x12, 0xe1, 0x5d, 0x54, 0x0b, 0x65, 0xaa, 0x33, 0x1a, 0xea, 0x40,
-	0xc9, 0x41, 0x16, 0xb2, 0x20, 0xa6, 0x2a, 0x26, 0x73, 0x10, 0x86, 0x73, 0xee, 0x0c, 0xa8, 0x63,
-	0xaa, 0x62, 0xfc, 0x0f, 0xaa, 0x2a, 0x4d, 0x33, 0x1d, 0xe8, 0x38, 0x63, 0x2a, 0x96, 0xbd, 0x88,
-	0xcc, 0x37, 0x9d, 0x56, 0xd1, 0x5f, 0x05, 0xf8, 0x3c, 0x47, 0xf1, 0xbf, 0x68, 0x8d, 0xe9, 0x98,
-	0x65, 0x6c, 0x90, 0x04, 0x34, 0x8a, 0x32, 0xa6, 0x14, 0x29, 0x42, 0xda, 0x6a, 0x8e, 0x1f, 0x1a,
-	0x18, 0xef, 0xa0, 0x6a, 0x37, 0x13, 0x11, 0x67, 0x41, 0x18, 0x53, 0x91, 0x8e, 0xf6, 0xb9, 0x00,
-	0x39, 0x57, 0x0c, 0xdc, 0x19, 0xa1, 0x27, 0x11, 0xde, 0x47, 0x1b, 0x4a, 0xf0, 0x94, 0x45, 0xc1,
-	0x90, 0xf6, 0x14, 0xd3, 0x2a, 0xb8, 0x14, 0x69, 0x24, 0x2f, 0x49, 0x09, 0xa2, 0xd7, 0x0d, 0xf9,
-	0xc6, 0x70, 0x6f, 0x81, 0x9a, 0xd2, 0xc0, 0xc9, 0xb1, 0xb1, 0xa6, 0x3c, 0xad, 0x69, 0x1b, 0xce,
-	0x6a, 0x76, 0x51, 0xcd, 0x6a, 0xc2, 0x1e, 0x15, 0xc9, 0x58, 0xb2, 0x08, 0x12, 0x6c, 0xb8, 0x0e,
-	0x50, 0x56, 0xd1, 0x45, 0x1b, 0xaa, 0x47, 0x55, 0x1c, 0x5c, 0x8c, 0x8e, 0x4b, 0xc8, 0xd4, 0xee,
-	0x90, 0x2c, 0x35, 0x9d, 0x56, 0xa5, 0xed, 0xde, 0xdc, 0x6d, 0x15, 0xbe, 0xde, 0x6d, 0xed, 0x70,
-	0xa1, 0xe3, 0x41, 0xd7, 0x0d, 0x65, 0xe2, 0x85, 0x52, 0x25, 0x52, 0xd9, 0xcf, 0xff, 0x2a, 0xfa,
-	0x60, 0x6f, 0xc1, 0x0b, 0x16, 0xfa, 0xeb, 0x90, 0xec, 0xc8, 0xe6, 0x32, 0x05, 0xe1, 0xf7, 0xa8,
-	0xf6, 0xc8, 0x03, 0x2a, 0x22, 0xe8, 0x49, 0x16, 0x78, 0xc6, 0x02, 0xea, 0xff, 0x8d, 0x03, 0xd4,
-	0x4f, 0x96, 0xff, 0x80, 0x03, 0x1c, 0x17, 0xbe, 0x44, 0xcd, 0xc7, 0x0e, 0x32, 0xbd, 0xe8, 0x89,
-	0x50, 0x8b, 0x94, 0x5b, 0xb7, 0xca, 0x93, 0xdc, 0xfe, 0x9e, 0x75, 0x9b, 0x64, 0x35, 0xc6, 0x04,
-	0x95, 0x95, 0x96, 0xfd, 0x3e, 0x8b, 0xc8, 0x4a, 0xd3, 0x69, 0x2d, 0xfa, 0xf9, 0xf2, 0x59, 0xf1,
-	0xe3, 0xb7, 0x66, 0x61, 0xfb, 0xc7, 0x1c, 0xaa, 0xbc, 0x34, 0xd3, 0x7b, 0xa6, 0xa9, 0x66, 0xb8,
-	0x85, 0x4a, 0x7d, 0x18, 0x1f, 0x18, 0x99, 0xe5, 0xfd, 0x35, 0x37, 0x9f, 0x66, 0xd7, 0x8c, 0x95,
-	0x6f, 0x79, 0xfc, 0x1f, 0x2a, 0xdb, 0xeb, 0x48, 0xe6, 0x9a, 0xf3, 0xb3, 0xa1, 0xa6, 0x75, 0x7e,
-	0x1e, 0x80, 0x3b, 0xa8, 0x6a, 0x7e, 0xa1, 0x6e, 0x91, 0x25, 0x8a, 0xcc, 0x83, 0xa6, 0x3e, 0xd1,
-	0x9c, 0x2a, 0x6e, 0x64, 0x1d, 0x13, 0xe2, 0xaf, 0x0e, 0xa7, 0x97, 0x0a, 0x1f, 0xa0, 0xb2, 0xbd,
-	0xcb, 0xa4, 0x08, 0xe2, 0xcd, 0x89, 0xf8, 0xd5, 0x40, 0x73, 0x29, 0x52, 0x7e, 0x7e, 0x05, 0x2d,
-	0xf5, 0xf3, 0x48, 0x7c, 0x84, 0x56, 0xe1, 0x77, 0x62, 0xbc, 0xf0, 0x58, 0x7b, 0xaa, 0xb8, 0xf5,
-	0x00, 0x6d, 0xbb, 0x38, 0x6a, 0x81, 0xbf, 0x02, 0xb2, 0xb1, 0xf9, 0x73, 0x54, 0x99, 0x7a, 0x6c,
-	0x14, 0x29, 0x41, 0x96, 0x8d, 0x49, 0x96, 0xc3, 0x09, 0x6b, 0x33, 0xcc, 0x08, 0xda, 0xc7, 0x37,
-	0xf7, 0x0d, 0xe7, 0xf6, 0xbe, 0xe1, 0x7c, 0xbf, 0x6f, 0x38, 0x9f, 0x1e, 0x1a, 0x85, 0xdb, 0x87,
-	0x46, 0xe1, 0xcb, 0x43, 0xa3, 0xf0, 0xce, 0x9d, 0x6a, 0xf5, 0xa9, 0x48, 0x35, 0xcb, 0xce, 0x19,
-	0x4d, 0xbc, 0x24, 0x1e, 0x74, 0x3d, 0x78, 0x18, 0xbc, 0x2b, 0xcf, 0xbc, 0x78, 0xd0, 0xf6, 0x6e,
-	0x09, 0x5e, 0xba, 0x83, 0x9f, 0x01, 0x00, 0x00, 0xff, 0xff, 0xfe, 0xe1, 0x99, 0xb5, 0x80, 0x05,
-	0x00, 0x00,
+	// 746 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xad, 0x54, 0x4b, 0x4f, 0xdb, 0x40,
+	0x10, 0xe6, 0x11, 0x12, 0x58, 0x42, 0x12, 0x2d, 0x0f, 0x99, 0x48, 0x05, 0xc4, 0xa1, 0xa5, 0x95,
+	0xb0, 0x0b, 0x5c, 0xaa, 0x5e, 0x2a, 0x92, 0x96, 0xd2, 0x03, 0xa5, 0x0a, 0x11, 0x95, 0x7a, 0xd9,
+	0x3a, 0xf6, 0x62, 0x5b, 0xc4, 0xde, 0xc8, 0xbb, 0x09, 0x70, 0xeb, 0x4f, 0xe8, 0xbf, 0x2a, 0x47,
+	0x8e, 0x55, 0x55, 0xa1, 0xaa, 0x3d, 0xf4, 0x6f, 0x74, 0x3c, 0xbb, 0x79, 0xd1, 0x9e, 0x50, 0x0f,
+	0x2b, 0x7b, 0xe7, 0x9b, 0x6f, 0xbe, 0xd9, 0xdd, 0x99, 0x21, 0x2b, 0x1d, 0x1e, 0x04, 0x57, 0x4e,
+	0x6f, 0xc7, 0x09, 0x78, 0xc2, 0x65, 0x24, 0xed, 0x4e, 0x2a, 0x94, 0xa0, 0xb3, 0x68, 0xb7, 0x7b,
+	0x3b, 0xd5, 0xa5, 0x40, 0x04, 0x02, 0x8d, 0x4e, 0xf6, 0xa7, 0xf1, 0xea, 0xd2, 0x80, 0xa7, 0xae,
+	0x3a, 0xdc, 0xb0, 0xaa, 0x8b, 0x03, 0x6b, 0x2c, 0x03, 0xf9, 0x97, 0x6b, 0xcb, 0x55, 0x5e, 0x68,
+	0xac, 0xd5, 0x81, 0xd5, 0x55, 0x8a, 0x4b, 0xe5, 0xaa, 0x48, 0x24, 0x1a, 0xdb, 0xfc, 0x52, 0x20,
+	0xf9, 0x77, 0x6e, 0xea, 0xc6, 0x92, 0xae, 0x12, 0x9d, 0x09, 0x8b, 0x7c, 0x6b, 0x72, 0x63, 0x72,
+	0x6b, 0xae, 0x51, 0xc0, 0xfd, 0x1b, 0x9f, 0x3e, 0x25, 0x4b, 0x9e, 0x48, 0x54, 0xea, 0x7a, 0x8a,
+	0x49, 0xd1, 0x4d, 0x3d, 0xce, 0x42, 0x57, 0x86, 0xd6, 0x14, 0xba, 0xd1, 0x3e, 0x76, 0x82, 0xd0,
+	0x21, 0x20, 0xf4, 0x11, 0x29, 0x83, 0x50, 0xaa, 0x98, 0x0a, 0x53, 0x2e, 0x43, 0xd1, 0xf6, 0xad,
+	0x69, 0x70, 0xce, 0x35, 0x4a, 0x68, 0x6e, 0xf6, 0xad, 0xf4, 0x31, 0xa9, 0x70, 0x15, 0xf2, 0x94,
+	0x77, 0x63, 0xe6, 0xfa, 0x3e, 0x98, 0xa5, 0x95, 0xc3, 0xb0, 0xe5, 0xbe, 0x7d, 0x5f, 0x9b, 0xe9,
+	0x43, 0x52, 0x6e, 0xa5, 0x91, 0x1f, 0x70, 0xe6, 0x85, 0x6e, 0x94, 0x64, 0x79, 0xce, 0x60, 0xcc,
+	0x05, 0x6d, 0xae, 0x67, 0x56, 0xc8, 0x76, 0x97, 0x2c, 0xcb, 0x28, 0x48, 0xb8, 0xcf, 0x7a, 0x6e,
+	0x5b, 0x72, 0x25, 0xd9, 0x45, 0x94, 0xf8, 0xe2, 0xc2, 0xca, 0xa3, 0xf7, 0xa2, 0x06, 0x4f, 0x35,
+	0xf6, 0x1e, 0xa1, 0x11, 0x0e, 0xde, 0x1c, 0x1f, 0x70, 0x0a, 0xa3, 0x9c, 0x9a, 0xc6, 0x0c, 0x07,
+	0x6e, 0xc5, 0x70, 0xbc, 0xb6, 0x1b, 0xc5, 0x03, 0xca, 0x2c, 0x52, 0xa8, 0xc6, 0xea, 0x08, 0x19,
+	0x46, 0x0b, 0x54, 0xda, 0x70, 0x3d, 0xec, 0x2c, 0xbb, 0x2e, 0x78, 0x05, 0x93, 0xa1, 0x35, 0x07,
+	0x94, 0x62, 0xcd, 0xbe, 0xbe, 0x5d, 0x9f, 0xf8, 0x76, 0xbb, 0xfe, 0x30, 0x88, 0x54, 0xd8, 0x6d,
+	0xd9, 0x9e, 0x88, 0x1d, 0x4f, 0xc8, 0x58, 0x48, 0xf3, 0xd9, 0x96, 0xfe, 0xb9, 0xa9, 0x82, 0x97,
+	0xdc, 0x83, 0xac, 0xb2, 0x60, 0x07, 0x26, 0x96, 0x3e, 0x10, 0xfd, 0x08, 0x59, 0x8d, 0x6b, 0xe0,
+	0x89, 0x2c, 0x72, 0x2f, 0x09, 0x3a, 0x26, 0x81, 0xe7, 0xff, 0x87, 0x02, 0x9e, 0xdf, 0x9a, 0xff,
+	0x0f, 0x0a, 0x78, 0x5d, 0xf4, 0x82, 0x6c, 0xdc, 0x55, 0x10, 0xc9, 0x59, 0x3b, 0x82, 0xff, 0x24,
+	0x30, 0x6a, 0xc5, 0x7b, 0xa9, 0x3d, 0x18, 0x57, 0x1b, 0x46, 0xd5, 0xc2, 0x16, 0x29, 0x48, 0x25,
+	0x3a, 0x1d, 0xee, 0x5b, 0x0b, 0x10, 0x7f, 0xb6, 0xd1, 0xdf, 0xd2, 0x6d, 0xb2, 0x18, 0xba, 0x6d,
+	0xc5, 0x5c, 0xc5, 0x78, 0x8f, 0x27, 0x8a, 0x25, 0x22, 0xf1, 0xb8, 0x55, 0xc2, 0xb7, 0xae, 0x64,
+	0xd0, 0xbe, 0x7a, 0x95, 0x01, 0x6f, 0x33, 0x3b, 0x7d, 0x46, 0xac, 0x18, 0x4a, 0x54, 0xa4, 0x59,
+	0xbd, 0x40, 0x73, 0x28, 0x91, 0xb2, 0x1e, 0x4f, 0x25, 0x88, 0x5a, 0x65, 0x2c, 0xef, 0x15, 0xc0,
+	0x8f, 0x47, 0xe0, 0x53, 0x8d, 0x66, 0x55, 0x85, 0x0f, 0xc6, 0x54, 0x14, 0x73, 0xd1, 0x55, 0xac,
+	0xd5, 0x16, 0xde, 0xb9, 0xb4, 0x2a, 0xba, 0xaa, 0x10, 0x6b, 0x6a, 0xa8, 0x86, 0xc8, 0xf3, 0xdc,
+	0xa7, 0xef, 0x1b, 0x13, 0x9b, 0xbf, 0xa7, 0x48, 0xf1, 0xb5, 0x1e, 0x2c, 0x27, 0xd0, 0xe2, 0x9c,
+	0x6e, 0x91, 0x7c, 0x07, 0x3b, 0x1b, 0xbb, 0x79, 0x7e, 0xb7, 0x62, 0xf7, 0x07, 0x8d, 0xad, 0x3b,
+	0xbe, 0x61, 0x70, 0xfa, 0x84, 0x14, 0x4c, 0xa7, 0x40, 0x47, 0x4f, 0x8f, 0xbb, 0xea, 0xaa, 0x6a,
+	0xf4, 0x1d, 0x68, 0x9d, 0x94, 0xf5, 0x2f, 0x3e, 0x49, 0x94, 0x42, 0xf8, 0x69, 0xe4, 0x54, 0x87,
+	0x9c, 0x23, 0x19, 0x68, 0x5a, 0x5d, 0xbb, 0x34, 0x4a, 0xbd, 0xd1, 0xad, 0xa4, 0x7b, 0xa4, 0x60,
+	0xda, 0x0c, 0x7a, 0x3d, 0x23, 0xaf, 0x0e, 0xc9, 0xc7, 0x5d, 0x15, 0x08, 0x78, 0x90, 0xe6, 0x25,
+	0x56, 0x5b, 0xa3, 0xef, 0x49, 0x0f, 0x48, 0x49, 0x5f, 0xcc, 0x40, 0x78, 0xe6, 0x2e, 0x17, 0x84,
+	0x8d, 0x06, 0x72, 0x6b, 0xb9, 0xac, 0x3a, 0x60, 0x3c, 0x64, 0x9b, 0x81, 0xf8, 0x0b, 0x52, 0x1c,
+	0x99, 0x83, 0x12, 0xa6, 0x42, 0x16, 0x65, 0x79, 0x18, 0x65, 0x7f, 0x88, 0x9a, 0x08, 0x63, 0x84,
+	0xda, 0xe1, 0xf5, 0xcf, 0xb5, 0xc9, 0x1b, 0x58, 0x3f, 0x60, 0x7d, 0xfe, 0xb5, 0x36, 0x71, 0x03,
+	0xeb, 0x2b, 0xac, 0x0f, 0xf6, 0x48, 0x15, 0x1e, 0x45, 0x89, 0xe2, 0x69, 0x93, 0xbb, 0xb1, 0x13,
+	0x83, 0xcd, 0xc1, 0x99, 0xe5, 0x5c, 0x3a, 0x7a, 0x18, 0x63, 0x45, 0xb6, 0xf2, 0x38, 0x84, 0xf7,
+	0xfe, 0x00, 0x93, 0x6a, 0xd5, 0x28, 0x1b, 0x06, 0x00, 0x00,
 }
 
 func (m *Params) Marshal() (dAtA []byte, err error) {
@@ -309,6 +337,25 @@ func (m *Params) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if m.BatchTimeoutBlocks != 0 {
+		i = encodeVarintGenesis(dAtA, i, uint64(m.BatchTimeoutBlocks))
+		i--
+		dAtA[i] = 0x1
+		i--
+		dAtA[i] = 0x80
+	}
+	if len(m.MinOrchestratorVersion) > 0 {
+		i -= len(m.MinOrchestratorVersion)
+		copy(dAtA[i:], m.MinOrchestratorVersion)
+		i = encodeVarintGenesis(dAtA, i, uint64(len(m.MinOrchestratorVersion)))
+		i--
+		dAtA[i] = 0x7a
+	}
+	if m.HaltAtEventNonce != 0 {
+		i = encodeVarintGenesis(dAtA, i, uint64(m.HaltAtEventNonce))
+		i--
+		dAtA[i] = 0x70
+	}
 	if m.Stopped {
 		i--
 		if m.Stopped {
@@ -568,6 +615,16 @@ func (m *Params) Size() (n int) {
 	if m.Stopped {
 		n += 2
 	}
+	if m.HaltAtEventNonce != 0 {
+		n += 1 + sovGenesis(uint64(m.HaltAtEventNonce))
+	}
+	l = len(m.MinOrchestratorVersion)
+	if l > 0 {
+		n += 1 + l + sovGenesis(uint64(l))
+	}
+	if m.BatchTimeoutBlocks != 0 {
+		n += 2 + sovGenesis(uint64(m.BatchTimeoutBlocks))
+	}
 	return n
 }
 
@@ -992,6 +1049,76 @@ func (m *Params) Unmarshal(dAtA []byte) error {
 				}
 			}
 			m.Stopped = bool(v != 0)
+		case 14:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field HaltAtEventNonce", wireType)
+			}
+			m.HaltAtEventNonce = 0
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowGenesis
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				m.HaltAtEventNonce |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+		case 15:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field MinOrchestratorVersion", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowGenesis
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthGenesis
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthGenesis
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.MinOrchestratorVersion = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
+		case 16:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field BatchTimeoutBlocks", wireType)
+			}
+			m.BatchTimeoutBlocks = 0
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowGenesis
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				m.BatchTimeoutBlocks |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
 		default:
 			iNdEx = preIndex
 			skippy, err := skipGenesis(dAtA[iNdEx:])
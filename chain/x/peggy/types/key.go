@@ -1,6 +1,8 @@
 package types
 
 import (
+	"strings"
+
 	oraclekeeper "github.com/MinterTeam/mhub/chain/x/oracle/keeper"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 )
@@ -84,6 +86,9 @@ var (
 	KeyOrchestratorAddress = []byte{0xe8}
 
 	LockedCoinsKey = []byte{0x19}
+
+	// AttestationByTxHashKey indexes the attestations of the claims of an Ethereum tx hash
+	AttestationByTxHashKey = []byte{0x1a}
 )
 
 // GetOrchestratorAddressKey returns the following key format
@@ -175,6 +180,24 @@ func GetAttestationKeyWithHash(eventNonce uint64, claimHash []byte) []byte {
 	return key
 }
 
+// GetAttestationByTxHashPrefix returns the following key format
+// prefix     tx-hash
+// [0x1a][0x0f7a0b3e5b3a2f9bb5c3c2b1f8b7a2c1f7c5e2a1c4f6d3e8b9a2c5d7e1f3a4b6/]
+func GetAttestationByTxHashPrefix(txHash string) []byte {
+	return append(AttestationByTxHashKey, []byte(strings.ToLower(txHash)+"/")...)
+}
+
+// GetAttestationByTxHashKey returns the following key format
+// prefix     tx-hash                                                              nonce             claim-details-hash
+// [0x1a][0x0f7a0b3e5b3a2f9bb5c3c2b1f8b7a2c1f7c5e2a1c4f6d3e8b9a2c5d7e1f3a4b6/][0 0 0 0 0 0 0 1][fd1af8cec6c67fcf156f1b61fdf91ebc04d05484d007436e75342fc05bbff35a]
+// Conflicting claims about one tx hash each get their own entry, so a later claim cannot replace the
+// attestation an earlier one voted for
+func GetAttestationByTxHashKey(txHash string, eventNonce uint64, claimHash []byte) []byte {
+	key := GetAttestationByTxHashPrefix(txHash)
+	key = append(key, UInt64Bytes(eventNonce)...)
+	return append(key, claimHash...)
+}
+
 // GetOutgoingTxPoolKey returns the following key format
 // prefix     id
 // [0x6][0 0 0 0 0 0 0 1]
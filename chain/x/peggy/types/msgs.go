@@ -5,6 +5,7 @@ import (
 	"fmt"
 	"regexp"
 
+	oracletypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	sdkerrors "github.com/cosmos/cosmos-sdk/types/errors"
 	"github.com/tendermint/tendermint/crypto/tmhash"
@@ -13,6 +14,8 @@ import (
 var (
 	_ sdk.Msg = &MsgValsetConfirm{}
 	_ sdk.Msg = &MsgSendToEth{}
+	_ sdk.Msg = &MsgCancelSendToEth{}
+	_ sdk.Msg = &MsgRelayReceipt{}
 	_ sdk.Msg = &MsgRequestBatch{}
 	_ sdk.Msg = &MsgConfirmBatch{}
 	_ sdk.Msg = &MsgSetOrchestratorAddress{}
@@ -135,6 +138,9 @@ func (msg MsgSendToEth) ValidateBasic() error {
 	if err := ValidateEthAddress(msg.EthDest); err != nil {
 		return sdkerrors.Wrap(err, "ethereum address")
 	}
+	if err := oracletypes.ValidateMemo(msg.Memo); err != nil {
+		return sdkerrors.Wrap(ErrInvalid, err.Error())
+	}
 	// TODO validate fee is sufficient, fixed fee to start
 	return nil
 }
@@ -154,6 +160,96 @@ func (msg MsgSendToEth) GetSigners() []sdk.AccAddress {
 	return []sdk.AccAddress{acc}
 }
 
+// NewMsgCancelSendToEth returns a new msgCancelSendToEth
+func NewMsgCancelSendToEth(sender sdk.AccAddress, id uint64) *MsgCancelSendToEth {
+	return &MsgCancelSendToEth{
+		TransactionId: id,
+		Sender:        sender.String(),
+	}
+}
+
+// Route should return the name of the module
+func (msg MsgCancelSendToEth) Route() string { return RouterKey }
+
+// Type should return the action
+func (msg MsgCancelSendToEth) Type() string { return "cancel_send_to_eth" }
+
+// ValidateBasic runs stateless checks on the message
+func (msg MsgCancelSendToEth) ValidateBasic() error {
+	if _, err := sdk.AccAddressFromBech32(msg.Sender); err != nil {
+		return sdkerrors.Wrap(sdkerrors.ErrInvalidAddress, msg.Sender)
+	}
+	if msg.TransactionId == 0 {
+		return sdkerrors.Wrap(ErrInvalid, "transaction id")
+	}
+	return nil
+}
+
+// GetSignBytes encodes the message for signing
+func (msg MsgCancelSendToEth) GetSignBytes() []byte {
+	return sdk.MustSortJSON(ModuleCdc.MustMarshalJSON(msg))
+}
+
+// GetSigners defines whose signature is required
+func (msg MsgCancelSendToEth) GetSigners() []sdk.AccAddress {
+	acc, err := sdk.AccAddressFromBech32(msg.Sender)
+	if err != nil {
+		panic(err)
+	}
+
+	return []sdk.AccAddress{acc}
+}
+
+// NewMsgRelayReceipt returns a new msgRelayReceipt, tokenContract is empty for a valset update
+func NewMsgRelayReceipt(relayer sdk.AccAddress, nonce uint64, tokenContract string, ethTxHash string) *MsgRelayReceipt {
+	return &MsgRelayReceipt{
+		Relayer:       relayer.String(),
+		Nonce:         nonce,
+		TokenContract: tokenContract,
+		EthTxHash:     ethTxHash,
+	}
+}
+
+// Route should return the name of the module
+func (msg MsgRelayReceipt) Route() string { return RouterKey }
+
+// Type should return the action
+func (msg MsgRelayReceipt) Type() string { return "relay_receipt" }
+
+// ValidateBasic runs stateless checks on the message
+func (msg MsgRelayReceipt) ValidateBasic() error {
+	if _, err := sdk.AccAddressFromBech32(msg.Relayer); err != nil {
+		return sdkerrors.Wrap(sdkerrors.ErrInvalidAddress, msg.Relayer)
+	}
+	if msg.Nonce == 0 {
+		return sdkerrors.Wrap(ErrInvalid, "nonce")
+	}
+	if msg.TokenContract != "" {
+		if err := ValidateEthAddress(msg.TokenContract); err != nil {
+			return sdkerrors.Wrap(err, "token contract")
+		}
+	}
+	if !regexp.MustCompile("^0x[0-9a-fA-F]{64}$").MatchString(msg.EthTxHash) {
+		return sdkerrors.Wrap(ErrInvalid, "eth tx hash")
+	}
+	return nil
+}
+
+// GetSignBytes encodes the message for signing
+func (msg MsgRelayReceipt) GetSignBytes() []byte {
+	return sdk.MustSortJSON(ModuleCdc.MustMarshalJSON(msg))
+}
+
+// GetSigners defines whose signature is required
+func (msg MsgRelayReceipt) GetSigners() []sdk.AccAddress {
+	acc, err := sdk.AccAddressFromBech32(msg.Relayer)
+	if err != nil {
+		panic(err)
+	}
+
+	return []sdk.AccAddress{acc}
+}
+
 // NewMsgRequestBatch returns a new msgRequestBatch
 func NewMsgRequestBatch(orchestrator sdk.AccAddress) *MsgRequestBatch {
 	return &MsgRequestBatch{
@@ -314,8 +410,8 @@ func (e *MsgSendToMinterClaim) GetType() ClaimType {
 
 // ValidateBasic performs stateless checks
 func (e *MsgSendToMinterClaim) ValidateBasic() error {
-	if err := ValidateMinterAddress(e.MinterReceiver); err != nil {
-		return sdkerrors.Wrap(sdkerrors.ErrInvalidAddress, e.MinterReceiver)
+	if err := e.ValidateDestination(); err != nil {
+		return err
 	}
 	if err := ValidateEthAddress(e.EthereumSender); err != nil {
 		return sdkerrors.Wrap(err, "eth sender")
@@ -332,6 +428,12 @@ func (e *MsgSendToMinterClaim) ValidateBasic() error {
 	return nil
 }
 
+// ValidateDestination checks the Minter receiver, a claim marked InvalidDestination must have one that doesn't
+// validate
+func (e *MsgSendToMinterClaim) ValidateDestination() error {
+	return checkDestination(e.MinterReceiver, ValidateMinterAddress(e.MinterReceiver), e.InvalidDestination)
+}
+
 // GetSignBytes encodes the message for signing
 func (msg MsgSendToMinterClaim) GetSignBytes() []byte {
 	return sdk.MustSortJSON(ModuleCdc.MustMarshalJSON(msg))
@@ -366,7 +468,7 @@ func (msg MsgSendToMinterClaim) Route() string { return RouterKey }
 // Hash implements BridgeDeposit.Hash
 func (b *MsgSendToMinterClaim) ClaimHash() []byte {
 	path := fmt.Sprintf("%s/%s/%s/", b.TokenContract, string(b.EthereumSender), b.MinterReceiver)
-	return tmhash.Sum([]byte(path))
+	return tmhash.Sum([]byte(path + refundPath(b.InvalidDestination, b.ValidateDestination())))
 }
 
 // GetType returns the type of the claim
@@ -376,8 +478,8 @@ func (e *MsgDepositClaim) GetType() ClaimType {
 
 // ValidateBasic performs stateless checks
 func (e *MsgDepositClaim) ValidateBasic() error {
-	if _, err := sdk.AccAddressFromBech32(e.CosmosReceiver); err != nil {
-		return sdkerrors.Wrap(sdkerrors.ErrInvalidAddress, e.CosmosReceiver)
+	if err := e.ValidateDestination(); err != nil {
+		return err
 	}
 	if err := ValidateEthAddress(e.EthereumSender); err != nil {
 		return sdkerrors.Wrap(err, "eth sender")
@@ -394,6 +496,13 @@ func (e *MsgDepositClaim) ValidateBasic() error {
 	return nil
 }
 
+// ValidateDestination checks the Cosmos receiver, a claim marked InvalidDestination must have one that doesn't
+// validate
+func (e *MsgDepositClaim) ValidateDestination() error {
+	_, err := sdk.AccAddressFromBech32(e.CosmosReceiver)
+	return checkDestination(e.CosmosReceiver, err, e.InvalidDestination)
+}
+
 // GetSignBytes encodes the message for signing
 func (msg MsgDepositClaim) GetSignBytes() []byte {
 	return sdk.MustSortJSON(ModuleCdc.MustMarshalJSON(msg))
@@ -432,7 +541,7 @@ const (
 // Hash implements BridgeDeposit.Hash
 func (b *MsgDepositClaim) ClaimHash() []byte {
 	path := fmt.Sprintf("%s/%s/%s/", b.TokenContract, string(b.EthereumSender), b.CosmosReceiver)
-	return tmhash.Sum([]byte(path))
+	return tmhash.Sum([]byte(path + refundPath(b.InvalidDestination, b.ValidateDestination())))
 }
 
 // GetType returns the claim type
@@ -498,6 +607,30 @@ const (
 )
 
 // ValidateMinterAddress validates the minter address strings
+// checkDestination refuses a receiver that doesn't validate unless the claim says so, and a claim that says so
+// about a receiver that validates, which would refund a deposit that can be delivered
+func checkDestination(receiver string, invalid error, invalidDestination bool) error {
+	switch {
+	case invalid != nil && !invalidDestination:
+		return sdkerrors.Wrap(sdkerrors.ErrInvalidAddress, receiver)
+	case invalid == nil && invalidDestination:
+		return sdkerrors.Wrapf(ErrInvalid, "invalid destination claimed for the valid receiver %s", receiver)
+	}
+	return nil
+}
+
+// refundPath separates the claim hash of a refund from a delivery of the same deposit, and a refund claimed
+// for a valid receiver, which fails ValidateDestination, from both so it never counts towards either
+func refundPath(invalidDestination bool, destinationErr error) string {
+	switch {
+	case !invalidDestination:
+		return ""
+	case destinationErr != nil:
+		return "refund/valid-receiver/"
+	}
+	return "refund/"
+}
+
 func ValidateMinterAddress(a string) error {
 	if a == "" {
 		return fmt.Errorf("empty")
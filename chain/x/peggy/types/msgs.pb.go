@@ -276,6 +276,7 @@ type MsgSendToEth struct {
 	EthDest   string     `protobuf:"bytes,2,opt,name=eth_dest,json=ethDest,proto3" json:"eth_dest,omitempty"`
 	Amount    types.Coin `protobuf:"bytes,3,opt,name=amount,proto3" json:"amount"`
 	BridgeFee types.Coin `protobuf:"bytes,4,opt,name=bridge_fee,json=bridgeFee,proto3" json:"bridge_fee"`
+	Memo      string     `protobuf:"bytes,5,opt,name=memo,proto3" json:"memo,omitempty"`
 }
 
 func (m *MsgSendToEth) Reset()         { *m = MsgSendToEth{} }
@@ -339,6 +340,13 @@ func (m *MsgSendToEth) GetBridgeFee() types.Coin {
 	return types.Coin{}
 }
 
+func (m *MsgSendToEth) GetMemo() string {
+	if m != nil {
+		return m.Memo
+	}
+	return ""
+}
+
 type MsgSendToEthResponse struct {
 }
 
@@ -598,13 +606,14 @@ var xxx_messageInfo_MsgConfirmBatchResponse proto.InternalMessageInfo
 // issued to the Cosmos address in question
 // -------------
 type MsgDepositClaim struct {
-	EventNonce     uint64                                 `protobuf:"varint,1,opt,name=event_nonce,json=eventNonce,proto3" json:"event_nonce,omitempty"`
-	TokenContract  string                                 `protobuf:"bytes,2,opt,name=token_contract,json=tokenContract,proto3" json:"token_contract,omitempty"`
-	Amount         github_com_cosmos_cosmos_sdk_types.Int `protobuf:"bytes,3,opt,name=amount,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Int" json:"amount"`
-	EthereumSender string                                 `protobuf:"bytes,4,opt,name=ethereum_sender,json=ethereumSender,proto3" json:"ethereum_sender,omitempty"`
-	CosmosReceiver string                                 `protobuf:"bytes,5,opt,name=cosmos_receiver,json=cosmosReceiver,proto3" json:"cosmos_receiver,omitempty"`
-	Orchestrator   string                                 `protobuf:"bytes,6,opt,name=orchestrator,proto3" json:"orchestrator,omitempty"`
-	TxHash         string                                 `protobuf:"bytes,7,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	EventNonce         uint64                                 `protobuf:"varint,1,opt,name=event_nonce,json=eventNonce,proto3" json:"event_nonce,omitempty"`
+	TokenContract      string                                 `protobuf:"bytes,2,opt,name=token_contract,json=tokenContract,proto3" json:"token_contract,omitempty"`
+	Amount             github_com_cosmos_cosmos_sdk_types.Int `protobuf:"bytes,3,opt,name=amount,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Int" json:"amount"`
+	EthereumSender     string                                 `protobuf:"bytes,4,opt,name=ethereum_sender,json=ethereumSender,proto3" json:"ethereum_sender,omitempty"`
+	CosmosReceiver     string                                 `protobuf:"bytes,5,opt,name=cosmos_receiver,json=cosmosReceiver,proto3" json:"cosmos_receiver,omitempty"`
+	Orchestrator       string                                 `protobuf:"bytes,6,opt,name=orchestrator,proto3" json:"orchestrator,omitempty"`
+	TxHash             string                                 `protobuf:"bytes,7,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	InvalidDestination bool                                   `protobuf:"varint,8,opt,name=invalid_destination,json=invalidDestination,proto3" json:"invalid_destination,omitempty"`
 }
 
 func (m *MsgDepositClaim) Reset()         { *m = MsgDepositClaim{} }
@@ -682,6 +691,13 @@ func (m *MsgDepositClaim) GetTxHash() string {
 	return ""
 }
 
+func (m *MsgDepositClaim) GetInvalidDestination() bool {
+	if m != nil {
+		return m.InvalidDestination
+	}
+	return false
+}
+
 type MsgDepositClaimResponse struct {
 }
 
@@ -719,13 +735,14 @@ func (m *MsgDepositClaimResponse) XXX_DiscardUnknown() {
 var xxx_messageInfo_MsgDepositClaimResponse proto.InternalMessageInfo
 
 type MsgSendToMinterClaim struct {
-	EventNonce     uint64                                 `protobuf:"varint,1,opt,name=event_nonce,json=eventNonce,proto3" json:"event_nonce,omitempty"`
-	TokenContract  string                                 `protobuf:"bytes,2,opt,name=token_contract,json=tokenContract,proto3" json:"token_contract,omitempty"`
-	Amount         github_com_cosmos_cosmos_sdk_types.Int `protobuf:"bytes,3,opt,name=amount,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Int" json:"amount"`
-	EthereumSender string                                 `protobuf:"bytes,4,opt,name=ethereum_sender,json=ethereumSender,proto3" json:"ethereum_sender,omitempty"`
-	MinterReceiver string                                 `protobuf:"bytes,5,opt,name=minter_receiver,json=minterReceiver,proto3" json:"minter_receiver,omitempty"`
-	Orchestrator   string                                 `protobuf:"bytes,6,opt,name=orchestrator,proto3" json:"orchestrator,omitempty"`
-	TxHash         string                                 `protobuf:"bytes,7,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	EventNonce         uint64                                 `protobuf:"varint,1,opt,name=event_nonce,json=eventNonce,proto3" json:"event_nonce,omitempty"`
+	TokenContract      string                                 `protobuf:"bytes,2,opt,name=token_contract,json=tokenContract,proto3" json:"token_contract,omitempty"`
+	Amount             github_com_cosmos_cosmos_sdk_types.Int `protobuf:"bytes,3,opt,name=amount,proto3,customtype=github.com/cosmos/cosmos-sdk/types.Int" json:"amount"`
+	EthereumSender     string                                 `protobuf:"bytes,4,opt,name=ethereum_sender,json=ethereumSender,proto3" json:"ethereum_sender,omitempty"`
+	MinterReceiver     string                                 `protobuf:"bytes,5,opt,name=minter_receiver,json=minterReceiver,proto3" json:"minter_receiver,omitempty"`
+	Orchestrator       string                                 `protobuf:"bytes,6,opt,name=orchestrator,proto3" json:"orchestrator,omitempty"`
+	TxHash             string                                 `protobuf:"bytes,7,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	InvalidDestination bool                                   `protobuf:"varint,8,opt,name=invalid_destination,json=invalidDestination,proto3" json:"invalid_destination,omitempty"`
 }
 
 func (m *MsgSendToMinterClaim) Reset()         { *m = MsgSendToMinterClaim{} }
@@ -803,6 +820,13 @@ func (m *MsgSendToMinterClaim) GetTxHash() string {
 	return ""
 }
 
+func (m *MsgSendToMinterClaim) GetInvalidDestination() bool {
+	if m != nil {
+		return m.InvalidDestination
+	}
+	return false
+}
+
 type MsgSendToMinterClaimResponse struct {
 }
 
@@ -961,6 +985,220 @@ func (m *MsgWithdrawClaimResponse) XXX_DiscardUnknown() {
 
 var xxx_messageInfo_MsgWithdrawClaimResponse proto.InternalMessageInfo
 
+// MsgCancelSendToEth
+// takes a transfer that no batch has picked up yet out of the pool and
+// refunds its amount and bridge fee to the sender, for example to send it
+// again with a higher fee
+// -------------
+// TRANSACTION_ID:
+// the id of the transfer in the outgoing pool
+// SENDER:
+// the sender of the transfer, only they can cancel it
+type MsgCancelSendToEth struct {
+	TransactionId uint64 `protobuf:"varint,1,opt,name=transaction_id,json=transactionId,proto3" json:"transaction_id,omitempty"`
+	Sender        string `protobuf:"bytes,2,opt,name=sender,proto3" json:"sender,omitempty"`
+}
+
+func (m *MsgCancelSendToEth) Reset()         { *m = MsgCancelSendToEth{} }
+func (m *MsgCancelSendToEth) String() string { return proto.CompactTextString(m) }
+func (*MsgCancelSendToEth) ProtoMessage()    {}
+func (*MsgCancelSendToEth) Descriptor() ([]byte, []int) {
+	return fileDescriptor_75b6627b296db358, []int{16}
+}
+func (m *MsgCancelSendToEth) XXX_Unmarshal(b []byte) error {
+	return m.Unmarshal(b)
+}
+func (m *MsgCancelSendToEth) XXX_Marshal(b []byte, deterministic bool) ([]byte, error) {
+	if deterministic {
+		return xxx_messageInfo_MsgCancelSendToEth.Marshal(b, m, deterministic)
+	} else {
+		b = b[:cap(b)]
+		n, err := m.MarshalToSizedBuffer(b)
+		if err != nil {
+			return nil, err
+		}
+		return b[:n], nil
+	}
+}
+func (m *MsgCancelSendToEth) XXX_Merge(src proto.Message) {
+	xxx_messageInfo_MsgCancelSendToEth.Merge(m, src)
+}
+func (m *MsgCancelSendToEth) XXX_Size() int {
+	return m.Size()
+}
+func (m *MsgCancelSendToEth) XXX_DiscardUnknown() {
+	xxx_messageInfo_MsgCancelSendToEth.DiscardUnknown(m)
+}
+
+var xxx_messageInfo_MsgCancelSendToEth proto.InternalMessageInfo
+
+func (m *MsgCancelSendToEth) GetTransactionId() uint64 {
+	if m != nil {
+		return m.TransactionId
+	}
+	return 0
+}
+
+func (m *MsgCancelSendToEth) GetSender() string {
+	if m != nil {
+		return m.Sender
+	}
+	return ""
+}
+
+type MsgCancelSendToEthResponse struct {
+}
+
+func (m *MsgCancelSendToEthResponse) Reset()         { *m = MsgCancelSendToEthResponse{} }
+func (m *MsgCancelSendToEthResponse) String() string { return proto.CompactTextString(m) }
+func (*MsgCancelSendToEthResponse) ProtoMessage()    {}
+func (*MsgCancelSendToEthResponse) Descriptor() ([]byte, []int) {
+	return fileDescriptor_75b6627b296db358, []int{17}
+}
+func (m *MsgCancelSendToEthResponse) XXX_Unmarshal(b []byte) error {
+	return m.Unmarshal(b)
+}
+func (m *MsgCancelSendToEthResponse) XXX_Marshal(b []byte, deterministic bool) ([]byte, error) {
+	if deterministic {
+		return xxx_messageInfo_MsgCancelSendToEthResponse.Marshal(b, m, deterministic)
+	} else {
+		b = b[:cap(b)]
+		n, err := m.MarshalToSizedBuffer(b)
+		if err != nil {
+			return nil, err
+		}
+		return b[:n], nil
+	}
+}
+func (m *MsgCancelSendToEthResponse) XXX_Merge(src proto.Message) {
+	xxx_messageInfo_MsgCancelSendToEthResponse.Merge(m, src)
+}
+func (m *MsgCancelSendToEthResponse) XXX_Size() int {
+	return m.Size()
+}
+func (m *MsgCancelSendToEthResponse) XXX_DiscardUnknown() {
+	xxx_messageInfo_MsgCancelSendToEthResponse.DiscardUnknown(m)
+}
+
+var xxx_messageInfo_MsgCancelSendToEthResponse proto.InternalMessageInfo
+
+// MsgRelayReceipt
+// records that a relayer submitted a valset update or batch to the Peggy
+// contract, so relayer work can be accounted for on chain. The Hub doesn't
+// check the Ethereum transaction, the receipt is only emitted as an event
+// -------------
+// RELAYER:
+// the Cosmos address of the relayer, usually an orchestrator's delegate key
+// NONCE:
+// the nonce of the valset or batch
+// TOKEN_CONTRACT:
+// the token contract of the batch, empty for a valset update
+// ETH_TX_HASH:
+// the Ethereum transaction it was submitted in
+type MsgRelayReceipt struct {
+	Relayer       string `protobuf:"bytes,1,opt,name=relayer,proto3" json:"relayer,omitempty"`
+	Nonce         uint64 `protobuf:"varint,2,opt,name=nonce,proto3" json:"nonce,omitempty"`
+	TokenContract string `protobuf:"bytes,3,opt,name=token_contract,json=tokenContract,proto3" json:"token_contract,omitempty"`
+	EthTxHash     string `protobuf:"bytes,4,opt,name=eth_tx_hash,json=ethTxHash,proto3" json:"eth_tx_hash,omitempty"`
+}
+
+func (m *MsgRelayReceipt) Reset()         { *m = MsgRelayReceipt{} }
+func (m *MsgRelayReceipt) String() string { return proto.CompactTextString(m) }
+func (*MsgRelayReceipt) ProtoMessage()    {}
+func (*MsgRelayReceipt) Descriptor() ([]byte, []int) {
+	return fileDescriptor_75b6627b296db358, []int{18}
+}
+func (m *MsgRelayReceipt) XXX_Unmarshal(b []byte) error {
+	return m.Unmarshal(b)
+}
+func (m *MsgRelayReceipt) XXX_Marshal(b []byte, deterministic bool) ([]byte, error) {
+	if deterministic {
+		return xxx_messageInfo_MsgRelayReceipt.Marshal(b, m, deterministic)
+	} else {
+		b = b[:cap(b)]
+		n, err := m.MarshalToSizedBuffer(b)
+		if err != nil {
+			return nil, err
+		}
+		return b[:n], nil
+	}
+}
+func (m *MsgRelayReceipt) XXX_Merge(src proto.Message) {
+	xxx_messageInfo_MsgRelayReceipt.Merge(m, src)
+}
+func (m *MsgRelayReceipt) XXX_Size() int {
+	return m.Size()
+}
+func (m *MsgRelayReceipt) XXX_DiscardUnknown() {
+	xxx_messageInfo_MsgRelayReceipt.DiscardUnknown(m)
+}
+
+var xxx_messageInfo_MsgRelayReceipt proto.InternalMessageInfo
+
+func (m *MsgRelayReceipt) GetRelayer() string {
+	if m != nil {
+		return m.Relayer
+	}
+	return ""
+}
+
+func (m *MsgRelayReceipt) GetNonce() uint64 {
+	if m != nil {
+		return m.Nonce
+	}
+	return 0
+}
+
+func (m *MsgRelayReceipt) GetTokenContract() string {
+	if m != nil {
+		return m.TokenContract
+	}
+	return ""
+}
+
+func (m *MsgRelayReceipt) GetEthTxHash() string {
+	if m != nil {
+		return m.EthTxHash
+	}
+	return ""
+}
+
+type MsgRelayReceiptResponse struct {
+}
+
+func (m *MsgRelayReceiptResponse) Reset()         { *m = MsgRelayReceiptResponse{} }
+func (m *MsgRelayReceiptResponse) String() string { return proto.CompactTextString(m) }
+func (*MsgRelayReceiptResponse) ProtoMessage()    {}
+func (*MsgRelayReceiptResponse) Descriptor() ([]byte, []int) {
+	return fileDescriptor_75b6627b296db358, []int{19}
+}
+func (m *MsgRelayReceiptResponse) XXX_Unmarshal(b []byte) error {
+	return m.Unmarshal(b)
+}
+func (m *MsgRelayReceiptResponse) XXX_Marshal(b []byte, deterministic bool) ([]byte, error) {
+	if deterministic {
+		return xxx_messageInfo_MsgRelayReceiptResponse.Marshal(b, m, deterministic)
+	} else {
+		b = b[:cap(b)]
+		n, err := m.MarshalToSizedBuffer(b)
+		if err != nil {
+			return nil, err
+		}
+		return b[:n], nil
+	}
+}
+func (m *MsgRelayReceiptResponse) XXX_Merge(src proto.Message) {
+	xxx_messageInfo_MsgRelayReceiptResponse.Merge(m, src)
+}
+func (m *MsgRelayReceiptResponse) XXX_Size() int {
+	return m.Size()
+}
+func (m *MsgRelayReceiptResponse) XXX_DiscardUnknown() {
+	xxx_messageInfo_MsgRelayReceiptResponse.DiscardUnknown(m)
+}
+
+var xxx_messageInfo_MsgRelayReceiptResponse proto.InternalMessageInfo
+
 func init() {
 	proto.RegisterType((*MsgSetOrchestratorAddress)(nil), "peggy.v1.MsgSetOrchestratorAddress")
 	proto.RegisterType((*MsgSetOrchestratorAddressResponse)(nil), "peggy.v1.MsgSetOrchestratorAddressResponse")
@@ -978,74 +1216,87 @@ func init() {
 	proto.RegisterType((*MsgSendToMinterClaimResponse)(nil), "peggy.v1.MsgSendToMinterClaimResponse")
 	proto.RegisterType((*MsgWithdrawClaim)(nil), "peggy.v1.MsgWithdrawClaim")
 	proto.RegisterType((*MsgWithdrawClaimResponse)(nil), "peggy.v1.MsgWithdrawClaimResponse")
+	proto.RegisterType((*MsgCancelSendToEth)(nil), "peggy.v1.MsgCancelSendToEth")
+	proto.RegisterType((*MsgCancelSendToEthResponse)(nil), "peggy.v1.MsgCancelSendToEthResponse")
+	proto.RegisterType((*MsgRelayReceipt)(nil), "peggy.v1.MsgRelayReceipt")
+	proto.RegisterType((*MsgRelayReceiptResponse)(nil), "peggy.v1.MsgRelayReceiptResponse")
 }
 
 func init() { proto.RegisterFile("peggy/v1/msgs.proto", fileDescriptor_75b6627b296db358) }
 
 var fileDescriptor_75b6627b296db358 = []byte{
-	// 988 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xd4, 0x56, 0xcf, 0x6f, 0xdc, 0x44,
-	0x14, 0x8e, 0xf3, 0x63, 0x93, 0x7d, 0xf9, 0x05, 0x26, 0x24, 0xbb, 0x26, 0xdd, 0x6d, 0x1c, 0xd1,
-	0x82, 0x10, 0xb6, 0x52, 0x0e, 0xdc, 0x90, 0x48, 0x4a, 0x55, 0x84, 0x16, 0xa4, 0x4d, 0x05, 0x12,
-	0x17, 0x6b, 0xd6, 0x7e, 0xb5, 0xad, 0xc6, 0x33, 0x1b, 0xcf, 0xec, 0x76, 0x7b, 0x80, 0x03, 0x57,
-	0x2e, 0x48, 0x88, 0xbf, 0x03, 0x24, 0xc4, 0x85, 0xbf, 0xa0, 0xc7, 0x4a, 0x08, 0x09, 0x71, 0xa8,
-	0x50, 0xc2, 0x1f, 0x82, 0x3c, 0x33, 0x76, 0xec, 0x5d, 0x6f, 0x15, 0xa4, 0x5e, 0x7a, 0xda, 0xf5,
-	0x37, 0xcf, 0xef, 0x7b, 0xdf, 0xf7, 0x9e, 0x9f, 0x0d, 0x6f, 0x0c, 0x31, 0x0c, 0x9f, 0xb8, 0xe3,
-	0x23, 0x37, 0xe1, 0x21, 0x77, 0x86, 0x29, 0x13, 0xcc, 0x5c, 0x93, 0xa0, 0x33, 0x3e, 0xb2, 0x3a,
-	0x3e, 0xe3, 0x09, 0xe3, 0xee, 0x80, 0x70, 0x74, 0xc7, 0x47, 0x03, 0x14, 0xe4, 0xc8, 0xf5, 0x59,
-	0x4c, 0x55, 0xa4, 0xb5, 0x13, 0xb2, 0x90, 0xc9, 0xbf, 0x6e, 0xf6, 0x4f, 0xa3, 0xfb, 0x21, 0x63,
-	0xe1, 0x19, 0xba, 0x64, 0x18, 0xbb, 0x84, 0x52, 0x26, 0x88, 0x88, 0x19, 0xd5, 0xd9, 0xed, 0x6f,
-	0xa1, 0xdd, 0xe3, 0xe1, 0x29, 0x8a, 0x2f, 0x52, 0x3f, 0x42, 0x2e, 0x52, 0x22, 0x58, 0xfa, 0x71,
-	0x10, 0xa4, 0xc8, 0xb9, 0xb9, 0x0f, 0xcd, 0x31, 0x39, 0x8b, 0x83, 0x0c, 0x6b, 0x19, 0x37, 0x8d,
-	0x77, 0x9a, 0xfd, 0x2b, 0xc0, 0xb4, 0x61, 0x83, 0x95, 0x6e, 0x6a, 0x2d, 0xca, 0x80, 0x0a, 0x66,
-	0x76, 0x61, 0x1d, 0x45, 0xe4, 0x11, 0x95, 0xb0, 0xb5, 0x24, 0x43, 0x00, 0x45, 0xa4, 0x29, 0xec,
-	0x43, 0x38, 0x98, 0xcb, 0xdf, 0x47, 0x3e, 0x64, 0x94, 0xa3, 0xfd, 0xbd, 0x01, 0xaf, 0xf5, 0x78,
-	0xf8, 0x25, 0x39, 0xe3, 0x28, 0x4e, 0x18, 0x7d, 0x18, 0xa7, 0x89, 0xb9, 0x03, 0x2b, 0x94, 0x51,
-	0x1f, 0x65, 0x61, 0xcb, 0x7d, 0x75, 0xf1, 0x52, 0x8a, 0xca, 0x74, 0xf3, 0x38, 0xa4, 0x44, 0x8c,
-	0x52, 0x6c, 0x2d, 0x2b, 0xdd, 0x05, 0x60, 0x5b, 0xd0, 0x9a, 0x2e, 0xa6, 0xa8, 0xf4, 0x77, 0x03,
-	0x36, 0xa4, 0x1e, 0x1a, 0x3c, 0x60, 0x9f, 0x88, 0xc8, 0xdc, 0x85, 0x06, 0x47, 0x1a, 0x60, 0xee,
-	0x9f, 0xbe, 0x32, 0xdb, 0xb0, 0x96, 0xd5, 0x10, 0x20, 0x17, 0xba, 0xc6, 0x55, 0x14, 0xd1, 0x5d,
-	0xe4, 0xc2, 0xfc, 0x10, 0x1a, 0x24, 0x61, 0x23, 0x2a, 0x64, 0x65, 0xeb, 0x77, 0xda, 0x8e, 0xea,
-	0xbb, 0x93, 0xf5, 0xdd, 0xd1, 0x7d, 0x77, 0x4e, 0x58, 0x4c, 0x8f, 0x97, 0x9f, 0x3e, 0xef, 0x2e,
-	0xf4, 0x75, 0xb8, 0xf9, 0x11, 0xc0, 0x20, 0x8d, 0x83, 0x10, 0xbd, 0x87, 0xa8, 0xea, 0xbe, 0xc6,
-	0xcd, 0x4d, 0x75, 0xcb, 0x3d, 0x44, 0x7b, 0x17, 0x76, 0xca, 0xb5, 0x17, 0xa2, 0x3e, 0x83, 0xed,
-	0x1e, 0x0f, 0xfb, 0x78, 0x3e, 0x42, 0x2e, 0x8e, 0x89, 0xf0, 0xa3, 0x19, 0x9b, 0x8d, 0x1a, 0x9b,
-	0x77, 0x60, 0x25, 0x40, 0xca, 0x12, 0xad, 0x4f, 0x5d, 0xd8, 0x6d, 0xd8, 0x9b, 0x4a, 0x56, 0xf0,
-	0xfc, 0x6c, 0x48, 0x22, 0xed, 0xa9, 0x22, 0xaa, 0xef, 0xf2, 0xdb, 0xb0, 0x25, 0xd8, 0x23, 0xa4,
-	0x9e, 0xcf, 0xa8, 0x48, 0x89, 0x9f, 0x7b, 0xb8, 0x29, 0xd1, 0x13, 0x0d, 0x9a, 0x37, 0x20, 0xeb,
-	0xaa, 0x97, 0xb5, 0x0e, 0x53, 0xdd, 0xe7, 0x26, 0x8a, 0xe8, 0x54, 0x02, 0x33, 0x22, 0x96, 0x6b,
-	0x44, 0x54, 0x46, 0x61, 0x65, 0x7a, 0x14, 0x94, 0x98, 0x72, 0xc1, 0x85, 0x98, 0x5f, 0x16, 0xa5,
-	0x98, 0xbb, 0x38, 0x64, 0x3c, 0x16, 0x27, 0x67, 0x24, 0x4e, 0xe4, 0xe0, 0x8d, 0x91, 0x0a, 0xaf,
-	0x2c, 0x09, 0x24, 0xf4, 0xf9, 0xff, 0xd1, 0x75, 0xaf, 0x32, 0x21, 0xcd, 0x63, 0x27, 0xeb, 0xe4,
-	0xdf, 0xcf, 0xbb, 0xb7, 0xc2, 0x58, 0x44, 0xa3, 0x81, 0xe3, 0xb3, 0xc4, 0xd5, 0xbb, 0x42, 0xfd,
-	0xbc, 0xcf, 0x83, 0x47, 0xae, 0x78, 0x32, 0x44, 0xee, 0x7c, 0x4a, 0x45, 0x31, 0x30, 0xb7, 0x61,
-	0x1b, 0x45, 0x84, 0x29, 0x8e, 0x12, 0x4f, 0x4f, 0xa9, 0xf2, 0x60, 0x2b, 0x87, 0x4f, 0xd5, 0xb4,
-	0xde, 0x86, 0x6d, 0x95, 0xc8, 0x4b, 0xd1, 0xc7, 0x78, 0x8c, 0xa9, 0xf6, 0x62, 0x4b, 0xc1, 0x7d,
-	0x8d, 0xce, 0x58, 0xda, 0xa8, 0xb1, 0x74, 0x0f, 0x56, 0xc5, 0xc4, 0x8b, 0x08, 0x8f, 0x5a, 0xab,
-	0xea, 0x99, 0x10, 0x93, 0xfb, 0x84, 0x47, 0xda, 0xcd, 0xb2, 0x63, 0x85, 0x9b, 0xbf, 0x2d, 0x96,
-	0x66, 0xb3, 0x17, 0x53, 0x81, 0xe9, 0xab, 0x6f, 0x69, 0x22, 0x75, 0xcc, 0x58, 0xaa, 0xe0, 0x97,
-	0x63, 0x69, 0x07, 0xf6, 0xeb, 0x6c, 0x2b, 0x7c, 0xfd, 0x53, 0x6d, 0xd6, 0xaf, 0x62, 0x11, 0x05,
-	0x29, 0x79, 0x7c, 0x4d, 0x4f, 0xbb, 0xb0, 0x3e, 0xc8, 0x86, 0x5d, 0x07, 0x2c, 0xaa, 0x00, 0x09,
-	0xcd, 0x33, 0x7d, 0xa9, 0xce, 0xf4, 0xeb, 0x3c, 0x80, 0x6f, 0x41, 0x53, 0x4c, 0x72, 0x2b, 0x95,
-	0x43, 0x6b, 0x62, 0xa2, 0x4d, 0x2c, 0xe9, 0x6e, 0x54, 0x74, 0xab, 0x1d, 0x5d, 0x91, 0x95, 0x6b,
-	0xbe, 0xf3, 0xeb, 0x2a, 0x2c, 0xf5, 0x78, 0x68, 0x9e, 0xc3, 0x66, 0xf5, 0x8d, 0x62, 0x39, 0xf9,
-	0xab, 0xd6, 0x99, 0x5e, 0xf0, 0x96, 0x3d, 0xff, 0xac, 0x30, 0xf3, 0xe6, 0x77, 0x7f, 0xfc, 0xfb,
-	0xe3, 0xa2, 0x65, 0xb7, 0xdc, 0xe2, 0x3d, 0x3e, 0x96, 0x81, 0x99, 0x0d, 0x92, 0x61, 0x00, 0xcd,
-	0xd2, 0xab, 0xa1, 0x92, 0xb2, 0xc0, 0xad, 0x4e, 0x3d, 0x5e, 0xd0, 0xdc, 0x90, 0x34, 0x7b, 0xf6,
-	0x9b, 0x57, 0x34, 0x99, 0x3d, 0x9e, 0x60, 0x1e, 0x8a, 0xc8, 0x4c, 0x60, 0xa3, 0xb2, 0xaa, 0xdb,
-	0x95, 0x74, 0xe5, 0x23, 0xeb, 0x60, 0xee, 0x51, 0x41, 0xd6, 0x95, 0x64, 0x6d, 0x7b, 0xef, 0x8a,
-	0x2c, 0x55, 0x71, 0x9e, 0xec, 0x77, 0x46, 0x57, 0x59, 0xd8, 0x55, 0xba, 0xf2, 0xd1, 0x14, 0x5d,
-	0xed, 0xd6, 0xac, 0xa1, 0xd3, 0xde, 0x5d, 0xd1, 0x55, 0x56, 0x6a, 0x95, 0xae, 0x7c, 0x34, 0x45,
-	0x57, 0xbb, 0x56, 0x6a, 0xe8, 0x02, 0x15, 0xe7, 0xf9, 0x32, 0xfd, 0x39, 0x6c, 0x56, 0x9f, 0x8d,
-	0xea, 0x8c, 0x54, 0xce, 0xa6, 0x66, 0xa4, 0x76, 0xf8, 0xea, 0x66, 0xe4, 0xb1, 0x0e, 0xd4, 0x94,
-	0xdf, 0xc0, 0xeb, 0xb3, 0x6b, 0xae, 0x6e, 0x26, 0x4a, 0xe7, 0xd6, 0xad, 0x17, 0x9f, 0xbf, 0x88,
-	0x3e, 0x9f, 0x1d, 0xb5, 0x76, 0xcc, 0x9f, 0x0c, 0xd8, 0x9d, 0xf3, 0x39, 0x78, 0x38, 0x45, 0x52,
-	0x17, 0x64, 0xbd, 0x77, 0x8d, 0xa0, 0xa2, 0x9c, 0x77, 0x65, 0x39, 0x87, 0xf6, 0x41, 0x5e, 0x8e,
-	0x9b, 0x3d, 0x2d, 0xe5, 0x3b, 0xf2, 0xaf, 0xb4, 0xe3, 0xfb, 0x4f, 0x2f, 0x3a, 0xc6, 0xb3, 0x8b,
-	0x8e, 0xf1, 0xcf, 0x45, 0xc7, 0xf8, 0xe1, 0xb2, 0xb3, 0xf0, 0xec, 0xb2, 0xb3, 0xf0, 0xd7, 0x65,
-	0x67, 0xe1, 0x6b, 0xa7, 0xb4, 0xa2, 0x95, 0xe6, 0x07, 0x48, 0x12, 0x37, 0x89, 0x46, 0x03, 0xd7,
-	0x8f, 0x48, 0x4c, 0xdd, 0x89, 0x4e, 0x2f, 0xd7, 0xf5, 0xa0, 0x21, 0xbf, 0x7c, 0x3f, 0xf8, 0x2f,
-	0x00, 0x00, 0xff, 0xff, 0x00, 0xd7, 0x5e, 0x71, 0x6e, 0x0b, 0x00, 0x00,
+	// 1132 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0xd5, 0x57, 0xcd, 0x6f, 0xdc, 0x44,
+	0x14, 0xef, 0x6e, 0x36, 0x9b, 0xdd, 0x69, 0x3e, 0x60, 0x1a, 0x12, 0xaf, 0xd9, 0x6e, 0x1a, 0x87,
+	0x7e, 0x09, 0x61, 0x2b, 0xe5, 0xc0, 0x0d, 0x89, 0x24, 0x54, 0xad, 0xa0, 0x20, 0x6d, 0x02, 0x95,
+	0xb8, 0x58, 0xb3, 0xf6, 0xd4, 0x6b, 0x75, 0x3d, 0xb3, 0xb5, 0x67, 0xb7, 0xc9, 0x01, 0x0e, 0x9c,
+	0x90, 0xb8, 0x20, 0x21, 0xfe, 0x0e, 0xfe, 0x8d, 0x9e, 0x50, 0x25, 0x84, 0x54, 0x71, 0xa8, 0xf8,
+	0x12, 0x7f, 0x07, 0xe3, 0x99, 0xb1, 0x77, 0xec, 0xf5, 0x46, 0x41, 0x42, 0x42, 0x3d, 0x58, 0xd9,
+	0x79, 0xef, 0x79, 0x7e, 0xef, 0xfd, 0xde, 0x97, 0x03, 0xae, 0x8c, 0x71, 0x10, 0x9c, 0x39, 0xd3,
+	0x7d, 0x27, 0x4a, 0x82, 0xc4, 0x1e, 0xc7, 0x94, 0x51, 0xd8, 0x12, 0x42, 0x7b, 0xba, 0x6f, 0xf6,
+	0x3c, 0x9a, 0x44, 0x34, 0x71, 0x06, 0x28, 0xc1, 0xdc, 0x68, 0x80, 0x19, 0xda, 0x77, 0x3c, 0x1a,
+	0x12, 0x69, 0x69, 0x6e, 0x06, 0x34, 0xa0, 0xe2, 0xa7, 0x93, 0xfe, 0x52, 0xd2, 0x6e, 0x40, 0x69,
+	0x30, 0xc2, 0x0e, 0x1a, 0x87, 0x0e, 0x22, 0x84, 0x32, 0xc4, 0x42, 0x4a, 0xd4, 0xed, 0xd6, 0x57,
+	0xa0, 0xf3, 0x20, 0x09, 0x8e, 0x31, 0xfb, 0x34, 0xf6, 0x86, 0x38, 0x61, 0x31, 0x62, 0x34, 0xfe,
+	0xc0, 0xf7, 0x63, 0x9c, 0x24, 0xb0, 0x0b, 0xda, 0x53, 0x34, 0x0a, 0xfd, 0x54, 0x66, 0xd4, 0xae,
+	0xd5, 0x6e, 0xb5, 0xfb, 0x33, 0x01, 0xb4, 0xc0, 0x2a, 0xd5, 0x5e, 0x32, 0xea, 0xc2, 0xa0, 0x20,
+	0x83, 0x3b, 0xe0, 0x32, 0x66, 0x43, 0x17, 0xc9, 0x0b, 0x8d, 0x25, 0x61, 0x02, 0xb8, 0x48, 0x41,
+	0x58, 0x7b, 0x60, 0x77, 0x21, 0x7e, 0x1f, 0x27, 0x63, 0xee, 0x29, 0xb6, 0xbe, 0xad, 0x81, 0xd7,
+	0xb8, 0xd5, 0xe7, 0x68, 0x94, 0x60, 0x76, 0x48, 0xc9, 0xa3, 0x30, 0x8e, 0xe0, 0x26, 0x58, 0x26,
+	0x94, 0x78, 0x58, 0x38, 0xd6, 0xe8, 0xcb, 0xc3, 0x7f, 0xe2, 0x54, 0x1a, 0x77, 0x12, 0x06, 0x04,
+	0xb1, 0x49, 0x8c, 0x8d, 0x86, 0x8c, 0x3b, 0x17, 0x58, 0x26, 0x30, 0xca, 0xce, 0xe4, 0x9e, 0xfe,
+	0x54, 0x03, 0xab, 0x22, 0x1e, 0xe2, 0x9f, 0xd0, 0x0f, 0xd9, 0x10, 0x6e, 0x81, 0x66, 0xc2, 0x0f,
+	0x38, 0xe3, 0x4f, 0x9d, 0x60, 0x07, 0xb4, 0x52, 0x1f, 0x7c, 0xee, 0x94, 0xf2, 0x71, 0x85, 0x9f,
+	0x8f, 0xf8, 0x11, 0xbe, 0x07, 0x9a, 0x28, 0xa2, 0x13, 0xc2, 0x84, 0x67, 0x97, 0xef, 0x74, 0x6c,
+	0x99, 0x77, 0x3b, 0xcd, 0xbb, 0xad, 0xf2, 0x6e, 0x1f, 0xf2, 0xbc, 0x1f, 0x34, 0x9e, 0xbd, 0xdc,
+	0xb9, 0xd4, 0x57, 0xe6, 0xf0, 0x7d, 0x00, 0x06, 0x71, 0xe8, 0x07, 0xd8, 0x7d, 0x84, 0xa5, 0xdf,
+	0x17, 0x78, 0xb9, 0x2d, 0x5f, 0xb9, 0x8b, 0x31, 0x84, 0xa0, 0x11, 0xe1, 0x88, 0x1a, 0xcb, 0xc2,
+	0x1f, 0xf1, 0xdb, 0xda, 0x02, 0x9b, 0x7a, 0x3c, 0x79, 0xa0, 0x1f, 0x81, 0x0d, 0x2e, 0xef, 0xe3,
+	0x27, 0x13, 0xee, 0xf2, 0x01, 0x62, 0xde, 0x70, 0x8e, 0xfa, 0x5a, 0x05, 0xf5, 0x3c, 0x69, 0x3e,
+	0x26, 0x34, 0x52, 0x31, 0xcb, 0x83, 0xd5, 0x01, 0xdb, 0xa5, 0xcb, 0x72, 0x9c, 0x1f, 0x6b, 0x02,
+	0x48, 0xf1, 0x2c, 0x81, 0xaa, 0x33, 0x7f, 0x1d, 0xac, 0x33, 0xfa, 0x18, 0x13, 0xd7, 0xa3, 0x84,
+	0xa3, 0x79, 0x19, 0xaf, 0x6b, 0x42, 0x7a, 0xa8, 0x84, 0xf0, 0x2a, 0x48, 0x33, 0xed, 0xa6, 0xe9,
+	0xe4, 0x49, 0x91, 0xb9, 0x6f, 0x73, 0xc9, 0xb1, 0x10, 0xcc, 0x05, 0xd1, 0xa8, 0x08, 0xa2, 0x50,
+	0x1e, 0xcb, 0xe5, 0xf2, 0x90, 0xc1, 0xe8, 0x0e, 0xe7, 0xc1, 0xfc, 0x5e, 0x17, 0xc1, 0x1c, 0xe1,
+	0x31, 0x4d, 0x42, 0x76, 0x38, 0x42, 0x61, 0x24, 0x8a, 0x71, 0x8a, 0x09, 0x73, 0xf5, 0x90, 0x80,
+	0x10, 0x7d, 0xf2, 0x6f, 0xe2, 0xba, 0x5b, 0xa8, 0x9a, 0xf6, 0x81, 0x9d, 0x66, 0xf7, 0xd7, 0x97,
+	0x3b, 0x37, 0x82, 0x90, 0x0d, 0x27, 0x03, 0x5e, 0x06, 0x91, 0xa3, 0xe6, 0x87, 0xfc, 0xf3, 0x4e,
+	0xe2, 0x3f, 0x76, 0xd8, 0xd9, 0x18, 0x27, 0xf6, 0x7d, 0xc2, 0xf2, 0x22, 0xba, 0x09, 0x36, 0x38,
+	0x1b, 0x38, 0xc6, 0x93, 0xc8, 0x55, 0x95, 0x2b, 0x39, 0x58, 0xcf, 0xc4, 0xc7, 0xb2, 0x82, 0xb9,
+	0xa1, 0xbc, 0xc8, 0x8d, 0xb1, 0x87, 0xc3, 0x29, 0x37, 0x94, 0x5c, 0xac, 0x4b, 0x71, 0x5f, 0x49,
+	0xe7, 0x28, 0x6d, 0x56, 0x50, 0xba, 0x0d, 0x56, 0xd8, 0xa9, 0x3b, 0x44, 0xc9, 0xd0, 0x58, 0x91,
+	0x7d, 0xc2, 0x4e, 0xef, 0xf1, 0x13, 0x74, 0xc0, 0x95, 0x90, 0x88, 0x99, 0x23, 0x7a, 0x25, 0x24,
+	0x62, 0x7a, 0x19, 0x2d, 0x6e, 0xd4, 0xea, 0x43, 0xa5, 0x3a, 0x9a, 0x69, 0x14, 0xfd, 0x3a, 0xc5,
+	0x39, 0xfd, 0x7f, 0xd7, 0xb5, 0x62, 0x7e, 0x10, 0x12, 0x86, 0xe3, 0x57, 0x3f, 0x07, 0x91, 0x88,
+	0x63, 0x2e, 0x07, 0x52, 0xfc, 0x3f, 0xe5, 0xa0, 0x07, 0xba, 0x55, 0x3c, 0xe7, 0x89, 0xf8, 0x45,
+	0xce, 0xf3, 0x87, 0x9c, 0x15, 0x3f, 0x46, 0x4f, 0x2f, 0x98, 0x04, 0x6e, 0x30, 0x48, 0xdb, 0x49,
+	0x19, 0xd4, 0xa5, 0x81, 0x10, 0x2d, 0xca, 0xd2, 0x52, 0x55, 0x96, 0x2e, 0xd2, 0xe2, 0x6f, 0x82,
+	0x36, 0xe7, 0x42, 0x71, 0x2f, 0x29, 0x6d, 0xb1, 0x53, 0xc5, 0xba, 0x46, 0x54, 0x53, 0x27, 0x4a,
+	0x6d, 0x86, 0x42, 0x58, 0x79, 0xcc, 0xc7, 0x00, 0xa6, 0x63, 0x01, 0x71, 0x47, 0x47, 0xb3, 0xf5,
+	0x90, 0xba, 0x1c, 0x23, 0x92, 0x70, 0xbf, 0x38, 0x71, 0x6e, 0xe8, 0xab, 0xb8, 0xd7, 0x34, 0xe9,
+	0x7d, 0x5f, 0xdb, 0x22, 0x75, 0x7d, 0x8b, 0x58, 0x5d, 0x60, 0xce, 0x5f, 0x9a, 0x43, 0x7e, 0x53,
+	0x53, 0x43, 0x7a, 0x84, 0xce, 0x44, 0x25, 0x8c, 0x19, 0x34, 0xc0, 0x4a, 0x9c, 0x9e, 0xf3, 0x85,
+	0x94, 0x1d, 0x67, 0x53, 0xb5, 0x7e, 0xfe, 0x54, 0xad, 0xe4, 0xb4, 0x27, 0x57, 0x6a, 0x46, 0x4b,
+	0x23, 0x1f, 0xab, 0x27, 0x92, 0x99, 0x6c, 0xc2, 0xcf, 0x3c, 0xc9, 0xbc, 0xbc, 0xf3, 0xa2, 0x05,
+	0x96, 0xb8, 0x0e, 0x3e, 0x01, 0x6b, 0xc5, 0x05, 0x6f, 0xda, 0xd9, 0x97, 0x8f, 0x5d, 0xde, 0xb7,
+	0xa6, 0xb5, 0x58, 0x97, 0x87, 0x7f, 0xed, 0xeb, 0x9f, 0xff, 0xfa, 0xbe, 0x6e, 0x5a, 0x86, 0x93,
+	0x7f, 0x56, 0x4d, 0x85, 0x61, 0x1a, 0x8b, 0x40, 0x18, 0x80, 0xb6, 0xb6, 0xa9, 0x0b, 0x57, 0xe6,
+	0x72, 0xb3, 0x57, 0x2d, 0xcf, 0x61, 0xae, 0x0a, 0x98, 0x6d, 0xeb, 0x8d, 0x19, 0x4c, 0x9a, 0x1d,
+	0x97, 0x51, 0x97, 0xc7, 0x0f, 0x23, 0xb0, 0x5a, 0xd8, 0x92, 0x9d, 0xc2, 0x75, 0xba, 0xca, 0xdc,
+	0x5d, 0xa8, 0xca, 0xc1, 0x76, 0x04, 0x58, 0xc7, 0xda, 0x9e, 0x81, 0xc5, 0xd2, 0xce, 0x15, 0x8d,
+	0x90, 0xc2, 0x15, 0x76, 0x65, 0x11, 0x4e, 0x57, 0x95, 0xe0, 0x2a, 0x17, 0x56, 0x05, 0x9c, 0xe2,
+	0x6e, 0x06, 0x57, 0xd8, 0x66, 0x45, 0x38, 0x5d, 0x55, 0x82, 0xab, 0x1c, 0xd0, 0x15, 0x70, 0xbe,
+	0xb4, 0x73, 0x3d, 0x71, 0x3d, 0xaf, 0x91, 0xe2, 0xd0, 0x28, 0xd6, 0x48, 0x41, 0x57, 0xaa, 0x91,
+	0xea, 0xae, 0xac, 0xa8, 0x91, 0xa7, 0xca, 0x50, 0x41, 0x7e, 0x09, 0x5e, 0x9f, 0x5f, 0x18, 0x55,
+	0x35, 0xa1, 0xe9, 0xcd, 0x1b, 0xe7, 0xeb, 0xcf, 0x83, 0xcf, 0x6a, 0x47, 0x0e, 0x70, 0xf8, 0x43,
+	0x0d, 0x6c, 0x2d, 0xf8, 0x3a, 0xdf, 0x2b, 0x81, 0x54, 0x19, 0x99, 0x6f, 0x5f, 0xc0, 0x28, 0x77,
+	0xe7, 0xb6, 0x70, 0x67, 0xcf, 0xda, 0xcd, 0xdc, 0x71, 0xd2, 0x6e, 0xd1, 0xdf, 0xc8, 0x3e, 0x9a,
+	0xe1, 0x67, 0x60, 0xa3, 0x3c, 0xcb, 0xba, 0xc5, 0x7a, 0x2a, 0x6a, 0xcd, 0xb7, 0xce, 0xd3, 0x66,
+	0x1e, 0xc0, 0x8f, 0xd3, 0x6e, 0xd1, 0xc6, 0x55, 0xb9, 0x5b, 0x66, 0xaa, 0xb9, 0x6e, 0x99, 0x1f,
+	0x2d, 0x07, 0xf7, 0x9e, 0xfd, 0xd1, 0xab, 0x3d, 0xe7, 0xcf, 0x6f, 0xfc, 0xf9, 0xee, 0xcf, 0xde,
+	0xa5, 0xe7, 0xfc, 0x79, 0xc1, 0x9f, 0x2f, 0x6c, 0x6d, 0x23, 0xcb, 0xc4, 0x9c, 0x60, 0x14, 0x39,
+	0x11, 0x97, 0x39, 0xde, 0x10, 0x85, 0xc4, 0x39, 0x55, 0x1c, 0x88, 0xed, 0x3c, 0x68, 0x8a, 0xff,
+	0x96, 0xde, 0xfd, 0x07, 0xc2, 0xc4, 0xc9, 0x68, 0xa2, 0x0d, 0x00, 0x00,
 }
 
 // Reference imports to suppress errors if they are not otherwise used.
@@ -1068,6 +1319,8 @@ type MsgClient interface {
 	WithdrawClaim(ctx context.Context, in *MsgWithdrawClaim, opts ...grpc.CallOption) (*MsgWithdrawClaimResponse, error)
 	SendToMinterClaim(ctx context.Context, in *MsgSendToMinterClaim, opts ...grpc.CallOption) (*MsgSendToMinterClaimResponse, error)
 	SetOrchestratorAddress(ctx context.Context, in *MsgSetOrchestratorAddress, opts ...grpc.CallOption) (*MsgSetOrchestratorAddressResponse, error)
+	CancelSendToEth(ctx context.Context, in *MsgCancelSendToEth, opts ...grpc.CallOption) (*MsgCancelSendToEthResponse, error)
+	RelayReceipt(ctx context.Context, in *MsgRelayReceipt, opts ...grpc.CallOption) (*MsgRelayReceiptResponse, error)
 }
 
 type msgClient struct {
@@ -1150,6 +1403,24 @@ func (c *msgClient) SetOrchestratorAddress(ctx context.Context, in *MsgSetOrches
 	return out, nil
 }
 
+func (c *msgClient) CancelSendToEth(ctx context.Context, in *MsgCancelSendToEth, opts ...grpc.CallOption) (*MsgCancelSendToEthResponse, error) {
+	out := new(MsgCancelSendToEthResponse)
+	err := c.cc.Invoke(ctx, "/peggy.v1.Msg/CancelSendToEth", in, out, opts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
+func (c *msgClient) RelayReceipt(ctx context.Context, in *MsgRelayReceipt, opts ...grpc.CallOption) (*MsgRelayReceiptResponse, error) {
+	out := new(MsgRelayReceiptResponse)
+	err := c.cc.Invoke(ctx, "/peggy.v1.Msg/RelayReceipt", in, out, opts...)
+	if err != nil {
+		return nil, err
+	}
+	return out, nil
+}
+
 // MsgServer is the server API for Msg service.
 type MsgServer interface {
 	ValsetConfirm(context.Context, *MsgValsetConfirm) (*MsgValsetConfirmResponse, error)
@@ -1160,6 +1431,8 @@ type MsgServer interface {
 	WithdrawClaim(context.Context, *MsgWithdrawClaim) (*MsgWithdrawClaimResponse, error)
 	SendToMinterClaim(context.Context, *MsgSendToMinterClaim) (*MsgSendToMinterClaimResponse, error)
 	SetOrchestratorAddress(context.Context, *MsgSetOrchestratorAddress) (*MsgSetOrchestratorAddressResponse, error)
+	CancelSendToEth(context.Context, *MsgCancelSendToEth) (*MsgCancelSendToEthResponse, error)
+	RelayReceipt(context.Context, *MsgRelayReceipt) (*MsgRelayReceiptResponse, error)
 }
 
 // UnimplementedMsgServer can be embedded to have forward compatible implementations.
@@ -1190,6 +1463,12 @@ func (*UnimplementedMsgServer) SendToMinterClaim(ctx context.Context, req *MsgSe
 func (*UnimplementedMsgServer) SetOrchestratorAddress(ctx context.Context, req *MsgSetOrchestratorAddress) (*MsgSetOrchestratorAddressResponse, error) {
 	return nil, status.Errorf(codes.Unimplemented, "method SetOrchestratorAddress not implemented")
 }
+func (*UnimplementedMsgServer) CancelSendToEth(ctx context.Context, req *MsgCancelSendToEth) (*MsgCancelSendToEthResponse, error) {
+	return nil, status.Errorf(codes.Unimplemented, "method CancelSendToEth not implemented")
+}
+func (*UnimplementedMsgServer) RelayReceipt(ctx context.Context, req *MsgRelayReceipt) (*MsgRelayReceiptResponse, error) {
+	return nil, status.Errorf(codes.Unimplemented, "method RelayReceipt not implemented")
+}
 
 func RegisterMsgServer(s grpc1.Server, srv MsgServer) {
 	s.RegisterService(&_Msg_serviceDesc, srv)
@@ -1339,6 +1618,42 @@ func _Msg_SetOrchestratorAddress_Handler(srv interface{}, ctx context.Context, d
 	return interceptor(ctx, in, info, handler)
 }
 
+func _Msg_CancelSendToEth_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(MsgCancelSendToEth)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(MsgServer).CancelSendToEth(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: "/peggy.v1.Msg/CancelSendToEth",
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(MsgServer).CancelSendToEth(ctx, req.(*MsgCancelSendToEth))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
+func _Msg_RelayReceipt_Handler(srv interface{}, ctx context.Context, dec func(interface{}) error, interceptor grpc.UnaryServerInterceptor) (interface{}, error) {
+	in := new(MsgRelayReceipt)
+	if err := dec(in); err != nil {
+		return nil, err
+	}
+	if interceptor == nil {
+		return srv.(MsgServer).RelayReceipt(ctx, in)
+	}
+	info := &grpc.UnaryServerInfo{
+		Server:     srv,
+		FullMethod: "/peggy.v1.Msg/RelayReceipt",
+	}
+	handler := func(ctx context.Context, req interface{}) (interface{}, error) {
+		return srv.(MsgServer).RelayReceipt(ctx, req.(*MsgRelayReceipt))
+	}
+	return interceptor(ctx, in, info, handler)
+}
+
 var _Msg_serviceDesc = grpc.ServiceDesc{
 	ServiceName: "peggy.v1.Msg",
 	HandlerType: (*MsgServer)(nil),
@@ -1375,6 +1690,14 @@ var _Msg_serviceDesc = grpc.ServiceDesc{
 			MethodName: "SetOrchestratorAddress",
 			Handler:    _Msg_SetOrchestratorAddress_Handler,
 		},
+		{
+			MethodName: "CancelSendToEth",
+			Handler:    _Msg_CancelSendToEth_Handler,
+		},
+		{
+			MethodName: "RelayReceipt",
+			Handler:    _Msg_RelayReceipt_Handler,
+		},
 	},
 	Streams:  []grpc.StreamDesc{},
 	Metadata: "peggy/v1/msgs.proto",
@@ -1539,6 +1862,13 @@ func (m *MsgSendToEth) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if len(m.Memo) > 0 {
+		i -= len(m.Memo)
+		copy(dAtA[i:], m.Memo)
+		i = encodeVarintMsgs(dAtA, i, uint64(len(m.Memo)))
+		i--
+		dAtA[i] = 0x2a
+	}
 	{
 		size, err := m.BridgeFee.MarshalToSizedBuffer(dAtA[:i])
 		if err != nil {
@@ -1758,6 +2088,16 @@ func (m *MsgDepositClaim) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if m.InvalidDestination {
+		i--
+		if m.InvalidDestination {
+			dAtA[i] = 1
+		} else {
+			dAtA[i] = 0
+		}
+		i--
+		dAtA[i] = 0x40
+	}
 	if len(m.TxHash) > 0 {
 		i -= len(m.TxHash)
 		copy(dAtA[i:], m.TxHash)
@@ -1854,6 +2194,16 @@ func (m *MsgSendToMinterClaim) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if m.InvalidDestination {
+		i--
+		if m.InvalidDestination {
+			dAtA[i] = 1
+		} else {
+			dAtA[i] = 0
+		}
+		i--
+		dAtA[i] = 0x40
+	}
 	if len(m.TxHash) > 0 {
 		i -= len(m.TxHash)
 		copy(dAtA[i:], m.TxHash)
@@ -2014,56 +2364,186 @@ func (m *MsgWithdrawClaimResponse) MarshalToSizedBuffer(dAtA []byte) (int, error
 	return len(dAtA) - i, nil
 }
 
-func encodeVarintMsgs(dAtA []byte, offset int, v uint64) int {
-	offset -= sovMsgs(v)
-	base := offset
-	for v >= 1<<7 {
-		dAtA[offset] = uint8(v&0x7f | 0x80)
-		v >>= 7
-		offset++
+func (m *MsgCancelSendToEth) Marshal() (dAtA []byte, err error) {
+	size := m.Size()
+	dAtA = make([]byte, size)
+	n, err := m.MarshalToSizedBuffer(dAtA[:size])
+	if err != nil {
+		return nil, err
 	}
-	dAtA[offset] = uint8(v)
-	return base
+	return dAtA[:n], nil
 }
-func (m *MsgSetOrchestratorAddress) Size() (n int) {
-	if m == nil {
-		return 0
-	}
+
+func (m *MsgCancelSendToEth) MarshalTo(dAtA []byte) (int, error) {
+	size := m.Size()
+	return m.MarshalToSizedBuffer(dAtA[:size])
+}
+
+func (m *MsgCancelSendToEth) MarshalToSizedBuffer(dAtA []byte) (int, error) {
+	i := len(dAtA)
+	_ = i
 	var l int
 	_ = l
-	l = len(m.Validator)
-	if l > 0 {
-		n += 1 + l + sovMsgs(uint64(l))
-	}
-	l = len(m.Orchestrator)
-	if l > 0 {
-		n += 1 + l + sovMsgs(uint64(l))
+	if len(m.Sender) > 0 {
+		i -= len(m.Sender)
+		copy(dAtA[i:], m.Sender)
+		i = encodeVarintMsgs(dAtA, i, uint64(len(m.Sender)))
+		i--
+		dAtA[i] = 0x12
 	}
-	l = len(m.EthAddress)
-	if l > 0 {
-		n += 1 + l + sovMsgs(uint64(l))
+	if m.TransactionId != 0 {
+		i = encodeVarintMsgs(dAtA, i, uint64(m.TransactionId))
+		i--
+		dAtA[i] = 0x8
 	}
-	return n
+	return len(dAtA) - i, nil
 }
 
-func (m *MsgSetOrchestratorAddressResponse) Size() (n int) {
-	if m == nil {
-		return 0
+func (m *MsgCancelSendToEthResponse) Marshal() (dAtA []byte, err error) {
+	size := m.Size()
+	dAtA = make([]byte, size)
+	n, err := m.MarshalToSizedBuffer(dAtA[:size])
+	if err != nil {
+		return nil, err
 	}
-	var l int
-	_ = l
-	return n
+	return dAtA[:n], nil
 }
 
-func (m *MsgValsetConfirm) Size() (n int) {
-	if m == nil {
-		return 0
-	}
+func (m *MsgCancelSendToEthResponse) MarshalTo(dAtA []byte) (int, error) {
+	size := m.Size()
+	return m.MarshalToSizedBuffer(dAtA[:size])
+}
+
+func (m *MsgCancelSendToEthResponse) MarshalToSizedBuffer(dAtA []byte) (int, error) {
+	i := len(dAtA)
+	_ = i
 	var l int
 	_ = l
-	if m.Nonce != 0 {
-		n += 1 + sovMsgs(uint64(m.Nonce))
-	}
+	return len(dAtA) - i, nil
+}
+
+func (m *MsgRelayReceipt) Marshal() (dAtA []byte, err error) {
+	size := m.Size()
+	dAtA = make([]byte, size)
+	n, err := m.MarshalToSizedBuffer(dAtA[:size])
+	if err != nil {
+		return nil, err
+	}
+	return dAtA[:n], nil
+}
+
+func (m *MsgRelayReceipt) MarshalTo(dAtA []byte) (int, error) {
+	size := m.Size()
+	return m.MarshalToSizedBuffer(dAtA[:size])
+}
+
+func (m *MsgRelayReceipt) MarshalToSizedBuffer(dAtA []byte) (int, error) {
+	i := len(dAtA)
+	_ = i
+	var l int
+	_ = l
+	if len(m.EthTxHash) > 0 {
+		i -= len(m.EthTxHash)
+		copy(dAtA[i:], m.EthTxHash)
+		i = encodeVarintMsgs(dAtA, i, uint64(len(m.EthTxHash)))
+		i--
+		dAtA[i] = 0x22
+	}
+	if len(m.TokenContract) > 0 {
+		i -= len(m.TokenContract)
+		copy(dAtA[i:], m.TokenContract)
+		i = encodeVarintMsgs(dAtA, i, uint64(len(m.TokenContract)))
+		i--
+		dAtA[i] = 0x1a
+	}
+	if m.Nonce != 0 {
+		i = encodeVarintMsgs(dAtA, i, uint64(m.Nonce))
+		i--
+		dAtA[i] = 0x10
+	}
+	if len(m.Relayer) > 0 {
+		i -= len(m.Relayer)
+		copy(dAtA[i:], m.Relayer)
+		i = encodeVarintMsgs(dAtA, i, uint64(len(m.Relayer)))
+		i--
+		dAtA[i] = 0xa
+	}
+	return len(dAtA) - i, nil
+}
+
+func (m *MsgRelayReceiptResponse) Marshal() (dAtA []byte, err error) {
+	size := m.Size()
+	dAtA = make([]byte, size)
+	n, err := m.MarshalToSizedBuffer(dAtA[:size])
+	if err != nil {
+		return nil, err
+	}
+	return dAtA[:n], nil
+}
+
+func (m *MsgRelayReceiptResponse) MarshalTo(dAtA []byte) (int, error) {
+	size := m.Size()
+	return m.MarshalToSizedBuffer(dAtA[:size])
+}
+
+func (m *MsgRelayReceiptResponse) MarshalToSizedBuffer(dAtA []byte) (int, error) {
+	i := len(dAtA)
+	_ = i
+	var l int
+	_ = l
+	return len(dAtA) - i, nil
+}
+
+func encodeVarintMsgs(dAtA []byte, offset int, v uint64) int {
+	offset -= sovMsgs(v)
+	base := offset
+	for v >= 1<<7 {
+		dAtA[offset] = uint8(v&0x7f | 0x80)
+		v >>= 7
+		offset++
+	}
+	dAtA[offset] = uint8(v)
+	return base
+}
+func (m *MsgSetOrchestratorAddress) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	l = len(m.Validator)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	l = len(m.Orchestrator)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	l = len(m.EthAddress)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	return n
+}
+
+func (m *MsgSetOrchestratorAddressResponse) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	return n
+}
+
+func (m *MsgValsetConfirm) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	if m.Nonce != 0 {
+		n += 1 + sovMsgs(uint64(m.Nonce))
+	}
 	l = len(m.Orchestrator)
 	if l > 0 {
 		n += 1 + l + sovMsgs(uint64(l))
@@ -2106,6 +2586,10 @@ func (m *MsgSendToEth) Size() (n int) {
 	n += 1 + l + sovMsgs(uint64(l))
 	l = m.BridgeFee.Size()
 	n += 1 + l + sovMsgs(uint64(l))
+	l = len(m.Memo)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
 	return n
 }
 
@@ -2212,6 +2696,9 @@ func (m *MsgDepositClaim) Size() (n int) {
 	if l > 0 {
 		n += 1 + l + sovMsgs(uint64(l))
 	}
+	if m.InvalidDestination {
+		n += 2
+	}
 	return n
 }
 
@@ -2255,6 +2742,9 @@ func (m *MsgSendToMinterClaim) Size() (n int) {
 	if l > 0 {
 		n += 1 + l + sovMsgs(uint64(l))
 	}
+	if m.InvalidDestination {
+		n += 2
+	}
 	return n
 }
 
@@ -2307,6 +2797,64 @@ func (m *MsgWithdrawClaimResponse) Size() (n int) {
 	return n
 }
 
+func (m *MsgCancelSendToEth) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	if m.TransactionId != 0 {
+		n += 1 + sovMsgs(uint64(m.TransactionId))
+	}
+	l = len(m.Sender)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	return n
+}
+
+func (m *MsgCancelSendToEthResponse) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	return n
+}
+
+func (m *MsgRelayReceipt) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	l = len(m.Relayer)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	if m.Nonce != 0 {
+		n += 1 + sovMsgs(uint64(m.Nonce))
+	}
+	l = len(m.TokenContract)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	l = len(m.EthTxHash)
+	if l > 0 {
+		n += 1 + l + sovMsgs(uint64(l))
+	}
+	return n
+}
+
+func (m *MsgRelayReceiptResponse) Size() (n int) {
+	if m == nil {
+		return 0
+	}
+	var l int
+	_ = l
+	return n
+}
+
 func sovMsgs(x uint64) (n int) {
 	return (math_bits.Len64(x|1) + 6) / 7
 }
@@ -2895,6 +3443,38 @@ func (m *MsgSendToEth) Unmarshal(dAtA []byte) error {
 				return err
 			}
 			iNdEx = postIndex
+		case 5:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Memo", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Memo = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
 		default:
 			iNdEx = preIndex
 			skippy, err := skipMsgs(dAtA[iNdEx:])
@@ -3637,6 +4217,26 @@ func (m *MsgDepositClaim) Unmarshal(dAtA []byte) error {
 			}
 			m.TxHash = string(dAtA[iNdEx:postIndex])
 			iNdEx = postIndex
+		case 8:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field InvalidDestination", wireType)
+			}
+			var v int
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				v |= int(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			m.InvalidDestination = bool(v != 0)
 		default:
 			iNdEx = preIndex
 			skippy, err := skipMsgs(dAtA[iNdEx:])
@@ -3956,6 +4556,26 @@ func (m *MsgSendToMinterClaim) Unmarshal(dAtA []byte) error {
 			}
 			m.TxHash = string(dAtA[iNdEx:postIndex])
 			iNdEx = postIndex
+		case 8:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field InvalidDestination", wireType)
+			}
+			var v int
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				v |= int(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			m.InvalidDestination = bool(v != 0)
 		default:
 			iNdEx = preIndex
 			skippy, err := skipMsgs(dAtA[iNdEx:])
@@ -4305,6 +4925,384 @@ func (m *MsgWithdrawClaimResponse) Unmarshal(dAtA []byte) error {
 	}
 	return nil
 }
+func (m *MsgCancelSendToEth) Unmarshal(dAtA []byte) error {
+	l := len(dAtA)
+	iNdEx := 0
+	for iNdEx < l {
+		preIndex := iNdEx
+		var wire uint64
+		for shift := uint(0); ; shift += 7 {
+			if shift >= 64 {
+				return ErrIntOverflowMsgs
+			}
+			if iNdEx >= l {
+				return io.ErrUnexpectedEOF
+			}
+			b := dAtA[iNdEx]
+			iNdEx++
+			wire |= uint64(b&0x7F) << shift
+			if b < 0x80 {
+				break
+			}
+		}
+		fieldNum := int32(wire >> 3)
+		wireType := int(wire & 0x7)
+		if wireType == 4 {
+			return fmt.Errorf("proto: MsgCancelSendToEth: wiretype end group for non-group")
+		}
+		if fieldNum <= 0 {
+			return fmt.Errorf("proto: MsgCancelSendToEth: illegal tag %d (wire type %d)", fieldNum, wire)
+		}
+		switch fieldNum {
+		case 1:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field TransactionId", wireType)
+			}
+			m.TransactionId = 0
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				m.TransactionId |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+		case 2:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Sender", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Sender = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
+		default:
+			iNdEx = preIndex
+			skippy, err := skipMsgs(dAtA[iNdEx:])
+			if err != nil {
+				return err
+			}
+			if skippy < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) > l {
+				return io.ErrUnexpectedEOF
+			}
+			iNdEx += skippy
+		}
+	}
+
+	if iNdEx > l {
+		return io.ErrUnexpectedEOF
+	}
+	return nil
+}
+func (m *MsgCancelSendToEthResponse) Unmarshal(dAtA []byte) error {
+	l := len(dAtA)
+	iNdEx := 0
+	for iNdEx < l {
+		preIndex := iNdEx
+		var wire uint64
+		for shift := uint(0); ; shift += 7 {
+			if shift >= 64 {
+				return ErrIntOverflowMsgs
+			}
+			if iNdEx >= l {
+				return io.ErrUnexpectedEOF
+			}
+			b := dAtA[iNdEx]
+			iNdEx++
+			wire |= uint64(b&0x7F) << shift
+			if b < 0x80 {
+				break
+			}
+		}
+		fieldNum := int32(wire >> 3)
+		wireType := int(wire & 0x7)
+		if wireType == 4 {
+			return fmt.Errorf("proto: MsgCancelSendToEthResponse: wiretype end group for non-group")
+		}
+		if fieldNum <= 0 {
+			return fmt.Errorf("proto: MsgCancelSendToEthResponse: illegal tag %d (wire type %d)", fieldNum, wire)
+		}
+		switch fieldNum {
+		default:
+			iNdEx = preIndex
+			skippy, err := skipMsgs(dAtA[iNdEx:])
+			if err != nil {
+				return err
+			}
+			if skippy < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) > l {
+				return io.ErrUnexpectedEOF
+			}
+			iNdEx += skippy
+		}
+	}
+
+	if iNdEx > l {
+		return io.ErrUnexpectedEOF
+	}
+	return nil
+}
+func (m *MsgRelayReceipt) Unmarshal(dAtA []byte) error {
+	l := len(dAtA)
+	iNdEx := 0
+	for iNdEx < l {
+		preIndex := iNdEx
+		var wire uint64
+		for shift := uint(0); ; shift += 7 {
+			if shift >= 64 {
+				return ErrIntOverflowMsgs
+			}
+			if iNdEx >= l {
+				return io.ErrUnexpectedEOF
+			}
+			b := dAtA[iNdEx]
+			iNdEx++
+			wire |= uint64(b&0x7F) << shift
+			if b < 0x80 {
+				break
+			}
+		}
+		fieldNum := int32(wire >> 3)
+		wireType := int(wire & 0x7)
+		if wireType == 4 {
+			return fmt.Errorf("proto: MsgRelayReceipt: wiretype end group for non-group")
+		}
+		if fieldNum <= 0 {
+			return fmt.Errorf("proto: MsgRelayReceipt: illegal tag %d (wire type %d)", fieldNum, wire)
+		}
+		switch fieldNum {
+		case 1:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Relayer", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Relayer = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
+		case 2:
+			if wireType != 0 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Nonce", wireType)
+			}
+			m.Nonce = 0
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				m.Nonce |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+		case 3:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field TokenContract", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.TokenContract = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
+		case 4:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field EthTxHash", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowMsgs
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.EthTxHash = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
+		default:
+			iNdEx = preIndex
+			skippy, err := skipMsgs(dAtA[iNdEx:])
+			if err != nil {
+				return err
+			}
+			if skippy < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) > l {
+				return io.ErrUnexpectedEOF
+			}
+			iNdEx += skippy
+		}
+	}
+
+	if iNdEx > l {
+		return io.ErrUnexpectedEOF
+	}
+	return nil
+}
+func (m *MsgRelayReceiptResponse) Unmarshal(dAtA []byte) error {
+	l := len(dAtA)
+	iNdEx := 0
+	for iNdEx < l {
+		preIndex := iNdEx
+		var wire uint64
+		for shift := uint(0); ; shift += 7 {
+			if shift >= 64 {
+				return ErrIntOverflowMsgs
+			}
+			if iNdEx >= l {
+				return io.ErrUnexpectedEOF
+			}
+			b := dAtA[iNdEx]
+			iNdEx++
+			wire |= uint64(b&0x7F) << shift
+			if b < 0x80 {
+				break
+			}
+		}
+		fieldNum := int32(wire >> 3)
+		wireType := int(wire & 0x7)
+		if wireType == 4 {
+			return fmt.Errorf("proto: MsgRelayReceiptResponse: wiretype end group for non-group")
+		}
+		if fieldNum <= 0 {
+			return fmt.Errorf("proto: MsgRelayReceiptResponse: illegal tag %d (wire type %d)", fieldNum, wire)
+		}
+		switch fieldNum {
+		default:
+			iNdEx = preIndex
+			skippy, err := skipMsgs(dAtA[iNdEx:])
+			if err != nil {
+				return err
+			}
+			if skippy < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) < 0 {
+				return ErrInvalidLengthMsgs
+			}
+			if (iNdEx + skippy) > l {
+				return io.ErrUnexpectedEOF
+			}
+			iNdEx += skippy
+		}
+	}
+
+	if iNdEx > l {
+		return io.ErrUnexpectedEOF
+	}
+	return nil
+}
 func skipMsgs(dAtA []byte) (n int, err error) {
 	l := len(dAtA)
 	iNdEx := 0
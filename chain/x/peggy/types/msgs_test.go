@@ -2,10 +2,13 @@ package types
 
 import (
 	"bytes"
+	"fmt"
+	"strings"
 	"testing"
 
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	"github.com/stretchr/testify/assert"
+	"github.com/tendermint/tendermint/crypto/tmhash"
 )
 
 func TestValidateMsgSetOrchestratorAddress(t *testing.T) {
@@ -73,3 +76,111 @@ func TestValidateMsgSetOrchestratorAddress(t *testing.T) {
 	}
 
 }
+
+func TestValidateMsgRelayReceipt(t *testing.T) {
+	var (
+		relayer sdk.AccAddress = bytes.Repeat([]byte{0x1}, sdk.AddrLen)
+		token                  = "0xb462864E395d88d6bc7C5dd5F3F5eb4cc2599255"
+		txHash                 = "0x" + strings.Repeat("ab", 32)
+	)
+	specs := map[string]struct {
+		msg    *MsgRelayReceipt
+		expErr bool
+	}{
+		"valset update":      {msg: NewMsgRelayReceipt(relayer, 5, "", txHash)},
+		"batch":              {msg: NewMsgRelayReceipt(relayer, 5, token, txHash)},
+		"empty relayer":      {msg: NewMsgRelayReceipt(nil, 5, token, txHash), expErr: true},
+		"zero nonce":         {msg: NewMsgRelayReceipt(relayer, 0, token, txHash), expErr: true},
+		"invalid token":      {msg: NewMsgRelayReceipt(relayer, 5, "invalid", txHash), expErr: true},
+		"empty tx hash":      {msg: NewMsgRelayReceipt(relayer, 5, token, ""), expErr: true},
+		"tx hash without 0x": {msg: NewMsgRelayReceipt(relayer, 5, token, strings.Repeat("ab", 32)), expErr: true},
+		"truncated tx hash":  {msg: NewMsgRelayReceipt(relayer, 5, token, txHash[:60]), expErr: true},
+	}
+	for name, spec := range specs {
+		t.Run(name, func(t *testing.T) {
+			err := spec.msg.ValidateBasic()
+			if spec.expErr {
+				assert.Error(t, err)
+				return
+			}
+			assert.NoError(t, err)
+		})
+	}
+}
+
+func TestDepositClaimHashInvalidDestination(t *testing.T) {
+	claim := MsgDepositClaim{
+		TokenContract:  "0xb462864E395d88d6bc7C5dd5F3F5eb4cc2599255",
+		EthereumSender: "0xc783df8a850f42e7F7e57013759C285caa701eB6",
+		CosmosReceiver: sdk.AccAddress(bytes.Repeat([]byte{0x1}, sdk.AddrLen)).String(),
+	}
+	refund := claim
+	refund.InvalidDestination = true
+	// orchestrators that disagree on the destination must not vote for the same claim
+	assert.NotEqual(t, claim.ClaimHash(), refund.ClaimHash())
+
+	// a refund claimed for a valid receiver never counts like an honest refund, whose hash is unchanged
+	refundHash := func(receiver string) []byte {
+		return tmhash.Sum([]byte(fmt.Sprintf("%s/%s/%s/refund/", claim.TokenContract, claim.EthereumSender, receiver)))
+	}
+	honest := claim
+	honest.CosmosReceiver = "cosmos1invalid"
+	honest.InvalidDestination = true
+	assert.Equal(t, refundHash(honest.CosmosReceiver), honest.ClaimHash())
+	assert.NotEqual(t, refundHash(claim.CosmosReceiver), refund.ClaimHash())
+}
+
+func TestValidateClaimDestination(t *testing.T) {
+	var (
+		tokenContract  = "0xb462864E395d88d6bc7C5dd5F3F5eb4cc2599255"
+		ethSender      = "0xc783df8a850f42e7F7e57013759C285caa701eB6"
+		cosmosReceiver = sdk.AccAddress(bytes.Repeat([]byte{0x1}, sdk.AddrLen)).String()
+		minterReceiver = "Mx7633980c000139dd3bd24a3f54e06474fa941e16"
+		orchestrator   = sdk.AccAddress(bytes.Repeat([]byte{0x2}, sdk.AddrLen)).String()
+	)
+	deposit := func(receiver string, invalidDestination bool) EthereumClaim {
+		return &MsgDepositClaim{
+			EventNonce:         1,
+			TokenContract:      tokenContract,
+			Amount:             sdk.NewInt(1),
+			EthereumSender:     ethSender,
+			CosmosReceiver:     receiver,
+			Orchestrator:       orchestrator,
+			InvalidDestination: invalidDestination,
+		}
+	}
+	sendToMinter := func(receiver string, invalidDestination bool) EthereumClaim {
+		return &MsgSendToMinterClaim{
+			EventNonce:         1,
+			TokenContract:      tokenContract,
+			Amount:             sdk.NewInt(1),
+			EthereumSender:     ethSender,
+			MinterReceiver:     receiver,
+			Orchestrator:       orchestrator,
+			InvalidDestination: invalidDestination,
+		}
+	}
+	specs := map[string]struct {
+		claim  EthereumClaim
+		expErr bool
+	}{
+		"deposit":                             {claim: deposit(cosmosReceiver, false)},
+		"deposit to an invalid receiver":      {claim: deposit("cosmos1invalid", false), expErr: true},
+		"refund of an invalid receiver":       {claim: deposit("cosmos1invalid", true)},
+		"refund of a valid receiver":          {claim: deposit(cosmosReceiver, true), expErr: true},
+		"send to minter":                      {claim: sendToMinter(minterReceiver, false)},
+		"send to an invalid minter receiver":  {claim: sendToMinter("Mxinvalid", false), expErr: true},
+		"refund of an invalid minter address": {claim: sendToMinter("Mxinvalid", true)},
+		"refund of a valid minter address":    {claim: sendToMinter(minterReceiver, true), expErr: true},
+	}
+	for name, spec := range specs {
+		t.Run(name, func(t *testing.T) {
+			err := spec.claim.ValidateBasic()
+			if spec.expErr {
+				assert.Error(t, err)
+				return
+			}
+			assert.NoError(t, err)
+		})
+	}
+}
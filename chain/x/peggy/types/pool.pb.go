@@ -34,6 +34,7 @@ type OutgoingTx struct {
 	Amount         types.Coin `protobuf:"bytes,5,opt,name=amount,proto3" json:"amount"`
 	BridgeFee      types.Coin `protobuf:"bytes,6,opt,name=bridge_fee,json=bridgeFee,proto3" json:"bridge_fee"`
 	TxHash         string     `protobuf:"bytes,7,opt,name=tx_hash,json=txHash,proto3" json:"tx_hash,omitempty"`
+	Memo           string     `protobuf:"bytes,8,opt,name=memo,proto3" json:"memo,omitempty"`
 }
 
 func (m *OutgoingTx) Reset()         { *m = OutgoingTx{} }
@@ -118,6 +119,13 @@ func (m *OutgoingTx) GetTxHash() string {
 	return ""
 }
 
+func (m *OutgoingTx) GetMemo() string {
+	if m != nil {
+		return m.Memo
+	}
+	return ""
+}
+
 // IDSet represents a set of IDs
 type IDSet struct {
 	Ids []uint64 `protobuf:"varint,1,rep,packed,name=ids,proto3" json:"ids,omitempty"`
@@ -171,31 +179,30 @@ func init() {
 func init() { proto.RegisterFile("peggy/v1/pool.proto", fileDescriptor_de0a859def4c189a) }
 
 var fileDescriptor_de0a859def4c189a = []byte{
-	// 369 bytes of a gzipped FileDescriptorProto
-	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x8c, 0x91, 0x31, 0x6f, 0xd4, 0x30,
-	0x1c, 0xc5, 0xcf, 0xcd, 0x35, 0xed, 0xb9, 0x12, 0x20, 0x83, 0x20, 0x2d, 0x52, 0x1a, 0x75, 0x21,
-	0x93, 0xad, 0xc0, 0xc0, 0x86, 0x44, 0x41, 0xa8, 0x0c, 0x08, 0x29, 0xdc, 0xc4, 0x12, 0x39, 0xf1,
-	0xbf, 0x8e, 0x25, 0x62, 0x47, 0xb6, 0x73, 0x4a, 0xbf, 0x05, 0x5f, 0x80, 0xef, 0xd3, 0xf1, 0x46,
-	0x26, 0x84, 0xee, 0xbe, 0x08, 0x4a, 0x7c, 0x12, 0x6b, 0xb7, 0xf7, 0xff, 0xbd, 0xf7, 0x1f, 0x9e,
-	0x1e, 0x7e, 0xda, 0x83, 0x94, 0x77, 0x6c, 0x53, 0xb0, 0xde, 0x98, 0x1f, 0xb4, 0xb7, 0xc6, 0x1b,
-	0x72, 0x3a, 0x43, 0xba, 0x29, 0x2e, 0xd2, 0xc6, 0xb8, 0xce, 0x38, 0x56, 0x73, 0x07, 0x6c, 0x53,
-	0xd4, 0xe0, 0x79, 0xc1, 0x1a, 0xa3, 0x74, 0x48, 0x5e, 0x3c, 0x93, 0x46, 0x9a, 0x59, 0xb2, 0x49,
-	0x05, 0x7a, 0xf5, 0xeb, 0x08, 0xe3, 0xaf, 0x83, 0x97, 0x46, 0x69, 0xb9, 0x1e, 0xc9, 0x73, 0x1c,
-	0x3b, 0xd0, 0x02, 0x6c, 0x82, 0x32, 0x94, 0xaf, 0xca, 0xc3, 0x45, 0x5e, 0xe2, 0x95, 0x00, 0xe7,
-	0x2b, 0x2e, 0x84, 0x4d, 0x8e, 0x66, 0xeb, 0x74, 0x02, 0xef, 0x85, 0xb0, 0xe4, 0x12, 0x9f, 0x59,
-	0xb8, 0x1d, 0xb4, 0x08, 0x76, 0x34, 0xdb, 0x38, 0xa0, 0x39, 0xf0, 0x0a, 0x3f, 0x86, 0xb1, 0x57,
-	0x96, 0x7b, 0x65, 0x74, 0xe5, 0x55, 0x07, 0xc9, 0x32, 0x43, 0x79, 0x54, 0x3e, 0xfa, 0x8f, 0xd7,
-	0xaa, 0x03, 0xf2, 0x16, 0xc7, 0xbc, 0x33, 0x83, 0xf6, 0xc9, 0x71, 0x86, 0xf2, 0xb3, 0xd7, 0xe7,
-	0x34, 0x94, 0xa2, 0x53, 0x29, 0x7a, 0x28, 0x45, 0x3f, 0x18, 0xa5, 0xaf, 0x97, 0xf7, 0x7f, 0x2e,
-	0x17, 0xe5, 0x21, 0x4e, 0xde, 0x61, 0x5c, 0x5b, 0x25, 0x24, 0x54, 0xb7, 0x00, 0x49, 0xfc, 0xb0,
-	0xe7, 0x55, 0x78, 0xf9, 0x04, 0x40, 0x5e, 0xe0, 0x13, 0x3f, 0x56, 0x2d, 0x77, 0x6d, 0x72, 0x12,
-	0x8a, 0xfb, 0xf1, 0x86, 0xbb, 0xf6, 0xea, 0x1c, 0x1f, 0x7f, 0xfe, 0xf8, 0x0d, 0x3c, 0x79, 0x82,
-	0x23, 0x25, 0x5c, 0x82, 0xb2, 0x28, 0x5f, 0x96, 0x93, 0xbc, 0xbe, 0xb9, 0xdf, 0xa5, 0x68, 0xbb,
-	0x4b, 0xd1, 0xdf, 0x5d, 0x8a, 0x7e, 0xee, 0xd3, 0xc5, 0x76, 0x9f, 0x2e, 0x7e, 0xef, 0xd3, 0xc5,
-	0x77, 0x2a, 0x95, 0x6f, 0x87, 0x9a, 0x36, 0xa6, 0x63, 0x5f, 0x94, 0xf6, 0x60, 0xd7, 0xc0, 0x3b,
-	0xd6, 0xb5, 0x43, 0xcd, 0x9a, 0x96, 0x2b, 0xcd, 0x46, 0x16, 0xc6, 0xf4, 0x77, 0x3d, 0xb8, 0x3a,
-	0x9e, 0xb7, 0x78, 0xf3, 0x2f, 0x00, 0x00, 0xff, 0xff, 0x53, 0xce, 0xaa, 0x70, 0xe2, 0x01, 0x00,
-	0x00,
+	// 361 bytes of a gzipped FileDescriptorProto
+	0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0xff, 0x8d, 0x51, 0xc1, 0x4a, 0xc3, 0x40,
+	0x14, 0x6c, 0x9b, 0x34, 0x6d, 0xb7, 0xa0, 0xb2, 0x8a, 0xa6, 0x15, 0x5a, 0xe9, 0xc5, 0x9e, 0xb2,
+	0x54, 0x0f, 0xde, 0x04, 0xab, 0x48, 0x3d, 0x88, 0x10, 0x7b, 0xf2, 0x12, 0x92, 0xe6, 0x75, 0xb3,
+	0x60, 0xb2, 0x21, 0xbb, 0x29, 0xe9, 0x5f, 0xf8, 0x2b, 0xfe, 0x45, 0x8f, 0x3d, 0x7a, 0x12, 0xd1,
+	0x1f, 0x71, 0xb3, 0x29, 0x78, 0xf5, 0x30, 0x30, 0x6f, 0x66, 0x16, 0xde, 0xec, 0x43, 0x87, 0x29,
+	0x50, 0xba, 0x26, 0xab, 0x09, 0x49, 0x39, 0x7f, 0x75, 0xd2, 0x8c, 0x4b, 0x8e, 0xdb, 0x5a, 0x74,
+	0x56, 0x93, 0xfe, 0x60, 0xc1, 0x45, 0xcc, 0x05, 0x09, 0x7c, 0x01, 0x2a, 0x14, 0x80, 0xf4, 0x27,
+	0x64, 0xc1, 0x59, 0x52, 0x25, 0xfb, 0x47, 0x94, 0x53, 0xae, 0x29, 0x29, 0x59, 0xa5, 0x8e, 0xde,
+	0x1b, 0x08, 0x3d, 0xe5, 0x92, 0xaa, 0x1c, 0x9d, 0x17, 0xf8, 0x18, 0x59, 0x02, 0x92, 0x10, 0x32,
+	0xbb, 0x7e, 0x56, 0x1f, 0x77, 0xdc, 0xdd, 0x84, 0x4f, 0x51, 0x27, 0x04, 0x21, 0x3d, 0x3f, 0x0c,
+	0x33, 0xbb, 0xa1, 0xad, 0x76, 0x29, 0xdc, 0xa8, 0x19, 0x0f, 0x51, 0x37, 0x83, 0x65, 0x9e, 0x84,
+	0x95, 0x6d, 0x68, 0x1b, 0x55, 0x92, 0x0e, 0x9c, 0xa3, 0x7d, 0x28, 0x52, 0x96, 0xf9, 0x92, 0xf1,
+	0xc4, 0x93, 0x2c, 0x06, 0xdb, 0x54, 0x21, 0xc3, 0xdd, 0xfb, 0x93, 0xe7, 0x4a, 0xc5, 0x57, 0xc8,
+	0xf2, 0x63, 0x9e, 0x27, 0xd2, 0x6e, 0x2a, 0xbf, 0x7b, 0xd1, 0x73, 0xaa, 0x52, 0x4e, 0x59, 0xca,
+	0xd9, 0x95, 0x72, 0x6e, 0xd5, 0xb2, 0x53, 0x73, 0xf3, 0x39, 0xac, 0xb9, 0xbb, 0x38, 0xbe, 0x46,
+	0x28, 0xc8, 0x58, 0x48, 0xc1, 0x5b, 0x02, 0xd8, 0xd6, 0xff, 0x1e, 0x77, 0xaa, 0x27, 0xf7, 0x00,
+	0xf8, 0x04, 0xb5, 0x64, 0xe1, 0x45, 0xbe, 0x88, 0xec, 0x56, 0x55, 0x5c, 0x16, 0x33, 0x35, 0x61,
+	0x8c, 0xcc, 0x18, 0x62, 0x6e, 0xb7, 0xb5, 0xaa, 0xf9, 0xa8, 0x87, 0x9a, 0x0f, 0x77, 0xcf, 0x20,
+	0xf1, 0x01, 0x32, 0x58, 0x28, 0xd4, 0x57, 0x19, 0x63, 0xd3, 0x2d, 0xe9, 0x74, 0xb6, 0xf9, 0x1e,
+	0xd4, 0xb7, 0x0a, 0x5f, 0x0a, 0x6f, 0x3f, 0x83, 0xda, 0x56, 0xe1, 0x43, 0xe1, 0xc5, 0xa1, 0x4c,
+	0x46, 0x79, 0xa0, 0x76, 0x8a, 0xc9, 0x23, 0x4b, 0x24, 0x64, 0x73, 0xf0, 0x63, 0x12, 0x2b, 0x8d,
+	0x2c, 0x22, 0x9f, 0x25, 0xa4, 0x20, 0xd5, 0x81, 0xe5, 0x3a, 0x05, 0x11, 0x58, 0xfa, 0x3e, 0x97,
+	0xbf, 0xfc, 0x50, 0x74, 0xf7, 0xf6, 0x01, 0x00, 0x00,
 }
 
 func (m *OutgoingTx) Marshal() (dAtA []byte, err error) {
@@ -218,6 +225,13 @@ func (m *OutgoingTx) MarshalToSizedBuffer(dAtA []byte) (int, error) {
 	_ = i
 	var l int
 	_ = l
+	if len(m.Memo) > 0 {
+		i -= len(m.Memo)
+		copy(dAtA[i:], m.Memo)
+		i = encodeVarintPool(dAtA, i, uint64(len(m.Memo)))
+		i--
+		dAtA[i] = 0x42
+	}
 	if len(m.TxHash) > 0 {
 		i -= len(m.TxHash)
 		copy(dAtA[i:], m.TxHash)
@@ -355,6 +369,10 @@ func (m *OutgoingTx) Size() (n int) {
 	if l > 0 {
 		n += 1 + l + sovPool(uint64(l))
 	}
+	l = len(m.Memo)
+	if l > 0 {
+		n += 1 + l + sovPool(uint64(l))
+	}
 	return n
 }
 
@@ -622,6 +640,38 @@ func (m *OutgoingTx) Unmarshal(dAtA []byte) error {
 			}
 			m.TxHash = string(dAtA[iNdEx:postIndex])
 			iNdEx = postIndex
+		case 8:
+			if wireType != 2 {
+				return fmt.Errorf("proto: wrong wireType = %d for field Memo", wireType)
+			}
+			var stringLen uint64
+			for shift := uint(0); ; shift += 7 {
+				if shift >= 64 {
+					return ErrIntOverflowPool
+				}
+				if iNdEx >= l {
+					return io.ErrUnexpectedEOF
+				}
+				b := dAtA[iNdEx]
+				iNdEx++
+				stringLen |= uint64(b&0x7F) << shift
+				if b < 0x80 {
+					break
+				}
+			}
+			intStringLen := int(stringLen)
+			if intStringLen < 0 {
+				return ErrInvalidLengthPool
+			}
+			postIndex := iNdEx + intStringLen
+			if postIndex < 0 {
+				return ErrInvalidLengthPool
+			}
+			if postIndex > l {
+				return io.ErrUnexpectedEOF
+			}
+			m.Memo = string(dAtA[iNdEx:postIndex])
+			iNdEx = postIndex
 		default:
 			iNdEx = preIndex
 			skippy, err := skipPool(dAtA[iNdEx:])
@@ -4,6 +4,7 @@ import (
 	c "context"
 	"crypto/ecdsa"
 	"encoding/hex"
+	"fmt"
 	"github.com/MinterTeam/mhub/chain/x/minter/types"
 	oracleTypes "github.com/MinterTeam/mhub/chain/x/oracle/types"
 	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
@@ -15,6 +16,11 @@ import (
 	"github.com/MinterTeam/minter-hub-connector/context"
 	"github.com/MinterTeam/minter-hub-connector/cosmos"
 	"github.com/MinterTeam/minter-hub-connector/minter"
+	"github.com/MinterTeam/minter-hub-connector/minter_claims"
+	"github.com/MinterTeam/minter-hub-connector/minter_multisig"
+	"github.com/MinterTeam/minter-hub-connector/minter_relay"
+	"github.com/MinterTeam/minter-hub-connector/minter_reorg"
+	"github.com/MinterTeam/minter-hub-connector/minter_verify"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	ethCrypto "github.com/ethereum/go-ethereum/crypto"
 	"github.com/tendermint/tendermint/libs/json"
@@ -22,13 +28,18 @@ import (
 	"google.golang.org/grpc"
 	"google.golang.org/grpc/backoff"
 	"math"
+	"math/big"
 	"os"
 	"strconv"
 	"strings"
 	"time"
 )
 
-const threshold = 667
+// how many scanned Minter blocks are remembered to find the common block after a fork
+const reorgTrackingWindow = 1000
+
+// how many claimed Minter events are remembered across restarts
+const claimedEventsWindow = 10000
 
 var cfg = config.Get()
 
@@ -50,18 +61,71 @@ func main() {
 		MinConnectTimeout: time.Second * 5,
 	}))
 
+	logger := log.NewTMLogger(os.Stdout)
+
+	verifier, err := minter_verify.New(cfg.Minter.VerifyNodeUrls, logger)
+	if err != nil {
+		panic(err)
+	}
+
+	claimStore, err := minter_claims.Open(cfg.StateDir, claimedEventsWindow)
+	if err != nil {
+		panic(err)
+	}
+
+	var relayer *minter_relay.Relayer
+	if cfg.Minter.Relay {
+		relayer = &minter_relay.Relayer{
+			CosmosConn:   cosmosConn,
+			MinterClient: minterClient,
+			ChainID:      cfg.Minter.ChainID,
+			MultisigAddr: cfg.Minter.MultisigAddr,
+			Logger:       logger,
+		}
+	}
+
+	reconciler := &minter_multisig.Reconciler{
+		CosmosConn:   cosmosConn,
+		MinterClient: minterClient,
+		MultisigAddr: cfg.Minter.MultisigAddr,
+		Logger:       logger,
+	}
+	if cfg.Minter.RotateMultisig {
+		reconciler.RequestValset = func() {
+			cosmos.SendCosmosTx([]sdk.Msg{types.NewMsgValsetRequest(orcAddress)}, orcAddress, orcPriv, cosmosConn, logger)
+		}
+	}
+
 	ctx := context.Context{
 		LastCheckedMinterBlock: cfg.Minter.StartBlock,
 		LastEventNonce:         cfg.Minter.StartEventNonce,
 		LastBatchNonce:         cfg.Minter.StartBatchNonce,
 		LastValsetNonce:        cfg.Minter.StartValsetNonce,
 		MinterMultisigAddr:     cfg.Minter.MultisigAddr,
+		MinterConfirmations:    cfg.Minter.Confirmations,
+		BlockTracker:           minter_reorg.New(reorgTrackingWindow),
+		ClaimStore:             claimStore,
 		CosmosConn:             cosmosConn,
 		MinterClient:           minterClient,
+		Verifier:               verifier,
+		Relayer:                relayer,
+		Reconciler:             reconciler,
 		OrcAddress:             orcAddress,
 		OrcPriv:                orcPriv,
 		MinterWallet:           minterWallet,
-		Logger:                 log.NewTMLogger(os.Stdout),
+		Logger:                 logger,
+	}
+
+	if cfg.StateDir == "" {
+		ctx.Logger.Info("No state dir given, claimed Minter events are not remembered across restarts")
+	}
+
+	if relayer == nil {
+		ctx.Logger.Info("Relaying to Minter is disabled, signed batches and valsets are left to minter-relayer")
+	}
+
+	if !verifier.Enabled() {
+		ctx.Logger.Info("Minter deposit verification is disabled, deposits are trusted to the primary Minter node")
 	}
 
 	ctx.Logger.Info("Syncing with Minter")
@@ -115,6 +179,7 @@ func main() {
 	for {
 		relayBatches(ctx)
 		relayValsets(ctx)
+		ctx.Reconciler.Check()
 		ctx = relayMinterEvents(ctx)
 
 		ctx.Logger.Info("Last checked minter block", "height", ctx.LastCheckedMinterBlock, "eventNonce", ctx.LastEventNonce, "batchNonce", ctx.LastBatchNonce, "valsetNonce", ctx.LastValsetNonce)
@@ -137,12 +202,7 @@ func relayBatches(ctx context.Context) {
 		if response.Batch != nil {
 			ctx.Logger.Info("Sending batch confirm", "batch nonce", response.Batch.BatchNonce)
 
-			txData := transaction.NewMultisendData()
-			for _, out := range response.Batch.Transactions {
-				txData.AddItem(transaction.NewSendData().SetCoin(out.MinterToken.CoinId).MustSetTo(out.DestAddress).SetValue(out.MinterToken.Amount.BigInt()))
-			}
-
-			tx, _ := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(txData)
+			tx, _ := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(minter_relay.BatchTxData(response.Batch))
 			signedTx, _ := tx.SetNonce(response.Batch.MinterNonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeMulti).Sign(
 				cfg.Minter.MultisigAddr,
 				ctx.MinterWallet.PrivateKey,
@@ -164,85 +224,8 @@ func relayBatches(ctx context.Context) {
 		}
 	}
 
-	latestBatches, err := cosmosClient.OutgoingTxBatches(c.Background(), &types.QueryOutgoingTxBatchesRequest{})
-	if err != nil {
-		ctx.Logger.Error("Error getting last batches", "err", err.Error())
-		return
-	}
-
-	var oldestSignedBatch *types.OutgoingTxBatch
-	var oldestSignatures []*types.MsgConfirmBatch
-
-	for _, batch := range latestBatches.Batches {
-		sigs, err := cosmosClient.BatchConfirms(c.Background(), &types.QueryBatchConfirmsRequest{
-			Nonce: batch.BatchNonce,
-		})
-		if err != nil {
-			ctx.Logger.Error("Error while getting batch confirms", "err", err.Error())
-			return
-		}
-
-		if sigs.Size() > 0 { // todo: check if we have enough votes
-			oldestSignedBatch = batch
-			oldestSignatures = sigs.Confirms
-		}
-	}
-
-	if oldestSignedBatch == nil {
-		return
-	}
-
-	if oldestSignedBatch.BatchNonce < ctx.LastBatchNonce {
-		return
-	}
-
-	ctx.Logger.Info("Sending batch to Minter")
-
-	txData := transaction.NewMultisendData()
-	for _, out := range oldestSignedBatch.Transactions {
-		txData.AddItem(transaction.NewSendData().SetCoin(out.MinterToken.CoinId).MustSetTo(out.DestAddress).SetValue(out.MinterToken.Amount.BigInt()))
-	}
-
-	tx, _ := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(txData)
-	tx.SetNonce(oldestSignedBatch.MinterNonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeMulti)
-	signedTx, err := tx.Sign(cfg.Minter.MultisigAddr)
-	if err != nil {
-		panic(err)
-	}
-
-	lastValset, err := cosmosClient.LastValset(c.Background(), &types.QueryLastValsetRequest{})
-	if err != nil {
-		panic(err)
-	}
-
-	// Check if signer is in the last confirmed valset
-	for _, sig := range oldestSignatures {
-		for _, member := range lastValset.GetValset().GetMembers() {
-			if strings.ToLower(member.MinterAddress) == strings.ToLower(sig.MinterSigner) {
-				signedTx, err = signedTx.AddSignature(sig.Signature)
-				if err != nil {
-					panic(err)
-				}
-			}
-		}
-	}
-
-	encodedTx, err := signedTx.Encode()
-	if err != nil {
-		panic(err)
-	}
-
-	ctx.Logger.Debug("Batch tx", "tx", encodedTx)
-	response, err := ctx.MinterClient.SendTransaction(encodedTx)
-	if err != nil {
-		code, body, err := http_client.ErrorBody(err)
-		if err != nil {
-			ctx.Logger.Error("Error on sending Minter Tx", "err", err.Error())
-		} else {
-			ctx.Logger.Error("Error on sending Minter Tx", "code", code, "err", body.Error.Message)
-		}
-	} else if response.Code != 0 {
-		ctx.Logger.Error("Error on sending Minter Tx", "err", response.Log)
+	if ctx.Relayer != nil {
+		ctx.Relayer.RelayBatch(ctx.LastBatchNonce)
 	}
 }
 
@@ -261,26 +244,7 @@ func relayValsets(ctx context.Context) {
 		if response.Valset != nil {
 			ctx.Logger.Info("Sending valset confirm", "valset nonce", response.Valset.Nonce)
 
-			txData := transaction.NewEditMultisigData()
-			txData.Threshold = threshold
-
-			totalPower := uint64(0)
-			for _, val := range response.Valset.Members {
-				totalPower += val.Power
-			}
-
-			for _, val := range response.Valset.Members {
-				var addr transaction.Address
-				bytes, _ := wallet.AddressToHex(val.MinterAddress)
-				copy(addr[:], bytes)
-
-				weight := uint32(sdk.NewUint(val.Power).MulUint64(1000).QuoUint64(totalPower).Uint64())
-
-				txData.Addresses = append(txData.Addresses, addr)
-				txData.Weights = append(txData.Weights, weight)
-			}
-
-			tx, _ := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(txData)
+			tx, _ := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(minter_relay.ValsetTxData(response.Valset))
 			tx.SetPayload([]byte(strconv.Itoa(int(response.Valset.Nonce))))
 			signedTx, _ := tx.SetNonce(response.Valset.MinterNonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeMulti).Sign(
 				cfg.Minter.MultisigAddr,
@@ -303,110 +267,23 @@ func relayValsets(ctx context.Context) {
 		}
 	}
 
-	latestValsets, err := cosmosClient.LastValsetRequests(c.Background(), &types.QueryLastValsetRequestsRequest{})
-	if err != nil {
-		ctx.Logger.Error("Error on getting last valset requests", "err", err.Error())
-		return
-	}
-
-	var oldestSignedValset *types.Valset
-	var oldestSignatures []*types.MsgValsetConfirm
-
-	for _, valset := range latestValsets.Valsets {
-		sigs, err := cosmosClient.ValsetConfirmsByNonce(c.Background(), &types.QueryValsetConfirmsByNonceRequest{
-			Nonce: valset.Nonce,
-		})
-		if err != nil {
-			ctx.Logger.Error("Error while getting valset confirms", "err", err.Error())
-			return
-		}
-
-		if sigs.Size() > 0 { // todo: check if we have enough votes
-			oldestSignedValset = valset
-			oldestSignatures = sigs.Confirms
-		}
-	}
-
-	if oldestSignedValset == nil {
-		return
-	}
-
-	if oldestSignedValset.Nonce < ctx.LastValsetNonce {
-		return
-	}
-
-	ctx.Logger.Info("Sending valset to Minter")
-
-	txData := transaction.NewEditMultisigData()
-	txData.Threshold = threshold
-
-	totalPower := uint64(0)
-	for _, val := range oldestSignedValset.Members {
-		totalPower += val.Power
-	}
-
-	for _, val := range oldestSignedValset.Members {
-		var addr transaction.Address
-		bytes, _ := wallet.AddressToHex(val.MinterAddress)
-		copy(addr[:], bytes)
-
-		weight := uint32(sdk.NewUint(val.Power).MulUint64(1000).QuoUint64(totalPower).Uint64())
-
-		txData.Addresses = append(txData.Addresses, addr)
-		txData.Weights = append(txData.Weights, weight)
-	}
-
-	tx, _ := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(txData)
-	tx.SetNonce(oldestSignedValset.MinterNonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeMulti)
-	tx.SetPayload([]byte(strconv.Itoa(int(oldestSignedValset.Nonce))))
-	signedTx, err := tx.Sign(cfg.Minter.MultisigAddr)
-	if err != nil {
-		panic(err)
-	}
-
-	lastValset, err := cosmosClient.LastValset(c.Background(), &types.QueryLastValsetRequest{})
-	if err != nil {
-		panic(err)
-	}
-
-	// Check if signer is in the last confirmed valset
-	for _, sig := range oldestSignatures {
-		hasMember := false
-		for _, member := range lastValset.GetValset().GetMembers() {
-			if strings.ToLower(member.MinterAddress) == strings.ToLower(sig.MinterAddress) {
-				hasMember = true
-			}
-		}
-
-		if hasMember || len(lastValset.GetValset().GetMembers()) == 0 {
-			signedTx, err = signedTx.AddSignature(sig.Signature)
-			if err != nil {
-				panic(err)
-			}
-		}
+	if ctx.Relayer != nil {
+		ctx.Relayer.RelayValset(ctx.LastValsetNonce)
 	}
+}
 
-	encodedTx, err := signedTx.Encode()
-	if err != nil {
-		panic(err)
+func relayMinterEvents(ctx context.Context) context.Context {
+	ctx, ok := checkMinterReorg(ctx)
+	if !ok {
+		return ctx
 	}
 
-	ctx.Logger.Debug("Valset update tx", "tx", encodedTx)
-	response, err := ctx.MinterClient.SendTransaction(encodedTx)
-	if err != nil {
-		code, body, err := http_client.ErrorBody(err)
-		if err != nil {
-			ctx.Logger.Error("Error on sending Minter Tx", "err", err.Error())
-		} else {
-			ctx.Logger.Error("Error on sending Minter Tx", "code", code, "err", body.Error.Message)
-		}
-	} else if response.Code != 0 {
-		ctx.Logger.Error("Error on sending Minter Tx", "err", response.Log)
+	initialCtx := ctx
+	latestBlock := minter_reorg.ConfirmedHeight(minter.GetLatestMinterBlock(ctx.MinterClient, ctx.Logger), ctx.MinterConfirmations)
+	if latestBlock <= ctx.LastCheckedMinterBlock {
+		return ctx
 	}
-}
 
-func relayMinterEvents(ctx context.Context) context.Context {
-	latestBlock := minter.GetLatestMinterBlock(ctx.MinterClient, ctx.Logger)
 	if latestBlock-ctx.LastCheckedMinterBlock > 100 {
 		latestBlock = ctx.LastCheckedMinterBlock + 100
 	}
@@ -422,6 +299,8 @@ func relayMinterEvents(ctx context.Context) context.Context {
 	var deposits []cosmos.Deposit
 	var batches []cosmos.Batch
 	var valsets []cosmos.Valset
+	var checkpoints []minter_reorg.Checkpoint
+	var eventIDs []string
 
 	const blocksPerBatch = 100
 	for i := uint64(0); i <= uint64(math.Ceil(float64(latestBlock-ctx.LastCheckedMinterBlock)/blocksPerBatch)); i++ {
@@ -467,6 +346,26 @@ func relayMinterEvents(ctx context.Context) context.Context {
 
 					for _, hubCoin := range coinList.GetCoins() {
 						if sendData.Coin.ID == hubCoin.MinterId {
+							eventID := minter_claims.EventID(tx.Hash, 0)
+							if ctx.ClaimStore.Claimed(eventID) {
+								// the event keeps its nonce, only the claim is skipped
+								ctx.Logger.Info("Deposit already claimed, skipping", "hash", tx.Hash, "eventNonce", ctx.LastEventNonce)
+								ctx.LastEventNonce++
+								continue
+							}
+
+							if err := ctx.Verifier.VerifyTransaction(block.Hash, tx); err != nil {
+								// nothing from this scan is claimed, we will retry from the same block on the next iteration
+								ctx.Logger.Error("Deposit verification failed, refusing to claim", "hash", tx.Hash, "err", err.Error())
+								return initialCtx
+							}
+
+							if err := checkSupplyCap(ctx, sendData.Coin.ID); err != nil {
+								// claims are made in event nonce order, so nothing after this deposit is claimed either
+								ctx.Logger.Error("SUPPLY CAP EXCEEDED, refusing to claim deposit", "hash", tx.Hash, "coin", sendData.Coin.ID, "err", err.Error())
+								return initialCtx
+							}
+
 							ctx.Logger.Info("Found new deposit", "from", tx.From, "to", string(tx.Payload), "amount", sendData.Value, "coin", sendData.Coin.ID)
 							deposits = append(deposits, cosmos.Deposit{
 								Sender:     tx.From,
@@ -478,6 +377,7 @@ func relayMinterEvents(ctx context.Context) context.Context {
 								CoinID:     sendData.Coin.ID,
 								TxHash:     tx.Hash,
 							})
+							eventIDs = append(eventIDs, eventID)
 
 							ctx.LastEventNonce++
 						}
@@ -485,12 +385,18 @@ func relayMinterEvents(ctx context.Context) context.Context {
 				}
 
 				if tx.Type == uint64(transaction.TypeMultisend) && tx.From == cfg.Minter.MultisigAddr {
-					ctx.Logger.Info("Found withdrawal")
-					batches = append(batches, cosmos.Batch{
-						BatchNonce: ctx.LastBatchNonce,
-						EventNonce: ctx.LastEventNonce,
-						TxHash:     tx.Hash,
-					})
+					eventID := minter_claims.EventID(tx.Hash, 0)
+					if ctx.ClaimStore.Claimed(eventID) {
+						ctx.Logger.Info("Withdrawal already claimed, skipping", "hash", tx.Hash, "eventNonce", ctx.LastEventNonce)
+					} else {
+						ctx.Logger.Info("Found withdrawal")
+						batches = append(batches, cosmos.Batch{
+							BatchNonce: ctx.LastBatchNonce,
+							EventNonce: ctx.LastEventNonce,
+							TxHash:     tx.Hash,
+						})
+						eventIDs = append(eventIDs, eventID)
+					}
 
 					ctx.LastEventNonce++
 					ctx.LastBatchNonce++
@@ -502,23 +408,122 @@ func relayMinterEvents(ctx context.Context) context.Context {
 					nonce, err := strconv.Atoi(string(tx.Payload))
 					if err != nil {
 						ctx.Logger.Error("Error while decoding valset update nonce", "err", err.Error())
+					} else if eventID := minter_claims.EventID(tx.Hash, 0); ctx.ClaimStore.Claimed(eventID) {
+						ctx.Logger.Info("Valset update already claimed, skipping", "hash", tx.Hash, "eventNonce", ctx.LastEventNonce)
+
+						ctx.LastEventNonce++
+						ctx.LastValsetNonce = uint64(nonce)
 					} else {
 						valsets = append(valsets, cosmos.Valset{
 							ValsetNonce: uint64(nonce),
 							EventNonce:  ctx.LastEventNonce,
+							TxHash:      tx.Hash,
 						})
+						eventIDs = append(eventIDs, eventID)
 
 						ctx.LastEventNonce++
 						ctx.LastValsetNonce = uint64(nonce)
 					}
 				}
 			}
+
+			checkpoints = append(checkpoints, minter_reorg.Checkpoint{
+				Height:      block.Height,
+				Hash:        block.Hash,
+				EventNonce:  ctx.LastEventNonce,
+				BatchNonce:  ctx.LastBatchNonce,
+				ValsetNonce: ctx.LastValsetNonce,
+			})
 		}
 	}
 
+	for _, checkpoint := range checkpoints {
+		ctx.BlockTracker.Record(checkpoint)
+	}
+
 	if len(deposits) > 0 || len(batches) > 0 || len(valsets) > 0 {
 		cosmos.SendCosmosTx(cosmos.CreateClaims(ctx.CosmosConn, ctx.OrcAddress, deposits, batches, valsets, ctx.Logger), ctx.OrcAddress, ctx.OrcPriv, ctx.CosmosConn, ctx.Logger)
+
+		// SendCosmosTx only returns once the Hub accepted the claims
+		if err := ctx.ClaimStore.Add(eventIDs...); err != nil {
+			ctx.Logger.Error("Cannot save claimed Minter events", "err", err.Error())
+		}
 	}
 
 	return ctx
 }
+
+// checkMinterReorg compares the last scanned Minter blocks with the node and rewinds the scan cursor to the
+// newest block still on the node's chain, false means nothing should be scanned in this iteration
+func checkMinterReorg(ctx context.Context) (context.Context, bool) {
+	checkpoint, rewound, ok, err := ctx.BlockTracker.Check(func(height uint64) (string, error) {
+		block, err := ctx.MinterClient.Block(height)
+		if err != nil {
+			// the node answered but doesn't have the block, it is on a shorter fork
+			if _, _, bodyErr := http_client.ErrorBody(err); bodyErr == nil {
+				return "", nil
+			}
+			return "", err
+		}
+
+		return block.Hash, nil
+	})
+
+	if err == minter_reorg.ErrReorgTooDeep {
+		ctx.Logger.Error("Minter reorg is deeper than the tracked blocks, resyncing from the start block", "height", ctx.LastCheckedMinterBlock)
+
+		ctx.LastCheckedMinterBlock = cfg.Minter.StartBlock
+		ctx.LastEventNonce = cfg.Minter.StartEventNonce
+		ctx.LastBatchNonce = cfg.Minter.StartBatchNonce
+		ctx.LastValsetNonce = cfg.Minter.StartValsetNonce
+
+		return minter.GetLatestMinterBlockAndNonce(ctx, cosmos.GetLastMinterNonce(ctx.OrcAddress.String(), ctx.CosmosConn)), false
+	}
+
+	if err != nil {
+		ctx.Logger.Error("Cannot check Minter blocks for reorg", "err", err.Error())
+		time.Sleep(time.Second)
+		return ctx, false
+	}
+
+	if ok && rewound {
+		ctx.Logger.Error("Minter node switched to a fork, rewinding scan", "from", ctx.LastCheckedMinterBlock, "to", checkpoint.Height)
+
+		ctx.LastCheckedMinterBlock = checkpoint.Height
+		ctx.LastEventNonce = checkpoint.EventNonce
+		ctx.LastBatchNonce = checkpoint.BatchNonce
+		ctx.LastValsetNonce = checkpoint.ValsetNonce
+	}
+
+	return ctx, true
+}
+
+// checkSupplyCap fails if more of the coin than its configured cap is held by the multisig, that is bridged to the Hub
+func checkSupplyCap(ctx context.Context, coinID uint64) error {
+	supplyCap, ok := cfg.Minter.SupplyCaps[coinID]
+	if !ok {
+		return nil
+	}
+
+	address, err := ctx.MinterClient.Address(ctx.MinterMultisigAddr)
+	if err != nil {
+		return fmt.Errorf("cannot get multisig balance: %s", err.Error())
+	}
+
+	for _, balance := range address.Balance {
+		if balance.Coin.ID != coinID {
+			continue
+		}
+
+		value, ok := new(big.Int).SetString(balance.Value, 10)
+		if !ok {
+			return fmt.Errorf("invalid multisig balance %s", balance.Value)
+		}
+
+		if value.Cmp(supplyCap) > 0 {
+			return fmt.Errorf("multisig holds %s, cap is %s", value.String(), supplyCap.String())
+		}
+	}
+
+	return nil
+}
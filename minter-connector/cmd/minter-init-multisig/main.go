@@ -0,0 +1,170 @@
+// minter-init-multisig creates the Minter multisig of a new bridge from the Hub's current valset, with the members
+// and weights valset updates install, and checks the multisig on Minter once it is created. The Minter account of
+// --minter-mnemonic creates it and pays the commission. It takes the connector's flags, --minter-multisig and the
+// Cosmos mnemonic are not needed. The address it prints has to be set as the minter module's bridge address by a
+// Hub governance proposal before the connectors are started with it.
+package main
+
+import (
+	c "context"
+	"fmt"
+	"os"
+	"reflect"
+	"time"
+
+	"github.com/MinterTeam/mhub/chain/x/minter/types"
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
+	"github.com/MinterTeam/minter-go-sdk/v2/transaction"
+	"github.com/MinterTeam/minter-go-sdk/v2/wallet"
+	"github.com/MinterTeam/minter-hub-connector/config"
+	"github.com/MinterTeam/minter-hub-connector/cosmos"
+	"github.com/MinterTeam/minter-hub-connector/minter_multisig"
+	"github.com/MinterTeam/minter-hub-connector/minter_simulate"
+	"github.com/tendermint/tendermint/libs/log"
+	"google.golang.org/grpc"
+	"google.golang.org/grpc/backoff"
+)
+
+// how long the creation has to show up in a Minter block
+const inclusionTimeout = time.Minute
+
+var cfg = config.Get()
+
+func main() {
+	cosmos.Setup()
+	logger := log.NewTMLogger(os.Stdout)
+
+	deployer, err := wallet.Create(cfg.Minter.Mnemonic, "")
+	if err != nil {
+		fail(logger, "Invalid deployer mnemonic", err)
+	}
+
+	minterClient, err := http_client.New(cfg.Minter.NodeUrl)
+	if err != nil {
+		fail(logger, "Invalid Minter node url", err)
+	}
+
+	cosmosConn, err := grpc.DialContext(c.Background(), cfg.Cosmos.NodeGrpcUrl, grpc.WithInsecure(), grpc.WithConnectParams(grpc.ConnectParams{
+		Backoff:           backoff.DefaultConfig,
+		MinConnectTimeout: time.Second * 5,
+	}))
+	if err != nil {
+		fail(logger, "Cannot connect to the Hub", err)
+	}
+
+	current, err := types.NewQueryClient(cosmosConn).CurrentValset(c.Background(), &types.QueryCurrentValsetRequest{})
+	if err != nil {
+		fail(logger, "Cannot get the current valset", err)
+	}
+
+	valset := current.GetValset()
+	if valset == nil || len(valset.GetMembers()) == 0 {
+		fail(logger, "The Hub has no valset yet", nil)
+	}
+
+	txData, err := minter_multisig.CreateTxData(valset)
+	if err != nil {
+		fail(logger, "Cannot build a multisig from the current valset", err)
+	}
+
+	for i, address := range txData.Addresses {
+		logger.Info("Multisig member", "address", fmt.Sprintf("Mx%x", address[:]), "weight", txData.Weights[i])
+	}
+
+	nonce, err := minterClient.Nonce(deployer.Address)
+	if err != nil {
+		fail(logger, "Cannot get the deployer's nonce", minter_simulate.FromHTTPError(err))
+	}
+
+	multisigAddr, err := minter_multisig.Address(deployer.Address, nonce)
+	if err != nil {
+		fail(logger, "Cannot derive the multisig address", err)
+	}
+
+	tx, err := transaction.NewBuilder(cfg.Minter.ChainID).NewTransaction(txData)
+	if err != nil {
+		fail(logger, "Cannot build the multisig creation", err)
+	}
+
+	signedTx, err := tx.SetNonce(nonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeSingle).Sign(deployer.PrivateKey)
+	if err != nil {
+		fail(logger, "Cannot sign the multisig creation", err)
+	}
+
+	encodedTx, err := signedTx.Encode()
+	if err != nil {
+		fail(logger, "Cannot encode the multisig creation", err)
+	}
+
+	// the deployer has to be able to pay the commission
+	if err := minter_simulate.Simulate(minterClient, deployer.Address, encodedTx, nonce, nil); err != nil {
+		fail(logger, "The multisig creation would fail", err)
+	}
+
+	logger.Info("Creating the Minter multisig", "deployer", deployer.Address, "multisig", multisigAddr)
+
+	response, err := minterClient.SendTransaction(encodedTx)
+	if err != nil {
+		fail(logger, "Cannot send the multisig creation", minter_simulate.FromHTTPError(err))
+	}
+
+	if txErr := minter_simulate.FromCode(response.Code, response.Log); txErr != nil {
+		fail(logger, "The multisig creation was rejected", txErr)
+	}
+
+	var height uint64
+	for deadline := time.Now().Add(inclusionTimeout); height == 0; {
+		if time.Now().After(deadline) {
+			fail(logger, "The multisig creation was not included in time, check "+response.Hash+" before trying again", nil)
+		}
+
+		time.Sleep(2 * time.Second)
+
+		included, err := minterClient.Transaction(response.Hash)
+		if err != nil {
+			continue
+		}
+
+		if txErr := minter_simulate.FromCode(included.Code, included.Log); txErr != nil {
+			fail(logger, "The multisig creation failed", txErr)
+		}
+
+		height = included.Height
+	}
+
+	actual, err := minter_multisig.FromMinter(minterClient, multisigAddr)
+	if err != nil {
+		fail(logger, "Cannot get the created multisig", err)
+	}
+
+	if expected := minter_multisig.Expected(valset); !reflect.DeepEqual(expected, actual) {
+		fail(logger, "The created multisig differs from the valset: "+minter_multisig.Compare(expected, actual).String(), nil)
+	}
+
+	logger.Info("Minter multisig created and verified", "multisig", multisigAddr, "height", height, "valset", valset.Nonce)
+
+	fmt.Printf(`
+Minter multisig: %s
+Created at Minter block: %d
+
+Set it as the bridge address of the minter module with a param change proposal, for example
+mhub tx gov submit-proposal param-change proposal.json with
+{
+  "title": "Minter multisig",
+  "description": "Bridge to the Minter multisig %s",
+  "changes": [{"subspace": "minter", "key": "BridgeContractAddress", "value": "\"%s\""}],
+  "deposit": "<DEPOSIT>"
+}
+Once it passes, start the connectors with --minter-multisig=%s --minter-start-block=%d
+`, multisigAddr, height, multisigAddr, multisigAddr, multisigAddr, height)
+}
+
+func fail(logger log.Logger, msg string, err error) {
+	if err != nil {
+		logger.Error(msg, "err", err.Error())
+	} else {
+		logger.Error(msg)
+	}
+
+	os.Exit(1)
+}
@@ -0,0 +1,54 @@
+// minter-relayer only relays the batches and valset updates signed on the Hub to the Minter multisig, so relaying
+// can be deployed and restarted apart from the connector. It takes the connector's flags and needs none of its
+// keys, run the connectors with --minter-relay=false once it is up.
+package main
+
+import (
+	c "context"
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
+	"github.com/MinterTeam/minter-hub-connector/config"
+	"github.com/MinterTeam/minter-hub-connector/cosmos"
+	"github.com/MinterTeam/minter-hub-connector/minter_relay"
+	"github.com/tendermint/tendermint/libs/log"
+	"google.golang.org/grpc"
+	"google.golang.org/grpc/backoff"
+	"os"
+	"time"
+)
+
+var cfg = config.Get()
+
+func main() {
+	cosmos.Setup()
+
+	minterClient, err := http_client.New(cfg.Minter.NodeUrl)
+	if err != nil {
+		panic(err)
+	}
+
+	cosmosConn, err := grpc.DialContext(c.Background(), cfg.Cosmos.NodeGrpcUrl, grpc.WithInsecure(), grpc.WithConnectParams(grpc.ConnectParams{
+		Backoff:           backoff.DefaultConfig,
+		MinConnectTimeout: time.Second * 5,
+	}))
+	if err != nil {
+		panic(err)
+	}
+
+	relayer := minter_relay.Relayer{
+		CosmosConn:   cosmosConn,
+		MinterClient: minterClient,
+		ChainID:      cfg.Minter.ChainID,
+		MultisigAddr: cfg.Minter.MultisigAddr,
+		Logger:       log.NewTMLogger(os.Stdout),
+	}
+
+	relayer.Logger.Info("Relaying to Minter multisig", "address", cfg.Minter.MultisigAddr)
+
+	// without scanning Minter we don't know the executed nonces, simulation skips what already landed
+	for {
+		relayer.RelayBatch(0)
+		relayer.RelayValset(0)
+
+		time.Sleep(2 * time.Second)
+	}
+}
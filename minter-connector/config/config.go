@@ -2,7 +2,11 @@ package config
 
 import (
 	"flag"
+	"fmt"
 	"github.com/MinterTeam/minter-go-sdk/v2/transaction"
+	"math/big"
+	"strconv"
+	"strings"
 )
 
 var cfg *Config
@@ -15,15 +19,22 @@ func Get() Config {
 		minterChain := flag.String("minter-chain", "", "")
 		minterMnemonic := flag.String("minter-mnemonic", "", "")
 		minterNodeUrl := flag.String("minter-node-url", "", "")
+		minterVerifyNodeUrls := flag.String("minter-verify-node-urls", "", "")
 		minterStartBlock := flag.Int("minter-start-block", 1, "")
 		minterStartEventNonce := flag.Int("minter-start-event-nonce", 1, "")
 		minterStartBatchNonce := flag.Int("minter-start-batch-nonce", 1, "")
 		minterStartValsetNonce := flag.Int("minter-start-valset-nonce", 1, "")
+		minterConfirmations := flag.Int("minter-confirmations", 5, "")
+		minterSupplyCaps := flag.String("minter-supply-caps", "", "")
+		minterRelay := flag.Bool("minter-relay", true, "")
+		minterRotateMultisig := flag.Bool("minter-rotate-multisig", false, "")
 
 		cosmosMnemonic := flag.String("cosmos-mnemonic", "", "")
 		cosmosNodeUrl := flag.String("cosmos-node-url", "", "")
 		tendermintNodeUrl := flag.String("tm-node-url", "", "")
 
+		stateDir := flag.String("state-dir", "", "")
+
 		flag.Parse()
 
 		var minterChainId transaction.ChainID
@@ -44,7 +55,12 @@ func Get() Config {
 			StartEventNonce:  uint64(*minterStartEventNonce),
 			StartBatchNonce:  uint64(*minterStartBatchNonce),
 			StartValsetNonce: uint64(*minterStartValsetNonce),
+			Confirmations:    uint64(*minterConfirmations),
 			NodeUrl:          *minterNodeUrl,
+			VerifyNodeUrls:   splitUrls(*minterVerifyNodeUrls),
+			SupplyCaps:       parseSupplyCaps(*minterSupplyCaps),
+			Relay:            *minterRelay,
+			RotateMultisig:   *minterRotateMultisig,
 		}
 
 		cfg.Cosmos = CosmosConfig{
@@ -52,6 +68,8 @@ func Get() Config {
 			NodeGrpcUrl: *cosmosNodeUrl,
 			TmUrl:       *tendermintNodeUrl,
 		}
+
+		cfg.StateDir = *stateDir
 	}
 
 	return *cfg
@@ -65,7 +83,16 @@ type MinterConfig struct {
 	StartEventNonce  uint64
 	StartBatchNonce  uint64
 	StartValsetNonce uint64
+	Confirmations    uint64
 	NodeUrl          string
+	VerifyNodeUrls   []string
+	SupplyCaps       map[uint64]*big.Int
+
+	// whether the connector relays signed batches and valsets itself, off when minter-relayer does it
+	Relay bool
+
+	// whether the connector asks the Hub for a new valset when the multisig diverges from the Hub's valset
+	RotateMultisig bool
 }
 
 type CosmosConfig struct {
@@ -75,6 +102,43 @@ type CosmosConfig struct {
 }
 
 type Config struct {
-	Minter MinterConfig
-	Cosmos CosmosConfig
+	Minter   MinterConfig
+	Cosmos   CosmosConfig
+	StateDir string
+}
+
+func splitUrls(urls string) []string {
+	var result []string
+	for _, url := range strings.Split(urls, ",") {
+		if url = strings.TrimSpace(url); url != "" {
+			result = append(result, url)
+		}
+	}
+
+	return result
+}
+
+// parseSupplyCaps parses comma separated <coin id>:<cap> pairs, caps are in pip
+func parseSupplyCaps(caps string) map[uint64]*big.Int {
+	result := map[uint64]*big.Int{}
+	for _, pair := range splitUrls(caps) {
+		parts := strings.SplitN(pair, ":", 2)
+		if len(parts) != 2 {
+			panic(fmt.Sprintf("invalid supply cap %s, expected <coin id>:<cap>", pair))
+		}
+
+		coinID, err := strconv.ParseUint(strings.TrimSpace(parts[0]), 10, 64)
+		if err != nil {
+			panic(fmt.Sprintf("invalid supply cap coin id %s", parts[0]))
+		}
+
+		supplyCap, ok := new(big.Int).SetString(strings.TrimSpace(parts[1]), 10)
+		if !ok {
+			panic(fmt.Sprintf("invalid supply cap amount %s", parts[1]))
+		}
+
+		result[coinID] = supplyCap
+	}
+
+	return result
 }
@@ -3,6 +3,11 @@ package context
 import (
 	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
 	"github.com/MinterTeam/minter-go-sdk/v2/wallet"
+	"github.com/MinterTeam/minter-hub-connector/minter_claims"
+	"github.com/MinterTeam/minter-hub-connector/minter_multisig"
+	"github.com/MinterTeam/minter-hub-connector/minter_relay"
+	"github.com/MinterTeam/minter-hub-connector/minter_reorg"
+	"github.com/MinterTeam/minter-hub-connector/minter_verify"
 	"github.com/cosmos/cosmos-sdk/crypto/keys/secp256k1"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	"github.com/tendermint/tendermint/libs/log"
@@ -15,10 +20,19 @@ type Context struct {
 	LastBatchNonce         uint64
 	LastValsetNonce        uint64
 
-	MinterMultisigAddr string
+	MinterMultisigAddr  string
+	MinterConfirmations uint64
+	BlockTracker        *minter_reorg.Tracker
+	ClaimStore          *minter_claims.Store
 
 	CosmosConn   *grpc.ClientConn
 	MinterClient *http_client.Client
+	Verifier     *minter_verify.Verifier
+
+	// nil when relaying to Minter is left to minter-relayer
+	Relayer *minter_relay.Relayer
+
+	Reconciler *minter_multisig.Reconciler
 
 	OrcAddress   sdk.AccAddress
 	OrcPriv      *secp256k1.PrivKey
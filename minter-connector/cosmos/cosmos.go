@@ -36,6 +36,7 @@ type Batch struct {
 type Valset struct {
 	ValsetNonce uint64
 	EventNonce  uint64
+	TxHash      string
 }
 
 type Deposit struct {
@@ -9,6 +9,7 @@ import (
 	"github.com/MinterTeam/minter-go-sdk/v2/transaction"
 	"github.com/MinterTeam/minter-hub-connector/command"
 	"github.com/MinterTeam/minter-hub-connector/context"
+	"github.com/MinterTeam/minter-hub-connector/minter_reorg"
 	sdk "github.com/cosmos/cosmos-sdk/types"
 	"github.com/tendermint/tendermint/libs/log"
 	"math"
@@ -30,7 +31,7 @@ func GetLatestMinterBlock(client *http_client.Client, logger log.Logger) uint64
 func GetLatestMinterBlockAndNonce(ctx context.Context, currentNonce uint64) context.Context {
 	ctx.Logger.Info("Current nonce @ hub", "nonce", currentNonce)
 
-	latestBlock := GetLatestMinterBlock(ctx.MinterClient, ctx.Logger)
+	latestBlock := minter_reorg.ConfirmedHeight(GetLatestMinterBlock(ctx.MinterClient, ctx.Logger), ctx.MinterConfirmations)
 
 	oracleClient := oracleTypes.NewQueryClient(ctx.CosmosConn)
 	coinList, err := oracleClient.Coins(c.Background(), &oracleTypes.QueryCoinsRequest{})
@@ -39,6 +40,9 @@ func GetLatestMinterBlockAndNonce(ctx context.Context, currentNonce uint64) cont
 	}
 
 	firstBlock := ctx.LastCheckedMinterBlock
+	if latestBlock <= firstBlock {
+		return ctx
+	}
 
 	const blocksPerBatch = 100
 	for i := uint64(0); i <= uint64(math.Ceil(float64(latestBlock-firstBlock)/blocksPerBatch)); i++ {
@@ -122,6 +126,13 @@ func GetLatestMinterBlockAndNonce(ctx context.Context, currentNonce uint64) cont
 			}
 
 			ctx.LastCheckedMinterBlock = block.Height
+			ctx.BlockTracker.Record(minter_reorg.Checkpoint{
+				Height:      block.Height,
+				Hash:        block.Hash,
+				EventNonce:  ctx.LastEventNonce,
+				BatchNonce:  ctx.LastBatchNonce,
+				ValsetNonce: ctx.LastValsetNonce,
+			})
 		}
 	}
 
@@ -0,0 +1,120 @@
+package minter_claims
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// FileName is the name of the claimed events file in the state dir
+const FileName = "claimed_minter_events.json"
+
+// Store remembers the Minter events whose claims the Hub accepted, so a restart in the middle of
+// catching up can't claim them again. The Hub rejects claims that are out of event nonce order,
+// this is a local guard on top of that which doesn't depend on the nonces. Only the newest limit
+// events are kept, older ones are covered by the Hub's last event nonce on startup.
+type Store struct {
+	path    string
+	limit   int
+	claimed map[string]bool
+	order   []string
+}
+
+type storeFile struct {
+	Claimed []string `json:"claimed"`
+}
+
+// EventID identifies an event by the hash of the Minter tx and the event's index within the tx
+func EventID(txHash string, index int) string {
+	return fmt.Sprintf("%s:%d", strings.ToLower(txHash), index)
+}
+
+// Open loads the store from the state dir, an empty dir keeps the store in memory only
+func Open(stateDir string, limit int) (*Store, error) {
+	if limit < 1 {
+		limit = 1
+	}
+
+	store := &Store{limit: limit, claimed: map[string]bool{}}
+	if stateDir == "" {
+		return store, nil
+	}
+
+	if err := os.MkdirAll(stateDir, 0700); err != nil {
+		return nil, fmt.Errorf("creating state dir %s: %s", stateDir, err.Error())
+	}
+
+	store.path = filepath.Join(stateDir, FileName)
+	data, err := ioutil.ReadFile(store.path)
+	if os.IsNotExist(err) {
+		return store, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("reading %s: %s", store.path, err.Error())
+	}
+
+	var file storeFile
+	if err := json.Unmarshal(data, &file); err != nil {
+		return nil, fmt.Errorf("decoding %s: %s", store.path, err.Error())
+	}
+
+	store.remember(file.Claimed...)
+	return store, nil
+}
+
+// Claimed returns true if the Hub already accepted a claim for the event
+func (s *Store) Claimed(id string) bool {
+	return s.claimed[id]
+}
+
+// Add records events the Hub accepted claims for and saves the store
+func (s *Store) Add(ids ...string) error {
+	if len(ids) == 0 {
+		return nil
+	}
+
+	s.remember(ids...)
+	return s.save()
+}
+
+func (s *Store) remember(ids ...string) {
+	for _, id := range ids {
+		if s.claimed[id] {
+			continue
+		}
+
+		s.claimed[id] = true
+		s.order = append(s.order, id)
+	}
+
+	for len(s.order) > s.limit {
+		delete(s.claimed, s.order[0])
+		s.order = s.order[1:]
+	}
+}
+
+// save writes the store to a temporary file first so a crash can't leave it half written
+func (s *Store) save() error {
+	if s.path == "" {
+		return nil
+	}
+
+	data, err := json.Marshal(storeFile{Claimed: s.order})
+	if err != nil {
+		return err
+	}
+
+	tmp := s.path + ".tmp"
+	if err := ioutil.WriteFile(tmp, data, 0600); err != nil {
+		return fmt.Errorf("writing %s: %s", tmp, err.Error())
+	}
+
+	if err := os.Rename(tmp, s.path); err != nil {
+		return fmt.Errorf("replacing %s: %s", s.path, err.Error())
+	}
+
+	return nil
+}
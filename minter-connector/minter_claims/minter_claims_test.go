@@ -0,0 +1,64 @@
+package minter_claims
+
+import (
+	"io/ioutil"
+	"os"
+	"testing"
+)
+
+func TestStorePersists(t *testing.T) {
+	dir, err := ioutil.TempDir("", "minter_claims")
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer os.RemoveAll(dir)
+
+	store, err := Open(dir, 2)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	if store.Claimed(EventID("Mt01", 0)) {
+		t.Fatal("New store should have nothing claimed")
+	}
+
+	if err := store.Add(EventID("Mt01", 0), EventID("Mt02", 0)); err != nil {
+		t.Fatal(err)
+	}
+
+	reopened, err := Open(dir, 2)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	if !reopened.Claimed(EventID("MT01", 0)) || !reopened.Claimed(EventID("Mt02", 0)) {
+		t.Fatal("Claimed events should survive a restart")
+	}
+
+	if reopened.Claimed(EventID("Mt01", 1)) {
+		t.Fatal("Other events of the same tx should not be claimed")
+	}
+
+	if err := reopened.Add(EventID("Mt03", 0)); err != nil {
+		t.Fatal(err)
+	}
+
+	if reopened.Claimed(EventID("Mt01", 0)) || !reopened.Claimed(EventID("Mt03", 0)) {
+		t.Fatal("Only the newest events should be kept")
+	}
+}
+
+func TestStoreInMemory(t *testing.T) {
+	store, err := Open("", 10)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	if err := store.Add(EventID("Mt01", 0)); err != nil {
+		t.Fatal(err)
+	}
+
+	if !store.Claimed(EventID("Mt01", 0)) {
+		t.Fatal("Events should be remembered without a state dir")
+	}
+}
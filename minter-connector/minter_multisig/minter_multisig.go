@@ -0,0 +1,289 @@
+// Package minter_multisig reconciles the members of the Minter multisig with the Hub's current valset. The Hub
+// asks for a new valset whenever the bonded power moves by more than 1%, once validators confirm it the relayer
+// installs it on the multisig with an edit. A multisig that was edited by hand, or a valset update that was
+// observed on the Hub but never made it to Minter, leaves the multisig signable by a set the Hub no longer
+// expects, which shows up as batches that never execute or, worse, as keys the Hub dropped still holding weight.
+//
+// The reconciler compares the two every CheckInterval and alerts on divergence. While a valset request is pending
+// on the Hub the rotation is already under way and only reported. Otherwise the multisig is rotated to the current
+// valset by asking the Hub for a new valset request, the usual confirm and relay steps then install it. The request
+// is only sent with --minter-rotate-multisig, without it the alert explains how to rotate.
+//
+// minter-init-multisig creates the multisig of a new bridge from the same valset weights.
+package minter_multisig
+
+import (
+	c "context"
+	"encoding/hex"
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/MinterTeam/mhub/chain/power"
+	"github.com/MinterTeam/mhub/chain/x/minter/types"
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
+	"github.com/MinterTeam/minter-go-sdk/v2/transaction"
+	"github.com/MinterTeam/minter-go-sdk/v2/wallet"
+	"github.com/MinterTeam/minter-hub-connector/minter_relay"
+	"github.com/ethereum/go-ethereum/crypto"
+	"github.com/ethereum/go-ethereum/rlp"
+	"github.com/tendermint/tendermint/libs/log"
+	"google.golang.org/grpc"
+)
+
+// CheckInterval is how often the multisig is compared with the Hub's valset
+const CheckInterval = 10 * time.Minute
+
+// WeightTolerance is how far a member's weight may drift from its share of the power, out of 1000. The Hub only
+// asks for a new valset once the power moved by 1%, and weights are rounded down
+const WeightTolerance = 10
+
+// MaxMembers is the most addresses a Minter multisig can have
+const MaxMembers = 32
+
+// Membership is a multisig's threshold and its members' weights by lowercase Minter address
+type Membership struct {
+	Threshold uint64
+	Weights   map[string]uint64
+}
+
+// MemberDifference is a member whose weight differs, a weight of 0 means it isn't a member
+type MemberDifference struct {
+	Address  string
+	Expected uint64
+	Actual   uint64
+}
+
+// Divergence is how the multisig differs from what the Hub expects
+type Divergence struct {
+	ExpectedThreshold uint64
+	ActualThreshold   uint64
+	Members           []MemberDifference
+}
+
+// Empty is true when the multisig matches within the tolerance
+func (d Divergence) Empty() bool {
+	return d.ExpectedThreshold == d.ActualThreshold && len(d.Members) == 0
+}
+
+func (d Divergence) String() string {
+	var parts []string
+	if d.ExpectedThreshold != d.ActualThreshold {
+		parts = append(parts, fmt.Sprintf("threshold %d, expected %d", d.ActualThreshold, d.ExpectedThreshold))
+	}
+
+	for _, member := range d.Members {
+		switch {
+		case member.Actual == 0:
+			parts = append(parts, fmt.Sprintf("%s missing, expected weight %d", member.Address, member.Expected))
+		case member.Expected == 0:
+			parts = append(parts, fmt.Sprintf("%s holds weight %d but is not in the valset", member.Address, member.Actual))
+		default:
+			parts = append(parts, fmt.Sprintf("%s holds weight %d, expected %d", member.Address, member.Actual, member.Expected))
+		}
+	}
+
+	return strings.Join(parts, "; ")
+}
+
+// Expected is the membership the valset is installed with, built from the same edit the relayer sends
+func Expected(valset *types.Valset) Membership {
+	txData := minter_relay.ValsetTxData(valset)
+	membership := Membership{Threshold: uint64(txData.Threshold), Weights: map[string]uint64{}}
+	for i, address := range txData.Addresses {
+		membership.Weights["mx"+hex.EncodeToString(address[:])] += uint64(txData.Weights[i])
+	}
+
+	return membership
+}
+
+// CreateTxData is the multisig creation for a new bridge, with the members and weights valset updates install
+func CreateTxData(valset *types.Valset) (*transaction.CreateMultisigData, error) {
+	seen := map[string]bool{}
+	for _, member := range valset.Members {
+		if member.MinterAddress == "" {
+			return nil, fmt.Errorf("a validator with power %d has not set its Minter address", member.Power)
+		}
+
+		if seen[strings.ToLower(member.MinterAddress)] {
+			return nil, fmt.Errorf("%s is the Minter address of two validators", member.MinterAddress)
+		}
+		seen[strings.ToLower(member.MinterAddress)] = true
+	}
+
+	if len(valset.Members) > MaxMembers {
+		return nil, fmt.Errorf("the valset has %d members, a Minter multisig at most %d", len(valset.Members), MaxMembers)
+	}
+
+	edit := minter_relay.ValsetTxData(valset)
+	var totalWeight uint64
+	for i, weight := range edit.Weights {
+		if weight > power.MaxMultisigWeight {
+			return nil, fmt.Errorf("%s would get weight %d, a Minter multisig member at most %d", valset.Members[i].MinterAddress, weight, power.MaxMultisigWeight)
+		}
+		totalWeight += uint64(weight)
+	}
+
+	// weights are rounded down, a valset of many small validators could lose enough to never reach the threshold
+	if !power.PassesMultisigThreshold(totalWeight) {
+		return nil, fmt.Errorf("the members' weights add up to %d, the multisig needs %d to send", totalWeight, edit.Threshold)
+	}
+
+	txData := transaction.NewCreateMultisigData()
+	txData.Threshold = edit.Threshold
+	txData.Weights = edit.Weights
+	txData.Addresses = edit.Addresses
+
+	return txData, nil
+}
+
+// Address is the address of the multisig created by owner's transaction with the given nonce, Minter derives
+// it from the two like Ethereum derives contract addresses
+func Address(owner string, nonce uint64) (string, error) {
+	bytes, err := wallet.AddressToHex(owner)
+	if err != nil {
+		return "", err
+	}
+
+	var ownerAddr [20]byte
+	copy(ownerAddr[:], bytes)
+
+	encoded, err := rlp.EncodeToBytes(&struct {
+		Owner [20]byte
+		Nonce uint64
+	}{ownerAddr, nonce})
+	if err != nil {
+		return "", err
+	}
+
+	return "Mx" + hex.EncodeToString(crypto.Keccak256(encoded)[12:]), nil
+}
+
+// FromMinter is the membership the multisig has on Minter
+func FromMinter(client *http_client.Client, multisigAddr string) (Membership, error) {
+	address, err := client.Address(multisigAddr)
+	if err != nil {
+		return Membership{}, err
+	}
+
+	if address.Multisig == nil {
+		return Membership{}, fmt.Errorf("%s is not a multisig", multisigAddr)
+	}
+
+	if len(address.Multisig.Addresses) != len(address.Multisig.Weights) {
+		return Membership{}, fmt.Errorf("%s has %d members but %d weights", multisigAddr, len(address.Multisig.Addresses), len(address.Multisig.Weights))
+	}
+
+	membership := Membership{Threshold: uint64(address.Multisig.Threshold), Weights: map[string]uint64{}}
+	for i, member := range address.Multisig.Addresses {
+		membership.Weights[strings.ToLower(member)] += uint64(address.Multisig.Weights[i])
+	}
+
+	return membership, nil
+}
+
+// Compare lists the members whose weights differ by more than WeightTolerance, by address
+func Compare(expected Membership, actual Membership) Divergence {
+	divergence := Divergence{ExpectedThreshold: expected.Threshold, ActualThreshold: actual.Threshold}
+
+	addresses := map[string]bool{}
+	for address := range expected.Weights {
+		addresses[address] = true
+	}
+	for address := range actual.Weights {
+		addresses[address] = true
+	}
+
+	for address := range addresses {
+		want, have := expected.Weights[address], actual.Weights[address]
+		var drift uint64
+		if want > have {
+			drift = want - have
+		} else {
+			drift = have - want
+		}
+
+		// membership itself never drifts
+		if drift > WeightTolerance || (want == 0) != (have == 0) {
+			divergence.Members = append(divergence.Members, MemberDifference{Address: address, Expected: want, Actual: have})
+		}
+	}
+
+	sort.Slice(divergence.Members, func(i, j int) bool {
+		return divergence.Members[i].Address < divergence.Members[j].Address
+	})
+
+	return divergence
+}
+
+// Reconciler periodically checks the multisig against the Hub's current valset
+type Reconciler struct {
+	CosmosConn   *grpc.ClientConn
+	MinterClient *http_client.Client
+	MultisigAddr string
+
+	// asks the Hub for a new valset to rotate a diverged multisig, nil leaves rotating to the operator
+	RequestValset func()
+	Logger        log.Logger
+
+	lastCheck time.Time
+	diverged  bool
+}
+
+// Check compares the multisig with the Hub's current valset once CheckInterval has passed since the last check
+func (r *Reconciler) Check() {
+	if time.Since(r.lastCheck) < CheckInterval {
+		return
+	}
+	r.lastCheck = time.Now()
+
+	cosmosClient := types.NewQueryClient(r.CosmosConn)
+	current, err := cosmosClient.CurrentValset(c.Background(), &types.QueryCurrentValsetRequest{})
+	if err != nil {
+		r.Logger.Error("Error getting the current valset", "err", err.Error())
+		return
+	}
+
+	if current.GetValset() == nil || len(current.GetValset().GetMembers()) == 0 {
+		return
+	}
+
+	actual, err := FromMinter(r.MinterClient, r.MultisigAddr)
+	if err != nil {
+		r.Logger.Error("Error getting the Minter multisig members", "err", err.Error())
+		return
+	}
+
+	divergence := Compare(Expected(current.GetValset()), actual)
+	if divergence.Empty() {
+		if r.diverged {
+			r.Logger.Info("Minter multisig matches the Hub valset again", "multisig", r.MultisigAddr)
+		}
+		r.diverged = false
+		return
+	}
+	r.diverged = true
+
+	pending, err := cosmosClient.LastValsetRequests(c.Background(), &types.QueryLastValsetRequestsRequest{})
+	if err != nil {
+		r.Logger.Error("Error getting the pending valset requests", "err", err.Error())
+		return
+	}
+
+	if len(pending.GetValsets()) > 0 {
+		nonce := pending.GetValsets()[0].GetNonce()
+		r.Logger.Info("Minter multisig differs from the Hub valset, rotation to valset is pending confirmations or relay", "nonce", nonce, "diff", divergence.String())
+		return
+	}
+
+	r.Logger.Error("MINTER MULTISIG DIVERGED from the Hub valset", "multisig", r.MultisigAddr, "diff", divergence.String())
+
+	if r.RequestValset == nil {
+		r.Logger.Error("Rotate the multisig by restarting a connector with --minter-rotate-multisig, it asks the Hub for a new valset which validators confirm and the relayer installs on Minter")
+		return
+	}
+
+	r.Logger.Info("Asking the Hub for a new valset to rotate the Minter multisig")
+	r.RequestValset()
+}
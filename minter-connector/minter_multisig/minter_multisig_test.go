@@ -0,0 +1,100 @@
+package minter_multisig
+
+import (
+	"testing"
+
+	"github.com/MinterTeam/mhub/chain/x/minter/types"
+	"github.com/MinterTeam/minter-hub-connector/minter_relay"
+)
+
+func TestCompare(t *testing.T) {
+	valset := &types.Valset{
+		Members: []*types.BridgeValidator{
+			{Power: 3, MinterAddress: "Mx0101010101010101010101010101010101010101"},
+			{Power: 1, MinterAddress: "Mx0202020202020202020202020202020202020202"},
+		},
+	}
+
+	expected := Expected(valset)
+	if expected.Threshold != minter_relay.Threshold || expected.Weights["mx0101010101010101010101010101010101010101"] != 750 {
+		t.Fatalf("Expected membership is %v", expected)
+	}
+
+	// weights drifting within the tolerance match
+	actual := Membership{Threshold: minter_relay.Threshold, Weights: map[string]uint64{
+		"mx0101010101010101010101010101010101010101": 745,
+		"mx0202020202020202020202020202020202020202": 255,
+	}}
+	if divergence := Compare(expected, actual); !divergence.Empty() {
+		t.Fatalf("Drift within the tolerance diverges: %s", divergence)
+	}
+
+	actual = Membership{Threshold: 500, Weights: map[string]uint64{
+		"mx0101010101010101010101010101010101010101": 700,
+		"mx0303030303030303030303030303030303030303": 300,
+	}}
+	divergence := Compare(expected, actual)
+	if divergence.Empty() || divergence.ActualThreshold != 500 {
+		t.Fatalf("Threshold change not found: %s", divergence)
+	}
+
+	if len(divergence.Members) != 3 {
+		t.Fatalf("Found %d member differences, expected 3: %s", len(divergence.Members), divergence)
+	}
+
+	if divergence.Members[1].Address != "mx0202020202020202020202020202020202020202" || divergence.Members[1].Actual != 0 {
+		t.Fatalf("Missing member not found: %s", divergence)
+	}
+
+	if divergence.Members[2].Expected != 0 || divergence.Members[2].Actual != 300 {
+		t.Fatalf("Unexpected member not found: %s", divergence)
+	}
+}
+
+func TestCreateTxData(t *testing.T) {
+	valset := &types.Valset{
+		Members: []*types.BridgeValidator{
+			{Power: 3, MinterAddress: "Mx0101010101010101010101010101010101010101"},
+			{Power: 1, MinterAddress: "Mx0202020202020202020202020202020202020202"},
+		},
+	}
+
+	txData, err := CreateTxData(valset)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	if txData.Threshold != minter_relay.Threshold || len(txData.Weights) != 2 || txData.Weights[0] != 750 {
+		t.Fatalf("Multisig is created with threshold %d and weights %v", txData.Threshold, txData.Weights)
+	}
+
+	zero := &types.Valset{
+		Members: []*types.BridgeValidator{
+			{Power: 0, MinterAddress: "Mx0101010101010101010101010101010101010101"},
+			{Power: 0, MinterAddress: "Mx0202020202020202020202020202020202020202"},
+		},
+	}
+	if _, err := CreateTxData(zero); err == nil {
+		t.Fatal("Multisig that can never reach its threshold accepted")
+	}
+
+	valset.Members[1].MinterAddress = ""
+	if _, err := CreateTxData(valset); err == nil {
+		t.Fatal("Member without a Minter address accepted")
+	}
+
+	valset.Members[1].MinterAddress = "mx0101010101010101010101010101010101010101"
+	if _, err := CreateTxData(valset); err == nil {
+		t.Fatal("Duplicate Minter address accepted")
+	}
+
+	first, err := Address("Mx0101010101010101010101010101010101010101", 1)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	second, _ := Address("Mx0101010101010101010101010101010101010101", 2)
+	if len(first) != 42 || first[:2] != "Mx" || first == second {
+		t.Fatalf("Multisig addresses %s and %s", first, second)
+	}
+}
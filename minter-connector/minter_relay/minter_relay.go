@@ -0,0 +1,290 @@
+// Package minter_relay submits the batches and valset updates signed on the Hub to the Minter multisig. It is
+// shared by the connector and the standalone minter-relayer. Relaying needs no keys: the signatures come from
+// the Hub and the multisig pays for its own transactions.
+package minter_relay
+
+import (
+	c "context"
+	"math/big"
+	"strconv"
+	"strings"
+
+	"github.com/MinterTeam/mhub/chain/power"
+	"github.com/MinterTeam/mhub/chain/x/minter/types"
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
+	"github.com/MinterTeam/minter-go-sdk/v2/transaction"
+	"github.com/MinterTeam/minter-go-sdk/v2/wallet"
+	"github.com/MinterTeam/minter-hub-connector/minter_simulate"
+	"github.com/tendermint/tendermint/libs/log"
+	"google.golang.org/grpc"
+)
+
+// Threshold is the multisig weight needed to send from it, out of power.MultisigTotal
+const Threshold = power.MultisigThreshold
+
+type Relayer struct {
+	CosmosConn   *grpc.ClientConn
+	MinterClient *http_client.Client
+	ChainID      transaction.ChainID
+	MultisigAddr string
+	Logger       log.Logger
+}
+
+// BatchTxData is the multisend paying out a batch, the same for signing and relaying. A batch built with
+// FoldOutputs pays each destination once per coin, in the order the destinations first appear
+func BatchTxData(batch *types.OutgoingTxBatch) *transaction.MultisendData {
+	txData := transaction.NewMultisendData()
+	if !batch.FoldOutputs {
+		for _, out := range batch.Transactions {
+			txData.AddItem(transaction.NewSendData().SetCoin(out.MinterToken.CoinId).MustSetTo(out.DestAddress).SetValue(out.MinterToken.Amount.BigInt()))
+		}
+
+		return txData
+	}
+
+	type output struct {
+		coin uint64
+		to   string
+	}
+	var order []*types.OutgoingTransferTx
+	values := map[output]*big.Int{}
+	for _, out := range batch.Transactions {
+		// addresses differing only in case are the same account
+		key := output{coin: out.MinterToken.CoinId, to: strings.ToLower(out.DestAddress)}
+		if _, ok := values[key]; !ok {
+			order = append(order, out)
+			values[key] = big.NewInt(0)
+		}
+		values[key].Add(values[key], out.MinterToken.Amount.BigInt())
+	}
+
+	for _, out := range order {
+		value := values[output{coin: out.MinterToken.CoinId, to: strings.ToLower(out.DestAddress)}]
+		txData.AddItem(transaction.NewSendData().SetCoin(out.MinterToken.CoinId).MustSetTo(out.DestAddress).SetValue(value))
+	}
+
+	return txData
+}
+
+// ValsetTxData is the multisig edit installing a valset, weights are the members' shares of the power
+func ValsetTxData(valset *types.Valset) *transaction.EditMultisigData {
+	txData := transaction.NewEditMultisigData()
+	txData.Threshold = Threshold
+
+	powers := make([]uint64, len(valset.Members))
+	for i, val := range valset.Members {
+		powers[i] = val.Power
+	}
+
+	for i, weight := range power.MultisigWeights(powers) {
+		var addr transaction.Address
+		bytes, _ := wallet.AddressToHex(valset.Members[i].MinterAddress)
+		copy(addr[:], bytes)
+
+		txData.Addresses = append(txData.Addresses, addr)
+		txData.Weights = append(txData.Weights, weight)
+	}
+
+	return txData
+}
+
+// RelayBatch sends the latest signed batch to Minter, batches below lastBatchNonce are known to be executed.
+// A batch executed by someone else fails simulation on its multisig nonce and is skipped
+func (r Relayer) RelayBatch(lastBatchNonce uint64) {
+	cosmosClient := types.NewQueryClient(r.CosmosConn)
+
+	latestBatches, err := cosmosClient.OutgoingTxBatches(c.Background(), &types.QueryOutgoingTxBatchesRequest{})
+	if err != nil {
+		r.Logger.Error("Error getting last batches", "err", err.Error())
+		return
+	}
+
+	var oldestSignedBatch *types.OutgoingTxBatch
+	var oldestSignatures []*types.MsgConfirmBatch
+
+	for _, batch := range latestBatches.Batches {
+		sigs, err := cosmosClient.BatchConfirms(c.Background(), &types.QueryBatchConfirmsRequest{
+			Nonce: batch.BatchNonce,
+		})
+		if err != nil {
+			r.Logger.Error("Error while getting batch confirms", "err", err.Error())
+			return
+		}
+
+		if sigs.Size() > 0 { // todo: check if we have enough votes
+			oldestSignedBatch = batch
+			oldestSignatures = sigs.Confirms
+		}
+	}
+
+	if oldestSignedBatch == nil {
+		return
+	}
+
+	if oldestSignedBatch.BatchNonce < lastBatchNonce {
+		return
+	}
+
+	r.Logger.Info("Sending batch to Minter")
+
+	tx, _ := transaction.NewBuilder(r.ChainID).NewTransaction(BatchTxData(oldestSignedBatch))
+	tx.SetNonce(oldestSignedBatch.MinterNonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeMulti)
+	signedTx, err := tx.Sign(r.MultisigAddr)
+	if err != nil {
+		panic(err)
+	}
+
+	lastValset, err := cosmosClient.LastValset(c.Background(), &types.QueryLastValsetRequest{})
+	if err != nil {
+		panic(err)
+	}
+
+	// Check if signer is in the last confirmed valset
+	for _, sig := range oldestSignatures {
+		for _, member := range lastValset.GetValset().GetMembers() {
+			if strings.ToLower(member.MinterAddress) == strings.ToLower(sig.MinterSigner) {
+				signedTx, err = signedTx.AddSignature(sig.Signature)
+				if err != nil {
+					panic(err)
+				}
+			}
+		}
+	}
+
+	encodedTx, err := signedTx.Encode()
+	if err != nil {
+		panic(err)
+	}
+
+	r.Logger.Debug("Batch tx", "tx", encodedTx)
+
+	spend := map[uint64]*big.Int{}
+	for _, out := range oldestSignedBatch.Transactions {
+		if _, ok := spend[out.MinterToken.CoinId]; !ok {
+			spend[out.MinterToken.CoinId] = big.NewInt(0)
+		}
+		spend[out.MinterToken.CoinId].Add(spend[out.MinterToken.CoinId], out.MinterToken.Amount.BigInt())
+	}
+
+	if err := minter_simulate.Simulate(r.MinterClient, r.MultisigAddr, encodedTx, oldestSignedBatch.MinterNonce, spend); err != nil {
+		r.logTxError("batch", "simulating", err)
+		return
+	}
+
+	response, err := r.MinterClient.SendTransaction(encodedTx)
+	if err != nil {
+		r.logTxError("batch", "sending", minter_simulate.FromHTTPError(err))
+	} else if txErr := minter_simulate.FromCode(response.Code, response.Log); txErr != nil {
+		r.logTxError("batch", "sending", txErr)
+	}
+}
+
+// RelayValset sends the latest signed valset update to Minter, valsets below lastValsetNonce are known to be
+// installed. One installed by someone else fails simulation on its multisig nonce and is skipped
+func (r Relayer) RelayValset(lastValsetNonce uint64) {
+	cosmosClient := types.NewQueryClient(r.CosmosConn)
+
+	latestValsets, err := cosmosClient.LastValsetRequests(c.Background(), &types.QueryLastValsetRequestsRequest{})
+	if err != nil {
+		r.Logger.Error("Error on getting last valset requests", "err", err.Error())
+		return
+	}
+
+	var oldestSignedValset *types.Valset
+	var oldestSignatures []*types.MsgValsetConfirm
+
+	for _, valset := range latestValsets.Valsets {
+		sigs, err := cosmosClient.ValsetConfirmsByNonce(c.Background(), &types.QueryValsetConfirmsByNonceRequest{
+			Nonce: valset.Nonce,
+		})
+		if err != nil {
+			r.Logger.Error("Error while getting valset confirms", "err", err.Error())
+			return
+		}
+
+		if sigs.Size() > 0 { // todo: check if we have enough votes
+			oldestSignedValset = valset
+			oldestSignatures = sigs.Confirms
+		}
+	}
+
+	if oldestSignedValset == nil {
+		return
+	}
+
+	if oldestSignedValset.Nonce < lastValsetNonce {
+		return
+	}
+
+	r.Logger.Info("Sending valset to Minter")
+
+	tx, _ := transaction.NewBuilder(r.ChainID).NewTransaction(ValsetTxData(oldestSignedValset))
+	tx.SetNonce(oldestSignedValset.MinterNonce).SetGasPrice(1).SetGasCoin(0).SetSignatureType(transaction.SignatureTypeMulti)
+	tx.SetPayload([]byte(strconv.Itoa(int(oldestSignedValset.Nonce))))
+	signedTx, err := tx.Sign(r.MultisigAddr)
+	if err != nil {
+		panic(err)
+	}
+
+	lastValset, err := cosmosClient.LastValset(c.Background(), &types.QueryLastValsetRequest{})
+	if err != nil {
+		panic(err)
+	}
+
+	// Check if signer is in the last confirmed valset
+	for _, sig := range oldestSignatures {
+		hasMember := false
+		for _, member := range lastValset.GetValset().GetMembers() {
+			if strings.ToLower(member.MinterAddress) == strings.ToLower(sig.MinterAddress) {
+				hasMember = true
+			}
+		}
+
+		if hasMember || len(lastValset.GetValset().GetMembers()) == 0 {
+			signedTx, err = signedTx.AddSignature(sig.Signature)
+			if err != nil {
+				panic(err)
+			}
+		}
+	}
+
+	encodedTx, err := signedTx.Encode()
+	if err != nil {
+		panic(err)
+	}
+
+	r.Logger.Debug("Valset update tx", "tx", encodedTx)
+
+	if err := minter_simulate.Simulate(r.MinterClient, r.MultisigAddr, encodedTx, oldestSignedValset.MinterNonce, nil); err != nil {
+		r.logTxError("valset", "simulating", err)
+		return
+	}
+
+	response, err := r.MinterClient.SendTransaction(encodedTx)
+	if err != nil {
+		r.logTxError("valset", "sending", minter_simulate.FromHTTPError(err))
+	} else if txErr := minter_simulate.FromCode(response.Code, response.Log); txErr != nil {
+		r.logTxError("valset", "sending", txErr)
+	}
+}
+
+// logTxError reports a Minter tx rejected on simulation or broadcast according to its retry policy,
+// the tx is rebuilt and tried again on the next loop in every case
+func (r Relayer) logTxError(kind string, stage string, err error) {
+	txErr, ok := err.(*minter_simulate.Error)
+	if !ok {
+		r.Logger.Error("Error on "+stage+" Minter Tx", "kind", kind, "err", err.Error())
+		return
+	}
+
+	switch txErr.Action() {
+	case minter_simulate.Skip:
+		r.Logger.Info("Minter Tx already executed, skipping", "kind", kind, "err", txErr.Error())
+	case minter_simulate.Wait:
+		r.Logger.Info("Minter Tx not executable yet, waiting", "kind", kind, "err", txErr.Error())
+	case minter_simulate.Alert:
+		r.Logger.Error("Minter Tx can't succeed without operator action", "kind", kind, "err", txErr.Error())
+	default:
+		r.Logger.Error("Error on "+stage+" Minter Tx", "kind", kind, "code", txErr.Code, "err", txErr.Error())
+	}
+}
@@ -0,0 +1,61 @@
+package minter_relay
+
+import (
+	"testing"
+
+	"github.com/MinterTeam/mhub/chain/x/minter/types"
+	sdk "github.com/cosmos/cosmos-sdk/types"
+)
+
+func TestValsetTxData(t *testing.T) {
+	valset := &types.Valset{
+		Nonce: 2,
+		Members: []*types.BridgeValidator{
+			{Power: 3, MinterAddress: "Mx0101010101010101010101010101010101010101"},
+			{Power: 1, MinterAddress: "Mx0202020202020202020202020202020202020202"},
+		},
+	}
+
+	txData := ValsetTxData(valset)
+	if txData.Threshold != Threshold {
+		t.Fatalf("Threshold is %d, expected %d", txData.Threshold, Threshold)
+	}
+
+	if len(txData.Weights) != 2 || txData.Weights[0] != 750 || txData.Weights[1] != 250 {
+		t.Fatalf("Weights are %v, expected [750 250]", txData.Weights)
+	}
+
+	if txData.Addresses[1][0] != 0x02 {
+		t.Fatalf("Second address is %x", txData.Addresses[1])
+	}
+}
+
+func TestBatchTxDataFoldsOutputs(t *testing.T) {
+	out := func(to string, coin uint64, amount int64) *types.OutgoingTransferTx {
+		return &types.OutgoingTransferTx{DestAddress: to, MinterToken: &types.MinterCoin{CoinId: coin, Amount: sdk.NewInt(amount)}}
+	}
+	batch := &types.OutgoingTxBatch{
+		Transactions: []*types.OutgoingTransferTx{
+			out("Mx0101010101010101010101010101010101010101", 1, 10),
+			out("Mx0202020202020202020202020202020202020202", 1, 20),
+			out("Mx0101010101010101010101010101010101010101", 1, 5),
+			out("Mx0101010101010101010101010101010101010101", 2, 7),
+		},
+	}
+
+	if items := BatchTxData(batch).List; len(items) != 4 {
+		t.Fatalf("Unfolded batch has %d items, expected 4", len(items))
+	}
+
+	batch.FoldOutputs = true
+	items := BatchTxData(batch).List
+	if len(items) != 3 {
+		t.Fatalf("Folded batch has %d items, expected 3", len(items))
+	}
+	if items[0].Value.Int64() != 15 || items[0].Coin != 1 || items[0].To[0] != 0x01 {
+		t.Fatalf("First item pays %d of coin %d to %x, expected 15 of coin 1", items[0].Value, items[0].Coin, items[0].To)
+	}
+	if items[1].Value.Int64() != 20 || items[2].Value.Int64() != 7 || items[2].Coin != 2 {
+		t.Fatalf("Items are out of order or misfolded: %v", items)
+	}
+}
@@ -0,0 +1,84 @@
+package minter_reorg
+
+import (
+	"errors"
+	"fmt"
+	"strings"
+)
+
+// ErrReorgTooDeep is returned when none of the remembered blocks are on the chain the node
+// serves anymore, the scan has to be restarted from the configured start block
+var ErrReorgTooDeep = errors.New("none of the tracked Minter blocks match the node, reorg is deeper than the tracking window")
+
+// Checkpoint is the scan cursor after a block was processed together with that block's hash
+type Checkpoint struct {
+	Height      uint64
+	Hash        string
+	EventNonce  uint64
+	BatchNonce  uint64
+	ValsetNonce uint64
+}
+
+// Tracker remembers the hashes of recently scanned Minter blocks. Before each scan the newest
+// remembered block is compared against the node, on a mismatch the node switched to a fork and
+// the scan is rewound to the newest block both chains still share.
+type Tracker struct {
+	checkpoints []Checkpoint
+	window      int
+}
+
+// New creates a tracker remembering up to window blocks
+func New(window int) *Tracker {
+	if window < 1 {
+		window = 1
+	}
+
+	return &Tracker{window: window}
+}
+
+// Record adds a scanned block, blocks must be recorded in increasing height order
+func (t *Tracker) Record(checkpoint Checkpoint) {
+	t.checkpoints = append(t.checkpoints, checkpoint)
+	if len(t.checkpoints) > t.window {
+		t.checkpoints = t.checkpoints[len(t.checkpoints)-t.window:]
+	}
+}
+
+// Reset forgets all recorded blocks
+func (t *Tracker) Reset() {
+	t.checkpoints = nil
+}
+
+// Check compares the recorded blocks against the hashes the node returns, newest first. It returns
+// the newest checkpoint still on the node's chain and whether anything after it had to be dropped.
+// With nothing recorded there is nothing to compare and ok is false.
+func (t *Tracker) Check(hashAt func(height uint64) (string, error)) (checkpoint Checkpoint, rewound bool, ok bool, err error) {
+	if len(t.checkpoints) == 0 {
+		return Checkpoint{}, false, false, nil
+	}
+
+	for i := len(t.checkpoints) - 1; i >= 0; i-- {
+		hash, err := hashAt(t.checkpoints[i].Height)
+		if err != nil {
+			return Checkpoint{}, false, false, fmt.Errorf("getting hash of block %d: %s", t.checkpoints[i].Height, err.Error())
+		}
+
+		if strings.EqualFold(hash, t.checkpoints[i].Hash) {
+			rewound = i != len(t.checkpoints)-1
+			t.checkpoints = t.checkpoints[:i+1]
+			return t.checkpoints[i], rewound, true, nil
+		}
+	}
+
+	t.checkpoints = nil
+	return Checkpoint{}, false, false, ErrReorgTooDeep
+}
+
+// ConfirmedHeight returns the newest height that has the required number of blocks on top of it
+func ConfirmedHeight(latest uint64, confirmations uint64) uint64 {
+	if latest < confirmations {
+		return 0
+	}
+
+	return latest - confirmations
+}
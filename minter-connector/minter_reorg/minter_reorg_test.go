@@ -0,0 +1,62 @@
+package minter_reorg
+
+import (
+	"errors"
+	"testing"
+)
+
+func chain(hashes map[uint64]string) func(uint64) (string, error) {
+	return func(height uint64) (string, error) {
+		hash, ok := hashes[height]
+		if !ok {
+			return "", errors.New("unknown block")
+		}
+		return hash, nil
+	}
+}
+
+func TestTrackerCheck(t *testing.T) {
+	tracker := New(10)
+	if _, _, ok, err := tracker.Check(chain(nil)); ok || err != nil {
+		t.Fatal("Empty tracker should have nothing to compare")
+	}
+
+	tracker.Record(Checkpoint{Height: 1, Hash: "A1", EventNonce: 1})
+	tracker.Record(Checkpoint{Height: 2, Hash: "A2", EventNonce: 2})
+	tracker.Record(Checkpoint{Height: 3, Hash: "A3", EventNonce: 3})
+
+	checkpoint, rewound, ok, err := tracker.Check(chain(map[uint64]string{1: "A1", 2: "A2", 3: "a3"}))
+	if err != nil || !ok || rewound || checkpoint.Height != 3 {
+		t.Fatalf("Same chain should not rewind: %+v %v", checkpoint, err)
+	}
+
+	checkpoint, rewound, ok, err = tracker.Check(chain(map[uint64]string{1: "A1", 2: "B2", 3: "B3"}))
+	if err != nil || !ok || !rewound || checkpoint.Height != 1 || checkpoint.EventNonce != 1 {
+		t.Fatalf("Fork should rewind to the common block: %+v %v", checkpoint, err)
+	}
+
+	if _, _, _, err = tracker.Check(chain(map[uint64]string{1: "C1"})); err != ErrReorgTooDeep {
+		t.Fatalf("Fork below the window should be reported: %v", err)
+	}
+}
+
+func TestTrackerWindow(t *testing.T) {
+	tracker := New(2)
+	for height := uint64(1); height <= 5; height++ {
+		tracker.Record(Checkpoint{Height: height, Hash: "A"})
+	}
+
+	if len(tracker.checkpoints) != 2 || tracker.checkpoints[0].Height != 4 {
+		t.Fatalf("Tracker should only keep the newest blocks: %+v", tracker.checkpoints)
+	}
+}
+
+func TestConfirmedHeight(t *testing.T) {
+	if ConfirmedHeight(100, 5) != 95 {
+		t.Fatal("Unexpected confirmed height")
+	}
+
+	if ConfirmedHeight(3, 5) != 0 {
+		t.Fatal("Confirmed height should not underflow")
+	}
+}
@@ -0,0 +1,212 @@
+package minter_simulate
+
+import (
+	"fmt"
+	"math/big"
+	"strconv"
+
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
+)
+
+// Minter node result codes we know how to react to
+const (
+	CodeWrongNonce        uint64 = 101
+	CodeCoinNotExists     uint64 = 102
+	CodeInsufficientFunds uint64 = 107
+)
+
+// Reason classifies why the Minter node would reject a transaction
+type Reason int
+
+const (
+	WrongNonce Reason = iota + 1
+	CoinNotExists
+	InsufficientFunds
+	Other
+)
+
+func (r Reason) String() string {
+	switch r {
+	case WrongNonce:
+		return "wrong nonce"
+	case CoinNotExists:
+		return "coin does not exist"
+	case InsufficientFunds:
+		return "insufficient multisig balance"
+	default:
+		return "other"
+	}
+}
+
+// Action is what the connector should do about a rejected transaction
+type Action int
+
+const (
+	// Skip the transaction, it has already been executed by someone else
+	Skip Action = iota + 1
+	// Wait for an earlier transaction to land or for the multisig to be topped up
+	Wait
+	// Alert the operator, the transaction can't succeed until someone intervenes
+	Alert
+	// Retry on the next loop, the failure may be transient
+	Retry
+)
+
+// Error is a typed rejection of a Minter transaction, either predicted by Simulate
+// or returned by the node on broadcast
+type Error struct {
+	Reason  Reason
+	Code    uint64
+	Message string
+
+	// set for nonce errors found by Simulate
+	ExpectedNonce uint64
+	TxNonce       uint64
+}
+
+func (e *Error) Error() string {
+	return fmt.Sprintf("%s (code %d): %s", e.Reason.String(), e.Code, e.Message)
+}
+
+// Action returns the retry policy for this rejection
+func (e *Error) Action() Action {
+	switch e.Reason {
+	case WrongNonce:
+		if e.ExpectedNonce == 0 && e.TxNonce == 0 {
+			return Retry
+		}
+		if e.TxNonce < e.ExpectedNonce {
+			return Skip
+		}
+		return Wait
+	case InsufficientFunds:
+		return Wait
+	case CoinNotExists:
+		return Alert
+	default:
+		return Retry
+	}
+}
+
+// FromCode maps a Minter node result code to a typed error, nil for success
+func FromCode(code uint64, message string) *Error {
+	switch code {
+	case 0:
+		return nil
+	case CodeWrongNonce:
+		return &Error{Reason: WrongNonce, Code: code, Message: message}
+	case CodeCoinNotExists:
+		return &Error{Reason: CoinNotExists, Code: code, Message: message}
+	case CodeInsufficientFunds:
+		return &Error{Reason: InsufficientFunds, Code: code, Message: message}
+	default:
+		return &Error{Reason: Other, Code: code, Message: message}
+	}
+}
+
+// FromHTTPError maps an error returned by the Minter http client to a typed error
+func FromHTTPError(err error) *Error {
+	_, body, bodyErr := http_client.ErrorBody(err)
+	if bodyErr != nil {
+		return &Error{Reason: Other, Message: err.Error()}
+	}
+
+	code, parseErr := strconv.ParseUint(body.Error.Code, 10, 64)
+	if parseErr != nil || code == 0 {
+		return &Error{Reason: Other, Message: body.Error.Message}
+	}
+
+	return FromCode(code, body.Error.Message)
+}
+
+// Simulate dry runs an encoded multisig transaction before it is broadcast. The node's commission
+// estimation decodes and prices the transaction, the nonce, coins and balances the node would check
+// on delivery are checked against its current state. spend is the amount of each coin id the
+// transaction sends out of the multisig, the commission is paid in the base coin.
+func Simulate(client *http_client.Client, multisig string, encodedTx string, nonce uint64, spend map[uint64]*big.Int) error {
+	estimate, err := client.EstimateTxCommission(encodedTx)
+	if err != nil {
+		return FromHTTPError(err)
+	}
+
+	commission, ok := new(big.Int).SetString(estimate.Commission, 10)
+	if !ok {
+		return &Error{Reason: Other, Message: fmt.Sprintf("invalid commission %s", estimate.Commission)}
+	}
+
+	expectedNonce, err := client.Nonce(multisig)
+	if err != nil {
+		return FromHTTPError(err)
+	}
+
+	if err := checkNonce(expectedNonce, nonce); err != nil {
+		return err
+	}
+
+	for coinID := range spend {
+		if _, err := client.CoinInfoByID(coinID); err != nil {
+			return FromHTTPError(err)
+		}
+	}
+
+	address, err := client.Address(multisig)
+	if err != nil {
+		return FromHTTPError(err)
+	}
+
+	balances := map[uint64]*big.Int{}
+	for _, balance := range address.Balance {
+		value, ok := new(big.Int).SetString(balance.Value, 10)
+		if !ok {
+			return &Error{Reason: Other, Message: fmt.Sprintf("invalid balance %s of coin %d", balance.Value, balance.Coin.ID)}
+		}
+		balances[balance.Coin.ID] = value
+	}
+
+	return checkBalances(balances, withCommission(spend, commission))
+}
+
+func checkNonce(expected, nonce uint64) error {
+	if expected == nonce {
+		return nil
+	}
+
+	return &Error{
+		Reason:        WrongNonce,
+		Code:          CodeWrongNonce,
+		Message:       fmt.Sprintf("multisig expects nonce %d, tx has %d", expected, nonce),
+		ExpectedNonce: expected,
+		TxNonce:       nonce,
+	}
+}
+
+func withCommission(spend map[uint64]*big.Int, commission *big.Int) map[uint64]*big.Int {
+	total := map[uint64]*big.Int{0: new(big.Int).Set(commission)}
+	for coinID, value := range spend {
+		if _, ok := total[coinID]; !ok {
+			total[coinID] = big.NewInt(0)
+		}
+		total[coinID].Add(total[coinID], value)
+	}
+
+	return total
+}
+
+func checkBalances(balances map[uint64]*big.Int, required map[uint64]*big.Int) error {
+	for coinID, value := range required {
+		balance, ok := balances[coinID]
+		if !ok {
+			balance = big.NewInt(0)
+		}
+
+		if balance.Cmp(value) < 0 {
+			return &Error{
+				Reason:  InsufficientFunds,
+				Code:    CodeInsufficientFunds,
+				Message: fmt.Sprintf("multisig has %s of coin %d, needs %s", balance.String(), coinID, value.String()),
+			}
+		}
+	}
+
+	return nil
+}
@@ -0,0 +1,57 @@
+package minter_simulate
+
+import (
+	"math/big"
+	"testing"
+)
+
+func TestFromCode(t *testing.T) {
+	if FromCode(0, "") != nil {
+		t.Fatal("Code 0 is not an error")
+	}
+
+	if err := FromCode(CodeInsufficientFunds, "not enough"); err.Reason != InsufficientFunds || err.Action() != Wait {
+		t.Fatalf("Unexpected mapping for insufficient funds: %s", err.Error())
+	}
+
+	if err := FromCode(CodeCoinNotExists, "no coin"); err.Action() != Alert {
+		t.Fatalf("Missing coin should alert the operator: %s", err.Error())
+	}
+
+	if err := FromCode(500, "unknown"); err.Reason != Other || err.Action() != Retry {
+		t.Fatalf("Unknown codes should be retried: %s", err.Error())
+	}
+}
+
+func TestCheckNonce(t *testing.T) {
+	if err := checkNonce(5, 5); err != nil {
+		t.Fatalf("Matching nonce should pass: %s", err.Error())
+	}
+
+	stale := checkNonce(6, 5).(*Error)
+	if stale.Action() != Skip {
+		t.Fatal("A nonce below the expected one was already used and should be skipped")
+	}
+
+	early := checkNonce(5, 6).(*Error)
+	if early.Action() != Wait {
+		t.Fatal("A nonce above the expected one should wait for the earlier tx")
+	}
+}
+
+func TestCheckBalances(t *testing.T) {
+	balances := map[uint64]*big.Int{0: big.NewInt(100), 1: big.NewInt(50)}
+
+	if err := checkBalances(balances, withCommission(map[uint64]*big.Int{1: big.NewInt(50)}, big.NewInt(10))); err != nil {
+		t.Fatalf("Sufficient balance should pass: %s", err.Error())
+	}
+
+	// the commission is paid in the base coin on top of what is sent
+	if err := checkBalances(balances, withCommission(map[uint64]*big.Int{0: big.NewInt(95)}, big.NewInt(10))); err == nil {
+		t.Fatal("Commission should count against the base coin balance")
+	}
+
+	if err := checkBalances(balances, withCommission(map[uint64]*big.Int{2: big.NewInt(1)}, big.NewInt(0))); err == nil {
+		t.Fatal("Coins the multisig doesn't hold should fail")
+	}
+}
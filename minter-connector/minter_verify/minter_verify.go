@@ -0,0 +1,200 @@
+package minter_verify
+
+import (
+	"bytes"
+	"errors"
+	"fmt"
+	"strings"
+
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client"
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client/models"
+	"github.com/tendermint/tendermint/libs/log"
+)
+
+// Verifier cross-checks Minter transactions observed on the primary node against
+// a set of independently operated Minter nodes. A deposit is only considered valid
+// if every verifier node agrees on the raw transaction, its inclusion height, its
+// result code, the hash of the block it was included in and every field the deposit
+// claim is built from: the sender, the payload and the decoded send data. This way a single
+// compromised Minter API node can't trick a validator into signing a fraudulent claim.
+type Verifier struct {
+	clients []*http_client.Client
+	urls    []string
+	logger  log.Logger
+}
+
+// New creates a verifier for the given node urls, an empty list disables verification
+func New(urls []string, logger log.Logger) (*Verifier, error) {
+	verifier := &Verifier{logger: logger}
+	for _, url := range urls {
+		url = strings.TrimSpace(url)
+		if url == "" {
+			continue
+		}
+
+		client, err := http_client.New(url)
+		if err != nil {
+			return nil, fmt.Errorf("creating verifier client for %s: %s", url, err.Error())
+		}
+
+		verifier.clients = append(verifier.clients, client)
+		verifier.urls = append(verifier.urls, url)
+	}
+
+	return verifier, nil
+}
+
+// Enabled reports whether at least one independent node is configured
+func (v *Verifier) Enabled() bool {
+	return v != nil && len(v.clients) > 0
+}
+
+// VerifyTransaction checks that the transaction seen by the primary node in the block
+// with the given hash is reported identically by all verifier nodes
+func (v *Verifier) VerifyTransaction(blockHash string, tx *models.TransactionResponse) error {
+	if !v.Enabled() {
+		return nil
+	}
+
+	if tx.Code != 0 {
+		return fmt.Errorf("tx %s has non-zero code %d on primary node", tx.Hash, tx.Code)
+	}
+
+	for i, client := range v.clients {
+		remoteTx, err := client.Transaction(tx.Hash)
+		if err != nil {
+			return fmt.Errorf("verifier %s: cannot get tx %s: %s", v.urls[i], tx.Hash, describeError(err))
+		}
+
+		if err := compareTransactions(tx, remoteTx); err != nil {
+			return fmt.Errorf("verifier %s: %s", v.urls[i], err.Error())
+		}
+
+		if err := compareDeposits(tx, remoteTx); err != nil {
+			return fmt.Errorf("verifier %s: %s", v.urls[i], err.Error())
+		}
+
+		remoteBlock, err := client.Block(tx.Height)
+		if err != nil {
+			return fmt.Errorf("verifier %s: cannot get block %d: %s", v.urls[i], tx.Height, describeError(err))
+		}
+
+		if !strings.EqualFold(remoteBlock.Hash, blockHash) {
+			return fmt.Errorf("verifier %s: block %d hash mismatch, primary %s, verifier %s", v.urls[i], tx.Height, blockHash, remoteBlock.Hash)
+		}
+	}
+
+	v.logger.Debug("Minter tx verified", "hash", tx.Hash, "verifiers", len(v.clients))
+
+	return nil
+}
+
+func compareTransactions(primary, remote *models.TransactionResponse) error {
+	if remote == nil {
+		return errors.New("empty tx response")
+	}
+
+	if !strings.EqualFold(primary.Hash, remote.Hash) {
+		return fmt.Errorf("tx hash mismatch, primary %s, verifier %s", primary.Hash, remote.Hash)
+	}
+
+	if !strings.EqualFold(primary.RawTx, remote.RawTx) {
+		return fmt.Errorf("tx %s raw data mismatch", primary.Hash)
+	}
+
+	if primary.Height != remote.Height {
+		return fmt.Errorf("tx %s height mismatch, primary %d, verifier %d", primary.Hash, primary.Height, remote.Height)
+	}
+
+	if remote.Code != 0 {
+		return fmt.Errorf("tx %s has non-zero code %d on verifier", primary.Hash, remote.Code)
+	}
+
+	return nil
+}
+
+// Deposit holds the fields of a send transaction a deposit claim is built from
+type Deposit struct {
+	From    string
+	Payload []byte
+	To      string
+	Value   string
+	CoinID  uint64
+}
+
+// DepositOf decodes the claim fields of a send transaction
+func DepositOf(tx *models.TransactionResponse) (*Deposit, error) {
+	if tx.Data == nil {
+		return nil, fmt.Errorf("tx %s has no data", tx.Hash)
+	}
+
+	data, err := tx.Data.UnmarshalNew()
+	if err != nil {
+		return nil, fmt.Errorf("tx %s data cannot be decoded: %s", tx.Hash, err.Error())
+	}
+
+	sendData, ok := data.(*models.SendData)
+	if !ok || sendData.Coin == nil {
+		return nil, fmt.Errorf("tx %s is not a send", tx.Hash)
+	}
+
+	return &Deposit{
+		From:    tx.From,
+		Payload: tx.Payload,
+		To:      sendData.To,
+		Value:   sendData.Value,
+		CoinID:  sendData.Coin.ID,
+	}, nil
+}
+
+func compareDeposits(primary, remote *models.TransactionResponse) error {
+	if primary.Type != remote.Type {
+		return fmt.Errorf("tx %s type mismatch, primary %d, verifier %d", primary.Hash, primary.Type, remote.Type)
+	}
+
+	primaryDeposit, err := DepositOf(primary)
+	if err != nil {
+		return err
+	}
+
+	remoteDeposit, err := DepositOf(remote)
+	if err != nil {
+		return err
+	}
+
+	return primaryDeposit.Compare(remoteDeposit)
+}
+
+// Compare returns an error naming the first field the deposits disagree on
+func (d *Deposit) Compare(other *Deposit) error {
+	if !strings.EqualFold(d.From, other.From) {
+		return fmt.Errorf("sender mismatch, primary %s, verifier %s", d.From, other.From)
+	}
+
+	if !bytes.Equal(d.Payload, other.Payload) {
+		return fmt.Errorf("payload mismatch, primary %q, verifier %q", d.Payload, other.Payload)
+	}
+
+	if !strings.EqualFold(d.To, other.To) {
+		return fmt.Errorf("recipient mismatch, primary %s, verifier %s", d.To, other.To)
+	}
+
+	if d.Value != other.Value {
+		return fmt.Errorf("amount mismatch, primary %s, verifier %s", d.Value, other.Value)
+	}
+
+	if d.CoinID != other.CoinID {
+		return fmt.Errorf("coin mismatch, primary %d, verifier %d", d.CoinID, other.CoinID)
+	}
+
+	return nil
+}
+
+func describeError(err error) string {
+	_, body, bodyErr := http_client.ErrorBody(err)
+	if bodyErr != nil {
+		return err.Error()
+	}
+
+	return body.Error.Message
+}
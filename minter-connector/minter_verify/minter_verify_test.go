@@ -0,0 +1,94 @@
+package minter_verify
+
+import (
+	"testing"
+
+	"github.com/MinterTeam/minter-go-sdk/v2/api/http_client/models"
+)
+
+func TestCompareTransactions(t *testing.T) {
+	primary := &models.TransactionResponse{
+		Hash:   "Mt9a0a39a2e9d8e0fa26dd4f9d4b4a5c1ba3c5f1cd68e3a6a3d0d0b7f1b2c3d4e5",
+		RawTx:  "f88a0102018a4d4e540000000000000001aae98a4d4e5400000000000000",
+		Height: 100,
+	}
+
+	same := *primary
+	if err := compareTransactions(primary, &same); err != nil {
+		t.Fatalf("Identical transactions should match: %s", err.Error())
+	}
+
+	forged := *primary
+	forged.RawTx = "f88a0102018a4d4e540000000000000001aae98a4d4e5400000000000001"
+	if err := compareTransactions(primary, &forged); err == nil {
+		t.Fatal("Transactions with different raw data should not match")
+	}
+
+	moved := *primary
+	moved.Height = 101
+	if err := compareTransactions(primary, &moved); err == nil {
+		t.Fatal("Transactions included at different heights should not match")
+	}
+
+	failed := *primary
+	failed.Code = 107
+	if err := compareTransactions(primary, &failed); err == nil {
+		t.Fatal("Failed transactions should not match")
+	}
+}
+
+func TestCompareDeposits(t *testing.T) {
+	primary := &Deposit{
+		From:    "Mx7633980c000139dd3bd24a3f54e06474fa941e16",
+		Payload: []byte(`{"type":"send_to_eth","recipient":"0x7580bFE88Dd3d07947908FAE12d95872a260F2D8","fee":"1"}`),
+		To:      "Mx68f4839d7f32831b9234f9575f3b95e1afe21a56",
+		Value:   "1000000000000000000",
+		CoinID:  1,
+	}
+
+	same := *primary
+	if err := primary.Compare(&same); err != nil {
+		t.Fatalf("Identical deposits should match: %s", err.Error())
+	}
+
+	amount := *primary
+	amount.Value = "9000000000000000000"
+	if err := primary.Compare(&amount); err == nil {
+		t.Fatal("Deposits with different amounts should not match")
+	}
+
+	recipient := *primary
+	recipient.Payload = []byte(`{"type":"send_to_eth","recipient":"0x0000000000000000000000000000000000000001","fee":"1"}`)
+	if err := primary.Compare(&recipient); err == nil {
+		t.Fatal("Deposits to different recipients should not match")
+	}
+
+	sender := *primary
+	sender.From = "Mx0000000000000000000000000000000000000001"
+	if err := primary.Compare(&sender); err == nil {
+		t.Fatal("Deposits from different senders should not match")
+	}
+
+	coin := *primary
+	coin.CoinID = 2
+	if err := primary.Compare(&coin); err == nil {
+		t.Fatal("Deposits of different coins should not match")
+	}
+
+	multisig := *primary
+	multisig.To = "Mx0000000000000000000000000000000000000002"
+	if err := primary.Compare(&multisig); err == nil {
+		t.Fatal("Deposits to different addresses should not match")
+	}
+}
+
+func TestDisabledVerifier(t *testing.T) {
+	var verifier *Verifier
+	if verifier.Enabled() {
+		t.Fatal("Nil verifier should be disabled")
+	}
+
+	if err := verifier.VerifyTransaction("", &models.TransactionResponse{Code: 1}); err != nil {
+		t.Fatalf("Disabled verifier should accept everything: %s", err.Error())
+	}
+}
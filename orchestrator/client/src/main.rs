@@ -16,11 +16,15 @@ use clarity::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
 use clarity::Uint256;
 use contact::client::Contact;
-use cosmos_peggy::send::{send_request_batch, send_to_eth};
+use cosmos_peggy::offline::export_tx;
+use cosmos_peggy::send::{request_batch_msg, send_request_batch, send_to_eth, send_to_eth_msg};
 use deep_space::address::Address as CosmosAddress;
 use deep_space::{coin::Coin, private_key::PrivateKey as CosmosPrivateKey};
 use docopt::Docopt;
 use ethereum_peggy::send_to_cosmos::send_to_cosmos;
+use peggy_utils::address_book::AddressBook;
+use peggy_utils::secrets::resolve_secret;
+use std::path::Path;
 use std::time::Duration;
 use url::Url;
 use web30::client::Web3;
@@ -39,6 +43,10 @@ struct Args {
     flag_cosmos_destination: String,
     flag_erc20_address: String,
     flag_eth_destination: String,
+    flag_memo: Option<String>,
+    flag_sender: String,
+    flag_offline: bool,
+    flag_address_book: Option<String>,
     cmd_eth_to_cosmos: bool,
     cmd_cosmos_to_eth: bool,
 }
@@ -46,8 +54,9 @@ struct Args {
 lazy_static! {
     pub static ref USAGE: String = format!(
     "Usage:
-        {} cosmos-to-eth --cosmos-phrase=<key> --cosmos-rpc=<url> --fees=<denom> --erc20-address=<addr> --amount=<amount> --eth-destination=<dest>
-        {} eth-to-cosmos --ethereum-key=<key> --ethereum-rpc=<url> --contract-address=<addr> --erc20-address=<addr> --amount=<amount> --cosmos-destination=<dest>
+        {} cosmos-to-eth --cosmos-phrase=<key> --cosmos-rpc=<url> --fees=<denom> --erc20-address=<addr> --amount=<amount> --eth-destination=<dest> [--memo=<memo>] [--address-book=<file>]
+        {} cosmos-to-eth --offline --sender=<addr> --cosmos-rpc=<url> --fees=<denom> --erc20-address=<addr> --amount=<amount> --eth-destination=<dest> [--memo=<memo>] [--address-book=<file>]
+        {} eth-to-cosmos --ethereum-key=<key> --ethereum-rpc=<url> --contract-address=<addr> --erc20-address=<addr> --amount=<amount> --cosmos-destination=<dest> [--address-book=<file>]
         Options:
             -h --help                   Show this screen.
             --cosmos-key=<ckey>         The Cosmos private key of the sender
@@ -58,14 +67,22 @@ lazy_static! {
             --contract-address=<addr>   The Ethereum contract address for Peggy, this is temporary
             --erc20-address=<addr>      An erc20 address to send funds
             --amount=<amount>           The amount of tokens to send
-            --cosmos-destination=<dest> A cosmos address to send tokens to
-            --eth-destination=<dest> A cosmos address to send tokens to
+            --cosmos-destination=<dest> A cosmos address or Hub label from the address book to send tokens to
+            --eth-destination=<dest>    An Ethereum address or Ethereum label from the address book to send tokens to
+            --memo=<memo>               (Optional) A reference kept with the transfer, for example a withdrawal id
+            --offline                   Print the unsigned transaction and its sign doc instead of sending it
+            --sender=<addr>             The Cosmos address or Hub label that will sign the offline transaction
+            --address-book=<file>       (Optional) Json file of labelled Ethereum, Hub and Minter addresses that can
+                                        be given instead of addresses
         About:
             Althea Peggy client software, moves tokens from Ethereum to Cosmos and back
+            Keys and phrases can be given as they are or as env:<var>, file:<path>, gpg:<path>,
+            vault:<path>#<field>, aws-sm:<secret id> or aws-kms:<path> references to where they are kept
             Written By: {}
             Version {}",
             env!("CARGO_PKG_NAME"),
             env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
             env!("CARGO_PKG_AUTHORS"),
             env!("CARGO_PKG_VERSION"),
         );
@@ -86,8 +103,51 @@ async fn main() {
         .flag_erc20_address
         .parse()
         .expect("Invalid contract address!");
-    if args.cmd_cosmos_to_eth {
-        let cosmos_key = CosmosPrivateKey::from_phrase(&args.flag_cosmos_phrase, "")
+    let address_book = match args.flag_address_book.as_deref() {
+        Some(path) => AddressBook::load(Path::new(path)).unwrap_or_else(|e| panic!("{}", e)),
+        None => AddressBook::default(),
+    };
+    if args.cmd_cosmos_to_eth && args.flag_offline {
+        let sender: CosmosAddress = address_book
+            .hub(&args.flag_sender)
+            .unwrap_or_else(|e| panic!("Invalid sender: {}", e));
+        let cosmos_url = Url::parse(&args.flag_cosmos_rpc).expect("Invalid Cosmos RPC url");
+        let cosmos_url = cosmos_url.to_string();
+        let cosmos_url = cosmos_url.trim_end_matches('/');
+        let fee = Coin {
+            denom: args.flag_fees,
+            amount: 1u64.into(),
+        };
+        let peggy_denom = format!("peggy/{}", erc20_address);
+        let contact = Contact::new(&cosmos_url, TIMEOUT);
+        let amount = Coin {
+            amount,
+            denom: peggy_denom.clone(),
+        };
+        let eth_dest: EthAddress = address_book
+            .ethereum(&args.flag_eth_destination)
+            .unwrap_or_else(|e| panic!("Invalid destination: {}", e));
+
+        // the batch request goes into the same tx, so there is only one signature to make offline
+        let msgs = vec![
+            send_to_eth_msg(
+                sender,
+                eth_dest,
+                amount,
+                fee.clone(),
+                args.flag_memo.unwrap_or_default(),
+            ),
+            request_batch_msg(sender, peggy_denom),
+        ];
+        let tx = export_tx(&contact, sender, msgs, fee)
+            .await
+            .expect("Failed to build the transaction");
+        println!("{}", serde_json::to_string_pretty(&tx).unwrap());
+    } else if args.cmd_cosmos_to_eth {
+        let cosmos_phrase = resolve_secret(&args.flag_cosmos_phrase)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e));
+        let cosmos_key = CosmosPrivateKey::from_phrase(&cosmos_phrase, "")
             .expect("Failed to parse cosmos key phrase, does it have a password?");
         let cosmos_url = Url::parse(&args.flag_cosmos_rpc).expect("Invalid Cosmos RPC url");
         let cosmos_url = cosmos_url.to_string();
@@ -103,10 +163,13 @@ async fn main() {
             amount,
             denom: peggy_denom.clone(),
         };
-        let eth_dest: EthAddress = args.flag_eth_destination.parse().unwrap();
+        let eth_dest: EthAddress = address_book
+            .ethereum(&args.flag_eth_destination)
+            .unwrap_or_else(|e| panic!("Invalid destination: {}", e));
 
         println!("Locking funds into the batch pool");
-        send_to_eth(cosmos_key, eth_dest, amount, fee.clone(), &contact)
+        let memo = args.flag_memo.unwrap_or_default();
+        send_to_eth(cosmos_key, eth_dest, amount, fee.clone(), memo, &contact)
             .await
             .expect("Failed to Send to ETH");
 
@@ -115,8 +178,9 @@ async fn main() {
             .await
             .expect("Failed to request batch");
     } else if args.cmd_eth_to_cosmos {
-        let ethereum_key: EthPrivateKey = args
-            .flag_ethereum_key
+        let ethereum_key: EthPrivateKey = resolve_secret(&args.flag_ethereum_key)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e))
             .parse()
             .expect("Invalid Ethereum private key!");
         let contract_address: EthAddress = args
@@ -127,7 +191,9 @@ async fn main() {
         let eth_url = eth_url.to_string();
         let eth_url = eth_url.trim_end_matches('/');
         let web3 = Web3::new(&eth_url, TIMEOUT);
-        let cosmos_dest: CosmosAddress = args.flag_cosmos_destination.parse().unwrap();
+        let cosmos_dest: CosmosAddress = address_book
+            .hub(&args.flag_cosmos_destination)
+            .unwrap_or_else(|e| panic!("Invalid destination: {}", e));
 
         let ethereum_public_key = ethereum_key.to_public_key().unwrap();
 
@@ -85,4 +85,36 @@ impl Contact {
             )
             .await
     }
+
+    /// The voting progress of the claim of an Ethereum transaction, None until a validator has claimed it
+    pub async fn get_attestation_progress(
+        &self,
+        tx_hash: &str,
+    ) -> Result<ResponseWrapper<Option<AttestationProgress>>, JsonRpcError> {
+        let none: Option<bool> = None;
+        self.jsonrpc_client
+            .request_method(
+                &format!("minter/tx_progress/{}", tx_hash),
+                none,
+                self.timeout,
+                None,
+            )
+            .await
+    }
+
+    /// The Hub's status of the transfer started by `tx_hash`
+    pub async fn get_transfer_status(
+        &self,
+        tx_hash: &str,
+    ) -> Result<ResponseWrapper<TransferStatus>, JsonRpcError> {
+        let none: Option<bool> = None;
+        self.jsonrpc_client
+            .request_method(
+                &format!("minter/tx_status/{}", tx_hash),
+                none,
+                self.timeout,
+                None,
+            )
+            .await
+    }
 }
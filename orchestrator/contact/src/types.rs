@@ -134,6 +134,10 @@ pub struct OptionalTXInfo {
 pub struct TXSendResponse {
     pub logs: Option<Value>,
     pub txhash: String,
+    /// non zero if the transaction was rejected by the chain
+    pub code: Option<u32>,
+    pub codespace: Option<String>,
+    pub raw_log: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
@@ -146,6 +150,24 @@ pub struct TxSendErrorResponse {
     pub raw_log: String,
 }
 
+/// How far the Hub's validators are with attesting the claim of an Ethereum transaction
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttestationProgress {
+    #[serde(deserialize_with = "parse_val")]
+    pub event_nonce: u64,
+    /// whether enough power has voted for the Hub to apply the claim
+    pub observed: bool,
+}
+
+/// The Hub's record of a transfer, keyed by the hash of the transaction that started it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransferStatus {
+    pub in_tx_hash: String,
+    pub out_tx_hash: String,
+    /// TX_STATUS_NOT_FOUND until the Hub has acted on the transfer
+    pub status: String,
+}
+
 /// Adapter that lets us parse any val that implements from_str into
 /// the type we want, this helps solve type problems like sigs or addresses
 /// being presented as strings and requiring a parse. For our own types like
@@ -0,0 +1,185 @@
+//! Signing valset and batch confirms. By default the orchestrator signs with its delegate Ethereum key, with
+//! `--threshold-signer` the delegate Ethereum key is instead split across an external threshold (MPC/TSS)
+//! signer, for example 2-of-3, so a compromised orchestrator box can't produce valid confirms by itself.
+//!
+//! The external signer is a JSON-RPC service. We hand it the checkpoint being confirmed, its co-signers can
+//! check that checkpoint against their own Hub node before contributing a share, and poll it until the
+//! signing rounds have produced a signature or failed:
+//!
+//! - `peggy_signerAddress()` returns the Ethereum address of the shared key, this is the address to register
+//!   as the validator's delegate Ethereum address
+//! - `peggy_startSigning({"message": "0x..", "hash": "0x.."})` starts a signing session and returns its id,
+//!   `hash` is the Ethereum signed message hash of `message` that the contract verifies
+//! - `peggy_signingStatus(id)` returns `{"shares": n, "threshold": t, "signature": "0x..", "error": ".."}`,
+//!   `signature` once the session is complete and `error` if it failed
+//!
+//! Every signature is checked against the signer's address before it is used. `orchestrator rotate-keys`
+//! registers a freshly generated local Ethereum key, it can't rotate a shared key.
+
+use clarity::{
+    Address as EthAddress, PrivateKey as EthPrivateKey, Signature as EthSignature, Uint256,
+};
+use deep_space::utils::{bytes_to_hex_str, hex_str_to_bytes};
+use ethereum_peggy::utils::get_signed_message_hash;
+use peggy_utils::error::PeggyError;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+use web30::jsonrpc::client::HttpClient;
+
+/// How long we wait for the threshold signer's co-signers to complete a signing session
+pub const SIGNING_SESSION_TIMEOUT: Duration = Duration::from_secs(120);
+/// How often a running signing session is polled
+const SIGNING_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub type SignFuture<'a> = Pin<Box<dyn Future<Output = Result<EthSignature, PeggyError>> + 'a>>;
+
+/// Something that can sign confirms as our delegate Ethereum address. Signing may take several rounds
+/// with other parties, so it is asynchronous and may fail
+pub trait EthSigner {
+    fn address(&self) -> EthAddress;
+    /// Signs the Ethereum signed message hash of `message`, the same signature `sign_ethereum_msg` produces
+    fn sign_ethereum_msg<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a>;
+}
+
+impl EthSigner for EthPrivateKey {
+    fn address(&self) -> EthAddress {
+        self.to_public_key().unwrap()
+    }
+
+    fn sign_ethereum_msg<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a> {
+        let signature = EthPrivateKey::sign_ethereum_msg(self, message);
+        Box::pin(async move { Ok(signature) })
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SigningRequest {
+    message: String,
+    hash: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct SigningStatus {
+    #[serde(default)]
+    shares: u64,
+    #[serde(default)]
+    threshold: u64,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// An external threshold signer holding shares of our delegate Ethereum key
+#[derive(Clone)]
+pub struct ThresholdSigner {
+    client: Arc<HttpClient>,
+    address: EthAddress,
+    timeout: Duration,
+}
+
+impl ThresholdSigner {
+    /// Connects to the threshold signer at `url` and asks it for the address of the shared key,
+    /// `timeout` applies to every request
+    pub async fn connect(url: &str, timeout: Duration) -> Result<ThresholdSigner, PeggyError> {
+        let client = Arc::new(HttpClient::new(url));
+        let address: String = client
+            .request_method("peggy_signerAddress", Vec::<String>::new(), timeout, None)
+            .await?;
+        let address: EthAddress = address.parse()?;
+        Ok(ThresholdSigner {
+            client,
+            address,
+            timeout,
+        })
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<EthSignature, PeggyError> {
+        let hash = get_signed_message_hash(message);
+        let request = SigningRequest {
+            message: format!("0x{}", bytes_to_hex_str(message)),
+            hash: format!("0x{}", bytes_to_hex_str(&hash)),
+        };
+        let session: String = self
+            .client
+            .request_method("peggy_startSigning", vec![request], self.timeout, None)
+            .await?;
+        debug!("Started threshold signing session {}", session);
+
+        let start = Instant::now();
+        let mut shares = 0;
+        loop {
+            let status: SigningStatus = self
+                .client
+                .request_method(
+                    "peggy_signingStatus",
+                    vec![session.clone()],
+                    self.timeout,
+                    None,
+                )
+                .await?;
+            if let Some(e) = status.error {
+                return Err(PeggyError::InvalidBridgeStateError(format!(
+                    "Threshold signing session {} failed: {}",
+                    session, e
+                )));
+            }
+            if let Some(signature) = status.signature {
+                let signature = parse_signature(&signature).ok_or_else(|| {
+                    PeggyError::InvalidBridgeStateError(format!(
+                        "Threshold signing session {} returned an invalid signature {}",
+                        session, signature
+                    ))
+                })?;
+                return match signature.recover(&hash) {
+                    Ok(signer) if signer == self.address => Ok(signature),
+                    _ => Err(PeggyError::InvalidBridgeStateError(format!(
+                        "Threshold signing session {} produced a signature that isn't from {}",
+                        session, self.address
+                    ))),
+                };
+            }
+            if status.shares != shares {
+                shares = status.shares;
+                info!(
+                    "Threshold signing session {} has {} of {} shares",
+                    session, status.shares, status.threshold
+                );
+            }
+            if Instant::now() - start > SIGNING_SESSION_TIMEOUT {
+                warn!(
+                    "Threshold signing session {} timed out with {} of {} shares",
+                    session, status.shares, status.threshold
+                );
+                return Err(PeggyError::TimeoutError);
+            }
+            delay_for(SIGNING_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Parses a hex encoded 65 byte r, s, v signature
+fn parse_signature(input: &str) -> Option<EthSignature> {
+    let bytes = hex_str_to_bytes(input).ok()?;
+    if bytes.len() != 65 {
+        return None;
+    }
+    Some(EthSignature::new(
+        bytes[64].into(),
+        Uint256::from_bytes_be(&bytes[0..32]),
+        Uint256::from_bytes_be(&bytes[32..64]),
+    ))
+}
+
+impl EthSigner for ThresholdSigner {
+    fn address(&self) -> EthAddress {
+        self.address
+    }
+
+    fn sign_ethereum_msg<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(self.sign(message))
+    }
+}
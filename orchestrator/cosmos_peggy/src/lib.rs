@@ -7,8 +7,12 @@
 extern crate serde_derive;
 #[macro_use]
 extern crate log;
+#[macro_use]
+extern crate lazy_static;
 
+pub mod eth_signer;
 pub mod messages;
+pub mod offline;
 pub mod query;
 pub mod send;
 pub mod utils;
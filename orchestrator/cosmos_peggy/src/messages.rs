@@ -2,13 +2,15 @@ use clarity::Address as EthAddress;
 use deep_space::address::Address;
 use deep_space::canonical_json::{to_canonical_json, CanonicalJsonError};
 use deep_space::coin::Coin;
-use deep_space::msg::DeepSpaceMsg;
+use deep_space::msg::{DeepSpaceMsg, SendMsg};
 use ethereum_peggy::utils::downcast_nonce;
 use num256::Uint256;
 use peggy_utils::types::{
     ERC20Token, SendToCosmosEvent, SendToMinterEvent, TransactionBatchExecutedEvent,
 };
+use serde_json::Value;
 use std::cmp::Ordering;
+use std::str::FromStr;
 /// Any arbitrary message
 #[derive(Serialize, Deserialize, Debug, Clone, Eq)]
 #[serde(tag = "type", content = "value")]
@@ -22,12 +24,18 @@ pub enum PeggyMsg {
     #[serde(rename = "peggy/MsgSendToEth")]
     SendToEthMsg(SendToEthMsg),
 
+    #[serde(rename = "peggy/MsgCancelSendToEth")]
+    CancelSendToEthMsg(CancelSendToEthMsg),
+
     #[serde(rename = "peggy/MsgRequestBatch")]
     RequestBatchMsg(RequestBatchMsg),
 
     #[serde(rename = "peggy/MsgConfirmBatch")]
     ConfirmBatchMsg(ConfirmBatchMsg),
 
+    #[serde(rename = "peggy/MsgRelayReceipt")]
+    RelayReceiptMsg(RelayReceiptMsg),
+
     #[serde(rename = "peggy/MsgCreateEthereumClaims")]
     CreateEthereumClaimsMsg(CreateEthereumClaimsMsg),
 
@@ -45,6 +53,10 @@ pub enum PeggyMsg {
 
     #[serde(rename = "minter/MsgRequestBatch")]
     RequestMinterBatchMsg(RequestMinterBatchMsg),
+
+    /// only used to make a separate fee payer the first signer of a tx
+    #[serde(rename = "cosmos-sdk/MsgSend")]
+    SendMsg(SendMsg),
 }
 
 impl PeggyMsg {
@@ -82,6 +94,124 @@ impl DeepSpaceMsg for PeggyMsg {
     }
 }
 
+/// The amino types of every `PeggyMsg` variant, keep this in sync with the enum
+pub const KNOWN_MSG_TYPES: [&str; 14] = [
+    "peggy/MsgSetOrchestratorAddress",
+    "peggy/MsgValsetConfirm",
+    "peggy/MsgSendToEth",
+    "peggy/MsgCancelSendToEth",
+    "peggy/MsgRequestBatch",
+    "peggy/MsgConfirmBatch",
+    "peggy/MsgRelayReceipt",
+    "peggy/MsgCreateEthereumClaims",
+    "peggy/MsgDepositClaim",
+    "peggy/MsgSendToMinterClaim",
+    "peggy/MsgWithdrawClaim",
+    "minter/MsgSendToMinter",
+    "minter/MsgRequestBatch",
+    "cosmos-sdk/MsgSend",
+];
+
+/// A message of a type this build doesn't know, such as one added by a Hub upgrade, kept exactly as the Hub
+/// sent it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UnknownMsg {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    pub value: Value,
+}
+
+impl DeepSpaceMsg for UnknownMsg {
+    fn to_sign_bytes(&self) -> Result<Vec<u8>, CanonicalJsonError> {
+        Ok(to_canonical_json(self)?)
+    }
+}
+
+/// What to do with messages of a type this build doesn't know. Fields a newer Hub adds to known messages are
+/// always ignored
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UnknownMsgPolicy {
+    /// refuse the whole input
+    Fail,
+    /// keep the message as an `UnknownMsg` and log a warning
+    Warn,
+    /// drop the message
+    Ignore,
+}
+
+impl Default for UnknownMsgPolicy {
+    fn default() -> Self {
+        UnknownMsgPolicy::Warn
+    }
+}
+
+impl FromStr for UnknownMsgPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "fail" => Ok(UnknownMsgPolicy::Fail),
+            "warn" => Ok(UnknownMsgPolicy::Warn),
+            "ignore" => Ok(UnknownMsgPolicy::Ignore),
+            other => Err(format!(
+                "Invalid unknown message policy {}, expected fail, warn or ignore",
+                other
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedMsg {
+    Known(PeggyMsg),
+    Unknown(UnknownMsg),
+}
+
+/// Decodes the amino json of a message. Known types must decode, an unknown type is handled according to
+/// `policy` and comes back as None when it is ignored
+pub fn decode_msg(json: Value, policy: UnknownMsgPolicy) -> Result<Option<DecodedMsg>, String> {
+    let msg_type = match json.get("type").and_then(|t| t.as_str()) {
+        Some(msg_type) => msg_type.to_string(),
+        None => return Err("Not a message, it has no type".to_string()),
+    };
+    if KNOWN_MSG_TYPES.contains(&msg_type.as_str()) {
+        return serde_json::from_value(json)
+            .map(|msg| Some(DecodedMsg::Known(msg)))
+            .map_err(|e| format!("Invalid {}: {}", msg_type, e));
+    }
+    match policy {
+        UnknownMsgPolicy::Fail => Err(format!("Unknown message type {}", msg_type)),
+        UnknownMsgPolicy::Warn => {
+            warn!(
+                "Unknown message type {}, is the Hub newer than this orchestrator?",
+                msg_type
+            );
+            serde_json::from_value(json)
+                .map(|msg| Some(DecodedMsg::Unknown(msg)))
+                .map_err(|e| format!("Invalid {}: {}", msg_type, e))
+        }
+        UnknownMsgPolicy::Ignore => {
+            debug!("Ignoring unknown message type {}", msg_type);
+            Ok(None)
+        }
+    }
+}
+
+/// Decodes a list of messages, such as the msgs of a tx, with `decode_msg`
+pub fn decode_msgs(json: Value, policy: UnknownMsgPolicy) -> Result<Vec<DecodedMsg>, String> {
+    let msgs = match json {
+        Value::Array(msgs) => msgs,
+        _ => return Err("Not a list of messages".to_string()),
+    };
+    let mut out = Vec::new();
+    for msg in msgs {
+        if let Some(msg) = decode_msg(msg, policy)? {
+            out.push(msg);
+        }
+    }
+    Ok(out)
+}
+
 /// This message sets both the Cosmos and Ethereum address being delegated for
 /// Orchestrator operations. This allows a validator to use their highly valuable
 /// valoper key to simply sign off on these addresses.
@@ -112,6 +242,17 @@ pub struct SendToEthMsg {
     pub eth_dest: EthAddress,
     pub amount: Coin,
     pub bridge_fee: Coin,
+    /// an optional reference kept with the transfer, omitted when empty like the Hub does when signing
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String,
+}
+
+/// a transaction we send to take a transfer that isn't in a batch yet out of the outgoing pool, the amount
+/// and bridge fee are refunded to the sender
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, Hash, PartialOrd)]
+pub struct CancelSendToEthMsg {
+    pub transaction_id: Uint256,
+    pub sender: Address,
 }
 
 /// a transaction we send to move funds from Cosmos to Ethereum
@@ -120,6 +261,8 @@ pub struct SendToMinterMsg {
     pub sender: Address,
     pub minter_dest: String,
     pub amount: Coin,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub memo: String,
 }
 
 /// This message requests that a batch be created on the Cosmos chain, this
@@ -149,6 +292,18 @@ pub struct ConfirmBatchMsg {
     pub eth_signature: String,
 }
 
+/// a transaction we send to record that our relayer submitted a valset update or a batch to Ethereum, the Hub
+/// only emits it as an event for accounting of relayer work
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, Hash, PartialOrd)]
+pub struct RelayReceiptMsg {
+    pub relayer: Address,
+    pub nonce: Uint256,
+    /// the token contract of the batch, empty for a valset update
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token_contract: String,
+    pub eth_tx_hash: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, Hash, PartialOrd)]
 pub struct EthereumBridgeDepositClaim {
     #[serde(rename = "nonce")]
@@ -253,6 +408,9 @@ pub struct DepositClaimMsg {
     pub cosmos_receiver: Address,
     pub orchestrator: Address,
     pub tx_hash: String,
+    /// asks the Hub to refund the deposit, omitted when false like the Hub does when signing
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub invalid_destination: bool,
 }
 
 impl DepositClaimMsg {
@@ -267,6 +425,7 @@ impl DepositClaimMsg {
             cosmos_receiver: input.destination,
             orchestrator: sender,
             tx_hash: input.tx_hash,
+            invalid_destination: input.invalid_destination,
         }
     }
 }
@@ -280,6 +439,8 @@ pub struct SendToMinterClaimMsg {
     pub minter_receiver: String,
     pub orchestrator: Address,
     pub tx_hash: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub invalid_destination: bool,
 }
 
 impl SendToMinterClaimMsg {
@@ -294,6 +455,11 @@ impl SendToMinterClaimMsg {
             minter_receiver: input.destination,
             orchestrator: sender,
             tx_hash: input.tx_hash,
+            invalid_destination: input.invalid_destination,
         }
     }
 }
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
@@ -0,0 +1,74 @@
+//! Unsigned transactions for offline signing. Rather than signing and broadcasting, the CLIs can print the
+//! transaction they would send together with its sign doc, the canonical json the signature covers, so that it
+//! can be signed on an airgapped machine and broadcast from anywhere. The account number and sequence are looked
+//! up at export time, the signer account must not send anything else until the exported tx is broadcast.
+
+use crate::messages::PeggyMsg;
+use contact::jsonrpc::error::JsonRpcError;
+use contact::{client::Contact, utils::maybe_get_optional_tx_info};
+use deep_space::address::Address;
+use deep_space::coin::Coin;
+use deep_space::stdfee::StdFee;
+use deep_space::stdsigndoc::StdSignDoc;
+use deep_space::stdsignmsg::StdSignMsg;
+use deep_space::stdtx::StdTx;
+
+/// The amino type of a legacy Cosmos transaction
+pub const STD_TX_TYPE: &str = "cosmos-sdk/StdTx";
+
+/// A transaction in the Hub's amino json encoding
+#[derive(Serialize, Debug, Clone)]
+pub struct AminoTx {
+    #[serde(rename = "type")]
+    pub tx_type: String,
+    pub value: StdTx<PeggyMsg>,
+}
+
+/// An unsigned transaction and what its single signer has to sign
+#[derive(Serialize, Debug)]
+pub struct UnsignedTx {
+    /// the tx to broadcast once the signature is added, `signatures` is empty
+    pub tx: AminoTx,
+    /// the document to sign, its canonical json is the signed payload
+    pub sign_doc: StdSignDoc,
+}
+
+/// Splits a sign msg into the unsigned tx and its sign doc
+pub fn to_unsigned_tx(std_sign_msg: StdSignMsg<PeggyMsg>) -> UnsignedTx {
+    let sign_doc = std_sign_msg
+        .to_sign_doc()
+        .expect("Failed to encode the sign doc");
+    UnsignedTx {
+        tx: AminoTx {
+            tx_type: STD_TX_TYPE.to_string(),
+            value: StdTx {
+                msg: std_sign_msg.msgs,
+                fee: std_sign_msg.fee,
+                memo: std_sign_msg.memo,
+                signatures: Vec::new(),
+            },
+        },
+        sign_doc,
+    }
+}
+
+/// Builds the unsigned tx `signer` would send with these msgs, using its current account number and sequence
+pub async fn export_tx(
+    contact: &Contact,
+    signer: Address,
+    msgs: Vec<PeggyMsg>,
+    fee: Coin,
+) -> Result<UnsignedTx, JsonRpcError> {
+    let tx_info = maybe_get_optional_tx_info(signer, None, None, None, contact).await?;
+    Ok(to_unsigned_tx(StdSignMsg {
+        chain_id: tx_info.chain_id,
+        account_number: tx_info.account_number,
+        sequence: tx_info.sequence,
+        fee: StdFee {
+            amount: vec![fee],
+            gas: 500_000u64.into(),
+        },
+        msgs,
+        memo: String::new(),
+    }))
+}
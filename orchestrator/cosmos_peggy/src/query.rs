@@ -1,20 +1,45 @@
 use clarity::Address as EthAddress;
+use contact::client::Contact;
+use contact::types::ResponseWrapper;
 use deep_space::address::Address;
 use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
 use peggy_proto::oracle::QueryCoinsRequest;
+use peggy_proto::oracle::QueryEthFeeRequest;
+use peggy_proto::oracle::QueryEthFeeResponse;
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_proto::peggy::Params;
 use peggy_proto::peggy::QueryBatchConfirmsRequest;
+use peggy_proto::peggy::QueryBatchRequestByNonceRequest;
 use peggy_proto::peggy::QueryCurrentValsetRequest;
 use peggy_proto::peggy::QueryLastEventNonceByAddrRequest;
 use peggy_proto::peggy::QueryLastPendingBatchRequestByAddrRequest;
 use peggy_proto::peggy::QueryLastPendingValsetRequestByAddrRequest;
 use peggy_proto::peggy::QueryLastValsetRequestsRequest;
 use peggy_proto::peggy::QueryOutgoingTxBatchesRequest;
+use peggy_proto::peggy::QueryParamsRequest;
 use peggy_proto::peggy::QueryValsetConfirmsByNonceRequest;
 use peggy_proto::peggy::QueryValsetRequestRequest;
 use peggy_utils::error::PeggyError;
 use peggy_utils::types::*;
+use std::collections::HashMap;
 use tonic::transport::Channel;
+use tonic::Request;
+
+/// The gRPC metadata key that makes the Hub answer a query from the state at an earlier height
+const BLOCK_HEIGHT_HEADER: &str = "x-cosmos-block-height";
+
+/// get the current Peggy module params
+pub async fn get_peggy_params(
+    client: &mut PeggyQueryClient<Channel>,
+) -> Result<Params, PeggyError> {
+    let request = client.params(QueryParamsRequest {}).await?;
+    match request.into_inner().params {
+        Some(params) => Ok(params),
+        None => Err(PeggyError::InvalidBridgeStateError(
+            "No Peggy params on the Hub".to_string(),
+        )),
+    }
+}
 
 /// get the valset for a given nonce (block) height
 pub async fn get_valset(
@@ -25,11 +50,10 @@ pub async fn get_valset(
         .valset_request(QueryValsetRequestRequest { nonce })
         .await?;
     let valset = request.into_inner().valset;
-    let valset = match valset {
-        Some(v) => Some(v.into()),
-        None => None,
-    };
-    Ok(valset)
+    match valset {
+        Some(v) => Ok(Some(Valset::from_proto(v)?)),
+        None => Ok(None),
+    }
 }
 
 /// get the current valset. You should never sign this valset
@@ -43,7 +67,7 @@ pub async fn get_current_valset(
     let request = client.current_valset(QueryCurrentValsetRequest {}).await?;
     let valset = request.into_inner().valset;
     if let Some(valset) = valset {
-        Ok(valset.into())
+        Valset::from_proto(valset)
     } else {
         error!("Current valset returned None? This should be impossible");
         Err(PeggyError::InvalidBridgeStateError(
@@ -64,11 +88,10 @@ pub async fn get_oldest_unsigned_valset(
         })
         .await?;
     let valset = request.into_inner().valset;
-    let valset = match valset {
-        Some(v) => Some(v.into()),
-        None => None,
-    };
-    Ok(valset)
+    match valset {
+        Some(v) => Ok(Some(Valset::from_proto(v)?)),
+        None => Ok(None),
+    }
 }
 
 /// this input views the last five valset requests that have been made, useful if you're
@@ -80,7 +103,7 @@ pub async fn get_latest_valsets(
         .last_valset_requests(QueryLastValsetRequestsRequest {})
         .await?;
     let valsets = request.into_inner().valsets;
-    Ok(valsets.iter().map(|v| v.into()).collect())
+    valsets.into_iter().map(Valset::from_proto).collect()
 }
 
 /// get all valset confirmations for a given nonce
@@ -92,6 +115,7 @@ pub async fn get_all_valset_confirms(
         .valset_confirms_by_nonce(QueryValsetConfirmsByNonceRequest { nonce })
         .await?;
     let confirms = request.into_inner().confirms;
+    check_response_len("valset confirms", confirms.len(), MAX_VALSET_MEMBERS)?;
     let mut parsed_confirms = Vec::new();
     for item in confirms {
         parsed_confirms.push(ValsetConfirmResponse::from_proto(item)?)
@@ -115,6 +139,31 @@ pub async fn get_oldest_unsigned_transaction_batch(
     }
 }
 
+/// Gets the batch with the given nonce for the given token, if the Hub still has it. The Hub deletes
+/// batches once they are executed, so `height` can ask for the state of an earlier block instead
+pub async fn get_transaction_batch(
+    client: &mut PeggyQueryClient<Channel>,
+    nonce: u64,
+    contract_address: EthAddress,
+    height: Option<u64>,
+) -> Result<Option<TransactionBatch>, PeggyError> {
+    let mut request = Request::new(QueryBatchRequestByNonceRequest {
+        nonce,
+        contract_address: contract_address.to_string(),
+    });
+    if let Some(height) = height {
+        request
+            .metadata_mut()
+            .insert(BLOCK_HEIGHT_HEADER, height.to_string().parse().unwrap());
+    }
+    let request = client.batch_request_by_nonce(request).await?;
+    let batch = request.into_inner().batch;
+    match batch {
+        Some(batch) => Ok(Some(TransactionBatch::from_proto(batch)?)),
+        None => Ok(None),
+    }
+}
+
 pub async fn get_latest_transaction_batches(
     client: &mut PeggyQueryClient<Channel>,
 ) -> Result<Vec<TransactionBatch>, PeggyError> {
@@ -142,6 +191,7 @@ pub async fn get_transaction_batch_signatures(
         })
         .await?;
     let batch_confirms = request.into_inner().confirms;
+    check_response_len("batch confirms", batch_confirms.len(), MAX_VALSET_MEMBERS)?;
     let mut out = Vec::new();
     for confirm in batch_confirms {
         out.push(BatchConfirmResponse::from_proto(confirm)?)
@@ -172,3 +222,38 @@ pub async fn get_coins(client: &mut OracleQueryClient<Channel>) -> Result<Vec<Co
     }
     Ok(out)
 }
+
+/// The oracle's USD cost of withdrawing to Ethereum, `fast` is the fee a batch must be worth to be built
+pub async fn get_eth_fee(
+    client: &mut OracleQueryClient<Channel>,
+) -> Result<QueryEthFeeResponse, PeggyError> {
+    let request = client.eth_fee(QueryEthFeeRequest {}).await?;
+    Ok(request.into_inner())
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct HubPrice {
+    name: String,
+    value: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct HubPrices {
+    #[serde(default)]
+    list: Vec<HubPrice>,
+}
+
+/// The raw prices of the Hub's oracle module by name, `eth/0` for ETH and `minter/<id>` for Minter coins
+pub async fn get_oracle_prices(contact: &Contact) -> Result<HashMap<String, String>, PeggyError> {
+    let none: Option<bool> = None;
+    let prices: ResponseWrapper<HubPrices> = contact
+        .jsonrpc_client
+        .request_method("oracle/prices", none, contact.timeout, None)
+        .await?;
+    Ok(prices
+        .result
+        .list
+        .into_iter()
+        .map(|price| (price.name, price.value))
+        .collect())
+}
@@ -1,18 +1,183 @@
+use crate::eth_signer::EthSigner;
 use crate::messages::*;
+use crate::utils::check_tx_response;
 use clarity::Address as EthAddress;
-use clarity::PrivateKey as EthPrivateKey;
 use contact::jsonrpc::error::JsonRpcError;
 use contact::types::TXSendResponse;
 use contact::{client::Contact, utils::maybe_get_optional_tx_info};
 use deep_space::address::Address;
+use deep_space::msg::SendMsg;
 use deep_space::private_key::PrivateKey;
 use deep_space::stdfee::StdFee;
 use deep_space::stdsignmsg::StdSignMsg;
-use deep_space::transaction::TransactionSendType;
+use deep_space::transaction::{Transaction, TransactionSendType};
 use deep_space::{coin::Coin, utils::bytes_to_hex_str};
-use ethereum_peggy::message_signatures::{encode_tx_batch_confirm, encode_valset_confirm};
+use ethereum_peggy::checkpoint_cache::{batch_checkpoint, valset_checkpoint};
 use ethereum_peggy::utils::downcast_nonce;
+use futures::future::join;
+use num256::Uint256;
+use peggy_utils::error::PeggyError;
+use peggy_utils::redact::Sensitive;
 use peggy_utils::types::*;
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref TX_MEMO: RwLock<String> = RwLock::new(String::new());
+}
+
+/// Sets the memo put on every claim and confirm tx, should be called once at startup
+pub fn set_tx_memo(memo: String) {
+    *TX_MEMO.write().unwrap() = memo;
+}
+
+fn tx_memo() -> String {
+    TX_MEMO.read().unwrap().clone()
+}
+
+/// Signs and broadcasts msgs from `private_key`, see `sign_tx`
+async fn sign_and_send(
+    contact: &Contact,
+    private_key: PrivateKey,
+    fee: StdFee,
+    msgs: Vec<PeggyMsg>,
+    fee_payer: Option<PrivateKey>,
+) -> Result<TXSendResponse, PeggyError> {
+    let tx = sign_tx(contact, private_key, fee, msgs, fee_payer).await?;
+    check_tx_response(contact.retry_on_block(tx).await?)
+}
+
+/// Broadcasts the same signed tx through a primary and a backup Hub node at once, so a flaky node
+/// can't hold it back. Both copies have the same bytes and sequence, at most one of them can make it
+/// into a block and the other is turned away as a duplicate, so whichever node delivers the tx answers
+/// for both and the tx is only reported once
+async fn broadcast_with_backup(
+    primary: &Contact,
+    backup: &Contact,
+    tx: Transaction<PeggyMsg>,
+) -> Result<TXSendResponse, PeggyError> {
+    let (primary_res, backup_res) = join(
+        primary.retry_on_block(tx.clone()),
+        backup.retry_on_block(tx),
+    )
+    .await;
+    let primary_res = primary_res
+        .map_err(PeggyError::from)
+        .and_then(check_tx_response);
+    let backup_res = backup_res
+        .map_err(PeggyError::from)
+        .and_then(check_tx_response);
+    match (primary_res, backup_res) {
+        (Ok(res), _) => Ok(res),
+        (Err(e), Ok(res)) => {
+            warn!(
+                "The backup Hub node delivered tx {}, the primary failed {:?}",
+                res.txhash, e
+            );
+            Ok(res)
+        }
+        (Err(e), Err(backup_e)) => {
+            trace!("The backup Hub node failed too {:?}", backup_e);
+            Err(e)
+        }
+    }
+}
+
+/// Signs msgs from `private_key` with its current sequence. If a fee payer is given it pays the fee instead,
+/// the Hub's Cosmos SDK version has no fee grants so the fee payer co-signs the tx. The fee is
+/// always charged to the first signer, so a 1 unit self send from the fee payer is put in front
+/// The tx carries the memo set with `set_tx_memo`, identifying the orchestrator build
+async fn sign_tx(
+    contact: &Contact,
+    private_key: PrivateKey,
+    fee: StdFee,
+    msgs: Vec<PeggyMsg>,
+    fee_payer: Option<PrivateKey>,
+) -> Result<Transaction<PeggyMsg>, PeggyError> {
+    let our_address = private_key
+        .to_public_key()
+        .expect("Invalid private key!")
+        .to_address();
+    let tx_info = maybe_get_optional_tx_info(our_address, None, None, None, contact).await?;
+
+    let tx = match fee_payer {
+        None => {
+            let std_sign_msg = StdSignMsg {
+                chain_id: tx_info.chain_id,
+                account_number: tx_info.account_number,
+                sequence: tx_info.sequence,
+                fee,
+                msgs,
+                memo: tx_memo(),
+            };
+            private_key
+                .sign_std_msg(std_sign_msg, TransactionSendType::Block)
+                .unwrap()
+        }
+        Some(fee_payer) => {
+            let payer_address = fee_payer
+                .to_public_key()
+                .expect("Invalid fee payer key!")
+                .to_address();
+            let payer_info =
+                maybe_get_optional_tx_info(payer_address, None, None, None, contact).await?;
+            let mut payer_msgs = vec![fee_payer_msg(payer_address, &fee)?];
+            payer_msgs.extend(msgs);
+            let std_sign_msg = StdSignMsg {
+                chain_id: tx_info.chain_id,
+                account_number: tx_info.account_number,
+                sequence: tx_info.sequence,
+                fee,
+                msgs: payer_msgs,
+                memo: tx_memo(),
+            };
+            PrivateKey::sign_std_msg_multi(
+                &[
+                    (fee_payer, payer_info.account_number, payer_info.sequence),
+                    (private_key, tx_info.account_number, tx_info.sequence),
+                ],
+                std_sign_msg,
+                TransactionSendType::Block,
+            )
+            .unwrap()
+        }
+    };
+    Ok(tx)
+}
+
+/// The 1 unit self send that makes the fee payer the first signer, in the fee's denom. There is no
+/// denom to send without a fee, and nothing for the fee payer to pay either
+fn fee_payer_msg(payer_address: Address, fee: &StdFee) -> Result<PeggyMsg, PeggyError> {
+    let denom = match fee.amount.first() {
+        Some(coin) => coin.denom.clone(),
+        None => {
+            return Err(PeggyError::InvalidOptionsError(
+                "A fee payer needs a fee to pay, set a fee amount".to_string(),
+            ))
+        }
+    };
+    Ok(PeggyMsg::SendMsg(SendMsg {
+        from_address: payer_address,
+        to_address: payer_address,
+        amount: vec![Coin {
+            denom,
+            amount: 1u64.into(),
+        }],
+    }))
+}
+
+/// The message delegating orchestrator operations of the validator with the given account address, the
+/// validator's operator address is the same key with the valoper prefix
+pub fn set_orchestrator_address_msg(
+    validator: Address,
+    delegate_eth_address: EthAddress,
+    delegate_cosmos_address: Address,
+) -> PeggyMsg {
+    PeggyMsg::SetOrchestratorAddressMsg(SetOrchestratorAddressMsg {
+        eth_address: delegate_eth_address,
+        validator: validator.to_bech32("cosmosvaloper").unwrap(),
+        orchestrator: delegate_cosmos_address,
+    })
+}
 
 /// Send a transaction updating the eth address for the sending
 /// Cosmos address. The sending Cosmos address should be a validator
@@ -24,12 +189,6 @@ pub async fn update_peggy_delegate_addresses(
     fee: Coin,
 ) -> Result<TXSendResponse, JsonRpcError> {
     trace!("Updating Peggy Delegate addresses");
-    let our_valoper_address = private_key
-        .to_public_key()
-        .expect("Invalid private key!")
-        .to_address()
-        .to_bech32("cosmosvaloper")
-        .unwrap();
     let our_address = private_key
         .to_public_key()
         .expect("Invalid private key!")
@@ -46,12 +205,10 @@ pub async fn update_peggy_delegate_addresses(
             amount: vec![fee],
             gas: 500_000u64.into(),
         },
-        msgs: vec![PeggyMsg::SetOrchestratorAddressMsg(
-            SetOrchestratorAddressMsg {
-                eth_address: delegate_eth_address,
-                validator: our_valoper_address,
-                orchestrator: delegate_cosmos_address,
-            },
+        msgs: vec![set_orchestrator_address_msg(
+            our_address,
+            delegate_eth_address,
+            delegate_cosmos_address,
         )],
         memo: String::new(),
     };
@@ -63,54 +220,54 @@ pub async fn update_peggy_delegate_addresses(
     contact.retry_on_block(tx).await
 }
 
-/// Send in a confirmation for a specific validator set for a specific block height
+/// Send in a confirmation for a specific validator set for a specific block height, the signature is
+/// encoded the way the contract takes it. With a `backup` node the confirm is broadcast through it as
+/// well, see `broadcast_with_backup`
 #[allow(clippy::too_many_arguments)]
 pub async fn send_valset_confirm(
     contact: &Contact,
-    eth_private_key: EthPrivateKey,
+    backup: Option<&Contact>,
+    eth_signer: &dyn EthSigner,
     fee: Coin,
     valset: Valset,
     private_key: PrivateKey,
     peggy_id: String,
-) -> Result<TXSendResponse, JsonRpcError> {
+    fee_payer: Option<PrivateKey>,
+    encoding: SignatureEncoding,
+) -> Result<TXSendResponse, PeggyError> {
     let our_address = private_key
         .to_public_key()
         .expect("Invalid private key!")
         .to_address();
-    let our_eth_address = eth_private_key.to_public_key().unwrap();
-
-    let tx_info = maybe_get_optional_tx_info(our_address, None, None, None, contact).await?;
+    let our_eth_address = eth_signer.address();
 
-    let message = encode_valset_confirm(peggy_id, valset.clone());
-    let eth_signature = eth_private_key.sign_ethereum_msg(&message);
+    let message = valset_checkpoint(&peggy_id, &valset).message;
+    let eth_signature = eth_signer.sign_ethereum_msg(&message).await?;
+    let eth_signature = bytes_to_hex_str(&encode_signature(&eth_signature, encoding));
 
     trace!(
         "Sent valset update with address {} and sig {}",
         our_eth_address,
-        bytes_to_hex_str(&eth_signature.to_bytes())
+        Sensitive(&eth_signature)
     );
-    let std_sign_msg = StdSignMsg {
-        chain_id: tx_info.chain_id,
-        account_number: tx_info.account_number,
-        sequence: tx_info.sequence,
-        fee: StdFee {
-            amount: vec![fee],
-            gas: 500_000u64.into(),
-        },
-        msgs: vec![PeggyMsg::ValsetConfirmMsg(ValsetConfirmMsg {
-            orchestrator: our_address,
-            eth_address: our_eth_address,
-            nonce: valset.nonce.into(),
-            eth_signature: bytes_to_hex_str(&eth_signature.to_bytes()),
-        })],
-        memo: String::new(),
+    let fee = StdFee {
+        amount: vec![fee],
+        gas: 500_000u64.into(),
     };
-
-    let tx = private_key
-        .sign_std_msg(std_sign_msg, TransactionSendType::Block)
-        .unwrap();
-
-    contact.retry_on_block(tx).await
+    let msgs = vec![PeggyMsg::ValsetConfirmMsg(ValsetConfirmMsg {
+        orchestrator: our_address,
+        eth_address: our_eth_address,
+        nonce: valset.nonce.into(),
+        eth_signature,
+    })];
+
+    match backup {
+        Some(backup) => {
+            let tx = sign_tx(contact, private_key, fee, msgs, fee_payer).await?;
+            broadcast_with_backup(contact, backup, tx).await
+        }
+        None => sign_and_send(contact, private_key, fee, msgs, fee_payer).await,
+    }
 }
 
 /// Send in a confirmation for a specific transaction batch set for a specific block height
@@ -118,46 +275,36 @@ pub async fn send_valset_confirm(
 #[allow(clippy::too_many_arguments)]
 pub async fn send_batch_confirm(
     contact: &Contact,
-    eth_private_key: EthPrivateKey,
+    eth_signer: &dyn EthSigner,
     fee: Coin,
     transaction_batch: TransactionBatch,
     private_key: PrivateKey,
     peggy_id: String,
-) -> Result<TXSendResponse, JsonRpcError> {
+    fee_payer: Option<PrivateKey>,
+    encoding: SignatureEncoding,
+) -> Result<TXSendResponse, PeggyError> {
     let our_address = private_key
         .to_public_key()
         .expect("Invalid private key!")
         .to_address();
-    let our_eth_address = eth_private_key.to_public_key().unwrap();
-
-    let tx_info = maybe_get_optional_tx_info(our_address, None, None, None, contact).await?;
+    let our_eth_address = eth_signer.address();
 
-    let batch_checkpoint = encode_tx_batch_confirm(peggy_id.clone(), transaction_batch.clone());
-    let eth_signature = eth_private_key.sign_ethereum_msg(&batch_checkpoint);
+    let message = batch_checkpoint(&peggy_id, &transaction_batch).message;
+    let eth_signature = eth_signer.sign_ethereum_msg(&message).await?;
 
-    let std_sign_msg = StdSignMsg {
-        chain_id: tx_info.chain_id,
-        account_number: tx_info.account_number,
-        sequence: tx_info.sequence,
-        fee: StdFee {
-            amount: vec![fee],
-            gas: 500_000u64.into(),
-        },
-        msgs: vec![PeggyMsg::ConfirmBatchMsg(ConfirmBatchMsg {
-            orchestrator: our_address,
-            token_contract: transaction_batch.token_contract,
-            eth_signer: our_eth_address,
-            nonce: transaction_batch.nonce.into(),
-            eth_signature: bytes_to_hex_str(&eth_signature.to_bytes()),
-        })],
-        memo: String::new(),
+    let fee = StdFee {
+        amount: vec![fee],
+        gas: 500_000u64.into(),
     };
-
-    let tx = private_key
-        .sign_std_msg(std_sign_msg, TransactionSendType::Block)
-        .unwrap();
-
-    contact.retry_on_block(tx).await
+    let msgs = vec![PeggyMsg::ConfirmBatchMsg(ConfirmBatchMsg {
+        orchestrator: our_address,
+        token_contract: transaction_batch.token_contract,
+        eth_signer: our_eth_address,
+        nonce: transaction_batch.nonce.into(),
+        eth_signature: bytes_to_hex_str(&encode_signature(&eth_signature, encoding)),
+    })];
+
+    sign_and_send(contact, private_key, fee, msgs, fee_payer).await
 }
 
 pub async fn send_ethereum_claims(
@@ -167,14 +314,13 @@ pub async fn send_ethereum_claims(
     withdraws: Vec<TransactionBatchExecutedEvent>,
     transfers: Vec<SendToMinterEvent>,
     fee: Coin,
-) -> Result<TXSendResponse, JsonRpcError> {
+    fee_payer: Option<PrivateKey>,
+) -> Result<TXSendResponse, PeggyError> {
     let our_address = private_key
         .to_public_key()
         .expect("Invalid private key!")
         .to_address();
 
-    let tx_info = maybe_get_optional_tx_info(our_address, None, None, None, contact).await?;
-
     let mut msgs = Vec::new();
     for transfer in transfers.clone() {
         msgs.push(PeggyMsg::SendToMinterClaimMsg(SendToMinterClaimMsg {
@@ -187,6 +333,7 @@ pub async fn send_ethereum_claims(
             minter_receiver: transfer.destination,
             orchestrator: our_address,
             tx_hash: transfer.tx_hash,
+            invalid_destination: transfer.invalid_destination,
         }))
     }
 
@@ -212,15 +359,89 @@ pub async fn send_ethereum_claims(
 
     msgs.sort();
 
+    let fee = StdFee {
+        amount: vec![fee],
+        gas: 500_000_000u64.into(),
+    };
+
+    sign_and_send(contact, private_key, fee, msgs, fee_payer).await
+}
+
+/// The message locking `amount` from `sender` into the batch pool, `fee` is paid to whoever relays its batch
+pub fn send_to_eth_msg(
+    sender: Address,
+    destination: EthAddress,
+    amount: Coin,
+    fee: Coin,
+    memo: String,
+) -> PeggyMsg {
+    PeggyMsg::SendToEthMsg(SendToEthMsg {
+        sender,
+        eth_dest: destination,
+        amount,
+        bridge_fee: fee,
+        memo,
+    })
+}
+
+/// The message taking `sender`'s transfer `transaction_id` out of the batch pool, refunding its amount and fee
+pub fn cancel_send_to_eth_msg(sender: Address, transaction_id: u64) -> PeggyMsg {
+    PeggyMsg::CancelSendToEthMsg(CancelSendToEthMsg {
+        transaction_id: transaction_id.into(),
+        sender,
+    })
+}
+
+/// The message recording that `relayer` submitted the valset update or the batch of `token_contract` with
+/// `nonce` to Ethereum in the transaction `eth_tx_hash`
+pub fn relay_receipt_msg(
+    relayer: Address,
+    nonce: u64,
+    token_contract: Option<EthAddress>,
+    eth_tx_hash: Uint256,
+) -> PeggyMsg {
+    PeggyMsg::RelayReceiptMsg(RelayReceiptMsg {
+        relayer,
+        nonce: nonce.into(),
+        token_contract: token_contract.map_or_else(String::new, |token| token.to_string()),
+        eth_tx_hash: format!("{:#066x}", eth_tx_hash),
+    })
+}
+
+/// The message asking the Hub to build a batch for `denom` out of the pool
+pub fn request_batch_msg(requester: Address, denom: String) -> PeggyMsg {
+    PeggyMsg::RequestBatchMsg(RequestBatchMsg {
+        denom,
+        orchestrator: requester,
+    })
+}
+
+/// Sends tokens from Cosmos to Ethereum. These tokens will not be sent immediately instead
+/// they will require some time to be included in a batch. The `memo` is returned with the
+/// transfer's status and events, it may be empty
+pub async fn send_to_eth(
+    private_key: PrivateKey,
+    destination: EthAddress,
+    amount: Coin,
+    fee: Coin,
+    memo: String,
+    contact: &Contact,
+) -> Result<TXSendResponse, JsonRpcError> {
+    let our_address = private_key
+        .to_public_key()
+        .expect("Invalid private key!")
+        .to_address();
+    let tx_info = maybe_get_optional_tx_info(our_address, None, None, None, contact).await?;
+
     let std_sign_msg = StdSignMsg {
         chain_id: tx_info.chain_id,
         account_number: tx_info.account_number,
         sequence: tx_info.sequence,
         fee: StdFee {
-            amount: vec![fee],
-            gas: 500_000_000u64.into(),
+            amount: vec![fee.clone()],
+            gas: 500_000u64.into(),
         },
-        msgs,
+        msgs: vec![send_to_eth_msg(our_address, destination, amount, fee, memo)],
         memo: String::new(),
     };
 
@@ -231,12 +452,17 @@ pub async fn send_ethereum_claims(
     contact.retry_on_block(tx).await
 }
 
-/// Sends tokens from Cosmos to Ethereum. These tokens will not be sent immediately instead
-/// they will require some time to be included in a batch
-pub async fn send_to_eth(
+/// Replaces our transfer `transaction_id` in the pool with one paying `bridge_fee`. The cancellation and the
+/// new transfer go in one transaction, so if the new one fails the old one stays in the pool. `amount` must be
+/// what the cancellation refunds, the refunded fee is spent again and only the difference comes from our balance
+#[allow(clippy::too_many_arguments)]
+pub async fn resend_to_eth(
     private_key: PrivateKey,
+    transaction_id: u64,
     destination: EthAddress,
     amount: Coin,
+    bridge_fee: Coin,
+    memo: String,
     fee: Coin,
     contact: &Contact,
 ) -> Result<TXSendResponse, JsonRpcError> {
@@ -251,15 +477,13 @@ pub async fn send_to_eth(
         account_number: tx_info.account_number,
         sequence: tx_info.sequence,
         fee: StdFee {
-            amount: vec![fee.clone()],
+            amount: vec![fee],
             gas: 500_000u64.into(),
         },
-        msgs: vec![PeggyMsg::SendToEthMsg(SendToEthMsg {
-            sender: our_address,
-            eth_dest: destination,
-            amount,
-            bridge_fee: fee,
-        })],
+        msgs: vec![
+            cancel_send_to_eth_msg(our_address, transaction_id),
+            send_to_eth_msg(our_address, destination, amount, bridge_fee, memo),
+        ],
         memo: String::new(),
     };
 
@@ -270,6 +494,21 @@ pub async fn send_to_eth(
     contact.retry_on_block(tx).await
 }
 
+/// Sends receipts for what our relayer submitted to Ethereum, built with `relay_receipt_msg`, in one tx
+pub async fn send_relay_receipts(
+    contact: &Contact,
+    private_key: PrivateKey,
+    fee: Coin,
+    msgs: Vec<PeggyMsg>,
+    fee_payer: Option<PrivateKey>,
+) -> Result<TXSendResponse, PeggyError> {
+    let fee = StdFee {
+        amount: vec![fee],
+        gas: 500_000u64.into(),
+    };
+    sign_and_send(contact, private_key, fee, msgs, fee_payer).await
+}
+
 pub async fn send_request_batch(
     private_key: PrivateKey,
     denom: String,
@@ -290,9 +529,43 @@ pub async fn send_request_batch(
             amount: vec![fee.clone()],
             gas: 500_000u64.into(),
         },
-        msgs: vec![PeggyMsg::RequestBatchMsg(RequestBatchMsg {
-            denom,
-            orchestrator: our_address,
+        msgs: vec![request_batch_msg(our_address, denom)],
+        memo: String::new(),
+    };
+
+    let tx = private_key
+        .sign_std_msg(std_sign_msg, TransactionSendType::Block)
+        .unwrap();
+
+    contact.retry_on_block(tx).await
+}
+
+/// Sends plain tokens between Hub accounts
+pub async fn send_tokens(
+    private_key: PrivateKey,
+    destination: Address,
+    amount: Coin,
+    fee: Coin,
+    contact: &Contact,
+) -> Result<TXSendResponse, JsonRpcError> {
+    let our_address = private_key
+        .to_public_key()
+        .expect("Invalid private key!")
+        .to_address();
+    let tx_info = maybe_get_optional_tx_info(our_address, None, None, None, contact).await?;
+
+    let std_sign_msg = StdSignMsg {
+        chain_id: tx_info.chain_id,
+        account_number: tx_info.account_number,
+        sequence: tx_info.sequence,
+        fee: StdFee {
+            amount: vec![fee],
+            gas: 500_000u64.into(),
+        },
+        msgs: vec![PeggyMsg::SendMsg(SendMsg {
+            from_address: our_address,
+            to_address: destination,
+            amount: vec![amount],
         })],
         memo: String::new(),
     };
@@ -303,3 +576,32 @@ pub async fn send_request_batch(
 
     contact.retry_on_block(tx).await
 }
+
+#[test]
+fn test_fee_payer_msg() {
+    let payer = PrivateKey::from_secret(b"fee payer")
+        .to_public_key()
+        .unwrap()
+        .to_address();
+    let fee = StdFee {
+        amount: vec![Coin {
+            denom: "hub".to_string(),
+            amount: 5u64.into(),
+        }],
+        gas: 500_000u64.into(),
+    };
+    match fee_payer_msg(payer, &fee).unwrap() {
+        PeggyMsg::SendMsg(send) => {
+            assert_eq!(send.from_address, payer);
+            assert_eq!(send.to_address, payer);
+            assert_eq!(send.amount[0].denom, "hub");
+        }
+        other => panic!("Unexpected message {:?}", other),
+    }
+
+    let free = StdFee {
+        amount: Vec::new(),
+        ..fee
+    };
+    assert!(fee_payer_msg(payer, &free).is_err());
+}
@@ -1,4 +1,6 @@
 use contact::client::Contact;
+use contact::types::TXSendResponse;
+use peggy_utils::error::{PeggyError, TxRejection};
 use std::time::Duration;
 use std::time::Instant;
 use tokio::time::delay_for;
@@ -42,3 +44,16 @@ pub async fn wait_for_cosmos_online(contact: &Contact, timeout: Duration) {
     wait_for_next_cosmos_block(contact, timeout).await;
     wait_for_next_cosmos_block(contact, timeout).await;
 }
+
+/// Block mode txs that fail in the Peggy module still come back as a successful
+/// response, with a non zero code, so we have to check the result ourselves
+pub fn check_tx_response(response: TXSendResponse) -> Result<TXSendResponse, PeggyError> {
+    match response.code {
+        Some(code) if code != 0 => Err(PeggyError::CosmosTxRejected(TxRejection::from_tx_result(
+            response.codespace.as_deref().unwrap_or_default(),
+            code,
+            response.raw_log.as_deref().unwrap_or_default(),
+        ))),
+        _ => Ok(response),
+    }
+}
@@ -178,6 +178,60 @@ impl PrivateKey {
         std_sign_msg: StdSignMsg<M>,
         mode: TransactionSendType,
     ) -> Result<Transaction<M>, PrivateKeyError> {
+        let signature = self.sign_doc(&std_sign_msg)?;
+
+        // Put a single signature in a result
+        let std_tx = StdTx {
+            msg: std_sign_msg.msgs,
+            fee: std_sign_msg.fee,
+            memo: std_sign_msg.memo,
+            signatures: vec![signature],
+        };
+
+        Ok(match mode {
+            TransactionSendType::Async => Transaction::Async(std_tx),
+            TransactionSendType::Block => Transaction::Block(std_tx),
+            TransactionSendType::Sync => Transaction::Sync(std_tx),
+        })
+    }
+
+    /// Signs a transaction with multiple signers, each given with their own account
+    /// number and sequence. Signers must be in the order their messages appear in the
+    /// transaction, the first signer pays the fee. The account number and sequence in
+    /// `std_sign_msg` are ignored.
+    pub fn sign_std_msg_multi<M: serde::Serialize + std::clone::Clone + DeepSpaceMsg>(
+        signers: &[(PrivateKey, u64, u64)],
+        std_sign_msg: StdSignMsg<M>,
+        mode: TransactionSendType,
+    ) -> Result<Transaction<M>, PrivateKeyError> {
+        let mut signatures = Vec::new();
+        for (key, account_number, sequence) in signers {
+            let signer_msg = StdSignMsg {
+                account_number: *account_number,
+                sequence: *sequence,
+                ..std_sign_msg.clone()
+            };
+            signatures.push(key.sign_doc(&signer_msg)?);
+        }
+
+        let std_tx = StdTx {
+            msg: std_sign_msg.msgs,
+            fee: std_sign_msg.fee,
+            memo: std_sign_msg.memo,
+            signatures,
+        };
+
+        Ok(match mode {
+            TransactionSendType::Async => Transaction::Async(std_tx),
+            TransactionSendType::Block => Transaction::Block(std_tx),
+            TransactionSendType::Sync => Transaction::Sync(std_tx),
+        })
+    }
+
+    fn sign_doc<M: serde::Serialize + std::clone::Clone + DeepSpaceMsg>(
+        &self,
+        std_sign_msg: &StdSignMsg<M>,
+    ) -> Result<Signature, PrivateKeyError> {
         let sign_doc = std_sign_msg.to_sign_doc()?;
         let bytes = sign_doc.to_bytes()?;
 
@@ -191,23 +245,9 @@ impl PrivateKey {
         let sig = secp256k1.sign(&msg, &sk);
         // Extract compact form
         let compact = sig.serialize_compact().to_vec();
-        let signature = Signature {
+        Ok(Signature {
             signature: compact.to_vec(),
             pub_key: self.to_public_key()?,
-        };
-
-        // Put a single signature in a result
-        let std_tx = StdTx {
-            msg: std_sign_msg.msgs,
-            fee: std_sign_msg.fee,
-            memo: std_sign_msg.memo,
-            signatures: vec![signature],
-        };
-
-        Ok(match mode {
-            TransactionSendType::Async => Transaction::Async(std_tx),
-            TransactionSendType::Block => Transaction::Block(std_tx),
-            TransactionSendType::Sync => Transaction::Sync(std_tx),
         })
     }
 }
@@ -364,6 +404,47 @@ fn test_secret() {
         .unwrap();
 }
 
+#[test]
+fn test_sign_multi() {
+    use crate::msg::Msg;
+    let payer = PrivateKey::from_secret(b"payer");
+    let signer = PrivateKey::from_secret(b"signer");
+    let std_sign_msg = StdSignMsg {
+        chain_id: "test-chain".to_string(),
+        msgs: vec![Msg::Test("foo".to_string())],
+        ..Default::default()
+    };
+
+    let multi = PrivateKey::sign_std_msg_multi(
+        &[(payer, 3, 7), (signer, 1, 2)],
+        std_sign_msg.clone(),
+        TransactionSendType::Block,
+    )
+    .unwrap();
+    // each signer signs with their own account number and sequence
+    let single = signer
+        .sign_std_msg(
+            StdSignMsg {
+                account_number: 1,
+                sequence: 2,
+                ..std_sign_msg
+            },
+            TransactionSendType::Block,
+        )
+        .unwrap();
+    match (multi, single) {
+        (Transaction::Block(multi), Transaction::Block(single)) => {
+            assert_eq!(multi.signatures.len(), 2);
+            assert_eq!(multi.signatures[0].pub_key, payer.to_public_key().unwrap());
+            assert_eq!(
+                multi.signatures[1].signature,
+                single.signatures[0].signature
+            );
+        }
+        _ => panic!("wrong transaction type"),
+    }
+}
+
 #[cfg(feature = "key_import")]
 #[test]
 fn test_cosmos_key_derivation_manual() {
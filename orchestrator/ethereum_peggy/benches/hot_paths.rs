@@ -0,0 +1,176 @@
+//! Benchmarks for the loops every relay cycle runs over the whole validator set: encoding and hashing
+//! checkpoints, ordering confirms into contract call arguments, decoding contract events and serializing
+//! messages to canonical JSON. Each runs with valsets of 10, 100 and 500 members.
+//!
+//! Run with `cargo bench -p ethereum_peggy`
+
+use clarity::abi::encode_tokens;
+use clarity::utils::bytes_to_hex_str;
+use clarity::{Address as EthAddress, PrivateKey as EthPrivateKey};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use deep_space::address::Address as CosmosAddress;
+use deep_space::canonical_json::to_canonical_json;
+use ethereum_peggy::message_signatures::encode_valset_confirm;
+use ethereum_peggy::utils::get_signed_message_hash;
+use peggy_utils::types::{
+    to_arrays, SendToCosmosEvent, SignatureEncoding, Valset, ValsetConfirmResponse, ValsetMember,
+    ValsetUpdatedEvent,
+};
+use web30::types::Log;
+
+const VALSET_SIZES: [usize; 3] = [10, 100, 500];
+const PEGGY_ID: &str = "defaultpeggyid";
+
+fn eth_key(i: usize) -> EthPrivateKey {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&(i as u64 + 1).to_be_bytes());
+    EthPrivateKey::from_slice(&bytes).unwrap()
+}
+
+fn valset(size: usize) -> Valset {
+    Valset {
+        nonce: 1,
+        members: (0..size)
+            .map(|i| ValsetMember {
+                power: u64::from(u32::MAX) / size as u64,
+                eth_address: Some(eth_key(i).to_public_key().unwrap()),
+            })
+            .collect(),
+    }
+}
+
+/// Confirms of every member, in reverse so ordering has to do the work
+fn confirms(valset: &Valset) -> Vec<ValsetConfirmResponse> {
+    let message = encode_valset_confirm(PEGGY_ID.to_string(), valset.clone());
+    (0..valset.members.len())
+        .rev()
+        .map(|i| {
+            let key = eth_key(i);
+            ValsetConfirmResponse {
+                orchestrator: CosmosAddress::from_bytes([i as u8; 20]),
+                eth_address: key.to_public_key().unwrap(),
+                nonce: valset.nonce,
+                eth_signature: key.sign_ethereum_msg(&message),
+            }
+        })
+        .collect()
+}
+
+/// A 32 byte topic holding `bytes` in its low bytes
+fn topic(bytes: &[u8]) -> String {
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(bytes);
+    format!("0x{}", bytes_to_hex_str(&word))
+}
+
+/// Logs are built from their JSON RPC form, the same way the node hands them to us
+fn log(topics: Vec<String>, data: Vec<u8>) -> Log {
+    serde_json::from_value(serde_json::json!({
+        "address": "0x0000000000000000000000000000000000000001",
+        "topics": topics,
+        "data": format!("0x{}", bytes_to_hex_str(&data)),
+        "blockNumber": "0x1",
+        "transactionHash": topic(&[0xaa; 32]),
+    }))
+    .unwrap()
+}
+
+fn valset_updated_log(valset: &Valset) -> Log {
+    let (addresses, powers) = valset.filter_empty_addresses();
+    log(
+        vec![topic(&[0x01; 32]), topic(&valset.nonce.to_be_bytes())],
+        encode_tokens(&[addresses.into(), powers.into()]),
+    )
+}
+
+/// One deposit per member, a block of deposits is about as common as a valset update
+fn deposit_logs(size: usize) -> Vec<Log> {
+    let erc20 = EthAddress::from_slice(&[0x11; 20]).unwrap();
+    (0..size)
+        .map(|i| {
+            let sender = eth_key(i).to_public_key().unwrap();
+            log(
+                vec![
+                    topic(&[0x02; 32]),
+                    topic(erc20.as_bytes()),
+                    topic(sender.as_bytes()),
+                    topic(&[0x22; 20]),
+                ],
+                encode_tokens(&[1000u64.into(), (i as u64 + 1).into()]),
+            )
+        })
+        .collect()
+}
+
+fn checkpoint_encoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("checkpoint_encoding");
+    for size in VALSET_SIZES.iter() {
+        let valset = valset(*size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &valset, |b, valset| {
+            b.iter(|| {
+                let message =
+                    encode_valset_confirm(PEGGY_ID.to_string(), black_box(valset.clone()));
+                get_signed_message_hash(&message)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn signature_ordering(c: &mut Criterion) {
+    let mut group = c.benchmark_group("signature_ordering");
+    for size in VALSET_SIZES.iter() {
+        let valset = valset(*size);
+        let confirms = confirms(&valset);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(size),
+            &confirms,
+            |b, confirms| {
+                b.iter(|| {
+                    let ordered = valset.order_valset_sigs(black_box(confirms)).unwrap();
+                    to_arrays(ordered, SignatureEncoding::Standard)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn event_decoding(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_decoding");
+    for size in VALSET_SIZES.iter() {
+        let valset_logs = vec![valset_updated_log(&valset(*size))];
+        group.bench_with_input(
+            BenchmarkId::new("valset_updated", size),
+            &valset_logs,
+            |b, logs| b.iter(|| ValsetUpdatedEvent::from_logs(black_box(logs)).unwrap()),
+        );
+        let deposits = deposit_logs(*size);
+        group.bench_with_input(
+            BenchmarkId::new("send_to_cosmos", size),
+            &deposits,
+            |b, logs| b.iter(|| SendToCosmosEvent::from_logs(black_box(logs)).unwrap()),
+        );
+    }
+    group.finish();
+}
+
+fn canonical_json(c: &mut Criterion) {
+    let mut group = c.benchmark_group("canonical_json");
+    for size in VALSET_SIZES.iter() {
+        let valset = valset(*size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &valset, |b, valset| {
+            b.iter(|| to_canonical_json(black_box(valset)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    hot_paths,
+    checkpoint_encoding,
+    signature_ordering,
+    event_decoding,
+    canonical_json
+);
+criterion_main!(hot_paths);
@@ -0,0 +1,296 @@
+//! Typed decoding of eth_call return data. A misbehaving or malicious RPC node can return anything, so
+//! every decoder checks the length and shape of the data and returns an error rather than silently
+//! interpreting short or garbage responses as zero. Transaction calldata is decoded with the same care.
+
+use crate::submit_batch::submit_batch_signature;
+use clarity::abi::encode_call;
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::types::SignatureEncoding;
+use web30::jsonrpc::error::Web3Error;
+
+/// Every static ABI value is padded to a 32 byte word
+pub const WORD_SIZE: usize = 32;
+
+fn bad_response(msg: String) -> Web3Error {
+    Web3Error::BadResponse(format!("Invalid eth_call return data: {}", msg))
+}
+
+/// Returns the 32 byte word at the given word index
+fn word(data: &[u8], index: usize) -> Result<&[u8], Web3Error> {
+    let start = index * WORD_SIZE;
+    let end = start + WORD_SIZE;
+    if data.len() < end {
+        return Err(bad_response(format!(
+            "expected at least {} bytes, got {}",
+            end,
+            data.len()
+        )));
+    }
+    Ok(&data[start..end])
+}
+
+/// Reads a word that is used as an offset or length and must fit in a usize
+fn word_as_usize(data: &[u8], index: usize) -> Result<usize, Web3Error> {
+    let word = word(data, index)?;
+    if word[..WORD_SIZE - 8].iter().any(|b| *b != 0) {
+        return Err(bad_response(format!("word {} is too large", index)));
+    }
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&word[WORD_SIZE - 8..]);
+    Ok(u64::from_be_bytes(bytes) as usize)
+}
+
+fn check_exact_len(data: &[u8], len: usize) -> Result<(), Web3Error> {
+    if data.len() != len {
+        return Err(bad_response(format!(
+            "expected {} bytes, got {}",
+            len,
+            data.len()
+        )));
+    }
+    Ok(())
+}
+
+pub fn decode_uint256(data: &[u8]) -> Result<Uint256, Web3Error> {
+    check_exact_len(data, WORD_SIZE)?;
+    Ok(Uint256::from_bytes_be(data))
+}
+
+/// Decodes a uint256 that must fit in a u64, such as a nonce
+pub fn decode_u64(data: &[u8]) -> Result<u64, Web3Error> {
+    check_exact_len(data, WORD_SIZE)?;
+    word_as_usize(data, 0).map(|v| v as u64)
+}
+
+pub fn decode_bytes32(data: &[u8]) -> Result<[u8; 32], Web3Error> {
+    check_exact_len(data, WORD_SIZE)?;
+    let mut out = [0u8; 32];
+    out.copy_from_slice(data);
+    Ok(out)
+}
+
+pub fn decode_bool(data: &[u8]) -> Result<bool, Web3Error> {
+    match decode_u64(data)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        val => Err(bad_response(format!("{} is not a bool", val))),
+    }
+}
+
+fn word_to_address(word: &[u8]) -> Result<EthAddress, Web3Error> {
+    if word[..12].iter().any(|b| *b != 0) {
+        return Err(bad_response("address has non zero padding".to_string()));
+    }
+    EthAddress::from_slice(&word[12..]).map_err(|e| bad_response(e.to_string()))
+}
+
+pub fn decode_address(data: &[u8]) -> Result<EthAddress, Web3Error> {
+    check_exact_len(data, WORD_SIZE)?;
+    word_to_address(data)
+}
+
+/// Returns the word index of the length of the dynamic array whose offset is at word `index`, and the
+/// index of the word right after the array
+fn array_bounds(data: &[u8], index: usize) -> Result<(usize, usize), Web3Error> {
+    let offset = word_as_usize(data, index)?;
+    if offset % WORD_SIZE != 0 {
+        return Err(bad_response(format!("unaligned array offset {}", offset)));
+    }
+    let len_index = offset / WORD_SIZE;
+    let len = word_as_usize(data, len_index)?;
+    let end = len_index
+        .checked_add(1)
+        .and_then(|start| start.checked_add(len))
+        .filter(|end| end.checked_mul(WORD_SIZE).is_some())
+        .ok_or_else(|| bad_response(format!("array length {} overflows", len)))?;
+    Ok((len_index, end))
+}
+
+/// Returns the words of the dynamic array whose offset is at word `index`
+fn array_at(data: &[u8], index: usize) -> Result<Vec<&[u8]>, Web3Error> {
+    let (len_index, end) = array_bounds(data, index)?;
+    (len_index + 1..end).map(|i| word(data, i)).collect()
+}
+
+/// Returns the words of a dynamic array that is the only return value
+fn array_words(data: &[u8]) -> Result<Vec<&[u8]>, Web3Error> {
+    let (_, end) = array_bounds(data, 0)?;
+    check_exact_len(data, end * WORD_SIZE)?;
+    array_at(data, 0)
+}
+
+pub fn decode_uint256_array(data: &[u8]) -> Result<Vec<Uint256>, Web3Error> {
+    Ok(array_words(data)?
+        .into_iter()
+        .map(Uint256::from_bytes_be)
+        .collect())
+}
+
+pub fn decode_address_array(data: &[u8]) -> Result<Vec<EthAddress>, Web3Error> {
+    array_words(data)?
+        .into_iter()
+        .map(word_to_address)
+        .collect()
+}
+
+/// The arguments of a submitBatch call that say what the batch paid out, the signatures are left out
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubmitBatchArgs {
+    pub current_validators: Vec<EthAddress>,
+    pub current_valset_nonce: u64,
+    pub amounts: Vec<Uint256>,
+    pub destinations: Vec<EthAddress>,
+    pub batch_nonce: u64,
+    pub token_contract: EthAddress,
+}
+
+/// Decodes the calldata of a submitBatch transaction with either signature encoding, see
+/// `encode_submit_batch` for the layout
+pub fn decode_submit_batch(calldata: &[u8]) -> Result<SubmitBatchArgs, Web3Error> {
+    let mut call = None;
+    for encoding in [SignatureEncoding::Standard, SignatureEncoding::Compact].iter() {
+        let selector = encode_call(&submit_batch_signature(*encoding), &[])
+            .map_err(|e| bad_response(format!("{:?}", e)))?;
+        if calldata.len() >= selector.len() && calldata[..selector.len()] == selector[..] {
+            call = Some((*encoding, &calldata[selector.len()..]));
+        }
+    }
+    let (encoding, data) =
+        call.ok_or_else(|| bad_response("not a submitBatch call".to_string()))?;
+    // the arguments after the signature arrays move up with fewer arrays
+    let after_signatures = 3 + encoding.array_count();
+    let addresses = |index| -> Result<Vec<EthAddress>, Web3Error> {
+        array_at(data, index)?
+            .into_iter()
+            .map(word_to_address)
+            .collect()
+    };
+    let current_validators = addresses(0)?;
+    let destinations = addresses(after_signatures + 1)?;
+    let amounts: Vec<Uint256> = array_at(data, after_signatures)?
+        .into_iter()
+        .map(Uint256::from_bytes_be)
+        .collect();
+    if amounts.len() != destinations.len() {
+        return Err(bad_response(format!(
+            "{} amounts for {} destinations",
+            amounts.len(),
+            destinations.len()
+        )));
+    }
+    Ok(SubmitBatchArgs {
+        current_validators,
+        current_valset_nonce: decode_u64(word(data, 2)?)?,
+        amounts,
+        destinations,
+        batch_nonce: decode_u64(word(data, after_signatures + 2)?)?,
+        token_contract: word_to_address(word(data, after_signatures + 3)?)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uint_word(val: u64) -> Vec<u8> {
+        let mut out = vec![0u8; 24];
+        out.extend_from_slice(&val.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn test_decode_static() {
+        assert_eq!(decode_u64(&uint_word(42)).unwrap(), 42);
+        assert_eq!(decode_uint256(&uint_word(42)).unwrap(), 42u64.into());
+        // short, empty and oversized responses are all errors, not zero
+        assert!(decode_u64(&[]).is_err());
+        assert!(decode_u64(&uint_word(42)[1..]).is_err());
+        assert!(decode_uint256(&[0u8; 64]).is_err());
+        // a nonce that doesn't fit into a u64
+        let mut big = uint_word(1);
+        big[0] = 1;
+        assert!(decode_u64(&big).is_err());
+
+        let mut address = vec![0u8; 12];
+        address.extend_from_slice(&[0x11; 20]);
+        assert_eq!(
+            decode_address(&address).unwrap(),
+            EthAddress::from_slice(&[0x11; 20]).unwrap()
+        );
+        address[0] = 1;
+        assert!(decode_address(&address).is_err());
+
+        assert!(decode_bool(&uint_word(1)).unwrap());
+        assert!(!decode_bool(&uint_word(0)).unwrap());
+        assert!(decode_bool(&uint_word(2)).is_err());
+    }
+
+    #[test]
+    fn test_decode_arrays() {
+        let mut data = uint_word(32);
+        data.extend(uint_word(2));
+        data.extend(uint_word(7));
+        data.extend(uint_word(9));
+        assert_eq!(
+            decode_uint256_array(&data).unwrap(),
+            vec![7u64.into(), 9u64.into()]
+        );
+        // claims two elements but only has one
+        assert!(decode_uint256_array(&data[..96]).is_err());
+        // absurd length must not allocate or overflow
+        let mut data = uint_word(32);
+        data.extend(vec![0xff; 32]);
+        assert!(decode_uint256_array(&data).is_err());
+    }
+
+    #[test]
+    fn test_decode_submit_batch() {
+        use clarity::abi::Token;
+
+        let validators = vec![EthAddress::from_slice(&[0x11; 20]).unwrap()];
+        let destinations = vec![
+            EthAddress::from_slice(&[0x22; 20]).unwrap(),
+            EthAddress::from_slice(&[0x33; 20]).unwrap(),
+        ];
+        let token = EthAddress::from_slice(&[0x44; 20]).unwrap();
+        let amounts: Vec<Uint256> = vec![5u64.into(), 1_000_000u64.into()];
+        let tokens = &[
+            validators.clone().into(),
+            vec![100u64].into(),
+            3u64.into(),
+            vec![Uint256::from(27u8)].into(),
+            Token::Dynamic(vec![Token::Bytes(vec![1; 32])]),
+            Token::Dynamic(vec![Token::Bytes(vec![2; 32])]),
+            Token::Dynamic(amounts.iter().cloned().map(Token::Uint).collect()),
+            destinations.clone().into(),
+            9u64.into(),
+            token.into(),
+        ];
+        let calldata =
+            encode_call(&submit_batch_signature(SignatureEncoding::Standard), tokens).unwrap();
+        let expected = SubmitBatchArgs {
+            current_validators: validators,
+            current_valset_nonce: 3,
+            amounts,
+            destinations,
+            batch_nonce: 9,
+            token_contract: token,
+        };
+        assert_eq!(decode_submit_batch(&calldata).unwrap(), expected);
+
+        // compact signatures leave out the v array
+        let mut compact = tokens.to_vec();
+        compact.remove(3);
+        let compact_calldata = encode_call(
+            &submit_batch_signature(SignatureEncoding::Compact),
+            &compact,
+        )
+        .unwrap();
+        assert_eq!(decode_submit_batch(&compact_calldata).unwrap(), expected);
+
+        // truncated calldata and other calls are errors
+        assert!(decode_submit_batch(&calldata[..calldata.len() - 32]).is_err());
+        let other = encode_call("lastBatchNonce(address)", &[token.into()]).unwrap();
+        assert!(decode_submit_batch(&other).is_err());
+    }
+}
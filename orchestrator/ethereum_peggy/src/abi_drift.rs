@@ -0,0 +1,220 @@
+//! Detects a deployed contract that doesn't have the functions and events this orchestrator encodes and
+//! decodes, for example a newer contract version whose submitBatch takes other arguments. Calls to such a
+//! contract would revert and its events would never be found, so rather than find out from failed relays and
+//! a silent oracle the deployed bytecode is checked at startup. The Solidity dispatcher pushes every function
+//! selector onto the stack and every emitted event pushes its topic, so each of them appears in the bytecode
+//! as the operand of a PUSH instruction.
+
+use crate::capabilities::{get_contract_capabilities, ContractCapabilities};
+use crate::submit_batch::submit_batch_signature;
+use crate::valset_update::valset_update_signature;
+use clarity::utils::hex_str_to_bytes;
+use clarity::Address as EthAddress;
+use peggy_utils::error::PeggyError;
+use sha3::{Digest, Keccak256};
+use std::fmt;
+use std::time::Duration;
+use web30::client::Web3;
+use web30::jsonrpc::client::HttpClient;
+
+/// The views the orchestrator and relayer read on every contract version
+pub const REQUIRED_VIEWS: [&str; 5] = [
+    "state_lastValsetNonce()",
+    "state_lastEventNonce()",
+    "lastBatchNonce(address)",
+    "state_peggyId()",
+    "state_powerThreshold()",
+];
+
+/// The events the oracle claims
+pub const REQUIRED_EVENTS: [&str; 4] = [
+    "SendToHubEvent(address,address,bytes32,uint256,uint256)",
+    "SendToMinterEvent(address,address,bytes32,uint256,uint256)",
+    "TransactionBatchExecutedEvent(uint256,address,address,uint256)",
+    "ValsetUpdatedEvent(uint256,address[],uint256[])",
+];
+
+/// What the contract is missing
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AbiDrift {
+    pub missing_functions: Vec<String>,
+    pub missing_events: Vec<String>,
+}
+
+impl AbiDrift {
+    pub fn is_empty(&self) -> bool {
+        self.missing_functions.is_empty() && self.missing_events.is_empty()
+    }
+}
+
+impl fmt::Display for AbiDrift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut parts = Vec::new();
+        if !self.missing_functions.is_empty() {
+            parts.push(format!("no function {}", self.missing_functions.join(", ")));
+        }
+        if !self.missing_events.is_empty() {
+            parts.push(format!("no event {}", self.missing_events.join(", ")));
+        }
+        write!(f, "{}", parts.join(" and "))
+    }
+}
+
+fn keccak(signature: &str) -> Vec<u8> {
+    Keccak256::digest(signature.as_bytes()).to_vec()
+}
+
+/// Whether the bytecode pushes `value`, the compiler drops leading zero bytes and uses a shorter PUSH
+fn pushes(code: &[u8], value: &[u8]) -> bool {
+    let value: Vec<u8> = value.iter().copied().skip_while(|b| *b == 0).collect();
+    if value.is_empty() {
+        return true;
+    }
+    // PUSH1 is 0x60, PUSH32 is 0x7f
+    let mut needle = vec![0x5f + value.len() as u8];
+    needle.extend(value);
+    code.windows(needle.len())
+        .any(|window| window == needle.as_slice())
+}
+
+/// The functions this orchestrator calls on a contract with these capabilities
+pub fn required_functions(capabilities: &ContractCapabilities) -> Vec<String> {
+    let mut functions = vec![valset_update_signature(capabilities.signature_encoding)];
+    // contracts with batch timeouts have another submitBatch, the relayer already leaves their batches alone
+    if capabilities.can_relay_batches() {
+        functions.push(submit_batch_signature(capabilities.signature_encoding));
+    }
+    functions.extend(REQUIRED_VIEWS.iter().map(|view| view.to_string()));
+    functions
+}
+
+pub fn find_abi_drift(code: &[u8], functions: &[String], events: &[&str]) -> AbiDrift {
+    AbiDrift {
+        missing_functions: functions
+            .iter()
+            .filter(|function| !pushes(code, &keccak(function)[..4]))
+            .cloned()
+            .collect(),
+        missing_events: events
+            .iter()
+            .filter(|event| !pushes(code, &keccak(event)))
+            .map(|event| event.to_string())
+            .collect(),
+    }
+}
+
+/// The deployed bytecode, requested over plain JSON-RPC
+pub async fn get_code(
+    eth_url: &str,
+    contract_address: EthAddress,
+    timeout: Duration,
+) -> Result<Vec<u8>, PeggyError> {
+    request_code(eth_url, contract_address, "latest".to_string(), timeout).await
+}
+
+/// The bytecode at `contract_address` as of `block`, empty before it was deployed. Nodes that pruned the
+/// state of old blocks fail this for anything but recent blocks
+pub async fn get_code_at(
+    eth_url: &str,
+    contract_address: EthAddress,
+    block: u64,
+    timeout: Duration,
+) -> Result<Vec<u8>, PeggyError> {
+    request_code(eth_url, contract_address, format!("{:#x}", block), timeout).await
+}
+
+async fn request_code(
+    eth_url: &str,
+    contract_address: EthAddress,
+    block: String,
+    timeout: Duration,
+) -> Result<Vec<u8>, PeggyError> {
+    let client = HttpClient::new(eth_url);
+    let code: String = client
+        .request_method(
+            "eth_getCode",
+            vec![contract_address.to_string(), block],
+            timeout,
+            None,
+        )
+        .await?;
+    Ok(hex_str_to_bytes(&code)?)
+}
+
+/// Detects the contract's capabilities and fails if it lacks any function or event this orchestrator relies on
+pub async fn check_contract_abi(
+    eth_url: &str,
+    contract_address: EthAddress,
+    timeout: Duration,
+) -> Result<ContractCapabilities, PeggyError> {
+    let code = get_code(eth_url, contract_address, timeout).await?;
+    if code.is_empty() {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "There is no contract at {}",
+            contract_address
+        )));
+    }
+    let capabilities =
+        get_contract_capabilities(contract_address, &Web3::new(eth_url, timeout)).await?;
+    let drift = find_abi_drift(&code, &required_functions(&capabilities), &REQUIRED_EVENTS);
+    if drift.is_empty() {
+        Ok(capabilities)
+    } else {
+        Err(PeggyError::InvalidBridgeStateError(format!(
+            "Contract {} ({}) is incompatible with this orchestrator, it has {}",
+            contract_address, capabilities, drift
+        )))
+    }
+}
+
+#[test]
+fn test_find_abi_drift() {
+    let capabilities = ContractCapabilities::default();
+    let functions = required_functions(&capabilities);
+    // a dispatcher with every selector but submitBatch's, and every event but the valset one
+    let mut code = vec![0x60, 0x80, 0x60, 0x40, 0x52];
+    for function in functions.iter().filter(|f| !f.starts_with("submitBatch")) {
+        let selector = keccak(function);
+        let selector: Vec<u8> = selector[..4]
+            .iter()
+            .copied()
+            .skip_while(|b| *b == 0)
+            .collect();
+        code.push(0x5f + selector.len() as u8);
+        code.extend(selector);
+        code.extend(&[0x14, 0x61, 0x01, 0x00, 0x57]);
+    }
+    for event in REQUIRED_EVENTS.iter().take(3) {
+        code.push(0x7f);
+        code.extend(keccak(event));
+        code.push(0xa3);
+    }
+
+    let drift = find_abi_drift(&code, &functions, &REQUIRED_EVENTS);
+    assert_eq!(
+        drift.missing_functions,
+        vec![submit_batch_signature(capabilities.signature_encoding)]
+    );
+    assert_eq!(drift.missing_events, vec![REQUIRED_EVENTS[3].to_string()]);
+    assert!(drift.to_string().starts_with("no function submitBatch("));
+
+    // the bytes of a selector elsewhere in the code, not pushed, don't count
+    assert!(!pushes(
+        &[0x01, 0xaa, 0xbb, 0xcc, 0xdd],
+        &[0xaa, 0xbb, 0xcc, 0xdd]
+    ));
+    assert!(pushes(
+        &[0x63, 0xaa, 0xbb, 0xcc, 0xdd],
+        &[0xaa, 0xbb, 0xcc, 0xdd]
+    ));
+    assert!(pushes(&[0x62, 0xbb, 0xcc, 0xdd], &[0x00, 0xbb, 0xcc, 0xdd]));
+
+    // contracts with batch timeouts aren't relayed to, their submitBatch isn't required
+    let timeouts = ContractCapabilities {
+        batch_timeouts: true,
+        ..Default::default()
+    };
+    assert!(
+        find_abi_drift(&code, &required_functions(&timeouts), &REQUIRED_EVENTS[..3]).is_empty()
+    );
+}
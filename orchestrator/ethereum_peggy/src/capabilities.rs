@@ -0,0 +1,156 @@
+//! Contract version detection. Not every deployment of the Peggy contract has the same layout, newer versions
+//! take compact signatures and forks add logic calls, ERC721 transfers or batch timeouts. Rather than assuming
+//! one layout the contract is asked: its version if it has a version getter, and for every feature a view that
+//! only contracts with the feature have. A view that reverts means the feature is missing, but only once the
+//! contract was checked to be reachable, so a node outage isn't mistaken for an old contract.
+
+use crate::abi_decode::{decode_bool, decode_u64};
+use crate::utils::{call_view, get_halted, get_signature_encoding};
+use clarity::abi::{encode_call, Token};
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::types::SignatureEncoding;
+use std::fmt::{self, Display};
+use web30::{client::Web3, jsonrpc::error::Web3Error};
+
+/// The version getter of versioned contracts
+pub const CONTRACT_VERSION_SIGNATURE: &str = "contractVersion()";
+/// The logic call nonce view of contracts with arbitrary logic calls
+pub const LOGIC_CALLS_SIGNATURE: &str = "lastLogicCallNonce(bytes32)";
+/// The view of contracts that bridge ERC721 tokens
+pub const ERC721_SIGNATURE: &str = "supportsERC721()";
+/// The view of contracts whose batches time out, their submitBatch takes the timeout as an extra argument
+pub const BATCH_TIMEOUTS_SIGNATURE: &str = "supportsBatchTimeouts()";
+
+/// What the deployed contract supports
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ContractCapabilities {
+    /// the version the contract reports, None for contracts without a version getter
+    pub version: Option<u64>,
+    pub signature_encoding: SignatureEncoding,
+    pub logic_calls: bool,
+    pub erc721: bool,
+    pub batch_timeouts: bool,
+}
+
+impl ContractCapabilities {
+    /// Whether this orchestrator can build submitBatch calls for the contract, contracts with batch timeouts
+    /// take a different submitBatch
+    pub fn can_relay_batches(&self) -> bool {
+        !self.batch_timeouts
+    }
+
+    /// The features of the contract this orchestrator doesn't use
+    pub fn unsupported(&self) -> Vec<&'static str> {
+        let mut out = Vec::new();
+        if self.logic_calls {
+            out.push("logic calls");
+        }
+        if self.erc721 {
+            out.push("ERC721 transfers");
+        }
+        if self.batch_timeouts {
+            out.push("batch timeouts");
+        }
+        out
+    }
+}
+
+impl Display for ContractCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.version {
+            Some(version) => write!(f, "version {}", version)?,
+            None => write!(f, "unversioned")?,
+        }
+        match self.signature_encoding {
+            SignatureEncoding::Standard => write!(f, ", 65 byte signatures")?,
+            SignatureEncoding::Compact => write!(f, ", compact signatures")?,
+        }
+        if self.logic_calls {
+            write!(f, ", logic calls")?;
+        }
+        if self.erc721 {
+            write!(f, ", ERC721")?;
+        }
+        if self.batch_timeouts {
+            write!(f, ", batch timeouts")?;
+        }
+        Ok(())
+    }
+}
+
+/// Calls a view that may not exist, None if it reverts or returns nothing
+async fn probe(
+    contract_address: EthAddress,
+    signature: &str,
+    tokens: &[Token],
+    web3: &Web3,
+) -> Result<Option<Vec<u8>>, Web3Error> {
+    let payload = encode_call(signature, tokens)?;
+    match call_view(contract_address, payload, web3).await {
+        Ok(bytes) if bytes.is_empty() => Ok(None),
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// A feature flag view, false if the contract doesn't have it
+async fn probe_flag(
+    contract_address: EthAddress,
+    signature: &str,
+    web3: &Web3,
+) -> Result<bool, Web3Error> {
+    match probe(contract_address, signature, &[], web3).await? {
+        Some(bytes) => decode_bool(&bytes),
+        None => Ok(false),
+    }
+}
+
+/// Detects the version and features of the contract
+pub async fn get_contract_capabilities(
+    contract_address: EthAddress,
+    web3: &Web3,
+) -> Result<ContractCapabilities, Web3Error> {
+    get_halted(contract_address, web3).await?;
+    let version = match probe(contract_address, CONTRACT_VERSION_SIGNATURE, &[], web3).await? {
+        Some(bytes) => Some(decode_u64(&bytes)?),
+        None => None,
+    };
+    // any invalidation id will do, the view only has to exist
+    let logic_calls = probe(
+        contract_address,
+        LOGIC_CALLS_SIGNATURE,
+        &[Uint256::from(0u8).into()],
+        web3,
+    )
+    .await?
+    .is_some();
+    Ok(ContractCapabilities {
+        version,
+        signature_encoding: get_signature_encoding(contract_address, web3).await?,
+        logic_calls,
+        erc721: probe_flag(contract_address, ERC721_SIGNATURE, web3).await?,
+        batch_timeouts: probe_flag(contract_address, BATCH_TIMEOUTS_SIGNATURE, web3).await?,
+    })
+}
+
+#[test]
+fn test_capabilities_display() {
+    let stock = ContractCapabilities::default();
+    assert_eq!(stock.to_string(), "unversioned, 65 byte signatures");
+    assert!(stock.can_relay_batches());
+    assert!(stock.unsupported().is_empty());
+
+    let fork = ContractCapabilities {
+        version: Some(3),
+        signature_encoding: SignatureEncoding::Compact,
+        logic_calls: true,
+        erc721: false,
+        batch_timeouts: true,
+    };
+    assert_eq!(
+        fork.to_string(),
+        "version 3, compact signatures, logic calls, batch timeouts"
+    );
+    assert!(!fork.can_relay_batches());
+    assert_eq!(fork.unsupported(), vec!["logic calls", "batch timeouts"]);
+}
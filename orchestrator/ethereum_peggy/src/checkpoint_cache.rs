@@ -0,0 +1,91 @@
+//! Valset and batch checkpoints are recomputed every time we sign, verify or submit them, which for a large
+//! valset that the relayer keeps retrying means ABI encoding and hashing the same data every loop. The
+//! encodings are cached here, keyed by the full content they are computed from so a changed valset or batch
+//! can never be served a stale checkpoint. Each cache keeps the `MAX_ENTRIES` most recently used checkpoints.
+
+use crate::message_signatures::{encode_tx_batch_confirm, encode_valset_confirm};
+use crate::utils::get_signed_message_hash;
+use peggy_utils::bounded_cache::{BoundedCache, CacheStats};
+use peggy_utils::types::{TransactionBatch, Valset};
+
+/// Only a handful of valsets and batches are in flight at any time, this is per cache
+const MAX_ENTRIES: usize = 64;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Checkpoint {
+    /// the ABI encoded message orchestrators sign
+    pub message: Vec<u8>,
+    /// the Ethereum signed message hash of `message`, what signatures are checked against
+    pub signed_hash: Vec<u8>,
+}
+
+impl Checkpoint {
+    fn new(message: Vec<u8>) -> Self {
+        let signed_hash = get_signed_message_hash(&message);
+        Checkpoint {
+            message,
+            signed_hash,
+        }
+    }
+}
+
+lazy_static! {
+    static ref VALSETS: BoundedCache<(String, Valset), Checkpoint> = BoundedCache::new(MAX_ENTRIES);
+    static ref BATCHES: BoundedCache<(String, TransactionBatch), Checkpoint> =
+        BoundedCache::new(MAX_ENTRIES);
+}
+
+/// The checkpoint of `valset` signed in valset confirms
+pub fn valset_checkpoint(peggy_id: &str, valset: &Valset) -> Checkpoint {
+    VALSETS.get_or_insert_with((peggy_id.to_string(), valset.clone()), || {
+        Checkpoint::new(encode_valset_confirm(peggy_id.to_string(), valset.clone()))
+    })
+}
+
+/// The checkpoint of `batch` signed in batch confirms
+pub fn batch_checkpoint(peggy_id: &str, batch: &TransactionBatch) -> Checkpoint {
+    BATCHES.get_or_insert_with((peggy_id.to_string(), batch.clone()), || {
+        Checkpoint::new(encode_tx_batch_confirm(peggy_id.to_string(), batch.clone()))
+    })
+}
+
+/// Lookups, evictions and size of the valset and batch caches together since startup
+pub fn checkpoint_cache_stats() -> CacheStats {
+    VALSETS.stats() + BATCHES.stats()
+}
+
+#[test]
+fn test_checkpoint_cache() {
+    use peggy_utils::types::ValsetMember;
+
+    let valset = Valset {
+        nonce: 7,
+        members: vec![ValsetMember {
+            eth_address: Some(
+                "0xc783df8a850f42e7F7e57013759C285caa701eB6"
+                    .parse()
+                    .unwrap(),
+            ),
+            power: 3333,
+        }],
+    };
+    let before = checkpoint_cache_stats();
+    let first = valset_checkpoint("foo", &valset);
+    assert_eq!(
+        first.message,
+        encode_valset_confirm("foo".to_string(), valset.clone())
+    );
+    assert_eq!(valset_checkpoint("foo", &valset), first);
+
+    // any change in content or peggy id is a different checkpoint
+    let mut changed = valset.clone();
+    changed.members[0].power = 3334;
+    assert_ne!(valset_checkpoint("foo", &changed), first);
+    assert_ne!(valset_checkpoint("bar", &valset), first);
+
+    // other tests share the counters, so only check our own lookups were counted
+    let after = checkpoint_cache_stats();
+    assert!(after.hits > before.hits);
+    assert!(after.misses >= before.misses + 3);
+    assert!(after.len <= after.capacity);
+}
@@ -0,0 +1,241 @@
+//! A model of the Peggy contract's state transitions for unit tests. It keeps the contract's valset, its
+//! valset nonce, the last batch nonce per token and the event nonce, and applies valset updates, batches
+//! and deposits with the same checks and in the same order as the contract, reverting with the contract's
+//! messages. Signatures are taken as valid, a confirm counts for the power of its signer. That lets the
+//! relayer's decisions be run against the contract many times over without an EVM.
+
+use clarity::Address as EthAddress;
+use peggy_utils::types::{BatchConfirmResponse, TransactionBatch, Valset, ValsetConfirmResponse};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Revert {
+    Halted,
+    ValsetNonceNotIncreasing,
+    BatchNonceNotIncreasing,
+    CheckpointMismatch,
+    NotEnoughPower,
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            Revert::Halted => "contract halted",
+            Revert::ValsetNonceNotIncreasing => {
+                "New valset nonce must be greater than the current nonce"
+            }
+            Revert::BatchNonceNotIncreasing => {
+                "New batch nonce must be greater than the current nonce"
+            }
+            Revert::CheckpointMismatch => {
+                "Supplied current validators and powers do not match checkpoint."
+            }
+            Revert::NotEnoughPower => {
+                "Submitted validator set signatures do not have enough power."
+            }
+        };
+        write!(f, "{}", message)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PeggyContractModel {
+    /// the valset the contract holds a checkpoint of
+    pub valset: Valset,
+    /// signatures need strictly more power than this
+    pub power_threshold: u64,
+    pub batch_nonces: HashMap<EthAddress, u64>,
+    pub event_nonce: u64,
+    pub halted: bool,
+}
+
+impl PeggyContractModel {
+    /// A freshly deployed contract, the deployment emits valset `valset` without an event nonce. Contracts are
+    /// deployed with a `power_threshold` of `ETHEREUM_POWER_THRESHOLD`
+    pub fn new(valset: Valset, power_threshold: u64) -> Self {
+        PeggyContractModel {
+            valset,
+            power_threshold,
+            batch_nonces: HashMap::new(),
+            event_nonce: 0,
+            halted: false,
+        }
+    }
+
+    pub fn valset_nonce(&self) -> u64 {
+        self.valset.nonce
+    }
+
+    pub fn batch_nonce(&self, token_contract: EthAddress) -> u64 {
+        self.batch_nonces.get(&token_contract).copied().unwrap_or(0)
+    }
+
+    /// Applies `updateValset`, `current` is the valset the relayer supplies as the contract's
+    pub fn update_valset(
+        &mut self,
+        new: &Valset,
+        current: &Valset,
+        confirms: &[ValsetConfirmResponse],
+    ) -> Result<(), Revert> {
+        if self.halted {
+            return Err(Revert::Halted);
+        }
+        if new.nonce <= current.nonce {
+            return Err(Revert::ValsetNonceNotIncreasing);
+        }
+        self.check_signatures(current, confirms.iter().map(|c| c.eth_address))?;
+        self.valset = new.clone();
+        Ok(())
+    }
+
+    /// Applies `submitBatch`, `current` is the valset the relayer supplies as the contract's
+    pub fn submit_batch(
+        &mut self,
+        batch: &TransactionBatch,
+        current: &Valset,
+        confirms: &[BatchConfirmResponse],
+    ) -> Result<(), Revert> {
+        if self.halted {
+            return Err(Revert::Halted);
+        }
+        if batch.nonce <= self.batch_nonce(batch.token_contract) {
+            return Err(Revert::BatchNonceNotIncreasing);
+        }
+        self.check_signatures(current, confirms.iter().map(|c| c.ethereum_signer))?;
+        self.batch_nonces.insert(batch.token_contract, batch.nonce);
+        self.event_nonce += 1;
+        Ok(())
+    }
+
+    /// Applies `sendToHub` or `sendToMinter`
+    pub fn deposit(&mut self) -> Result<(), Revert> {
+        if self.halted {
+            return Err(Revert::Halted);
+        }
+        self.event_nonce += 1;
+        Ok(())
+    }
+
+    fn check_signatures(
+        &self,
+        current: &Valset,
+        signers: impl Iterator<Item = EthAddress>,
+    ) -> Result<(), Revert> {
+        // the checkpoint covers the members, their powers and the nonce
+        if current.nonce != self.valset.nonce || current.members != self.valset.members {
+            return Err(Revert::CheckpointMismatch);
+        }
+        let signers: HashSet<EthAddress> = signers.collect();
+        let power: u64 = current
+            .members
+            .iter()
+            .filter(|m| m.eth_address.map(|a| signers.contains(&a)).unwrap_or(false))
+            .map(|m| m.power)
+            .sum();
+        if power > self.power_threshold {
+            Ok(())
+        } else {
+            Err(Revert::NotEnoughPower)
+        }
+    }
+}
+
+#[test]
+fn test_contract_model() {
+    use clarity::PrivateKey as EthPrivateKey;
+    use deep_space::address::Address as CosmosAddress;
+    use peggy_utils::types::{
+        ERC20Token, ValsetMember, ETHEREUM_POWER_THRESHOLD, TOTAL_PEGGY_POWER,
+    };
+
+    let address = |i: u8| EthAddress::from_slice(&[i; 20]).unwrap();
+    let valset = |nonce: u64, members: &[u8]| Valset {
+        nonce,
+        members: members
+            .iter()
+            .map(|i| ValsetMember {
+                power: TOTAL_PEGGY_POWER / members.len() as u64,
+                eth_address: Some(address(*i)),
+            })
+            .collect(),
+    };
+    let signature = EthPrivateKey::from_slice(&[1u8; 32])
+        .unwrap()
+        .sign_ethereum_msg(b"confirm");
+    let valset_confirms = |nonce: u64, signers: &[u8]| -> Vec<ValsetConfirmResponse> {
+        signers
+            .iter()
+            .map(|i| ValsetConfirmResponse {
+                orchestrator: CosmosAddress::from_bytes([*i; 20]),
+                eth_address: address(*i),
+                nonce,
+                eth_signature: signature.clone(),
+            })
+            .collect()
+    };
+    let token = address(9);
+    let batch = |nonce: u64| TransactionBatch {
+        nonce,
+        transactions: Vec::new(),
+        total_fee: ERC20Token {
+            amount: 0u8.into(),
+            token_contract_address: token,
+        },
+        token_contract: token,
+        block: 0,
+    };
+    let batch_confirms = |nonce: u64, signers: &[u8]| -> Vec<BatchConfirmResponse> {
+        signers
+            .iter()
+            .map(|i| BatchConfirmResponse {
+                nonce,
+                orchestrator: CosmosAddress::from_bytes([*i; 20]),
+                token_contract: token,
+                ethereum_signer: address(*i),
+                eth_signature: signature.clone(),
+            })
+            .collect()
+    };
+
+    let first = valset(0, &[1, 2, 3]);
+    let mut contract = PeggyContractModel::new(first.clone(), ETHEREUM_POWER_THRESHOLD);
+
+    let second = valset(5, &[1, 2, 4]);
+    assert_eq!(
+        contract.update_valset(&second, &first, &valset_confirms(5, &[1])),
+        Err(Revert::NotEnoughPower)
+    );
+    // two thirds is just over the 66% the contract needs
+    contract
+        .update_valset(&second, &first, &valset_confirms(5, &[1, 2]))
+        .unwrap();
+    assert_eq!(contract.valset_nonce(), 5);
+    assert_eq!(
+        contract.update_valset(&valset(6, &[1]), &first, &valset_confirms(6, &[1, 2, 3])),
+        Err(Revert::CheckpointMismatch)
+    );
+    assert_eq!(
+        contract.update_valset(&valset(4, &[1]), &second, &valset_confirms(4, &[1, 2, 4])),
+        Err(Revert::ValsetNonceNotIncreasing)
+    );
+
+    // batches are signed by the valset the contract holds now
+    assert_eq!(
+        contract.submit_batch(&batch(2), &second, &batch_confirms(2, &[1, 3])),
+        Err(Revert::NotEnoughPower)
+    );
+    contract
+        .submit_batch(&batch(2), &second, &batch_confirms(2, &[1, 2, 4]))
+        .unwrap();
+    assert_eq!(contract.batch_nonce(token), 2);
+    assert_eq!(
+        contract.submit_batch(&batch(1), &second, &batch_confirms(1, &[1, 2, 4])),
+        Err(Revert::BatchNonceNotIncreasing)
+    );
+    contract.deposit().unwrap();
+    assert_eq!(contract.event_nonce, 2);
+
+    contract.halted = true;
+    assert_eq!(contract.deposit(), Err(Revert::Halted));
+}
@@ -0,0 +1,122 @@
+//! Dry runs build and sign batch and valset submissions exactly like a real relay, but write the raw
+//! transactions to a file instead of broadcasting them, one 0x prefixed hex transaction per line. They can be
+//! inspected, simulated or broadcast through another channel with eth_sendRawTransaction. Transactions
+//! written in the same run take consecutive nonces, so they go through when broadcast in file order.
+
+use clarity::utils::bytes_to_hex_str;
+use clarity::PrivateKey as EthPrivateKey;
+use clarity::Transaction;
+use num256::Uint256;
+use peggy_utils::error::PeggyError;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use web30::client::Web3;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DryRunOutput {
+    path: PathBuf,
+    /// the nonce after the last transaction we wrote
+    next_nonce: Option<Uint256>,
+    pub written: usize,
+}
+
+fn file_error(path: &Path, e: std::io::Error) -> PeggyError {
+    PeggyError::InvalidOptionsError(format!("Failed to write {}: {}", path.display(), e))
+}
+
+impl DryRunOutput {
+    /// Starts with an empty file at `path`, replacing what an earlier run wrote
+    pub fn create(path: &Path) -> Result<Self, PeggyError> {
+        fs::write(path, "").map_err(|e| file_error(path, e))?;
+        Ok(DryRunOutput {
+            path: path.to_path_buf(),
+            next_nonce: None,
+            written: 0,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The nonce to sign with, our account's `nonce` unless it's taken by a transaction we already wrote
+    pub fn nonce(&self, nonce: Uint256) -> Uint256 {
+        match self.next_nonce.as_ref() {
+            Some(next) if *next > nonce => next.clone(),
+            _ => nonce,
+        }
+    }
+
+    /// Signs `transaction` for the chain `web3` is on and writes it, returns the raw transaction
+    pub async fn write(
+        &mut self,
+        web3: &Web3,
+        transaction: Transaction,
+        key: &EthPrivateKey,
+    ) -> Result<String, PeggyError> {
+        let transaction = Transaction {
+            nonce: self.nonce(transaction.nonce.clone()),
+            ..transaction
+        };
+        let signed = transaction.sign(key, Some(web3.net_version().await?));
+        self.write_signed(&signed)
+    }
+
+    pub fn write_signed(&mut self, signed: &Transaction) -> Result<String, PeggyError> {
+        let raw = format!("0x{}", bytes_to_hex_str(&signed.to_bytes()?));
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| file_error(&self.path, e))?;
+        writeln!(file, "{}", raw).map_err(|e| file_error(&self.path, e))?;
+        self.next_nonce = Some(signed.nonce.clone() + 1u8.into());
+        self.written += 1;
+        Ok(raw)
+    }
+}
+
+#[test]
+fn test_dry_run_output() {
+    let path = std::env::temp_dir().join("peggy-dry-run-test.tx");
+    let mut output = DryRunOutput::create(&path).unwrap();
+    let key: EthPrivateKey = "0xfe1e6d8d2b6b2a3a0b5c4cb1b2c5ac7c9ab4cb5e8a8fb4e1f7a7c4fd1e8e0c11"
+        .parse()
+        .unwrap();
+    let transaction = |nonce: u64| {
+        Transaction {
+            to: "0x0101010101010101010101010101010101010101"
+                .parse()
+                .unwrap(),
+            nonce: nonce.into(),
+            gas_price: 1u8.into(),
+            gas_limit: 1_000_000u64.into(),
+            value: 0u8.into(),
+            data: vec![1, 2, 3],
+            signature: None,
+        }
+        .sign(&key, Some(1))
+    };
+
+    assert_eq!(output.nonce(5u8.into()), 5u8.into());
+    let first = output.write_signed(&transaction(5)).unwrap();
+    assert!(first.starts_with("0x"));
+    // our account nonce doesn't move in a dry run, the next transaction follows the one we wrote
+    assert_eq!(output.nonce(5u8.into()), 6u8.into());
+    assert_eq!(output.nonce(9u8.into()), 9u8.into());
+    output.write_signed(&transaction(6)).unwrap();
+    assert_eq!(output.written, 2);
+
+    let lines: Vec<String> = fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], first);
+
+    // a new run starts over
+    assert!(DryRunOutput::create(&path).is_ok());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    fs::remove_file(&path).unwrap();
+}
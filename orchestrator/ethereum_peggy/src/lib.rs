@@ -2,7 +2,15 @@
 
 #[macro_use]
 extern crate log;
+#[macro_use]
+extern crate lazy_static;
 
+pub mod abi_decode;
+pub mod abi_drift;
+pub mod capabilities;
+pub mod checkpoint_cache;
+pub mod contract_model;
+pub mod dry_run;
 pub mod message_signatures;
 pub mod send_to_cosmos;
 pub mod submit_batch;
@@ -1,6 +1,10 @@
 use clarity::abi::{encode_tokens, Token};
 use peggy_utils::types::{TransactionBatch, Valset};
 
+/// Bumped whenever the encoding of the signed valset or batch messages changes, orchestrators
+/// on different schema versions produce signatures the contract won't accept together
+pub const ENCODING_SCHEMA_VERSION: u32 = 1;
+
 /// takes the required input data and produces the required signature to confirm a validator
 /// set update on the Peggy Ethereum contract. This value will then be signed before being
 /// submitted to Cosmos, verified, and then relayed to Ethereum
@@ -150,6 +154,7 @@ fn test_batch_signature() {
         }],
         total_fee: token,
         token_contract: erc20_addr,
+        block: 0,
     };
 
     let checkpoint = encode_tx_batch_confirm("foo".to_string(), batch);
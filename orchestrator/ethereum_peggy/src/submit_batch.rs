@@ -1,39 +1,61 @@
-use crate::utils::get_tx_batch_nonce;
+use crate::capabilities::get_contract_capabilities;
+use crate::checkpoint_cache::{batch_checkpoint, Checkpoint};
+use crate::dry_run::DryRunOutput;
+use crate::utils::{get_peggy_id_string, get_power_threshold, get_tx_batch_nonce};
 use clarity::{Address as EthAddress, Transaction};
 use clarity::PrivateKey as EthPrivateKey;
 use num256::Uint256;
 use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::redact::{log_sensitive, Sensitive};
 use peggy_utils::types::*;
 use std::time::Duration;
 use web30::client::Web3;
 use web30::types::{SendTxOption, TransactionRequest};
 use clarity::utils::bytes_to_hex_str;
 
-/// this function generates an appropriate Ethereum transaction
-/// to submit the provided transaction batch and validator set update.
-pub async fn send_eth_transaction_batch(
-    current_valset: Valset,
-    batch: TransactionBatch,
+/// The submitBatch function signature for the contract's signature encoding
+pub fn submit_batch_signature(encoding: SignatureEncoding) -> String {
+    format!(
+        "submitBatch(address[],uint256[],uint256,{},uint256[],address[],uint256,address)",
+        encoding.abi_types()
+    )
+}
+
+/// The gas limit batches are submitted with, also an upper bound of what relaying a batch costs
+pub const BATCH_GAS_LIMIT: u64 = 1_000_000;
+
+/// The submitBatch calldata for a batch along with what it was built from
+#[derive(Debug, Clone)]
+pub struct SubmitBatchCall {
+    pub checkpoint: Checkpoint,
+    /// the signatures as submitted in valset order, zeroed for validators that are left out
+    pub signatures: Vec<PeggySignature>,
+    pub payload: Vec<u8>,
+}
+
+/// Builds the exact submitBatch calldata a relayer submits for `batch` against the valset currently
+/// on Ethereum, the same code path the relayer uses so the result can be compared byte for byte.
+/// `power_threshold` is the contract's `state_powerThreshold`
+pub fn encode_submit_batch(
+    current_valset: &Valset,
+    batch: &TransactionBatch,
     confirms: &[BatchConfirmResponse],
-    web3: &Web3,
-    timeout: Duration,
-    peggy_contract_address: EthAddress,
-    our_eth_key: EthPrivateKey,
-    nonce: Uint256,
-) -> Result<(), PeggyError> {
+    peggy_id: &str,
+    signatures: SignatureSelection,
+    power_threshold: u64,
+    encoding: SignatureEncoding,
+) -> Result<SubmitBatchCall, PeggyError> {
     let (current_addresses, current_powers) = current_valset.filter_empty_addresses();
-    let current_valset_nonce = current_valset.nonce;
-    let new_batch_nonce = batch.nonce;
-    //assert!(new_valset_nonce > old_valset_nonce);
-    let eth_address = our_eth_key.to_public_key().unwrap();
-    info!(
-        "Ordering signatures and submitting TransacqtionBatch {}:{} to Ethereum",
-        batch.token_contract, new_batch_nonce
-    );
-    trace!("Batch {:?}", batch);
-
     let sig_data = current_valset.order_batch_sigs(confirms)?;
-    let sig_arrays = to_arrays(sig_data);
+    let checkpoint = batch_checkpoint(peggy_id, batch);
+    let sig_data = select_signatures(
+        sig_data,
+        &checkpoint.signed_hash,
+        signatures,
+        power_threshold,
+    )?;
+    let sig_arrays = to_arrays(sig_data.clone(), encoding);
     let (amounts, destinations) = batch.get_checkpoint_values();
 
     // Solidity function signature
@@ -42,7 +64,7 @@ pub async fn send_eth_transaction_batch(
     // address[] memory _currentValidators,
     // uint256[] memory _currentPowers,
     // uint256 _currentValsetNonce,
-    // // These are arrays of the parts of the validators signatures
+    // // These are arrays of the parts of the validators signatures, _r and _vs with compact signatures
     // uint8[] memory _v,
     // bytes32[] memory _r,
     // bytes32[] memory _s,
@@ -51,21 +73,72 @@ pub async fn send_eth_transaction_batch(
     // address[] memory _destinations,
     // uint256 _batchNonce,
     // address _tokenContract
-    let tokens = &[
+    let mut tokens = vec![
         current_addresses.into(),
         current_powers.into(),
-        current_valset_nonce.into(),
-        sig_arrays.v,
-        sig_arrays.r,
-        sig_arrays.s,
+        current_valset.nonce.into(),
+    ];
+    tokens.extend(sig_arrays.signatures);
+    tokens.extend(vec![
         amounts,
         destinations,
-        new_batch_nonce.clone().into(),
+        batch.nonce.into(),
         batch.token_contract.into(),
-    ];
-    let payload = clarity::abi::encode_call("submitBatch(address[],uint256[],uint256,uint8[],bytes32[],bytes32[],uint256[],address[],uint256,address)",
-    tokens).unwrap();
-    trace!("Tokens {:?}", tokens);
+    ]);
+    let payload = clarity::abi::encode_call(&submit_batch_signature(encoding), &tokens)?;
+    trace!("Tokens {:?}", Sensitive(&tokens));
+    Ok(SubmitBatchCall {
+        checkpoint,
+        signatures: sig_data,
+        payload,
+    })
+}
+
+/// this function generates an appropriate Ethereum transaction
+/// to submit the provided transaction batch and validator set update. Returns the hash of our
+/// transaction, also if it wasn't mined within `timeout`, None if we didn't send one. With a `dry_run`
+/// the signed transaction is written there instead of sent
+#[allow(clippy::too_many_arguments)]
+pub async fn send_eth_transaction_batch(
+    current_valset: Valset,
+    batch: TransactionBatch,
+    confirms: &[BatchConfirmResponse],
+    web3: &Web3,
+    timeout: Duration,
+    peggy_contract_address: EthAddress,
+    our_eth_key: EthPrivateKey,
+    nonce: Uint256,
+    signatures: SignatureSelection,
+    dry_run: Option<&mut DryRunOutput>,
+) -> Result<Option<Uint256>, PeggyError> {
+    let new_batch_nonce = batch.nonce;
+    //assert!(new_valset_nonce > old_valset_nonce);
+    let eth_address = our_eth_key.to_public_key().unwrap();
+    info!(
+        "Ordering signatures and submitting TransacqtionBatch {}:{} to Ethereum",
+        batch.token_contract, new_batch_nonce
+    );
+    trace!("Batch {:?}", batch);
+
+    let peggy_id = get_peggy_id_string(peggy_contract_address, eth_address, web3).await?;
+    let capabilities = get_contract_capabilities(peggy_contract_address, web3).await?;
+    if !capabilities.can_relay_batches() {
+        return Err(PeggyError::EthereumContractError(format!(
+            "Can't relay batches to the contract ({}), its submitBatch takes batch timeouts",
+            capabilities
+        )));
+    }
+    let power_threshold = get_power_threshold(peggy_contract_address, web3).await?;
+    let payload = encode_submit_batch(
+        &current_valset,
+        &batch,
+        confirms,
+        &peggy_id,
+        signatures,
+        power_threshold,
+        capabilities.signature_encoding,
+    )?
+    .payload;
 
     let before_nonce = get_tx_batch_nonce(
         peggy_contract_address,
@@ -79,7 +152,7 @@ pub async fn send_eth_transaction_batch(
             "Someone else updated the batch to {}, exiting early",
             before_nonce
         );
-        return Ok(());
+        return Ok(None);
     }
 
     info!("Sending ethereum tx");
@@ -87,14 +160,29 @@ pub async fn send_eth_transaction_batch(
     let transaction = Transaction {
         to: peggy_contract_address,
         nonce: nonce.clone(),
+        // always the spot price, averaged gas prices are only for estimates
         gas_price: web3.eth_gas_price().await?,
-        gas_limit: 1_000_000u32.into(),
+        gas_limit: BATCH_GAS_LIMIT.into(),
         value: 0u32.into(),
         data: payload.clone(),
         signature: None,
     };
 
-    info!("tx: {}", bytes_to_hex_str(&transaction.sign(&our_eth_key, Some(web3.net_version().await?)).to_bytes().unwrap()));
+    if let Some(dry_run) = dry_run {
+        dry_run.write(web3, transaction, &our_eth_key).await?;
+        info!(
+            "Dry run, wrote batch {}:{} to {} instead of sending it",
+            batch.token_contract,
+            new_batch_nonce,
+            dry_run.path().display()
+        );
+        return Ok(None);
+    }
+
+    // the raw signed tx is only worth the extra signing and net_version call if it's going to be logged
+    if log_sensitive() {
+        info!("tx: {}", bytes_to_hex_str(&transaction.sign(&our_eth_key, Some(web3.net_version().await?)).to_bytes().unwrap()));
+    }
 
     let estimate_result = web3.eth_estimate_gas(TransactionRequest {
         from: Some(eth_address),
@@ -108,7 +196,7 @@ pub async fn send_eth_transaction_batch(
 
     match estimate_result {
         Ok(gas) => {
-            if gas.gt(&1_000_000u64.into()) {
+            if gas.gt(&BATCH_GAS_LIMIT.into()) {
                 error!("Error while sending tx: gas limit is too high, possibly trying to send failing tx {}", gas);
             }
         }
@@ -125,7 +213,7 @@ pub async fn send_eth_transaction_batch(
             eth_address,
             our_eth_key,
             vec![
-                SendTxOption::GasLimit(1_000_000u32.into()),
+                SendTxOption::GasLimit(BATCH_GAS_LIMIT.into()),
                 SendTxOption::Nonce(nonce),
             ],
         )
@@ -136,18 +224,22 @@ pub async fn send_eth_transaction_batch(
         Err(e) => {
             error!("Error while sending tx: {}", e);
 
-            return Ok(());
+            return Ok(None);
         }
     };
 
-    info!("Sent batch update with txid {:#066x}", tx);
+    let txid = explorer::tx(Chain::Ethereum, format!("{:#066x}", tx));
+    info!("Sent batch update with txid {}", txid);
 
     // TODO this segment of code works around the race condition for submitting batches mostly
     // by not caring if our own submission reverts and only checking if the valset has been updated
     // period not if our update succeeded in particular. This will require some further consideration
     // in the future as many independent relayers racing to update the same thing will hopefully
     // be the common case.
-    web3.wait_for_transaction(tx, timeout, None).await?;
+    if let Err(e) = web3.wait_for_transaction(tx.clone(), timeout, None).await {
+        warn!("Batch update {} not mined yet: {}", txid, e);
+        return Ok(Some(tx));
+    }
 
     let last_nonce = get_tx_batch_nonce(
         peggy_contract_address,
@@ -158,11 +250,11 @@ pub async fn send_eth_transaction_batch(
     .await?;
     if last_nonce != new_batch_nonce {
         error!(
-            "Current nonce is {} expected to update to nonce {}",
-            last_nonce, new_batch_nonce
+            "Current nonce is {} expected to update to nonce {} with txid {}",
+            last_nonce, new_batch_nonce, txid
         );
     } else {
         info!("Successfully updated Batch with new Nonce {:?}", last_nonce);
     }
-    Ok(())
+    Ok(Some(tx))
 }
@@ -1,3 +1,4 @@
+use crate::abi_decode::{decode_bool, decode_bytes32, decode_u64, decode_uint256};
 use clarity::abi::{Token, encode_call};
 use clarity::Uint256;
 use clarity::{abi::encode_tokens, Address as EthAddress};
@@ -42,6 +43,26 @@ pub fn get_checkpoint_hash(valset: &Valset, peggy_id: &str) -> Result<Vec<u8>, P
     Ok(locally_computed_digest.to_vec())
 }
 
+/// The hash an orchestrator actually signs for `message`, the same Ethereum signed message digest
+/// `sign_ethereum_msg` produces and the contract checks signatures against
+pub fn get_signed_message_hash(message: &[u8]) -> Vec<u8> {
+    let digest = Keccak256::digest(message);
+    let mut salted = b"\x19Ethereum Signed Message:\n32".to_vec();
+    salted.extend_from_slice(&digest);
+    Keccak256::digest(&salted).to_vec()
+}
+
+/// Gets the PeggyID as the string orchestrators sign with
+pub async fn get_peggy_id_string(
+    contract_address: EthAddress,
+    caller_address: EthAddress,
+    web3: &Web3,
+) -> Result<String, PeggyError> {
+    let peggy_id = get_peggy_id(contract_address, caller_address, web3).await?;
+    String::from_utf8(peggy_id)
+        .map_err(|e| PeggyError::InvalidBridgeStateError(format!("Invalid PeggyID {}", e)))
+}
+
 pub fn downcast_nonce(input: Uint256) -> Option<u64> {
     if input >= U64MAX.into() {
         None
@@ -74,13 +95,29 @@ fn test_downcast_nonce() {
     }
 }
 
-/// Gets the latest validator set nonce
-pub async fn get_valset_nonce(
+#[test]
+fn test_signed_message_hash() {
+    use clarity::PrivateKey as EthPrivateKey;
+
+    let key: EthPrivateKey = "0xe108657c5d7bb1b1b4a6d8bcda6ca16b8ba0d8a6d0e7cbb2b2a2ee0d9a1fa5e1"
+        .parse()
+        .unwrap();
+    let message = b"checkpoint";
+    let signature = key.sign_ethereum_msg(message);
+    assert_eq!(
+        signature
+            .recover(&get_signed_message_hash(message))
+            .unwrap(),
+        key.to_public_key().unwrap()
+    );
+}
+
+/// Makes a read only eth_call to the given contract and returns the raw return data
+pub(crate) async fn call_view(
     contract_address: EthAddress,
-    _caller_address: EthAddress,
+    payload: Vec<u8>,
     web3: &Web3,
-) -> Result<u64, Web3Error> {
-    let payload = encode_call("state_lastValsetNonce()", &[])?;
+) -> Result<Vec<u8>, Web3Error> {
     let transaction = TransactionRequest {
         from: None,
         to: contract_address,
@@ -91,13 +128,29 @@ pub async fn get_valset_nonce(
         nonce: None
     };
 
-    let bytes = match web3.eth_call(transaction).await {
-        Ok(val) => val,
-        Err(e) => return Err(e),
-    };
+    let bytes = web3.eth_call(transaction).await?;
+    Ok(bytes.0)
+}
+
+/// Gets the latest validator set nonce
+pub async fn get_valset_nonce(
+    contract_address: EthAddress,
+    _caller_address: EthAddress,
+    web3: &Web3,
+) -> Result<u64, Web3Error> {
+    let payload = encode_call("state_lastValsetNonce()", &[])?;
+    let bytes = call_view(contract_address, payload, web3).await?;
+    decode_u64(&bytes)
+}
 
-    let real_num = Uint256::from_bytes_be(&bytes.0);
-    Ok(downcast_nonce(real_num).expect("Valset nonce overflow! Bridge Halt!"))
+/// Gets the latest event nonce issued by the contract
+pub async fn get_event_nonce(
+    contract_address: EthAddress,
+    web3: &Web3,
+) -> Result<u64, Web3Error> {
+    let payload = encode_call("state_lastEventNonce()", &[])?;
+    let bytes = call_view(contract_address, payload, web3).await?;
+    decode_u64(&bytes)
 }
 
 /// Gets the latest transaction batch nonce
@@ -108,23 +161,8 @@ pub async fn get_tx_batch_nonce(
     web3: &Web3,
 ) -> Result<u64, Web3Error> {
     let payload = encode_call("lastBatchNonce(address)", &[erc20_contract_address.into()])?;
-    let transaction = TransactionRequest {
-        from: None,
-        to: peggy_contract_address,
-        gas: Some((u64::MAX - 1).into()),
-        gas_price: None,
-        value: Some(UnpaddedHex(0u64.into())),
-        data: Some(Data(payload)),
-        nonce: None
-    };
-
-    let bytes = match web3.eth_call(transaction).await {
-        Ok(val) => val,
-        Err(e) => return Err(e),
-    };
-
-    let real_num = Uint256::from_bytes_be(&bytes.0);
-    Ok(downcast_nonce(real_num).expect("TxBatch nonce overflow! Bridge Halt!"))
+    let bytes = call_view(peggy_contract_address, payload, web3).await?;
+    decode_u64(&bytes)
 }
 
 /// Gets the peggyID
@@ -134,20 +172,104 @@ pub async fn get_peggy_id(
     web3: &Web3,
 ) -> Result<Vec<u8>, Web3Error> {
     let payload = encode_call("state_peggyId()", &[])?;
-    let transaction = TransactionRequest {
-        from: None,
-        to: contract_address,
-        gas: Some((u64::MAX - 1).into()),
-        gas_price: None,
-        value: Some(UnpaddedHex(0u64.into())),
-        data: Some(Data(payload)),
-        nonce: None
-    };
+    let bytes = call_view(contract_address, payload, web3).await?;
+    Ok(decode_bytes32(&bytes)?.to_vec())
+}
 
-    let bytes = match web3.eth_call(transaction).await {
-        Ok(val) => val,
-        Err(e) => return Err(e),
-    };
+/// Gets the power the contract was deployed to require, valsets and batches need signatures of strictly more
+pub async fn get_power_threshold(
+    contract_address: EthAddress,
+    web3: &Web3,
+) -> Result<u64, Web3Error> {
+    let payload = encode_call("state_powerThreshold()", &[])?;
+    let bytes = call_view(contract_address, payload, web3).await?;
+    decode_u64(&bytes)
+}
 
-    Ok(bytes.0)
+/// Checks if the guardian has halted the contract, while halted valset updates and batches revert
+pub async fn get_halted(contract_address: EthAddress, web3: &Web3) -> Result<bool, Web3Error> {
+    let payload = encode_call("halted()", &[])?;
+    let bytes = call_view(contract_address, payload, web3).await?;
+    decode_bool(&bytes)
+}
+
+/// Checks if the guardian has stopped deposits to the Hub
+pub async fn get_deposits_stopped(
+    contract_address: EthAddress,
+    web3: &Web3,
+) -> Result<bool, Web3Error> {
+    let payload = encode_call("depositsStopped()", &[])?;
+    let bytes = call_view(contract_address, payload, web3).await?;
+    decode_bool(&bytes)
+}
+
+/// The view of permissioned deployments that tells if an address may call submitBatch
+pub const BATCH_RELAYER_WHITELIST_SIGNATURE: &str = "isBatchRelayer(address)";
+
+/// Whether an address may relay batches to the contract
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RelayerAuthorization {
+    /// the contract has no relayer whitelist, anyone may relay
+    Unrestricted,
+    Authorized,
+    NotAuthorized,
+}
+
+/// Checks the relayer whitelist of permissioned deployments. The stock contract has no whitelist and reverts
+/// the whitelist call, so the contract is first checked to be reachable with a view every deployment has and
+/// a revert of the whitelist call after that means there is no whitelist.
+pub async fn get_relayer_authorization(
+    contract_address: EthAddress,
+    relayer: EthAddress,
+    web3: &Web3,
+) -> Result<RelayerAuthorization, Web3Error> {
+    get_halted(contract_address, web3).await?;
+    let payload = encode_call(BATCH_RELAYER_WHITELIST_SIGNATURE, &[relayer.into()])?;
+    match call_view(contract_address, payload, web3).await {
+        Ok(bytes) if bytes.is_empty() => Ok(RelayerAuthorization::Unrestricted),
+        Ok(bytes) => {
+            if decode_bool(&bytes)? {
+                Ok(RelayerAuthorization::Authorized)
+            } else {
+                Ok(RelayerAuthorization::NotAuthorized)
+            }
+        }
+        Err(_) => Ok(RelayerAuthorization::Unrestricted),
+    }
+}
+
+/// The view of contract versions that accept EIP-2098 compact signatures
+pub const COMPACT_SIGNATURES_SIGNATURE: &str = "supportsCompactSignatures()";
+
+/// Detects which signature encoding the contract takes. Like the relayer whitelist the view only exists on
+/// contract versions that have it, so after checking the contract is reachable a revert means the stock
+/// 65 byte signatures.
+pub async fn get_signature_encoding(
+    contract_address: EthAddress,
+    web3: &Web3,
+) -> Result<SignatureEncoding, Web3Error> {
+    get_halted(contract_address, web3).await?;
+    let payload = encode_call(COMPACT_SIGNATURES_SIGNATURE, &[])?;
+    match call_view(contract_address, payload, web3).await {
+        Ok(bytes) if bytes.is_empty() => Ok(SignatureEncoding::Standard),
+        Ok(bytes) => {
+            if decode_bool(&bytes)? {
+                Ok(SignatureEncoding::Compact)
+            } else {
+                Ok(SignatureEncoding::Standard)
+            }
+        }
+        Err(_) => Ok(SignatureEncoding::Standard),
+    }
+}
+
+/// Gets the amount of an ERC20 token locked in the Peggy contract, that is the supply bridged to the Hub
+pub async fn get_locked_balance(
+    peggy_contract_address: EthAddress,
+    erc20_contract_address: EthAddress,
+    web3: &Web3,
+) -> Result<Uint256, Web3Error> {
+    let payload = encode_call("balanceOf(address)", &[peggy_contract_address.into()])?;
+    let bytes = call_view(erc20_contract_address, payload, web3).await?;
+    decode_uint256(&bytes)
 }
@@ -1,14 +1,34 @@
-use crate::utils::get_valset_nonce;
+use crate::checkpoint_cache::valset_checkpoint;
+use crate::dry_run::DryRunOutput;
+use crate::utils::{
+    get_peggy_id_string, get_power_threshold, get_signature_encoding, get_valset_nonce,
+};
 use clarity::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
+use clarity::{Transaction, Uint256};
 use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
 use peggy_utils::types::*;
 use std::time::Duration;
 use web30::client::Web3;
 use web30::types::SendTxOption;
 
+/// The updateValset function signature for the contract's signature encoding
+pub fn valset_update_signature(encoding: SignatureEncoding) -> String {
+    format!(
+        "updateValset(address[],uint256[],uint256,address[],uint256[],uint256,{})",
+        encoding.abi_types()
+    )
+}
+
+/// The gas limit valset updates are submitted with
+const VALSET_GAS_LIMIT: u64 = 1_000_000;
+
 /// this function generates an appropriate Ethereum transaction
-/// to submit the provided validator set and signatures.
+/// to submit the provided validator set and signatures. Returns the hash of our transaction, also if it
+/// wasn't mined within `timeout`, None if we didn't send one. With a `dry_run` the signed transaction
+/// is written there instead of sent
+#[allow(clippy::too_many_arguments)]
 pub async fn send_eth_valset_update(
     new_valset: Valset,
     old_valset: Valset,
@@ -17,7 +37,9 @@ pub async fn send_eth_valset_update(
     timeout: Duration,
     peggy_contract_address: EthAddress,
     our_eth_key: EthPrivateKey,
-) -> Result<(), PeggyError> {
+    signatures: SignatureSelection,
+    dry_run: Option<&mut DryRunOutput>,
+) -> Result<Option<Uint256>, PeggyError> {
     let (old_addresses, old_powers) = old_valset.filter_empty_addresses();
     let (new_addresses, new_powers) = new_valset.filter_empty_addresses();
     let old_nonce = old_valset.nonce;
@@ -32,7 +54,17 @@ pub async fn send_eth_valset_update(
     // we need to use the old valset here because our signatures need to match the current
     // members of the validator set in the contract.
     let sig_data = old_valset.order_valset_sigs(confirms)?;
-    let sig_arrays = to_arrays(sig_data);
+    let peggy_id = get_peggy_id_string(peggy_contract_address, eth_address, web3).await?;
+    let checkpoint = valset_checkpoint(&peggy_id, &new_valset);
+    let power_threshold = get_power_threshold(peggy_contract_address, web3).await?;
+    let sig_data = select_signatures(
+        sig_data,
+        &checkpoint.signed_hash,
+        signatures,
+        power_threshold,
+    )?;
+    let encoding = get_signature_encoding(peggy_contract_address, web3).await?;
+    let sig_arrays = to_arrays(sig_data, encoding);
 
     // Solidity function signature
     // function updateValset(
@@ -44,23 +76,21 @@ pub async fn send_eth_valset_update(
     // address[] memory _currentValidators,
     // uint256[] memory _currentPowers,
     // uint256 _currentValsetNonce,
-    // // These are arrays of the parts of the current validator's signatures
+    // // These are arrays of the parts of the current validator's signatures, _r and _vs with compact
+    // // signatures
     // uint8[] memory _v,
     // bytes32[] memory _r,
     // bytes32[] memory _s
-    let tokens = &[
+    let mut tokens = vec![
         new_addresses.into(),
         new_powers.into(),
         new_nonce.into(),
         old_addresses.into(),
         old_powers.into(),
         old_nonce.into(),
-        sig_arrays.v,
-        sig_arrays.r,
-        sig_arrays.s,
     ];
-    let payload = clarity::abi::encode_call("updateValset(address[],uint256[],uint256,address[],uint256[],uint256,uint8[],bytes32[],bytes32[])",
-    tokens).unwrap();
+    tokens.extend(sig_arrays.signatures);
+    let payload = clarity::abi::encode_call(&valset_update_signature(encoding), &tokens).unwrap();
 
     let before_nonce = get_valset_nonce(peggy_contract_address, eth_address, web3).await?;
     if before_nonce != old_nonce {
@@ -68,7 +98,27 @@ pub async fn send_eth_valset_update(
             "Someone else updated the valset to {}, exiting early",
             before_nonce
         );
-        return Ok(());
+        return Ok(None);
+    }
+
+    if let Some(dry_run) = dry_run {
+        let transaction = Transaction {
+            to: peggy_contract_address,
+            nonce: web3.eth_get_transaction_count(eth_address).await?,
+            gas_price: web3.eth_gas_price().await?,
+            gas_limit: VALSET_GAS_LIMIT.into(),
+            value: 0u32.into(),
+            data: payload,
+            signature: None,
+        };
+        dry_run.write(web3, transaction, &our_eth_key).await?;
+        info!(
+            "Dry run, wrote valset update {} -> {} to {} instead of sending it",
+            old_nonce,
+            new_nonce,
+            dry_run.path().display()
+        );
+        return Ok(None);
     }
 
     let tx = web3
@@ -78,23 +128,27 @@ pub async fn send_eth_valset_update(
             0u32.into(),
             eth_address,
             our_eth_key,
-            vec![SendTxOption::GasLimit(1_000_000u32.into())],
+            vec![SendTxOption::GasLimit(VALSET_GAS_LIMIT.into())],
         )
         .await?;
-    info!("Sent valset update with txid {:#066x}", tx);
+    let txid = explorer::tx(Chain::Ethereum, format!("{:#066x}", tx));
+    info!("Sent valset update with txid {}", txid);
 
     // TODO this segment of code works around the race condition for submitting valsets mostly
     // by not caring if our own submission reverts and only checking if the valset has been updated
     // period not if our update succeeded in particular. This will require some further consideration
     // in the future as many independent relayers racing to update the same thing will hopefully
     // be the common case.
-    web3.wait_for_transaction(tx, timeout, None).await?;
+    if let Err(e) = web3.wait_for_transaction(tx.clone(), timeout, None).await {
+        warn!("Valset update {} not mined yet: {}", txid, e);
+        return Ok(Some(tx));
+    }
 
     let last_nonce = get_valset_nonce(peggy_contract_address, eth_address, web3).await?;
     if last_nonce != new_nonce {
         error!(
-            "Current nonce is {} expected to update to nonce {}",
-            last_nonce, new_nonce
+            "Current nonce is {} expected to update to nonce {} with txid {}",
+            last_nonce, new_nonce, txid
         );
     } else {
         info!(
@@ -102,5 +156,5 @@ pub async fn send_eth_valset_update(
             last_nonce
         );
     }
-    Ok(())
+    Ok(Some(tx))
 }
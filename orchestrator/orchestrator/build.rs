@@ -0,0 +1,28 @@
+//! Embeds the git commit and build profile so every orchestrator can report exactly what it runs. Release
+//! builds should set PEGGY_GIT_COMMIT, that keeps the build reproducible from a source tarball without git.
+
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=PEGGY_GIT_COMMIT");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+
+    let commit = env::var("PEGGY_GIT_COMMIT").ok().or_else(|| {
+        Command::new("git")
+            .args(&["rev-parse", "--short=12", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|commit| commit.trim().to_string())
+    });
+    println!(
+        "cargo:rustc-env=PEGGY_GIT_COMMIT={}",
+        commit.unwrap_or_else(|| "unknown".to_string())
+    );
+    println!(
+        "cargo:rustc-env=PEGGY_BUILD_PROFILE={}",
+        env::var("PROFILE").unwrap_or_else(|_| "unknown".to_string())
+    );
+}
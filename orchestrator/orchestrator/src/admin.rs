@@ -0,0 +1,297 @@
+//! Operational overrides for a running orchestrator. `orchestrator admin` writes the operator's instructions to
+//! a file in the state directory and the running orchestrator picks them up between loop iterations, the same
+//! way rotate-keys hands over new keys, so pausing the relayer, skipping a bad batch, turning relaying for a
+//! token off or on, rescanning Ethereum, signing a delayed claim early or changing a subsystem's log level doesn't
+//! need a restart. Only whoever can write to the state directory can give instructions, the file itself is
+//! created readable by the current user only.
+
+use crate::log_levels::{parse_level, set_levels, Subsystem};
+use crate::main_loop::LOOP_SPEED;
+use clarity::{Address as EthAddress, Uint256};
+use log::LevelFilter;
+use peggy_utils::error::PeggyError;
+use relayer::main_loop::RelayerControls;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tokio::time::delay_for;
+
+pub const ADMIN_FILE: &str = "admin.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SkippedBatch {
+    pub token_contract: EthAddress,
+    pub nonce: u64,
+}
+
+/// The pending operator instructions as stored in the state directory
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct AdminRequests {
+    #[serde(default)]
+    pub relaying_paused: bool,
+    #[serde(default)]
+    pub skipped_batches: Vec<SkippedBatch>,
+    /// tokens the relayer stops relaying batches for
+    #[serde(default)]
+    pub disabled_tokens: Vec<EthAddress>,
+    /// tokens the relayer relays even though they are disabled in the config
+    #[serde(default)]
+    pub enabled_tokens: Vec<EthAddress>,
+    /// the oracle restarts its scan from this block, cleared once the oracle has applied it
+    #[serde(default)]
+    pub rescan_from_block: Option<Uint256>,
+    /// event nonces of delayed claims to sign without waiting out the delay, cleared once applied
+    #[serde(default)]
+    pub released_claims: Vec<u64>,
+    /// log levels by subsystem name, for the subsystems not logged as `RUST_LOG` sets
+    #[serde(default)]
+    pub log_levels: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum AdminCommand {
+    PauseRelaying,
+    ResumeRelaying,
+    SkipBatch(SkippedBatch),
+    DisableToken(EthAddress),
+    EnableToken(EthAddress),
+    Rescan(Uint256),
+    ReleaseClaim(u64),
+    /// None hands the subsystem back to `RUST_LOG`
+    SetLogLevel(Subsystem, Option<LevelFilter>),
+}
+
+impl AdminRequests {
+    pub fn apply(&mut self, command: AdminCommand) {
+        match command {
+            AdminCommand::PauseRelaying => self.relaying_paused = true,
+            AdminCommand::ResumeRelaying => self.relaying_paused = false,
+            AdminCommand::SkipBatch(batch) => {
+                if !self.skipped_batches.contains(&batch) {
+                    self.skipped_batches.push(batch)
+                }
+            }
+            AdminCommand::DisableToken(token) => {
+                self.enabled_tokens.retain(|t| *t != token);
+                if !self.disabled_tokens.contains(&token) {
+                    self.disabled_tokens.push(token)
+                }
+            }
+            AdminCommand::EnableToken(token) => {
+                self.disabled_tokens.retain(|t| *t != token);
+                if !self.enabled_tokens.contains(&token) {
+                    self.enabled_tokens.push(token)
+                }
+            }
+            // an earlier block covers both requests
+            AdminCommand::Rescan(block) => match self.rescan_from_block.as_ref() {
+                Some(existing) if *existing <= block => {}
+                _ => self.rescan_from_block = Some(block),
+            },
+            AdminCommand::ReleaseClaim(nonce) => {
+                if !self.released_claims.contains(&nonce) {
+                    self.released_claims.push(nonce)
+                }
+            }
+            AdminCommand::SetLogLevel(subsystem, Some(level)) => {
+                self.log_levels.insert(
+                    subsystem.name().to_string(),
+                    level.to_string().to_lowercase(),
+                );
+            }
+            AdminCommand::SetLogLevel(subsystem, None) => {
+                self.log_levels.remove(subsystem.name());
+            }
+        }
+    }
+
+    /// The subsystem log levels, entries that don't parse are left to `RUST_LOG`
+    pub fn log_levels(&self) -> BTreeMap<Subsystem, LevelFilter> {
+        self.log_levels
+            .iter()
+            .filter_map(|(subsystem, level)| {
+                let level = parse_level(level).ok()??;
+                Some((subsystem.parse().ok()?, level))
+            })
+            .collect()
+    }
+
+    /// The relayer controls for these instructions on top of the `configured` ones from --skip-batches and
+    /// --disable-tokens, the operator can enable a token the config disables
+    pub fn relayer_controls(&self, configured: &RelayerControls) -> RelayerControls {
+        let mut skipped_batches = configured.skipped_batches.clone();
+        for batch in self.skipped_batches.iter() {
+            if !skipped_batches.contains(&(batch.token_contract, batch.nonce)) {
+                skipped_batches.push((batch.token_contract, batch.nonce));
+            }
+        }
+        let mut disabled_tokens = configured.disabled_tokens.clone();
+        for token in self.disabled_tokens.iter() {
+            if !disabled_tokens.contains(token) {
+                disabled_tokens.push(*token);
+            }
+        }
+        disabled_tokens.retain(|token| !self.enabled_tokens.contains(token));
+        RelayerControls {
+            paused: self.relaying_paused,
+            skipped_batches,
+            disabled_tokens,
+            requested_batches: configured.requested_batches.clone(),
+            sponsors: configured.sponsors.clone(),
+        }
+    }
+}
+
+fn admin_error(path: &Path, action: &str, e: impl ToString) -> PeggyError {
+    PeggyError::StateStoreError(format!(
+        "Failed to {} {}: {}",
+        action,
+        path.display(),
+        e.to_string()
+    ))
+}
+
+pub fn read_admin(dir: &Path) -> Result<AdminRequests, PeggyError> {
+    let path = dir.join(ADMIN_FILE);
+    if !path.exists() {
+        return Ok(AdminRequests::default());
+    }
+    let contents = fs::read_to_string(&path).map_err(|e| admin_error(&path, "read", e))?;
+    serde_json::from_str(&contents).map_err(|e| admin_error(&path, "parse", e))
+}
+
+pub fn write_admin(dir: &Path, requests: &AdminRequests) -> Result<(), PeggyError> {
+    let path = dir.join(ADMIN_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&tmp_path)
+        .map_err(|e| admin_error(&tmp_path, "create", e))?;
+    file.write_all(serde_json::to_string_pretty(requests).unwrap().as_bytes())
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::rename(&tmp_path, &path))
+        .map_err(|e| admin_error(&path, "write", e))
+}
+
+/// Records an operator instruction for the running orchestrator
+pub fn submit_admin_command(dir: &Path, command: AdminCommand) -> Result<(), PeggyError> {
+    let mut requests = read_admin(dir)?;
+    requests.apply(command);
+    write_admin(dir, &requests)
+}
+
+/// Returns the one off oracle instructions and clears them so they are only applied once
+pub fn take_oracle_requests(dir: &Path) -> Result<(Option<Uint256>, Vec<u64>), PeggyError> {
+    let mut requests = read_admin(dir)?;
+    if requests.rescan_from_block.is_none() && requests.released_claims.is_empty() {
+        return Ok((None, Vec::new()));
+    }
+    let rescan_from_block = requests.rescan_from_block.take();
+    let released_claims = std::mem::take(&mut requests.released_claims);
+    write_admin(dir, &requests)?;
+    Ok((rescan_from_block, released_claims))
+}
+
+/// Runs alongside the orchestrator loops and keeps the relayer controls and log levels in step with the admin
+/// file
+pub async fn watch_admin(
+    dir: &Path,
+    configured: RelayerControls,
+    controls: Arc<RwLock<RelayerControls>>,
+) {
+    loop {
+        match read_admin(dir) {
+            Ok(requests) => {
+                *controls.write().unwrap() = requests.relayer_controls(&configured);
+                set_levels(&requests.log_levels());
+            }
+            Err(e) => error!("Failed to read admin instructions: {}", e),
+        }
+        delay_for(LOOP_SPEED).await;
+    }
+}
+
+#[test]
+fn test_admin_requests() {
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let dir = std::env::temp_dir().join(format!("peggy-admin-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    submit_admin_command(&dir, AdminCommand::PauseRelaying).unwrap();
+    let batch = SkippedBatch {
+        token_contract: token,
+        nonce: 7,
+    };
+    submit_admin_command(&dir, AdminCommand::SkipBatch(batch)).unwrap();
+    submit_admin_command(&dir, AdminCommand::SkipBatch(batch)).unwrap();
+    submit_admin_command(&dir, AdminCommand::Rescan(100u64.into())).unwrap();
+    submit_admin_command(&dir, AdminCommand::Rescan(200u64.into())).unwrap();
+    submit_admin_command(&dir, AdminCommand::ReleaseClaim(3)).unwrap();
+
+    let controls = read_admin(&dir)
+        .unwrap()
+        .relayer_controls(&RelayerControls::default());
+    assert!(controls.paused);
+    assert_eq!(controls.skipped_batches, vec![(token, 7)]);
+    // batches skipped in the config and by the operator are merged
+    let configured = RelayerControls {
+        skipped_batches: relayer::batch_relaying::parse_skipped_batches(
+            "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:5, 0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:7",
+        )
+        .unwrap(),
+        ..Default::default()
+    };
+    let controls = read_admin(&dir).unwrap().relayer_controls(&configured);
+    assert_eq!(controls.skipped_batches, vec![(token, 5), (token, 7)]);
+    assert!(relayer::batch_relaying::parse_skipped_batches("0x00:1").is_err());
+
+    // the operator can disable tokens and enable ones the config disables
+    let other: EthAddress = "0x0000000000000000000000000000000000000001"
+        .parse()
+        .unwrap();
+    let configured = RelayerControls {
+        disabled_tokens: relayer::batch_relaying::parse_tokens(
+            "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8,",
+        )
+        .unwrap(),
+        ..Default::default()
+    };
+    submit_admin_command(&dir, AdminCommand::DisableToken(other)).unwrap();
+    let controls = read_admin(&dir).unwrap().relayer_controls(&configured);
+    assert_eq!(controls.disabled_tokens, vec![token, other]);
+    submit_admin_command(&dir, AdminCommand::EnableToken(token)).unwrap();
+    submit_admin_command(&dir, AdminCommand::EnableToken(other)).unwrap();
+    let controls = read_admin(&dir).unwrap().relayer_controls(&configured);
+    assert!(controls.disabled_tokens.is_empty());
+    submit_admin_command(&dir, AdminCommand::DisableToken(token)).unwrap();
+    let controls = read_admin(&dir).unwrap().relayer_controls(&configured);
+    assert_eq!(controls.disabled_tokens, vec![token]);
+
+    assert_eq!(
+        take_oracle_requests(&dir).unwrap(),
+        (Some(100u64.into()), vec![3])
+    );
+    // applied once only, the relayer controls stay
+    assert_eq!(take_oracle_requests(&dir).unwrap(), (None, Vec::new()));
+    assert!(read_admin(&dir).unwrap().relaying_paused);
+
+    submit_admin_command(&dir, AdminCommand::ResumeRelaying).unwrap();
+    assert!(!read_admin(&dir).unwrap().relaying_paused);
+
+    let trace = AdminCommand::SetLogLevel(Subsystem::Relayer, Some(LevelFilter::Trace));
+    submit_admin_command(&dir, trace).unwrap();
+    let levels = read_admin(&dir).unwrap().log_levels();
+    assert_eq!(levels.get(&Subsystem::Relayer), Some(&LevelFilter::Trace));
+    submit_admin_command(&dir, AdminCommand::SetLogLevel(Subsystem::Relayer, None)).unwrap();
+    assert!(read_admin(&dir).unwrap().log_levels().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
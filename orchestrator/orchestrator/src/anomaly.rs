@@ -0,0 +1,254 @@
+//! A lightweight anomaly detector over the deposits the oracle observes. Deposits are grouped per token into
+//! fixed time buckets, the current bucket's event count, average transfer size and number of unique senders are
+//! compared against the rolling mean and standard deviation of the previous buckets and a spike raises an alert.
+//! Optionally an alert also switches the claim delay on for every deposit, see `ClaimDelay::escalate`.
+
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::types::{SendToCosmosEvent, SendToMinterEvent};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Length of one bucket in seconds
+pub const BUCKET_SECS: u64 = 10 * 60;
+/// How many past buckets make up the baseline
+const HISTORY_BUCKETS: usize = 36;
+/// No alerts until we have this many buckets of history
+const MIN_HISTORY_BUCKETS: usize = 6;
+/// How many standard deviations above the mean count as a spike
+const SIGMA: f64 = 4.0;
+/// A spike also has to be this many times the mean, so a flat history doesn't alert on tiny changes
+const MIN_RATIO: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Metric {
+    EventRate,
+    AverageSize,
+    UniqueSenders,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub erc20: EthAddress,
+    pub metric: Metric,
+    pub value: f64,
+    pub mean: f64,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} of {} is {:.2}, rolling mean is {:.2}",
+            self.metric,
+            explorer::address(Chain::Ethereum, self.erc20),
+            self.value,
+            self.mean
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct BucketStats {
+    events: u64,
+    total_amount: f64,
+    senders: HashSet<EthAddress>,
+}
+
+impl BucketStats {
+    fn metric(&self, metric: Metric) -> Option<f64> {
+        match metric {
+            Metric::EventRate => Some(self.events as f64),
+            Metric::AverageSize if self.events > 0 => Some(self.total_amount / self.events as f64),
+            Metric::AverageSize => None,
+            Metric::UniqueSenders => Some(self.senders.len() as f64),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AnomalyDetector {
+    enabled: bool,
+    /// switch every deposit to delayed signing when an anomaly is found
+    pub escalate: bool,
+    bucket_start: u64,
+    current: HashMap<EthAddress, BucketStats>,
+    history: VecDeque<HashMap<EthAddress, BucketStats>>,
+    /// anomalies already reported for the current bucket
+    alerted: HashSet<(EthAddress, Metric)>,
+    /// deposits are seen again while they are held back, only count each event nonce once
+    last_observed_nonce: Option<Uint256>,
+}
+
+impl AnomalyDetector {
+    pub fn new(enabled: bool, escalate: bool) -> Self {
+        AnomalyDetector {
+            enabled,
+            escalate,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Adds newly observed deposits to the current bucket and returns any metric that spiked because of them
+    pub fn observe(
+        &mut self,
+        now: u64,
+        deposits: &[SendToCosmosEvent],
+        transfers: &[SendToMinterEvent],
+    ) -> Vec<Anomaly> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        self.roll(now);
+
+        let events = deposits
+            .iter()
+            .map(|e| (e.erc20, e.sender, &e.amount, &e.event_nonce))
+            .chain(
+                transfers
+                    .iter()
+                    .map(|e| (e.erc20, e.sender, &e.amount, &e.event_nonce)),
+            );
+        let mut touched = HashSet::new();
+        let mut highest = self.last_observed_nonce.clone();
+        for (erc20, sender, amount, event_nonce) in events {
+            if let Some(last) = self.last_observed_nonce.as_ref() {
+                if event_nonce <= last {
+                    continue;
+                }
+            }
+            if highest.as_ref().map(|h| event_nonce > h).unwrap_or(true) {
+                highest = Some(event_nonce.clone());
+            }
+            let stats = self.current.entry(erc20).or_default();
+            stats.events += 1;
+            stats.total_amount += to_f64(amount);
+            stats.senders.insert(sender);
+            touched.insert(erc20);
+        }
+        self.last_observed_nonce = highest;
+
+        let mut anomalies = Vec::new();
+        for erc20 in touched {
+            for metric in [
+                Metric::EventRate,
+                Metric::AverageSize,
+                Metric::UniqueSenders,
+            ]
+            .iter()
+            {
+                if self.alerted.contains(&(erc20, *metric)) {
+                    continue;
+                }
+                if let Some(anomaly) = self.check(erc20, *metric) {
+                    self.alerted.insert((erc20, *metric));
+                    anomalies.push(anomaly);
+                }
+            }
+        }
+        anomalies
+    }
+
+    /// Moves the current bucket into the history once its time is up, buckets without any deposits count as zero
+    fn roll(&mut self, now: u64) {
+        if self.bucket_start == 0 {
+            self.bucket_start = now;
+            return;
+        }
+        while now >= self.bucket_start + BUCKET_SECS {
+            let finished = std::mem::take(&mut self.current);
+            self.history.push_back(finished);
+            if self.history.len() > HISTORY_BUCKETS {
+                self.history.pop_front();
+            }
+            self.alerted.clear();
+            self.bucket_start += BUCKET_SECS;
+        }
+    }
+
+    fn check(&self, erc20: EthAddress, metric: Metric) -> Option<Anomaly> {
+        if self.history.len() < MIN_HISTORY_BUCKETS {
+            return None;
+        }
+        let value = self.current.get(&erc20)?.metric(metric)?;
+        let samples: Vec<f64> = self
+            .history
+            .iter()
+            .filter_map(|bucket| match bucket.get(&erc20) {
+                Some(stats) => stats.metric(metric),
+                None if metric == Metric::AverageSize => None,
+                None => Some(0.0),
+            })
+            .collect();
+        if samples.is_empty() {
+            return None;
+        }
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let variance =
+            samples.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / samples.len() as f64;
+        let threshold = (mean + SIGMA * variance.sqrt()).max(mean * MIN_RATIO);
+        if value > threshold && value >= 1.0 {
+            Some(Anomaly {
+                erc20,
+                metric,
+                value,
+                mean,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+fn to_f64(amount: &Uint256) -> f64 {
+    amount.to_string().parse().unwrap_or(f64::MAX)
+}
+
+#[test]
+fn test_anomaly_detector() {
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let transfer = |nonce: u64, amount: u64| SendToMinterEvent {
+        erc20: token,
+        sender: token,
+        destination: "Mx00".to_string(),
+        amount: amount.into(),
+        event_nonce: nonce.into(),
+        tx_hash: String::new(),
+        invalid_destination: false,
+    };
+
+    let mut detector = AnomalyDetector::new(true, false);
+    let mut nonce = 1;
+    let mut now = 1000;
+    // a steady baseline of one small transfer per bucket
+    for _ in 0..MIN_HISTORY_BUCKETS + 1 {
+        assert!(detector
+            .observe(now, &[], &[transfer(nonce, 100)])
+            .is_empty());
+        nonce += 1;
+        now += BUCKET_SECS;
+    }
+
+    // seeing the same event again doesn't count it twice
+    assert!(detector
+        .observe(now, &[], &[transfer(nonce - 1, 100)])
+        .is_empty());
+
+    let anomalies = detector.observe(now, &[], &[transfer(nonce, 1_000_000)]);
+    assert_eq!(anomalies.len(), 1);
+    assert_eq!(anomalies[0].metric, Metric::AverageSize);
+    // only reported once per bucket
+    assert!(detector
+        .observe(now, &[], &[transfer(nonce + 1, 1_000_000)])
+        .is_empty());
+
+    assert!(AnomalyDetector::new(false, false)
+        .observe(now, &[], &[transfer(1, 1_000_000)])
+        .is_empty());
+}
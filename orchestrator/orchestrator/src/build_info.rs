@@ -0,0 +1,63 @@
+//! What exactly this orchestrator binary is, for debugging validators that disagree with each other. The build
+//! info is logged and journaled at startup, shown in the tui and put in the memo of every tx we send to the
+//! Hub, so which orchestrator produced a claim can be read from the chain itself and a Hub module that
+//! supports it can record it alongside the claim.
+
+use ethereum_peggy::message_signatures::ENCODING_SCHEMA_VERSION;
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct BuildInfo {
+    pub version: String,
+    /// the commit the binary was built from, unknown when built outside of git without PEGGY_GIT_COMMIT
+    pub git_commit: String,
+    /// debug or release
+    pub profile: String,
+    /// the version of the valset and batch signature encoding we sign with
+    pub encoding_schema: u32,
+}
+
+impl BuildInfo {
+    pub fn current() -> BuildInfo {
+        BuildInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_commit: env!("PEGGY_GIT_COMMIT").to_string(),
+            profile: env!("PEGGY_BUILD_PROFILE").to_string(),
+            encoding_schema: ENCODING_SCHEMA_VERSION,
+        }
+    }
+
+    /// The tx memo identifying this orchestrator, kept short since memos are size limited
+    pub fn tx_memo(&self) -> String {
+        format!(
+            "orchestrator/{}+{} schema/{}",
+            self.version, self.git_commit, self.encoding_schema
+        )
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} commit {} ({}, encoding schema {})",
+            self.version, self.git_commit, self.profile, self.encoding_schema
+        )
+    }
+}
+
+#[test]
+fn test_build_info() {
+    let info = BuildInfo {
+        version: "0.1.0".to_string(),
+        git_commit: "eb58f54a1b2c".to_string(),
+        profile: "release".to_string(),
+        encoding_schema: 1,
+    };
+    assert_eq!(info.tx_memo(), "orchestrator/0.1.0+eb58f54a1b2c schema/1");
+    assert_eq!(
+        info.to_string(),
+        "0.1.0 commit eb58f54a1b2c (release, encoding schema 1)"
+    );
+    assert_eq!(BuildInfo::current().version, env!("CARGO_PKG_VERSION"));
+}
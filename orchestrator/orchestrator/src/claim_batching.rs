@@ -0,0 +1,148 @@
+//! Optional batching of claims. Without it every loop that finds new events claims them right away, which in
+//! busy periods means a Hub transaction, and its fee, every few blocks. With a batch window events are gathered
+//! instead and claimed together once enough of them are pending or the oldest has waited out the window, so the
+//! window bounds how much later we attest. Held events aren't stored, the oracle doesn't advance past them and
+//! finds them again on the next scan.
+//!
+//! A gap in the event nonces means we are out of step with the Hub, which only accepts claims in nonce order.
+//! Waiting can't fix that, so the events are claimed right away and a rejection resyncs us.
+
+/// The most claims gathered into one transaction when only a window is configured
+pub const DEFAULT_MAX_CLAIMS: usize = 50;
+
+#[derive(Debug, Clone, Default)]
+pub struct ClaimBatching {
+    max_wait_secs: u64,
+    max_claims: usize,
+    /// unix time in seconds since which the currently pending events are held
+    holding_since: Option<u64>,
+}
+
+impl ClaimBatching {
+    /// Takes the window in seconds and the number of claims that is claimed without waiting out the window.
+    /// Without a window claims are sent as soon as they are found
+    pub fn new(window_secs: Option<&str>, max_claims: Option<&str>) -> Result<Self, String> {
+        let window_secs = match window_secs {
+            Some(window_secs) => window_secs,
+            None if max_claims.is_some() => {
+                return Err("--claim-batch-size requires --claim-batch-window".to_string())
+            }
+            None => return Ok(ClaimBatching::default()),
+        };
+        let max_wait_secs: u64 = window_secs
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid claim batch window {}: {:?}", window_secs, e))?;
+        let max_claims = match max_claims {
+            Some(max_claims) => max_claims
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid claim batch size {}: {:?}", max_claims, e))?,
+            None => DEFAULT_MAX_CLAIMS,
+        };
+        if max_claims == 0 {
+            return Err("Claim batch size must be at least 1".to_string());
+        }
+        Ok(ClaimBatching {
+            max_wait_secs,
+            max_claims,
+            holding_since: None,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_wait_secs > 0
+    }
+
+    /// Whether to claim the pending events now. `event_nonces` are the events we could claim after
+    /// `last_event_nonce`, our last claimed one. While `catching_up` later events are outside the scanned
+    /// range, holding would only stall the scan so everything is claimed right away
+    pub fn should_claim(
+        &mut self,
+        last_event_nonce: u64,
+        event_nonces: &[u64],
+        catching_up: bool,
+        now: u64,
+    ) -> bool {
+        if event_nonces.is_empty() {
+            self.holding_since = None;
+            return true;
+        }
+        if !self.is_enabled() || catching_up {
+            return true;
+        }
+        if let Some(gap) = first_gap(last_event_nonce, event_nonces) {
+            warn!("Event nonce gap, expected {}, claiming right away", gap);
+            return true;
+        }
+        if event_nonces.len() >= self.max_claims {
+            return true;
+        }
+        let since = *self.holding_since.get_or_insert(now);
+        now >= since + self.max_wait_secs
+    }
+
+    /// Starts a new window once the pending events are claimed
+    pub fn claimed(&mut self) {
+        self.holding_since = None;
+    }
+
+    /// Describes the held events for the logs
+    pub fn describe_held(&self, event_nonces: &[u64], now: u64) -> String {
+        let deadline = self
+            .holding_since
+            .map_or(now, |since| since + self.max_wait_secs);
+        format!(
+            "Holding {} claims, claiming them within {} seconds or once {} are pending",
+            event_nonces.len(),
+            deadline.saturating_sub(now),
+            self.max_claims
+        )
+    }
+}
+
+/// The first missing event nonce if `event_nonces` don't directly follow `last_event_nonce`
+fn first_gap(last_event_nonce: u64, event_nonces: &[u64]) -> Option<u64> {
+    let mut sorted = event_nonces.to_vec();
+    sorted.sort_unstable();
+    let mut expected = last_event_nonce + 1;
+    for nonce in sorted {
+        if nonce != expected {
+            return Some(expected);
+        }
+        expected += 1;
+    }
+    None
+}
+
+#[test]
+fn test_claim_batching() {
+    let mut disabled = ClaimBatching::new(None, None).unwrap();
+    assert!(disabled.should_claim(0, &[1], false, 0));
+    assert!(ClaimBatching::new(None, Some("10")).is_err());
+    assert!(ClaimBatching::new(Some("30"), Some("0")).is_err());
+    assert!(ClaimBatching::new(Some("soon"), None).is_err());
+
+    let mut batching = ClaimBatching::new(Some("30"), Some("3")).unwrap();
+    // held until the window is over
+    assert!(!batching.should_claim(0, &[1], false, 100));
+    assert!(!batching.should_claim(0, &[1, 2], false, 120));
+    assert!(batching.should_claim(0, &[1, 2], false, 130));
+    batching.claimed();
+
+    // or enough claims are pending
+    assert!(!batching.should_claim(2, &[3], false, 200));
+    assert!(batching.should_claim(2, &[3, 4, 5], false, 201));
+    batching.claimed();
+
+    // a nonce gap or catching up claims right away
+    assert!(batching.should_claim(5, &[7], false, 300));
+    assert!(batching.should_claim(5, &[6], true, 300));
+    assert_eq!(first_gap(5, &[8, 6, 7]), None);
+    assert_eq!(first_gap(5, &[6, 8]), Some(7));
+
+    // no events ends the window
+    assert!(!batching.should_claim(5, &[6], false, 400));
+    assert!(batching.should_claim(6, &[], false, 500));
+    assert!(!batching.should_claim(6, &[7], false, 500));
+}
@@ -0,0 +1,227 @@
+//! Optional timelock on large deposits. Claims for deposits above a per token threshold are only signed once
+//! they have been pending for the configured delay, the pending claims are published in the state store so
+//! the tui shows them and operators have a window to investigate and halt the bridge before we attest.
+//! Claims must be made in event nonce order, so everything after a delayed deposit waits with it.
+
+use crate::supply_caps::parse_token_amounts;
+use clarity::{Address as EthAddress, Uint256};
+use ethereum_peggy::utils::downcast_nonce;
+use peggy_utils::types::{SendToCosmosEvent, SendToMinterEvent};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct DelayedClaim {
+    pub event_nonce: u64,
+    pub erc20: EthAddress,
+    pub amount: Uint256,
+    pub destination: String,
+    /// unix time in seconds after which we sign the claim
+    pub release_at: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClaimDelay {
+    thresholds: HashMap<EthAddress, Uint256>,
+    delay_secs: u64,
+    pending: BTreeMap<u64, DelayedClaim>,
+    /// unix time until which every deposit is delayed regardless of its size
+    escalated_until: u64,
+}
+
+impl ClaimDelay {
+    /// Takes comma separated `<erc20>:<amount>` thresholds and the delay in minutes. The delay can be set without
+    /// thresholds, then only an escalation delays deposits
+    pub fn new(thresholds: Option<&str>, delay_minutes: Option<&str>) -> Result<Self, String> {
+        let delay_minutes = match delay_minutes {
+            Some(delay_minutes) => delay_minutes,
+            None if thresholds.is_some() => {
+                return Err("--delay-claims-above requires --claim-delay".to_string())
+            }
+            None => return Ok(ClaimDelay::default()),
+        };
+        let delay_minutes: u64 = delay_minutes
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid claim delay {}: {:?}", delay_minutes, e))?;
+        Ok(ClaimDelay {
+            thresholds: match thresholds {
+                Some(thresholds) => parse_token_amounts(thresholds)?,
+                None => HashMap::new(),
+            },
+            delay_secs: delay_minutes * 60,
+            ..Default::default()
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.delay_secs > 0
+    }
+
+    /// Delays every deposit seen during the next delay period, not just the large ones
+    pub fn escalate(&mut self, now: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        if self.escalated_until <= now {
+            warn!(
+                "Delaying claims for all deposits for the next {} minutes",
+                self.delay_secs / 60
+            );
+        }
+        self.escalated_until = now + self.delay_secs;
+    }
+
+    /// Picks up claims that were already waiting before a restart so their delay isn't started over
+    pub fn restore(&mut self, claims: &[DelayedClaim]) {
+        for claim in claims {
+            self.pending.insert(claim.event_nonce, claim.clone());
+        }
+    }
+
+    /// Returns the event nonce of the first deposit above its token's threshold that is still inside its delay
+    /// window, deposits seen for the first time start their delay now
+    pub fn first_delayed(
+        &mut self,
+        deposits: &[SendToCosmosEvent],
+        transfers: &[SendToMinterEvent],
+        now: u64,
+    ) -> Option<Uint256> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let events = deposits
+            .iter()
+            .map(|e| {
+                (
+                    e.erc20,
+                    &e.amount,
+                    &e.event_nonce,
+                    e.destination.to_string(),
+                )
+            })
+            .chain(
+                transfers
+                    .iter()
+                    .map(|e| (e.erc20, &e.amount, &e.event_nonce, e.destination.clone())),
+            );
+
+        let mut first: Option<Uint256> = None;
+        for (erc20, amount, event_nonce, destination) in events {
+            let escalated = now < self.escalated_until;
+            match self.thresholds.get(&erc20) {
+                Some(threshold) if amount > threshold => {}
+                _ if escalated => {}
+                _ => continue,
+            }
+            let nonce = match downcast_nonce(event_nonce.clone()) {
+                Some(nonce) => nonce,
+                None => continue,
+            };
+            let delay_secs = self.delay_secs;
+            let claim = self.pending.entry(nonce).or_insert_with(|| {
+                warn!(
+                    "Deposit of {} {} to {} with event nonce {} is being delayed, signing its claim in {} minutes",
+                    amount,
+                    erc20,
+                    destination,
+                    nonce,
+                    delay_secs / 60
+                );
+                DelayedClaim {
+                    event_nonce: nonce,
+                    erc20,
+                    amount: amount.clone(),
+                    destination,
+                    release_at: now + delay_secs,
+                }
+            });
+            if now < claim.release_at && first.as_ref().map(|f| event_nonce < f).unwrap_or(true) {
+                first = Some(event_nonce.clone());
+            }
+        }
+        first
+    }
+
+    /// Ends the delay of a pending claim early, returns false if no claim with this event nonce is delayed
+    pub fn release(&mut self, event_nonce: u64) -> bool {
+        match self.pending.get_mut(&event_nonce) {
+            Some(claim) => {
+                claim.release_at = 0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forgets delayed claims the Hub has accepted from us
+    pub fn claimed(&mut self, last_event_nonce: u64) {
+        self.pending = self.pending.split_off(&(last_event_nonce + 1));
+    }
+
+    pub fn pending(&self) -> Vec<DelayedClaim> {
+        self.pending.values().cloned().collect()
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_claim_delay() {
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    assert!(!ClaimDelay::new(None, None).unwrap().is_enabled());
+    assert!(ClaimDelay::new(Some("0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:100"), None).is_err());
+    assert!(ClaimDelay::new(None, Some("10")).unwrap().is_enabled());
+
+    let mut delay = ClaimDelay::new(
+        Some("0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:100"),
+        Some("10"),
+    )
+    .unwrap();
+    let transfer = |nonce: u64, amount: u64| SendToMinterEvent {
+        erc20: token,
+        sender: token,
+        destination: "Mx00".to_string(),
+        amount: amount.into(),
+        event_nonce: nonce.into(),
+        tx_hash: String::new(),
+        invalid_destination: false,
+    };
+    let transfers = vec![transfer(1, 50), transfer(2, 500), transfer(3, 50)];
+
+    assert_eq!(
+        delay.first_delayed(&[], &transfers, 1000),
+        Some(2u64.into())
+    );
+    assert_eq!(delay.pending().len(), 1);
+    assert_eq!(delay.pending()[0].release_at, 1600);
+    // the delay starts when the deposit is first seen, not on every check
+    assert_eq!(
+        delay.first_delayed(&[], &transfers, 1599),
+        Some(2u64.into())
+    );
+    assert_eq!(delay.first_delayed(&[], &transfers, 1600), None);
+    assert!(delay.release(2));
+    assert_eq!(delay.first_delayed(&[], &transfers, 1000), None);
+    assert!(!delay.release(5));
+
+    delay.claimed(3);
+    assert!(delay.pending().is_empty());
+
+    // while escalated small deposits are delayed too
+    delay.escalate(2000);
+    let transfers = vec![transfer(4, 50)];
+    assert_eq!(
+        delay.first_delayed(&[], &transfers, 2000),
+        Some(4u64.into())
+    );
+    assert_eq!(delay.first_delayed(&[], &transfers, 2600), None);
+}
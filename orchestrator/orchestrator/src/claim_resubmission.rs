@@ -0,0 +1,211 @@
+//! Optional TTL on submitted claims. Without it a claim tx that never makes it into a block, for example because
+//! it was dropped from the mempool, is sent again on the very next loop while the first may still be pending,
+//! and this goes on for as long as it keeps failing. With a TTL the claims are given that long to show up on the
+//! Hub before they are rebuilt with our current sequence and the configured fee and broadcast again, up to the
+//! retry budget. Once the budget is spent the claims expire and the oracle stops sending them for
+//! `EXPIRED_BACKOFF_TTLS` TTLs, then they get a fresh budget, so a Hub that was down for a while doesn't stop
+//! the oracle for good.
+//!
+//! A submission is observed once the Hub's last event nonce for us covers its first event, the pending
+//! submission is published in the state store so the tui and the admin route of the status API show it.
+
+/// How many times claims are rebroadcast when only a TTL is configured
+pub const DEFAULT_CLAIM_RETRIES: u32 = 3;
+/// How many TTLs after their last broadcast expired claims wait before they get a fresh budget
+pub const EXPIRED_BACKOFF_TTLS: u64 = 10;
+
+/// Claims we sent that the Hub hasn't accepted yet
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ClaimSubmission {
+    pub event_nonces: Vec<u64>,
+    /// the hash of the last broadcast, None if broadcasting failed before the node returned one
+    pub txhash: Option<String>,
+    /// unix time in seconds of the first and the last broadcast
+    pub first_submitted_at: u64,
+    pub submitted_at: u64,
+    /// how many times the claims were broadcast, the first one included
+    pub attempts: u32,
+    /// the retry budget is spent and the claims aren't sent until the backoff is over
+    #[serde(default)]
+    pub expired: bool,
+}
+
+/// What to do with the claims the oracle is about to send
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ClaimAction {
+    /// nothing is pending for them, send them
+    Submit,
+    /// they were sent and are still within their TTL, the seconds left
+    Wait(u64),
+    /// their TTL is over, rebroadcast them as the given attempt
+    Resubmit(u32),
+    /// the retry budget is spent, don't send them for the given seconds
+    Expired(u64),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClaimResubmission {
+    ttl_secs: u64,
+    max_retries: u32,
+    pending: Option<ClaimSubmission>,
+}
+
+impl ClaimResubmission {
+    /// Takes the TTL in seconds and how many times claims are rebroadcast after the first broadcast. Without a
+    /// TTL claims are sent again on every loop until they are accepted
+    pub fn new(ttl_secs: Option<&str>, retries: Option<&str>) -> Result<Self, String> {
+        let ttl_secs = match ttl_secs {
+            Some(ttl_secs) => ttl_secs,
+            None if retries.is_some() => {
+                return Err("--claim-retries requires --claim-ttl".to_string())
+            }
+            None => return Ok(ClaimResubmission::default()),
+        };
+        let ttl_secs: u64 = ttl_secs
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid claim TTL {}: {:?}", ttl_secs, e))?;
+        if ttl_secs == 0 {
+            return Err("Claim TTL must be at least 1 second".to_string());
+        }
+        let max_retries = match retries {
+            Some(retries) => retries
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid claim retries {}: {:?}", retries, e))?,
+            None => DEFAULT_CLAIM_RETRIES,
+        };
+        Ok(ClaimResubmission {
+            ttl_secs,
+            max_retries,
+            pending: None,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.ttl_secs > 0
+    }
+
+    /// Decides about the claims for `event_nonces`, the events after our last accepted one. A pending
+    /// submission whose first event isn't among them anymore was accepted and is forgotten
+    pub fn check(&mut self, event_nonces: &[u64], now: u64) -> ClaimAction {
+        let pending = match self.pending.as_mut() {
+            Some(pending) if self.ttl_secs > 0 => pending,
+            _ => return ClaimAction::Submit,
+        };
+        let first = pending.event_nonces.iter().min();
+        if first.map_or(true, |first| !event_nonces.contains(first)) {
+            self.pending = None;
+            return ClaimAction::Submit;
+        }
+        let retry_at = pending.submitted_at + self.ttl_secs * EXPIRED_BACKOFF_TTLS;
+        if pending.expired && now >= retry_at {
+            // the backoff is over, the claims start over with a fresh budget
+            pending.expired = false;
+            pending.attempts = 0;
+            return ClaimAction::Resubmit(1);
+        }
+        if pending.expired {
+            return ClaimAction::Expired(retry_at - now);
+        }
+        let deadline = pending.submitted_at + self.ttl_secs;
+        if now < deadline {
+            return ClaimAction::Wait(deadline - now);
+        }
+        if pending.attempts > self.max_retries {
+            pending.expired = true;
+            return ClaimAction::Expired(retry_at.saturating_sub(now));
+        }
+        ClaimAction::Resubmit(pending.attempts + 1)
+    }
+
+    /// Records a broadcast of the claims for `event_nonces` that the Hub hasn't accepted yet
+    pub fn submitted(&mut self, event_nonces: &[u64], txhash: Option<String>, now: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        match self.pending.as_mut() {
+            Some(pending) if pending.event_nonces.iter().min() == event_nonces.iter().min() => {
+                pending.event_nonces = event_nonces.to_vec();
+                pending.txhash = txhash;
+                pending.submitted_at = now;
+                pending.attempts += 1;
+            }
+            _ => {
+                self.pending = Some(ClaimSubmission {
+                    event_nonces: event_nonces.to_vec(),
+                    txhash,
+                    first_submitted_at: now,
+                    submitted_at: now,
+                    attempts: 1,
+                    expired: false,
+                })
+            }
+        }
+    }
+
+    /// Forgets the pending submission once the Hub accepted or rejected it
+    pub fn settled(&mut self) {
+        self.pending = None;
+    }
+
+    /// The submission waiting to be seen on the Hub, published in the state store
+    pub fn pending(&self) -> Option<ClaimSubmission> {
+        self.pending.clone()
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_retries + 1
+    }
+}
+
+#[test]
+fn test_claim_resubmission() {
+    let mut disabled = ClaimResubmission::new(None, None).unwrap();
+    disabled.submitted(&[1], None, 0);
+    assert_eq!(disabled.check(&[1], 0), ClaimAction::Submit);
+    assert!(ClaimResubmission::new(None, Some("2")).is_err());
+    assert!(ClaimResubmission::new(Some("0"), None).is_err());
+    assert!(ClaimResubmission::new(Some("soon"), None).is_err());
+
+    let mut resubmission = ClaimResubmission::new(Some("60"), Some("1")).unwrap();
+    assert_eq!(resubmission.check(&[1, 2], 100), ClaimAction::Submit);
+    resubmission.submitted(&[1, 2], Some("AB".to_string()), 100);
+    // the broadcast gets its TTL before it is sent again
+    assert_eq!(resubmission.check(&[1, 2], 130), ClaimAction::Wait(30));
+    assert_eq!(
+        resubmission.check(&[1, 2, 3], 160),
+        ClaimAction::Resubmit(2)
+    );
+    resubmission.submitted(&[1, 2, 3], None, 160);
+    let pending = resubmission.pending().unwrap();
+    assert_eq!(pending.attempts, 2);
+    assert_eq!(pending.first_submitted_at, 100);
+    assert_eq!(pending.txhash, None);
+    // until the budget is spent
+    assert_eq!(
+        resubmission.check(&[1, 2, 3], 220),
+        ClaimAction::Expired(540)
+    );
+    assert!(resubmission.pending().unwrap().expired);
+    assert_eq!(
+        resubmission.check(&[1, 2, 3], 700),
+        ClaimAction::Expired(60)
+    );
+    // and the backoff is over, then they start over with a fresh budget
+    assert_eq!(
+        resubmission.check(&[1, 2, 3], 760),
+        ClaimAction::Resubmit(1)
+    );
+    assert!(!resubmission.pending().unwrap().expired);
+    resubmission.submitted(&[1, 2, 3], None, 760);
+    assert_eq!(resubmission.pending().unwrap().attempts, 1);
+    assert_eq!(resubmission.check(&[1, 2, 3], 800), ClaimAction::Wait(20));
+
+    // accepted claims are forgotten
+    assert_eq!(resubmission.check(&[4], 1000), ClaimAction::Submit);
+    assert_eq!(resubmission.pending(), None);
+    resubmission.submitted(&[4], None, 1000);
+    resubmission.settled();
+    assert_eq!(resubmission.check(&[4], 2000), ClaimAction::Submit);
+}
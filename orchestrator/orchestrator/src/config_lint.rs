@@ -0,0 +1,589 @@
+//! `orchestrator config lint` checks a chain config file for `orchestrator multi` without starting anything or
+//! connecting anywhere. The orchestrator itself panics on the first bad setting, often only after connecting to
+//! every node, the linter instead reports every problem at once with the chain and field it is in.
+//!
+//! Besides parsing every setting the way the orchestrator does, it catches what parses but is still wrong:
+//! placeholders such as `CHANGEME` or `0x...` copied from the docs, unknown profiles, urls that aren't http(s), a
+//! verification node that is the main node, Hub endpoints, contracts or PeggyIDs shared between chains, and config
+//! and key files other users can read. Settings left to a profile are linted with the profile's values.
+
+use crate::claim_batching::ClaimBatching;
+use crate::claim_delay::ClaimDelay;
+use crate::claim_resubmission::ClaimResubmission;
+use crate::deployment_block::DeploymentBlock;
+use crate::deposit_finality::DepositFinality;
+use crate::enrichment::AddressLabels;
+use crate::fee_payers::FeePayers;
+use crate::fee_top_up::FeeTopUp;
+use crate::key_rotation::{ACTIVE_KEYS_FILE, PENDING_KEYS_FILE};
+use crate::multi_chain::{check_chain_configs, ChainConfig};
+use crate::profiles::Profile;
+use crate::runtime::TaskQueues;
+use crate::state_encryption::StateKey;
+use crate::supply_caps::SupplyCaps;
+use clarity::Address as EthAddress;
+use clarity::PrivateKey as EthPrivateKey;
+use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+use peggy_utils::secrets::SecretSource;
+use peggy_utils::timings::Timings;
+use peggy_utils::types::SignatureSelection;
+use relayer::batch_relaying::{parse_skipped_batches, parse_tokens};
+use relayer::sponsorship::Sponsors;
+use relayer::valset_relaying::parse_overlap_percent;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use url::Url;
+
+/// Values from the docs and templates that must be replaced before use
+const PLACEHOLDERS: [&str; 4] = ["changeme", "...", "<", "todo"];
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct LintIssue {
+    /// the chain name, empty for problems with the file itself
+    pub chain: String,
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.chain.is_empty() {
+            write!(f, "{}: {}", self.field, self.message)
+        } else {
+            write!(f, "{}.{}: {}", self.chain, self.field, self.message)
+        }
+    }
+}
+
+fn is_placeholder(value: &str) -> bool {
+    let value = value.trim().to_lowercase();
+    value.is_empty() || PLACEHOLDERS.iter().any(|p| value.contains(p))
+}
+
+/// Whether other users can read the file, it holds keys or phrases
+fn readable_by_others(path: &Path) -> bool {
+    fs::metadata(path)
+        .map(|metadata| metadata.permissions().mode() & 0o077 != 0)
+        .unwrap_or(false)
+}
+
+/// Strips what doesn't change which endpoint a url points at
+fn normalize_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_lowercase()
+}
+
+struct ChainLinter<'a> {
+    config: &'a ChainConfig,
+    issues: Vec<LintIssue>,
+}
+
+impl<'a> ChainLinter<'a> {
+    fn issue(&mut self, field: &str, message: String) {
+        self.issues.push(LintIssue {
+            chain: self.config.name.clone(),
+            field: field.to_string(),
+            message,
+        });
+    }
+
+    /// Checks a value for placeholders, returns the value if it is worth checking further
+    fn value(&mut self, field: &str, value: Option<&'a str>) -> Option<&'a str> {
+        let value = value?;
+        if is_placeholder(value) {
+            self.issue(field, "is empty or a placeholder".to_string());
+            return None;
+        }
+        Some(value)
+    }
+
+    /// Checks a key or phrase, returns it if it is given as it is. References to a secret backend are only
+    /// checked for syntax, the linter doesn't fetch secrets
+    fn secret(&mut self, field: &str, value: Option<&'a str>) -> Option<&'a str> {
+        let value = self.value(field, value)?;
+        match value.parse::<SecretSource>() {
+            Ok(source) if source.is_plain() => Some(value),
+            Ok(_) => None,
+            Err(e) => {
+                self.issue(field, e);
+                None
+            }
+        }
+    }
+
+    fn url(&mut self, field: &str, value: Option<&'a str>) {
+        if let Some(value) = self.value(field, value) {
+            match Url::parse(value) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                Ok(url) => self.issue(
+                    field,
+                    format!(
+                        "{} has scheme {}, expected http or https",
+                        value,
+                        url.scheme()
+                    ),
+                ),
+                Err(e) => self.issue(field, format!("{} is not a valid url: {}", value, e)),
+            }
+        }
+    }
+
+    /// Checks a comma separated list of urls
+    fn urls(&mut self, field: &str, value: Option<&'a str>) {
+        if let Some(value) = self.value(field, value) {
+            for url in value
+                .split(',')
+                .map(str::trim)
+                .filter(|url| !url.is_empty())
+            {
+                self.url(field, Some(url));
+            }
+        }
+    }
+
+    fn minutes(&mut self, field: &str, value: Option<&'a str>) {
+        if let Some(value) = self.value(field, value) {
+            if value.trim().parse::<u64>().is_err() {
+                self.issue(field, format!("{} is not a number of minutes", value));
+            }
+        }
+    }
+
+    fn file(&mut self, field: &str, value: Option<&'a str>) -> Option<&'a Path> {
+        let path = Path::new(self.value(field, value)?);
+        if !path.is_file() {
+            self.issue(field, format!("{} doesn't exist", path.display()));
+            return None;
+        }
+        Some(path)
+    }
+
+    fn result<E: fmt::Display>(&mut self, field: &str, result: Result<(), E>) {
+        if let Err(e) = result {
+            self.issue(field, e.to_string());
+        }
+    }
+
+    fn lint(&mut self) {
+        let config = self.config;
+        if config.name.is_empty() {
+            self.issue("name", "every chain needs a name".to_string());
+        }
+
+        if let Some(phrase) = self.secret("cosmos_phrase", Some(config.cosmos_phrase.as_str())) {
+            if CosmosPrivateKey::from_phrase(phrase, "").is_err() {
+                self.issue("cosmos_phrase", "is not a valid Cosmos phrase".to_string());
+            }
+        }
+        if let Some(phrase) = self.secret("fee_payer_phrase", config.fee_payer_phrase.as_deref()) {
+            if CosmosPrivateKey::from_phrase(phrase, "").is_err() {
+                self.issue(
+                    "fee_payer_phrase",
+                    "is not a valid Cosmos phrase".to_string(),
+                );
+            }
+        }
+        if let Some(key) = self.secret("ethereum_key", Some(config.ethereum_key.as_str())) {
+            if key.parse::<EthPrivateKey>().is_err() {
+                self.issue(
+                    "ethereum_key",
+                    "is not a valid Ethereum private key".to_string(),
+                );
+            }
+        }
+        if let Some(address) =
+            self.value("contract_address", Some(config.contract_address.as_str()))
+        {
+            match address.parse::<EthAddress>() {
+                Ok(address) if address == EthAddress::default() => {
+                    self.issue("contract_address", "is the zero address".to_string())
+                }
+                Ok(_) => {}
+                Err(e) => self.issue(
+                    "contract_address",
+                    format!("{} is not a valid Ethereum address: {:?}", address, e),
+                ),
+            }
+        }
+        self.value("fees", Some(config.fees.as_str()));
+        self.value("peggy_id", config.peggy_id.as_deref());
+        if let Some(block) = config.deployment_block.as_deref() {
+            self.result(
+                "deployment_block",
+                block.parse::<DeploymentBlock>().map(|_| ()),
+            );
+        }
+
+        self.url("cosmos_legacy_rpc", Some(config.cosmos_legacy_rpc.as_str()));
+        self.url("cosmos_grpc", Some(config.cosmos_grpc.as_str()));
+        self.url("cosmos_backup_rpc", config.cosmos_backup_rpc.as_deref());
+        if let Some(backup) = config.cosmos_backup_rpc.as_deref() {
+            if normalize_url(backup) == normalize_url(&config.cosmos_legacy_rpc) {
+                self.issue(
+                    "cosmos_backup_rpc",
+                    "is the same node as cosmos_legacy_rpc".to_string(),
+                );
+            }
+        }
+        self.urls("ethereum_rpc", Some(config.ethereum_rpc.as_str()));
+        self.url("ethereum_verify_rpc", config.ethereum_verify_rpc.as_deref());
+        self.urls(
+            "ethereum_backfill_rpc",
+            config.ethereum_backfill_rpc.as_deref(),
+        );
+        self.url("threshold_signer", config.threshold_signer.as_deref());
+        self.url("minter_api", config.minter_api.as_deref());
+        if config.watch_solvency
+            && (config.minter_api.is_none() || config.minter_multisig.is_none())
+        {
+            self.issue(
+                "watch_solvency",
+                "requires minter_api and minter_multisig".to_string(),
+            );
+        }
+        if let Some(verify_rpc) = config.ethereum_verify_rpc.as_deref() {
+            if config
+                .ethereum_rpc
+                .split(',')
+                .any(|url| normalize_url(url) == normalize_url(verify_rpc))
+            {
+                self.issue(
+                    "ethereum_verify_rpc",
+                    "is the same node as ethereum_rpc, it must be independently operated"
+                        .to_string(),
+                );
+            }
+        }
+
+        // a phrase kept in a secret backend stands in for a valid one
+        let fee_payer = config.fee_payer_phrase.as_deref().and_then(|phrase| {
+            match phrase.parse::<SecretSource>() {
+                Ok(source) if !source.is_plain() => Some(CosmosPrivateKey::from_secret(b"lint")),
+                _ => CosmosPrivateKey::from_phrase(phrase, "").ok(),
+            }
+        });
+        if fee_payer.is_some() || config.fee_payer_phrase.is_none() {
+            let kinds = config.fee_payer_for.as_deref();
+            self.result(
+                "fee_payer_for",
+                FeePayers::new(fee_payer, kinds).map(|_| ()),
+            );
+        }
+        let supply_caps = SupplyCaps::parse(config.supply_caps.as_deref());
+        self.result("supply_caps", supply_caps.map(|_| ()));
+        let deposit_finality = DepositFinality::parse(config.deposit_finality.as_deref());
+        self.result("deposit_finality", deposit_finality.map(|_| ()));
+        match ClaimDelay::new(
+            config.delay_claims_above.as_deref(),
+            config.claim_delay.as_deref(),
+        ) {
+            Ok(delay) if config.anomaly_delay && !delay.is_enabled() => {
+                self.issue("anomaly_delay", "requires claim_delay".to_string())
+            }
+            Ok(_) => {}
+            Err(e) => self.issue("claim_delay", e),
+        }
+        let claim_batching = ClaimBatching::new(
+            config.claim_batch_window.as_deref(),
+            config.claim_batch_size.as_deref(),
+        );
+        self.result("claim_batch_window", claim_batching.map(|_| ()));
+        let claim_resubmission =
+            ClaimResubmission::new(config.claim_ttl.as_deref(), config.claim_retries.as_deref());
+        self.result("claim_ttl", claim_resubmission.map(|_| ()));
+        let task_queues = TaskQueues::parse(config.task_queues.as_deref());
+        self.result("task_queues", task_queues.map(|_| ()));
+        if let Some(batches) = config.skip_batches.as_deref() {
+            self.result("skip_batches", parse_skipped_batches(batches).map(|_| ()));
+        }
+        if let Some(tokens) = config.disable_tokens.as_deref() {
+            self.result("disable_tokens", parse_tokens(tokens).map(|_| ()));
+        }
+        if let Some(selection) = config.signatures.as_deref() {
+            self.result(
+                "signatures",
+                selection.parse::<SignatureSelection>().map(|_| ()),
+            );
+        }
+        if let Some(percent) = config.force_valset_relay_below.as_deref() {
+            self.result(
+                "force_valset_relay_below",
+                parse_overlap_percent(percent).map(|_| ()),
+            );
+        }
+        self.minutes("maintenance_window", config.maintenance_window.as_deref());
+        self.minutes(
+            "stalled_transfer_alert",
+            config.stalled_transfer_alert.as_deref(),
+        );
+        self.minutes("standby", config.standby.as_deref());
+        let stalled_transfer_alert = config
+            .stalled_transfer_alert
+            .as_deref()
+            .and_then(|minutes| minutes.trim().parse::<u64>().ok())
+            .map(|minutes| minutes * 60);
+        let fee_top_up = FeeTopUp::new(
+            config.top_up_fees.as_deref(),
+            config.top_up_percent.as_deref(),
+            stalled_transfer_alert,
+        );
+        self.result("top_up_fees", fee_top_up.map(|_| ()));
+
+        if let Some(path) = self.file("timings", config.timings.as_deref()) {
+            self.result("timings", Timings::load(path).map(|_| ()));
+        }
+        if let Some(path) = self.file("sponsors", config.sponsors.as_deref()) {
+            self.result("sponsors", Sponsors::load(path).map(|_| ()));
+        }
+        if let Some(path) = self.file("address_labels", config.address_labels.as_deref()) {
+            self.result("address_labels", AddressLabels::load(path).map(|_| ()));
+        }
+        if config.price_feed.as_deref() != Some("hub") {
+            self.file("price_feed", config.price_feed.as_deref());
+        }
+
+        if let Some(key) = self.secret("state_key", config.state_key.as_deref()) {
+            if let Err(e) = StateKey::from_secret(key) {
+                self.issue("state_key", e);
+            }
+        }
+        if config.state_key.is_some() && config.state_dir.is_none() {
+            self.issue("state_key", "requires state_dir".to_string());
+        }
+
+        match self.value("state_dir", config.state_dir.as_deref()) {
+            Some(dir) => {
+                let dir = Path::new(dir);
+                if dir.exists() && !dir.is_dir() {
+                    self.issue("state_dir", format!("{} is not a directory", dir.display()));
+                }
+                for keys_file in [ACTIVE_KEYS_FILE, PENDING_KEYS_FILE].iter() {
+                    let path = dir.join(keys_file);
+                    if readable_by_others(&path) {
+                        self.issue(
+                            "state_dir",
+                            format!(
+                                "{} holds delegate keys but other users can read it, chmod 600 it",
+                                path.display()
+                            ),
+                        );
+                    }
+                }
+            }
+            None if config.verify_restart => {
+                self.issue("verify_restart", "requires state_dir".to_string())
+            }
+            None => {}
+        }
+    }
+}
+
+/// Reports every setting of every chain that is invalid or suspicious, and settings chains must not share
+pub fn lint_chain_configs(configs: &[ChainConfig]) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    // lint what the orchestrator runs with, the settings a profile fills in included
+    let mut resolved = Vec::new();
+    for config in configs {
+        let mut config = config.clone();
+        if let Some(profile) = config.profile.as_deref() {
+            match profile.parse::<Profile>() {
+                Ok(profile) => profile.apply(&mut config),
+                Err(e) => issues.push(LintIssue {
+                    chain: config.name.clone(),
+                    field: "profile".to_string(),
+                    message: e,
+                }),
+            }
+        }
+        resolved.push(config);
+    }
+    let configs = resolved.as_slice();
+    if let Err(e) = check_chain_configs(configs) {
+        issues.push(LintIssue {
+            chain: String::new(),
+            field: "chains".to_string(),
+            message: e,
+        });
+    }
+    for config in configs {
+        let mut linter = ChainLinter {
+            config,
+            issues: Vec::new(),
+        };
+        linter.lint();
+        issues.extend(linter.issues);
+    }
+
+    // two Hub chains can share an Ethereum node but nothing that identifies the chain itself
+    let shared: [(&str, fn(&ChainConfig) -> Option<String>); 4] = [
+        ("cosmos_grpc", |c| Some(normalize_url(&c.cosmos_grpc))),
+        ("cosmos_legacy_rpc", |c| {
+            Some(normalize_url(&c.cosmos_legacy_rpc))
+        }),
+        ("contract_address", |c| {
+            Some(c.contract_address.trim().to_lowercase())
+        }),
+        ("peggy_id", |c| c.peggy_id.clone()),
+    ];
+    for (field, key) in shared.iter() {
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for config in configs {
+            let value = match key(config) {
+                Some(value) if !is_placeholder(&value) => value,
+                _ => continue,
+            };
+            match seen.get(&value) {
+                Some(other) if *other != config.name => issues.push(LintIssue {
+                    chain: config.name.clone(),
+                    field: field.to_string(),
+                    message: format!("{} is also used by chain {}", value, other),
+                }),
+                Some(_) => {}
+                None => {
+                    seen.insert(value, &config.name);
+                }
+            }
+        }
+    }
+    issues
+}
+
+/// Whether the config holds keys or phrases as they are rather than references to a secret backend
+fn has_plain_secrets(config: &ChainConfig) -> bool {
+    Some(config.cosmos_phrase.as_str())
+        .into_iter()
+        .chain(Some(config.ethereum_key.as_str()))
+        .chain(config.fee_payer_phrase.as_deref())
+        .chain(config.state_key.as_deref())
+        .any(|secret| {
+            secret
+                .parse::<SecretSource>()
+                .map_or(true, |source| source.is_plain())
+        })
+}
+
+/// Lints the chain config file at `path`, including the file's own permissions
+pub fn lint_config_file(path: &Path) -> Vec<LintIssue> {
+    let file_issue = |message: String| LintIssue {
+        chain: String::new(),
+        field: path.display().to_string(),
+        message,
+    };
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => return vec![file_issue(format!("failed to read: {}", e))],
+    };
+    let configs: Vec<ChainConfig> = match serde_json::from_str(&contents) {
+        Ok(configs) => configs,
+        Err(e) => return vec![file_issue(format!("failed to parse: {}", e))],
+    };
+    let mut issues = Vec::new();
+    if readable_by_others(path) && configs.iter().any(has_plain_secrets) {
+        issues.push(file_issue(
+            "holds phrases and keys but other users can read it, chmod 600 it".to_string(),
+        ));
+    }
+    issues.extend(lint_chain_configs(&configs));
+    issues
+}
+
+#[test]
+fn test_lint_chain_configs() {
+    let chain = |name: &str, grpc: &str| ChainConfig {
+        name: name.to_string(),
+        cosmos_phrase: "CHANGEME".to_string(),
+        ethereum_key: "0x...".to_string(),
+        cosmos_legacy_rpc: format!("http://{}:1317", name),
+        cosmos_grpc: grpc.to_string(),
+        ethereum_rpc: "http://localhost:8545".to_string(),
+        ethereum_verify_rpc: Some("http://localhost:8545/".to_string()),
+        ethereum_backfill_rpc: Some("http://archive:8545,ftp://archive".to_string()),
+        contract_address: "0x0000000000000000000000000000000000000000".to_string(),
+        fees: "hub".to_string(),
+        peggy_id: Some("minter-hub-1".to_string()),
+        claim_delay: Some("ten".to_string()),
+        standby: Some("5".to_string()),
+        state_dir: Some(format!("/tmp/{}", name)),
+        state_key: Some("correct horse battery staple".to_string()),
+        ..Default::default()
+    };
+    let testnet = ChainConfig {
+        threshold_signer: Some("ws://localhost:8000".to_string()),
+        force_valset_relay_below: Some("120".to_string()),
+        claim_batch_size: Some("10".to_string()),
+        cosmos_backup_rpc: Some("http://TESTNET:1317/".to_string()),
+        deployment_block: Some("genesis".to_string()),
+        watch_solvency: true,
+        minter_api: Some("https://node-api.testnet.minter.network/v2".to_string()),
+        cosmos_phrase: "env:TESTNET_PHRASE".to_string(),
+        ethereum_key: "vault:secret/data/testnet".to_string(),
+        state_key: Some("env:TESTNET_STATE_KEY".to_string()),
+        ..chain("testnet", "http://LOCALHOST:9090/")
+    };
+    let issues = lint_chain_configs(&[chain("mainnet", "http://localhost:9090"), testnet]);
+    let fields = |name: &str| -> Vec<String> {
+        issues
+            .iter()
+            .filter(|issue| issue.chain == name)
+            .map(|issue| issue.field.clone())
+            .collect()
+    };
+    for field in [
+        "cosmos_phrase",
+        "ethereum_key",
+        "contract_address",
+        "ethereum_verify_rpc",
+        "ethereum_backfill_rpc",
+        "claim_delay",
+        "state_key",
+    ]
+    .iter()
+    {
+        assert!(fields("mainnet").contains(&field.to_string()), "{}", field);
+    }
+    assert!(!fields("mainnet").contains(&"standby".to_string()));
+    assert!(!fields("mainnet").contains(&"cosmos_grpc".to_string()));
+    // the same Hub endpoint and PeggyID as mainnet, a signer url that isn't http, an overlap over 100%,
+    // a claim batch size without a window, a deployment block that isn't one, a solvency watch without the
+    // multisig, a Vault reference without a field and a backup node that is the primary
+    for field in [
+        "cosmos_grpc",
+        "peggy_id",
+        "threshold_signer",
+        "force_valset_relay_below",
+        "claim_batch_window",
+        "deployment_block",
+        "watch_solvency",
+        "ethereum_key",
+        "cosmos_backup_rpc",
+    ]
+    .iter()
+    {
+        assert!(fields("testnet").contains(&field.to_string()), "{}", field);
+    }
+    assert!(!fields("testnet").contains(&"cosmos_legacy_rpc".to_string()));
+    assert!(!fields("testnet").contains(&"minter_api".to_string()));
+    // secrets kept elsewhere aren't fetched
+    assert!(!fields("testnet").contains(&"cosmos_phrase".to_string()));
+    assert!(!fields("testnet").contains(&"state_key".to_string()));
+
+    // a profile fills in the contract, and an unknown one is reported
+    let profiled = |name: &str, profile: &str| ChainConfig {
+        profile: Some(profile.to_string()),
+        contract_address: String::new(),
+        ..chain(name, "")
+    };
+    let issues = lint_chain_configs(&[profiled("mainnet", "mainnet"), profiled("dev", "devnet")]);
+    assert!(!issues
+        .iter()
+        .any(|issue| issue.chain == "mainnet" && issue.field == "contract_address"));
+    assert!(issues
+        .iter()
+        .any(|issue| issue.chain == "dev" && issue.field == "profile"));
+
+    assert!(is_placeholder(" "));
+    assert!(is_placeholder("<your key>"));
+    assert!(!is_placeholder("http://localhost:9090"));
+}
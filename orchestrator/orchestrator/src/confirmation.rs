@@ -0,0 +1,103 @@
+//! How far along a transfer from Ethereum is, so integrators can pick the point they credit users at rather
+//! than trusting a single done flag. A transfer moves through four stages:
+//!
+//! - `observed` the event is in a block our Ethereum node has
+//! - `attested` validators holding two thirds of the power have claimed it on the Hub
+//! - `executed` the Hub has acted on it, credited or refunded the deposit or paid a transfer to Minter out
+//! - `finalized` it is executed and its block is `FINALITY_BLOCKS` deep, past any reorg seen in practice
+//!
+//! The attestation and the Hub's status come from the Hub's tracking routes, the depth from our Ethereum node.
+//! Claims submitted before the Hub indexed attestations by tx hash never show as attested, once the Hub has
+//! executed them they move on all the same.
+
+use clarity::Uint256;
+use contact::client::Contact;
+use ethereum_peggy::utils::downcast_nonce;
+use peggy_utils::error::PeggyError;
+
+/// Blocks on top of a transfer's block before it counts as final
+pub const FINALITY_BLOCKS: u64 = 64;
+/// The Hub's status of a transfer it hasn't acted on
+const NOT_FOUND: &str = "TX_STATUS_NOT_FOUND";
+
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmationStage {
+    Observed,
+    Attested,
+    Executed,
+    Finalized,
+}
+
+impl ConfirmationStage {
+    /// The stage of a transfer `confirmations` blocks deep, the Hub executing it implies it was attested
+    pub fn new(confirmations: u64, attested: bool, executed: bool) -> Self {
+        if executed && confirmations >= FINALITY_BLOCKS {
+            ConfirmationStage::Finalized
+        } else if executed {
+            ConfirmationStage::Executed
+        } else if attested {
+            ConfirmationStage::Attested
+        } else {
+            ConfirmationStage::Observed
+        }
+    }
+}
+
+/// The blocks from `block` up to and including `eth_block`
+pub fn confirmations(block: &Uint256, eth_block: &Uint256) -> u64 {
+    if block > eth_block {
+        return 0;
+    }
+    downcast_nonce(eth_block.clone() - block.clone() + 1u8.into()).unwrap_or(u64::MAX)
+}
+
+/// Asks the Hub how far the transfer started by `tx_hash` is, `confirmations` blocks deep on Ethereum
+pub async fn get_confirmation_stage(
+    contact: &Contact,
+    tx_hash: &str,
+    confirmations: u64,
+) -> Result<ConfirmationStage, PeggyError> {
+    let executed = contact.get_transfer_status(tx_hash).await?.result.status != NOT_FOUND;
+    let attested = executed
+        || contact
+            .get_attestation_progress(tx_hash)
+            .await?
+            .result
+            .map_or(false, |progress| progress.observed);
+    Ok(ConfirmationStage::new(confirmations, attested, executed))
+}
+
+#[test]
+fn test_confirmation_stage() {
+    assert_eq!(
+        ConfirmationStage::new(1, false, false),
+        ConfirmationStage::Observed
+    );
+    // deep blocks alone don't make a transfer final
+    assert_eq!(
+        ConfirmationStage::new(FINALITY_BLOCKS, false, false),
+        ConfirmationStage::Observed
+    );
+    assert_eq!(
+        ConfirmationStage::new(FINALITY_BLOCKS, true, false),
+        ConfirmationStage::Attested
+    );
+    assert_eq!(
+        ConfirmationStage::new(FINALITY_BLOCKS - 1, true, true),
+        ConfirmationStage::Executed
+    );
+    assert_eq!(
+        ConfirmationStage::new(FINALITY_BLOCKS, true, true),
+        ConfirmationStage::Finalized
+    );
+    assert!(ConfirmationStage::Attested < ConfirmationStage::Executed);
+
+    assert_eq!(confirmations(&100u64.into(), &100u64.into()), 1);
+    assert_eq!(
+        confirmations(&100u64.into(), &163u64.into()),
+        FINALITY_BLOCKS
+    );
+    // a node that is behind the block the transfer was seen in
+    assert_eq!(confirmations(&101u64.into(), &100u64.into()), 0);
+}
@@ -0,0 +1,123 @@
+//! Where the oracle's scan of Ethereum starts on a fresh deployment. An orchestrator that never claimed an event
+//! starts at the chain head, and one that can't find its last claimed event searches back range by range to
+//! genesis. With `--deployment-block=<block>` nothing before the Peggy contract was deployed is scanned: a new
+//! orchestrator starts at the deployment block, so it claims every event of a freshly deployed contract, and the
+//! search for the last claimed event stops there instead of reading the whole chain history.
+//!
+//! `--deployment-block=auto` finds the block by binary search on whether the contract has code at a block. That
+//! takes a node that still has the state of the deployment block, an archive node for anything older than a few
+//! minutes, the search asks `--ethereum-backfill-rpc` if there is one.
+
+use clarity::Address as EthAddress;
+use ethereum_peggy::abi_drift::get_code_at;
+use peggy_utils::error::PeggyError;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DeploymentBlock {
+    Block(u64),
+    Auto,
+}
+
+impl FromStr for DeploymentBlock {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "auto" => Ok(DeploymentBlock::Auto),
+            block => block
+                .parse()
+                .map(DeploymentBlock::Block)
+                .map_err(|e| format!("Invalid deployment block {}: {}", s, e)),
+        }
+    }
+}
+
+/// Narrows down the first block of `[low, high]` with code, given that `high` has it
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Bisection {
+    low: u64,
+    high: u64,
+}
+
+impl Bisection {
+    /// The next block to look at, None once `high` is the first block with code
+    fn probe(&self) -> Option<u64> {
+        if self.low < self.high {
+            Some(self.low + (self.high - self.low) / 2)
+        } else {
+            None
+        }
+    }
+
+    fn observe(&mut self, block: u64, has_code: bool) {
+        if has_code {
+            self.high = block;
+        } else {
+            self.low = block + 1;
+        }
+    }
+}
+
+/// The block `contract_address` was deployed in, `latest_block` must be at or after it
+pub async fn find_deployment_block(
+    eth_url: &str,
+    contract_address: EthAddress,
+    latest_block: u64,
+    timeout: Duration,
+) -> Result<u64, PeggyError> {
+    if get_code_at(eth_url, contract_address, latest_block, timeout)
+        .await?
+        .is_empty()
+    {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "There is no contract at {} as of block {}",
+            contract_address, latest_block
+        )));
+    }
+    let mut bisection = Bisection {
+        low: 0,
+        high: latest_block,
+    };
+    while let Some(block) = bisection.probe() {
+        let code = get_code_at(eth_url, contract_address, block, timeout)
+            .await
+            .map_err(|e| {
+                PeggyError::InvalidBridgeStateError(format!(
+                    "Failed to get the code of {} at block {}, finding the deployment block takes an \
+                     archive node, give --deployment-block a number instead: {:?}",
+                    contract_address, block, e
+                ))
+            })?;
+        bisection.observe(block, !code.is_empty());
+    }
+    Ok(bisection.high)
+}
+
+#[test]
+fn test_deployment_block() {
+    assert_eq!("auto".parse(), Ok(DeploymentBlock::Auto));
+    assert_eq!(" 12090000".parse(), Ok(DeploymentBlock::Block(12_090_000)));
+    assert!("latest".parse::<DeploymentBlock>().is_err());
+
+    let search = |deployed: u64, latest: u64| {
+        let mut bisection = Bisection {
+            low: 0,
+            high: latest,
+        };
+        let mut probes = 0;
+        while let Some(block) = bisection.probe() {
+            bisection.observe(block, block >= deployed);
+            probes += 1;
+        }
+        (bisection.high, probes)
+    };
+    assert_eq!(search(0, 100).0, 0);
+    assert_eq!(search(100, 100).0, 100);
+    assert_eq!(search(37, 100).0, 37);
+    // a mainnet deployment is found in about 25 requests
+    let (block, probes) = search(11_500_123, 20_000_000);
+    assert_eq!(block, 11_500_123);
+    assert!(probes <= 25);
+}
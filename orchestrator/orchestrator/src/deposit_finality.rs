@@ -0,0 +1,193 @@
+//! Optional confirmation depths that scale with the value of a deposit. Every event is normally claimed once it
+//! is `eth_confirmations` blocks deep, which is either slow for small deposits or risky for large ones. With
+//! value bands a token's deposits of at least a band's amount wait for that band's depth instead, so small
+//! deposits can be claimed after a few blocks while large ones wait out a deeper reorg. Deposits below every
+//! band of their token, and all other events, keep `eth_confirmations`.
+//!
+//! The oracle scans as close to the chain head as the shallowest depth allows and holds back the first event
+//! that isn't deep enough yet, with every later event since claims have to stay in event nonce order.
+
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::types::{SendToCosmosEvent, SendToMinterEvent, TransactionBatchExecutedEvent};
+use std::collections::HashMap;
+use web30::types::Log;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct DepositFinality {
+    /// the `(amount, confirmations)` bands of every token, by amount
+    bands: HashMap<EthAddress, Vec<(Uint256, u64)>>,
+}
+
+impl DepositFinality {
+    /// Parses comma separated `<erc20>:<amount>:<confirmations>` bands, deposits of the token of at least the
+    /// amount in base units need that many confirmations
+    pub fn parse(bands: Option<&str>) -> Result<Self, String> {
+        let bands = match bands {
+            Some(bands) => bands,
+            None => return Ok(DepositFinality::default()),
+        };
+        let mut parsed: HashMap<EthAddress, Vec<(Uint256, u64)>> = HashMap::new();
+        for band in bands.split(',').map(|b| b.trim()).filter(|b| !b.is_empty()) {
+            let parts: Vec<&str> = band.split(':').map(|p| p.trim()).collect();
+            let (erc20, amount, confirmations) = match parts.as_slice() {
+                [erc20, amount, confirmations] => (*erc20, *amount, *confirmations),
+                _ => {
+                    return Err(format!(
+                        "Invalid finality band {}, expected <erc20>:<amount>:<confirmations>",
+                        band
+                    ))
+                }
+            };
+            let erc20: EthAddress = erc20
+                .parse()
+                .map_err(|e| format!("Invalid token address {}: {:?}", erc20, e))?;
+            let amount: Uint256 = amount
+                .parse()
+                .map_err(|e| format!("Invalid token amount {}: {:?}", amount, e))?;
+            let confirmations: u64 = confirmations
+                .parse()
+                .map_err(|e| format!("Invalid confirmations {}: {:?}", confirmations, e))?;
+            if confirmations == 0 {
+                return Err(format!(
+                    "Finality band {} must need at least 1 confirmation",
+                    band
+                ));
+            }
+            let token_bands = parsed.entry(erc20).or_insert_with(Vec::new);
+            if token_bands.iter().any(|(a, _)| *a == amount) {
+                return Err(format!("Two finality bands for {} at {}", erc20, amount));
+            }
+            token_bands.push((amount, confirmations));
+        }
+        for token_bands in parsed.values_mut() {
+            token_bands.sort();
+        }
+        Ok(DepositFinality { bands: parsed })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bands.is_empty()
+    }
+
+    /// The confirmations a deposit of `amount` of `erc20` needs, `base` if none of its token's bands covers it
+    pub fn required(&self, erc20: &EthAddress, amount: &Uint256, base: u64) -> u64 {
+        self.bands
+            .get(erc20)
+            .and_then(|bands| bands.iter().rev().find(|(min, _)| amount >= min))
+            .map_or(base, |(_, confirmations)| *confirmations)
+    }
+
+    /// The fewest confirmations any event can need, the oracle scans up to this many blocks from the head
+    pub fn scan_confirmations(&self, base: u64) -> u64 {
+        self.bands
+            .values()
+            .flatten()
+            .map(|(_, confirmations)| *confirmations)
+            .fold(base, u64::min)
+    }
+
+    /// The event nonce of the first event that isn't deep enough yet at `latest_block`, `blocks` maps event
+    /// nonces to the block the event is in
+    pub fn first_unfinal(
+        &self,
+        deposits: &[SendToCosmosEvent],
+        transfers: &[SendToMinterEvent],
+        withdraws: &[TransactionBatchExecutedEvent],
+        blocks: &HashMap<Uint256, Uint256>,
+        latest_block: &Uint256,
+        base: u64,
+    ) -> Option<Uint256> {
+        if self.is_empty() {
+            return None;
+        }
+        deposits
+            .iter()
+            .map(|e| (&e.event_nonce, self.required(&e.erc20, &e.amount, base)))
+            .chain(
+                transfers
+                    .iter()
+                    .map(|e| (&e.event_nonce, self.required(&e.erc20, &e.amount, base))),
+            )
+            .chain(withdraws.iter().map(|e| (&e.event_nonce, base)))
+            .filter(|(nonce, required)| match blocks.get(*nonce) {
+                Some(block) => block.clone() + Uint256::from(*required) > *latest_block,
+                None => true,
+            })
+            .map(|(nonce, _)| nonce.clone())
+            .min()
+    }
+}
+
+/// The block every event is in by event nonce, from the logs the events were parsed from. Logs without a
+/// block are pending and count as in `latest_block`
+pub fn event_blocks<'a>(
+    events: impl Iterator<Item = (&'a Log, &'a Uint256)>,
+    latest_block: &Uint256,
+) -> HashMap<Uint256, Uint256> {
+    events
+        .map(|(log, event_nonce)| {
+            (
+                event_nonce.clone(),
+                log.block_number
+                    .clone()
+                    .unwrap_or_else(|| latest_block.clone()),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_deposit_finality() {
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let other = EthAddress::default();
+    assert!(DepositFinality::parse(None).unwrap().is_empty());
+    assert!(DepositFinality::parse(Some("0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:10")).is_err());
+    assert!(
+        DepositFinality::parse(Some("0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:10:0")).is_err()
+    );
+    assert!(DepositFinality::parse(Some(
+        "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:10:5,0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:10:6"
+    ))
+    .is_err());
+
+    let finality = DepositFinality::parse(Some(
+        "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:1000:50, 0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:0:3",
+    ))
+    .unwrap();
+    assert_eq!(finality.required(&token, &10u64.into(), 12), 3);
+    assert_eq!(finality.required(&token, &1000u64.into(), 12), 50);
+    assert_eq!(finality.required(&other, &1000u64.into(), 12), 12);
+    assert_eq!(finality.scan_confirmations(12), 3);
+    assert_eq!(finality.scan_confirmations(2), 2);
+
+    let deposit = |nonce: u64, amount: u64| SendToCosmosEvent {
+        erc20: token,
+        amount: amount.into(),
+        event_nonce: nonce.into(),
+        ..Default::default()
+    };
+    let withdraw = TransactionBatchExecutedEvent {
+        event_nonce: 3u64.into(),
+        ..Default::default()
+    };
+    let blocks: HashMap<Uint256, Uint256> = vec![(1u64, 100u64), (2, 100), (3, 100)]
+        .into_iter()
+        .map(|(nonce, block)| (nonce.into(), block.into()))
+        .collect();
+    let first_unfinal = |latest: u64| {
+        finality.first_unfinal(
+            &[deposit(1, 10), deposit(2, 5000)],
+            &[],
+            &[withdraw.clone()],
+            &blocks,
+            &latest.into(),
+            12,
+        )
+    };
+    // the small deposit is final after 3 blocks, the large one after 50 and the batch after 12
+    assert_eq!(first_unfinal(102), Some(1u64.into()));
+    assert_eq!(first_unfinal(103), Some(2u64.into()));
+    assert_eq!(first_unfinal(150), None);
+}
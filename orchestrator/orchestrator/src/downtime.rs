@@ -0,0 +1,211 @@
+//! Estimates how long this orchestrator can be offline before our validator misses a signing window on the Hub.
+//! Valsets and batches have to be confirmed within `signed_valsets_window` and `signed_batches_window` blocks of
+//! being created, so the tolerance is the number of blocks left in the tightest window, the full window if we have
+//! nothing left to sign, at the Hub block time we observe. The estimate is published in the state store for the
+//! tui and we warn when it is shorter than the maintenance window the operator plans for.
+//!
+//! Valset confirms that are close to missing their window are also broadcast through the backup Hub node, if
+//! there is one, so a flaky node can't make us miss it.
+
+use crate::claim_delay::unix_now;
+use cosmos_peggy::query::{
+    get_oldest_unsigned_transaction_batch, get_oldest_unsigned_valset, get_peggy_params,
+};
+use deep_space::address::Address as CosmosAddress;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use std::collections::VecDeque;
+use tonic::transport::Channel;
+
+/// How many height samples the block time is averaged over
+const BLOCK_TIME_SAMPLES: usize = 60;
+/// Valset confirms with fewer blocks than this left in their signing window go through the backup Hub node too,
+/// or a tenth of the window if that is shorter
+pub const BACKUP_BROADCAST_BLOCKS: u64 = 1000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct DowntimeTolerance {
+    /// Hub blocks left before the tightest signing window closes
+    pub blocks: u64,
+    /// the same at the current Hub block time, unknown until we have seen a few blocks
+    pub secs: Option<u64>,
+}
+
+/// Tracks the Hub block time from the heights we see over time
+#[derive(Debug, Clone, Default)]
+pub struct BlockTimer {
+    /// (unix time in seconds, Hub height)
+    samples: VecDeque<(u64, u64)>,
+}
+
+impl BlockTimer {
+    pub fn observe(&mut self, now: u64, height: u64) {
+        if let Some((_, last)) = self.samples.back() {
+            if height <= *last {
+                return;
+            }
+        }
+        self.samples.push_back((now, height));
+        if self.samples.len() > BLOCK_TIME_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn secs_per_block(&self) -> Option<f64> {
+        let (first_time, first_height) = self.samples.front()?;
+        let (last_time, last_height) = self.samples.back()?;
+        if last_height <= first_height || last_time <= first_time {
+            return None;
+        }
+        Some((last_time - first_time) as f64 / (last_height - first_height) as f64)
+    }
+}
+
+/// Blocks left in a signing window, `oldest_unsigned` is the height the oldest valset or batch we
+/// still have to sign was created at. A zero window means the Hub doesn't enforce one
+fn blocks_left(window: u64, current_height: u64, oldest_unsigned: Option<u64>) -> Option<u64> {
+    if window == 0 {
+        return None;
+    }
+    Some(match oldest_unsigned {
+        Some(created) => (created + window).saturating_sub(current_height),
+        None => window,
+    })
+}
+
+pub fn estimate_tolerance(
+    valsets_window: u64,
+    batches_window: u64,
+    current_height: u64,
+    unsigned_valset: Option<u64>,
+    unsigned_batch: Option<u64>,
+    secs_per_block: Option<f64>,
+) -> Option<DowntimeTolerance> {
+    let valsets = blocks_left(valsets_window, current_height, unsigned_valset);
+    let batches = blocks_left(batches_window, current_height, unsigned_batch);
+    let blocks = match (valsets, batches) {
+        (Some(a), Some(b)) => a.min(b),
+        (Some(a), None) | (None, Some(a)) => a,
+        (None, None) => return None,
+    };
+    Some(DowntimeTolerance {
+        blocks,
+        secs: secs_per_block.map(|s| (blocks as f64 * s) as u64),
+    })
+}
+
+/// Computes the current downtime tolerance for our orchestrator address
+pub async fn get_downtime_tolerance(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    our_cosmos_address: CosmosAddress,
+    current_height: u64,
+    timer: &mut BlockTimer,
+) -> Result<Option<DowntimeTolerance>, PeggyError> {
+    timer.observe(unix_now(), current_height);
+    let params = get_peggy_params(grpc_client).await?;
+    // valset nonces are the height the valset was created at
+    let unsigned_valset = get_oldest_unsigned_valset(grpc_client, our_cosmos_address)
+        .await?
+        .map(|v| v.nonce);
+    let unsigned_batch = get_oldest_unsigned_transaction_batch(grpc_client, our_cosmos_address)
+        .await?
+        .map(|b| b.block);
+    Ok(estimate_tolerance(
+        params.signed_valsets_window,
+        params.signed_batches_window,
+        current_height,
+        unsigned_valset,
+        unsigned_batch,
+        timer.secs_per_block(),
+    ))
+}
+
+/// Blocks left in the valset signing window below which confirms go through the backup Hub node too, it
+/// follows the window so a shortened window doesn't send every confirm through the backup
+pub fn backup_broadcast_blocks(valsets_window: u64) -> u64 {
+    BACKUP_BROADCAST_BLOCKS.min(valsets_window / 10)
+}
+
+/// Whether the confirm for the valset created at `created` is close enough to missing its window to be
+/// broadcast through the backup Hub node too
+pub fn near_signing_deadline(valsets_window: u64, current_height: u64, created: u64) -> bool {
+    blocks_left(valsets_window, current_height, Some(created))
+        .map_or(false, |left| left < backup_broadcast_blocks(valsets_window))
+}
+
+/// Whether to broadcast the confirm for the valset created at `created` through the backup Hub node. Without
+/// the current height or the window we can't tell how close it is, which likely means the primary node is
+/// having trouble, so the backup is used then too
+pub async fn needs_backup_broadcast(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    current_height: Option<u64>,
+    created: u64,
+) -> bool {
+    let current_height = match current_height {
+        Some(height) => height,
+        None => return true,
+    };
+    match get_peggy_params(grpc_client).await {
+        Ok(params) => near_signing_deadline(params.signed_valsets_window, current_height, created),
+        Err(_) => true,
+    }
+}
+
+/// Warns when we could not be offline for the whole planned maintenance window
+pub fn check_maintenance_window(tolerance: &DowntimeTolerance, maintenance_secs: u64) {
+    match tolerance.secs {
+        Some(secs) if secs < maintenance_secs => warn!(
+            "The orchestrator can only be offline for about {} minutes ({} Hub blocks) before missing a signing window, less than the {} minute maintenance window",
+            secs / 60,
+            tolerance.blocks,
+            maintenance_secs / 60
+        ),
+        _ => {}
+    }
+}
+
+#[test]
+fn test_estimate_tolerance() {
+    let mut timer = BlockTimer::default();
+    assert_eq!(timer.secs_per_block(), None);
+    timer.observe(1000, 100);
+    timer.observe(1050, 110);
+    // heights that don't advance are ignored
+    timer.observe(1060, 110);
+    assert_eq!(timer.secs_per_block(), Some(5.0));
+
+    // nothing to sign, the full window
+    assert_eq!(
+        estimate_tolerance(1000, 2000, 500, None, None, Some(5.0)),
+        Some(DowntimeTolerance {
+            blocks: 1000,
+            secs: Some(5000)
+        })
+    );
+    // a batch created at height 100 has to be signed before 2100
+    assert_eq!(
+        estimate_tolerance(1000, 2000, 1500, None, Some(100), None),
+        Some(DowntimeTolerance {
+            blocks: 600,
+            secs: None
+        })
+    );
+    assert_eq!(
+        estimate_tolerance(1000, 0, 1500, Some(100), None, None).map(|t| t.blocks),
+        Some(0)
+    );
+    assert_eq!(estimate_tolerance(0, 0, 1500, None, None, None), None);
+}
+
+#[test]
+fn test_near_signing_deadline() {
+    assert!(!near_signing_deadline(10_000, 5_000, 4_000));
+    assert!(near_signing_deadline(10_000, 13_500, 4_000));
+    // past the window it is still worth trying
+    assert!(near_signing_deadline(10_000, 20_000, 4_000));
+    // a short window has a proportionally short margin
+    assert!(!near_signing_deadline(2_000, 1_500, 0));
+    assert!(near_signing_deadline(2_000, 1_850, 0));
+    // no window, no deadline
+    assert!(!near_signing_deadline(0, 20_000, 4_000));
+}
@@ -0,0 +1,236 @@
+//! Accounting metadata for the transfers we claim. With `--address-labels` senders and destinations that appear in
+//! a json file of `{"<address>": "<label>"}` are journaled with their label, with `--price-feed` every transfer is
+//! journaled with its token's USD price when we observed it. Both are optional and only for the journal and its
+//! exports, a missing price or label never holds back a claim. With `--fold-minter-deposits` the deposits to one
+//! Minter address found in the same scan are journaled as a single entry with their total and count.
+//!
+//! Price feeds are pluggable, `hub` uses the prices the Hub's oracle module agreed on and a file path uses fixed
+//! prices from a json file of `{"<erc20>": "<usd price>"}`, for stablecoins or test deployments. The price of
+//! ETH itself is listed under the zero address, `fee_value` needs it to quote fees in ETH.
+
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use cosmos_peggy::query::{get_coins, get_oracle_prices};
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_utils::amount::{format_decimal, parse_decimal};
+use peggy_utils::error::PeggyError;
+use std::collections::HashMap;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use tonic::transport::Channel;
+
+/// The Hub oracle stores prices as integer USD times this
+const HUB_PRICE_DECIMALS: usize = 10;
+/// The Hub oracle's name for the price of ETH
+const HUB_ETH_PRICE: &str = "eth/0";
+
+/// Price feeds list the price of ETH under this address
+pub fn eth_price_key() -> EthAddress {
+    EthAddress::default()
+}
+
+pub type PriceFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HashMap<EthAddress, String>, PeggyError>> + 'a>>;
+
+/// A source of token prices, prices are decimal USD strings
+pub trait PriceFeed {
+    fn usd_prices(&self) -> PriceFuture<'_>;
+}
+
+/// Fixed prices from a json file
+pub struct FixedPrices(HashMap<EthAddress, String>);
+
+impl PriceFeed for FixedPrices {
+    fn usd_prices(&self) -> PriceFuture<'_> {
+        let prices = self.0.clone();
+        Box::pin(async move { Ok(prices) })
+    }
+}
+
+/// The prices agreed on by the Hub's oracle module, these are kept per Minter coin so they are matched to
+/// the ERC20s through the Hub's coin list
+pub struct HubPriceFeed {
+    contact: Contact,
+    oracle_client: OracleQueryClient<Channel>,
+}
+
+impl HubPriceFeed {
+    async fn prices(&self) -> Result<HashMap<EthAddress, String>, PeggyError> {
+        let mut oracle_client = self.oracle_client.clone();
+        let coins = get_coins(&mut oracle_client).await?;
+        let prices = get_oracle_prices(&self.contact).await?;
+
+        let mut out = HashMap::new();
+        if let Some(price) = prices.get(HUB_ETH_PRICE).and_then(|v| format_hub_price(v)) {
+            out.insert(eth_price_key(), price);
+        }
+        for coin in coins {
+            let erc20: EthAddress = match coin.eth_addr.parse() {
+                Ok(erc20) => erc20,
+                Err(_) => continue,
+            };
+            let price = prices
+                .get(&format!("minter/{}", coin.minter_id))
+                .and_then(|value| format_hub_price(value));
+            if let Some(price) = price {
+                out.insert(erc20, price);
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl PriceFeed for HubPriceFeed {
+    fn usd_prices(&self) -> PriceFuture<'_> {
+        Box::pin(self.prices())
+    }
+}
+
+/// Formats a Hub oracle price as a decimal string
+pub fn format_hub_price(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let value = parse_decimal(value, 0)?;
+    Some(format_decimal(&value, HUB_PRICE_DECIMALS))
+}
+
+/// Reads a json object of string keys and values
+fn read_string_map(path: &Path) -> Result<HashMap<String, String>, PeggyError> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        PeggyError::InvalidBridgeStateError(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    serde_json::from_str(&contents).map_err(|e| {
+        PeggyError::InvalidBridgeStateError(format!("Failed to parse {}: {}", path.display(), e))
+    })
+}
+
+/// Labels for addresses of any chain, looked up case insensitively
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AddressLabels(HashMap<String, String>);
+
+impl AddressLabels {
+    pub fn new(labels: HashMap<String, String>) -> Self {
+        AddressLabels(
+            labels
+                .into_iter()
+                .map(|(address, label)| (address.trim().to_lowercase(), label))
+                .collect(),
+        )
+    }
+
+    pub fn load(path: &Path) -> Result<Self, PeggyError> {
+        Ok(AddressLabels::new(read_string_map(path)?))
+    }
+
+    pub fn label(&self, address: &str) -> Option<String> {
+        self.0.get(&address.trim().to_lowercase()).cloned()
+    }
+}
+
+/// Sets up a price feed given on the command line, `hub` or a json file of fixed prices
+pub async fn new_price_feed(
+    price_feed: &str,
+    contact: &Contact,
+    cosmos_grpc_url: &str,
+) -> Result<Box<dyn PriceFeed>, PeggyError> {
+    if price_feed == "hub" {
+        return Ok(Box::new(HubPriceFeed {
+            contact: contact.clone(),
+            oracle_client: connect_oracle(cosmos_grpc_url).await?,
+        }));
+    }
+    let mut prices = HashMap::new();
+    for (erc20, price) in read_string_map(Path::new(price_feed))? {
+        let erc20: EthAddress = erc20.parse()?;
+        prices.insert(erc20, price);
+    }
+    Ok(Box::new(FixedPrices(prices)))
+}
+
+pub async fn connect_oracle(
+    cosmos_grpc_url: &str,
+) -> Result<OracleQueryClient<Channel>, PeggyError> {
+    OracleQueryClient::connect(cosmos_grpc_url.to_string())
+        .await
+        .map_err(|e| {
+            PeggyError::InvalidBridgeStateError(format!(
+                "Failed to connect to the Hub oracle: {}",
+                e
+            ))
+        })
+}
+
+/// Everything we add to the transfers we journal
+#[derive(Default)]
+pub struct Enrichment {
+    pub labels: AddressLabels,
+    pub price_feed: Option<Box<dyn PriceFeed>>,
+    /// journal the deposits to one Minter address in a scan as one entry
+    pub fold_minter_deposits: bool,
+}
+
+impl Enrichment {
+    pub async fn new(
+        labels: Option<&str>,
+        price_feed: Option<&str>,
+        fold_minter_deposits: bool,
+        contact: &Contact,
+        cosmos_grpc_url: &str,
+    ) -> Result<Self, PeggyError> {
+        let labels = match labels {
+            Some(path) => AddressLabels::load(Path::new(path))?,
+            None => AddressLabels::default(),
+        };
+        let price_feed = match price_feed {
+            Some(feed) => Some(new_price_feed(feed, contact, cosmos_grpc_url).await?),
+            None => None,
+        };
+        Ok(Enrichment {
+            labels,
+            price_feed,
+            fold_minter_deposits,
+        })
+    }
+
+    /// The current prices, empty without a price feed or when the feed fails
+    pub async fn usd_prices(&self) -> HashMap<EthAddress, String> {
+        match self.price_feed.as_ref() {
+            Some(feed) => feed.usd_prices().await.unwrap_or_else(|e| {
+                warn!(
+                    "Failed to get token prices, journaling transfers without them {:?}",
+                    e
+                );
+                HashMap::new()
+            }),
+            None => HashMap::new(),
+        }
+    }
+}
+
+#[test]
+fn test_format_hub_price() {
+    assert_eq!(format_hub_price("12345000000"), Some("1.2345".to_string()));
+    assert_eq!(format_hub_price("20000000000"), Some("2".to_string()));
+    assert_eq!(format_hub_price("500"), Some("0.00000005".to_string()));
+    assert_eq!(format_hub_price("0"), Some("0".to_string()));
+    assert_eq!(format_hub_price("-1"), None);
+}
+
+#[test]
+fn test_address_labels() {
+    let mut labels = HashMap::new();
+    labels.insert(
+        "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8".to_string(),
+        "exchange hot wallet".to_string(),
+    );
+    let labels = AddressLabels::new(labels);
+    assert_eq!(
+        labels.label("0x7580bfe88dd3d07947908fae12d95872a260f2d8"),
+        Some("exchange hot wallet".to_string())
+    );
+    assert_eq!(labels.label("Mx00"), None);
+}
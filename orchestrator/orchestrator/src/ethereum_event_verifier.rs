@@ -0,0 +1,108 @@
+//! Cross checks the Ethereum events used for claims against a second, independently configured Ethereum
+//! node. Claims are only sent if the verification node reports exactly the same events, so a single lying
+//! or broken RPC endpoint can't get the validator to attest to deposits that never happened.
+
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::{
+    error::PeggyError,
+    types::{SendToCosmosEvent, SendToMinterEvent, TransactionBatchExecutedEvent},
+};
+use std::fmt::Debug;
+use web30::client::Web3;
+
+pub async fn verify_events(
+    verify_web3: &Web3,
+    peggy_contract_address: EthAddress,
+    starting_block: Uint256,
+    ending_block: Uint256,
+    deposits: &[SendToCosmosEvent],
+    transfers: &[SendToMinterEvent],
+    withdraws: &[TransactionBatchExecutedEvent],
+) -> Result<(), PeggyError> {
+    let verify_latest_block = verify_web3.eth_block_number().await?;
+    if verify_latest_block < ending_block {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "Verification Ethereum node is at block {} behind our scan height {}",
+            verify_latest_block, ending_block
+        )));
+    }
+
+    if !deposits.is_empty() {
+        let logs = verify_web3
+            .check_for_events(
+                starting_block.clone(),
+                Some(ending_block.clone()),
+                vec![peggy_contract_address],
+                vec!["SendToHubEvent(address,address,bytes32,uint256,uint256)"],
+            )
+            .await?;
+        let verified = SendToCosmosEvent::from_logs(&logs)?;
+        ensure_all_verified("deposit", deposits, &verified)?;
+    }
+
+    if !transfers.is_empty() {
+        let logs = verify_web3
+            .check_for_events(
+                starting_block.clone(),
+                Some(ending_block.clone()),
+                vec![peggy_contract_address],
+                vec!["SendToMinterEvent(address,address,bytes32,uint256,uint256)"],
+            )
+            .await?;
+        let verified = SendToMinterEvent::from_logs(&logs)?;
+        ensure_all_verified("transfer", transfers, &verified)?;
+    }
+
+    if !withdraws.is_empty() {
+        let logs = verify_web3
+            .check_for_events(
+                starting_block,
+                Some(ending_block),
+                vec![peggy_contract_address],
+                vec!["TransactionBatchExecutedEvent(uint256,address,address,uint256)"],
+            )
+            .await?;
+        let verified = TransactionBatchExecutedEvent::from_logs(&logs)?;
+        ensure_all_verified("batch", withdraws, &verified)?;
+    }
+
+    Ok(())
+}
+
+/// Every event we are about to claim must be reported, field for field, by the verification node
+fn ensure_all_verified<T: PartialEq + Debug>(
+    kind: &str,
+    observed: &[T],
+    verified: &[T],
+) -> Result<(), PeggyError> {
+    for event in observed {
+        if !verified.contains(event) {
+            return Err(PeggyError::InvalidBridgeStateError(format!(
+                "Ethereum {} event {:?} was not reported by the verification node, refusing to sign claims!",
+                kind, event
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_ensure_all_verified() {
+    let event = SendToCosmosEvent {
+        erc20: "0x34Ac3eB6180FdD94043664C22043F004734Dc480"
+            .parse()
+            .unwrap(),
+        sender: "0x527FBEE652609AB150F0AEE9D61A2F76CFC4A73E"
+            .parse()
+            .unwrap(),
+        amount: 100u64.into(),
+        event_nonce: 5u64.into(),
+        ..Default::default()
+    };
+    let mut forged = event.clone();
+    forged.amount = 1_000_000u64.into();
+
+    assert!(ensure_all_verified("deposit", &[event.clone()], &[event.clone()]).is_ok());
+    assert!(ensure_all_verified("deposit", &[forged], &[event.clone()]).is_err());
+    assert!(ensure_all_verified("deposit", &[event], &[]).is_err());
+}
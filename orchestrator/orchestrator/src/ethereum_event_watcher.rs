@@ -1,33 +1,135 @@
 //! Ethereum Event watcher watches for events such as a deposit to the Peggy Ethereum contract or a validator set update
 //! or a transaction batch update. It then responds to these events by performing actions on the Cosmos chain if required
 
+use crate::anomaly::AnomalyDetector;
+use crate::claim_batching::ClaimBatching;
+use crate::claim_delay::{unix_now, ClaimDelay};
+use crate::claim_resubmission::{ClaimAction, ClaimResubmission};
+use crate::deposit_finality::{event_blocks, DepositFinality};
+use crate::enrichment::Enrichment;
+use crate::ethereum_event_verifier::verify_events;
+use crate::journal::{
+    fold_minter_deposits, ClaimedEvent, Direction, JournalEntry, TransferMetadata,
+};
+use crate::pause::PauseState;
+use crate::state_store::LocalStorage;
+use crate::supply_caps::{first_capped_deposit, SupplyCaps};
 use clarity::{Address as EthAddress, Uint256};
 use contact::client::Contact;
 use cosmos_peggy::{query::get_last_event_nonce, send::send_ethereum_claims};
 use deep_space::{coin::Coin, private_key::PrivateKey as CosmosPrivateKey};
+use ethereum_peggy::utils::downcast_nonce;
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
 use peggy_utils::{
     error::PeggyError,
+    explorer::{self, Chain},
     types::{
         SendToCosmosEvent, SendToMinterEvent, TransactionBatchExecutedEvent, ValsetUpdatedEvent,
     },
 };
+use std::collections::HashMap;
 use std::ops::Sub;
 use tonic::transport::Channel;
 use web30::client::Web3;
 use web30::jsonrpc::error::Web3Error;
 
+/// The most blocks we request logs for at once. Catching up after downtime would otherwise fetch and
+/// decode every log since we last checked in one response, which can run a small VM out of memory,
+/// instead we work through the backlog a range at a time and report progress along the way
+pub const MAX_BLOCKS_PER_SCAN: u64 = 10_000;
+/// The most logs we take from one range, a busier range is dropped and scanned again in smaller ones
+pub const MAX_LOGS_PER_SCAN: usize = 2_000;
+
+/// How many blocks are scanned at once, up to `MAX_BLOCKS_PER_SCAN`. A range whose logs are over
+/// `MAX_LOGS_PER_SCAN`, or that the node fails to return, halves the window, one well under it doubles the
+/// window again, so a busy stretch of the chain is worked through in small ranges without holding all of its
+/// logs at once
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ScanWindow {
+    blocks: u64,
+}
+
+impl Default for ScanWindow {
+    fn default() -> Self {
+        ScanWindow {
+            blocks: MAX_BLOCKS_PER_SCAN,
+        }
+    }
+}
+
+impl ScanWindow {
+    /// The last block to scan this round, at most the window past `starting_block`
+    pub fn end(&self, starting_block: &Uint256, latest_block: Uint256) -> Uint256 {
+        let limit = starting_block.clone() + self.blocks.into();
+        if latest_block > limit {
+            limit
+        } else {
+            latest_block
+        }
+    }
+
+    /// Fits the window to the number of logs a range returned, false if there were too many to process and
+    /// the range has to be scanned again. A single block is always processed
+    pub fn fit(&mut self, logs: usize) -> bool {
+        if logs > MAX_LOGS_PER_SCAN && self.blocks > 1 {
+            self.shrink();
+            return false;
+        }
+        if logs < MAX_LOGS_PER_SCAN / 4 {
+            self.blocks = (self.blocks * 2).min(MAX_BLOCKS_PER_SCAN);
+        }
+        true
+    }
+
+    /// Halves the window, for a range the node failed to return
+    pub fn shrink(&mut self) {
+        self.blocks = (self.blocks / 2).max(1);
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn check_for_events(
     web3: &Web3,
+    verify_web3: Option<&Web3>,
     contact: &Contact,
     grpc_client: &mut PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
     our_private_key: CosmosPrivateKey,
     fee: Coin,
+    fee_payer: Option<CosmosPrivateKey>,
     starting_block: Uint256,
+    scan_window: &mut ScanWindow,
+    confirmations: u64,
+    deposit_finality: &DepositFinality,
+    pause_state: PauseState,
+    supply_caps: &SupplyCaps,
+    claim_delay: &mut ClaimDelay,
+    claim_batching: &mut ClaimBatching,
+    claim_resubmission: &mut ClaimResubmission,
+    anomalies: &mut AnomalyDetector,
+    enrichment: &Enrichment,
+    storage: Option<&LocalStorage>,
 ) -> Result<Uint256, PeggyError> {
     let our_cosmos_address = our_private_key.to_public_key().unwrap().to_address();
-    let latest_block = web3.eth_block_number().await?.sub(5u64.into());
+    let eth_block = web3.eth_block_number().await?;
+    // with finality bands small deposits may be claimable sooner, deeper events are held back below
+    let chain_head = eth_block
+        .clone()
+        .sub(deposit_finality.scan_confirmations(confirmations).into());
+    let latest_block = scan_window.end(&starting_block, chain_head.clone());
+    let catching_up = latest_block < chain_head;
+    if catching_up {
+        info!(
+            "Oracle catching up, scanning blocks {} to {}, {} blocks behind",
+            starting_block,
+            latest_block,
+            chain_head - latest_block.clone()
+        );
+    }
 
     let deposits = web3
         .check_for_events(
@@ -69,18 +171,45 @@ pub async fn check_for_events(
         .await;
     trace!("Valsets {:?}", valsets);
 
-    if let (Ok(valsets), Ok(batches), Ok(deposits), Ok(transfers)) =
+    if let (Ok(valsets), Ok(batch_logs), Ok(deposit_logs), Ok(transfer_logs)) =
         (valsets, batches, deposits, transfers)
     {
+        let logs = valsets.len() + batch_logs.len() + deposit_logs.len() + transfer_logs.len();
+        if !scan_window.fit(logs) {
+            warn!(
+                "Blocks {} to {} have {} logs, scanning {} blocks at a time instead",
+                starting_block,
+                latest_block,
+                logs,
+                scan_window.blocks()
+            );
+            return Ok(starting_block);
+        }
         let valsets = ValsetUpdatedEvent::from_logs(&valsets)?;
         trace!("parsed valsets {:?}", valsets);
-        let withdraws = TransactionBatchExecutedEvent::from_logs(&batches)?;
-        trace!("parsed batches {:?}", batches);
-        let deposits = SendToCosmosEvent::from_logs(&deposits)?;
+        let withdraws = TransactionBatchExecutedEvent::from_logs(&batch_logs)?;
+        trace!("parsed batches {:?}", withdraws);
+        let deposits = SendToCosmosEvent::from_logs(&deposit_logs)?;
         trace!("parsed deposits {:?}", deposits);
 
-        let transfers = SendToMinterEvent::from_logs(&transfers)?;
-        trace!("parsed deposits {:?}", deposits);
+        let transfers = SendToMinterEvent::from_logs(&transfer_logs)?;
+        trace!("parsed transfers {:?}", transfers);
+        let blocks = event_blocks(
+            batch_logs
+                .iter()
+                .zip(withdraws.iter().map(|e| &e.event_nonce))
+                .chain(
+                    deposit_logs
+                        .iter()
+                        .zip(deposits.iter().map(|e| &e.event_nonce)),
+                )
+                .chain(
+                    transfer_logs
+                        .iter()
+                        .zip(transfers.iter().map(|e| &e.event_nonce)),
+                ),
+            &eth_block,
+        );
 
         // note that starting block overlaps with our last checked block, because we have to deal with
         // the possibility that the relayer was killed after relaying only one of multiple events in a single
@@ -93,17 +222,182 @@ pub async fn check_for_events(
         let withdraws =
             TransactionBatchExecutedEvent::filter_by_event_nonce(last_event_nonce, &withdraws);
 
+        let now = unix_now();
+        let found = anomalies.observe(now, &deposits, &transfers);
+        for anomaly in found.iter() {
+            error!("DEPOSIT ANOMALY {}", anomaly);
+            if let Some(storage) = storage {
+                storage.count_error("deposit_anomaly");
+            }
+        }
+        if !found.is_empty() && anomalies.escalate {
+            claim_delay.escalate(now);
+        }
+
+        // events past a governance halt are left for later, we must not advance past them either
+        // so they are picked up again once the halt is lifted
+        let halted = deposits
+            .iter()
+            .map(|e| &e.event_nonce)
+            .chain(transfers.iter().map(|e| &e.event_nonce))
+            .chain(withdraws.iter().map(|e| &e.event_nonce))
+            .any(|nonce| pause_state.claim_halted(nonce));
+        let deposits: Vec<SendToCosmosEvent> = deposits
+            .into_iter()
+            .filter(|e| !pause_state.claim_halted(&e.event_nonce))
+            .collect();
+        let transfers: Vec<SendToMinterEvent> = transfers
+            .into_iter()
+            .filter(|e| !pause_state.claim_halted(&e.event_nonce))
+            .collect();
+        let withdraws: Vec<TransactionBatchExecutedEvent> = withdraws
+            .into_iter()
+            .filter(|e| !pause_state.claim_halted(&e.event_nonce))
+            .collect();
+        if halted {
+            error!(
+                "Bridge halted at event nonce {:?}, not claiming later events",
+                pause_state.halt_at_event_nonce
+            );
+        }
+
+        // deposits of a token over its supply cap are held back the same way, along with every
+        // later event since claims have to stay in event nonce order
+        let capped = first_capped_deposit(
+            web3,
+            peggy_contract_address,
+            supply_caps,
+            &deposits,
+            &transfers,
+        )
+        .await?;
+        let held_back = |nonce: &Uint256| capped.as_ref().map(|c| nonce >= c).unwrap_or(false);
+        let deposits: Vec<SendToCosmosEvent> = deposits
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        let transfers: Vec<SendToMinterEvent> = transfers
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        let withdraws: Vec<TransactionBatchExecutedEvent> = withdraws
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        if let Some(capped) = capped.as_ref() {
+            error!(
+                "Deposit with event nonce {} is over its token's supply cap, not claiming it or any later events",
+                capped
+            );
+        }
+
+        // large deposits wait out the claim delay, again holding back every later event
+        let delayed = claim_delay.first_delayed(&deposits, &transfers, now);
+        let held_back = |nonce: &Uint256| delayed.as_ref().map(|d| nonce >= d).unwrap_or(false);
+        let deposits: Vec<SendToCosmosEvent> = deposits
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        let transfers: Vec<SendToMinterEvent> = transfers
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        let withdraws: Vec<TransactionBatchExecutedEvent> = withdraws
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        if let Some(delayed) = delayed.as_ref() {
+            info!(
+                "Claim for the deposit with event nonce {} is delayed, not claiming it or any later events yet",
+                delayed
+            );
+        }
+
+        // and events that aren't as deep as their value calls for
+        let unfinal = deposit_finality.first_unfinal(
+            &deposits,
+            &transfers,
+            &withdraws,
+            &blocks,
+            &eth_block,
+            confirmations,
+        );
+        let held_back = |nonce: &Uint256| unfinal.as_ref().map(|u| nonce >= u).unwrap_or(false);
+        let deposits: Vec<SendToCosmosEvent> = deposits
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        let transfers: Vec<SendToMinterEvent> = transfers
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        let withdraws: Vec<TransactionBatchExecutedEvent> = withdraws
+            .into_iter()
+            .filter(|e| !held_back(&e.event_nonce))
+            .collect();
+        if let Some(unfinal) = unfinal.as_ref() {
+            info!(
+                "Event nonce {} doesn't have the confirmations its value needs yet, not claiming it or any later events",
+                unfinal
+            );
+        }
+
+        let event_nonces: Vec<u64> = deposits
+            .iter()
+            .map(|e| e.event_nonce.clone())
+            .chain(transfers.iter().map(|e| e.event_nonce.clone()))
+            .chain(withdraws.iter().map(|e| e.event_nonce.clone()))
+            .filter_map(downcast_nonce)
+            .collect();
+        // with a claim batch window the events are gathered and claimed together, we don't advance past
+        // them so they are found again next time
+        if !claim_batching.should_claim(last_event_nonce, &event_nonces, catching_up, now) {
+            info!("{}", claim_batching.describe_held(&event_nonces, now));
+            return Ok(starting_block);
+        }
+
+        if claims_held(claim_resubmission, &event_nonces, now, storage) {
+            return Ok(starting_block);
+        }
+
+        for (nonce, sender, tx_hash) in deposits
+            .iter()
+            .filter(|e| e.invalid_destination)
+            .map(|e| (&e.event_nonce, &e.sender, &e.tx_hash))
+            .chain(
+                transfers
+                    .iter()
+                    .filter(|e| e.invalid_destination)
+                    .map(|e| (&e.event_nonce, &e.sender, &e.tx_hash)),
+            )
+        {
+            warn!(
+                "Deposit with event nonce {} in tx {} has an invalid destination, claiming it as a refund to {}",
+                nonce,
+                explorer::tx(Chain::Ethereum, tx_hash),
+                sender
+            );
+        }
+
         if !deposits.is_empty() {
             info!(
-                "Oracle observed deposit with sender {}, destination {}, amount {}, and event nonce {}",
-                deposits[0].sender, deposits[0].destination, deposits[0].amount, deposits[0].event_nonce
+                "Oracle observed deposit with sender {}, destination {}, amount {}, and event nonce {} in tx {}",
+                deposits[0].sender,
+                deposits[0].destination,
+                deposits[0].amount,
+                deposits[0].event_nonce,
+                explorer::tx(Chain::Ethereum, &deposits[0].tx_hash)
             )
         }
 
         if !transfers.is_empty() {
             info!(
-                "Oracle observed transfer with sender {}, destination {}, amount {}, and event nonce {}",
-                transfers[0].sender, transfers[0].destination, transfers[0].amount, transfers[0].event_nonce
+                "Oracle observed transfer with sender {}, destination {}, amount {}, and event nonce {} in tx {}",
+                transfers[0].sender,
+                transfers[0].destination,
+                transfers[0].amount,
+                transfers[0].event_nonce,
+                explorer::tx(Chain::Ethereum, &transfers[0].tx_hash)
             )
         }
 
@@ -115,29 +409,232 @@ pub async fn check_for_events(
         }
 
         if !deposits.is_empty() || !withdraws.is_empty() || !transfers.is_empty() {
-            let _res = send_ethereum_claims(
+            if let Some(verify_web3) = verify_web3 {
+                verify_events(
+                    verify_web3,
+                    peggy_contract_address,
+                    starting_block.clone(),
+                    latest_block.clone(),
+                    &deposits,
+                    &transfers,
+                    &withdraws,
+                )
+                .await?;
+            }
+
+            // the metadata only goes into the journal, don't bother the price feed without one
+            let prices = match storage {
+                Some(_) => enrichment.usd_prices().await,
+                None => HashMap::new(),
+            };
+            let deposit_metadata =
+                |erc20: &EthAddress, sender: &EthAddress, destination: &str| TransferMetadata {
+                    sender: Some(*sender),
+                    sender_label: enrichment.labels.label(&sender.to_string()),
+                    destination: Some(destination.to_string()),
+                    destination_label: enrichment.labels.label(destination),
+                    usd_price: prices.get(erc20).cloned(),
+                };
+            let events: Vec<ClaimedEvent> = deposits
+                .iter()
+                .map(|e| ClaimedEvent {
+                    direction: Direction::ToHub,
+                    erc20: e.erc20,
+                    amount: e.amount.clone(),
+                    batch_nonce: None,
+                    metadata: deposit_metadata(&e.erc20, &e.sender, &e.destination.to_string()),
+                    folded: None,
+                })
+                .chain(transfers.iter().map(|e| ClaimedEvent {
+                    direction: Direction::ToMinter,
+                    erc20: e.erc20,
+                    amount: e.amount.clone(),
+                    batch_nonce: None,
+                    metadata: deposit_metadata(&e.erc20, &e.sender, &e.destination),
+                    folded: None,
+                }))
+                .chain(withdraws.iter().map(|e| ClaimedEvent {
+                    direction: Direction::FromHub,
+                    erc20: e.erc20,
+                    amount: 0u64.into(),
+                    batch_nonce: downcast_nonce(e.batch_nonce.clone()),
+                    metadata: TransferMetadata {
+                        usd_price: prices.get(&e.erc20).cloned(),
+                        ..Default::default()
+                    },
+                    folded: None,
+                }))
+                .collect();
+            let transfers_claimed = events
+                .iter()
+                .filter(|e| e.direction != Direction::FromHub)
+                .count() as u64;
+            // the claims still carry every deposit, only the journal entries are folded
+            let events = if enrichment.fold_minter_deposits {
+                fold_minter_deposits(events)
+            } else {
+                events
+            };
+
+            // every broadcast signs with our current sequence, so a resubmission is a fresh tx
+            let res = match send_ethereum_claims(
                 contact,
                 our_private_key,
                 deposits,
                 withdraws,
                 transfers,
                 fee,
+                fee_payer,
             )
-            .await?;
+            .await
+            {
+                Ok(res) => res,
+                Err(e @ PeggyError::CosmosTxRejected(_)) => {
+                    claim_resubmission.settled();
+                    return Err(e);
+                }
+                Err(e) => {
+                    claim_resubmission.submitted(&event_nonces, None, unix_now());
+                    return Err(e);
+                }
+            };
             let new_event_nonce = get_last_event_nonce(grpc_client, our_cosmos_address).await?;
             // since we can't actually trust that the above txresponse is correct we have to check here
             // we may be able to trust the tx response post grpc
             if new_event_nonce == last_event_nonce {
-                return Err(PeggyError::InvalidBridgeStateError(
-                    "Claims did not process, trying again in a moment".to_string(),
-                ));
+                claim_resubmission.submitted(&event_nonces, Some(res.txhash.clone()), unix_now());
+                return Err(PeggyError::InvalidBridgeStateError(format!(
+                    "Claims in tx {} did not process, trying again in a moment",
+                    explorer::tx(Chain::Hub, &res.txhash)
+                )));
+            }
+            claim_resubmission.settled();
+            claim_delay.claimed(new_event_nonce);
+            claim_batching.claimed();
+            if let Some(storage) = storage {
+                let claimed = event_nonces.len() as u64;
+                storage.record(
+                    JournalEntry::Claims {
+                        event_nonces,
+                        txhash: res.txhash,
+                        events,
+                        timestamp: unix_now(),
+                    },
+                    |state| {
+                        state.last_event_nonce = new_event_nonce;
+                        state.count("claim_txs", 1);
+                        state.count("events_claimed", claimed);
+                        state.count("transfers_claimed", transfers_claimed);
+                    },
+                );
             }
         }
+        if halted || capped.is_some() || delayed.is_some() || unfinal.is_some() {
+            return Ok(starting_block);
+        }
         Ok(latest_block)
     } else {
-        error!("Failed to get events");
+        // the node may refuse a range with too many logs, try a smaller one next time
+        scan_window.shrink();
+        error!(
+            "Failed to get events, scanning {} blocks at a time",
+            scan_window.blocks()
+        );
         Err(PeggyError::EthereumRestError(Web3Error::BadResponse(
             "Failed to get logs!".to_string(),
         )))
     }
 }
+
+/// Whether the claims for `event_nonces` are held back because we already sent them, claims we sent get their
+/// TTL to show up on the Hub before they are sent again and expired claims wait out their backoff
+fn claims_held(
+    claim_resubmission: &mut ClaimResubmission,
+    event_nonces: &[u64],
+    now: u64,
+    storage: Option<&LocalStorage>,
+) -> bool {
+    match claim_resubmission.check(event_nonces, now) {
+        ClaimAction::Submit => false,
+        ClaimAction::Wait(remaining) => {
+            info!(
+                "Claims for event nonces {:?} were sent but aren't on the Hub yet, sending them again in {} seconds",
+                event_nonces, remaining
+            );
+            true
+        }
+        ClaimAction::Resubmit(attempt) => {
+            warn!(
+                "Claims for event nonces {:?} didn't make it onto the Hub, sending them again, attempt {} of {}",
+                event_nonces,
+                attempt,
+                claim_resubmission.max_attempts()
+            );
+            false
+        }
+        ClaimAction::Expired(remaining) => {
+            error!(
+                "Claims for event nonces {:?} were sent {} times without making it onto the Hub, sending them again in {} seconds",
+                event_nonces,
+                claim_resubmission.max_attempts(),
+                remaining
+            );
+            if let Some(storage) = storage {
+                storage.count_error("claims_expired");
+            }
+            true
+        }
+    }
+}
+
+#[test]
+fn test_claims_held() {
+    let mut resubmission = ClaimResubmission::new(Some("60"), Some("0")).unwrap();
+    assert!(!claims_held(&mut resubmission, &[1], 100, None));
+    resubmission.submitted(&[1], None, 100);
+    assert!(claims_held(&mut resubmission, &[1], 130, None));
+    // the only attempt is spent, the watcher stays put while the claims are expired
+    assert!(claims_held(&mut resubmission, &[1], 160, None));
+    assert!(claims_held(&mut resubmission, &[1, 2], 600, None));
+    // and sends them again once the backoff is over, advancing when the Hub takes them
+    assert!(!claims_held(&mut resubmission, &[1, 2], 700, None));
+    resubmission.submitted(&[1, 2], None, 700);
+    assert!(claims_held(&mut resubmission, &[1, 2], 730, None));
+    assert!(!claims_held(&mut resubmission, &[3], 740, None));
+}
+
+#[test]
+fn test_scan_window() {
+    let start: Uint256 = 100u64.into();
+    let mut window = ScanWindow::default();
+    assert_eq!(window.end(&start, 150u64.into()), 150u64.into());
+    assert_eq!(
+        window.end(&start, 1_000_000u64.into()),
+        (100 + MAX_BLOCKS_PER_SCAN).into()
+    );
+
+    // a busy range is dropped and the window halves
+    assert!(!window.fit(MAX_LOGS_PER_SCAN + 1));
+    assert_eq!(window.blocks(), MAX_BLOCKS_PER_SCAN / 2);
+    assert_eq!(
+        window.end(&start, 1_000_000u64.into()),
+        (100 + MAX_BLOCKS_PER_SCAN / 2).into()
+    );
+    // a range the node fails to return halves it too
+    window.shrink();
+    assert_eq!(window.blocks(), MAX_BLOCKS_PER_SCAN / 4);
+    // a range that is neither busy nor quiet keeps it
+    assert!(window.fit(MAX_LOGS_PER_SCAN));
+    assert_eq!(window.blocks(), MAX_BLOCKS_PER_SCAN / 4);
+    // and quiet ranges grow it back up to the most blocks per scan
+    for _ in 0..4 {
+        assert!(window.fit(0));
+    }
+    assert_eq!(window.blocks(), MAX_BLOCKS_PER_SCAN);
+
+    // a single block is processed however many logs it has
+    let mut window = ScanWindow { blocks: 1 };
+    assert!(window.fit(MAX_LOGS_PER_SCAN * 10));
+    window.shrink();
+    assert_eq!(window.blocks(), 1);
+}
@@ -0,0 +1,462 @@
+//! `orchestrator explain-msg` shows what signing a Hub message actually commits to, so a validator can check
+//! what their orchestrator signs without reading the code. It takes the amino json of any Peggy message, as
+//! logged with --log-sensitive or printed by an offline export, and prints its canonical sign bytes and every
+//! field. For valset and batch confirms the Ethereum signature inside the message commits to a checkpoint,
+//! which is rebuilt from the Hub's valset or batch and the PeggyID, and the signature is checked against it.
+//!
+//! A list of messages, such as the msgs of a tx, is explained message by message. Message types added by a
+//! newer Hub are shown with their raw fields and sign bytes, or ignored or refused with `--unknown-msgs`.
+
+use clarity::Address as EthAddress;
+use cosmos_peggy::messages::{decode_msg, decode_msgs, DecodedMsg, PeggyMsg, UnknownMsgPolicy};
+use cosmos_peggy::query::{get_transaction_batch, get_valset};
+use deep_space::msg::DeepSpaceMsg;
+use deep_space::utils::bytes_to_hex_str;
+use ethereum_peggy::checkpoint_cache::{batch_checkpoint, valset_checkpoint, Checkpoint};
+use ethereum_peggy::utils::downcast_nonce;
+use num256::Uint256;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use peggy_utils::types::decode_signature;
+use serde_json::Value;
+use std::fmt::Write;
+use tonic::transport::Channel;
+
+/// The valset or batch whose checkpoint a confirm's Ethereum signature commits to
+#[derive(Serialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Commitment {
+    Valset {
+        nonce: u64,
+    },
+    Batch {
+        nonce: u64,
+        token_contract: EthAddress,
+    },
+}
+
+#[derive(Debug)]
+pub struct Explanation {
+    /// the amino type of the message
+    pub kind: String,
+    /// the canonical json the Cosmos signature covers
+    pub sign_bytes: Vec<u8>,
+    /// every field of the message by its dotted path
+    pub fields: Vec<(String, String)>,
+    pub commitment: Option<Commitment>,
+}
+
+/// A confirm's Ethereum signature checked against the rebuilt checkpoint
+#[derive(Debug)]
+pub struct SignatureCheck {
+    pub peggy_id: String,
+    pub checkpoint: Checkpoint,
+    /// the address the confirm says signed it
+    pub signer: EthAddress,
+    pub valid: bool,
+}
+
+/// Parses one message or a list of them, unknown message types are handled according to `policy`
+pub fn parse_msgs(json: &str, policy: UnknownMsgPolicy) -> Result<Vec<DecodedMsg>, String> {
+    let json: Value =
+        serde_json::from_str(json).map_err(|e| format!("Not a Peggy message: {}", e))?;
+    if json.is_array() {
+        decode_msgs(json, policy)
+    } else {
+        Ok(decode_msg(json, policy)?.into_iter().collect())
+    }
+}
+
+pub fn explain(msg: &DecodedMsg) -> Result<Explanation, String> {
+    let (sign_bytes, value, commitment) = match msg {
+        DecodedMsg::Known(msg) => (
+            msg.to_sign_bytes(),
+            serde_json::to_value(msg),
+            commitment(msg)?,
+        ),
+        DecodedMsg::Unknown(msg) => (msg.to_sign_bytes(), serde_json::to_value(msg), None),
+    };
+    let sign_bytes = sign_bytes.map_err(|e| format!("Failed to encode the sign bytes: {:?}", e))?;
+    let value = value.map_err(|e| e.to_string())?;
+    let kind = value["type"].as_str().unwrap_or_default().to_string();
+    let mut fields = Vec::new();
+    flatten("", &value["value"], &mut fields);
+    Ok(Explanation {
+        kind,
+        sign_bytes,
+        fields,
+        commitment,
+    })
+}
+
+fn flatten(path: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                flatten(&join(&i.to_string()), value, out);
+            }
+        }
+        Value::String(s) => out.push((path.to_string(), s.clone())),
+        other => out.push((path.to_string(), other.to_string())),
+    }
+}
+
+fn to_nonce(nonce: &Uint256) -> Result<u64, String> {
+    downcast_nonce(nonce.clone()).ok_or_else(|| format!("Nonce {} is out of range", nonce))
+}
+
+/// What a message's Ethereum signature commits to, None for messages without one
+pub fn commitment(msg: &PeggyMsg) -> Result<Option<Commitment>, String> {
+    Ok(match msg {
+        PeggyMsg::ValsetConfirmMsg(confirm) => Some(Commitment::Valset {
+            nonce: to_nonce(&confirm.nonce)?,
+        }),
+        PeggyMsg::ConfirmBatchMsg(confirm) => Some(Commitment::Batch {
+            nonce: to_nonce(&confirm.nonce)?,
+            token_contract: confirm.token_contract,
+        }),
+        _ => None,
+    })
+}
+
+/// Rebuilds the checkpoint from the Hub's valset or batch and checks the confirm's signature against it
+pub async fn check_signature(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    msg: &PeggyMsg,
+    commitment: Commitment,
+    peggy_id: String,
+) -> Result<SignatureCheck, PeggyError> {
+    let checkpoint = match commitment {
+        Commitment::Valset { nonce } => {
+            let valset = get_valset(grpc_client, nonce).await?.ok_or_else(|| {
+                PeggyError::InvalidBridgeStateError(format!("The Hub has no valset {}", nonce))
+            })?;
+            valset_checkpoint(&peggy_id, &valset)
+        }
+        Commitment::Batch {
+            nonce,
+            token_contract,
+        } => {
+            let batch = get_transaction_batch(grpc_client, nonce, token_contract, None)
+                .await?
+                .ok_or_else(|| {
+                    PeggyError::InvalidBridgeStateError(format!(
+                        "The Hub has no batch {} for {}",
+                        nonce, token_contract
+                    ))
+                })?;
+            batch_checkpoint(&peggy_id, &batch)
+        }
+    };
+    let (signer, signature) = match msg {
+        PeggyMsg::ValsetConfirmMsg(confirm) => (confirm.eth_address, &confirm.eth_signature),
+        PeggyMsg::ConfirmBatchMsg(confirm) => (confirm.eth_signer, &confirm.eth_signature),
+        _ => unreachable!("only confirms have a commitment"),
+    };
+    let valid = match decode_signature(signature)?.recover(&checkpoint.signed_hash) {
+        Ok(recovered) => recovered == signer,
+        Err(_) => false,
+    };
+    Ok(SignatureCheck {
+        peggy_id,
+        checkpoint,
+        signer,
+        valid,
+    })
+}
+
+/// What the message does, in a sentence
+fn describe(msg: &DecodedMsg) -> &'static str {
+    let msg = match msg {
+        DecodedMsg::Known(msg) => msg,
+        DecodedMsg::Unknown(_) => {
+            return "Unknown to this orchestrator, likely added by a newer Hub, check its fields yourself"
+        }
+    };
+    match msg {
+        PeggyMsg::SetOrchestratorAddressMsg(_) => {
+            "Delegates the validator's Peggy duties to an orchestrator and an Ethereum address"
+        }
+        PeggyMsg::ValsetConfirmMsg(_) => {
+            "Confirms a valset update, the Ethereum signature lets relayers submit it to the contract"
+        }
+        PeggyMsg::SendToEthMsg(_) => "Sends tokens from the Hub to an Ethereum address",
+        PeggyMsg::CancelSendToEthMsg(_) => {
+            "Takes a withdrawal that isn't in a batch yet out of the pool and refunds it"
+        }
+        PeggyMsg::RequestBatchMsg(_) => "Asks the Hub to build a batch of pending withdrawals",
+        PeggyMsg::ConfirmBatchMsg(_) => {
+            "Confirms a withdrawal batch, the Ethereum signature lets relayers submit it to the contract"
+        }
+        PeggyMsg::RelayReceiptMsg(_) => {
+            "Records that a relayer submitted a valset update or batch to Ethereum, for accounting only"
+        }
+        PeggyMsg::CreateEthereumClaimsMsg(_) => "Attests to a set of Ethereum events",
+        PeggyMsg::DepositClaimMsg(_) => "Attests to a deposit into the Peggy contract",
+        PeggyMsg::SendToMinterClaimMsg(_) => "Attests to a deposit sent on to Minter",
+        PeggyMsg::WithdrawClaimMsg(_) => "Attests to a batch executed on Ethereum",
+        PeggyMsg::SendToMinterMsg(_) => "Sends tokens from the Hub to a Minter address",
+        PeggyMsg::RequestMinterBatchMsg(_) => "Asks the Hub to build a batch of Minter withdrawals",
+        PeggyMsg::SendMsg(_) => "Sends tokens between Hub accounts",
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ExplainedField {
+    pub path: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct SignatureView {
+    pub peggy_id: String,
+    pub checkpoint: String,
+    pub signed_hash: String,
+    pub signer: EthAddress,
+    pub valid: bool,
+}
+
+/// What `explain-msg --output json` prints for every message
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ExplanationView {
+    pub kind: String,
+    pub description: String,
+    pub fields: Vec<ExplainedField>,
+    pub sign_bytes: String,
+    pub sign_bytes_hex: String,
+    pub commitment: Option<Commitment>,
+    /// the signature checked against the rebuilt checkpoint, None if it wasn't checked
+    pub signature: Option<SignatureView>,
+    /// why the checkpoint couldn't be rebuilt
+    pub signature_error: Option<String>,
+}
+
+pub fn explanation_view(
+    msg: &DecodedMsg,
+    explanation: &Explanation,
+    check: Option<&Result<SignatureCheck, PeggyError>>,
+) -> ExplanationView {
+    let (signature, signature_error) = match check {
+        Some(Ok(check)) => (
+            Some(SignatureView {
+                peggy_id: check.peggy_id.clone(),
+                checkpoint: format!("0x{}", bytes_to_hex_str(&check.checkpoint.message)),
+                signed_hash: format!("0x{}", bytes_to_hex_str(&check.checkpoint.signed_hash)),
+                signer: check.signer,
+                valid: check.valid,
+            }),
+            None,
+        ),
+        Some(Err(e)) => (None, Some(format!("{:?}", e))),
+        None => (None, None),
+    };
+    ExplanationView {
+        kind: explanation.kind.clone(),
+        description: describe(msg).to_string(),
+        fields: explanation
+            .fields
+            .iter()
+            .map(|(path, value)| ExplainedField {
+                path: path.clone(),
+                value: value.clone(),
+            })
+            .collect(),
+        sign_bytes: String::from_utf8_lossy(&explanation.sign_bytes).to_string(),
+        sign_bytes_hex: format!("0x{}", bytes_to_hex_str(&explanation.sign_bytes)),
+        commitment: explanation.commitment,
+        signature,
+        signature_error,
+    }
+}
+
+pub fn format_explanation(
+    msg: &DecodedMsg,
+    explanation: &Explanation,
+    check: Option<&Result<SignatureCheck, PeggyError>>,
+) -> String {
+    let mut out = String::new();
+    writeln!(out, "{}", explanation.kind).unwrap();
+    writeln!(out, "  {}", describe(msg)).unwrap();
+    writeln!(out, "Fields").unwrap();
+    let width = explanation
+        .fields
+        .iter()
+        .map(|(path, _)| path.len())
+        .max()
+        .unwrap_or(0);
+    for (path, value) in explanation.fields.iter() {
+        writeln!(out, "  {:width$}  {}", path, value, width = width).unwrap();
+    }
+    writeln!(out, "Sign bytes").unwrap();
+    writeln!(
+        out,
+        "  {}",
+        String::from_utf8_lossy(&explanation.sign_bytes)
+    )
+    .unwrap();
+    writeln!(out, "  0x{}", bytes_to_hex_str(&explanation.sign_bytes)).unwrap();
+
+    let commitment = match explanation.commitment {
+        Some(commitment) => commitment,
+        None => return out,
+    };
+    match commitment {
+        Commitment::Valset { nonce } => writeln!(out, "Commits to valset {}", nonce).unwrap(),
+        Commitment::Batch {
+            nonce,
+            token_contract,
+        } => writeln!(out, "Commits to batch {}:{}", token_contract, nonce).unwrap(),
+    }
+    match check {
+        Some(Ok(check)) => {
+            writeln!(out, "  peggy id: {}", check.peggy_id).unwrap();
+            writeln!(
+                out,
+                "  checkpoint: 0x{}",
+                bytes_to_hex_str(&check.checkpoint.message)
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "  signed hash: 0x{}",
+                bytes_to_hex_str(&check.checkpoint.signed_hash)
+            )
+            .unwrap();
+            let valid = if check.valid {
+                "valid"
+            } else {
+                "INVALID, not signed by this address over this checkpoint"
+            };
+            writeln!(out, "  signature of {}: {}", check.signer, valid).unwrap();
+        }
+        Some(Err(e)) => writeln!(out, "  Failed to rebuild the checkpoint: {:?}", e).unwrap(),
+        None => writeln!(
+            out,
+            "  Give --cosmos-grpc and --peggy-id or the contract to rebuild its checkpoint"
+        )
+        .unwrap(),
+    }
+    out
+}
+
+#[test]
+fn test_explain_msg() {
+    use cosmos_peggy::messages::ConfirmBatchMsg;
+    use deep_space::address::Address as CosmosAddress;
+
+    let token_contract: EthAddress = "0x0101010101010101010101010101010101010101"
+        .parse()
+        .unwrap();
+    let json = serde_json::to_string(&PeggyMsg::ConfirmBatchMsg(ConfirmBatchMsg {
+        nonce: 7u64.into(),
+        orchestrator: CosmosAddress::from_bytes([1; 20]),
+        token_contract,
+        eth_signer: "0x0202020202020202020202020202020202020202"
+            .parse()
+            .unwrap(),
+        eth_signature: "aa".to_string(),
+    }))
+    .unwrap();
+    let msgs = parse_msgs(&json, UnknownMsgPolicy::Fail).unwrap();
+    let explanation = explain(&msgs[0]).unwrap();
+    assert_eq!(explanation.kind, "peggy/MsgConfirmBatch");
+    match &msgs[0] {
+        DecodedMsg::Known(msg) => assert_eq!(explanation.sign_bytes, msg.to_sign_bytes().unwrap()),
+        DecodedMsg::Unknown(_) => panic!("decoded a known message as unknown"),
+    }
+    assert!(explanation
+        .fields
+        .contains(&("nonce".to_string(), "7".to_string())));
+    assert_eq!(
+        explanation.commitment,
+        Some(Commitment::Batch {
+            nonce: 7,
+            token_contract
+        })
+    );
+    // sign bytes are canonical, keys sorted
+    let sign_bytes = String::from_utf8(explanation.sign_bytes).unwrap();
+    assert!(sign_bytes.find("eth_signer") < sign_bytes.find("nonce"));
+
+    let mut fields = Vec::new();
+    flatten(
+        "",
+        &serde_json::json!({"amount": {"denom": "hub", "amount": "1"}, "list": [true]}),
+        &mut fields,
+    );
+    assert_eq!(
+        fields,
+        vec![
+            ("amount.amount".to_string(), "1".to_string()),
+            ("amount.denom".to_string(), "hub".to_string()),
+            ("list.0".to_string(), "true".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_msgs() {
+    use deep_space::address::Address as CosmosAddress;
+
+    let orchestrator = CosmosAddress::from_bytes([1; 20]);
+    let unknown = format!(
+        r#"{{"type": "peggy/MsgNewFeature", "value": {{"orchestrator": "{}", "level": 2}}}}"#,
+        orchestrator
+    );
+    let known = format!(
+        r#"{{"type": "peggy/MsgRequestBatch", "value": {{"orchestrator": "{}", "denom": "hub", "added_later": true}}}}"#,
+        orchestrator
+    );
+    let list = format!("[{}, {}]", unknown, known);
+
+    assert!(parse_msgs(&unknown, UnknownMsgPolicy::Fail).is_err());
+    assert!(parse_msgs(&list, UnknownMsgPolicy::Fail).is_err());
+    assert_eq!(
+        parse_msgs(&unknown, UnknownMsgPolicy::Ignore),
+        Ok(Vec::new())
+    );
+    // fields added to known messages are ignored whatever the policy
+    let msgs = parse_msgs(&list, UnknownMsgPolicy::Ignore).unwrap();
+    assert_eq!(msgs.len(), 1);
+    assert!(matches!(
+        msgs[0],
+        DecodedMsg::Known(PeggyMsg::RequestBatchMsg(_))
+    ));
+
+    // kept unknown messages explain with the fields and sign bytes the Hub sent
+    let msgs = parse_msgs(&list, UnknownMsgPolicy::Warn).unwrap();
+    assert_eq!(msgs.len(), 2);
+    let explanation = explain(&msgs[0]).unwrap();
+    assert_eq!(explanation.kind, "peggy/MsgNewFeature");
+    assert!(explanation
+        .fields
+        .contains(&("level".to_string(), "2".to_string())));
+    assert_eq!(
+        String::from_utf8(explanation.sign_bytes).unwrap(),
+        format!(
+            r#"{{"type":"peggy/MsgNewFeature","value":{{"level":2,"orchestrator":"{}"}}}}"#,
+            orchestrator
+        )
+    );
+    assert_eq!(explanation.commitment, None);
+
+    // a known type that doesn't decode is an error, not an unknown message
+    assert!(parse_msgs(
+        r#"{"type": "peggy/MsgRequestBatch", "value": {"denom": 1}}"#,
+        UnknownMsgPolicy::Warn
+    )
+    .is_err());
+    assert!(parse_msgs(r#"{"value": {}}"#, UnknownMsgPolicy::Warn).is_err());
+}
@@ -0,0 +1,266 @@
+//! Funding keys from testnet faucets, so a devnet can bring up orchestrators that are ready to pay their fees
+//! without shuffling tokens around by hand. `orchestrator faucet` funds the given addresses and `register
+//! --faucet` funds the delegate keys it generates. The faucets are set in a json file, one per chain:
+//!
+//! ```json
+//! {
+//!     "hub": {"key": "env:HUB_FAUCET_PHRASE", "denom": "hub", "amount": "1000000000000000000"},
+//!     "ethereum": {"key": "file:/keys/goerli-faucet", "amount": "100000000000000000"},
+//!     "minter": {"url": "https://faucet.testnet.example/fund"}
+//! }
+//! ```
+//!
+//! A faucet is either a funded key, a Hub phrase or Ethereum private key that sends `amount`, or an HTTP
+//! faucet that is posted `{"address": "<address>"}` with the `denom` if one is set. Keys can be secret
+//! references like any other key. Minter faucets can only be HTTP faucets. Faucets refuse to run against the
+//! Hub or Ethereum mainnet.
+
+use clarity::Address as EthAddress;
+use clarity::PrivateKey as EthPrivateKey;
+use contact::client::Contact;
+use cosmos_peggy::send::send_tokens;
+use cosmos_peggy::utils::check_tx_response;
+use deep_space::address::Address as CosmosAddress;
+use deep_space::coin::Coin;
+use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+use num256::Uint256;
+use peggy_utils::error::PeggyError;
+use peggy_utils::secrets::resolve_secret;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use web30::client::Web3;
+
+/// How long to wait for an HTTP faucet or the Ethereum funding tx
+const FAUCET_TIMEOUT: Duration = Duration::from_secs(120);
+/// The chain id of the Hub mainnet
+const HUB_MAINNET_CHAIN_ID: &str = "mhub-mainnet-1";
+/// The network id of Ethereum mainnet
+const ETHEREUM_MAINNET_ID: u64 = 1;
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum FaucetSource {
+    Key {
+        key: String,
+        amount: String,
+        #[serde(default)]
+        denom: Option<String>,
+    },
+    Http {
+        url: String,
+        #[serde(default)]
+        denom: Option<String>,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FaucetConfig {
+    #[serde(default)]
+    pub hub: Option<FaucetSource>,
+    #[serde(default)]
+    pub ethereum: Option<FaucetSource>,
+    #[serde(default)]
+    pub minter: Option<FaucetSource>,
+}
+
+impl FaucetConfig {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let config: FaucetConfig =
+            serde_json::from_str(json).map_err(|e| format!("Invalid faucet file: {}", e))?;
+        for (chain, source) in [
+            ("hub", &config.hub),
+            ("ethereum", &config.ethereum),
+            ("minter", &config.minter),
+        ]
+        .iter()
+        {
+            if let Some(FaucetSource::Key { amount, denom, .. }) = source {
+                if amount.parse::<Uint256>().is_err() {
+                    return Err(format!("Invalid {} faucet amount {}", chain, amount));
+                }
+                if *chain == "hub" && denom.is_none() {
+                    return Err("The hub faucet key needs a denom to send".to_string());
+                }
+                if *chain == "minter" {
+                    return Err("Minter faucets can only be HTTP faucets".to_string());
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        FaucetConfig::parse(&json)
+    }
+}
+
+fn faucet_error(message: String) -> PeggyError {
+    PeggyError::InvalidOptionsError(message)
+}
+
+fn missing(chain: &str) -> PeggyError {
+    faucet_error(format!("The faucet file has no {} faucet", chain))
+}
+
+/// Refuses Hub and Ethereum mainnet, the Ethereum check is skipped without an Ethereum node
+pub async fn check_devnet(contact: &Contact, web3: Option<&Web3>) -> Result<(), PeggyError> {
+    let block = contact.get_latest_block().await?;
+    if block.block.header.chain_id == HUB_MAINNET_CHAIN_ID {
+        return Err(faucet_error(format!(
+            "{} is the Hub mainnet, faucets are for devnets and testnets",
+            HUB_MAINNET_CHAIN_ID
+        )));
+    }
+    if let Some(web3) = web3 {
+        if web3.net_version().await? == ETHEREUM_MAINNET_ID.into() {
+            return Err(faucet_error(
+                "The Ethereum node is on mainnet, faucets are for devnets and testnets".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Posts the address to an HTTP faucet, returns its reply
+async fn request_http(
+    url: &str,
+    address: &str,
+    denom: &Option<String>,
+) -> Result<String, PeggyError> {
+    let mut body = serde_json::json!({ "address": address });
+    if let Some(denom) = denom {
+        body["denom"] = denom.clone().into();
+    }
+    let mut res = actix_web::client::Client::default()
+        .post(url)
+        .timeout(FAUCET_TIMEOUT)
+        .send_json(&body)
+        .await
+        .map_err(|e| faucet_error(format!("Failed to reach the faucet at {}: {}", url, e)))?;
+    let reply = res.body().await.unwrap_or_default();
+    let reply = String::from_utf8_lossy(&reply).trim().to_string();
+    if !res.status().is_success() {
+        return Err(faucet_error(format!(
+            "The faucet at {} returned {}: {}",
+            url,
+            res.status(),
+            reply
+        )));
+    }
+    Ok(reply)
+}
+
+/// Funds a Hub address, returns the txid or the faucet's reply
+pub async fn fund_hub(
+    config: &FaucetConfig,
+    contact: &Contact,
+    address: CosmosAddress,
+) -> Result<String, PeggyError> {
+    match config.hub.as_ref().ok_or_else(|| missing("hub"))? {
+        FaucetSource::Http { url, denom } => request_http(url, &address.to_string(), denom).await,
+        FaucetSource::Key { key, amount, denom } => {
+            let phrase = resolve_secret(key).await?;
+            let key = CosmosPrivateKey::from_phrase(&phrase, "")
+                .map_err(|_| faucet_error("Invalid hub faucet phrase".to_string()))?;
+            let denom = denom.clone().unwrap_or_default();
+            let res = send_tokens(
+                key,
+                address,
+                Coin {
+                    amount: amount.parse().unwrap(),
+                    denom: denom.clone(),
+                },
+                Coin {
+                    amount: 1u64.into(),
+                    denom,
+                },
+                contact,
+            )
+            .await
+            .map_err(PeggyError::from)
+            .and_then(check_tx_response)?;
+            Ok(res.txhash)
+        }
+    }
+}
+
+/// Funds an Ethereum address with ETH for gas, returns the tx hash or the faucet's reply
+pub async fn fund_ethereum(
+    config: &FaucetConfig,
+    web3: &Web3,
+    address: EthAddress,
+) -> Result<String, PeggyError> {
+    match config
+        .ethereum
+        .as_ref()
+        .ok_or_else(|| missing("ethereum"))?
+    {
+        FaucetSource::Http { url, denom } => request_http(url, &address.to_string(), denom).await,
+        FaucetSource::Key { key, amount, .. } => {
+            let key: EthPrivateKey = resolve_secret(key)
+                .await?
+                .parse()
+                .map_err(|_| faucet_error("Invalid ethereum faucet key".to_string()))?;
+            let tx_hash = web3
+                .send_transaction(
+                    address,
+                    Vec::new(),
+                    amount.parse().unwrap(),
+                    key.to_public_key()?,
+                    key,
+                    vec![],
+                )
+                .await?;
+            web3.wait_for_transaction(tx_hash.clone(), FAUCET_TIMEOUT, None)
+                .await?;
+            Ok(format!("{:#066x}", tx_hash))
+        }
+    }
+}
+
+/// Funds a Minter address, returns the faucet's reply
+pub async fn fund_minter(config: &FaucetConfig, address: &str) -> Result<String, PeggyError> {
+    match config.minter.as_ref().ok_or_else(|| missing("minter"))? {
+        FaucetSource::Http { url, denom } => request_http(url, address, denom).await,
+        FaucetSource::Key { .. } => Err(faucet_error(
+            "Minter faucets can only be HTTP faucets".to_string(),
+        )),
+    }
+}
+
+#[test]
+fn test_faucet_config() {
+    let config = FaucetConfig::parse(
+        r#"{
+            "hub": {"key": "env:HUB_FAUCET_PHRASE", "denom": "hub", "amount": "1000"},
+            "minter": {"url": "http://localhost:8080/fund", "denom": "BIP"}
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        config.hub,
+        Some(FaucetSource::Key {
+            key: "env:HUB_FAUCET_PHRASE".to_string(),
+            amount: "1000".to_string(),
+            denom: Some("hub".to_string()),
+        })
+    );
+    assert_eq!(
+        config.minter,
+        Some(FaucetSource::Http {
+            url: "http://localhost:8080/fund".to_string(),
+            denom: Some("BIP".to_string()),
+        })
+    );
+    assert_eq!(config.ethereum, None);
+
+    // a hub key needs a denom, amounts must be numbers, minter has no keys
+    assert!(FaucetConfig::parse(r#"{"hub": {"key": "a", "amount": "1"}}"#).is_err());
+    assert!(FaucetConfig::parse(r#"{"ethereum": {"key": "a", "amount": "1 eth"}}"#).is_err());
+    assert!(FaucetConfig::parse(r#"{"minter": {"key": "a", "amount": "1"}}"#).is_err());
+    assert!(FaucetConfig::parse(r#"{"goerli": {"url": "http://localhost"}}"#).is_err());
+}
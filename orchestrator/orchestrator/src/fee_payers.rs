@@ -0,0 +1,67 @@
+//! Lets a separate funding key pay the Hub fees for claims and confirms so the orchestrator delegate key
+//! can be kept at a minimal balance. The funding key can be used for every message type or only some of
+//! them, anything not covered is paid by the delegate key as before.
+
+use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+
+/// Names accepted by --fee-payer-for
+pub const FEE_PAYER_KINDS: [&str; 3] = ["claims", "valset_confirms", "batch_confirms"];
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct FeePayers {
+    pub claims: Option<CosmosPrivateKey>,
+    pub valset_confirms: Option<CosmosPrivateKey>,
+    pub batch_confirms: Option<CosmosPrivateKey>,
+}
+
+impl FeePayers {
+    /// Assigns the funding key to the comma separated message kinds, or to all of them if none are given
+    pub fn new(key: Option<CosmosPrivateKey>, kinds: Option<&str>) -> Result<Self, String> {
+        let key = match key {
+            Some(key) => key,
+            None if kinds.is_some() => {
+                return Err("--fee-payer-for requires --fee-payer-phrase".to_string())
+            }
+            None => return Ok(FeePayers::default()),
+        };
+        let kinds: Vec<&str> = match kinds {
+            Some(kinds) => kinds.split(',').map(|k| k.trim()).collect(),
+            None => FEE_PAYER_KINDS.to_vec(),
+        };
+        let mut payers = FeePayers::default();
+        for kind in kinds {
+            match kind {
+                "claims" => payers.claims = Some(key),
+                "valset_confirms" => payers.valset_confirms = Some(key),
+                "batch_confirms" => payers.batch_confirms = Some(key),
+                _ => {
+                    return Err(format!(
+                        "Unknown fee payer message kind {}, expected one of {}",
+                        kind,
+                        FEE_PAYER_KINDS.join(", ")
+                    ))
+                }
+            }
+        }
+        Ok(payers)
+    }
+}
+
+#[test]
+fn test_fee_payers() {
+    let key = CosmosPrivateKey::from_secret(b"fee payer");
+    assert_eq!(FeePayers::new(None, None).unwrap(), FeePayers::default());
+    assert!(FeePayers::new(None, Some("claims")).is_err());
+
+    let all = FeePayers::new(Some(key), None).unwrap();
+    assert_eq!(all.claims, Some(key));
+    assert_eq!(all.valset_confirms, Some(key));
+    assert_eq!(all.batch_confirms, Some(key));
+
+    let some = FeePayers::new(Some(key), Some("claims, batch_confirms")).unwrap();
+    assert_eq!(some.claims, Some(key));
+    assert_eq!(some.valset_confirms, None);
+    assert_eq!(some.batch_confirms, Some(key));
+
+    assert!(FeePayers::new(Some(key), Some("claims,deposits")).is_err());
+}
@@ -0,0 +1,228 @@
+//! Tops up the bridge fee of our own withdrawals that wait in the Hub's outgoing pool because their fee is too
+//! low to be batched. With `--top-up-fees=<caps>` every transfer sent from the orchestrator's Hub address that
+//! the stalled transfer scan blames on its fee is cancelled and sent again in the same transaction, paying
+//! `--top-up-percent` more, 50% by default, up to the token's cap. The new transfer gets a new id so it waits
+//! the full `--stalled-transfer-alert` threshold again before the next top up.
+//!
+//! Caps are comma separated `<erc20>:<fee>` pairs in Hub units, tokens without a cap are never topped up. A
+//! transfer paying no fee at all goes straight to its cap. The Hub refunds transfers that have been in the pool
+//! for an hour, so the alert threshold must be shorter than that. The cancellation refunds the old fee, only
+//! the difference to the new one is paid from the orchestrator's balance.
+
+use crate::solvency::from_eth_units;
+use crate::stalled_transfers::{StallCause, StalledTransfer};
+use crate::supply_caps::parse_token_amounts;
+use clarity::{Address as EthAddress, Uint256};
+use contact::client::Contact;
+use cosmos_peggy::query::get_coins;
+use cosmos_peggy::send::resend_to_eth;
+use cosmos_peggy::utils::check_tx_response;
+use deep_space::coin::Coin;
+use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_utils::error::PeggyError;
+use std::collections::HashMap;
+use tonic::transport::Channel;
+
+pub const DEFAULT_TOP_UP_PERCENT: u64 = 50;
+/// The Hub refunds transfers that have been in the outgoing pool this long
+pub const POOL_EXPIRY_SECS: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FeeTopUp {
+    /// the highest fee we pay per token, in Hub units
+    caps: HashMap<EthAddress, Uint256>,
+    percent: u64,
+}
+
+impl FeeTopUp {
+    /// Parses the caps and percentage, None without caps. `threshold_secs` is the stalled transfer alert
+    /// threshold, top ups only happen when a transfer is reported as stalled
+    pub fn new(
+        caps: Option<&str>,
+        percent: Option<&str>,
+        threshold_secs: Option<u64>,
+    ) -> Result<Option<Self>, String> {
+        let caps = match caps {
+            Some(caps) => parse_token_amounts(caps)?,
+            None if percent.is_some() => {
+                return Err("--top-up-percent requires --top-up-fees".to_string())
+            }
+            None => return Ok(None),
+        };
+        match threshold_secs {
+            Some(threshold_secs) if threshold_secs < POOL_EXPIRY_SECS => {}
+            _ => {
+                return Err(format!(
+                    "--top-up-fees requires a --stalled-transfer-alert below {} minutes, the Hub refunds transfers after that",
+                    POOL_EXPIRY_SECS / 60
+                ))
+            }
+        }
+        let percent = match percent {
+            Some(percent) => percent
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| format!("Invalid top up percentage {}: {}", percent, e))?,
+            None => DEFAULT_TOP_UP_PERCENT,
+        };
+        if percent == 0 {
+            return Err("The top up percentage must be above 0".to_string());
+        }
+        Ok(Some(FeeTopUp { caps, percent }))
+    }
+
+    /// The fee to send `stalled` again with, None unless we sent it, it waits for its fee and the fee is below
+    /// the token's cap
+    pub fn new_fee(&self, stalled: &StalledTransfer, our_address: &str) -> Option<Uint256> {
+        match stalled.cause {
+            StallCause::NoFee | StallCause::FeeTooLow { .. } => {}
+            _ => return None,
+        }
+        let transfer = &stalled.transfer;
+        if transfer.sender != our_address {
+            return None;
+        }
+        let cap = self.caps.get(&transfer.token())?;
+        let fee = transfer.erc20_fee.amount.clone();
+        if fee >= *cap {
+            return None;
+        }
+        if fee == 0u8.into() {
+            return Some(cap.clone());
+        }
+        let bumped = fee.clone() * Uint256::from(100 + self.percent) / Uint256::from(100u8);
+        // tiny fees round down to no bump at all
+        let bumped = bumped.max(fee + 1u8.into());
+        Some(bumped.min(cap.clone()))
+    }
+}
+
+/// Cancels `stalled` and sends it again paying `new_fee`, the Hub denom and decimals of its token come from the
+/// oracle's coin list
+pub async fn top_up_fee(
+    contact: &Contact,
+    oracle_client: &mut OracleQueryClient<Channel>,
+    cosmos_key: CosmosPrivateKey,
+    fee: Coin,
+    stalled: &StalledTransfer,
+    new_fee: Uint256,
+) -> Result<(), PeggyError> {
+    let transfer = &stalled.transfer;
+    let token = transfer.token();
+    let coin = get_coins(oracle_client)
+        .await?
+        .into_iter()
+        .find(|coin| coin.eth_addr.parse::<EthAddress>().ok() == Some(token))
+        .ok_or_else(|| PeggyError::InvalidBridgeStateError(format!("No Hub coin for {}", token)))?;
+    let destination: EthAddress = transfer.dest_address.parse().map_err(|e| {
+        PeggyError::InvalidBridgeStateError(format!(
+            "Invalid destination {}: {:?}",
+            transfer.dest_address, e
+        ))
+    })?;
+    // the pool keeps the amount in the token's Ethereum decimals, the cancellation refunds it in Hub units
+    let amount = Coin {
+        denom: coin.denom.clone(),
+        amount: from_eth_units(transfer.erc20_token.amount.clone(), coin.eth_decimals),
+    };
+    let bridge_fee = Coin {
+        denom: coin.denom,
+        amount: new_fee,
+    };
+    let res = resend_to_eth(
+        cosmos_key,
+        transfer.id,
+        destination,
+        amount,
+        bridge_fee,
+        transfer.memo.clone(),
+        fee,
+        contact,
+    )
+    .await?;
+    check_tx_response(res)?;
+    Ok(())
+}
+
+#[test]
+fn test_fee_top_up() {
+    use crate::stalled_transfers::PoolTransfer;
+    use peggy_utils::types::ERC20Token;
+
+    let token: EthAddress = "0x0101010101010101010101010101010101010101"
+        .parse()
+        .unwrap();
+    let caps = format!("{}:1000", token);
+    let stalled = |sender: &str, fee: u64, cause: StallCause| StalledTransfer {
+        transfer: PoolTransfer {
+            id: 1,
+            sender: sender.to_string(),
+            dest_address: "0x0303030303030303030303030303030303030303".to_string(),
+            erc20_token: ERC20Token {
+                amount: 5000u64.into(),
+                token_contract_address: token,
+            },
+            erc20_fee: ERC20Token {
+                amount: fee.into(),
+                token_contract_address: token,
+            },
+            memo: String::new(),
+        },
+        waiting_secs: 600,
+        cause,
+    };
+    let too_low = StallCause::FeeTooLow { batches: 2 };
+
+    assert_eq!(FeeTopUp::new(None, None, Some(600)), Ok(None));
+    assert!(FeeTopUp::new(None, Some("50"), Some(600)).is_err());
+    // without alerts or with a threshold the Hub's refund beats, nothing would be topped up
+    assert!(FeeTopUp::new(Some(&caps), None, None).is_err());
+    assert!(FeeTopUp::new(Some(&caps), None, Some(POOL_EXPIRY_SECS)).is_err());
+    assert!(FeeTopUp::new(Some(&caps), Some("0"), Some(600)).is_err());
+
+    let top_up = FeeTopUp::new(Some(&caps), None, Some(600))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1us", 100, too_low), "hub1us"),
+        Some(150u64.into())
+    );
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1us", 1, too_low), "hub1us"),
+        Some(2u64.into())
+    );
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1us", 800, too_low), "hub1us"),
+        Some(1000u64.into())
+    );
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1us", 0, StallCause::NoFee), "hub1us"),
+        Some(1000u64.into())
+    );
+    // at the cap, someone else's, or stalled for another reason
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1us", 1000, too_low), "hub1us"),
+        None
+    );
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1them", 100, too_low), "hub1us"),
+        None
+    );
+    assert_eq!(
+        top_up.new_fee(
+            &stalled("hub1us", 100, StallCause::NoBatchRequested),
+            "hub1us"
+        ),
+        None
+    );
+
+    let other = format!("{}:1000", "0x0202020202020202020202020202020202020202");
+    let top_up = FeeTopUp::new(Some(&other), Some("10"), Some(600))
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        top_up.new_fee(&stalled("hub1us", 100, too_low), "hub1us"),
+        None
+    );
+}
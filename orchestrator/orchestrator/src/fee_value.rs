@@ -0,0 +1,191 @@
+//! Batch fees are paid in the batch's own token, so fees of different tokens can't be compared or added up as
+//! they are. This values them in a single quote asset, USD or ETH, using the `enrichment` price feeds and the
+//! token decimals from the Hub's coin list. A token without a price or decimals has no value rather than a
+//! wrong one.
+
+use crate::enrichment::{eth_price_key, PriceFeed};
+use clarity::{Address as EthAddress, Uint256};
+use cosmos_peggy::query::get_coins;
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_utils::amount::{format_amount, parse_decimal, pow10};
+use peggy_utils::error::PeggyError;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use tonic::transport::Channel;
+
+/// Values are kept as integers of this many decimals
+pub const VALUE_DECIMALS: usize = 18;
+/// Ether has 18 decimals like most ERC20s
+pub const ETH_DECIMALS: u64 = 18;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum QuoteAsset {
+    Usd,
+    Eth,
+}
+
+impl Default for QuoteAsset {
+    fn default() -> Self {
+        QuoteAsset::Usd
+    }
+}
+
+impl FromStr for QuoteAsset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "usd" => Ok(QuoteAsset::Usd),
+            "eth" => Ok(QuoteAsset::Eth),
+            other => Err(format!(
+                "Unknown quote asset {}, expected usd or eth",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for QuoteAsset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QuoteAsset::Usd => write!(f, "USD"),
+            QuoteAsset::Eth => write!(f, "ETH"),
+        }
+    }
+}
+
+/// Values token amounts in the quote asset
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FeeValuer {
+    pub quote: QuoteAsset,
+    /// USD per whole token with `VALUE_DECIMALS` decimals, ETH under `eth_price_key`
+    usd_prices: HashMap<EthAddress, Uint256>,
+    decimals: HashMap<EthAddress, u64>,
+}
+
+impl FeeValuer {
+    pub fn new(
+        quote: QuoteAsset,
+        usd_prices: &HashMap<EthAddress, String>,
+        decimals: HashMap<EthAddress, u64>,
+    ) -> Self {
+        FeeValuer {
+            quote,
+            usd_prices: usd_prices
+                .iter()
+                .filter_map(|(token, price)| Some((*token, parse_decimal(price, VALUE_DECIMALS)?)))
+                .collect(),
+            decimals,
+        }
+    }
+
+    /// The value of `amount` base units of `token` in the quote asset with `VALUE_DECIMALS` decimals
+    pub fn value(&self, token: EthAddress, amount: &Uint256) -> Option<Uint256> {
+        let price = self.usd_prices.get(&token)?;
+        let decimals = *self.decimals.get(&token)?;
+        let usd = amount.clone() * price.clone() / pow10(decimals);
+        match self.quote {
+            QuoteAsset::Usd => Some(usd),
+            QuoteAsset::Eth => {
+                let eth_price = self.usd_prices.get(&eth_price_key())?;
+                if *eth_price == Uint256::from(0u8) {
+                    return None;
+                }
+                Some(usd * pow10(ETH_DECIMALS) / eth_price.clone())
+            }
+        }
+    }
+
+    /// The total value of amounts in different tokens, None if any of them has no value
+    pub fn total<'a>(
+        &self,
+        amounts: impl IntoIterator<Item = (EthAddress, &'a Uint256)>,
+    ) -> Option<Uint256> {
+        let mut total: Uint256 = 0u8.into();
+        for (token, amount) in amounts {
+            total = total + self.value(token, amount)?;
+        }
+        Some(total)
+    }
+
+    /// The value of an amount of ether in wei, for example the gas cost of a transaction
+    pub fn eth_value(&self, wei: &Uint256) -> Option<Uint256> {
+        match self.quote {
+            QuoteAsset::Eth => Some(wei.clone()),
+            QuoteAsset::Usd => {
+                let eth_price = self.usd_prices.get(&eth_price_key())?;
+                Some(wei.clone() * eth_price.clone() / pow10(ETH_DECIMALS))
+            }
+        }
+    }
+
+    pub fn format(&self, value: &Uint256) -> String {
+        format!("{} {}", format_amount(value, VALUE_DECIMALS), self.quote)
+    }
+}
+
+/// A price feed and the Hub's coin list, refreshed into a `FeeValuer` whenever fees need valuing
+pub struct FeeQuoter {
+    pub quote: QuoteAsset,
+    pub price_feed: Box<dyn PriceFeed>,
+    pub oracle_client: OracleQueryClient<Channel>,
+}
+
+impl FeeQuoter {
+    pub async fn valuer(&self) -> Result<FeeValuer, PeggyError> {
+        let prices = self.price_feed.usd_prices().await?;
+        let mut oracle_client = self.oracle_client.clone();
+        let decimals = get_token_decimals(&mut oracle_client).await?;
+        Ok(FeeValuer::new(self.quote, &prices, decimals))
+    }
+}
+
+/// The Ethereum decimals of every token in the Hub's coin list
+pub async fn get_token_decimals(
+    oracle_client: &mut OracleQueryClient<Channel>,
+) -> Result<HashMap<EthAddress, u64>, PeggyError> {
+    Ok(get_coins(oracle_client)
+        .await?
+        .into_iter()
+        .filter_map(|coin| Some((coin.eth_addr.parse().ok()?, coin.eth_decimals)))
+        .collect())
+}
+
+#[test]
+fn test_fee_value() {
+    let usdt = EthAddress::from_slice(&[0x11; 20]).unwrap();
+    let weth = EthAddress::from_slice(&[0x22; 20]).unwrap();
+    let unpriced = EthAddress::from_slice(&[0x33; 20]).unwrap();
+    let mut prices = HashMap::new();
+    prices.insert(usdt, "1".to_string());
+    prices.insert(weth, "2000".to_string());
+    prices.insert(eth_price_key(), "2000".to_string());
+    let mut decimals = HashMap::new();
+    decimals.insert(usdt, 6);
+    decimals.insert(weth, 18);
+    decimals.insert(unpriced, 18);
+
+    let usd = FeeValuer::new(QuoteAsset::Usd, &prices, decimals.clone());
+    let fee: Uint256 = 2_500_000u64.into();
+    let value = usd.value(usdt, &fee).unwrap();
+    assert_eq!(usd.format(&value), "2.5 USD");
+    let fee: Uint256 = 1_000_000_000_000_000u64.into();
+    assert_eq!(usd.format(&usd.value(weth, &fee).unwrap()), "2 USD");
+    assert_eq!(usd.value(unpriced, &fee), None);
+
+    // fees in different tokens add up in the quote asset
+    let eth = FeeValuer::new(QuoteAsset::Eth, &prices, decimals);
+    let usdt_fee: Uint256 = 4_000_000u64.into();
+    let total = eth.total(vec![(usdt, &usdt_fee), (weth, &fee)]).unwrap();
+    assert_eq!(eth.format(&total), "0.003 ETH");
+    assert_eq!(eth.total(vec![(usdt, &usdt_fee), (unpriced, &fee)]), None);
+
+    // gas costs are valued through the ETH price
+    let gas_cost: Uint256 = 500_000_000_000_000u64.into();
+    assert_eq!(usd.format(&usd.eth_value(&gas_cost).unwrap()), "1 USD");
+    assert_eq!(eth.format(&eth.eth_value(&gas_cost).unwrap()), "0.0005 ETH");
+
+    assert_eq!("eth".parse::<QuoteAsset>(), Ok(QuoteAsset::Eth));
+    assert!("btc".parse::<QuoteAsset>().is_err());
+}
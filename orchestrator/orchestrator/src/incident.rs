@@ -0,0 +1,354 @@
+//! Incident bundles keep the chain state around a production incident so it can be replayed against later
+//! code as a regression test. `orchestrator capture-incident` writes the Hub's pending valsets and batches with
+//! their confirms and the contract's valset, nonces and threshold to a json bundle while the incident is live.
+//! Rejections and the reorg depth can't be read back from the chains, they are added to the bundle by hand
+//! from the logs.
+//!
+//! Bundles checked in under `tests/incidents` are replayed by `test_replay_incidents`. A stuck batch replays
+//! the relayer's valset and batch decisions against the contract model and fails if anything the Hub has
+//! signed is left stuck or would revert, a sequence desync fails if any recorded rejection would halt the
+//! orchestrator and a reorg fails if the default confirmation depth doesn't cover it.
+
+use clarity::Address as EthAddress;
+use cosmos_peggy::query::{
+    get_all_valset_confirms, get_last_event_nonce, get_latest_transaction_batches,
+    get_latest_valsets, get_transaction_batch_signatures,
+};
+use deep_space::address::Address as CosmosAddress;
+use ethereum_peggy::contract_model::PeggyContractModel;
+use ethereum_peggy::utils::{
+    downcast_nonce, get_event_nonce, get_power_threshold, get_tx_batch_nonce,
+};
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::{PeggyError, RejectionAction, TxRejection};
+use peggy_utils::timings::Timings;
+use peggy_utils::types::{BatchConfirmResponse, TransactionBatch, Valset, ValsetConfirmResponse};
+use relayer::batch_relaying::batches_to_relay;
+use relayer::find_latest_valset::find_latest_valset;
+use relayer::valset_relaying::pick_valset_update;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentKind {
+    StuckBatch,
+    Reorg,
+    SequenceDesync,
+}
+
+impl FromStr for IncidentKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stuck-batch" => Ok(IncidentKind::StuckBatch),
+            "reorg" => Ok(IncidentKind::Reorg),
+            "sequence-desync" => Ok(IncidentKind::SequenceDesync),
+            _ => Err(format!(
+                "Invalid incident kind {}, expected stuck-batch, reorg or sequence-desync",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedValset {
+    pub valset: Valset,
+    pub confirms: Vec<ValsetConfirmResponse>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedBatch {
+    pub batch: TransactionBatch,
+    pub confirms: Vec<BatchConfirmResponse>,
+}
+
+/// A failed Hub tx as the logs show it
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct RecordedRejection {
+    pub codespace: String,
+    pub code: u32,
+    pub raw_log: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IncidentBundle {
+    pub name: String,
+    pub kind: IncidentKind,
+    /// unix time in seconds
+    pub captured_at: u64,
+    pub hub_valsets: Vec<SignedValset>,
+    pub hub_batches: Vec<SignedBatch>,
+    /// the last event nonce the Hub has from the orchestrator the bundle was captured for
+    #[serde(default)]
+    pub hub_event_nonce: Option<u64>,
+    pub ethereum_block: u64,
+    pub ethereum_valset: Valset,
+    /// the contract's last batch nonce for each token with a batch on the Hub
+    pub ethereum_batch_nonces: Vec<(EthAddress, u64)>,
+    pub ethereum_event_nonce: u64,
+    pub power_threshold: u64,
+    /// how many Ethereum blocks were reorganized, added by hand
+    #[serde(default)]
+    pub reorg_depth: u64,
+    /// the Hub's rejections of our txs during the incident, added by hand
+    #[serde(default)]
+    pub rejections: Vec<RecordedRejection>,
+}
+
+/// Reads the chain state an incident bundle keeps, `orchestrator` is the Cosmos address whose event nonce is
+/// recorded
+pub async fn capture_incident(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    web3: &Web3,
+    peggy_contract_address: EthAddress,
+    orchestrator: Option<CosmosAddress>,
+    name: String,
+    kind: IncidentKind,
+    captured_at: u64,
+) -> Result<IncidentBundle, PeggyError> {
+    let mut hub_valsets = Vec::new();
+    for valset in get_latest_valsets(grpc_client).await? {
+        let confirms = get_all_valset_confirms(grpc_client, valset.nonce).await?;
+        hub_valsets.push(SignedValset { valset, confirms });
+    }
+    let mut hub_batches = Vec::new();
+    let mut ethereum_batch_nonces: HashMap<EthAddress, u64> = HashMap::new();
+    for batch in get_latest_transaction_batches(grpc_client).await? {
+        let confirms =
+            get_transaction_batch_signatures(grpc_client, batch.nonce, batch.token_contract)
+                .await?;
+        if !ethereum_batch_nonces.contains_key(&batch.token_contract) {
+            let nonce = get_tx_batch_nonce(
+                peggy_contract_address,
+                batch.token_contract,
+                peggy_contract_address,
+                web3,
+            )
+            .await?;
+            ethereum_batch_nonces.insert(batch.token_contract, nonce);
+        }
+        hub_batches.push(SignedBatch { batch, confirms });
+    }
+    let hub_event_nonce = match orchestrator {
+        Some(orchestrator) => Some(get_last_event_nonce(grpc_client, orchestrator).await?),
+        None => None,
+    };
+
+    Ok(IncidentBundle {
+        name,
+        kind,
+        captured_at,
+        hub_valsets,
+        hub_batches,
+        hub_event_nonce,
+        ethereum_block: downcast_nonce(web3.eth_block_number().await?).unwrap_or_default(),
+        ethereum_valset: find_latest_valset(
+            grpc_client,
+            peggy_contract_address,
+            peggy_contract_address,
+            web3,
+        )
+        .await?,
+        ethereum_batch_nonces: ethereum_batch_nonces.into_iter().collect(),
+        ethereum_event_nonce: get_event_nonce(peggy_contract_address, web3).await?,
+        power_threshold: get_power_threshold(peggy_contract_address, web3).await?,
+        reorg_depth: 0,
+        rejections: Vec::new(),
+    })
+}
+
+pub fn load_bundle(path: &Path) -> Result<IncidentBundle, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid bundle {}: {}", path.display(), e))
+}
+
+/// Replays the bundle against the current code, returns what would still go wrong
+pub fn replay(bundle: &IncidentBundle) -> Vec<String> {
+    match bundle.kind {
+        IncidentKind::StuckBatch => replay_stuck_batch(bundle),
+        IncidentKind::SequenceDesync => bundle
+            .rejections
+            .iter()
+            .filter_map(|r| {
+                let rejection = TxRejection::from_tx_result(&r.codespace, r.code, &r.raw_log);
+                match rejection.action() {
+                    RejectionAction::Halt => Some(format!("{} halts the orchestrator", rejection)),
+                    _ => None,
+                }
+            })
+            .collect(),
+        IncidentKind::Reorg => {
+            let confirmations = Timings::default().eth_confirmations;
+            if confirmations > bundle.reorg_depth {
+                Vec::new()
+            } else {
+                vec![format!(
+                    "A reorg of {} blocks is deeper than the default {} confirmations",
+                    bundle.reorg_depth, confirmations
+                )]
+            }
+        }
+    }
+}
+
+/// Relays valset updates until the relayer picks none and then every batch it would relay, like relayer
+/// rounds would
+fn replay_stuck_batch(bundle: &IncidentBundle) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut contract =
+        PeggyContractModel::new(bundle.ethereum_valset.clone(), bundle.power_threshold);
+    contract.batch_nonces = bundle.ethereum_batch_nonces.iter().cloned().collect();
+
+    loop {
+        let current = contract.valset.clone();
+        let mut candidates: Vec<(Valset, Vec<ValsetConfirmResponse>)> = bundle
+            .hub_valsets
+            .iter()
+            .filter(|signed| signed.valset.nonce > current.nonce)
+            .map(|signed| (signed.valset.clone(), signed.confirms.clone()))
+            .collect();
+        candidates.sort_by(|a, b| b.0.nonce.cmp(&a.0.nonce));
+        let (valset, confirms, _) = match pick_valset_update(&current, candidates) {
+            Some(update) => update,
+            None => break,
+        };
+        if let Err(revert) = contract.update_valset(&valset, &current, &confirms) {
+            problems.push(format!("Valset {} reverts: {}", valset.nonce, revert));
+            break;
+        }
+    }
+
+    let batches: Vec<TransactionBatch> = bundle
+        .hub_batches
+        .iter()
+        .map(|signed| signed.batch.clone())
+        .collect();
+    let current = contract.valset.clone();
+    let nonces = contract.batch_nonces.clone();
+    for batch in batches_to_relay(batches, &nonces) {
+        let confirms = bundle
+            .hub_batches
+            .iter()
+            .find(|signed| signed.batch == batch)
+            .map(|signed| signed.confirms.clone())
+            .unwrap_or_default();
+        if let Err(revert) = contract.submit_batch(&batch, &current, &confirms) {
+            problems.push(format!(
+                "Batch {}:{} stays stuck: {}",
+                batch.token_contract, batch.nonce, revert
+            ));
+        }
+    }
+    problems
+}
+
+#[test]
+fn test_replay_incidents() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/incidents");
+    let mut replayed = 0;
+    for entry in fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map(|e| e != "json").unwrap_or(true) {
+            continue;
+        }
+        let bundle = load_bundle(&path).unwrap();
+        let problems = replay(&bundle);
+        assert!(
+            problems.is_empty(),
+            "{} ({}) is not resolved: {:?}",
+            bundle.name,
+            path.display(),
+            problems
+        );
+        replayed += 1;
+    }
+    assert!(replayed > 0);
+}
+
+#[test]
+fn test_replay_stuck_batch() {
+    use clarity::PrivateKey as EthPrivateKey;
+    use peggy_utils::types::{
+        ERC20Token, ValsetMember, ETHEREUM_POWER_THRESHOLD, TOTAL_PEGGY_POWER,
+    };
+
+    let address = |i: u8| EthAddress::from_slice(&[i; 20]).unwrap();
+    let valset = |nonce: u64, members: &[u8]| Valset {
+        nonce,
+        members: members
+            .iter()
+            .map(|i| ValsetMember {
+                power: TOTAL_PEGGY_POWER / members.len() as u64,
+                eth_address: Some(address(*i)),
+            })
+            .collect(),
+    };
+    let signature = EthPrivateKey::from_slice(&[1u8; 32])
+        .unwrap()
+        .sign_ethereum_msg(b"confirm");
+    let token = address(9);
+    let batch_confirm = |i: u8| BatchConfirmResponse {
+        nonce: 3,
+        orchestrator: CosmosAddress::from_bytes([i; 20]),
+        token_contract: token,
+        ethereum_signer: address(i),
+        eth_signature: signature.clone(),
+    };
+    let valset_confirm = |i: u8| ValsetConfirmResponse {
+        orchestrator: CosmosAddress::from_bytes([i; 20]),
+        eth_address: address(i),
+        nonce: 2,
+        eth_signature: signature.clone(),
+    };
+
+    // the batch is signed by the new valset, which is still waiting to be relayed, the old valset has only
+    // half of the power behind it
+    let mut bundle = IncidentBundle {
+        name: "batch signed by a valset not relayed yet".to_string(),
+        kind: IncidentKind::StuckBatch,
+        captured_at: 0,
+        hub_valsets: vec![SignedValset {
+            valset: valset(2, &[1, 2, 5, 6]),
+            confirms: [1, 2, 3].iter().map(|i| valset_confirm(*i)).collect(),
+        }],
+        hub_batches: vec![SignedBatch {
+            batch: TransactionBatch {
+                nonce: 3,
+                transactions: Vec::new(),
+                total_fee: ERC20Token {
+                    amount: 0u8.into(),
+                    token_contract_address: token,
+                },
+                token_contract: token,
+                block: 0,
+            },
+            confirms: [1, 2, 5, 6].iter().map(|i| batch_confirm(*i)).collect(),
+        }],
+        hub_event_nonce: None,
+        ethereum_block: 0,
+        ethereum_valset: valset(1, &[1, 2, 3, 4]),
+        ethereum_batch_nonces: vec![(token, 2)],
+        ethereum_event_nonce: 0,
+        power_threshold: ETHEREUM_POWER_THRESHOLD,
+        reorg_depth: 0,
+        rejections: Vec::new(),
+    };
+    let json = serde_json::to_string(&bundle).unwrap();
+    let bundle_back: IncidentBundle = serde_json::from_str(&json).unwrap();
+    assert_eq!(bundle_back.kind, IncidentKind::StuckBatch);
+    assert!(replay(&bundle_back).is_empty());
+
+    // without enough confirms for the valset update the batch stays stuck
+    bundle.hub_valsets[0].confirms.truncate(1);
+    let problems = replay(&bundle);
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("stays stuck"));
+}
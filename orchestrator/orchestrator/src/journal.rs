@@ -0,0 +1,515 @@
+//! The journal is an append only log of every submission the Hub has accepted from this orchestrator.
+//! Unlike the state store, which only keeps the latest progress, the journal keeps the full history so
+//! that after a crash we can check exactly what was already submitted. Each line is one json entry after a
+//! header line with the journal's schema version, see `state_schema`. With a state key every line is
+//! encrypted on its own and bound to the line before it, see `state_encryption`.
+
+use crate::build_info::BuildInfo;
+use crate::state_encryption::{is_encrypted, journal_line_aad, StateKey, PLAINTEXT_REFUSED};
+use crate::state_schema::{
+    check_nothing_dropped, migrate, JOURNAL_MIGRATIONS, JOURNAL_SCHEMA_VERSION,
+};
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::error::PeggyError;
+use relayer::main_loop::RelayedTx;
+use serde_json::Value;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const JOURNAL_FILE_NAME: &str = "journal.jsonl";
+/// How much of the end of the journal is read at first to find its last line
+const TAIL_LEN: u64 = 4096;
+
+/// The first line of the journal, entries are tagged with their type so the two can't be confused
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct JournalHeader {
+    schema_version: u32,
+}
+
+/// Which way value crossed the bridge, used for the statistics
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Direction {
+    /// a deposit on Ethereum to the Hub
+    ToHub,
+    /// a deposit on Ethereum straight to Minter
+    ToMinter,
+    /// a withdrawal batch executed on Ethereum
+    FromHub,
+}
+
+/// The value carrying part of a claimed event, batches only carry their nonce, their amounts are
+/// taken from our confirm for the same batch
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct ClaimedEvent {
+    pub direction: Direction,
+    pub erc20: EthAddress,
+    #[serde(default)]
+    pub amount: Uint256,
+    #[serde(default)]
+    pub batch_nonce: Option<u64>,
+    #[serde(flatten)]
+    pub metadata: TransferMetadata,
+    /// how many deposits to the same Minter address this entry stands for, see `fold_minter_deposits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folded: Option<u64>,
+}
+
+/// Accounting metadata for deposits, see `enrichment`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct TransferMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender: Option<EthAddress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender_label: Option<String>,
+    /// the Hub or Minter address the deposit went to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_label: Option<String>,
+    /// the token's USD price when we observed the event
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_price: Option<String>,
+}
+
+/// A transfer of a relayed batch to a destination that likely can't use the tokens, such as a burn address or
+/// a contract, see `relayer::destination_check`
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct RiskyTransfer {
+    pub transfer_id: u64,
+    pub destination: EthAddress,
+    pub risk: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+#[serde(tag = "type")]
+pub enum JournalEntry {
+    Claims {
+        event_nonces: Vec<u64>,
+        txhash: String,
+        /// entries written before statistics were added have no events and a zero timestamp
+        #[serde(default)]
+        events: Vec<ClaimedEvent>,
+        /// unix time in seconds the Hub accepted the claims
+        #[serde(default)]
+        timestamp: u64,
+    },
+    ValsetConfirm {
+        nonce: u64,
+        txhash: String,
+    },
+    BatchConfirm {
+        nonce: u64,
+        token_contract: EthAddress,
+        txhash: String,
+        #[serde(default)]
+        total_amount: Uint256,
+        #[serde(default)]
+        total_fee: Uint256,
+        #[serde(default)]
+        timestamp: u64,
+    },
+    /// an update our relayer sent to Ethereum, journaled once sent whether or not it was mined
+    ValsetRelay {
+        nonce: u64,
+        tx_hash: String,
+        timestamp: u64,
+    },
+    BatchRelay {
+        nonce: u64,
+        token_contract: EthAddress,
+        tx_hash: String,
+        timestamp: u64,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        risky_destinations: Vec<RiskyTransfer>,
+    },
+    /// written at every startup, so each submission can be traced back to the binary that made it
+    Started {
+        build: BuildInfo,
+        timestamp: u64,
+    },
+}
+
+impl JournalEntry {
+    pub fn relayed(tx: RelayedTx, timestamp: u64) -> Self {
+        match tx {
+            RelayedTx::Valset { nonce, tx_hash } => JournalEntry::ValsetRelay {
+                nonce,
+                tx_hash: format!("{:#066x}", tx_hash),
+                timestamp,
+            },
+            RelayedTx::Batch {
+                token_contract,
+                nonce,
+                tx_hash,
+                risky_destinations,
+            } => JournalEntry::BatchRelay {
+                nonce,
+                token_contract,
+                tx_hash: format!("{:#066x}", tx_hash),
+                timestamp,
+                risky_destinations: risky_destinations
+                    .into_iter()
+                    .map(|risky| RiskyTransfer {
+                        transfer_id: risky.transfer_id,
+                        destination: risky.destination,
+                        risk: risky.risk.name().to_string(),
+                    })
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Folds the deposits to the same Minter address and token into one entry with their total, for operators
+/// whose accounting tracks Minter destinations rather than single deposits. The entries keep the order their
+/// destinations first appear in, a sender is only kept when every folded deposit has it
+pub fn fold_minter_deposits(events: Vec<ClaimedEvent>) -> Vec<ClaimedEvent> {
+    let mut out: Vec<ClaimedEvent> = Vec::with_capacity(events.len());
+    for event in events {
+        let destination = match (&event.direction, &event.metadata.destination) {
+            (Direction::ToMinter, Some(destination)) => destination.to_lowercase(),
+            _ => {
+                out.push(event);
+                continue;
+            }
+        };
+        let folded_into = out.iter_mut().find(|e| {
+            e.direction == Direction::ToMinter
+                && e.erc20 == event.erc20
+                && e.metadata.destination.as_deref().map(str::to_lowercase)
+                    == Some(destination.clone())
+        });
+        match folded_into {
+            Some(entry) => {
+                entry.amount = entry.amount.clone() + event.amount;
+                entry.folded = Some(entry.folded.unwrap_or(1) + event.folded.unwrap_or(1));
+                if entry.metadata.sender != event.metadata.sender {
+                    entry.metadata.sender = None;
+                    entry.metadata.sender_label = None;
+                }
+            }
+            None => out.push(event),
+        }
+    }
+    out
+}
+
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+    key: Option<StateKey>,
+    /// reads lines written in plaintext before the key was given, only so `state migrate` can encrypt them
+    plaintext_allowed: bool,
+    /// held while writing, every encrypted line depends on the one before it
+    writing: Arc<Mutex<()>>,
+}
+
+impl Journal {
+    /// Opens the journal in the given directory, creating the directory if needed
+    pub fn open(dir: &Path) -> Result<Self, PeggyError> {
+        Self::open_with_key(dir, None)
+    }
+
+    /// Opens the journal, lines are encrypted with `key` if there is one and lines in plaintext are refused
+    pub fn open_with_key(dir: &Path, key: Option<StateKey>) -> Result<Self, PeggyError> {
+        Self::open_with(dir, key, false)
+    }
+
+    /// Opens the journal for `state migrate`, which also reads lines in plaintext to encrypt them with `key`
+    pub fn open_for_migration(dir: &Path, key: Option<StateKey>) -> Result<Self, PeggyError> {
+        Self::open_with(dir, key, true)
+    }
+
+    fn open_with(
+        dir: &Path,
+        key: Option<StateKey>,
+        plaintext_allowed: bool,
+    ) -> Result<Self, PeggyError> {
+        fs::create_dir_all(dir).map_err(|e| {
+            PeggyError::StateStoreError(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+        Ok(Journal {
+            path: dir.join(JOURNAL_FILE_NAME),
+            key,
+            plaintext_allowed,
+            writing: Arc::new(Mutex::new(())),
+        })
+    }
+
+    pub fn append(&self, entry: &JournalEntry) -> Result<(), PeggyError> {
+        let _writing = self.writing.lock().unwrap();
+        let empty = fs::metadata(&self.path)
+            .map(|m| m.len() == 0)
+            .unwrap_or(true);
+        let mut line = String::new();
+        let previous = if empty {
+            let header = self.seal(header_line(), None)?;
+            line = format!("{}\n", header);
+            Some(header)
+        } else if self.key.is_some() {
+            self.last_line()?
+        } else {
+            None
+        };
+        line.push_str(&self.seal(serde_json::to_string(entry).unwrap(), previous.as_deref())?);
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{}", line).and_then(|_| file.sync_data()))
+            .map_err(|e| {
+                PeggyError::StateStoreError(format!(
+                    "Failed to append to {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+
+    /// The schema version of the journal, a missing journal will be written at the current version
+    pub fn schema_version(&self) -> Result<u32, PeggyError> {
+        let contents = self.read()?;
+        Ok(match contents.lines().find(|l| !l.trim().is_empty()) {
+            Some(line) => parse_header(line).map(|h| h.schema_version).unwrap_or(0),
+            None => JOURNAL_SCHEMA_VERSION,
+        })
+    }
+
+    /// Reads back every entry in the order it was written, entries from an older orchestrator are
+    /// migrated, a journal from a newer one or entries with fields we don't know are refused
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, PeggyError> {
+        let contents = self.read()?;
+        let what = self.path.display().to_string();
+        let mut version = 0;
+        let mut entries = Vec::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(header) = parse_header(line) {
+                version = header.schema_version;
+                continue;
+            }
+            let corrupted = |e: String| {
+                PeggyError::StateStoreError(format!(
+                    "Corrupted journal entry on line {}: {}",
+                    i + 1,
+                    e
+                ))
+            };
+            let value: Value = serde_json::from_str(line).map_err(|e| corrupted(e.to_string()))?;
+            let value = migrate(
+                &what,
+                value,
+                version,
+                JOURNAL_SCHEMA_VERSION,
+                JOURNAL_MIGRATIONS,
+            )
+            .map_err(PeggyError::StateStoreError)?;
+            let entry: JournalEntry =
+                serde_json::from_value(value.clone()).map_err(|e| corrupted(e.to_string()))?;
+            check_nothing_dropped(
+                &format!("{} line {}", what, i + 1),
+                &value,
+                &serde_json::to_value(&entry).unwrap(),
+            )
+            .map_err(PeggyError::StateStoreError)?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Replaces the journal with the given entries at the current schema version, used by migrations
+    pub fn rewrite(&self, entries: &[JournalEntry]) -> Result<(), PeggyError> {
+        let _writing = self.writing.lock().unwrap();
+        let mut previous = self.seal(header_line(), None)?;
+        let mut contents = previous.clone();
+        for entry in entries {
+            let line = self.seal(serde_json::to_string(entry).unwrap(), Some(&previous))?;
+            contents.push('\n');
+            contents.push_str(&line);
+            previous = line;
+        }
+        contents.push('\n');
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, contents)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| {
+                PeggyError::StateStoreError(format!(
+                    "Failed to write {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+
+    /// Whether there is a key but lines written before it was given are in plaintext
+    pub fn needs_encrypting(&self) -> Result<bool, PeggyError> {
+        if self.key.is_none() || !self.path.exists() {
+            return Ok(false);
+        }
+        Ok(self
+            .read_raw()?
+            .lines()
+            .any(|line| !line.trim().is_empty() && !is_encrypted(line.as_bytes())))
+    }
+
+    /// The journal with every line decrypted
+    fn read(&self) -> Result<String, PeggyError> {
+        let raw = self.read_raw()?;
+        let mut contents = String::new();
+        let mut previous = None;
+        for (i, line) in raw.lines().enumerate() {
+            if line.trim().is_empty() {
+                contents.push('\n');
+                continue;
+            }
+            contents.push_str(&self.unseal(line, previous).map_err(|e| {
+                PeggyError::StateStoreError(format!(
+                    "Failed to read line {} of {}: {}",
+                    i + 1,
+                    self.path.display(),
+                    e
+                ))
+            })?);
+            contents.push('\n');
+            previous = Some(line);
+        }
+        Ok(contents)
+    }
+
+    /// The last line of the journal as written, read from the end so appending doesn't read all of it
+    fn last_line(&self) -> Result<Option<String>, PeggyError> {
+        let read_error = |e: std::io::Error| {
+            PeggyError::StateStoreError(format!("Failed to read {}: {}", self.path.display(), e))
+        };
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(read_error(e)),
+        };
+        let len = file.metadata().map_err(read_error)?.len();
+        let mut window = TAIL_LEN;
+        loop {
+            let start = len.saturating_sub(window);
+            let mut tail = Vec::new();
+            file.seek(SeekFrom::Start(start))
+                .and_then(|_| file.read_to_end(&mut tail))
+                .map_err(read_error)?;
+            let tail = String::from_utf8_lossy(&tail);
+            let tail = tail.trim_end();
+            match tail.rfind('\n') {
+                Some(newline) => return Ok(Some(tail[newline + 1..].to_string())),
+                // the last line starts before the window
+                None if start > 0 => window *= 2,
+                None if tail.is_empty() => return Ok(None),
+                None => return Ok(Some(tail.to_string())),
+            }
+        }
+    }
+
+    fn read_raw(&self) -> Result<String, PeggyError> {
+        if !self.path.exists() {
+            return Ok(String::new());
+        }
+        fs::read_to_string(&self.path).map_err(|e| {
+            PeggyError::StateStoreError(format!("Failed to read {}: {}", self.path.display(), e))
+        })
+    }
+
+    /// Encrypts a line bound to the `previous` line as written, None for the first line
+    fn seal(&self, line: String, previous: Option<&str>) -> Result<String, PeggyError> {
+        match self.key.as_ref() {
+            Some(key) => key
+                .encrypt(
+                    line.as_bytes(),
+                    &journal_line_aad(JOURNAL_FILE_NAME, previous),
+                )
+                .map_err(PeggyError::StateStoreError),
+            None => Ok(line),
+        }
+    }
+
+    /// Lines in plaintext are read as they are without a key, with one only to migrate them
+    fn unseal(&self, line: &str, previous: Option<&str>) -> Result<String, String> {
+        if !is_encrypted(line.as_bytes()) {
+            if self.key.is_some() && !self.plaintext_allowed {
+                return Err(PLAINTEXT_REFUSED.to_string());
+            }
+            return Ok(line.to_string());
+        }
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| "it is encrypted, --state-key is needed to read it".to_string())?;
+        let aad = journal_line_aad(JOURNAL_FILE_NAME, previous);
+        String::from_utf8(key.decrypt(line.as_bytes(), &aad)?)
+            .map_err(|_| "it doesn't decrypt to text".to_string())
+    }
+}
+
+fn header_line() -> String {
+    serde_json::to_string(&JournalHeader {
+        schema_version: JOURNAL_SCHEMA_VERSION,
+    })
+    .unwrap()
+}
+
+fn parse_header(line: &str) -> Option<JournalHeader> {
+    serde_json::from_str(line).ok()
+}
+
+#[test]
+fn test_fold_minter_deposits() {
+    let erc20: EthAddress = "0x0000000000000000000000000000000000000001"
+        .parse()
+        .unwrap();
+    let sender: EthAddress = "0x0000000000000000000000000000000000000002"
+        .parse()
+        .unwrap();
+    let event =
+        |direction: Direction, destination: &str, amount: u64, sender: EthAddress| ClaimedEvent {
+            direction,
+            erc20,
+            amount: amount.into(),
+            batch_nonce: None,
+            metadata: TransferMetadata {
+                sender: Some(sender),
+                destination: Some(destination.to_string()),
+                ..Default::default()
+            },
+            folded: None,
+        };
+    let a = "Mx0101010101010101010101010101010101010101";
+    let b = "Mx0202020202020202020202020202020202020202";
+    let folded = fold_minter_deposits(vec![
+        event(Direction::ToMinter, a, 10, sender),
+        event(Direction::ToHub, "hub1receiver", 1, sender),
+        event(Direction::ToMinter, b, 20, sender),
+        event(
+            Direction::ToMinter,
+            &a.to_uppercase().replacen("MX", "Mx", 1),
+            5,
+            sender,
+        ),
+        event(Direction::ToMinter, a, 7, EthAddress::default()),
+    ]);
+
+    assert_eq!(folded.len(), 3);
+    assert_eq!(folded[0].amount, 22u64.into());
+    assert_eq!(folded[0].folded, Some(3));
+    // the senders differ, so the entry can't name one
+    assert_eq!(folded[0].metadata.sender, None);
+    assert_eq!(folded[1].direction, Direction::ToHub);
+    assert_eq!(folded[2].folded, None);
+    assert_eq!(folded[2].metadata.sender, Some(sender));
+
+    // entries of older journals have no count and still parse
+    let json = serde_json::to_string(&folded[2]).unwrap();
+    assert!(!json.contains("folded"));
+    let parsed: ClaimedEvent = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, folded[2]);
+}
@@ -0,0 +1,469 @@
+//! Delegate key rotation. `orchestrator rotate-keys` generates new delegate keys, waits until our validator
+//! has no valset or batch confirms outstanding, registers the new keys on the Hub and waits for that to take
+//! effect. The new keys are handed to the running orchestrator through its state directory, it checks for
+//! them between loop iterations and switches over as soon as the Hub accepts them, so nothing is signed with
+//! a key the Hub no longer recognizes and no signing window is missed.
+//!
+//! Once switched the new keys are kept in the state directory and take precedence over the keys on the
+//! command line, so a restart before the operator updates their configuration doesn't revert the rotation.
+//! The keys are written encrypted with the `--state-key` (see `state_encryption`), rotate-keys refuses to run
+//! without one. Key files written in plaintext by an older orchestrator are refused until `state migrate`
+//! encrypts them.
+
+use crate::main_loop::LOOP_SPEED;
+use crate::state_encryption::{is_encrypted, StateKey, PLAINTEXT_REFUSED};
+use crate::state_store::LocalStorage;
+use clarity::{Address as EthAddress, PrivateKey as EthPrivateKey};
+use contact::client::Contact;
+use cosmos_peggy::query::{
+    get_last_event_nonce, get_oldest_unsigned_transaction_batch, get_oldest_unsigned_valset,
+};
+use cosmos_peggy::send::update_peggy_delegate_addresses;
+use cosmos_peggy::utils::check_tx_response;
+use deep_space::address::Address as CosmosAddress;
+use deep_space::coin::Coin;
+use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+use deep_space::utils::bytes_to_hex_str;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::coin_math::CoinMath;
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use rand::{thread_rng, Rng};
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+
+/// Keys generated by rotate-keys that the running orchestrator has not switched to yet
+pub const PENDING_KEYS_FILE: &str = "pending_keys.json";
+/// Keys the orchestrator has switched to, these override the keys given on the command line
+pub const ACTIVE_KEYS_FILE: &str = "active_keys.json";
+/// How long rotate-keys waits for the new keys to show up on the Hub
+pub const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Hex encoded delegate private keys as stored in the state directory
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct StoredKeys {
+    pub cosmos_key: String,
+    pub ethereum_key: String,
+}
+
+impl StoredKeys {
+    pub fn generate() -> Self {
+        let mut rng = thread_rng();
+        let cosmos_key: [u8; 32] = rng.gen();
+        let ethereum_key: [u8; 32] = rng.gen();
+        StoredKeys {
+            cosmos_key: bytes_to_hex_str(&cosmos_key),
+            ethereum_key: EthPrivateKey::from_slice(&ethereum_key)
+                .unwrap()
+                .to_string(),
+        }
+    }
+
+    pub fn keys(&self) -> Result<OrchestratorKeys, PeggyError> {
+        let cosmos_key: CosmosPrivateKey = self.cosmos_key.parse().map_err(|e| {
+            PeggyError::StateStoreError(format!("Invalid stored Cosmos key: {}", e))
+        })?;
+        let ethereum_key: EthPrivateKey = self.ethereum_key.parse()?;
+        Ok(OrchestratorKeys {
+            cosmos_key,
+            ethereum_key,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct OrchestratorKeys {
+    pub cosmos_key: CosmosPrivateKey,
+    pub ethereum_key: EthPrivateKey,
+}
+
+impl OrchestratorKeys {
+    pub fn cosmos_address(&self) -> CosmosAddress {
+        self.cosmos_key.to_public_key().unwrap().to_address()
+    }
+
+    pub fn ethereum_address(&self) -> EthAddress {
+        self.ethereum_key.to_public_key().unwrap()
+    }
+}
+
+/// The keys currently in use, shared between the orchestrator loops so a rotation
+/// is picked up by all of them
+#[derive(Debug, Clone)]
+pub struct KeyRing {
+    keys: Arc<RwLock<OrchestratorKeys>>,
+    relayer_key: Arc<RwLock<EthPrivateKey>>,
+    state_dir: Option<PathBuf>,
+}
+
+impl KeyRing {
+    pub fn new(keys: OrchestratorKeys, state_dir: Option<PathBuf>) -> Self {
+        KeyRing {
+            keys: Arc::new(RwLock::new(keys)),
+            relayer_key: Arc::new(RwLock::new(keys.ethereum_key)),
+            state_dir,
+        }
+    }
+
+    pub fn current(&self) -> OrchestratorKeys {
+        *self.keys.read().unwrap()
+    }
+
+    /// The key the relayer pays gas with, kept in step with our Ethereum delegate key
+    pub fn relayer_key(&self) -> Arc<RwLock<EthPrivateKey>> {
+        self.relayer_key.clone()
+    }
+
+    /// True while rotate-keys has generated keys we have not switched to yet, rejections
+    /// for our current keys are expected during this time
+    pub fn rotation_pending(&self) -> bool {
+        match &self.state_dir {
+            Some(dir) => dir.join(PENDING_KEYS_FILE).exists(),
+            None => false,
+        }
+    }
+
+    fn set(&self, keys: OrchestratorKeys) {
+        *self.keys.write().unwrap() = keys;
+        *self.relayer_key.write().unwrap() = keys.ethereum_key;
+    }
+}
+
+fn keys_error(path: &Path, action: &str, e: impl ToString) -> PeggyError {
+    PeggyError::StateStoreError(format!(
+        "Failed to {} {}: {}",
+        action,
+        path.display(),
+        e.to_string()
+    ))
+}
+
+/// The keys are bound to the name of their file, so pending keys can't be passed off as active ones
+fn keys_aad(path: &Path) -> Vec<u8> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// Writes keys encrypted with `key` and readable only by the current user, these are live signing keys
+pub fn write_keys(path: &Path, keys: &StoredKeys, key: &StateKey) -> Result<(), PeggyError> {
+    let sealed = key
+        .encrypt(
+            serde_json::to_string_pretty(keys).unwrap().as_bytes(),
+            &keys_aad(path),
+        )
+        .map_err(|e| keys_error(path, "encrypt", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&tmp_path)
+        .map_err(|e| keys_error(&tmp_path, "create", e))?;
+    file.write_all(sealed.as_bytes())
+        .and_then(|_| file.sync_all())
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|e| keys_error(path, "write", e))
+}
+
+/// Reads keys written by `write_keys`. Keys an older orchestrator wrote in plaintext are refused with a key
+/// unless `plaintext_allowed`, which only `state migrate` sets to encrypt them
+pub fn read_keys(
+    path: &Path,
+    key: Option<&StateKey>,
+    plaintext_allowed: bool,
+) -> Result<Option<StoredKeys>, PeggyError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut contents = fs::read(path).map_err(|e| keys_error(path, "read", e))?;
+    if is_encrypted(&contents) {
+        let key = key.ok_or_else(|| {
+            keys_error(
+                path,
+                "read",
+                "it is encrypted, --state-key is needed to read it",
+            )
+        })?;
+        contents = key
+            .decrypt(&contents, &keys_aad(path))
+            .map_err(|e| keys_error(path, "decrypt", e))?;
+    } else if key.is_some() && !plaintext_allowed {
+        return Err(keys_error(path, "read", PLAINTEXT_REFUSED));
+    }
+    serde_json::from_slice(&contents)
+        .map(Some)
+        .map_err(|e| keys_error(path, "parse", e))
+}
+
+/// Whether there is a key but the keys at `path` were written in plaintext
+pub fn keys_need_encrypting(path: &Path, key: Option<&StateKey>) -> Result<bool, PeggyError> {
+    if key.is_none() || !path.exists() {
+        return Ok(false);
+    }
+    let contents = fs::read(path).map_err(|e| keys_error(path, "read", e))?;
+    Ok(!is_encrypted(&contents))
+}
+
+/// Returns the keys a previous rotation switched to, if any
+pub fn load_active_keys(
+    dir: &Path,
+    key: Option<&StateKey>,
+) -> Result<Option<OrchestratorKeys>, PeggyError> {
+    match read_keys(&dir.join(ACTIVE_KEYS_FILE), key, false)? {
+        Some(keys) => Ok(Some(keys.keys()?)),
+        None => Ok(None),
+    }
+}
+
+/// Returns true once the Hub maps this orchestrator address to a validator
+pub async fn is_registered(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    address: CosmosAddress,
+) -> bool {
+    get_last_event_nonce(grpc_client, address).await.is_ok()
+}
+
+/// Waits until the given orchestrator address has nothing left to sign, so switching keys
+/// can't strand a confirm halfway
+async fn wait_for_no_pending_confirms(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    address: CosmosAddress,
+) -> Result<(), PeggyError> {
+    loop {
+        let valset = get_oldest_unsigned_valset(grpc_client, address).await?;
+        let batch = get_oldest_unsigned_transaction_batch(grpc_client, address).await?;
+        match (valset, batch) {
+            (None, None) => return Ok(()),
+            (valset, batch) => info!(
+                "Waiting for {} to confirm valset {:?} and batch {:?} before rotating",
+                address,
+                valset.map(|v| v.nonce),
+                batch.map(|b| b.nonce)
+            ),
+        }
+        delay_for(LOOP_SPEED).await;
+    }
+}
+
+/// Waits until the new Cosmos delegate address can pay for claims and confirms
+async fn wait_for_funding(contact: &Contact, address: CosmosAddress, fee: &Coin) {
+    loop {
+        if let Ok(balances) = contact.get_balances(address).await {
+            if balances
+                .result
+                .iter()
+                .any(|c| fee.fits_in(c).unwrap_or(false))
+            {
+                return;
+            }
+        }
+        info!(
+            "Waiting for {} to be funded with {} to pay Hub fees",
+            address, fee.denom
+        );
+        delay_for(LOOP_SPEED).await;
+    }
+}
+
+/// The rotate-keys workflow, resumes an interrupted rotation if pending keys already exist
+pub async fn rotate_keys(
+    contact: &Contact,
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    validator_key: CosmosPrivateKey,
+    fee: Coin,
+    storage: &LocalStorage,
+) -> Result<OrchestratorKeys, PeggyError> {
+    let key = storage.key.as_ref().ok_or_else(|| {
+        PeggyError::InvalidOptionsError(
+            "rotate-keys requires --state-key, the new delegate keys are stored encrypted"
+                .to_string(),
+        )
+    })?;
+    let current_address: CosmosAddress = match storage.state.load()?.orchestrator_address {
+        Some(address) => address.parse()?,
+        None => {
+            return Err(PeggyError::StateStoreError(
+                "No orchestrator address in the state store, run the orchestrator with this state dir first"
+                    .to_string(),
+            ))
+        }
+    };
+
+    let pending_path = storage.dir.join(PENDING_KEYS_FILE);
+    // the keys are written out before anything else happens so they can't be lost
+    let stored = match read_keys(&pending_path, Some(key), false)? {
+        Some(stored) => {
+            info!("Resuming the key rotation from {}", pending_path.display());
+            stored
+        }
+        None => {
+            let stored = StoredKeys::generate();
+            write_keys(&pending_path, &stored, key)?;
+            stored
+        }
+    };
+    let new_keys = stored.keys()?;
+    let new_address = new_keys.cosmos_address();
+    info!(
+        "Rotating to Cosmos address {} Ethereum address {}",
+        new_address,
+        new_keys.ethereum_address()
+    );
+
+    if !is_registered(grpc_client, new_address).await {
+        wait_for_funding(contact, new_address, &fee).await;
+        wait_for_no_pending_confirms(grpc_client, current_address).await?;
+        let res = update_peggy_delegate_addresses(
+            contact,
+            new_keys.ethereum_address(),
+            new_address,
+            validator_key,
+            fee,
+        )
+        .await?;
+        let res = check_tx_response(res)?;
+        info!(
+            "Submitted the new delegate keys with txid {}",
+            explorer::tx(Chain::Hub, &res.txhash)
+        );
+    }
+
+    let start = Instant::now();
+    while !is_registered(grpc_client, new_address).await {
+        if Instant::now() - start > REGISTRATION_TIMEOUT {
+            return Err(PeggyError::InvalidBridgeStateError(format!(
+                "New delegate keys did not take effect after {:?}, run rotate-keys again to resume",
+                REGISTRATION_TIMEOUT
+            )));
+        }
+        delay_for(Duration::from_secs(5)).await;
+    }
+    Ok(new_keys)
+}
+
+/// Runs alongside the orchestrator loops and switches the key ring over once the keys
+/// generated by rotate-keys are accepted by the Hub
+pub async fn watch_for_rotation(
+    keys: KeyRing,
+    grpc_client: PeggyQueryClient<Channel>,
+    storage: LocalStorage,
+) {
+    let mut grpc_client = grpc_client;
+    let pending_path = storage.dir.join(PENDING_KEYS_FILE);
+    let active_path = storage.dir.join(ACTIVE_KEYS_FILE);
+    loop {
+        delay_for(LOOP_SPEED).await;
+
+        let new_keys =
+            match read_keys(&pending_path, storage.key.as_ref(), false).and_then(|k| match k {
+                Some(stored) => stored.keys().map(|keys| Some((stored, keys))),
+                None => Ok(None),
+            }) {
+                Ok(Some(keys)) => keys,
+                Ok(None) => continue,
+                Err(e) => {
+                    error!("Failed to read pending delegate keys: {}", e);
+                    continue;
+                }
+            };
+        let (stored, new_keys) = new_keys;
+        if !is_registered(&mut grpc_client, new_keys.cosmos_address()).await {
+            continue;
+        }
+
+        let old_keys = keys.current();
+        let key = match storage.key.as_ref() {
+            Some(key) => key,
+            None => {
+                error!(
+                    "The rotated delegate keys can't be stored without --state-key, not switching"
+                );
+                continue;
+            }
+        };
+        if let Err(e) = write_keys(&active_path, &stored, key) {
+            error!(
+                "Failed to store the rotated delegate keys, not switching: {}",
+                e
+            );
+            continue;
+        }
+        keys.set(new_keys);
+        if let Err(e) = fs::remove_file(&pending_path) {
+            error!("Failed to remove {}: {}", pending_path.display(), e);
+        }
+        let new_address = new_keys.cosmos_address().to_string();
+        if let Err(e) = storage
+            .state
+            .update(|state| state.orchestrator_address = Some(new_address))
+        {
+            error!("Failed to update state store: {}", e);
+        }
+        warn!(
+            "Switched to delegate keys {} / {}, the old keys {} / {} are retired and should be removed from your configuration",
+            new_keys.cosmos_address(),
+            new_keys.ethereum_address(),
+            old_keys.cosmos_address(),
+            old_keys.ethereum_address()
+        );
+    }
+}
+
+#[test]
+fn test_stored_keys_roundtrip() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("peggy-keys-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(PENDING_KEYS_FILE);
+    let key = StateKey::from_secret(&"5a".repeat(32)).unwrap();
+
+    let stored = StoredKeys::generate();
+    write_keys(&path, &stored, &key).unwrap();
+    assert_eq!(
+        read_keys(&path, Some(&key), false).unwrap(),
+        Some(stored.clone())
+    );
+    assert_eq!(
+        fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+        0o600
+    );
+    // the keys never touch the disk in plaintext
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(is_encrypted(contents.as_bytes()));
+    assert!(!contents.contains(&stored.cosmos_key));
+    assert!(read_keys(&path, None, false).is_err());
+    let wrong = StateKey::from_secret(&"a5".repeat(32)).unwrap();
+    assert!(read_keys(&path, Some(&wrong), false).is_err());
+    // pending keys can't be passed off as active ones
+    let active = dir.join(ACTIVE_KEYS_FILE);
+    fs::copy(&path, &active).unwrap();
+    assert!(read_keys(&active, Some(&key), false).is_err());
+    assert!(!keys_need_encrypting(&path, Some(&key)).unwrap());
+    // the same keys come out every time they are parsed
+    assert_eq!(stored.keys().unwrap(), stored.keys().unwrap());
+    assert!(read_keys(&dir.join("missing.json"), Some(&key), false)
+        .unwrap()
+        .is_none());
+
+    // keys an older orchestrator wrote in plaintext are only read with a key to migrate them
+    fs::write(&path, serde_json::to_string(&stored).unwrap()).unwrap();
+    assert!(read_keys(&path, Some(&key), false).is_err());
+    assert_eq!(
+        read_keys(&path, Some(&key), true).unwrap(),
+        Some(stored.clone())
+    );
+    assert_eq!(read_keys(&path, None, false).unwrap(), Some(stored));
+    assert!(keys_need_encrypting(&path, Some(&key)).unwrap());
+    assert!(!keys_need_encrypting(&path, None).unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -1,6 +1,57 @@
 #[macro_use]
+extern crate serde_derive;
+#[macro_use]
+extern crate lazy_static;
+#[macro_use]
 extern crate log;
 
+pub mod admin;
+pub mod anomaly;
+pub mod build_info;
+pub mod claim_batching;
+pub mod claim_delay;
+pub mod claim_resubmission;
+pub mod config_lint;
+pub mod confirmation;
+pub mod deployment_block;
+pub mod deposit_finality;
+pub mod downtime;
+pub mod enrichment;
+pub mod ethereum_event_verifier;
 pub mod ethereum_event_watcher;
+pub mod explain_msg;
+pub mod faucet;
+pub mod fee_payers;
+pub mod fee_top_up;
+pub mod fee_value;
+pub mod incident;
+pub mod journal;
+pub mod key_rotation;
+pub mod log_levels;
 pub mod main_loop;
+pub mod multi_chain;
 pub mod oracle_resync;
+pub mod output;
+pub mod param_watch;
+pub mod pause;
+pub mod preview_batch;
+pub mod profiles;
+pub mod register;
+pub mod relay_receipts;
+pub mod restart_check;
+pub mod runtime;
+pub mod signing_guard;
+pub mod signing_report;
+pub mod solvency;
+pub mod stalled_transfers;
+pub mod standby;
+pub mod state_encryption;
+pub mod state_schema;
+pub mod state_store;
+pub mod stats;
+pub mod status_api;
+pub mod supply_caps;
+pub mod tui;
+pub mod verify_batch;
+pub mod verify_execution;
+pub mod version;
@@ -0,0 +1,226 @@
+//! Log levels per subsystem that can be changed while the orchestrator runs. `RUST_LOG` sets the levels at
+//! startup as usual, `orchestrator admin log-level` then raises or lowers the level of one subsystem through the
+//! admin file, for example to trace the relayer during an incident without restarting or tracing everything
+//! else. Setting a subsystem back to `default` hands it back to `RUST_LOG`. The levels are process wide, with
+//! `orchestrator multi` they apply to every chain. The Minter scanner runs in mhub-minter-connector and keeps
+//! its own logger.
+
+use env_logger::filter::Filter;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+/// Log target of the oracle loop, whose logs would otherwise share `orchestrator::main_loop` with the signer
+pub const ETH_SCANNER_TARGET: &str = "orchestrator::eth_scanner";
+/// Log target of the signer loop
+pub const SIGNER_TARGET: &str = "orchestrator::signer";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Subsystem {
+    EthScanner,
+    Signer,
+    Relayer,
+    CosmosBroadcast,
+}
+
+pub const SUBSYSTEMS: [Subsystem; 4] = [
+    Subsystem::EthScanner,
+    Subsystem::Signer,
+    Subsystem::Relayer,
+    Subsystem::CosmosBroadcast,
+];
+
+impl Subsystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Subsystem::EthScanner => "eth-scanner",
+            Subsystem::Signer => "signer",
+            Subsystem::Relayer => "relayer",
+            Subsystem::CosmosBroadcast => "cosmos-broadcast",
+        }
+    }
+
+    /// The log targets, module paths unless a log call names its own, the subsystem logs under
+    pub fn targets(&self) -> &'static [&'static str] {
+        match self {
+            Subsystem::EthScanner => &[
+                ETH_SCANNER_TARGET,
+                "orchestrator::ethereum_event_watcher",
+                "orchestrator::ethereum_event_verifier",
+                "orchestrator::oracle_resync",
+            ],
+            Subsystem::Signer => &[SIGNER_TARGET, "cosmos_peggy::eth_signer"],
+            Subsystem::Relayer => &["relayer", "ethereum_peggy"],
+            Subsystem::CosmosBroadcast => &[
+                "cosmos_peggy::send",
+                "cosmos_peggy::messages",
+                "contact",
+                "orchestrator::claim_batching",
+                "orchestrator::fee_payers",
+            ],
+        }
+    }
+
+    fn logs_under(&self, target: &str) -> bool {
+        self.targets().iter().any(|prefix| {
+            target == *prefix
+                || (target.starts_with(prefix) && target[prefix.len()..].starts_with("::"))
+        })
+    }
+}
+
+impl fmt::Display for Subsystem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Subsystem {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SUBSYSTEMS
+            .iter()
+            .find(|subsystem| subsystem.name() == s.trim())
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = SUBSYSTEMS.iter().map(|s| s.name()).collect();
+                format!(
+                    "Unknown subsystem {}, expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// A level for `admin log-level`, None is `default`, back to `RUST_LOG`
+pub fn parse_level(level: &str) -> Result<Option<LevelFilter>, String> {
+    match level.trim() {
+        "default" => Ok(None),
+        level => level.parse().map(Some).map_err(|_| {
+            format!(
+                "Unknown log level {}, expected off, error, warn, info, debug, trace or default",
+                level
+            )
+        }),
+    }
+}
+
+struct Levels {
+    /// the most verbose level `RUST_LOG` lets through anywhere
+    default: LevelFilter,
+    overrides: BTreeMap<Subsystem, LevelFilter>,
+}
+
+lazy_static! {
+    static ref LEVELS: RwLock<Levels> = RwLock::new(Levels {
+        default: LevelFilter::Error,
+        overrides: BTreeMap::new(),
+    });
+}
+
+fn level_override(target: &str) -> Option<LevelFilter> {
+    let levels = LEVELS.read().unwrap();
+    levels
+        .overrides
+        .iter()
+        .find(|(subsystem, _)| subsystem.logs_under(target))
+        .map(|(_, level)| *level)
+}
+
+/// Applies `RUST_LOG` to everything but the subsystems with a level of their own
+struct RuntimeLogger {
+    /// formats and writes whatever passes the levels
+    writer: env_logger::Logger,
+    filter: Filter,
+}
+
+impl Log for RuntimeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        match level_override(metadata.target()) {
+            Some(level) => metadata.level() <= level,
+            None => self.filter.enabled(metadata),
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.writer.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.writer.flush();
+    }
+}
+
+/// Installs the logger, in place of `env_logger::init`
+pub fn init() {
+    let filter = env_logger::filter::Builder::from_env("RUST_LOG").build();
+    let writer = env_logger::Builder::new()
+        .filter_level(LevelFilter::Trace)
+        .build();
+    LEVELS.write().unwrap().default = filter.filter();
+    log::set_max_level(filter.filter());
+    log::set_boxed_logger(Box::new(RuntimeLogger { writer, filter }))
+        .expect("Failed to install the logger");
+}
+
+/// Replaces the subsystem levels, subsystems not in `overrides` go back to `RUST_LOG`
+pub fn set_levels(overrides: &BTreeMap<Subsystem, LevelFilter>) {
+    let changes: Vec<String> = {
+        let mut levels = LEVELS.write().unwrap();
+        let changes = SUBSYSTEMS
+            .iter()
+            .filter(|subsystem| levels.overrides.get(*subsystem) != overrides.get(*subsystem))
+            .map(|subsystem| match overrides.get(subsystem) {
+                Some(level) => format!("Logging {} at level {}", subsystem, level),
+                None => format!("Logging {} as set by RUST_LOG", subsystem),
+            })
+            .collect();
+        levels.overrides = overrides.clone();
+        let max = overrides
+            .values()
+            .copied()
+            .fold(levels.default, std::cmp::max);
+        log::set_max_level(max);
+        changes
+    };
+    // the logger reads the levels, so only log once the lock is released
+    for change in changes {
+        info!("{}", change);
+    }
+}
+
+#[test]
+fn test_subsystems() {
+    assert!(Subsystem::Relayer.logs_under("relayer::batch_relaying"));
+    assert!(Subsystem::Relayer.logs_under("ethereum_peggy"));
+    assert!(!Subsystem::Relayer.logs_under("relayers"));
+    assert!(Subsystem::EthScanner.logs_under(ETH_SCANNER_TARGET));
+    assert!(!Subsystem::Signer.logs_under("orchestrator::main_loop"));
+    assert!(Subsystem::CosmosBroadcast.logs_under("contact::client::send"));
+
+    for subsystem in SUBSYSTEMS.iter() {
+        assert_eq!(subsystem.name().parse::<Subsystem>(), Ok(*subsystem));
+    }
+    assert!("minter-scanner".parse::<Subsystem>().is_err());
+    assert_eq!(parse_level("TRACE"), Ok(Some(LevelFilter::Trace)));
+    assert_eq!(parse_level("default"), Ok(None));
+    assert!(parse_level("loud").is_err());
+
+    // an override makes the subsystem as verbose as asked, whatever RUST_LOG says
+    let mut overrides = BTreeMap::new();
+    overrides.insert(Subsystem::Relayer, LevelFilter::Trace);
+    set_levels(&overrides);
+    assert_eq!(
+        level_override("relayer::main_loop"),
+        Some(LevelFilter::Trace)
+    );
+    assert_eq!(level_override("orchestrator::main_loop"), None);
+    set_levels(&BTreeMap::new());
+    assert_eq!(level_override("relayer::main_loop"), None);
+}
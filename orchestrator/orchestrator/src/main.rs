@@ -16,18 +16,139 @@ extern crate lazy_static;
 #[macro_use]
 extern crate log;
 
+mod admin;
+mod anomaly;
+mod build_info;
+mod claim_batching;
+mod claim_delay;
+mod claim_resubmission;
+mod config_lint;
+mod confirmation;
+mod deployment_block;
+mod deposit_finality;
+mod downtime;
+mod enrichment;
+mod ethereum_event_verifier;
 mod ethereum_event_watcher;
+mod explain_msg;
+mod faucet;
+mod fee_payers;
+mod fee_top_up;
+mod fee_value;
+mod incident;
+mod journal;
+mod key_rotation;
+mod log_levels;
 mod main_loop;
+mod multi_chain;
 mod oracle_resync;
+mod output;
+mod param_watch;
+mod pause;
+mod preview_batch;
+mod profiles;
+mod register;
+mod relay_receipts;
+mod restart_check;
+mod runtime;
+mod signing_guard;
+mod signing_report;
+mod solvency;
+mod stalled_transfers;
+mod standby;
+mod state_encryption;
+mod state_schema;
+mod state_store;
+mod stats;
+mod status_api;
+mod supply_caps;
+mod tui;
+mod verify_batch;
+mod verify_execution;
+mod version;
 
+use crate::admin::{submit_admin_command, AdminCommand, SkippedBatch};
+use crate::anomaly::AnomalyDetector;
+use crate::build_info::BuildInfo;
+use crate::claim_batching::ClaimBatching;
+use crate::claim_delay::{unix_now, ClaimDelay};
+use crate::claim_resubmission::ClaimResubmission;
+use crate::config_lint::lint_config_file;
+use crate::deployment_block::{find_deployment_block, DeploymentBlock};
+use crate::deposit_finality::DepositFinality;
+use crate::enrichment::{connect_oracle, new_price_feed, Enrichment};
+use crate::explain_msg::{
+    check_signature, explain, explanation_view, format_explanation, parse_msgs,
+};
+use crate::faucet::{check_devnet, fund_ethereum, fund_hub, fund_minter, FaucetConfig};
+use crate::fee_payers::FeePayers;
+use crate::fee_top_up::FeeTopUp;
+use crate::fee_value::{get_token_decimals, FeeQuoter, QuoteAsset};
+use crate::incident::{capture_incident, IncidentKind};
+use crate::journal::JournalEntry;
+use crate::key_rotation::{load_active_keys, rotate_keys, KeyRing, OrchestratorKeys};
+use crate::log_levels::parse_level;
 use crate::main_loop::orchestrator_main_loop;
 use crate::main_loop::LOOP_SPEED;
+use crate::multi_chain::{load_chain_configs, ChainConfig};
+use crate::output::{exit_unless, OutputFormat, EXIT_CHECK_FAILED};
+use crate::preview_batch::{
+    format_preview, hub_check, parse_denom, preview_batch, preview_view, DEFAULT_BATCH_SIZE,
+};
+use crate::profiles::{resolve_profile, Profile};
+use crate::register::{
+    can_pay_fee, find_conflicts, unsigned_registration_tx, wait_for_registration,
+};
+use crate::restart_check::{reconcile_restart, verify_restart};
+use crate::runtime::{assign_chains, watch_event_loop, TaskQueues};
+use crate::solvency::MinterMultisig;
+use crate::stalled_transfers::get_outgoing_pool;
+use crate::standby::wait_for_takeover;
+use crate::state_encryption::StateKey;
+use crate::state_schema::{migrate_storage, plan_migrations};
+use crate::state_store::LocalStorage;
+use crate::stats::{compute_stats, list_transfers, to_csv, transfers_to_csv};
+use crate::status_api::{run_status_api, tls_acceptor, ApiTokens, StatusApi, DEFAULT_RATE_LIMIT};
+use crate::supply_caps::SupplyCaps;
+use crate::tui::run_tui;
+use crate::verify_batch::{format_verification, verify_batch};
+use crate::verify_execution::{format_execution, parse_tx_hash, verify_execution};
+use actix_rt::System;
 use clarity::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
 use contact::client::Contact;
+use cosmos_peggy::eth_signer::{EthSigner, ThresholdSigner};
+use cosmos_peggy::messages::{DecodedMsg, UnknownMsgPolicy};
+use cosmos_peggy::query::get_current_valset;
+use cosmos_peggy::send::{set_tx_memo, update_peggy_delegate_addresses};
+use cosmos_peggy::utils::check_tx_response;
+use deep_space::address::Address as CosmosAddress;
+use deep_space::coin::Coin;
+use deep_space::mnemonic::Mnemonic;
 use deep_space::private_key::PrivateKey as CosmosPrivateKey;
 use docopt::Docopt;
+use ethereum_peggy::abi_drift::check_contract_abi;
+use ethereum_peggy::utils::{downcast_nonce, get_peggy_id, get_peggy_id_string};
+use futures::future::{join, join_all};
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::address_book::AddressBook;
+use peggy_utils::endpoints::{parse_urls, Endpoints, Purpose};
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{set_explorer, Chain};
+use peggy_utils::gas_price::DEFAULT_GAS_PRICE_WINDOW;
+use peggy_utils::redact::set_log_sensitive;
+use peggy_utils::secrets::resolve_secret;
+use peggy_utils::timings::Timings;
+use peggy_utils::types::SignatureSelection;
+use rand::{thread_rng, Rng};
+use relayer::batch_relaying::{parse_skipped_batches, parse_tokens};
+use relayer::main_loop::RelayerControls;
+use relayer::sponsorship::Sponsors;
+use relayer::valset_relaying::parse_overlap_percent;
+use std::io::Read;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
 use url::Url;
 use web30::client::Web3;
 
@@ -37,29 +158,340 @@ struct Args {
     flag_ethereum_key: String,
     flag_cosmos_legacy_rpc: String,
     flag_cosmos_grpc: String,
+    flag_cosmos_backup_rpc: Option<String>,
     flag_ethereum_rpc: String,
+    flag_ethereum_verify_rpc: Option<String>,
+    flag_ethereum_backfill_rpc: Option<String>,
     flag_contract_address: String,
     flag_fees: String,
+    flag_profile: Option<String>,
+    flag_state_dir: Option<String>,
+    flag_state_key: Option<String>,
+    flag_verify_restart: bool,
+    flag_log_sensitive: bool,
+    flag_hub_explorer: Option<String>,
+    flag_ethereum_explorer: Option<String>,
+    flag_ethereum_address: Option<String>,
+    flag_validator_phrase: String,
+    flag_fee_payer_phrase: Option<String>,
+    flag_fee_payer_for: Option<String>,
+    flag_supply_caps: Option<String>,
+    flag_deposit_finality: Option<String>,
+    flag_delay_claims_above: Option<String>,
+    flag_claim_delay: Option<String>,
+    flag_claim_batch_window: Option<String>,
+    flag_claim_batch_size: Option<String>,
+    flag_claim_ttl: Option<String>,
+    flag_claim_retries: Option<String>,
+    flag_detect_anomalies: bool,
+    flag_anomaly_delay: bool,
+    flag_stalled_transfer_alert: Option<String>,
+    flag_top_up_fees: Option<String>,
+    flag_top_up_percent: Option<String>,
+    flag_watch_solvency: bool,
+    flag_minter_api: Option<String>,
+    flag_minter_multisig: Option<String>,
+    flag_skip_batches: Option<String>,
+    flag_disable_tokens: Option<String>,
+    flag_sponsors: Option<String>,
+    flag_signatures: Option<String>,
+    flag_force_valset_relay_below: Option<String>,
+    flag_maintenance_window: Option<String>,
+    flag_enforce_min_version: bool,
+    flag_relay_receipts: bool,
+    flag_task_queues: Option<String>,
+    flag_timings: Option<String>,
+    flag_threshold_signer: Option<String>,
+    flag_standby: Option<String>,
+    flag_address_labels: Option<String>,
+    flag_price_feed: Option<String>,
+    flag_fold_minter_deposits: bool,
+    flag_quote: Option<String>,
+    flag_gas_price_window: Option<String>,
+    cmd_tui: bool,
+    cmd_status_api: bool,
+    flag_bind: Option<String>,
+    flag_public: bool,
+    flag_rate_limit: Option<String>,
+    flag_api_tokens: Option<String>,
+    flag_tls_cert: Option<String>,
+    flag_tls_key: Option<String>,
+    cmd_register: bool,
+    flag_validator_address: Option<String>,
+    flag_force: bool,
+    flag_offline: bool,
+    flag_faucet: Option<String>,
+    cmd_rotate_keys: bool,
+    cmd_stats: bool,
+    flag_format: Option<String>,
+    flag_output: Option<String>,
+    flag_transfers: bool,
+    cmd_state: bool,
+    cmd_migrate: bool,
+    flag_check: bool,
+    cmd_admin: bool,
+    cmd_pause_relaying: bool,
+    cmd_resume_relaying: bool,
+    cmd_skip_batch: bool,
+    cmd_disable_token: bool,
+    cmd_enable_token: bool,
+    cmd_rescan: bool,
+    cmd_release_claim: bool,
+    cmd_log_level: bool,
+    flag_subsystem: Option<String>,
+    flag_level: Option<String>,
+    flag_token: Option<String>,
+    flag_batch_nonce: Option<String>,
+    flag_from_block: Option<String>,
+    flag_event_nonce: Option<String>,
+    flag_peggy_id: Option<String>,
+    flag_deployment_block: Option<String>,
+    cmd_verify_batch: bool,
+    flag_nonce: Option<String>,
+    cmd_verify_execution: bool,
+    flag_txhash: Option<String>,
+    flag_hub_height: Option<String>,
+    cmd_capture_incident: bool,
+    flag_kind: Option<String>,
+    flag_out: Option<String>,
+    flag_name: Option<String>,
+    flag_orchestrator: Option<String>,
+    cmd_explain_msg: bool,
+    flag_msg: Option<String>,
+    flag_unknown_msgs: Option<String>,
+    cmd_faucet: bool,
+    flag_hub_address: Option<String>,
+    flag_minter_address: Option<String>,
+    cmd_preview_batch: bool,
+    flag_denom: Option<String>,
+    flag_batch_size: Option<String>,
+    cmd_multi: bool,
+    cmd_config: bool,
+    cmd_lint: bool,
+    flag_chains: Option<String>,
+    flag_worker_threads: Option<String>,
 }
 
 lazy_static! {
     pub static ref USAGE: String = format!(
-    "Usage: {} --cosmos-phrase=<cphrase> --ethereum-key=<key> --cosmos-legacy-rpc=<url> --cosmos-grpc=<url> --ethereum-rpc=<url> --fees=<denom> --contract-address=<addr>
+    "Usage: {} --cosmos-phrase=<cphrase> --ethereum-key=<key> [--profile=<profile>] [--cosmos-legacy-rpc=<url>] [--cosmos-grpc=<url>] [--cosmos-backup-rpc=<url>] [--ethereum-rpc=<url>] [--ethereum-verify-rpc=<url>] [--ethereum-backfill-rpc=<urls>] [--fees=<denom>] [--contract-address=<addr>] [--peggy-id=<id>] [--deployment-block=<block>] [--fee-payer-phrase=<fphrase>] [--fee-payer-for=<kinds>] [--supply-caps=<caps>] [--deposit-finality=<bands>] [--delay-claims-above=<amounts>] [--claim-delay=<minutes>] [--claim-batch-window=<seconds>] [--claim-batch-size=<claims>] [--claim-ttl=<seconds> [--claim-retries=<retries>]] [--detect-anomalies] [--anomaly-delay] [--stalled-transfer-alert=<minutes> [--top-up-fees=<caps> [--top-up-percent=<percent>]]] [--watch-solvency --minter-api=<url> [--minter-multisig=<addr>]] [--skip-batches=<batches>] [--disable-tokens=<erc20s>] [--sponsors=<file>] [--signatures=<selection>] [--force-valset-relay-below=<percent>] [--maintenance-window=<minutes>] [--enforce-min-version] [--relay-receipts] [--task-queues=<queues>] [--timings=<file>] [--threshold-signer=<url>] [--standby=<minutes>] [--address-labels=<file>] [--price-feed=<feed>] [--fold-minter-deposits] [--state-dir=<dir> [--state-key=<key>]] [--verify-restart] [--log-sensitive] [--hub-explorer=<url>] [--ethereum-explorer=<url>]
+       {} multi --chains=<file> [--worker-threads=<n>] [--log-sensitive] [--hub-explorer=<url>] [--ethereum-explorer=<url>]
+       {} config lint --chains=<file> [--output=<fmt>]
+       {} tui --cosmos-grpc=<url> --ethereum-rpc=<url> --contract-address=<addr> [--ethereum-address=<addr>] [--state-dir=<dir> [--state-key=<key>]] [--price-feed=<feed> [--quote=<asset>] [--cosmos-legacy-rpc=<url>]] [--gas-price-window=<minutes>] [--hub-explorer=<url>] [--ethereum-explorer=<url>]
+       {} status-api --bind=<addr> --cosmos-grpc=<url> --ethereum-rpc=<url> --contract-address=<addr> [--cosmos-legacy-rpc=<url>] [--state-dir=<dir> [--state-key=<key>]] [--public [--rate-limit=<n>] | --api-tokens=<file>] [--tls-cert=<file> --tls-key=<file>]
+       {} register (--validator-phrase=<vphrase> | --validator-address=<addr> [--offline]) --cosmos-legacy-rpc=<url> --cosmos-grpc=<url> --fees=<denom> [--cosmos-phrase=<cphrase>] [--ethereum-key=<key> | --threshold-signer=<url>] [--faucet=<file> [--ethereum-rpc=<url>]] [--force]
+       {} rotate-keys --validator-phrase=<vphrase> --cosmos-legacy-rpc=<url> --cosmos-grpc=<url> --fees=<denom> --state-dir=<dir> --state-key=<key>
+       {} stats --state-dir=<dir> [--state-key=<key>] [--format=<fmt>] [--transfers]
+       {} state migrate --state-dir=<dir> [--state-key=<key>] [--check] [--output=<fmt>]
+       {} verify-batch --nonce=<nonce> --token=<addr> --cosmos-grpc=<url> --ethereum-rpc=<url> --contract-address=<addr> [--signatures=<selection>] [--output=<fmt>]
+       {} verify-execution --txhash=<hash> --cosmos-grpc=<url> --ethereum-rpc=<url> --contract-address=<addr> [--hub-height=<height>] [--output=<fmt>]
+       {} capture-incident --kind=<kind> --out=<file> --cosmos-grpc=<url> --ethereum-rpc=<url> --contract-address=<addr> [--orchestrator=<addr>] [--name=<name>]
+       {} explain-msg --msg=<file> [--unknown-msgs=<policy>] [--output=<fmt>] [--cosmos-grpc=<url> (--peggy-id=<id> | --ethereum-rpc=<url> --contract-address=<addr>)]
+       {} preview-batch --denom=<denom> --cosmos-legacy-rpc=<url> --cosmos-grpc=<url> --ethereum-rpc=<url> [--batch-size=<n>] [--price-feed=<feed> [--quote=<asset>]] [--output=<fmt>]
+       {} faucet --faucet=<file> --cosmos-legacy-rpc=<url> [--ethereum-rpc=<url>] [--hub-address=<addr>] [--ethereum-address=<addr>] [--minter-address=<addr>]
+       {} admin (pause-relaying | resume-relaying | rescan --from-block=<block> | release-claim --event-nonce=<nonce>) --state-dir=<dir>
+       {} admin skip-batch --token=<addr> --batch-nonce=<nonce> --state-dir=<dir>
+       {} admin (disable-token | enable-token) --token=<addr> --state-dir=<dir>
+       {} admin log-level --subsystem=<name> --level=<level> --state-dir=<dir>
         Options:
             -h --help                    Show this screen.
             --cosmos-phrase=<cphrase>          The Cosmos private key of the validator
             --ethereum-key=<ekey>        The Ethereum private key of the validator
+            --profile=<profile>          (Optional) mainnet, testnet or local, fills in the network's contract,
+                                         PeggyID, fee denom, local node urls and timings for the flags not given
             --cosmos-legacy-rpc=<curl>   The Cosmos RPC url, usually the validator
             --cosmos-grpc=<gurl>         The Cosmos gRPC url, usually the validator
-            --ethereum-rpc=<eurl>        The Ethereum RPC url, should be a self hosted node
+            --cosmos-backup-rpc=<url>    (Optional) A second Cosmos RPC url, valset confirms close to their signing
+                                         deadline are broadcast through it as well as --cosmos-legacy-rpc
+            --ethereum-rpc=<eurl>        The Ethereum RPC url, should be a self hosted node. Several comma separated
+                                         urls are probed for latency and the fastest up to date one is used
+            --ethereum-verify-rpc=<vurl> (Optional) A second, independently operated Ethereum RPC url. Events are
+                                         only claimed if this node reports them identically
+            --ethereum-backfill-rpc=<burls> (Optional) Comma separated Ethereum RPC urls used to rescan old blocks
+                                         instead of --ethereum-rpc, such as rate limited or archive nodes
             --fees=<denom>               The Cosmos Denom in which to pay Cosmos chain fees
             --contract-address=<addr>    The Ethereum contract address for Peggy, this is temporary
+            --peggy-id=<id>              (Optional) Refuse to start unless the contract reports this PeggyID
+            --deployment-block=<block>   (Optional) The block the contract was deployed in, or auto to find it on an
+                                         archive node. Ethereum isn't scanned before it, a new orchestrator
+                                         starts there instead of at the latest block
+            --fee-payer-phrase=<fphrase> (Optional) The Cosmos phrase of a funding key that pays Hub fees instead
+                                         of the orchestrator key
+            --fee-payer-for=<kinds>      (Optional) Comma separated message kinds the funding key pays for, any of
+                                         claims, valset_confirms, batch_confirms. Defaults to all of them
+            --supply-caps=<caps>         (Optional) Comma separated <erc20>:<cap> pairs, deposits of a token are
+                                         not claimed while more than its cap is locked in the Peggy contract
+            --deposit-finality=<bands>   (Optional) Comma separated <erc20>:<amount>:<confirmations> bands, deposits
+                                         of at least the amount are claimed once that many blocks deep instead of
+                                         the configured eth_confirmations
+            --delay-claims-above=<amounts> (Optional) Comma separated <erc20>:<amount> pairs, claims for larger
+                                         deposits are only signed after --claim-delay
+            --claim-delay=<minutes>      (Optional) How long large deposit claims are held back
+            --claim-batch-window=<seconds> (Optional) Gather claims for up to this long and send them in one Hub
+                                         transaction, they are sent right away on an event nonce gap
+            --claim-batch-size=<claims>  (Optional) Send gathered claims before the window is over once this many
+                                         are pending, defaults to 50
+            --claim-ttl=<seconds>        (Optional) Give sent claims this long to show up on the Hub before they are
+                                         sent again, instead of sending them again on the next loop
+            --claim-retries=<retries>    (Optional) How many times claims are sent again before they expire,
+                                         defaults to 3, expired claims get a fresh budget after 10 TTLs
+            --detect-anomalies           Alert on spikes in deposit rate, size or unique senders per token
+            --anomaly-delay              Delay claims for all deposits for --claim-delay after an anomaly
+            --stalled-transfer-alert=<minutes> (Optional) Alert on withdrawals that have waited this long in the Hub's
+                                         outgoing pool without a batch, with the likely cause
+            --top-up-fees=<caps>         (Optional) Comma separated <erc20>:<fee> pairs, our own withdrawals stalled
+                                         for their fee are resent paying more, up to this bridge fee in Hub units
+            --top-up-percent=<percent>   (Optional) How much higher each top up makes the fee, defaults to 50
+            --watch-solvency             Alert as soon as the Peggy contract and the Minter multisig together hold
+                                         less of a coin than the Hub's supply of it, checked every minute
+            --minter-api=<url>           A Minter Gate v2 api url, for example https://node-api.minter.network/v2
+            --minter-multisig=<addr>     The Minter multisig of the bridge
+            --skip-batches=<batches>     (Optional) Comma separated <erc20>:<nonce> pairs of batches the relayer
+                                         must never submit, more can be added with admin skip-batch
+            --disable-tokens=<erc20s>    (Optional) Comma separated ERC20 contracts the relayer doesn't relay batches
+                                         for, they are still signed. admin enable-token and disable-token change this
+            --sponsors=<file>            (Optional) Json file of sponsors paying the gas for withdrawals to their
+                                         users, those batches are always relayed and logged to a ledger
+            --signatures=<selection>     (Optional) minimal to submit just enough signatures to pass, the default,
+                                         or full for contracts that need every signature
+            --force-valset-relay-below=<percent> (Optional) Relay valset updates regardless of gas once less than
+                                         this percentage of the Ethereum valset's power is held by current Hub
+                                         validators, for example 75
+            --maintenance-window=<minutes> (Optional) Warn when the orchestrator couldn't be offline this long
+                                         without missing a valset or batch signing window
+            --enforce-min-version        Stop signing while older than the minimum orchestrator version set by
+                                         governance, without it an outdated orchestrator only warns
+            --relay-receipts             Report the valset updates and batches our relayer sends to Ethereum to
+                                         the Hub, for accounting of relayer work
+            --task-queues=<queues>       (Optional) Comma separated <task>:<capacity> sizes of the queues tasks hand
+                                         work over in, relay_receipts for the receipts waiting to be sent,
+                                         defaults to 1000 each
+            --timings=<file>             (Optional) Json file overriding loop intervals, timeouts and confirmations
+            --threshold-signer=<url>     (Optional) Sign valset and batch confirms with the key shared by this
+                                         threshold signer instead of --ethereum-key, which still pays for relaying
+            --standby=<minutes>          (Optional) Run as a backup with the primary's keys, submitting nothing until
+                                         the primary has left work pending this long
+            --address-labels=<file>      (Optional) Json file of address labels to journal with deposit senders and
+                                         destinations
+            --price-feed=<feed>          (Optional) Journal transfers with their USD price, hub for the Hub oracle
+                                         prices or a json file of fixed <erc20>: <price> pairs
+            --fold-minter-deposits       Journal deposits to the same Minter address in one scan as a single entry
+            --state-dir=<dir>            (Optional) Directory for the submission journal and state store
+            --state-key=<key>            (Optional) Encrypt the journal, state store and rotated keys at rest with
+                                         this secret of 32 random bytes in hex, like `openssl rand -hex 32`,
+                                         files written in plaintext before are refused until state migrate
+                                         encrypts them
+            --verify-restart             Check the journal and state store against the Hub before starting
+                                         and refuse to start if anything would be submitted twice
+            --log-sensitive              Log raw signed transactions, signatures and calldata, for debugging only
+            --hub-explorer=<url>         (Optional) Hub explorer url template for links in logs, {{kind}} is replaced
+                                         with tx or address and {{id}} with the hash or address
+            --ethereum-explorer=<url>    (Optional) Ethereum explorer url template, for example
+                                         https://etherscan.io/{{kind}}/{{id}}
+            --ethereum-address=<addr>    (Optional) The relayer address whose balance and whitelisting the tui shows,
+                                         or an Ethereum address for the faucet command to fund
+            --quote=<asset>              (Optional) usd or eth, the asset the tui values batch fees of all tokens
+                                         in with the --price-feed prices, defaults to usd
+            --gas-price-window=<minutes> (Optional) How fast the tui's average gas price follows the spot price,
+                                         a price's weight halves every window, defaults to 60
+            --bind=<addr>                The address and port the status API listens on, for example 0.0.0.0:8080,
+                                         the admin route is only served on loopback such as 127.0.0.1:8080
+            --public                     Serve the status API to anyone, without the admin route, rate limited per
+                                         client IP and with responses cached for 15 seconds
+            --rate-limit=<n>             (Optional) Requests a client IP may make a minute on a public status API,
+                                         defaults to 60
+            --api-tokens=<file>          (Optional) Json file of the bearer tokens the status API accepts and their
+                                         scope, read or admin, every request then needs one
+            --tls-cert=<file>            (Optional) PEM certificate chain to serve the status API over HTTPS with
+            --tls-key=<file>             The PEM private key of --tls-cert
+            --validator-phrase=<vphrase> The Cosmos private key of the validator, used to register delegate keys
+            --validator-address=<addr>   The validator's Cosmos address, register prints the unsigned transaction
+                                         for signing offline instead of sending it
+            --offline                    With --validator-address, only print the unsigned transaction and exit
+            --force                      Register even if the Hub already knows the delegate addresses
+            --faucet=<file>              Json file of the devnet or testnet faucets of the Hub, Ethereum and Minter,
+                                         register funds the delegate keys from it
+            --hub-address=<addr>         (Optional) A Hub address for the faucet command to fund
+            --minter-address=<addr>      (Optional) A Minter address for the faucet command to fund
+            --chains=<file>              A json file with the settings of every Hub chain the multi command runs
+            --worker-threads=<n>         (Optional) Threads the multi command spreads the chains over, each with its
+                                         own event loop so a slow chain can't hold up the others, defaults to 1
+            --format=<fmt>               (Optional) Output format of the stats command, json or csv, defaults to json
+            --output=<fmt>               (Optional) text, the default, or json with a schema scripts can rely on.
+                                         Exits with 1 when what the command checks doesn't hold
+            --transfers                  Export every journaled transfer with its labels and price instead of daily stats
+            --check                      Only show what state migrate would change and check that it would work
+            --from-block=<block>         The Ethereum block the oracle should rescan from
+            --event-nonce=<nonce>        The event nonce of the delayed claim to sign right away
+            --subsystem=<name>           The subsystem whose log level to change, eth-scanner, signer, relayer or
+                                         cosmos-broadcast
+            --level=<level>              off, error, warn, info, debug or trace, or default to follow RUST_LOG again
+            --token=<addr>               The ERC20 contract of the batch to skip or verify
+            --batch-nonce=<nonce>        The nonce of the batch the relayer should skip
+            --nonce=<nonce>              The nonce of the batch to verify
+            --txhash=<hash>              The submitBatch transaction to verify
+            --hub-height=<height>        (Optional) Compare with the Hub batch as of this block, for batches the Hub
+                                         already deleted after they executed
+            --kind=<kind>                The kind of incident to capture, stuck-batch, reorg or sequence-desync
+            --out=<file>                 The json file the incident bundle is written to
+            --orchestrator=<addr>        (Optional) The orchestrator's Cosmos address whose last event nonce the
+                                         bundle records
+            --name=<name>                (Optional) What the incident bundle is about, defaults to the kind
+            --msg=<file>                 Json file of the Peggy message or list of messages to explain, - for stdin
+            --unknown-msgs=<policy>      (Optional) What to do with message types added by a newer Hub, warn to
+                                         show their raw fields, the default, ignore to skip them or fail
+            --denom=<denom>              The peggy/<erc20> denom or ERC20 contract of the batch to preview
+            --batch-size=<n>             (Optional) The most transfers the Hub puts in a batch, defaults to 100
         About:
             The Validator companion binary for Peggy. This must be run by all Peggy chain validators
-            and is a mix of a relayer + oracle + ethereum signing infrastructure
+            and is a mix of a relayer + oracle + ethereum signing infrastructure. The tui command shows
+            a live dashboard of the bridge status instead, the status-api command serves it as json over HTTP.
+            With --cosmos-legacy-rpc the status-api also reports how far every transfer is confirmed.
+            The multi command runs independent orchestrators
+            for several Hub chains from one process, config lint checks its chain file before it is used.
+            The register command checks and registers the validator's delegate keys, generating any key that
+            isn't given, and waits until the Hub accepts them. On devnets and testnets the faucet command
+            funds addresses from the configured faucets.
+            The rotate-keys command generates new delegate keys, registers them on the Hub and hands them
+            to the orchestrator running with the same state dir, encrypted with the --state-key.
+            The stats command exports daily volume, fee and latency statistics from the journal, or with
+            --transfers every transfer for accounting. The state migrate command brings the state dir up to
+            the current schema version, which the orchestrator also does at startup, and is the only way to
+            encrypt files written in plaintext before --state-key was given. The verify-batch command rebuilds a batch's submitBatch
+            calldata from the Hub and the contract, checks every confirm and prints the calldata. The
+            verify-execution command checks an executed submitBatch transaction against the Hub's batch.
+            The capture-incident command saves the Hub's and the contract's state during an incident to a
+            bundle that can be replayed against later versions as a regression test.
+            The explain-msg command prints the sign bytes and fields of a message, and for confirms checks
+            their signature against the checkpoint rebuilt from the Hub. The preview-batch command shows
+            which pooled transfers the next batch of a token would include, whether the Hub would build it,
+            its estimated gas and whether relaying it pays, and the fee a transfer that's left out needs.
+            The admin commands instruct the orchestrator running with the same state dir, they pause or
+            resume relaying, skip a batch, disable or enable relaying for a token, rescan Ethereum from a
+            block, sign a delayed claim early or change the log level of one subsystem.
+            Keys and phrases can be given as they are or as env:<var>, file:<path>, gpg:<path>,
+            vault:<path>#<field>, aws-sm:<secret id> or aws-kms:<path> references to where they are kept
             Written By: {}
             Version {}",
             env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
             env!("CARGO_PKG_AUTHORS"),
             env!("CARGO_PKG_VERSION"),
         );
@@ -67,7 +499,7 @@ lazy_static! {
 
 #[actix_rt::main]
 async fn main() {
-    env_logger::init();
+    log_levels::init();
     // On Linux static builds we need to probe ssl certs path to be able to
     // do TLS stuff.
     openssl_probe::init_ssl_cert_env_vars();
@@ -75,33 +507,415 @@ async fn main() {
     let args: Args = Docopt::new(USAGE.as_str())
         .and_then(|d| d.deserialize())
         .unwrap_or_else(|e| e.exit());
-    let cosmos_key = CosmosPrivateKey::from_phrase(&args.flag_cosmos_phrase, "")
+    set_log_sensitive(args.flag_log_sensitive);
+    set_explorer(Chain::Hub, args.flag_hub_explorer.clone());
+    set_explorer(Chain::Ethereum, args.flag_ethereum_explorer.clone());
+    set_tx_memo(BuildInfo::current().tx_memo());
+
+    if args.cmd_tui {
+        return tui_main(args).await;
+    }
+    if args.cmd_status_api {
+        return status_api_main(args).await;
+    }
+    if args.cmd_register {
+        return register_main(args).await;
+    }
+    if args.cmd_rotate_keys {
+        return rotate_keys_main(args).await;
+    }
+    if args.cmd_stats {
+        return stats_main(args).await;
+    }
+    if args.cmd_state && args.cmd_migrate {
+        return state_migrate_main(args).await;
+    }
+    if args.cmd_admin {
+        return admin_main(args);
+    }
+    if args.cmd_verify_batch {
+        return verify_batch_main(args).await;
+    }
+    if args.cmd_capture_incident {
+        return capture_incident_main(args).await;
+    }
+    if args.cmd_verify_execution {
+        return verify_execution_main(args).await;
+    }
+    if args.cmd_explain_msg {
+        return explain_msg_main(args).await;
+    }
+    if args.cmd_preview_batch {
+        return preview_batch_main(args).await;
+    }
+    if args.cmd_faucet {
+        return faucet_main(args).await;
+    }
+    if args.cmd_config && args.cmd_lint {
+        return config_lint_main(args);
+    }
+    if args.cmd_multi {
+        let path = args.flag_chains.expect("multi requires --chains");
+        let configs = load_chain_configs(Path::new(&path)).unwrap_or_else(|e| panic!("{}", e));
+        let threads = args.flag_worker_threads.map_or(1, |threads| {
+            threads
+                .trim()
+                .parse()
+                .expect("Invalid number of worker threads")
+        });
+        let mut groups = assign_chains(configs, threads).into_iter();
+        let ours = groups.next().unwrap_or_default();
+        // the other groups each get a thread with an event loop of their own
+        let handles: Vec<_> = groups
+            .enumerate()
+            .map(|(i, group)| {
+                thread::Builder::new()
+                    .name(format!("orchestrator-{}", i + 1))
+                    .spawn(move || {
+                        System::new("orchestrator")
+                            .block_on(join_all(group.into_iter().map(run_orchestrator)));
+                    })
+                    .expect("Failed to start worker thread")
+            })
+            .collect();
+        join_all(ours.into_iter().map(run_orchestrator)).await;
+        for handle in handles {
+            let _ = handle.join();
+        }
+        return;
+    }
+
+    let mut config = ChainConfig {
+        name: "default".to_string(),
+        profile: args.flag_profile,
+        cosmos_phrase: args.flag_cosmos_phrase,
+        ethereum_key: args.flag_ethereum_key,
+        cosmos_legacy_rpc: args.flag_cosmos_legacy_rpc,
+        cosmos_grpc: args.flag_cosmos_grpc,
+        cosmos_backup_rpc: args.flag_cosmos_backup_rpc,
+        ethereum_rpc: args.flag_ethereum_rpc,
+        ethereum_verify_rpc: args.flag_ethereum_verify_rpc,
+        ethereum_backfill_rpc: args.flag_ethereum_backfill_rpc,
+        contract_address: args.flag_contract_address,
+        fees: args.flag_fees,
+        peggy_id: args.flag_peggy_id,
+        deployment_block: args.flag_deployment_block,
+        fee_payer_phrase: args.flag_fee_payer_phrase,
+        fee_payer_for: args.flag_fee_payer_for,
+        supply_caps: args.flag_supply_caps,
+        deposit_finality: args.flag_deposit_finality,
+        delay_claims_above: args.flag_delay_claims_above,
+        claim_delay: args.flag_claim_delay,
+        claim_batch_window: args.flag_claim_batch_window,
+        claim_batch_size: args.flag_claim_batch_size,
+        claim_ttl: args.flag_claim_ttl,
+        claim_retries: args.flag_claim_retries,
+        detect_anomalies: args.flag_detect_anomalies,
+        anomaly_delay: args.flag_anomaly_delay,
+        stalled_transfer_alert: args.flag_stalled_transfer_alert,
+        top_up_fees: args.flag_top_up_fees,
+        top_up_percent: args.flag_top_up_percent,
+        watch_solvency: args.flag_watch_solvency,
+        minter_api: args.flag_minter_api,
+        minter_multisig: args.flag_minter_multisig,
+        skip_batches: args.flag_skip_batches,
+        disable_tokens: args.flag_disable_tokens,
+        sponsors: args.flag_sponsors,
+        signatures: args.flag_signatures,
+        force_valset_relay_below: args.flag_force_valset_relay_below,
+        maintenance_window: args.flag_maintenance_window,
+        enforce_min_version: args.flag_enforce_min_version,
+        relay_receipts: args.flag_relay_receipts,
+        task_queues: args.flag_task_queues,
+        timings: args.flag_timings,
+        threshold_signer: args.flag_threshold_signer,
+        standby: args.flag_standby,
+        address_labels: args.flag_address_labels,
+        price_feed: args.flag_price_feed,
+        fold_minter_deposits: args.flag_fold_minter_deposits,
+        state_dir: args.flag_state_dir,
+        state_key: args.flag_state_key,
+        verify_restart: args.flag_verify_restart,
+    };
+    resolve_profile(&mut config).unwrap_or_else(|e| panic!("{}", e));
+    run_orchestrator(config).await;
+}
+
+/// Resolves a key or phrase that may be a reference to a secret backend, see `peggy_utils::secrets`
+fn output_format(args: &Args) -> OutputFormat {
+    match args.flag_output.as_deref() {
+        Some(format) => format.parse().unwrap_or_else(|e| panic!("{}", e)),
+        None => OutputFormat::default(),
+    }
+}
+
+async fn secret(value: &str) -> String {
+    resolve_secret(value)
+        .await
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// The key the journal, state store and rotated keys are encrypted with, see `state_encryption`
+async fn state_key(value: Option<&str>) -> Option<StateKey> {
+    match value {
+        Some(value) => Some(
+            StateKey::from_secret(&secret(value).await)
+                .unwrap_or_else(|e| panic!("Invalid --state-key, {}", e)),
+        ),
+        None => None,
+    }
+}
+
+/// Runs the orchestrator for one Hub chain until it stops
+async fn run_orchestrator(config: ChainConfig) {
+    let name = config.name;
+    let profile: Option<Profile> = config
+        .profile
+        .as_deref()
+        .map(|profile| profile.parse().unwrap_or_else(|e| panic!("{}", e)));
+    let cosmos_key = CosmosPrivateKey::from_phrase(&secret(&config.cosmos_phrase).await, "")
         .expect("Invalid Private Cosmos Key!");
-    let ethereum_key: EthPrivateKey = args
-        .flag_ethereum_key
+    let ethereum_key: EthPrivateKey = secret(&config.ethereum_key)
+        .await
         .parse()
         .expect("Invalid Ethereum private key!");
-    let contract_address: EthAddress = args
-        .flag_contract_address
+    let contract_address: EthAddress = config
+        .contract_address
         .parse()
         .expect("Invalid contract address!");
 
-    let _ = Url::parse(&args.flag_cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
-    let cosmos_legacy_url = args.flag_cosmos_legacy_rpc.trim_end_matches('/');
+    let _ = Url::parse(&config.cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
+    let cosmos_legacy_url = config.cosmos_legacy_rpc.trim_end_matches('/');
 
-    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
-    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+    let _ = Url::parse(&config.cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = config.cosmos_grpc.trim_end_matches('/').to_string();
 
-    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
-    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/');
+    if let Some(url) = config.cosmos_backup_rpc.as_deref() {
+        let _ = Url::parse(url).expect("Invalid Cosmos backup RPC url");
+    }
+
+    let eth_urls = parse_urls(&config.ethereum_rpc);
+    for url in eth_urls.iter() {
+        let _ = Url::parse(url).expect("Invalid Ethereum RPC url");
+    }
+    let backfill_eth_urls = parse_urls(config.ethereum_backfill_rpc.as_deref().unwrap_or(""));
+    for url in backfill_eth_urls.iter() {
+        let _ = Url::parse(url).expect("Invalid Ethereum backfill RPC url");
+    }
 
-    let fee_denom = args.flag_fees;
+    let verify_eth_url = config.ethereum_verify_rpc.map(|url| {
+        let _ = Url::parse(&url).expect("Invalid Ethereum verification RPC url");
+        url.trim_end_matches('/').to_string()
+    });
+
+    let fee_denom = config.fees;
+    let fee_payer = match config.fee_payer_phrase.as_deref() {
+        Some(phrase) => Some(
+            CosmosPrivateKey::from_phrase(&secret(phrase).await, "")
+                .expect("Invalid fee payer phrase!"),
+        ),
+        None => None,
+    };
+    let fee_payers = FeePayers::new(fee_payer, config.fee_payer_for.as_deref())
+        .unwrap_or_else(|e| panic!("{}", e));
+    let supply_caps =
+        SupplyCaps::parse(config.supply_caps.as_deref()).unwrap_or_else(|e| panic!("{}", e));
+    let deposit_finality = DepositFinality::parse(config.deposit_finality.as_deref())
+        .unwrap_or_else(|e| panic!("{}", e));
+    let claim_delay = ClaimDelay::new(
+        config.delay_claims_above.as_deref(),
+        config.claim_delay.as_deref(),
+    )
+    .unwrap_or_else(|e| panic!("{}", e));
+    if config.anomaly_delay && !claim_delay.is_enabled() {
+        panic!("--anomaly-delay requires --claim-delay");
+    }
+    let claim_batching = ClaimBatching::new(
+        config.claim_batch_window.as_deref(),
+        config.claim_batch_size.as_deref(),
+    )
+    .unwrap_or_else(|e| panic!("{}", e));
+    let claim_resubmission =
+        ClaimResubmission::new(config.claim_ttl.as_deref(), config.claim_retries.as_deref())
+            .unwrap_or_else(|e| panic!("{}", e));
+    let task_queues =
+        TaskQueues::parse(config.task_queues.as_deref()).unwrap_or_else(|e| panic!("{}", e));
+    let relayer_controls = RelayerControls {
+        paused: false,
+        skipped_batches: match config.skip_batches.as_deref() {
+            Some(batches) => parse_skipped_batches(batches).unwrap_or_else(|e| panic!("{}", e)),
+            None => Vec::new(),
+        },
+        disabled_tokens: match config.disable_tokens.as_deref() {
+            Some(tokens) => parse_tokens(tokens).unwrap_or_else(|e| panic!("{}", e)),
+            None => Vec::new(),
+        },
+        requested_batches: None,
+        sponsors: match config.sponsors.as_deref() {
+            Some(file) => Sponsors::load(Path::new(file)).unwrap_or_else(|e| panic!("{}", e)),
+            None => Sponsors::default(),
+        },
+    };
+    let signatures: SignatureSelection = match config.signatures.as_deref() {
+        Some(selection) => selection.parse().unwrap_or_else(|e| panic!("{}", e)),
+        None => SignatureSelection::default(),
+    };
+    let force_valset_relay_below = config
+        .force_valset_relay_below
+        .as_deref()
+        .map(|percent| parse_overlap_percent(percent).unwrap_or_else(|e| panic!("{}", e)));
+    let default_timings = profile.map_or_else(Timings::default, |p| p.timings());
+    let timings = match config.timings.as_deref() {
+        Some(file) => {
+            Timings::load_over(Path::new(file), default_timings).unwrap_or_else(|e| panic!("{}", e))
+        }
+        None => default_timings,
+    };
+    let maintenance_window = config.maintenance_window.map(|minutes| {
+        minutes
+            .trim()
+            .parse::<u64>()
+            .expect("Invalid maintenance window!")
+            * 60
+    });
+    let stalled_transfer_alert = config.stalled_transfer_alert.map(|minutes| {
+        minutes
+            .trim()
+            .parse::<u64>()
+            .expect("Invalid stalled transfer alert threshold!")
+            * 60
+    });
+    let fee_top_up = FeeTopUp::new(
+        config.top_up_fees.as_deref(),
+        config.top_up_percent.as_deref(),
+        stalled_transfer_alert,
+    )
+    .unwrap_or_else(|e| panic!("{}", e));
+    let standby = config.standby.map(|minutes| {
+        minutes
+            .trim()
+            .parse::<u64>()
+            .expect("Invalid standby period!")
+            * 60
+    });
+    let deployment_block: Option<DeploymentBlock> = config
+        .deployment_block
+        .as_deref()
+        .map(|block| block.parse().unwrap_or_else(|e| panic!("{}", e)));
+    let anomalies = AnomalyDetector::new(
+        config.detect_anomalies || config.anomaly_delay,
+        config.anomaly_delay,
+    );
+
+    let key = state_key(config.state_key.as_deref()).await;
+    let storage = config.state_dir.map(|dir| {
+        LocalStorage::open_with_key(Path::new(&dir), key)
+            .expect("Failed to open the orchestrator state dir")
+    });
+    if let Some(storage) = storage.as_ref() {
+        let migrations =
+            migrate_storage(storage).expect("Failed to migrate the orchestrator state dir");
+        for migration in migrations.iter().filter(|m| !m.steps.is_empty()) {
+            info!("{}", migration);
+        }
+    }
+    if config.verify_restart && storage.is_none() {
+        panic!("--verify-restart requires --state-dir");
+    }
+
+    let mut keys = OrchestratorKeys {
+        cosmos_key,
+        ethereum_key,
+    };
+    if let Some(storage) = storage.as_ref() {
+        if let Some(active_keys) = load_active_keys(&storage.dir, storage.key.as_ref())
+            .expect("Failed to load rotated delegate keys")
+        {
+            warn!(
+                "Using the rotated delegate keys in {} instead of the keys given on the command line",
+                storage.dir.display()
+            );
+            keys = active_keys;
+        }
+        let address = keys.cosmos_address().to_string();
+        storage
+            .state
+            .update(|state| state.orchestrator_address = Some(address))
+            .expect("Failed to update the state store");
+    }
+    let OrchestratorKeys {
+        cosmos_key,
+        ethereum_key,
+    } = keys;
 
     let grpc_client = PeggyQueryClient::connect(cosmos_grpc_url.clone())
         .await
         .unwrap();
-    let web3 = Web3::new(&eth_url, LOOP_SPEED);
-    let contact = Contact::new(&cosmos_legacy_url, LOOP_SPEED);
+    let ethereum_endpoints = Endpoints::new(&eth_urls, &backfill_eth_urls, timings.rpc_timeout);
+    if ethereum_endpoints.urls().len() > 1 {
+        ethereum_endpoints.probe().await;
+    }
+    let web3 = ethereum_endpoints.web3(Purpose::TimeCritical);
+    let verify_web3 = verify_eth_url.map(|url| Web3::new(&url, timings.rpc_timeout));
+    let contact = Contact::new(&cosmos_legacy_url, timings.rpc_timeout);
+    let backup_contact = config
+        .cosmos_backup_rpc
+        .as_deref()
+        .map(|url| Contact::new(url.trim_end_matches('/'), timings.rpc_timeout));
+    if let Some(profile) = profile {
+        profile
+            .check_hub_chain_id(&contact)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
+    let enrichment = Enrichment::new(
+        config.address_labels.as_deref(),
+        config.price_feed.as_deref(),
+        config.fold_minter_deposits,
+        &contact,
+        &cosmos_grpc_url,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("{}", e));
+    let solvency_watch = if config.watch_solvency {
+        let multisig = match (config.minter_api, config.minter_multisig) {
+            (Some(api), Some(address)) => {
+                let _ = Url::parse(&api).expect("Invalid Minter api url");
+                MinterMultisig { api, address }
+            }
+            _ => panic!("--watch-solvency requires --minter-api and --minter-multisig"),
+        };
+        let oracle_client = connect_oracle(&cosmos_grpc_url)
+            .await
+            .unwrap_or_else(|e| panic!("{}", e));
+        Some((oracle_client, multisig))
+    } else {
+        None
+    };
+    let fee_top_up = match fee_top_up {
+        Some(top_up) => {
+            let oracle_client = connect_oracle(&cosmos_grpc_url)
+                .await
+                .unwrap_or_else(|e| panic!("{}", e));
+            Some((top_up, oracle_client))
+        }
+        None => None,
+    };
+    let threshold_signer = match config.threshold_signer.as_deref() {
+        Some(url) => {
+            let _ = Url::parse(url).expect("Invalid threshold signer url");
+            let signer = ThresholdSigner::connect(url.trim_end_matches('/'), timings.rpc_timeout)
+                .await
+                .expect("Failed to get the address of the threshold signer");
+            info!(
+                "Signing confirms as {} with the threshold signer at {}",
+                signer.address(),
+                url
+            );
+            Some(signer)
+        }
+        None => None,
+    };
 
     let public_eth_key = ethereum_key
         .to_public_key()
@@ -110,20 +924,915 @@ async fn main() {
         .to_public_key()
         .expect("Invalid Cosmos Phrase!")
         .to_address();
-    info!("Starting Peggy Validator companion binary Relayer + Oracle + Eth Signer");
+    info!(
+        "Starting Peggy Validator companion binary Relayer + Oracle + Eth Signer for chain {}",
+        name
+    );
     info!(
         "Ethereum Address: {} Cosmos Address {}",
         public_eth_key, public_cosmos_key
     );
+    info!("Orchestrator build {}", BuildInfo::current());
+    if let Some(expected) = config.peggy_id {
+        let peggy_id = get_peggy_id(contract_address, public_eth_key, &web3)
+            .await
+            .expect("Failed to get the PeggyID, check your Eth node");
+        let peggy_id = String::from_utf8_lossy(&peggy_id);
+        // the id is stored as bytes32, ignore the padding
+        if peggy_id.trim_end_matches('\0') != expected {
+            panic!(
+                "Contract {} has PeggyID {}, expected {}",
+                contract_address, peggy_id, expected
+            );
+        }
+    }
+    check_contract_abi(
+        &ethereum_endpoints.select(Purpose::TimeCritical),
+        contract_address,
+        timings.rpc_timeout,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("{}", e));
+    let deployment_block = match deployment_block {
+        Some(DeploymentBlock::Block(block)) => Some(block),
+        Some(DeploymentBlock::Auto) => {
+            let latest_block = downcast_nonce(
+                web3.eth_block_number()
+                    .await
+                    .expect("Failed to get the latest block, check your Eth node"),
+            )
+            .expect("Invalid latest block");
+            let block = find_deployment_block(
+                &ethereum_endpoints.select(Purpose::Backfill),
+                contract_address,
+                latest_block,
+                timings.rpc_timeout,
+            )
+            .await
+            .unwrap_or_else(|e| panic!("{}", e));
+            info!(
+                "Contract {} was deployed in block {}",
+                contract_address, block
+            );
+            Some(block)
+        }
+        None => None,
+    };
+    if let (Some(storage), true) = (storage.as_ref(), config.verify_restart) {
+        verify_restart(
+            &mut grpc_client.clone(),
+            public_cosmos_key,
+            &storage.journal,
+            &storage.state,
+        )
+        .await
+        .expect("Restart check failed, refusing to start");
+    }
+    if let Some(storage) = storage.as_ref() {
+        if let Err(e) = reconcile_restart(
+            &mut grpc_client.clone(),
+            &contact,
+            &web3,
+            public_cosmos_key,
+            storage,
+        )
+        .await
+        {
+            warn!("Failed to reconcile the state store with the chains: {}", e);
+        }
+    }
+    if verify_web3.is_none() {
+        info!("No Ethereum verification RPC provided, Ethereum events will not be cross checked");
+    }
+    if let Some(storage) = storage.as_ref() {
+        let entry = JournalEntry::Started {
+            build: BuildInfo::current(),
+            timestamp: unix_now(),
+        };
+        if let Err(e) = storage.journal.append(&entry) {
+            error!("Failed to journal {:?}: {}", entry, e);
+        }
+    }
 
-    orchestrator_main_loop(
-        cosmos_key,
-        ethereum_key,
-        web3,
+    if let Some(silence) = standby {
+        wait_for_takeover(
+            grpc_client.clone(),
+            &web3,
+            contract_address,
+            public_cosmos_key,
+            silence,
+        )
+        .await;
+    }
+
+    let orchestrator = orchestrator_main_loop(
+        KeyRing::new(keys, storage.as_ref().map(|s| s.dir.clone())),
+        threshold_signer,
+        ethereum_endpoints,
+        verify_web3,
         contact,
+        backup_contact,
         grpc_client,
         contract_address,
+        deployment_block,
         fee_denom,
+        fee_payers,
+        supply_caps,
+        deposit_finality,
+        claim_delay,
+        claim_batching,
+        claim_resubmission,
+        anomalies,
+        enrichment,
+        relayer_controls,
+        signatures,
+        force_valset_relay_below,
+        maintenance_window,
+        stalled_transfer_alert,
+        fee_top_up,
+        solvency_watch,
+        config.enforce_min_version,
+        config.relay_receipts,
+        task_queues,
+        timings,
+        storage,
+    );
+    join(orchestrator, watch_event_loop(name)).await;
+}
+
+async fn tui_main(args: Args) {
+    let contract_address: EthAddress = args
+        .flag_contract_address
+        .parse()
+        .expect("Invalid contract address!");
+    let relayer_address: Option<EthAddress> = args
+        .flag_ethereum_address
+        .map(|a| a.parse().expect("Invalid Ethereum address!"));
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/');
+
+    let key = state_key(args.flag_state_key.as_deref()).await;
+    let storage = args.flag_state_dir.map(|dir| {
+        LocalStorage::open_with_key(Path::new(&dir), key)
+            .expect("Failed to open the orchestrator state dir")
+    });
+
+    // the Hub price feed reads the oracle prices over the legacy rpc
+    let contact = Contact::new(
+        args.flag_cosmos_legacy_rpc.trim_end_matches('/'),
+        LOOP_SPEED,
+    );
+    let fee_quoter = match args.flag_price_feed.as_deref() {
+        Some(feed) => Some(FeeQuoter {
+            quote: match args.flag_quote.as_deref() {
+                Some(quote) => quote.parse().unwrap_or_else(|e| panic!("{}", e)),
+                None => QuoteAsset::default(),
+            },
+            price_feed: new_price_feed(feed, &contact, &cosmos_grpc_url)
+                .await
+                .unwrap_or_else(|e| panic!("{}", e)),
+            oracle_client: connect_oracle(&cosmos_grpc_url)
+                .await
+                .unwrap_or_else(|e| panic!("{}", e)),
+        }),
+        None => None,
+    };
+
+    let gas_price_window = match args.flag_gas_price_window {
+        Some(minutes) => Duration::from_secs(
+            minutes
+                .trim()
+                .parse::<u64>()
+                .expect("Invalid gas price window!")
+                * 60,
+        ),
+        None => DEFAULT_GAS_PRICE_WINDOW,
+    };
+
+    let oracle_client = connect_oracle(&cosmos_grpc_url)
+        .await
+        .unwrap_or_else(|e| panic!("{}", e));
+    let grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let web3 = Web3::new(&eth_url, LOOP_SPEED);
+
+    run_tui(
+        web3,
+        grpc_client,
+        oracle_client,
+        contract_address,
+        relayer_address,
+        storage,
+        fee_quoter,
+        gas_price_window,
+    )
+    .await
+}
+
+async fn status_api_main(args: Args) {
+    let contract_address: EthAddress = args
+        .flag_contract_address
+        .parse()
+        .expect("Invalid contract address!");
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/').to_string();
+
+    // the stage of every transfer comes from the Hub's tracking routes on the legacy rpc
+    let cosmos_legacy_url = if args.flag_cosmos_legacy_rpc.is_empty() {
+        None
+    } else {
+        let _ = Url::parse(&args.flag_cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
+        Some(
+            args.flag_cosmos_legacy_rpc
+                .trim_end_matches('/')
+                .to_string(),
+        )
+    };
+
+    let rate_limit = if args.flag_public {
+        Some(args.flag_rate_limit.map_or(DEFAULT_RATE_LIMIT, |limit| {
+            limit.trim().parse().expect("Invalid rate limit!")
+        }))
+    } else {
+        None
+    };
+
+    let mut api = StatusApi::new(
+        cosmos_grpc_url,
+        cosmos_legacy_url,
+        eth_url,
+        contract_address,
+        LOOP_SPEED,
+        args.flag_state_dir.map(|dir| dir.into()),
+        state_key(args.flag_state_key.as_deref()).await,
+        rate_limit,
+    );
+    if let Some(path) = args.flag_api_tokens {
+        api.tokens = Some(
+            ApiTokens::load(Path::new(&path))
+                .await
+                .unwrap_or_else(|e| panic!("{}", e)),
+        );
+    }
+    let tls = match (args.flag_tls_cert, args.flag_tls_key) {
+        (Some(cert), Some(key)) => Some(
+            tls_acceptor(Path::new(&cert), Path::new(&key)).unwrap_or_else(|e| panic!("{}", e)),
+        ),
+        _ => None,
+    };
+    let bind = args.flag_bind.expect("status-api requires --bind");
+    run_status_api(bind, api, tls)
+        .await
+        .expect("Failed to run the status API");
+}
+
+async fn verify_batch_main(args: Args) {
+    let contract_address: EthAddress = args
+        .flag_contract_address
+        .parse()
+        .expect("Invalid contract address!");
+    let token: EthAddress = args
+        .flag_token
+        .expect("verify-batch requires --token")
+        .parse()
+        .expect("Invalid token address!");
+    let nonce: u64 = args
+        .flag_nonce
+        .expect("verify-batch requires --nonce")
+        .trim()
+        .parse()
+        .expect("Invalid batch nonce");
+    let signatures: SignatureSelection = match args.flag_signatures {
+        Some(selection) => selection.parse().unwrap_or_else(|e| panic!("{}", e)),
+        None => SignatureSelection::default(),
+    };
+    let output = output_format(&args);
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/');
+
+    let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let web3 = Web3::new(&eth_url, LOOP_SPEED);
+
+    let verification = verify_batch(
+        &mut grpc_client,
+        &web3,
+        contract_address,
+        token,
+        nonce,
+        signatures,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("Failed to verify batch {}:{} {:?}", token, nonce, e));
+    output.print(&format_verification(&verification), &verification.view());
+    exit_unless(verification.passed());
+}
+
+async fn capture_incident_main(args: Args) {
+    let contract_address: EthAddress = args
+        .flag_contract_address
+        .parse()
+        .expect("Invalid contract address!");
+    let kind_name = args.flag_kind.expect("capture-incident requires --kind");
+    let kind: IncidentKind = kind_name.parse().unwrap_or_else(|e| panic!("{}", e));
+    let out = args.flag_out.expect("capture-incident requires --out");
+    let orchestrator: Option<CosmosAddress> = args
+        .flag_orchestrator
+        .map(|address| address.parse().expect("Invalid orchestrator address!"));
+    let name = args.flag_name.unwrap_or(kind_name);
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/');
+
+    let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let web3 = Web3::new(&eth_url, LOOP_SPEED);
+
+    let bundle = capture_incident(
+        &mut grpc_client,
+        &web3,
+        contract_address,
+        orchestrator,
+        name,
+        kind,
+        unix_now(),
+    )
+    .await
+    .unwrap_or_else(|e| panic!("Failed to capture the incident {:?}", e));
+    let json = serde_json::to_string_pretty(&bundle).unwrap();
+    std::fs::write(&out, json).unwrap_or_else(|e| panic!("Failed to write {}: {}", out, e));
+    println!(
+        "Captured {} valsets and {} batches to {}, add the reorg depth or the rejections from the logs by hand",
+        bundle.hub_valsets.len(),
+        bundle.hub_batches.len(),
+        out
+    );
+}
+
+async fn verify_execution_main(args: Args) {
+    let contract_address: EthAddress = args
+        .flag_contract_address
+        .parse()
+        .expect("Invalid contract address!");
+    let tx_hash = parse_tx_hash(
+        &args
+            .flag_txhash
+            .expect("verify-execution requires --txhash"),
     )
-    .await;
+    .unwrap_or_else(|e| panic!("{}", e));
+    let hub_height: Option<u64> = args
+        .flag_hub_height
+        .map(|height| height.trim().parse().expect("Invalid Hub height"));
+    let output = output_format(&args);
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/');
+
+    let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let web3 = Web3::new(&eth_url, LOOP_SPEED);
+
+    let verification = verify_execution(
+        &mut grpc_client,
+        &web3,
+        contract_address,
+        tx_hash.clone(),
+        hub_height,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("Failed to verify transaction {:#066x} {:?}", tx_hash, e));
+    output.print(&format_execution(&verification), &verification.view());
+    exit_unless(verification.matches());
+}
+
+async fn explain_msg_main(args: Args) {
+    let path = args.flag_msg.expect("explain-msg requires --msg");
+    let json = if path == "-" {
+        let mut json = String::new();
+        std::io::stdin()
+            .read_to_string(&mut json)
+            .expect("Failed to read the message from stdin");
+        json
+    } else {
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read {}: {}", path, e))
+    };
+    let policy = match args.flag_unknown_msgs.as_deref() {
+        Some(policy) => policy.parse().unwrap_or_else(|e| panic!("{}", e)),
+        None => UnknownMsgPolicy::default(),
+    };
+    let msgs = parse_msgs(&json, policy).unwrap_or_else(|e| panic!("{}", e));
+    let output = output_format(&args);
+
+    let mut invalid = false;
+    let mut text = String::new();
+    let mut views = Vec::new();
+    for (i, msg) in msgs.iter().enumerate() {
+        let explanation = explain(msg).unwrap_or_else(|e| panic!("{}", e));
+        let check = match (msg, explanation.commitment) {
+            (DecodedMsg::Known(known), Some(commitment)) if !args.flag_cosmos_grpc.is_empty() => {
+                let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+                let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+                let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+                let peggy_id = match args.flag_peggy_id.clone() {
+                    Some(peggy_id) => peggy_id,
+                    None => {
+                        let contract_address: EthAddress = args
+                            .flag_contract_address
+                            .parse()
+                            .expect("Invalid contract address!");
+                        let _ =
+                            Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+                        let web3 =
+                            Web3::new(args.flag_ethereum_rpc.trim_end_matches('/'), LOOP_SPEED);
+                        get_peggy_id_string(contract_address, contract_address, &web3)
+                            .await
+                            .expect("Failed to get the PeggyID from the contract")
+                    }
+                };
+                Some(check_signature(&mut grpc_client, known, commitment, peggy_id).await)
+            }
+            _ => None,
+        };
+        if i > 0 {
+            text.push('\n');
+        }
+        text.push_str(&format_explanation(msg, &explanation, check.as_ref()));
+        views.push(explanation_view(msg, &explanation, check.as_ref()));
+        if let Some(Ok(check)) = check {
+            invalid |= !check.valid;
+        }
+    }
+    output.print(&text, &views);
+    exit_unless(!invalid);
+}
+
+async fn preview_batch_main(args: Args) {
+    let token = parse_denom(&args.flag_denom.expect("preview-batch requires --denom"))
+        .unwrap_or_else(|e| panic!("{}", e));
+    let batch_size = match args.flag_batch_size {
+        Some(size) => size.trim().parse().expect("Invalid batch size!"),
+        None => DEFAULT_BATCH_SIZE,
+    };
+    let output = output_format(&args);
+
+    let _ = Url::parse(&args.flag_cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
+    let contact = Contact::new(
+        args.flag_cosmos_legacy_rpc.trim_end_matches('/'),
+        LOOP_SPEED,
+    );
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+    let web3 = Web3::new(args.flag_ethereum_rpc.trim_end_matches('/'), LOOP_SPEED);
+
+    let valuer = match args.flag_price_feed.as_deref() {
+        Some(feed) => {
+            let quoter = FeeQuoter {
+                quote: match args.flag_quote.as_deref() {
+                    Some(quote) => quote.parse().unwrap_or_else(|e| panic!("{}", e)),
+                    None => QuoteAsset::default(),
+                },
+                price_feed: new_price_feed(feed, &contact, &cosmos_grpc_url)
+                    .await
+                    .unwrap_or_else(|e| panic!("{}", e)),
+                oracle_client: connect_oracle(&cosmos_grpc_url)
+                    .await
+                    .unwrap_or_else(|e| panic!("{}", e)),
+            };
+            Some(
+                quoter
+                    .valuer()
+                    .await
+                    .expect("Failed to get prices from the price feed"),
+            )
+        }
+        None => None,
+    };
+
+    let mut oracle_client = connect_oracle(&cosmos_grpc_url)
+        .await
+        .unwrap_or_else(|e| panic!("{}", e));
+    let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let valset = get_current_valset(&mut grpc_client)
+        .await
+        .expect("Failed to get the current valset from the Hub");
+    let pool = get_outgoing_pool(&contact)
+        .await
+        .expect("Failed to get the outgoing pool from the Hub");
+    let gas_price = web3
+        .eth_gas_price()
+        .await
+        .expect("Failed to get the Ethereum gas price");
+
+    let preview = preview_batch(&pool, token, batch_size, valset.members.len());
+    let hub = hub_check(&contact, &mut oracle_client, token, &preview.total_fee).await;
+    let decimals = get_token_decimals(&mut oracle_client)
+        .await
+        .ok()
+        .and_then(|decimals| decimals.get(&token).copied());
+    output.print(
+        &format_preview(&preview, decimals, &gas_price, valuer.as_ref(), &hub),
+        &preview_view(&preview, &gas_price, valuer.as_ref(), &hub),
+    );
+}
+
+async fn faucet_main(args: Args) {
+    let path = args.flag_faucet.expect("faucet requires --faucet");
+    let faucet = FaucetConfig::load(Path::new(&path)).unwrap_or_else(|e| panic!("{}", e));
+    let _ = Url::parse(&args.flag_cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
+    let contact = Contact::new(
+        args.flag_cosmos_legacy_rpc.trim_end_matches('/'),
+        LOOP_SPEED,
+    );
+    let web3 = if args.flag_ethereum_rpc.is_empty() {
+        None
+    } else {
+        let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+        Some(Web3::new(
+            args.flag_ethereum_rpc.trim_end_matches('/'),
+            LOOP_SPEED,
+        ))
+    };
+    check_devnet(&contact, web3.as_ref())
+        .await
+        .unwrap_or_else(|e| panic!("{:?}", e));
+
+    let address_book = AddressBook::default();
+    if let Some(address) = args.flag_hub_address {
+        let address = address_book
+            .hub(&address)
+            .unwrap_or_else(|e| panic!("{}", e));
+        let res = fund_hub(&faucet, &contact, address)
+            .await
+            .expect("Failed to fund the Hub address");
+        println!("Funded {} from the faucet: {}", address, res);
+    }
+    if let Some(address) = args.flag_ethereum_address {
+        let address = address_book
+            .ethereum(&address)
+            .unwrap_or_else(|e| panic!("{}", e));
+        let web3 = web3.expect("Funding an Ethereum address requires --ethereum-rpc");
+        let res = fund_ethereum(&faucet, &web3, address)
+            .await
+            .expect("Failed to fund the Ethereum address");
+        println!("Funded {} from the faucet: {}", address, res);
+    }
+    if let Some(address) = args.flag_minter_address {
+        let address = address_book
+            .minter(&address)
+            .unwrap_or_else(|e| panic!("{}", e));
+        let res = fund_minter(&faucet, &address)
+            .await
+            .expect("Failed to fund the Minter address");
+        println!("Funded {} from the faucet: {}", address, res);
+    }
+}
+
+async fn register_main(args: Args) {
+    let _ = Url::parse(&args.flag_cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
+    let cosmos_legacy_url = args.flag_cosmos_legacy_rpc.trim_end_matches('/');
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let contact = Contact::new(&cosmos_legacy_url, LOOP_SPEED);
+    let fee = Coin {
+        denom: args.flag_fees,
+        amount: 1u32.into(),
+    };
+
+    let validator_key = if args.flag_validator_phrase.is_empty() {
+        None
+    } else {
+        Some(
+            CosmosPrivateKey::from_phrase(&secret(&args.flag_validator_phrase).await, "")
+                .expect("Invalid validator phrase!"),
+        )
+    };
+    let validator_address = match (validator_key, args.flag_validator_address) {
+        (Some(key), _) => key.to_public_key().unwrap().to_address(),
+        (None, Some(address)) => {
+            CosmosAddress::from_bech32(address).expect("Invalid validator address!")
+        }
+        (None, None) => panic!("register requires --validator-phrase or --validator-address"),
+    };
+
+    let cosmos_key = if args.flag_cosmos_phrase.is_empty() {
+        let phrase = Mnemonic::generate(24).unwrap();
+        let key = CosmosPrivateKey::from_phrase(phrase.as_str(), "").unwrap();
+        println!(
+            "No Cosmos phrase provided, your generated phrase is {} -> {}",
+            phrase,
+            key.to_public_key().unwrap().to_address()
+        );
+        key
+    } else {
+        CosmosPrivateKey::from_phrase(&secret(&args.flag_cosmos_phrase).await, "")
+            .expect("Invalid Cosmos phrase!")
+    };
+    let orchestrator_address = cosmos_key.to_public_key().unwrap().to_address();
+    let ethereum_address = if let Some(url) = args.flag_threshold_signer {
+        let _ = Url::parse(&url).expect("Invalid threshold signer url");
+        ThresholdSigner::connect(url.trim_end_matches('/'), LOOP_SPEED)
+            .await
+            .expect("Failed to get the address of the threshold signer")
+            .address()
+    } else if !args.flag_ethereum_key.is_empty() {
+        let key: EthPrivateKey = secret(&args.flag_ethereum_key)
+            .await
+            .parse()
+            .expect("Invalid Ethereum Private key!");
+        key.to_public_key().unwrap()
+    } else {
+        let key: [u8; 32] = thread_rng().gen();
+        let key = EthPrivateKey::from_slice(&key).unwrap();
+        println!(
+            "No Ethereum key provided, your generated key is {} -> {}",
+            key,
+            key.to_public_key().unwrap()
+        );
+        key.to_public_key().unwrap()
+    };
+
+    let conflicts = find_conflicts(&mut grpc_client, orchestrator_address, ethereum_address)
+        .await
+        .expect("Failed to check the delegate keys against the Hub");
+    for conflict in conflicts.iter() {
+        warn!("Conflicting registration: {}", conflict);
+    }
+    if !conflicts.is_empty() && !args.flag_force {
+        panic!(
+            "Refusing to register conflicting delegate keys, pass --force to register them anyway"
+        );
+    }
+    if let Some(path) = args.flag_faucet.as_deref() {
+        let faucet = FaucetConfig::load(Path::new(path)).unwrap_or_else(|e| panic!("{}", e));
+        let web3 = if args.flag_ethereum_rpc.is_empty() {
+            None
+        } else {
+            let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
+            Some(Web3::new(
+                args.flag_ethereum_rpc.trim_end_matches('/'),
+                LOOP_SPEED,
+            ))
+        };
+        check_devnet(&contact, web3.as_ref())
+            .await
+            .unwrap_or_else(|e| panic!("{:?}", e));
+        let res = fund_hub(&faucet, &contact, orchestrator_address)
+            .await
+            .expect("Failed to fund the orchestrator address");
+        println!("Funded {} from the faucet: {}", orchestrator_address, res);
+        if let (Some(web3), Some(_)) = (web3.as_ref(), faucet.ethereum.as_ref()) {
+            let res = fund_ethereum(&faucet, web3, ethereum_address)
+                .await
+                .expect("Failed to fund the Ethereum address");
+            println!("Funded {} from the faucet: {}", ethereum_address, res);
+        }
+    }
+    if !can_pay_fee(&contact, orchestrator_address, &fee).await {
+        warn!(
+            "Fund {} with {} before starting the orchestrator, it pays for claims and confirms",
+            orchestrator_address, fee.denom
+        );
+    }
+
+    match validator_key {
+        Some(validator_key) => {
+            if !can_pay_fee(&contact, validator_address, &fee).await {
+                panic!(
+                    "The validator account {} can't pay the {} fee",
+                    validator_address, fee.denom
+                );
+            }
+            let res = update_peggy_delegate_addresses(
+                &contact,
+                ethereum_address,
+                orchestrator_address,
+                validator_key,
+                fee,
+            )
+            .await
+            .map_err(PeggyError::from)
+            .and_then(check_tx_response)
+            .expect("Failed to register the delegate keys");
+            println!("Submitted the delegate keys with txid {}", res.txhash);
+        }
+        None => {
+            let tx = unsigned_registration_tx(
+                &contact,
+                validator_address,
+                ethereum_address,
+                orchestrator_address,
+                fee,
+            )
+            .await
+            .expect("Failed to build the registration transaction");
+            println!(
+                "Sign this transaction with the key of {} and broadcast it:\n{}",
+                validator_address,
+                serde_json::to_string_pretty(&tx).unwrap()
+            );
+            if args.flag_offline {
+                return;
+            }
+            println!("Waiting for the transaction to be broadcast");
+        }
+    }
+
+    let bonded = wait_for_registration(&mut grpc_client, orchestrator_address, ethereum_address)
+        .await
+        .expect("Registration failed");
+    println!(
+        "Registered Cosmos {} Ethereum {} for validator {}",
+        orchestrator_address, ethereum_address, validator_address
+    );
+    if !bonded {
+        println!(
+            "The validator is not bonded yet, its Ethereum address joins the valset once it is"
+        );
+    }
+}
+
+async fn rotate_keys_main(args: Args) {
+    let validator_key =
+        CosmosPrivateKey::from_phrase(&secret(&args.flag_validator_phrase).await, "")
+            .expect("Invalid validator phrase!");
+    let state_dir = args
+        .flag_state_dir
+        .expect("rotate-keys requires --state-dir");
+    let key = state_key(args.flag_state_key.as_deref()).await;
+    let storage = LocalStorage::open_with_key(Path::new(&state_dir), key)
+        .expect("Failed to open the orchestrator state dir");
+
+    let _ = Url::parse(&args.flag_cosmos_legacy_rpc).expect("Invalid Cosmos legacy RPC url");
+    let cosmos_legacy_url = args.flag_cosmos_legacy_rpc.trim_end_matches('/');
+
+    let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
+    let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
+
+    let mut grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
+    let contact = Contact::new(&cosmos_legacy_url, LOOP_SPEED);
+    let fee = Coin {
+        denom: args.flag_fees,
+        amount: 1u32.into(),
+    };
+
+    let keys = rotate_keys(&contact, &mut grpc_client, validator_key, fee, &storage)
+        .await
+        .expect("Key rotation failed");
+    println!(
+        "Registered new delegate keys Cosmos {} Ethereum {}, the running orchestrator will switch to them shortly",
+        keys.cosmos_address(),
+        keys.ethereum_address()
+    );
+    println!(
+        "Fund {} with ETH if this orchestrator also relays",
+        keys.ethereum_address()
+    );
+}
+
+async fn state_migrate_main(args: Args) {
+    let state_dir = args
+        .flag_state_dir
+        .expect("state migrate requires --state-dir");
+    let key = state_key(args.flag_state_key.as_deref()).await;
+    let storage = LocalStorage::open_for_migration(Path::new(&state_dir), key)
+        .expect("Failed to open the orchestrator state dir");
+    let migrations = if args.flag_check {
+        plan_migrations(&storage)
+    } else {
+        migrate_storage(&storage)
+    }
+    .unwrap_or_else(|e| panic!("{}", e));
+    let text: String = migrations
+        .iter()
+        .map(|migration| format!("{}\n", migration))
+        .collect();
+    output_format(&args).print(&text, &migrations);
+    // a check that finds work left to do fails, so deployments can gate on it
+    exit_unless(!args.flag_check || migrations.iter().all(|m| m.steps.is_empty()));
+}
+
+async fn stats_main(args: Args) {
+    let state_dir = args.flag_state_dir.expect("stats requires --state-dir");
+    let key = state_key(args.flag_state_key.as_deref()).await;
+    let storage = LocalStorage::open_with_key(Path::new(&state_dir), key)
+        .expect("Failed to open the orchestrator state dir");
+    let entries = storage
+        .journal
+        .entries()
+        .expect("Failed to read the journal");
+    if args.flag_transfers {
+        let transfers = list_transfers(&entries);
+        match args.flag_format.as_deref() {
+            None | Some("json") => {
+                println!("{}", serde_json::to_string_pretty(&transfers).unwrap())
+            }
+            Some("csv") => print!("{}", transfers_to_csv(&transfers)),
+            Some(format) => panic!("Unknown stats format {}, expected json or csv", format),
+        }
+        return;
+    }
+    let stats = compute_stats(&entries);
+    match args.flag_format.as_deref() {
+        None | Some("json") => println!("{}", serde_json::to_string_pretty(&stats).unwrap()),
+        Some("csv") => print!("{}", to_csv(&stats)),
+        Some(format) => panic!("Unknown stats format {}, expected json or csv", format),
+    }
+}
+
+fn config_lint_main(args: Args) {
+    let path = args.flag_chains.expect("config lint requires --chains");
+    let issues = lint_config_file(Path::new(&path));
+    let output = output_format(&args);
+    if output == OutputFormat::Json {
+        output.print("", &issues);
+        exit_unless(issues.is_empty());
+        return;
+    }
+    for issue in issues.iter() {
+        println!("{}", issue);
+    }
+    if !issues.is_empty() {
+        eprintln!("{} problems found in {}", issues.len(), path);
+        std::process::exit(EXIT_CHECK_FAILED);
+    }
+    println!("{} looks good", path);
+}
+
+fn admin_main(args: Args) {
+    let state_dir = args.flag_state_dir.expect("admin requires --state-dir");
+    let command = if args.cmd_pause_relaying {
+        AdminCommand::PauseRelaying
+    } else if args.cmd_resume_relaying {
+        AdminCommand::ResumeRelaying
+    } else if args.cmd_skip_batch {
+        AdminCommand::SkipBatch(SkippedBatch {
+            token_contract: args
+                .flag_token
+                .unwrap()
+                .parse()
+                .expect("Invalid token address!"),
+            nonce: args
+                .flag_batch_nonce
+                .unwrap()
+                .parse()
+                .expect("Invalid batch nonce!"),
+        })
+    } else if args.cmd_disable_token || args.cmd_enable_token {
+        let token: EthAddress = args
+            .flag_token
+            .unwrap()
+            .parse()
+            .expect("Invalid token address!");
+        if args.cmd_disable_token {
+            AdminCommand::DisableToken(token)
+        } else {
+            AdminCommand::EnableToken(token)
+        }
+    } else if args.cmd_rescan {
+        AdminCommand::Rescan(
+            args.flag_from_block
+                .unwrap()
+                .parse()
+                .expect("Invalid block number!"),
+        )
+    } else if args.cmd_release_claim {
+        AdminCommand::ReleaseClaim(
+            args.flag_event_nonce
+                .unwrap()
+                .parse()
+                .expect("Invalid event nonce!"),
+        )
+    } else if args.cmd_log_level {
+        AdminCommand::SetLogLevel(
+            args.flag_subsystem
+                .unwrap()
+                .parse()
+                .unwrap_or_else(|e| panic!("{}", e)),
+            parse_level(&args.flag_level.unwrap()).unwrap_or_else(|e| panic!("{}", e)),
+        )
+    } else {
+        unreachable!()
+    };
+    println!("{:?}", command);
+    submit_admin_command(Path::new(&state_dir), command)
+        .expect("Failed to write the admin instructions");
+    println!("The orchestrator running with this state dir will pick this up shortly");
 }
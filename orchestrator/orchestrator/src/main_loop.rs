@@ -2,28 +2,62 @@
 //! that can only be run by a validator. This single binary the 'Orchestrator' runs not only these two rules but also the untrusted role of a relayer, that does not need any permissions and has it's
 //! own crate and binary so that anyone may run it.
 
-use crate::{ethereum_event_watcher::check_for_events, oracle_resync::get_last_checked_block};
-use clarity::PrivateKey as EthPrivateKey;
+use crate::admin::{take_oracle_requests, watch_admin};
+use crate::anomaly::AnomalyDetector;
+use crate::claim_batching::ClaimBatching;
+use crate::claim_delay::{unix_now, ClaimDelay};
+use crate::claim_resubmission::ClaimResubmission;
+use crate::deposit_finality::DepositFinality;
+use crate::downtime::{
+    check_maintenance_window, get_downtime_tolerance, needs_backup_broadcast, BlockTimer,
+};
+use crate::enrichment::Enrichment;
+use crate::ethereum_event_watcher::{check_for_events, ScanWindow, MAX_BLOCKS_PER_SCAN};
+use crate::fee_payers::FeePayers;
+use crate::fee_top_up::FeeTopUp;
+use crate::journal::JournalEntry;
+use crate::key_rotation::{watch_for_rotation, KeyRing, OrchestratorKeys};
+use crate::log_levels::{ETH_SCANNER_TARGET, SIGNER_TARGET};
+use crate::oracle_resync::get_last_checked_block;
+use crate::param_watch::param_watch_loop;
+use crate::pause::{get_pause_state, log_pause_transition, PauseState};
+use crate::relay_receipts::{relay_receipt_loop, ReceiptQueue};
+use crate::runtime::{check_iteration, TaskQueues};
+use crate::signing_guard::SigningGuard;
+use crate::signing_report::signing_report_loop;
+use crate::solvency::{solvency_loop, MinterMultisig};
+use crate::stalled_transfers::stalled_transfer_loop;
+use crate::state_store::LocalStorage;
+use crate::supply_caps::SupplyCaps;
 use clarity::{address::Address as EthAddress, Uint256};
 use contact::client::Contact;
 use cosmos_peggy::{
+    eth_signer::{EthSigner, ThresholdSigner},
     query::{get_oldest_unsigned_transaction_batch, get_oldest_unsigned_valset},
     send::{send_batch_confirm, send_valset_confirm},
 };
+use deep_space::utils::bytes_to_hex_str;
 use deep_space::{coin::Coin, private_key::PrivateKey as CosmosPrivateKey};
+use ethereum_peggy::capabilities::get_contract_capabilities;
+use ethereum_peggy::checkpoint_cache::{batch_checkpoint, valset_checkpoint};
 use ethereum_peggy::utils::get_peggy_id;
-use futures::future::join3;
+use futures::future::{join, join3, join4, join5};
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
-use relayer::main_loop::relayer_main_loop;
+use peggy_utils::endpoints::{probe_loop, Endpoints, Purpose};
+use peggy_utils::error::{PeggyError, RejectionAction};
+use peggy_utils::timings::Timings;
+use peggy_utils::types::SignatureSelection;
+use relayer::main_loop::{relayer_main_loop, RelayRecorder, RelayedTx, RelayerControls};
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 use std::time::Instant;
 use tokio::time::delay_for;
 use tonic::transport::Channel;
 use web30::client::Web3;
 
-/// The execution speed governing all loops in this file
-/// which is to say all loops started by Orchestrator main
-/// loop except the relayer loop
+/// The polling speed of the admin and key rotation watchers, the main loops
+/// run at the configured `Timings::loop_speed`
 pub const LOOP_SPEED: Duration = Duration::from_secs(10);
 
 /// This loop combines the three major roles required to make
@@ -31,14 +65,38 @@ pub const LOOP_SPEED: Duration = Duration::from_secs(10);
 /// meaning they will occupy the same thread, but since they do
 /// very little actual cpu bound work and spend the vast majority
 /// of all execution time sleeping this shouldn't be an issue at all.
+#[allow(clippy::too_many_arguments)]
 pub async fn orchestrator_main_loop(
-    cosmos_key: CosmosPrivateKey,
-    ethereum_key: EthPrivateKey,
-    web3: Web3,
+    keys: KeyRing,
+    threshold_signer: Option<ThresholdSigner>,
+    ethereum_endpoints: Endpoints,
+    verify_web3: Option<Web3>,
     contact: Contact,
+    backup_contact: Option<Contact>,
     grpc_client: PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
+    deployment_block: Option<u64>,
     pay_fees_in: String,
+    fee_payers: FeePayers,
+    supply_caps: SupplyCaps,
+    deposit_finality: DepositFinality,
+    claim_delay: ClaimDelay,
+    claim_batching: ClaimBatching,
+    claim_resubmission: ClaimResubmission,
+    anomalies: AnomalyDetector,
+    enrichment: Enrichment,
+    configured_controls: RelayerControls,
+    signatures: SignatureSelection,
+    force_valset_relay_below: Option<f32>,
+    maintenance_window: Option<u64>,
+    stalled_transfer_alert: Option<u64>,
+    fee_top_up: Option<(FeeTopUp, OracleQueryClient<Channel>)>,
+    solvency_watch: Option<(OracleQueryClient<Channel>, MinterMultisig)>,
+    enforce_min_version: bool,
+    relay_receipts: bool,
+    task_queues: TaskQueues,
+    timings: Timings,
+    storage: Option<LocalStorage>,
 ) {
     let fee = Coin {
         denom: pay_fees_in.clone(),
@@ -46,56 +104,247 @@ pub async fn orchestrator_main_loop(
     };
 
     let a = eth_oracle_main_loop(
-        cosmos_key,
-        web3.clone(),
+        keys.clone(),
+        ethereum_endpoints.clone(),
+        verify_web3,
         contact.clone(),
         grpc_client.clone(),
         peggy_contract_address,
+        deployment_block,
         fee.clone(),
+        fee_payers.claims,
+        supply_caps,
+        deposit_finality,
+        claim_delay,
+        claim_batching,
+        claim_resubmission,
+        anomalies,
+        enrichment,
+        enforce_min_version,
+        timings,
+        storage.clone(),
     );
     let b = eth_signer_main_loop(
-        cosmos_key,
-        ethereum_key,
-        web3.clone(),
+        keys.clone(),
+        threshold_signer,
+        ethereum_endpoints.clone(),
         contact.clone(),
+        backup_contact,
         grpc_client.clone(),
         peggy_contract_address,
         fee.clone(),
+        fee_payers,
+        maintenance_window,
+        enforce_min_version,
+        timings,
+        storage.clone(),
     );
+    let relayer_controls = Arc::new(RwLock::new(configured_controls.clone()));
+    let receipts: Option<ReceiptQueue> = if relay_receipts {
+        Some(task_queues.queue("relay_receipts"))
+    } else {
+        None
+    };
+    // Ethereum relays are journaled so the next startup can check whether they were mined, and queued
+    // for their receipts
+    let on_relay = match (storage.clone(), receipts.clone()) {
+        (None, None) => None,
+        (journal, receipts) => Some(Arc::new(move |tx: RelayedTx| {
+            if let Some(receipts) = receipts.as_ref() {
+                receipts.push(tx.clone());
+            }
+            if let Some(storage) = journal.as_ref() {
+                let entry = JournalEntry::relayed(tx, unix_now());
+                if let Err(e) = storage.journal.append(&entry) {
+                    error!("Failed to journal {:?}: {}", entry, e);
+                }
+            }
+        }) as RelayRecorder),
+    };
     let c = relayer_main_loop(
-        ethereum_key,
-        web3,
+        keys.current().ethereum_key,
+        ethereum_endpoints.clone(),
         grpc_client.clone(),
         peggy_contract_address,
+        signatures,
+        timings,
+        Some(keys.relayer_key()),
+        Some(relayer_controls.clone()),
+        force_valset_relay_below,
+        None,
+        None,
+        on_relay,
+    );
+    let d = join4(
+        signing_report_loop(
+            contact.clone(),
+            grpc_client.clone(),
+            keys.clone(),
+            ethereum_endpoints.clone(),
+            peggy_contract_address,
+            storage.clone(),
+        ),
+        stalled_transfer_loop(
+            contact.clone(),
+            grpc_client.clone(),
+            stalled_transfer_alert,
+            fee_top_up,
+            keys.clone(),
+            fee.clone(),
+            relayer_controls.clone(),
+            storage.clone(),
+        ),
+        solvency_loop(
+            contact.clone(),
+            solvency_watch,
+            ethereum_endpoints.clone(),
+            peggy_contract_address,
+            storage.clone(),
+        ),
+        join3(
+            probe_loop(ethereum_endpoints),
+            param_watch_loop(grpc_client.clone(), peggy_contract_address, storage.clone()),
+            relay_receipt_loop(contact, keys.clone(), fee.clone(), receipts),
+        ),
     );
-    join3(a, b, c).await;
+    let e = async move {
+        if let Some(storage) = storage {
+            let dir = storage.dir.clone();
+            join(
+                watch_for_rotation(keys, grpc_client, storage),
+                watch_admin(&dir, configured_controls, relayer_controls),
+            )
+            .await;
+        }
+    };
+    join5(a, b, c, d, e).await;
 }
 
 /// This function is responsible for making sure that Ethereum events are retrieved from the Ethereum blockchain
 /// and ferried over to Cosmos where they will be used to issue tokens or process batches.
 /// TODO this loop requires a method to bootstrap back to the correct event nonce when restarted
+#[allow(clippy::too_many_arguments)]
 pub async fn eth_oracle_main_loop(
-    cosmos_key: CosmosPrivateKey,
-    web3: Web3,
+    keys: KeyRing,
+    ethereum_endpoints: Endpoints,
+    verify_web3: Option<Web3>,
     contact: Contact,
     grpc_client: PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
+    deployment_block: Option<u64>,
     fee: Coin,
+    fee_payer: Option<CosmosPrivateKey>,
+    supply_caps: SupplyCaps,
+    deposit_finality: DepositFinality,
+    claim_delay: ClaimDelay,
+    claim_batching: ClaimBatching,
+    claim_resubmission: ClaimResubmission,
+    anomalies: AnomalyDetector,
+    enrichment: Enrichment,
+    enforce_min_version: bool,
+    timings: Timings,
+    storage: Option<LocalStorage>,
 ) {
-    let our_cosmos_address = cosmos_key.to_public_key().unwrap().to_address();
-    let long_timeout_web30 = Web3::new(&web3.get_url(), Duration::from_secs(120));
+    let our_cosmos_address = keys.current().cosmos_address();
+    let long_timeout_web30 =
+        ethereum_endpoints.web3_with_timeout(Purpose::Backfill, Duration::from_secs(120));
     let mut last_checked_block: Uint256 = get_last_checked_block(
         grpc_client.clone(),
         our_cosmos_address,
         peggy_contract_address,
         &long_timeout_web30,
+        timings.eth_confirmations,
+        deployment_block,
     )
     .await;
-    info!("Oracle resync complete, Oracle now operational");
+    info!(target: ETH_SCANNER_TARGET, "Oracle resync complete, Oracle now operational");
     let mut grpc_client = grpc_client;
+    let mut pause_state = PauseState::default();
+    let mut scan_window = ScanWindow::default();
+    let mut claim_delay = claim_delay;
+    let mut claim_batching = claim_batching;
+    let mut claim_resubmission = claim_resubmission;
+    let mut anomalies = anomalies;
+    if let Some(storage) = storage.as_ref() {
+        match storage.state.load() {
+            Ok(state) => claim_delay.restore(&state.delayed_claims),
+            Err(e) => error!(
+                target: ETH_SCANNER_TARGET,
+                "Failed to load delayed claims from the state store: {}",
+                e
+            ),
+        }
+    }
 
     loop {
         let loop_start = Instant::now();
+        // keys only change between iterations, never halfway through a submission
+        let OrchestratorKeys { cosmos_key, .. } = keys.current();
+        let our_cosmos_address = cosmos_key.to_public_key().unwrap().to_address();
+
+        if let Some(storage) = storage.as_ref() {
+            match take_oracle_requests(&storage.dir) {
+                Ok((rescan_from_block, released_claims)) => {
+                    if let Some(block) = rescan_from_block {
+                        warn!(
+                            target: ETH_SCANNER_TARGET,
+                            "Rescanning Ethereum from block {} as instructed by the operator",
+                            block
+                        );
+                        last_checked_block = block;
+                    }
+                    for nonce in released_claims {
+                        if claim_delay.release(nonce) {
+                            warn!(
+                                target: ETH_SCANNER_TARGET,
+                                "Signing the delayed claim for event nonce {} now as instructed by the operator",
+                                nonce
+                            );
+                        } else {
+                            warn!(
+                                target: ETH_SCANNER_TARGET,
+                                "No delayed claim with event nonce {} to release",
+                                nonce
+                            );
+                        }
+                    }
+                }
+                Err(e) => error!(
+                    target: ETH_SCANNER_TARGET,
+                    "Failed to read admin instructions: {}",
+                    e
+                ),
+            }
+        }
+
+        // far behind the chain head is a backfill, near it every block counts
+        let catching_up = ethereum_endpoints
+            .latest_block()
+            .map(|latest| {
+                Uint256::from(latest) > last_checked_block.clone() + MAX_BLOCKS_PER_SCAN.into()
+            })
+            .unwrap_or(false);
+        let web3 = ethereum_endpoints.web3(if catching_up {
+            Purpose::Backfill
+        } else {
+            Purpose::TimeCritical
+        });
+
+        match get_pause_state(&web3, &mut grpc_client, peggy_contract_address).await {
+            Ok(state) => {
+                log_pause_transition("Oracle", pause_state, state);
+                pause_state = state;
+            }
+            Err(e) => warn!(
+                target: ETH_SCANNER_TARGET,
+                "Failed to get the bridge pause state {:?}",
+                e
+            ),
+        }
+        if pause_state.signing_paused(enforce_min_version) {
+            delay_for(timings.loop_speed).await;
+            continue;
+        }
 
         let latest_eth_block = web3.eth_block_number().await;
         let latest_cosmos_block = contact.get_latest_block_number().await;
@@ -103,6 +352,7 @@ pub async fn eth_oracle_main_loop(
             (latest_eth_block, latest_cosmos_block)
         {
             trace!(
+                target: ETH_SCANNER_TARGET,
                 "Latest Eth block {} Latest Cosmos block {}",
                 latest_eth_block,
                 latest_cosmos_block,
@@ -112,120 +362,446 @@ pub async fn eth_oracle_main_loop(
         // Relays events from Ethereum -> Cosmos
         match check_for_events(
             &web3,
+            verify_web3.as_ref(),
             &contact,
             &mut grpc_client,
             peggy_contract_address,
             cosmos_key,
             fee.clone(),
+            fee_payer,
             last_checked_block.clone(),
+            &mut scan_window,
+            timings.eth_confirmations,
+            &deposit_finality,
+            pause_state,
+            &supply_caps,
+            &mut claim_delay,
+            &mut claim_batching,
+            &mut claim_resubmission,
+            &mut anomalies,
+            &enrichment,
+            storage.as_ref(),
         )
         .await
         {
-            Ok(new_block) => last_checked_block = new_block,
-            Err(e) => error!(
-                "Failed to get events for block range, Check your Eth node and Cosmos gRPC {:?}",
-                e
-            ),
+            Ok(new_block) => {
+                if let Some(storage) = storage.as_ref() {
+                    if let Err(e) = storage
+                        .state
+                        .update(|state| state.last_checked_block = Some(new_block.clone()))
+                    {
+                        error!(target: ETH_SCANNER_TARGET, "Failed to update state store: {}", e);
+                    }
+                }
+                last_checked_block = new_block
+            }
+            Err(PeggyError::CosmosTxRejected(rejection)) => {
+                if let Some(storage) = storage.as_ref() {
+                    storage.count_error("claims_rejected");
+                }
+                match rejection.action() {
+                    RejectionAction::Resync => {
+                        warn!(
+                            target: ETH_SCANNER_TARGET,
+                            "Claims rejected, resyncing our event nonce: {}",
+                            rejection
+                        );
+                        last_checked_block = get_last_checked_block(
+                            grpc_client.clone(),
+                            our_cosmos_address,
+                            peggy_contract_address,
+                            &ethereum_endpoints
+                                .web3_with_timeout(Purpose::Backfill, Duration::from_secs(120)),
+                            timings.eth_confirmations,
+                            deployment_block,
+                        )
+                        .await;
+                    }
+                    RejectionAction::Halt if keys.rotation_pending() => {
+                        warn!(
+                            target: ETH_SCANNER_TARGET,
+                            "Claims rejected during a key rotation, retrying with the new keys: {}",
+                            rejection
+                        )
+                    }
+                    RejectionAction::Halt => {
+                        error!(
+                            target: ETH_SCANNER_TARGET,
+                            "Claims rejected, halting the Oracle: {}",
+                            rejection
+                        );
+                        return;
+                    }
+                    RejectionAction::Skip | RejectionAction::Retry => {
+                        warn!(
+                            target: ETH_SCANNER_TARGET,
+                            "Claims rejected, trying again later: {}",
+                            rejection
+                        )
+                    }
+                }
+            }
+            Err(e) => {
+                if let Some(storage) = storage.as_ref() {
+                    storage.count_error("oracle");
+                }
+                error!(
+                    target: ETH_SCANNER_TARGET,
+                    "Failed to get events for block range, Check your Eth node and Cosmos gRPC {:?}",
+                    e
+                )
+            }
+        }
+
+        // publish the claims held back by the claim delay so operators can see them in the tui
+        if let (Some(storage), true) = (storage.as_ref(), claim_delay.is_enabled()) {
+            let pending = claim_delay.pending();
+            if let Err(e) = storage.state.update(|state| state.delayed_claims = pending) {
+                error!(target: ETH_SCANNER_TARGET, "Failed to update state store: {}", e);
+            }
+        }
+        // and the claims waiting to show up on the Hub, with how often they were sent
+        if let (Some(storage), true) = (storage.as_ref(), claim_resubmission.is_enabled()) {
+            let pending = claim_resubmission.pending();
+            if let Err(e) = storage
+                .state
+                .update(|state| state.claim_submission = pending)
+            {
+                error!(target: ETH_SCANNER_TARGET, "Failed to update state store: {}", e);
+            }
         }
 
-        // a bit of logic that tires to keep things running every LOOP_SPEED seconds exactly
+        // a bit of logic that tires to keep things running every loop_speed seconds exactly
         // this is not required for any specific reason. In fact we expect and plan for
         // the timing being off significantly
         let elapsed = Instant::now() - loop_start;
-        if elapsed < LOOP_SPEED {
-            delay_for(LOOP_SPEED - elapsed).await;
+        check_iteration("Oracle", elapsed);
+        if elapsed < timings.loop_speed {
+            delay_for(timings.loop_speed - elapsed).await;
         }
     }
 }
 
 /// The eth_signer simply signs off on any batches or validator sets provided by the validator
 /// since these are provided directly by a trusted Cosmsos node they can simply be assumed to be
-/// valid and signed off on.
+/// valid and signed off on. `maintenance_window` is in seconds, we warn if we couldn't be offline
+/// that long without missing a signing window. With `enforce_min_version` nothing is signed while
+/// we are older than the minimum orchestrator version set by governance. Valset confirms close to
+/// their deadline are broadcast through `backup_contact` as well, if there is one. Nothing is signed
+/// twice for the same valset or batch with a different checkpoint, see `signing_guard`.
+#[allow(clippy::too_many_arguments)]
 pub async fn eth_signer_main_loop(
-    cosmos_key: CosmosPrivateKey,
-    ethereum_key: EthPrivateKey,
-    web3: Web3,
+    keys: KeyRing,
+    threshold_signer: Option<ThresholdSigner>,
+    ethereum_endpoints: Endpoints,
     contact: Contact,
+    backup_contact: Option<Contact>,
     grpc_client: PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
     fee: Coin,
+    fee_payers: FeePayers,
+    maintenance_window: Option<u64>,
+    enforce_min_version: bool,
+    timings: Timings,
+    storage: Option<LocalStorage>,
 ) {
-    let our_cosmos_address = cosmos_key.to_public_key().unwrap().to_address();
-    let our_ethereum_address = ethereum_key.to_public_key().unwrap();
+    let our_ethereum_address = keys.current().ethereum_address();
     let mut grpc_client = grpc_client;
+    let web3 = ethereum_endpoints.web3(Purpose::TimeCritical);
     let peggy_id = get_peggy_id(peggy_contract_address, our_ethereum_address, &web3).await;
     if peggy_id.is_err() {
-        error!("Failed to get PeggyID, check your Eth node: {}", peggy_id.unwrap_err());
+        error!(
+            target: SIGNER_TARGET,
+            "Failed to get PeggyID, check your Eth node: {}",
+            peggy_id.unwrap_err()
+        );
         return;
     }
     let peggy_id = peggy_id.unwrap();
     let peggy_id = String::from_utf8(peggy_id.clone()).expect("Invalid PeggyID");
+    let capabilities = match get_contract_capabilities(peggy_contract_address, &web3).await {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            error!(
+                target: SIGNER_TARGET,
+                "Failed to detect the contract version, check your Eth node: {}",
+                e
+            );
+            return;
+        }
+    };
+    info!(target: SIGNER_TARGET, "Peggy contract {}", capabilities);
+    for feature in capabilities.unsupported() {
+        warn!(
+            target: SIGNER_TARGET,
+            "The contract supports {}, this orchestrator ignores them",
+            feature
+        );
+    }
+    // confirms are signed in the encoding the contract takes, the Hub accepts both
+    let signature_encoding = capabilities.signature_encoding;
+    let mut pause_state = PauseState::default();
+    let mut block_timer = BlockTimer::default();
+    let mut signing_guard = SigningGuard::load(storage.clone());
 
     loop {
         let loop_start = Instant::now();
+        // keys only change between iterations, never halfway through a confirm
+        let OrchestratorKeys {
+            cosmos_key,
+            ethereum_key,
+        } = keys.current();
+        let our_cosmos_address = cosmos_key.to_public_key().unwrap().to_address();
+        let eth_signer: &dyn EthSigner = match threshold_signer.as_ref() {
+            Some(signer) => signer,
+            None => &ethereum_key,
+        };
+        let web3 = ethereum_endpoints.web3(Purpose::TimeCritical);
+
+        match get_pause_state(&web3, &mut grpc_client, peggy_contract_address).await {
+            Ok(state) => {
+                log_pause_transition("Eth signer", pause_state, state);
+                pause_state = state;
+            }
+            Err(e) => warn!(target: SIGNER_TARGET, "Failed to get the bridge pause state {:?}", e),
+        }
+        if pause_state.signing_paused(enforce_min_version) {
+            delay_for(timings.loop_speed).await;
+            continue;
+        }
 
         let latest_eth_block = web3.eth_block_number().await;
         let latest_cosmos_block = contact.get_latest_block_number().await;
         if let (Ok(latest_eth_block), Ok(latest_cosmos_block)) =
-            (latest_eth_block, latest_cosmos_block)
+            (&latest_eth_block, &latest_cosmos_block)
         {
             trace!(
+                target: SIGNER_TARGET,
                 "Latest Eth block {} Latest Cosmos block {}",
                 latest_eth_block,
                 latest_cosmos_block
             );
         }
 
-        // sign the last unsigned valset, TODO check if we already have signed this
+        // sign the last unsigned valset, unless we signed another checkpoint for its nonce
         match get_oldest_unsigned_valset(&mut grpc_client, our_cosmos_address).await {
+            Ok(Some(valset))
+                if !signing_guard.allow_valset(
+                    valset.nonce,
+                    &bytes_to_hex_str(&valset_checkpoint(&peggy_id, &valset).signed_hash),
+                ) => {}
             Ok(Some(last_unsigned_valset)) => {
-                info!("Sending valset confirm for {}", last_unsigned_valset.nonce);
+                info!(
+                    target: SIGNER_TARGET,
+                    "Sending valset confirm for {}",
+                    last_unsigned_valset.nonce
+                );
+                let nonce = last_unsigned_valset.nonce;
+                // valset nonces are the height the valset was created at
+                let backup = match backup_contact.as_ref() {
+                    Some(backup)
+                        if needs_backup_broadcast(
+                            &mut grpc_client,
+                            latest_cosmos_block.as_ref().ok().copied(),
+                            nonce,
+                        )
+                        .await =>
+                    {
+                        warn!(
+                            target: SIGNER_TARGET,
+                            "Valset {} is close to its signing deadline, broadcasting the confirm through the backup Hub node too",
+                            nonce
+                        );
+                        Some(backup)
+                    }
+                    _ => None,
+                };
                 let res = send_valset_confirm(
                     &contact,
-                    ethereum_key,
+                    backup,
+                    eth_signer,
                     fee.clone(),
                     last_unsigned_valset,
                     cosmos_key,
                     peggy_id.clone(),
+                    fee_payers.valset_confirms,
+                    signature_encoding,
                 )
                 .await;
-                trace!("Valset confirm result is {:?}", res);
+                trace!(target: SIGNER_TARGET, "Valset confirm result is {:?}", res);
+                if let (Ok(res), Some(storage)) = (&res, storage.as_ref()) {
+                    storage.record(
+                        JournalEntry::ValsetConfirm {
+                            nonce,
+                            txhash: res.txhash.clone(),
+                        },
+                        |state| {
+                            state.last_valset_confirm_nonce = nonce;
+                            state.count("valset_confirms", 1);
+                        },
+                    );
+                }
+                if !handle_confirm_result("Valset", res, storage.as_ref(), &keys) {
+                    return;
+                }
             }
-            Ok(None) => trace!("No valset waiting to be signed!"),
+            Ok(None) => trace!(target: SIGNER_TARGET, "No valset waiting to be signed!"),
             Err(e) => trace!(
+                target: SIGNER_TARGET,
                 "Failed to get unsigned valsets, check your Cosmos gRPC {:?}",
                 e
             ),
         }
 
-        // sign the last unsigned batch, TODO check if we already have signed this
+        // sign the last unsigned batch, unless we signed another checkpoint for its token and nonce
         match get_oldest_unsigned_transaction_batch(&mut grpc_client, our_cosmos_address).await {
+            Ok(Some(batch))
+                if !signing_guard.allow_batch(
+                    batch.token_contract,
+                    batch.nonce,
+                    &bytes_to_hex_str(&batch_checkpoint(&peggy_id, &batch).signed_hash),
+                ) => {}
             Ok(Some(last_unsigned_batch)) => {
-                info!("Sending batch confirm for {}", last_unsigned_batch.nonce);
+                info!(
+                    target: SIGNER_TARGET,
+                    "Sending batch confirm for {}",
+                    last_unsigned_batch.nonce
+                );
+                let nonce = last_unsigned_batch.nonce;
+                let token_contract = last_unsigned_batch.token_contract;
+                // checked when the batch was parsed
+                let total_amount = last_unsigned_batch
+                    .total_amount()
+                    .map(|total| total.amount)
+                    .unwrap_or_default();
+                let total_fee = last_unsigned_batch.total_fee.amount.clone();
                 let res = send_batch_confirm(
                     &contact,
-                    ethereum_key,
+                    eth_signer,
                     fee.clone(),
                     last_unsigned_batch,
                     cosmos_key,
                     peggy_id.clone(),
+                    fee_payers.batch_confirms,
+                    signature_encoding,
                 )
                 .await;
-                trace!("Batch confirm result is {:?}", res);
+                trace!(target: SIGNER_TARGET, "Batch confirm result is {:?}", res);
+                if let (Ok(res), Some(storage)) = (&res, storage.as_ref()) {
+                    storage.record(
+                        JournalEntry::BatchConfirm {
+                            nonce,
+                            token_contract,
+                            txhash: res.txhash.clone(),
+                            total_amount,
+                            total_fee,
+                            timestamp: unix_now(),
+                        },
+                        |state| {
+                            state.last_batch_confirm_nonce = nonce;
+                            state.count("batch_confirms", 1);
+                        },
+                    );
+                }
+                if !handle_confirm_result("Batch", res, storage.as_ref(), &keys) {
+                    return;
+                }
             }
-            Ok(None) => trace!("No unsigned batches! Everything good!"),
+            Ok(None) => trace!(target: SIGNER_TARGET, "No unsigned batches! Everything good!"),
             Err(e) => trace!(
+                target: SIGNER_TARGET,
                 "Failed to get unsigned Batches, check your Cosmos gRPC {:?}",
                 e
             ),
         }
 
-        // a bit of logic that tires to keep things running every LOOP_SPEED seconds exactly
+        // whatever is still unsigned now counts against the signing windows
+        if let Ok(height) = latest_cosmos_block {
+            match get_downtime_tolerance(
+                &mut grpc_client,
+                our_cosmos_address,
+                height,
+                &mut block_timer,
+            )
+            .await
+            {
+                Ok(Some(tolerance)) => {
+                    if let Some(maintenance_window) = maintenance_window {
+                        check_maintenance_window(&tolerance, maintenance_window);
+                    }
+                    if let Some(storage) = storage.as_ref() {
+                        if let Err(e) = storage
+                            .state
+                            .update(|state| state.downtime_tolerance = Some(tolerance))
+                        {
+                            error!(target: SIGNER_TARGET, "Failed to update state store: {}", e);
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => trace!(
+                    target: SIGNER_TARGET,
+                    "Failed to estimate the downtime tolerance {:?}",
+                    e
+                ),
+            }
+        }
+
+        // a bit of logic that tires to keep things running every loop_speed seconds exactly
         // this is not required for any specific reason. In fact we expect and plan for
         // the timing being off significantly
         let elapsed = Instant::now() - loop_start;
-        if elapsed < LOOP_SPEED {
-            delay_for(LOOP_SPEED - elapsed).await;
+        check_iteration("Eth signer", elapsed);
+        if elapsed < timings.loop_speed {
+            delay_for(timings.loop_speed - elapsed).await;
+        }
+    }
+}
+
+/// Logs the outcome of a valset or batch confirm, returns false if the rejection
+/// means nothing we sign will ever be accepted and the signer should stop
+fn handle_confirm_result<T>(
+    kind: &str,
+    res: Result<T, PeggyError>,
+    storage: Option<&LocalStorage>,
+    keys: &KeyRing,
+) -> bool {
+    if let (Err(_), Some(storage)) = (&res, storage) {
+        storage.count_error(&format!("{}_confirm", kind.to_lowercase()));
+    }
+    match res {
+        Ok(_) => true,
+        Err(PeggyError::CosmosTxRejected(rejection)) => match rejection.action() {
+            // our old keys stop being accepted the moment the new ones are registered
+            RejectionAction::Halt if keys.rotation_pending() => {
+                warn!(
+                    "{} confirm rejected during a key rotation, retrying with the new keys: {}",
+                    kind, rejection
+                );
+                true
+            }
+            RejectionAction::Halt => {
+                error!(
+                    "{} confirm rejected, halting the signer: {}",
+                    kind, rejection
+                );
+                false
+            }
+            RejectionAction::Skip => {
+                info!("{} confirm already submitted: {}", kind, rejection);
+                true
+            }
+            RejectionAction::Resync | RejectionAction::Retry => {
+                warn!(
+                    "{} confirm rejected, trying again later: {}",
+                    kind, rejection
+                );
+                true
+            }
+        },
+        Err(e) => {
+            warn!("{} confirm failed, trying again later: {}", kind, e);
+            true
         }
     }
 }
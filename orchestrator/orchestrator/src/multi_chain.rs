@@ -0,0 +1,233 @@
+//! Running several Hub chains, for example mainnet and a testnet, from one orchestrator process. `orchestrator multi`
+//! reads a json file with one entry per chain, every entry takes the same settings as the command line flags and
+//! runs as a fully independent orchestrator with its own keys, contract, state dir and error counters. The state
+//! dir is mandatory here and can't be shared, otherwise the chains would overwrite each others progress. With a
+//! `"profile"` the network's contract, PeggyID, fee denom and endpoints can be left out, see `profiles`.
+//!
+//! ```json
+//! [
+//!     {
+//!         "name": "mainnet",
+//!         "cosmos_phrase": "...",
+//!         "ethereum_key": "0x...",
+//!         "cosmos_legacy_rpc": "http://localhost:1317",
+//!         "cosmos_grpc": "http://localhost:9090",
+//!         "ethereum_rpc": "http://localhost:8545",
+//!         "contract_address": "0x...",
+//!         "fees": "hub",
+//!         "peggy_id": "minter-hub-1",
+//!         "state_dir": "/var/lib/orchestrator/mainnet"
+//!     }
+//! ]
+//! ```
+
+use crate::profiles::resolve_profile;
+use peggy_utils::error::PeggyError;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// Everything needed to run the orchestrator for one Hub chain, see the command line flags of the same name
+#[derive(Deserialize, Debug, Clone, Default, Eq, PartialEq)]
+pub struct ChainConfig {
+    /// a label for the chain, logged at startup
+    pub name: String,
+    /// mainnet, testnet or local, fills in the settings left empty
+    #[serde(default)]
+    pub profile: Option<String>,
+    pub cosmos_phrase: String,
+    pub ethereum_key: String,
+    #[serde(default)]
+    pub cosmos_legacy_rpc: String,
+    #[serde(default)]
+    pub cosmos_grpc: String,
+    /// a second Hub node valset confirms close to their deadline are broadcast through too
+    #[serde(default)]
+    pub cosmos_backup_rpc: Option<String>,
+    /// comma separated, the fastest up to date one is used
+    #[serde(default)]
+    pub ethereum_rpc: String,
+    #[serde(default)]
+    pub ethereum_verify_rpc: Option<String>,
+    /// comma separated, used to rescan old blocks instead of `ethereum_rpc`
+    #[serde(default)]
+    pub ethereum_backfill_rpc: Option<String>,
+    #[serde(default)]
+    pub contract_address: String,
+    #[serde(default)]
+    pub fees: String,
+    /// the peggy id the contract must report, for whitelabel deployments with their own id
+    #[serde(default)]
+    pub peggy_id: Option<String>,
+    /// the block the contract was deployed in or `auto`, the oracle never scans before it
+    #[serde(default)]
+    pub deployment_block: Option<String>,
+    #[serde(default)]
+    pub fee_payer_phrase: Option<String>,
+    #[serde(default)]
+    pub fee_payer_for: Option<String>,
+    #[serde(default)]
+    pub supply_caps: Option<String>,
+    /// comma separated `<erc20>:<amount>:<confirmations>` bands, deeper confirmations for larger deposits
+    #[serde(default)]
+    pub deposit_finality: Option<String>,
+    #[serde(default)]
+    pub delay_claims_above: Option<String>,
+    #[serde(default)]
+    pub claim_delay: Option<String>,
+    /// seconds claims are gathered for before they are sent together
+    #[serde(default)]
+    pub claim_batch_window: Option<String>,
+    #[serde(default)]
+    pub claim_batch_size: Option<String>,
+    /// seconds sent claims have to show up on the Hub before they are sent again
+    #[serde(default)]
+    pub claim_ttl: Option<String>,
+    #[serde(default)]
+    pub claim_retries: Option<String>,
+    #[serde(default)]
+    pub detect_anomalies: bool,
+    #[serde(default)]
+    pub anomaly_delay: bool,
+    /// minutes a withdrawal may wait in the outgoing pool before we alert on it
+    #[serde(default)]
+    pub stalled_transfer_alert: Option<String>,
+    /// comma separated `<erc20>:<fee>` caps up to which our own stalled withdrawals are topped up
+    #[serde(default)]
+    pub top_up_fees: Option<String>,
+    #[serde(default)]
+    pub top_up_percent: Option<String>,
+    /// check that the Peggy contract and Minter multisig back the Hub's supply of every coin
+    #[serde(default)]
+    pub watch_solvency: bool,
+    /// a Minter Gate v2 api url
+    #[serde(default)]
+    pub minter_api: Option<String>,
+    #[serde(default)]
+    pub minter_multisig: Option<String>,
+    #[serde(default)]
+    pub skip_batches: Option<String>,
+    /// comma separated token contracts whose batches aren't relayed
+    #[serde(default)]
+    pub disable_tokens: Option<String>,
+    /// a json file of the sponsors whose users' withdrawals are always relayed
+    #[serde(default)]
+    pub sponsors: Option<String>,
+    #[serde(default)]
+    pub signatures: Option<String>,
+    /// percentage of valset overlap with the Hub below which valset updates are relayed regardless of gas
+    #[serde(default)]
+    pub force_valset_relay_below: Option<String>,
+    #[serde(default)]
+    pub maintenance_window: Option<String>,
+    #[serde(default)]
+    pub enforce_min_version: bool,
+    /// report our relays to the Hub with `MsgRelayReceipt`
+    #[serde(default)]
+    pub relay_receipts: bool,
+    /// comma separated `<task>:<capacity>` queue sizes, see `runtime::TaskQueues`
+    #[serde(default)]
+    pub task_queues: Option<String>,
+    /// a json file of `Timings` overrides
+    #[serde(default)]
+    pub timings: Option<String>,
+    /// url of the threshold signer that signs our confirms
+    #[serde(default)]
+    pub threshold_signer: Option<String>,
+    /// minutes the primary may leave work pending before this backup takes over
+    #[serde(default)]
+    pub standby: Option<String>,
+    /// a json file of address labels
+    #[serde(default)]
+    pub address_labels: Option<String>,
+    /// `hub` or a json file of fixed prices
+    #[serde(default)]
+    pub price_feed: Option<String>,
+    #[serde(default)]
+    pub fold_minter_deposits: bool,
+    #[serde(default)]
+    pub state_dir: Option<String>,
+    /// a secret or secret reference the state dir is encrypted with
+    #[serde(default)]
+    pub state_key: Option<String>,
+    #[serde(default)]
+    pub verify_restart: bool,
+}
+
+/// Loads and checks the chain list for `orchestrator multi`
+pub fn load_chain_configs(path: &Path) -> Result<Vec<ChainConfig>, PeggyError> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        PeggyError::InvalidBridgeStateError(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    let mut configs: Vec<ChainConfig> = serde_json::from_str(&contents).map_err(|e| {
+        PeggyError::InvalidBridgeStateError(format!("Failed to parse {}: {}", path.display(), e))
+    })?;
+    for config in configs.iter_mut() {
+        resolve_profile(config).map_err(PeggyError::InvalidBridgeStateError)?;
+    }
+    check_chain_configs(&configs).map_err(PeggyError::InvalidBridgeStateError)?;
+    Ok(configs)
+}
+
+pub fn check_chain_configs(configs: &[ChainConfig]) -> Result<(), String> {
+    if configs.is_empty() {
+        return Err("No chains configured".to_string());
+    }
+    let mut names = HashSet::new();
+    let mut state_dirs = HashSet::new();
+    for config in configs {
+        if config.name.is_empty() {
+            return Err("Every chain needs a name".to_string());
+        }
+        if !names.insert(config.name.as_str()) {
+            return Err(format!("Chain {} is configured twice", config.name));
+        }
+        let state_dir = match config.state_dir.as_deref() {
+            Some(dir) => Path::new(dir),
+            None => return Err(format!("Chain {} has no state_dir", config.name)),
+        };
+        // compare the directories as given, a best effort check against copy and paste mistakes
+        if !state_dirs.insert(state_dir) {
+            return Err(format!(
+                "Chain {} shares its state_dir {} with another chain",
+                config.name,
+                state_dir.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check_chain_configs() {
+    let chain = |name: &str, state_dir: Option<&str>| ChainConfig {
+        name: name.to_string(),
+        state_dir: state_dir.map(|d| d.to_string()),
+        ..Default::default()
+    };
+    assert!(check_chain_configs(&[]).is_err());
+    assert!(check_chain_configs(&[
+        chain("mainnet", Some("/tmp/mainnet")),
+        chain("testnet", Some("/tmp/testnet")),
+    ])
+    .is_ok());
+    assert!(check_chain_configs(&[chain("mainnet", None)]).is_err());
+    assert!(check_chain_configs(&[
+        chain("mainnet", Some("/tmp/mainnet")),
+        chain("mainnet", Some("/tmp/testnet")),
+    ])
+    .is_err());
+    assert!(check_chain_configs(&[
+        chain("mainnet", Some("/tmp/state")),
+        chain("testnet", Some("/tmp/state")),
+    ])
+    .is_err());
+
+    let parsed: Vec<ChainConfig> = serde_json::from_str(
+        r#"[{"name": "testnet", "cosmos_phrase": "a", "ethereum_key": "b", "cosmos_legacy_rpc": "c",
+        "cosmos_grpc": "d", "ethereum_rpc": "e", "contract_address": "f", "fees": "g", "state_dir": "h"}]"#,
+    )
+    .unwrap();
+    assert_eq!(parsed[0].peggy_id, None);
+    assert!(check_chain_configs(&parsed).is_ok());
+}
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use crate::ethereum_event_watcher::MAX_BLOCKS_PER_SCAN;
 use clarity::{Address, Uint256};
 use cosmos_peggy::query::get_last_event_nonce;
 use deep_space::address::Address as CosmosAddress;
@@ -9,6 +10,7 @@ use std::ops::Sub;
 use tokio::time::delay_for;
 use tonic::transport::Channel;
 use web30::client::Web3;
+use web30::types::Log;
 
 const RETRY_TIME: Duration = Duration::from_secs(5);
 
@@ -16,21 +18,25 @@ const RETRY_TIME: Duration = Duration::from_secs(5);
 /// it then uses the Ethereum indexes to determine what block the last entry
 /// TODO this should simply be stored in the deposit or withdraw claim and we
 /// ask the Cosmos chain, this searching is a total waste of work
+/// With `deployment_block` an oracle that never submitted an event starts there rather than at the
+/// latest block and the search never goes back further, see `deployment_block`
 pub async fn get_last_checked_block(
     grpc_client: PeggyQueryClient<Channel>,
     our_cosmos_address: CosmosAddress,
     peggy_contract_address: Address,
     web3: &Web3,
+    confirmations: u64,
+    deployment_block: Option<u64>,
 ) -> Uint256 {
     let mut grpc_client = grpc_client;
-    const BLOCKS_TO_SEARCH: u128 = 50_000u128;
 
-    let latest_block = (get_block_number_with_retry(web3).await).sub(Uint256::from(5u64));
+    let latest_block = (get_block_number_with_retry(web3).await).sub(Uint256::from(confirmations));
     let last_event_nonce: Uint256 =
         get_last_event_nonce_with_retry(&mut grpc_client, our_cosmos_address)
             .await
             .into();
     let first_run = false;
+    let earliest_block: Uint256 = deployment_block.unwrap_or(0).into();
 
     // zero indicates this oracle has never submitted an event before
     // this can mean one of two things, there are actually no events in history
@@ -40,63 +46,38 @@ pub async fn get_last_checked_block(
     if last_event_nonce == 0u8.into() {
         // last_event_nonce = 1u8.into();
         // first_run = true;
+        if deployment_block.is_some() {
+            info!(
+                "Oracle has never submitted an event, scanning from the deployment block {}",
+                earliest_block
+            );
+            return earliest_block.min(latest_block);
+        }
         return latest_block;
     }
 
     let mut current_block: Uint256 = latest_block.clone();
 
-    while current_block.clone() > 0u8.into() {
+    while current_block > earliest_block {
         info!(
             "Oracle is resyncing, looking back into the history to find our last event nonce {}, on block {}",
             last_event_nonce, current_block
         );
-        let end_search = if current_block.clone() < BLOCKS_TO_SEARCH.into() {
-            0u8.into()
-        } else {
-            current_block.clone() - BLOCKS_TO_SEARCH.into()
-        };
-        let all_batch_events = web3
-            .check_for_events(
-                end_search.clone(),
-                Some(current_block.clone()),
-                vec![peggy_contract_address],
-                vec!["TransactionBatchExecutedEvent(uint256,address,address,uint256)"],
-            )
-            .await;
-        let all_send_to_cosmos_events = web3
-            .check_for_events(
-                end_search.clone(),
-                Some(current_block.clone()),
-                vec![peggy_contract_address],
-                vec!["SendToHubEvent(address,address,bytes32,uint256,uint256)"],
-            )
-            .await;
-        let all_send_to_minter_events = web3
-            .check_for_events(
-                end_search.clone(),
-                Some(current_block.clone()),
-                vec![peggy_contract_address],
-                vec!["SendToMinterEvent(address,address,bytes32,uint256,uint256)"],
-            )
-            .await;
-        if all_batch_events.is_err()
-            || all_send_to_cosmos_events.is_err()
-            || all_send_to_minter_events.is_err()
-        {
-            error!("Failed to get blockchain events while resyncing, is your Eth node working?");
-            delay_for(RETRY_TIME).await;
-            continue;
-        }
-        let all_batch_events = all_batch_events.unwrap();
-        let all_send_to_cosmos_events = all_send_to_cosmos_events.unwrap();
-        let all_send_to_minter_events = all_send_to_minter_events.unwrap();
-
-        trace!(
-            "Found events {:?} {:?} {:?}",
-            all_batch_events,
-            all_send_to_cosmos_events,
-            all_send_to_minter_events
-        );
+        let end_search =
+            if current_block.clone() < earliest_block.clone() + MAX_BLOCKS_PER_SCAN.into() {
+                earliest_block.clone()
+            } else {
+                current_block.clone() - MAX_BLOCKS_PER_SCAN.into()
+            };
+        // one event type at a time, so only one set of logs is held in memory
+        let all_batch_events = get_events_with_retry(
+            web3,
+            &end_search,
+            &current_block,
+            peggy_contract_address,
+            "TransactionBatchExecutedEvent(uint256,address,address,uint256)",
+        )
+        .await;
         for event in all_batch_events {
             match TransactionBatchExecutedEvent::from_log(&event) {
                 Ok(batch) => {
@@ -107,6 +88,14 @@ pub async fn get_last_checked_block(
                 Err(e) => error!("Got batch event that we can't parse {}", e),
             }
         }
+        let all_send_to_cosmos_events = get_events_with_retry(
+            web3,
+            &end_search,
+            &current_block,
+            peggy_contract_address,
+            "SendToHubEvent(address,address,bytes32,uint256,uint256)",
+        )
+        .await;
         for event in all_send_to_cosmos_events {
             match SendToCosmosEvent::from_log(&event) {
                 Ok(send) => {
@@ -117,6 +106,14 @@ pub async fn get_last_checked_block(
                 Err(e) => error!("Got event that we can't parse {}", e),
             }
         }
+        let all_send_to_minter_events = get_events_with_retry(
+            web3,
+            &end_search,
+            &current_block,
+            peggy_contract_address,
+            "SendToMinterEvent(address,address,bytes32,uint256,uint256)",
+        )
+        .await;
         for event in all_send_to_minter_events {
             match SendToMinterEvent::from_log(&event) {
                 Ok(send) => {
@@ -139,6 +136,38 @@ pub async fn get_last_checked_block(
     panic!("Could not find the last event relayed by {}, Last Event nonce is {} but no event matching that could be found!", our_cosmos_address, last_event_nonce)
 }
 
+/// gets the logs of one event type in a block range, no matter how long it takes
+async fn get_events_with_retry(
+    web3: &Web3,
+    from_block: &Uint256,
+    to_block: &Uint256,
+    peggy_contract_address: Address,
+    event: &str,
+) -> Vec<Log> {
+    loop {
+        match web3
+            .check_for_events(
+                from_block.clone(),
+                Some(to_block.clone()),
+                vec![peggy_contract_address],
+                vec![event],
+            )
+            .await
+        {
+            Ok(events) => {
+                trace!("Found events {:?}", events);
+                return events;
+            }
+            Err(_) => {
+                error!(
+                    "Failed to get blockchain events while resyncing, is your Eth node working?"
+                );
+                delay_for(RETRY_TIME).await;
+            }
+        }
+    }
+}
+
 /// gets the current block number, no matter how long it takes
 async fn get_block_number_with_retry(web3: &Web3) -> Uint256 {
     let mut res = web3.eth_block_number().await;
@@ -0,0 +1,60 @@
+//! How the query commands print their results. Text, the default, is meant for people and changes whenever
+//! it reads better. `--output json` prints a single json document instead, fields are only ever added to it so
+//! scripts can rely on them. Either way the exit code tells the result: 0 when everything checked out,
+//! `EXIT_CHECK_FAILED` when the command ran but what it checked doesn't hold, such as a batch whose
+//! signatures don't verify, and 101 when it couldn't run at all.
+
+use serde::Serialize;
+use std::str::FromStr;
+
+/// The exit code of a command that ran but whose check failed
+pub const EXIT_CHECK_FAILED: i32 = 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            other => Err(format!("Unknown output {}, expected text or json", other)),
+        }
+    }
+}
+
+impl OutputFormat {
+    /// Prints `text` or the json of `value`, depending on the format
+    pub fn print<T: Serialize>(&self, text: &str, value: &T) {
+        match self {
+            OutputFormat::Text => print!("{}", text),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value).unwrap()),
+        }
+    }
+}
+
+/// Exits with `EXIT_CHECK_FAILED` unless `passed`
+pub fn exit_unless(passed: bool) {
+    if !passed {
+        std::process::exit(EXIT_CHECK_FAILED);
+    }
+}
+
+#[test]
+fn test_output_format() {
+    assert_eq!("json".parse(), Ok(OutputFormat::Json));
+    assert_eq!(" text".parse(), Ok(OutputFormat::Text));
+    assert!("yaml".parse::<OutputFormat>().is_err());
+    assert_eq!(OutputFormat::default(), OutputFormat::Text);
+}
@@ -0,0 +1,298 @@
+//! Watches the Peggy module params for governance changes. The params are polled every `PARAM_WATCH_SPEED` and
+//! compared with the last ones we saw, which are kept in the state store so a change passed while the
+//! orchestrator was down is caught at the next start. Every change is logged field by field as one alert and
+//! counted as a `peggy_params_changed` error.
+//!
+//! Most settings that depend on the params are derived from them again every loop, the downtime tolerance and
+//! the backup broadcast margin from the signing windows, the withheld batch report from `batch_timeout_blocks`
+//! and the pause state from `stopped`, `halt_at_event_nonce` and `min_orchestrator_version`. The alert spells
+//! out their new values. The Peggy contract and its PeggyID are fixed at startup, a bridge address that no
+//! longer matches ours is alerted until the orchestrator is restarted with the new contract.
+
+use crate::downtime::backup_broadcast_blocks;
+use crate::state_store::LocalStorage;
+use clarity::{Address as EthAddress, Uint256};
+use cosmos_peggy::query::get_peggy_params;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_proto::peggy::Params;
+use peggy_utils::amount::format_decimal;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+
+/// How often the Hub is polled for its params
+pub const PARAM_WATCH_SPEED: Duration = Duration::from_secs(60);
+/// Slash fractions are Cosmos decimals, integers with this many decimals
+const DEC_PRECISION: usize = 18;
+
+/// A param that governance changed, a side is None for a field the other params don't have
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParamChange {
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+fn slash_fraction(bytes: &[u8]) -> String {
+    let raw = String::from_utf8_lossy(bytes);
+    match raw.parse::<Uint256>() {
+        Ok(value) => format_decimal(&value, DEC_PRECISION),
+        Err(_) => raw.to_string(),
+    }
+}
+
+/// The params as readable values by field name, as they are stored and compared
+pub fn param_values(params: &Params) -> BTreeMap<String, String> {
+    vec![
+        ("peggy_id", params.peggy_id.clone()),
+        ("contract_source_hash", params.contract_source_hash.clone()),
+        ("start_threshold", params.start_threshold.to_string()),
+        ("ethereum_address", params.ethereum_address.clone()),
+        ("bridge_chain_id", params.bridge_chain_id.to_string()),
+        (
+            "signed_valsets_window",
+            params.signed_valsets_window.to_string(),
+        ),
+        (
+            "signed_batches_window",
+            params.signed_batches_window.to_string(),
+        ),
+        (
+            "signed_claims_window",
+            params.signed_claims_window.to_string(),
+        ),
+        (
+            "slash_fraction_valset",
+            slash_fraction(&params.slash_fraction_valset),
+        ),
+        (
+            "slash_fraction_batch",
+            slash_fraction(&params.slash_fraction_batch),
+        ),
+        (
+            "slash_fraction_claim",
+            slash_fraction(&params.slash_fraction_claim),
+        ),
+        (
+            "slash_fraction_conflicting_claim",
+            slash_fraction(&params.slash_fraction_conflicting_claim),
+        ),
+        ("stopped", params.stopped.to_string()),
+        (
+            "halt_at_event_nonce",
+            params.halt_at_event_nonce.to_string(),
+        ),
+        (
+            "min_orchestrator_version",
+            params.min_orchestrator_version.clone(),
+        ),
+        (
+            "batch_timeout_blocks",
+            params.batch_timeout_blocks.to_string(),
+        ),
+    ]
+    .into_iter()
+    .map(|(field, value)| (field.to_string(), value))
+    .collect()
+}
+
+/// The fields that differ between `old` and `new`, by field name
+pub fn diff_params(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<ParamChange> {
+    let mut fields: Vec<&String> = old.keys().chain(new.keys()).collect();
+    fields.sort();
+    fields.dedup();
+    fields
+        .into_iter()
+        .filter(|field| old.get(*field) != new.get(*field))
+        .map(|field| ParamChange {
+            field: field.clone(),
+            old: old.get(field).cloned(),
+            new: new.get(field).cloned(),
+        })
+        .collect()
+}
+
+/// What the changes mean for this orchestrator, the settings derived from the new params and what
+/// can't follow them without a restart
+pub fn consequences(
+    changes: &[ParamChange],
+    params: &Params,
+    peggy_contract_address: EthAddress,
+) -> Vec<String> {
+    let changed = |field: &str| changes.iter().any(|c| c.field == field);
+    let mut out = Vec::new();
+    if changed("signed_valsets_window") {
+        out.push(format!(
+            "valset confirms are now due within {} blocks, confirms with fewer than {} blocks left go through the backup Hub node",
+            params.signed_valsets_window,
+            backup_broadcast_blocks(params.signed_valsets_window)
+        ));
+    }
+    if changed("signed_batches_window") {
+        out.push(format!(
+            "batch confirms are now due within {} blocks",
+            params.signed_batches_window
+        ));
+    }
+    if changed("signed_valsets_window") || changed("signed_batches_window") {
+        out.push("the downtime tolerance follows from the next signer loop on".to_string());
+    }
+    if changed("batch_timeout_blocks") {
+        out.push(match params.batch_timeout_blocks {
+            0 => "the Hub no longer supersedes withheld batches".to_string(),
+            blocks => format!(
+                "the Hub now supersedes withheld batches after {} blocks",
+                blocks
+            ),
+        });
+    }
+    if changed("stopped") || changed("halt_at_event_nonce") || changed("min_orchestrator_version") {
+        out.push(
+            "the oracle and signer pick up the new pause state on their next loop".to_string(),
+        );
+    }
+    if changed("ethereum_address") && !bridges_to(params, peggy_contract_address) {
+        out.push(format!(
+            "the Hub now bridges to {} but this orchestrator watches {}, restart it with --contract-address={}",
+            params.ethereum_address, peggy_contract_address, params.ethereum_address
+        ));
+    }
+    if changed("peggy_id") {
+        out.push(
+            "confirms are still signed with the PeggyID read from the contract at startup, restart once the contract was redeployed with the new one"
+                .to_string(),
+        );
+    }
+    out
+}
+
+/// Whether the Hub's bridge address is our contract, an empty address means the Hub doesn't name one
+fn bridges_to(params: &Params, peggy_contract_address: EthAddress) -> bool {
+    params.ethereum_address.is_empty()
+        || params
+            .ethereum_address
+            .parse::<EthAddress>()
+            .map_or(false, |address| address == peggy_contract_address)
+}
+
+fn log_changes(changes: &[ParamChange], params: &Params, peggy_contract_address: EthAddress) {
+    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "<unset>".to_string());
+    error!(
+        "PEGGY PARAMS CHANGED by governance, {} field(s):",
+        changes.len()
+    );
+    for change in changes {
+        error!(
+            "  {}: {} -> {}",
+            change.field,
+            value(&change.old),
+            value(&change.new)
+        );
+    }
+    for consequence in consequences(changes, params, peggy_contract_address) {
+        error!("  so {}", consequence);
+    }
+}
+
+/// Polls the Peggy params and alerts on every change, the last params seen are kept in the state store
+pub async fn param_watch_loop(
+    grpc_client: PeggyQueryClient<Channel>,
+    peggy_contract_address: EthAddress,
+    storage: Option<LocalStorage>,
+) {
+    let mut grpc_client = grpc_client;
+    let mut last: Option<BTreeMap<String, String>> = storage
+        .as_ref()
+        .and_then(|storage| storage.state.load().ok())
+        .map(|state| state.peggy_params)
+        .filter(|params| !params.is_empty());
+    let mut misdirected = false;
+    loop {
+        match get_peggy_params(&mut grpc_client).await {
+            Ok(params) => {
+                let values = param_values(&params);
+                match last.as_ref() {
+                    Some(last) => {
+                        let changes = diff_params(last, &values);
+                        if !changes.is_empty() {
+                            log_changes(&changes, &params, peggy_contract_address);
+                            if let Some(storage) = storage.as_ref() {
+                                storage.count_error("peggy_params_changed");
+                            }
+                        }
+                    }
+                    None => info!("Watching the Peggy params for changes: {:?}", values),
+                }
+                // alerted whenever the Hub's bridge address stops matching ours, at startup too
+                let ours = bridges_to(&params, peggy_contract_address);
+                if !ours && !misdirected {
+                    error!(
+                        "PEGGY PARAMS MISMATCH, the Hub bridges to {} but this orchestrator watches {}",
+                        params.ethereum_address, peggy_contract_address
+                    );
+                }
+                misdirected = !ours;
+                if let Some(storage) = storage.as_ref() {
+                    let stored = values.clone();
+                    if let Err(e) = storage.state.update(|state| state.peggy_params = stored) {
+                        error!("Failed to update state store: {}", e);
+                    }
+                }
+                last = Some(values);
+            }
+            Err(e) => trace!("Failed to get the Peggy params {:?}", e),
+        }
+        delay_for(PARAM_WATCH_SPEED).await;
+    }
+}
+
+#[test]
+fn test_param_diff() {
+    let contract: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let old = Params {
+        peggy_id: "minter-hub-1".to_string(),
+        ethereum_address: "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8".to_string(),
+        signed_valsets_window: 10_000,
+        signed_batches_window: 10_000,
+        slash_fraction_valset: b"1000000000000000".to_vec(),
+        ..Default::default()
+    };
+    let new = Params {
+        ethereum_address: "0x0000000000000000000000000000000000000001".to_string(),
+        signed_valsets_window: 5_000,
+        slash_fraction_valset: b"10000000000000000".to_vec(),
+        ..old.clone()
+    };
+    assert_eq!(param_values(&old)["slash_fraction_valset"], "0.001");
+    assert!(diff_params(&param_values(&old), &param_values(&old)).is_empty());
+
+    let changes = diff_params(&param_values(&old), &param_values(&new));
+    let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+    assert_eq!(
+        fields,
+        vec![
+            "ethereum_address",
+            "signed_valsets_window",
+            "slash_fraction_valset"
+        ]
+    );
+    assert_eq!(changes[2].old.as_deref(), Some("0.001"));
+    assert_eq!(changes[2].new.as_deref(), Some("0.01"));
+
+    let consequences = consequences(&changes, &new, contract);
+    assert!(consequences[0].contains("within 5000 blocks"));
+    assert!(consequences[0].contains("fewer than 500 blocks"));
+    assert!(consequences
+        .iter()
+        .any(|c| c.contains("--contract-address=0x0000000000000000000000000000000000000001")));
+    // the bridge moving back to our contract needs no restart
+    assert!(consequences(&changes, &old, contract)
+        .iter()
+        .all(|c| !c.contains("restart")));
+}
@@ -0,0 +1,139 @@
+//! During an incident the guardian can halt the Peggy contract and governance can stop the bridge on the
+//! Hub. Anything we submit in that state just reverts or gets rejected, so the loops check the pause state
+//! every cycle, sit out while paused and pick up where they left off once it is lifted.
+//!
+//! Governance can also halt the bridge at a specific event nonce, the oracle keeps attesting to events up
+//! to and including that nonce so that every validator stops at the same point, then waits for the halt
+//! to be lifted before claiming anything newer.
+//!
+//! The minimum orchestrator version governance publishes is read along with the rest, see `version`.
+
+use crate::version::{parse_min_version, Version};
+use clarity::{Address as EthAddress, Uint256};
+use cosmos_peggy::query::get_peggy_params;
+use ethereum_peggy::utils::{get_deposits_stopped, get_halted};
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct PauseState {
+    /// the guardian halted the contract, valset updates and batches revert
+    pub contract_halted: bool,
+    /// the guardian stopped deposits to the Hub, already emitted events are still valid
+    pub deposits_stopped: bool,
+    /// governance stopped the bridge on the Hub, claims are rejected
+    pub hub_stopped: bool,
+    /// governance halted the bridge at this event nonce, claims for later events are rejected
+    pub halt_at_event_nonce: Option<u64>,
+    /// the lowest orchestrator version governance wants validators to run
+    pub min_orchestrator_version: Option<Version>,
+}
+
+impl PauseState {
+    pub fn is_paused(&self) -> bool {
+        self.contract_halted
+            || self.deposits_stopped
+            || self.hub_stopped
+            || self.halt_at_event_nonce.is_some()
+    }
+
+    /// Returns true if we must not sign a claim for the event with this nonce
+    pub fn claim_halted(&self, event_nonce: &Uint256) -> bool {
+        match self.halt_at_event_nonce {
+            Some(halt_at) => *event_nonce > halt_at.into(),
+            None => false,
+        }
+    }
+
+    /// claims and confirms go to the Hub, only a Hub side stop makes them pointless
+    pub fn hub_submissions_paused(&self) -> bool {
+        self.hub_stopped
+    }
+
+    /// Returns true if this orchestrator is older than the minimum version set by governance
+    pub fn version_outdated(&self) -> bool {
+        match self.min_orchestrator_version {
+            Some(min) => Version::ours() < min,
+            None => false,
+        }
+    }
+
+    /// Whether the oracle and signer have to sit out, with `enforce_min_version` an outdated
+    /// orchestrator stops signing as well
+    pub fn signing_paused(&self, enforce_min_version: bool) -> bool {
+        self.hub_submissions_paused() || (enforce_min_version && self.version_outdated())
+    }
+}
+
+pub async fn get_pause_state(
+    web3: &Web3,
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    peggy_contract_address: EthAddress,
+) -> Result<PauseState, PeggyError> {
+    let params = get_peggy_params(grpc_client).await?;
+    Ok(PauseState {
+        contract_halted: get_halted(peggy_contract_address, web3).await?,
+        deposits_stopped: get_deposits_stopped(peggy_contract_address, web3).await?,
+        hub_stopped: params.stopped,
+        halt_at_event_nonce: match params.halt_at_event_nonce {
+            0 => None,
+            nonce => Some(nonce),
+        },
+        min_orchestrator_version: parse_min_version(&params.min_orchestrator_version),
+    })
+}
+
+/// Logs pause state transitions so operators see exactly when the loop stopped and resumed
+pub fn log_pause_transition(role: &str, previous: PauseState, current: PauseState) {
+    if previous == current {
+        return;
+    }
+    if current.version_outdated() && !previous.version_outdated() {
+        error!(
+            "OUTDATED ORCHESTRATOR, this is version {} but governance requires at least {}, upgrade as soon as possible",
+            Version::ours(),
+            current.min_orchestrator_version.unwrap()
+        );
+    }
+    if current.hub_submissions_paused() {
+        warn!("{} paused by the bridge: {:?}", role, current);
+    } else if let Some(halt_at) = current.halt_at_event_nonce {
+        error!(
+            "BRIDGE HALTED by governance at event nonce {}, {} will not claim any later events until the halt is lifted",
+            halt_at, role
+        );
+    } else {
+        info!("{} running, bridge pause state is now {:?}", role, current);
+    }
+}
+
+#[test]
+fn test_claim_halted() {
+    let mut state = PauseState::default();
+    assert!(!state.claim_halted(&100u64.into()));
+    state.halt_at_event_nonce = Some(10);
+    assert!(state.is_paused());
+    assert!(!state.claim_halted(&10u64.into()));
+    assert!(state.claim_halted(&11u64.into()));
+}
+
+#[test]
+fn test_version_outdated() {
+    let mut state = PauseState::default();
+    assert!(!state.version_outdated());
+    state.min_orchestrator_version = Some(Version::ours());
+    assert!(!state.version_outdated());
+    assert!(!state.signing_paused(true));
+    let ours = Version::ours();
+    state.min_orchestrator_version = Some(Version {
+        major: ours.major + 1,
+        ..ours
+    });
+    assert!(state.version_outdated());
+    // only warn unless enforced
+    assert!(!state.signing_paused(false));
+    assert!(state.signing_paused(true));
+    assert!(!state.is_paused());
+}
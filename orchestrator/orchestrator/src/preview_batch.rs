@@ -0,0 +1,328 @@
+//! `orchestrator preview-batch` shows what the next batch of a token would hold if it was requested now, so a
+//! user whose withdrawal is waiting can tell whether their bridge fee is enough. The Hub builds a batch from
+//! the highest fee transfers in the outgoing pool, up to its batch size, and only if their fees at the oracle
+//! price of the token are worth the oracle's fast withdrawal fee. A relayer then only submits it if the fees
+//! cover the gas. Transfers left out are listed with the fee they would need to get in.
+//!
+//! The Hub doesn't expose its batch size, `DEFAULT_BATCH_SIZE` is the module default and `--batch-size`
+//! overrides it. Gas is a rough estimate from the valset size and the number of transfers, capped at the
+//! gas limit batches are submitted with.
+
+use crate::enrichment::format_hub_price;
+use crate::fee_value::{FeeValuer, ETH_DECIMALS};
+use crate::stalled_transfers::PoolTransfer;
+use crate::tui::GWEI_DECIMALS;
+use clarity::Address as EthAddress;
+use ethereum_peggy::submit_batch::BATCH_GAS_LIMIT;
+use num256::Uint256;
+use peggy_utils::amount::{format_amount, format_decimal, format_token_amount};
+use peggy_utils::error::PeggyError;
+use relayer::profitability::HUB_FEE_DECIMALS;
+pub use relayer::profitability::{hub_check, HubCheck};
+use std::fmt::Write;
+
+/// The most transfers the Hub puts in one batch by default
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+/// Rough gas of a submitBatch call without transfers or signatures
+const BATCH_BASE_GAS: u64 = 100_000;
+/// Rough gas of checking one validator's signature
+const SIGNATURE_GAS: u64 = 7_000;
+/// Rough gas of one ERC20 transfer out of the contract
+const TRANSFER_GAS: u64 = 35_000;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BatchPreview {
+    pub token: EthAddress,
+    /// the transfers the next batch would include, highest fee first
+    pub included: Vec<PoolTransfer>,
+    /// the pooled transfers of the token left out
+    pub excluded: Vec<PoolTransfer>,
+    pub total_fee: Uint256,
+    pub estimated_gas: u64,
+}
+
+/// Takes `peggy/<erc20>` denoms as well as the bare ERC20 contract
+pub fn parse_denom(denom: &str) -> Result<EthAddress, String> {
+    let denom = denom.trim();
+    let contract = denom.strip_prefix("peggy/").unwrap_or(denom);
+    contract
+        .parse()
+        .map_err(|e| format!("Invalid denom {}: {}", denom, e))
+}
+
+pub fn estimate_batch_gas(transfers: usize, valset_size: usize) -> u64 {
+    let gas = BATCH_BASE_GAS + SIGNATURE_GAS * valset_size as u64 + TRANSFER_GAS * transfers as u64;
+    gas.min(BATCH_GAS_LIMIT)
+}
+
+/// Picks the transfers of `token` the Hub would batch, the highest fees first and the oldest on equal fees
+pub fn preview_batch(
+    pool: &[PoolTransfer],
+    token: EthAddress,
+    batch_size: usize,
+    valset_size: usize,
+) -> BatchPreview {
+    let mut transfers: Vec<PoolTransfer> = pool
+        .iter()
+        .filter(|transfer| transfer.erc20_token.token_contract_address == token)
+        .cloned()
+        .collect();
+    transfers.sort_by(|a, b| {
+        b.erc20_fee
+            .amount
+            .cmp(&a.erc20_fee.amount)
+            .then(a.id.cmp(&b.id))
+    });
+    let excluded = transfers.split_off(batch_size.min(transfers.len()));
+    let mut total_fee: Uint256 = 0u8.into();
+    for transfer in transfers.iter() {
+        total_fee = total_fee + transfer.erc20_fee.amount.clone();
+    }
+    BatchPreview {
+        token,
+        estimated_gas: estimate_batch_gas(transfers.len(), valset_size),
+        included: transfers,
+        excluded,
+        total_fee,
+    }
+}
+
+/// What `preview-batch --output json` prints, values from the oracle are in its integer USD
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PreviewView {
+    pub token: EthAddress,
+    pub included: Vec<PoolTransfer>,
+    pub excluded: Vec<PoolTransfer>,
+    pub total_fee: String,
+    /// whether the Hub would build the batch, None if the oracle couldn't be asked
+    pub hub_builds: Option<bool>,
+    pub hub_error: Option<String>,
+    pub fee_value: Option<String>,
+    /// the least the batch's fees must be worth for the Hub to build it
+    pub fee_threshold: Option<String>,
+    /// how much more fee in total gets the batch built
+    pub shortfall: Option<String>,
+    pub estimated_gas: u64,
+    /// the estimated gas in wei at the current gas price
+    pub gas_cost: String,
+    /// whether the fees cover the gas, None without prices of ETH and the token
+    pub profitable: Option<bool>,
+    /// the fee that gets a left out transfer in
+    pub needed_fee: Option<String>,
+}
+
+pub fn preview_view(
+    preview: &BatchPreview,
+    gas_price: &Uint256,
+    valuer: Option<&FeeValuer>,
+    hub: &Result<HubCheck, PeggyError>,
+) -> PreviewView {
+    let gas_cost = gas_price.clone() * Uint256::from(preview.estimated_gas);
+    let check = hub.as_ref().ok();
+    PreviewView {
+        token: preview.token,
+        included: preview.included.clone(),
+        excluded: preview.excluded.clone(),
+        total_fee: preview.total_fee.to_string(),
+        hub_builds: check.map(|check| check.accepts()),
+        hub_error: hub.as_ref().err().map(|e| format!("{:?}", e)),
+        fee_value: check.map(|check| check.value.to_string()),
+        fee_threshold: check.map(|check| check.threshold.to_string()),
+        shortfall: check
+            .and_then(|check| check.shortfall())
+            .map(|shortfall| shortfall.to_string()),
+        estimated_gas: preview.estimated_gas,
+        profitable: valuer.and_then(|valuer| {
+            Some(valuer.value(preview.token, &preview.total_fee)? >= valuer.eth_value(&gas_cost)?)
+        }),
+        gas_cost: gas_cost.to_string(),
+        needed_fee: match (preview.included.last(), preview.excluded.is_empty()) {
+            (Some(lowest), false) => {
+                Some((lowest.erc20_fee.amount.clone() + 1u8.into()).to_string())
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Formats the preview for people, `decimals` are the token's Ethereum decimals the transfer amounts are in,
+/// fees are in Hub units
+pub fn format_preview(
+    preview: &BatchPreview,
+    decimals: Option<u64>,
+    gas_price: &Uint256,
+    valuer: Option<&FeeValuer>,
+    hub: &Result<HubCheck, PeggyError>,
+) -> String {
+    let fee = |fee: &Uint256| format_amount(fee, HUB_FEE_DECIMALS);
+    let mut out = String::new();
+    if preview.included.is_empty() {
+        writeln!(
+            out,
+            "No transfers of {} are waiting to be batched",
+            preview.token
+        )
+        .unwrap();
+        return out;
+    }
+    writeln!(
+        out,
+        "Next batch of {}: {} transfers",
+        preview.token,
+        preview.included.len()
+    )
+    .unwrap();
+    for transfer in preview.included.iter() {
+        writeln!(
+            out,
+            "  #{} {} to {} fee {}",
+            transfer.id,
+            format_token_amount(&transfer.erc20_token.amount, decimals),
+            transfer.dest_address,
+            fee(&transfer.erc20_fee.amount)
+        )
+        .unwrap();
+    }
+    writeln!(out, "Total fee: {}", fee(&preview.total_fee)).unwrap();
+
+    let usd = |value: &Uint256| format_hub_price(&value.to_string()).unwrap_or_default();
+    match hub {
+        Ok(check) if check.accepts() => writeln!(
+            out,
+            "Hub: builds it, fees worth {} USD at the oracle price, at least {} USD needed",
+            usd(&check.value),
+            usd(&check.threshold)
+        )
+        .unwrap(),
+        Ok(check) => {
+            writeln!(
+                out,
+                "Hub: WON'T build it, fees worth {} USD at the oracle price, at least {} USD needed",
+                usd(&check.value),
+                usd(&check.threshold)
+            )
+            .unwrap();
+            if let Some(shortfall) = check.shortfall() {
+                writeln!(out, "  {} more fee in total gets it built", fee(&shortfall)).unwrap();
+            }
+        }
+        Err(e) => writeln!(out, "Hub: unknown, failed to check the oracle {:?}", e).unwrap(),
+    }
+
+    let gas_cost = gas_price.clone() * Uint256::from(preview.estimated_gas);
+    writeln!(
+        out,
+        "Estimated gas: {}, {} ETH at {} gwei per gas",
+        preview.estimated_gas,
+        format_amount(&gas_cost, ETH_DECIMALS as usize),
+        format_decimal(gas_price, GWEI_DECIMALS)
+    )
+    .unwrap();
+    let verdict = valuer.and_then(|valuer| {
+        let cost = valuer.eth_value(&gas_cost)?;
+        let value = valuer.value(preview.token, &preview.total_fee)?;
+        let verdict = if value >= cost {
+            "profitable"
+        } else {
+            "NOT profitable"
+        };
+        Some(format!(
+            "fees worth {} against a relay cost of {}, {}",
+            valuer.format(&value),
+            valuer.format(&cost),
+            verdict
+        ))
+    });
+    match verdict {
+        Some(verdict) => writeln!(out, "Verdict: {}", verdict).unwrap(),
+        None => writeln!(
+            out,
+            "Verdict: unknown, give --price-feed with prices of ETH and the token"
+        )
+        .unwrap(),
+    }
+
+    if !preview.excluded.is_empty() {
+        // one more than the lowest included fee beats it, equal fees go by age
+        let needed = preview.included.last().unwrap().erc20_fee.amount.clone() + 1u8.into();
+        writeln!(
+            out,
+            "Left out: {} transfers, a fee of {} gets a transfer in",
+            preview.excluded.len(),
+            fee(&needed)
+        )
+        .unwrap();
+        for transfer in preview.excluded.iter() {
+            writeln!(
+                out,
+                "  #{} fee {}",
+                transfer.id,
+                fee(&transfer.erc20_fee.amount)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+#[test]
+fn test_preview_batch() {
+    use peggy_utils::types::ERC20Token;
+
+    let token: EthAddress = "0x0101010101010101010101010101010101010101"
+        .parse()
+        .unwrap();
+    let other: EthAddress = "0x0202020202020202020202020202020202020202"
+        .parse()
+        .unwrap();
+    let transfer = |id: u64, token: EthAddress, fee: u64| PoolTransfer {
+        id,
+        sender: "hub1sender".to_string(),
+        dest_address: "0x0303030303030303030303030303030303030303".to_string(),
+        erc20_token: ERC20Token {
+            amount: 1000u64.into(),
+            token_contract_address: token,
+        },
+        erc20_fee: ERC20Token {
+            amount: fee.into(),
+            token_contract_address: token,
+        },
+        memo: String::new(),
+    };
+    let pool = vec![
+        transfer(1, token, 5),
+        transfer(2, token, 20),
+        transfer(3, other, 50),
+        transfer(4, token, 5),
+        transfer(5, token, 1),
+    ];
+
+    let preview = preview_batch(&pool, token, 3, 10);
+    let ids: Vec<u64> = preview.included.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![2, 1, 4]);
+    assert_eq!(preview.excluded.len(), 1);
+    assert_eq!(preview.total_fee, 30u64.into());
+    assert_eq!(preview.estimated_gas, 100_000 + 70_000 + 105_000);
+    // 30 tokens at 2 USD are worth 60 USD, 100 USD needs 20 more
+    let whole =
+        |amount: &str| peggy_utils::amount::parse_decimal(amount, HUB_FEE_DECIMALS).unwrap();
+    let usd = |amount: &str| peggy_utils::amount::parse_decimal(amount, 10).unwrap();
+    let check = HubCheck::new(&whole("30"), usd("2"), usd("100"));
+    let out = format_preview(&preview, Some(6), &1u8.into(), None, &Ok(check));
+    assert!(out.contains(
+        "#2 0.001 to 0x0303030303030303030303030303030303030303 fee 0.00000000000000002"
+    ));
+    assert!(out.contains("a fee of 0.000000000000000006 gets a transfer in"));
+    assert!(out.contains("20 more fee in total gets it built"));
+    assert!(out.contains("Verdict: unknown"));
+    assert!(out.contains("WON'T build it"));
+
+    assert!(preview_batch(&pool, token, 10, 10).excluded.is_empty());
+    assert_eq!(estimate_batch_gas(1000, 150), BATCH_GAS_LIMIT);
+    assert_eq!(parse_denom(&format!("peggy/{}", token)), Ok(token));
+    assert_eq!(
+        parse_denom("0x0101010101010101010101010101010101010101"),
+        Ok(token)
+    );
+    assert!(parse_denom("hub").is_err());
+}
@@ -0,0 +1,249 @@
+//! Named profiles for the networks the Hub runs on, picked with `--profile` or `"profile"` in a chain config for
+//! `orchestrator multi`. A profile fills in what is the same for every operator of a network: the Peggy contract,
+//! PeggyID, Minter multisig and fee denom, the usual local node endpoints and the confirmation depth and timings
+//! recommended for its Ethereum chain. Anything set on the command line, in the chain config or in a `--timings`
+//! file takes precedence. At startup the Hub node's chain id is checked against the profile, so a mainnet profile
+//! pointed at a testnet node, or the other way around, refuses to start.
+
+use crate::multi_chain::ChainConfig;
+use contact::client::Contact;
+use peggy_utils::error::PeggyError;
+use peggy_utils::timings::Timings;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+const LOCAL_COSMOS_LEGACY_RPC: &str = "http://127.0.0.1:1317";
+const LOCAL_COSMOS_GRPC: &str = "http://127.0.0.1:9090";
+const LOCAL_ETHEREUM_RPC: &str = "http://127.0.0.1:8545";
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Profile {
+    Mainnet,
+    Testnet,
+    /// the devnet started by the scripts in `tests/`, its contract is deployed fresh every time
+    Local,
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "mainnet" => Ok(Profile::Mainnet),
+            "testnet" => Ok(Profile::Testnet),
+            "local" => Ok(Profile::Local),
+            other => Err(format!(
+                "Unknown profile {}, expected mainnet, testnet or local",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Profile::Mainnet => write!(f, "mainnet"),
+            Profile::Testnet => write!(f, "testnet"),
+            Profile::Local => write!(f, "local"),
+        }
+    }
+}
+
+impl Profile {
+    pub fn hub_chain_id(&self) -> &'static str {
+        match self {
+            Profile::Mainnet => "mhub-mainnet-1",
+            Profile::Testnet => "mhub-test-10",
+            Profile::Local => "peggy-test",
+        }
+    }
+
+    pub fn contract_address(&self) -> Option<&'static str> {
+        match self {
+            Profile::Mainnet => Some("0xc735478ef7562ecc37662fc7c5e521eb835f9dab"),
+            Profile::Testnet => Some("0x8D0B99eAE97a247B2C3E9B3dA6774B8359a37537"),
+            Profile::Local => None,
+        }
+    }
+
+    pub fn peggy_id(&self) -> Option<&'static str> {
+        match self {
+            Profile::Mainnet => Some("minter-hub-1"),
+            Profile::Testnet => Some("defaultpeggyid"),
+            Profile::Local => None,
+        }
+    }
+
+    pub fn minter_multisig(&self) -> Option<&'static str> {
+        match self {
+            Profile::Mainnet => Some("Mx68f4839d7f32831b9234f9575f3b95e1afe21a56"),
+            Profile::Testnet => Some("Mx703880f64588b3247f8a583f1bef5a6ac5aeac59"),
+            Profile::Local => None,
+        }
+    }
+
+    pub fn fee_denom(&self) -> &'static str {
+        match self {
+            Profile::Mainnet | Profile::Testnet => "hub",
+            Profile::Local => "stake",
+        }
+    }
+
+    /// The recommended timings, a `--timings` file overrides them field by field
+    pub fn timings(&self) -> Timings {
+        match self {
+            // the defaults are made for Ethereum mainnet
+            Profile::Mainnet => Timings::default(),
+            // testnet value is worth little, claims can follow the chain more closely
+            Profile::Testnet => Timings {
+                eth_confirmations: 3,
+                ..Timings::default()
+            },
+            // a single geth node mining every second, it can't reorg
+            Profile::Local => Timings {
+                loop_speed: Duration::from_secs(2),
+                rpc_timeout: Duration::from_secs(5),
+                eth_tx_timeout: Duration::from_secs(30),
+                eth_block_time: Duration::from_secs(1),
+                eth_confirmations: 1,
+            },
+        }
+    }
+
+    /// Fills in every setting the profile knows that the config leaves empty
+    pub fn apply(&self, config: &mut ChainConfig) {
+        fn fill(field: &mut String, value: &str) {
+            if field.trim().is_empty() {
+                *field = value.to_string();
+            }
+        }
+        fill(&mut config.cosmos_legacy_rpc, LOCAL_COSMOS_LEGACY_RPC);
+        fill(&mut config.cosmos_grpc, LOCAL_COSMOS_GRPC);
+        fill(&mut config.ethereum_rpc, LOCAL_ETHEREUM_RPC);
+        fill(&mut config.fees, self.fee_denom());
+        if let Some(address) = self.contract_address() {
+            fill(&mut config.contract_address, address);
+        }
+        if config.peggy_id.is_none() {
+            config.peggy_id = self.peggy_id().map(|id| id.to_string());
+        }
+        if config.minter_multisig.is_none() {
+            config.minter_multisig = self.minter_multisig().map(|m| m.to_string());
+        }
+    }
+
+    /// Refuses a Hub node of another network than the profile's
+    pub async fn check_hub_chain_id(&self, contact: &Contact) -> Result<(), PeggyError> {
+        let block = contact.get_latest_block().await?;
+        let chain_id = block.block.header.chain_id;
+        if chain_id != self.hub_chain_id() {
+            return Err(PeggyError::InvalidBridgeStateError(format!(
+                "The Hub node is on chain {} but the {} profile is for {}",
+                chain_id,
+                self,
+                self.hub_chain_id()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Applies the config's profile, if it has one, and checks that every setting without a default is there
+pub fn resolve_profile(config: &mut ChainConfig) -> Result<(), String> {
+    if let Some(profile) = config.profile.as_deref() {
+        let profile: Profile = profile.parse()?;
+        profile.apply(config);
+    }
+    let required = [
+        ("cosmos_phrase", &config.cosmos_phrase),
+        ("ethereum_key", &config.ethereum_key),
+        ("cosmos_legacy_rpc", &config.cosmos_legacy_rpc),
+        ("cosmos_grpc", &config.cosmos_grpc),
+        ("ethereum_rpc", &config.ethereum_rpc),
+        ("contract_address", &config.contract_address),
+        ("fees", &config.fees),
+    ];
+    let missing: Vec<&str> = required
+        .iter()
+        .filter(|(_, value)| value.trim().is_empty())
+        .map(|(field, _)| *field)
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Chain {} is missing settings without a default: {}",
+            config.name,
+            missing.join(", ")
+        ))
+    }
+}
+
+#[test]
+fn test_resolve_profile() {
+    let keys = ChainConfig {
+        name: "test".to_string(),
+        cosmos_phrase: "phrase".to_string(),
+        ethereum_key: "key".to_string(),
+        ..Default::default()
+    };
+
+    let mut config = ChainConfig {
+        profile: Some("mainnet".to_string()),
+        ..keys.clone()
+    };
+    resolve_profile(&mut config).unwrap();
+    assert_eq!(
+        config.contract_address,
+        "0xc735478ef7562ecc37662fc7c5e521eb835f9dab"
+    );
+    assert_eq!(config.peggy_id.as_deref(), Some("minter-hub-1"));
+    assert_eq!(config.fees, "hub");
+    assert_eq!(config.cosmos_grpc, LOCAL_COSMOS_GRPC);
+
+    // what the operator set wins over the profile
+    let mut config = ChainConfig {
+        profile: Some("testnet".to_string()),
+        cosmos_grpc: "http://hub.example:9090".to_string(),
+        contract_address: "0x0000000000000000000000000000000000000001".to_string(),
+        peggy_id: Some("whitelabel".to_string()),
+        ..keys.clone()
+    };
+    resolve_profile(&mut config).unwrap();
+    assert_eq!(config.cosmos_grpc, "http://hub.example:9090");
+    assert_eq!(
+        config.contract_address,
+        "0x0000000000000000000000000000000000000001"
+    );
+    assert_eq!(config.peggy_id.as_deref(), Some("whitelabel"));
+    assert_eq!(
+        config.minter_multisig.as_deref(),
+        Some("Mx703880f64588b3247f8a583f1bef5a6ac5aeac59")
+    );
+
+    // the local contract changes with every deployment
+    let mut config = ChainConfig {
+        profile: Some("local".to_string()),
+        ..keys.clone()
+    };
+    assert_eq!(
+        resolve_profile(&mut config).unwrap_err(),
+        "Chain test is missing settings without a default: contract_address"
+    );
+    let mut config = keys.clone();
+    assert!(resolve_profile(&mut config)
+        .unwrap_err()
+        .contains("cosmos_legacy_rpc, cosmos_grpc, ethereum_rpc, contract_address, fees"));
+    let mut config = ChainConfig {
+        profile: Some("devnet".to_string()),
+        ..keys
+    };
+    assert!(resolve_profile(&mut config).is_err());
+
+    for profile in [Profile::Mainnet, Profile::Testnet, Profile::Local].iter() {
+        assert!(profile.timings().validate().is_ok());
+        assert_eq!(profile.to_string().parse::<Profile>().unwrap(), *profile);
+    }
+}
@@ -0,0 +1,181 @@
+//! Guided delegate key registration. `orchestrator register` checks the delegate keys against what the Hub
+//! already knows before anything is sent, then either registers them with the validator phrase or, given only
+//! the validator's address, prints the unsigned transaction and its sign doc for signing on an offline machine.
+//! Either way it waits until the Hub accepts the keys, unless `--offline` says the tx is broadcast later.
+//!
+//! An orchestrator address the Hub already maps to a validator, or an Ethereum address a bonded validator
+//! already signs with, is refused unless `--force`. Registering replaces the validator's delegate keys, but the
+//! Hub keeps the old orchestrator address mapped to the validator too.
+
+use crate::key_rotation::{is_registered, REGISTRATION_TIMEOUT};
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use contact::jsonrpc::error::JsonRpcError;
+use cosmos_peggy::offline::{export_tx, UnsignedTx};
+use cosmos_peggy::query::get_current_valset;
+use cosmos_peggy::send::set_orchestrator_address_msg;
+use deep_space::address::Address as CosmosAddress;
+use deep_space::coin::Coin;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::coin_math::CoinMath;
+use peggy_utils::error::PeggyError;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+
+/// A reason not to register a pair of delegate keys
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Conflict {
+    /// the Hub already maps this orchestrator address to a validator
+    OrchestratorRegistered(CosmosAddress),
+    /// a bonded validator already signs with this Ethereum address
+    EthereumAddressInUse(EthAddress),
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Conflict::OrchestratorRegistered(address) => write!(
+                f,
+                "the orchestrator address {} is already registered for a validator",
+                address
+            ),
+            Conflict::EthereumAddressInUse(address) => write!(
+                f,
+                "the Ethereum address {} is already used by a bonded validator, two validators signing with one key can't be told apart",
+                address
+            ),
+        }
+    }
+}
+
+pub async fn find_conflicts(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    orchestrator: CosmosAddress,
+    ethereum_address: EthAddress,
+) -> Result<Vec<Conflict>, PeggyError> {
+    let mut out = Vec::new();
+    if is_registered(grpc_client, orchestrator).await {
+        out.push(Conflict::OrchestratorRegistered(orchestrator));
+    }
+    if signs_current_valset(grpc_client, ethereum_address).await? {
+        out.push(Conflict::EthereumAddressInUse(ethereum_address));
+    }
+    Ok(out)
+}
+
+async fn signs_current_valset(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    ethereum_address: EthAddress,
+) -> Result<bool, PeggyError> {
+    Ok(get_current_valset(grpc_client)
+        .await?
+        .members
+        .iter()
+        .any(|member| member.eth_address == Some(ethereum_address)))
+}
+
+/// True if the account holds enough of the fee denom to pay one fee
+pub async fn can_pay_fee(contact: &Contact, address: CosmosAddress, fee: &Coin) -> bool {
+    match contact.get_balances(address).await {
+        Ok(balances) => balances
+            .result
+            .iter()
+            .any(|c| fee.fits_in(c).unwrap_or(false)),
+        Err(_) => false,
+    }
+}
+
+/// The unsigned registration transaction, to be signed offline with the validator key
+pub async fn unsigned_registration_tx(
+    contact: &Contact,
+    validator: CosmosAddress,
+    ethereum_address: EthAddress,
+    orchestrator: CosmosAddress,
+    fee: Coin,
+) -> Result<UnsignedTx, JsonRpcError> {
+    let msg = set_orchestrator_address_msg(validator, ethereum_address, orchestrator);
+    export_tx(contact, validator, vec![msg], fee).await
+}
+
+/// Waits until the Hub maps the orchestrator address to a validator, returns whether the Ethereum address is
+/// in the current valset, which it only is once the validator is bonded
+pub async fn wait_for_registration(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    orchestrator: CosmosAddress,
+    ethereum_address: EthAddress,
+) -> Result<bool, PeggyError> {
+    let start = Instant::now();
+    while !is_registered(grpc_client, orchestrator).await {
+        if Instant::now() - start > REGISTRATION_TIMEOUT {
+            return Err(PeggyError::InvalidBridgeStateError(format!(
+                "The delegate keys did not take effect after {:?}",
+                REGISTRATION_TIMEOUT
+            )));
+        }
+        delay_for(Duration::from_secs(5)).await;
+    }
+    signs_current_valset(grpc_client, ethereum_address).await
+}
+
+#[test]
+fn test_unsigned_registration_tx() {
+    use cosmos_peggy::offline::to_unsigned_tx;
+    use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+    use deep_space::stdfee::StdFee;
+    use deep_space::stdsignmsg::StdSignMsg;
+
+    let address = |secret: &[u8]| {
+        CosmosPrivateKey::from_secret(secret)
+            .to_public_key()
+            .unwrap()
+            .to_address()
+    };
+    let validator = address(b"validator");
+    let orchestrator = address(b"orchestrator");
+    let ethereum_address = EthAddress::from_slice(&[0x11; 20]).unwrap();
+    let fee = Coin {
+        denom: "hub".to_string(),
+        amount: 1u32.into(),
+    };
+
+    let unsigned = to_unsigned_tx(StdSignMsg {
+        chain_id: "hub-test".to_string(),
+        account_number: 7,
+        sequence: 3,
+        fee: StdFee {
+            amount: vec![fee],
+            gas: 500_000u64.into(),
+        },
+        msgs: vec![set_orchestrator_address_msg(
+            validator,
+            ethereum_address,
+            orchestrator,
+        )],
+        memo: String::new(),
+    });
+    let json = serde_json::to_value(&unsigned).unwrap();
+    let tx = &json["tx"];
+    assert_eq!(tx["type"], "cosmos-sdk/StdTx");
+    let msg = &tx["value"]["msg"][0];
+    assert_eq!(msg["type"], "peggy/MsgSetOrchestratorAddress");
+    assert_eq!(
+        msg["value"]["validator"],
+        validator.to_bech32("cosmosvaloper").unwrap()
+    );
+    assert_eq!(msg["value"]["orchestrator"], orchestrator.to_string());
+    assert_eq!(tx["value"]["signatures"], serde_json::json!([]));
+    let sign_doc = &json["sign_doc"];
+    assert_eq!(sign_doc["chain_id"], "hub-test");
+    assert_eq!(sign_doc["account_number"], "7");
+    assert_eq!(sign_doc["sequence"], "3");
+    assert_eq!(&sign_doc["msgs"][0], msg);
+    assert_eq!(
+        Conflict::OrchestratorRegistered(orchestrator).to_string(),
+        format!(
+            "the orchestrator address {} is already registered for a validator",
+            orchestrator
+        )
+    );
+}
@@ -0,0 +1,138 @@
+//! Relay receipts, with `--relay-receipts` every valset update and batch our relayer sends to Ethereum is
+//! reported to the Hub in a `MsgRelayReceipt` signed with our Cosmos delegate key. The Hub only accepts
+//! receipts from orchestrators for a valset or batch it still has and doesn't store them, it emits them as
+//! events so relayer work can be accounted for on chain, for example by a future reward scheme.
+//!
+//! The relay recorder queues a receipt as soon as the Ethereum transaction is sent, before it is mined, so a
+//! relay that reverts or is beaten by another relayer is reported too. Whoever counts receipts has to check
+//! the transaction on Ethereum. The queue is sent in one tx every `RECEIPT_SPEED`, a Hub that doesn't know
+//! the message yet rejects it and the receipts are dropped with a warning, they are only worth a retry for
+//! a flaky node. The queue holds `--task-queues` `relay_receipts` receipts, the oldest are dropped beyond that.
+
+use crate::key_rotation::KeyRing;
+use crate::runtime::BoundedQueue;
+use contact::client::Contact;
+use cosmos_peggy::messages::PeggyMsg;
+use cosmos_peggy::send::{relay_receipt_msg, send_relay_receipts};
+use deep_space::address::Address;
+use deep_space::coin::Coin;
+use relayer::main_loop::RelayedTx;
+use std::time::Duration;
+use tokio::time::delay_for;
+
+/// How often queued receipts are sent to the Hub
+pub const RECEIPT_SPEED: Duration = Duration::from_secs(60);
+/// How often a failed send is tried again before its receipts are dropped
+const RECEIPT_ATTEMPTS: u32 = 3;
+
+/// Relays waiting for their receipt to be sent
+pub type ReceiptQueue = BoundedQueue<RelayedTx>;
+
+/// The receipt `relayer` sends for `tx`
+pub fn receipt_msg(relayer: Address, tx: &RelayedTx) -> PeggyMsg {
+    match tx {
+        RelayedTx::Valset { nonce, tx_hash } => {
+            relay_receipt_msg(relayer, *nonce, None, tx_hash.clone())
+        }
+        RelayedTx::Batch {
+            token_contract,
+            nonce,
+            tx_hash,
+            ..
+        } => relay_receipt_msg(relayer, *nonce, Some(*token_contract), tx_hash.clone()),
+    }
+}
+
+/// Sends the queued receipts to the Hub every `RECEIPT_SPEED`, does nothing without a queue
+pub async fn relay_receipt_loop(
+    contact: Contact,
+    keys: KeyRing,
+    fee: Coin,
+    queue: Option<ReceiptQueue>,
+) {
+    let queue = match queue {
+        Some(queue) => queue,
+        None => return,
+    };
+    let mut pending: Vec<RelayedTx> = Vec::new();
+    let mut attempts = 0;
+    loop {
+        delay_for(RECEIPT_SPEED).await;
+        pending.extend(queue.take());
+        if pending.is_empty() {
+            continue;
+        }
+        let our_keys = keys.current();
+        let msgs = pending
+            .iter()
+            .map(|tx| receipt_msg(our_keys.cosmos_address(), tx))
+            .collect();
+        match send_relay_receipts(&contact, our_keys.cosmos_key, fee.clone(), msgs, None).await {
+            Ok(res) => {
+                info!(
+                    "Sent {} relay receipt(s) to the Hub with {}",
+                    pending.len(),
+                    res.txhash
+                );
+                pending.clear();
+                attempts = 0;
+            }
+            Err(e) => {
+                attempts += 1;
+                if attempts < RECEIPT_ATTEMPTS {
+                    warn!("Failed to send relay receipts, trying again: {:?}", e);
+                } else {
+                    warn!(
+                        "Dropping {} relay receipt(s), the Hub may not support them: {:?}",
+                        pending.len(),
+                        e
+                    );
+                    pending.clear();
+                    attempts = 0;
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_receipt_msg() {
+    use clarity::{Address as EthAddress, Uint256};
+    use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+    let relayer = CosmosPrivateKey::from_secret(b"relay receipts")
+        .to_public_key()
+        .unwrap()
+        .to_address();
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let tx_hash: Uint256 = 0xabcdu64.into();
+    let hash = format!("0x{}abcd", "0".repeat(60));
+
+    let valset = RelayedTx::Valset {
+        nonce: 7,
+        tx_hash: tx_hash.clone(),
+    };
+    match receipt_msg(relayer, &valset) {
+        PeggyMsg::RelayReceiptMsg(receipt) => {
+            assert_eq!(receipt.nonce, Uint256::from(7u64));
+            assert!(receipt.token_contract.is_empty());
+            assert_eq!(receipt.eth_tx_hash, hash);
+        }
+        other => panic!("Unexpected receipt {:?}", other),
+    }
+
+    let batch = RelayedTx::Batch {
+        token_contract: token,
+        nonce: 3,
+        tx_hash,
+        risky_destinations: Vec::new(),
+    };
+    match receipt_msg(relayer, &batch) {
+        PeggyMsg::RelayReceiptMsg(receipt) => {
+            assert_eq!(receipt.relayer, relayer);
+            assert_eq!(receipt.token_contract, token.to_string());
+        }
+        other => panic!("Unexpected receipt {:?}", other),
+    }
+}
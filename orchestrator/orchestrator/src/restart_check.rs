@@ -0,0 +1,453 @@
+//! Replays the journal and state store against the current Hub state and makes sure that nothing we
+//! already had accepted would be submitted again. A disagreement here means either our local records or
+//! the chain are not what we think they are, and signing anything in that situation risks a double claim.
+//!
+//! Every startup also reconciles the state store with the journal and the chains. It looks up the last
+//! journaled Hub transaction and Ethereum relay to see whether they made it, and brings the state store's
+//! nonces in line where a crash left it behind the journal or out of step with the Hub.
+
+use crate::journal::{Journal, JournalEntry};
+use crate::state_store::{LocalStorage, OrchestratorState, StateStore};
+use crate::verify_execution::parse_tx_hash;
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use cosmos_peggy::query::{
+    get_last_event_nonce, get_oldest_unsigned_transaction_batch, get_oldest_unsigned_valset,
+};
+use deep_space::address::Address as CosmosAddress;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use std::fmt;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// What the Hub currently expects from us, in other words what the orchestrator would submit next
+#[derive(Debug, Clone, Default)]
+pub struct PendingWork {
+    pub last_event_nonce: u64,
+    pub unsigned_valset_nonce: Option<u64>,
+    pub unsigned_batch: Option<(u64, EthAddress)>,
+}
+
+pub async fn get_pending_work(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    our_cosmos_address: CosmosAddress,
+) -> Result<PendingWork, PeggyError> {
+    let last_event_nonce = get_last_event_nonce(grpc_client, our_cosmos_address).await?;
+    let unsigned_valset = get_oldest_unsigned_valset(grpc_client, our_cosmos_address).await?;
+    let unsigned_batch =
+        get_oldest_unsigned_transaction_batch(grpc_client, our_cosmos_address).await?;
+    Ok(PendingWork {
+        last_event_nonce,
+        unsigned_valset_nonce: unsigned_valset.map(|v| v.nonce),
+        unsigned_batch: unsigned_batch.map(|b| (b.nonce, b.token_contract)),
+    })
+}
+
+/// Loads our local records and checks them against the Hub, see check_replay
+pub async fn verify_restart(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    our_cosmos_address: CosmosAddress,
+    journal: &Journal,
+    state_store: &StateStore,
+) -> Result<(), PeggyError> {
+    let entries = journal.entries()?;
+    let state = state_store.load()?;
+    let pending = get_pending_work(grpc_client, our_cosmos_address).await?;
+    check_replay(&entries, &state, &pending)?;
+    info!(
+        "Restart check passed, {} journal entries consistent with the Hub",
+        entries.len()
+    );
+    Ok(())
+}
+
+pub fn check_replay(
+    entries: &[JournalEntry],
+    state: &OrchestratorState,
+    pending: &PendingWork,
+) -> Result<(), PeggyError> {
+    if state.last_event_nonce > pending.last_event_nonce {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "State store says event nonce {} was accepted but the Hub only has {}, claims would be resubmitted",
+            state.last_event_nonce, pending.last_event_nonce
+        )));
+    }
+
+    for entry in entries {
+        match entry {
+            JournalEntry::Claims {
+                event_nonces,
+                txhash,
+                ..
+            } => {
+                if let Some(nonce) = event_nonces.iter().find(|n| **n > pending.last_event_nonce) {
+                    return Err(PeggyError::InvalidBridgeStateError(format!(
+                        "Claim for event nonce {} in tx {} was accepted but the Hub only has {}, it would be resubmitted",
+                        nonce,
+                        explorer::tx(Chain::Hub, txhash),
+                        pending.last_event_nonce
+                    )));
+                }
+            }
+            JournalEntry::ValsetConfirm { nonce, txhash } => {
+                if pending.unsigned_valset_nonce == Some(*nonce) {
+                    return Err(PeggyError::InvalidBridgeStateError(format!(
+                        "Valset {} was confirmed in tx {} but the Hub still asks for our signature",
+                        nonce,
+                        explorer::tx(Chain::Hub, txhash)
+                    )));
+                }
+            }
+            JournalEntry::BatchConfirm {
+                nonce,
+                token_contract,
+                txhash,
+                ..
+            } => {
+                if pending.unsigned_batch == Some((*nonce, *token_contract)) {
+                    return Err(PeggyError::InvalidBridgeStateError(format!(
+                        "Batch {} for {} was confirmed in tx {} but the Hub still asks for our signature",
+                        nonce,
+                        token_contract,
+                        explorer::tx(Chain::Hub, txhash)
+                    )));
+                }
+            }
+            JournalEntry::ValsetRelay { .. }
+            | JournalEntry::BatchRelay { .. }
+            | JournalEntry::Started { .. } => {}
+        }
+    }
+    Ok(())
+}
+
+/// What became of a journaled transaction
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TxStatus {
+    Included,
+    Failed,
+    /// sent to Ethereum but not mined yet
+    Pending,
+    /// dropped, or never made it out of the node
+    Missing,
+}
+
+/// The last journaled transaction on one chain and what became of it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LastTx {
+    pub chain: Chain,
+    pub description: String,
+    pub txhash: String,
+    pub status: TxStatus,
+}
+
+impl fmt::Display for LastTx {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tx = explorer::tx(self.chain, &self.txhash);
+        match self.status {
+            TxStatus::Included => write!(f, "{} in tx {} was included", self.description, tx),
+            TxStatus::Failed => write!(f, "{} in tx {} failed", self.description, tx),
+            TxStatus::Pending => write!(
+                f,
+                "{} in tx {} is still pending, the relayer's next transactions may replace it",
+                self.description, tx
+            ),
+            TxStatus::Missing => write!(
+                f,
+                "{} in tx {} is not on chain, if it is still needed it will be sent again",
+                self.description, tx
+            ),
+        }
+    }
+}
+
+/// The last journaled Hub submission as (description, txhash)
+pub fn last_hub_submission(entries: &[JournalEntry]) -> Option<(String, String)> {
+    entries.iter().rev().find_map(|entry| match entry {
+        JournalEntry::Claims {
+            event_nonces,
+            txhash,
+            ..
+        } => Some((
+            format!("Claims for event nonces {:?}", event_nonces),
+            txhash.clone(),
+        )),
+        JournalEntry::ValsetConfirm { nonce, txhash } => {
+            Some((format!("Valset {} confirm", nonce), txhash.clone()))
+        }
+        JournalEntry::BatchConfirm {
+            nonce,
+            token_contract,
+            txhash,
+            ..
+        } => Some((
+            format!("Batch {}:{} confirm", token_contract, nonce),
+            txhash.clone(),
+        )),
+        _ => None,
+    })
+}
+
+/// The last journaled Ethereum relay as (description, tx hash)
+pub fn last_ethereum_relay(entries: &[JournalEntry]) -> Option<(String, String)> {
+    entries.iter().rev().find_map(|entry| match entry {
+        JournalEntry::ValsetRelay { nonce, tx_hash, .. } => {
+            Some((format!("Valset {} update", nonce), tx_hash.clone()))
+        }
+        JournalEntry::BatchRelay {
+            nonce,
+            token_contract,
+            tx_hash,
+            ..
+        } => Some((
+            format!("Batch {}:{} relay", token_contract, nonce),
+            tx_hash.clone(),
+        )),
+        _ => None,
+    })
+}
+
+/// Brings the state store in line with the journal and the Hub. The Hub's event nonce is the truth, the
+/// confirm nonces are raised to what the journal has, a crash between journaling a submission and
+/// updating the state leaves them behind. Returns the reconciled state and what was changed
+pub fn reconcile_state(
+    entries: &[JournalEntry],
+    state: &OrchestratorState,
+    pending: &PendingWork,
+) -> (OrchestratorState, Vec<String>) {
+    let mut reconciled = state.clone();
+    let mut changes = Vec::new();
+    if state.last_event_nonce != pending.last_event_nonce {
+        changes.push(format!(
+            "event nonce {} -> {}, the Hub's",
+            state.last_event_nonce, pending.last_event_nonce
+        ));
+        reconciled.last_event_nonce = pending.last_event_nonce;
+    }
+    let journaled_valset = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            JournalEntry::ValsetConfirm { nonce, .. } => Some(*nonce),
+            _ => None,
+        })
+        .max();
+    if let Some(nonce) = journaled_valset.filter(|n| *n > state.last_valset_confirm_nonce) {
+        changes.push(format!(
+            "valset confirm nonce {} -> {}, the journal's",
+            state.last_valset_confirm_nonce, nonce
+        ));
+        reconciled.last_valset_confirm_nonce = nonce;
+    }
+    let journaled_batch = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            JournalEntry::BatchConfirm { nonce, .. } => Some(*nonce),
+            _ => None,
+        })
+        .max();
+    if let Some(nonce) = journaled_batch.filter(|n| *n > state.last_batch_confirm_nonce) {
+        changes.push(format!(
+            "batch confirm nonce {} -> {}, the journal's",
+            state.last_batch_confirm_nonce, nonce
+        ));
+        reconciled.last_batch_confirm_nonce = nonce;
+    }
+    (reconciled, changes)
+}
+
+async fn hub_tx_status(contact: &Contact, txhash: &str) -> TxStatus {
+    match contact.get_tx_by_hash(txhash).await {
+        Ok(res) if res.code.unwrap_or(0) == 0 => TxStatus::Included,
+        Ok(_) => TxStatus::Failed,
+        // also when the node doesn't index txs, we can't tell the two apart
+        Err(_) => TxStatus::Missing,
+    }
+}
+
+async fn ethereum_tx_status(web3: &Web3, tx_hash: &str) -> Result<TxStatus, PeggyError> {
+    let tx_hash = parse_tx_hash(tx_hash).map_err(PeggyError::InvalidBridgeStateError)?;
+    Ok(match web3.eth_get_transaction_by_hash(tx_hash).await? {
+        Some(tx) if tx.block_number.is_some() => TxStatus::Included,
+        Some(_) => TxStatus::Pending,
+        None => TxStatus::Missing,
+    })
+}
+
+/// Checks the last journaled transactions against the chains and reconciles the state store before
+/// the orchestrator resumes, returns the last transactions that were checked
+pub async fn reconcile_restart(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    contact: &Contact,
+    web3: &Web3,
+    our_cosmos_address: CosmosAddress,
+    storage: &LocalStorage,
+) -> Result<Vec<LastTx>, PeggyError> {
+    let entries = storage.journal.entries()?;
+    let state = storage.state.load()?;
+    let pending = get_pending_work(grpc_client, our_cosmos_address).await?;
+
+    let mut last_txs = Vec::new();
+    if let Some((description, txhash)) = last_hub_submission(&entries) {
+        let status = hub_tx_status(contact, &txhash).await;
+        last_txs.push(LastTx {
+            chain: Chain::Hub,
+            description,
+            txhash,
+            status,
+        });
+    }
+    if let Some((description, txhash)) = last_ethereum_relay(&entries) {
+        let status = ethereum_tx_status(web3, &txhash).await?;
+        last_txs.push(LastTx {
+            chain: Chain::Ethereum,
+            description,
+            txhash,
+            status,
+        });
+    }
+    for last in last_txs.iter() {
+        if last.status == TxStatus::Included {
+            info!("{}", last);
+        } else {
+            warn!("{}", last);
+        }
+    }
+
+    let (reconciled, changes) = reconcile_state(&entries, &state, &pending);
+    if !changes.is_empty() {
+        warn!(
+            "Reconciled the state store with the journal and the Hub: {}",
+            changes.join(", ")
+        );
+        storage.state.update(|state| {
+            state.last_event_nonce = reconciled.last_event_nonce;
+            state.last_valset_confirm_nonce = reconciled.last_valset_confirm_nonce;
+            state.last_batch_confirm_nonce = reconciled.last_batch_confirm_nonce;
+        })?;
+    }
+    Ok(last_txs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn journal() -> Vec<JournalEntry> {
+        vec![
+            JournalEntry::Claims {
+                event_nonces: vec![1, 2, 3],
+                txhash: "A1".to_string(),
+                events: Vec::new(),
+                timestamp: 0,
+            },
+            JournalEntry::ValsetConfirm {
+                nonce: 4,
+                txhash: "B2".to_string(),
+            },
+            JournalEntry::BatchConfirm {
+                nonce: 7,
+                token_contract: "0x34Ac3eB6180FdD94043664C22043F004734Dc480"
+                    .parse()
+                    .unwrap(),
+                txhash: "C3".to_string(),
+                total_amount: 0u64.into(),
+                total_fee: 0u64.into(),
+                timestamp: 0,
+            },
+        ]
+    }
+
+    fn state() -> OrchestratorState {
+        OrchestratorState {
+            last_event_nonce: 3,
+            last_valset_confirm_nonce: 4,
+            last_batch_confirm_nonce: 7,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_consistent_restart() {
+        let pending = PendingWork {
+            last_event_nonce: 5,
+            unsigned_valset_nonce: Some(5),
+            unsigned_batch: None,
+        };
+        assert!(check_replay(&journal(), &state(), &pending).is_ok());
+        assert!(check_replay(&[], &OrchestratorState::default(), &pending).is_ok());
+    }
+
+    #[test]
+    fn test_claims_would_be_resubmitted() {
+        let pending = PendingWork {
+            last_event_nonce: 2,
+            ..Default::default()
+        };
+        assert!(check_replay(&journal(), &OrchestratorState::default(), &pending).is_err());
+        assert!(check_replay(&[], &state(), &pending).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_state() {
+        let pending = PendingWork {
+            last_event_nonce: 5,
+            ..Default::default()
+        };
+        let (reconciled, changes) = reconcile_state(&journal(), &state(), &pending);
+        assert_eq!(reconciled.last_event_nonce, 5);
+        assert_eq!(changes.len(), 1);
+
+        // a crash after journaling the confirms but before updating the state
+        let behind = OrchestratorState {
+            last_event_nonce: 5,
+            ..Default::default()
+        };
+        let (reconciled, changes) = reconcile_state(&journal(), &behind, &pending);
+        assert_eq!(
+            reconciled,
+            OrchestratorState {
+                last_event_nonce: 5,
+                ..state()
+            }
+        );
+        assert_eq!(changes.len(), 2);
+
+        let mut entries = journal();
+        entries.push(JournalEntry::BatchRelay {
+            nonce: 7,
+            token_contract: "0x34Ac3eB6180FdD94043664C22043F004734Dc480"
+                .parse()
+                .unwrap(),
+            tx_hash: "0x0a".to_string(),
+            timestamp: 0,
+            risky_destinations: Vec::new(),
+        });
+        assert_eq!(last_hub_submission(&entries).unwrap().1, "C3");
+        assert_eq!(last_ethereum_relay(&entries).unwrap().1, "0x0a");
+        assert!(last_ethereum_relay(&journal()).is_none());
+    }
+
+    #[test]
+    fn test_confirms_would_be_resubmitted() {
+        let pending = PendingWork {
+            last_event_nonce: 3,
+            unsigned_valset_nonce: Some(4),
+            unsigned_batch: None,
+        };
+        assert!(check_replay(&journal(), &state(), &pending).is_err());
+
+        let pending = PendingWork {
+            last_event_nonce: 3,
+            unsigned_valset_nonce: None,
+            unsigned_batch: Some((
+                7,
+                "0x34Ac3eB6180FdD94043664C22043F004734Dc480"
+                    .parse()
+                    .unwrap(),
+            )),
+        };
+        assert!(check_replay(&journal(), &state(), &pending).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+//! All loops of a chain share one single threaded event loop, so a task that blocks the thread, for example
+//! on a slow RPC response it decodes synchronously, holds up every other task of that chain. Here we watch
+//! for that: each chain runs a watchdog that measures how late its timer fires and the main loops report
+//! iterations that take unusually long. `orchestrator multi --worker-threads` spreads the chains over
+//! several threads, each with its own event loop, so one chain can't starve the others.
+//!
+//! Work one task hands to another goes through a `BoundedQueue`, so a task that falls behind can't grow its
+//! queue without limit. `--task-queues` sizes the queues of `QUEUED_TASKS`, a full queue drops its oldest item.
+//!
+//! Builds with the `task-metrics` feature also count every task's iterations, their slowest one, the event
+//! loop lag and the items dropped from full queues, and log them every `METRICS_INTERVAL` under the
+//! `task_metrics` target. tokio-console needs tokio 1, until we are there this is the hook for it.
+
+use crate::multi_chain::ChainConfig;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+
+/// How often the watchdog checks the event loop
+const WATCHDOG_INTERVAL: Duration = Duration::from_secs(1);
+/// The watchdog warns when its timer fires this much too late
+const LAG_WARNING: Duration = Duration::from_millis(500);
+/// Loop iterations that take longer than this are reported
+const SLOW_ITERATION: Duration = Duration::from_secs(60);
+/// How many items a task's queue holds unless `--task-queues` says otherwise
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1_000;
+/// The tasks with a queue, the relay receipts our relayer queues for the receipt loop to send
+pub const QUEUED_TASKS: &[&str] = &["relay_receipts"];
+
+/// Spreads the chains round robin over `threads` groups, each group runs on its own thread
+pub fn assign_chains(configs: Vec<ChainConfig>, threads: usize) -> Vec<Vec<ChainConfig>> {
+    let threads = threads.max(1).min(configs.len().max(1));
+    let mut groups: Vec<Vec<ChainConfig>> = vec![Vec::new(); threads];
+    for (i, config) in configs.into_iter().enumerate() {
+        groups[i % threads].push(config);
+    }
+    groups
+}
+
+/// The queue capacities of `--task-queues`, comma separated `<task>:<capacity>` pairs
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TaskQueues {
+    capacities: BTreeMap<String, usize>,
+}
+
+impl TaskQueues {
+    pub fn parse(input: Option<&str>) -> Result<Self, String> {
+        let mut capacities = BTreeMap::new();
+        let input = match input {
+            Some(input) => input,
+            None => return Ok(TaskQueues { capacities }),
+        };
+        for entry in input.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let mut parts = entry.splitn(2, ':');
+            let (task, capacity) = match (parts.next(), parts.next()) {
+                (Some(task), Some(capacity)) => (task.trim(), capacity.trim()),
+                _ => {
+                    return Err(format!(
+                        "Invalid task queue {}, expected <task>:<capacity>",
+                        entry
+                    ))
+                }
+            };
+            if !QUEUED_TASKS.contains(&task) {
+                return Err(format!(
+                    "Unknown task {}, expected one of {}",
+                    task,
+                    QUEUED_TASKS.join(", ")
+                ));
+            }
+            let capacity: usize = capacity
+                .parse()
+                .map_err(|_| format!("Invalid capacity {} of task {}", capacity, task))?;
+            if capacity == 0 {
+                return Err(format!("The queue of task {} needs room for an item", task));
+            }
+            capacities.insert(task.to_string(), capacity);
+        }
+        Ok(TaskQueues { capacities })
+    }
+
+    pub fn capacity(&self, task: &str) -> usize {
+        self.capacities
+            .get(task)
+            .copied()
+            .unwrap_or(DEFAULT_QUEUE_CAPACITY)
+    }
+
+    /// An empty queue for `task` with its configured capacity
+    pub fn queue<T>(&self, task: &'static str) -> BoundedQueue<T> {
+        BoundedQueue {
+            task,
+            capacity: self.capacity(task),
+            items: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+}
+
+/// A queue one task fills and another drains, shared between them by cloning it. At its capacity the oldest
+/// item is dropped with a warning to make room
+#[derive(Debug)]
+pub struct BoundedQueue<T> {
+    task: &'static str,
+    capacity: usize,
+    items: Arc<Mutex<VecDeque<T>>>,
+}
+
+impl<T> Clone for BoundedQueue<T> {
+    fn clone(&self) -> Self {
+        BoundedQueue {
+            task: self.task,
+            capacity: self.capacity,
+            items: self.items.clone(),
+        }
+    }
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn push(&self, item: T) {
+        let mut items = self.items.lock().unwrap();
+        if items.len() >= self.capacity {
+            items.pop_front();
+            metrics::record_drop(self.task);
+            warn!(
+                "The {} queue is full at {} items, dropping the oldest",
+                self.task, self.capacity
+            );
+        }
+        items.push_back(item);
+    }
+
+    /// Empties the queue, oldest item first
+    pub fn take(&self) -> Vec<T> {
+        self.items.lock().unwrap().drain(..).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Runs next to a chain's loops and warns when the event loop they share is starved
+pub async fn watch_event_loop(chain: String) {
+    loop {
+        let start = Instant::now();
+        delay_for(WATCHDOG_INTERVAL).await;
+        let lag = start.elapsed().checked_sub(WATCHDOG_INTERVAL);
+        if let Some(lag) = lag {
+            metrics::record_lag(&chain, lag);
+        }
+        if let Some(lag) = lag.filter(|lag| *lag > LAG_WARNING) {
+            warn!(
+                "Event loop of chain {} is lagging {}ms behind, a task is blocking the thread",
+                chain,
+                lag.as_millis()
+            );
+        }
+        metrics::report();
+    }
+}
+
+/// Reports a main loop iteration that took unusually long
+pub fn check_iteration(task: &str, elapsed: Duration) {
+    metrics::record_iteration(task, elapsed);
+    if elapsed > SLOW_ITERATION {
+        warn!(
+            "{} loop iteration took {}s, its RPC endpoints may be slow",
+            task,
+            elapsed.as_secs()
+        );
+    }
+}
+
+/// How often the `task-metrics` feature logs what it counted
+#[cfg(feature = "task-metrics")]
+pub const METRICS_INTERVAL: Duration = Duration::from_secs(60);
+
+#[cfg(feature = "task-metrics")]
+mod metrics {
+    use super::METRICS_INTERVAL;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    #[derive(Debug, Default)]
+    struct TaskMetrics {
+        iterations: u64,
+        slowest: Duration,
+        dropped: u64,
+    }
+
+    struct Metrics {
+        tasks: BTreeMap<String, TaskMetrics>,
+        /// the worst event loop lag of every chain
+        lag: BTreeMap<String, Duration>,
+        reported: Instant,
+    }
+
+    lazy_static! {
+        static ref METRICS: Mutex<Metrics> = Mutex::new(Metrics {
+            tasks: BTreeMap::new(),
+            lag: BTreeMap::new(),
+            reported: Instant::now(),
+        });
+    }
+
+    pub fn record_iteration(task: &str, elapsed: Duration) {
+        let mut metrics = METRICS.lock().unwrap();
+        let task = metrics.tasks.entry(task.to_string()).or_default();
+        task.iterations += 1;
+        task.slowest = task.slowest.max(elapsed);
+    }
+
+    pub fn record_drop(task: &str) {
+        let mut metrics = METRICS.lock().unwrap();
+        metrics.tasks.entry(task.to_string()).or_default().dropped += 1;
+    }
+
+    pub fn record_lag(chain: &str, lag: Duration) {
+        let mut metrics = METRICS.lock().unwrap();
+        let worst = metrics.lag.entry(chain.to_string()).or_default();
+        *worst = (*worst).max(lag);
+    }
+
+    /// Logs and resets the counts once every `METRICS_INTERVAL`, whichever chain's watchdog gets there first
+    pub fn report() {
+        let mut metrics = METRICS.lock().unwrap();
+        if metrics.reported.elapsed() < METRICS_INTERVAL {
+            return;
+        }
+        for (task, counts) in metrics.tasks.iter() {
+            info!(
+                target: "task_metrics",
+                "task {} iterations {} slowest {}ms dropped {}",
+                task,
+                counts.iterations,
+                counts.slowest.as_millis(),
+                counts.dropped
+            );
+        }
+        for (chain, lag) in metrics.lag.iter() {
+            info!(
+                target: "task_metrics",
+                "chain {} worst event loop lag {}ms",
+                chain,
+                lag.as_millis()
+            );
+        }
+        metrics.tasks.clear();
+        metrics.lag.clear();
+        metrics.reported = Instant::now();
+    }
+}
+
+#[cfg(not(feature = "task-metrics"))]
+mod metrics {
+    use std::time::Duration;
+
+    pub fn record_iteration(_task: &str, _elapsed: Duration) {}
+
+    pub fn record_drop(_task: &str) {}
+
+    pub fn record_lag(_chain: &str, _lag: Duration) {}
+
+    pub fn report() {}
+}
+
+#[test]
+fn test_task_queues() {
+    let queues = TaskQueues::parse(None).unwrap();
+    assert_eq!(queues.capacity("relay_receipts"), DEFAULT_QUEUE_CAPACITY);
+    let queues = TaskQueues::parse(Some(" relay_receipts:2 ")).unwrap();
+    assert_eq!(queues.capacity("relay_receipts"), 2);
+    assert!(TaskQueues::parse(Some("relay_receipts")).is_err());
+    assert!(TaskQueues::parse(Some("relay_receipts:0")).is_err());
+    assert!(TaskQueues::parse(Some("relay_receipts:many")).is_err());
+    assert!(TaskQueues::parse(Some("oracle:10")).is_err());
+
+    // a full queue drops its oldest item, every clone shares the items
+    let queue = queues.queue("relay_receipts");
+    let producer = queue.clone();
+    for i in 0..3 {
+        producer.push(i);
+    }
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.take(), vec![1, 2]);
+    assert!(producer.is_empty());
+}
+
+#[test]
+fn test_assign_chains() {
+    let chains: Vec<ChainConfig> = (0..5)
+        .map(|i| ChainConfig {
+            name: i.to_string(),
+            ..Default::default()
+        })
+        .collect();
+    let names = |groups: Vec<Vec<ChainConfig>>| -> Vec<Vec<String>> {
+        groups
+            .into_iter()
+            .map(|g| g.into_iter().map(|c| c.name).collect())
+            .collect()
+    };
+    assert_eq!(
+        names(assign_chains(chains.clone(), 2)),
+        vec![vec!["0", "2", "4"], vec!["1", "3"]]
+    );
+    assert_eq!(names(assign_chains(chains.clone(), 0)).len(), 1);
+    // never more threads than chains
+    assert_eq!(names(assign_chains(chains, 8)).len(), 5);
+}
@@ -0,0 +1,345 @@
+//! Local double-sign protection for valset and batch confirms. Before a confirm is signed the checkpoint it
+//! signs is recorded in the state store, by nonce for valsets and by token and nonce for batches, so signing
+//! stays idempotent across restarts and Hub halts: a confirm that never made it onto the Hub is simply signed
+//! again, which gives the same signature. The Hub presenting a different checkpoint for something we already
+//! signed, after a rollback, from a Hub bug or a malicious node, is refused with a `VALSET CONFLICT` or
+//! `BATCH CONFLICT` alert. Two signatures for one nonce would let anyone relay either and make us slashable.
+//!
+//! Only the last `SIGNED_CHECKPOINTS_KEPT` nonces are kept, the highest one dropped is remembered and nothing
+//! at or below it is signed anymore, since we can no longer tell what we signed for it. A checkpoint that
+//! can't be recorded isn't signed. Without a state dir the record only lasts as long as the process.
+
+use crate::state_store::{LocalStorage, OrchestratorState};
+use clarity::Address as EthAddress;
+use std::collections::{BTreeMap, HashSet};
+
+/// How many signed checkpoints are kept, the oldest are dropped first and never signed again. Nonces that
+/// old are long past their signing window
+pub const SIGNED_CHECKPOINTS_KEPT: usize = 1000;
+
+/// What the record says about signing a checkpoint for a nonce
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Signed {
+    /// nothing was signed for the nonce yet
+    Nothing,
+    /// this very checkpoint was signed, signing it again is harmless
+    Same,
+    /// another checkpoint was signed for the nonce, this is its hash
+    Other(String),
+    /// the nonce is at or below the highest one dropped from the record, whatever was signed for it is
+    /// forgotten
+    Forgotten,
+}
+
+/// Looks up what was signed for `nonce`, `evicted` is the highest nonce dropped from `signed`
+pub fn signed_for(
+    signed: &BTreeMap<u64, String>,
+    evicted: Option<u64>,
+    nonce: u64,
+    checkpoint: &str,
+) -> Signed {
+    match signed.get(&nonce) {
+        None if evicted.map_or(false, |evicted| nonce <= evicted) => Signed::Forgotten,
+        None => Signed::Nothing,
+        Some(ours) if ours == checkpoint => Signed::Same,
+        Some(ours) => Signed::Other(ours.clone()),
+    }
+}
+
+/// Records `checkpoint` as signed for `nonce`, dropping the oldest beyond `SIGNED_CHECKPOINTS_KEPT` and
+/// raising `evicted` to the highest nonce dropped
+pub fn remember(
+    signed: &mut BTreeMap<u64, String>,
+    evicted: &mut Option<u64>,
+    nonce: u64,
+    checkpoint: &str,
+) {
+    signed.insert(nonce, checkpoint.to_string());
+    while signed.len() > SIGNED_CHECKPOINTS_KEPT {
+        let oldest = *signed.keys().next().unwrap();
+        signed.remove(&oldest);
+        *evicted = Some(evicted.map_or(oldest, |evicted| evicted.max(oldest)));
+    }
+}
+
+/// The signer's record of signed checkpoints, loaded from and written through to the state store
+/// `remember` for the batches of `token`, `evicted` holds the highest nonce dropped by token
+fn remember_batch(
+    signed: &mut BTreeMap<String, BTreeMap<u64, String>>,
+    evicted: &mut BTreeMap<String, u64>,
+    token: &str,
+    nonce: u64,
+    checkpoint: &str,
+) {
+    let mut dropped = evicted.get(token).copied();
+    remember(
+        signed.entry(token.to_string()).or_default(),
+        &mut dropped,
+        nonce,
+        checkpoint,
+    );
+    if let Some(dropped) = dropped {
+        evicted.insert(token.to_string(), dropped);
+    }
+}
+
+#[derive(Debug)]
+pub struct SigningGuard {
+    storage: Option<LocalStorage>,
+    valsets: BTreeMap<u64, String>,
+    valsets_evicted: Option<u64>,
+    /// by token contract
+    batches: BTreeMap<String, BTreeMap<u64, String>>,
+    batches_evicted: BTreeMap<String, u64>,
+    alerted: HashSet<String>,
+}
+
+impl SigningGuard {
+    pub fn load(storage: Option<LocalStorage>) -> Self {
+        let (valsets, valsets_evicted, batches, batches_evicted) =
+            match storage.as_ref().map(|storage| storage.state.load()) {
+                Some(Ok(state)) => (
+                    state.signed_valsets,
+                    state.signed_valsets_evicted,
+                    state.signed_batches,
+                    state.signed_batches_evicted,
+                ),
+                Some(Err(e)) => {
+                    error!(
+                        "Failed to load the signed checkpoints, starting without: {}",
+                        e
+                    );
+                    Default::default()
+                }
+                None => Default::default(),
+            };
+        SigningGuard {
+            storage,
+            valsets,
+            valsets_evicted,
+            batches,
+            batches_evicted,
+            alerted: HashSet::new(),
+        }
+    }
+
+    /// Whether the valset confirm for `nonce` with the hex `checkpoint` may be signed, records it if so
+    pub fn allow_valset(&mut self, nonce: u64, checkpoint: &str) -> bool {
+        let signed = signed_for(&self.valsets, self.valsets_evicted, nonce, checkpoint);
+        let allowed = self.allow(
+            "valset",
+            format!("valset {}", nonce),
+            checkpoint,
+            signed,
+            |state| {
+                remember(
+                    &mut state.signed_valsets,
+                    &mut state.signed_valsets_evicted,
+                    nonce,
+                    checkpoint,
+                )
+            },
+        );
+        if allowed {
+            remember(
+                &mut self.valsets,
+                &mut self.valsets_evicted,
+                nonce,
+                checkpoint,
+            );
+        }
+        allowed
+    }
+
+    /// Whether the confirm for batch `nonce` of `token_contract` with the hex `checkpoint` may be signed,
+    /// records it if so
+    pub fn allow_batch(
+        &mut self,
+        token_contract: EthAddress,
+        nonce: u64,
+        checkpoint: &str,
+    ) -> bool {
+        let token = token_contract.to_string();
+        let evicted = self.batches_evicted.get(&token).copied();
+        let signed = match self.batches.get(&token) {
+            Some(batches) => signed_for(batches, evicted, nonce, checkpoint),
+            None => signed_for(&BTreeMap::new(), evicted, nonce, checkpoint),
+        };
+        let allowed = self.allow(
+            "batch",
+            format!("batch {} of {}", nonce, token),
+            checkpoint,
+            signed,
+            |state| {
+                remember_batch(
+                    &mut state.signed_batches,
+                    &mut state.signed_batches_evicted,
+                    &token,
+                    nonce,
+                    checkpoint,
+                )
+            },
+        );
+        if allowed {
+            remember_batch(
+                &mut self.batches,
+                &mut self.batches_evicted,
+                &token,
+                nonce,
+                checkpoint,
+            );
+        }
+        allowed
+    }
+
+    /// Refuses a checkpoint that conflicts with the signed one with an alert, `record` stores a new one
+    /// before it may be signed
+    fn allow<F: FnOnce(&mut OrchestratorState)>(
+        &mut self,
+        kind: &str,
+        what: String,
+        checkpoint: &str,
+        signed: Signed,
+        record: F,
+    ) -> bool {
+        match signed {
+            Signed::Same => {
+                info!(
+                    "The {} was signed before but isn't on the Hub, signing it again",
+                    what
+                );
+                true
+            }
+            Signed::Other(ours) => {
+                if self.alerted.insert(what.clone()) {
+                    error!(
+                        "{} CONFLICT, the Hub asks us to sign checkpoint {} for {} but we signed {}, refusing",
+                        kind.to_uppercase(),
+                        checkpoint,
+                        what,
+                        ours
+                    );
+                    if let Some(storage) = self.storage.as_ref() {
+                        storage.count_error(&format!("{}_conflict", kind));
+                    }
+                }
+                false
+            }
+            Signed::Forgotten => {
+                if self.alerted.insert(what.clone()) {
+                    error!(
+                        "Refusing to sign checkpoint {} for {}, it is older than the signed checkpoints we keep",
+                        checkpoint, what
+                    );
+                    if let Some(storage) = self.storage.as_ref() {
+                        storage.count_error(&format!("{}_forgotten", kind));
+                    }
+                }
+                false
+            }
+            Signed::Nothing => match self
+                .storage
+                .as_ref()
+                .map(|storage| storage.state.update(record))
+            {
+                Some(Err(e)) => {
+                    error!(
+                        "Failed to record the checkpoint of {}, not signing it: {}",
+                        what, e
+                    );
+                    false
+                }
+                _ => true,
+            },
+        }
+    }
+}
+
+#[test]
+fn test_signing_guard() {
+    let dir = std::env::temp_dir().join(format!("peggy-guard-test-{}", std::process::id()));
+    let storage = LocalStorage::open(&dir).unwrap();
+    let mut guard = SigningGuard::load(Some(storage.clone()));
+    assert!(guard.allow_valset(5, "aa"));
+    assert!(guard.allow_valset(5, "aa"));
+    assert!(!guard.allow_valset(5, "bb"));
+    assert!(guard.allow_valset(6, "bb"));
+
+    // the record survives a restart
+    let mut guard = SigningGuard::load(Some(storage.clone()));
+    assert!(!guard.allow_valset(5, "bb"));
+    assert!(guard.allow_valset(6, "bb"));
+
+    // batches of different tokens can share a nonce
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    assert!(guard.allow_batch(token, 5, "cc"));
+    assert!(guard.allow_batch(EthAddress::default(), 5, "dd"));
+    assert!(!guard.allow_batch(token, 5, "dd"));
+    let mut guard = SigningGuard::load(Some(storage.clone()));
+    assert!(guard.allow_batch(token, 5, "cc"));
+    assert!(!guard.allow_batch(token, 5, "dd"));
+
+    let state = storage.state.load().unwrap();
+    assert_eq!(state.error_counts.get("valset_conflict"), Some(&2));
+    assert_eq!(state.error_counts.get("batch_conflict"), Some(&2));
+
+    let mut signed = BTreeMap::new();
+    let mut evicted = None;
+    for nonce in 0..SIGNED_CHECKPOINTS_KEPT as u64 + 2 {
+        remember(&mut signed, &mut evicted, nonce, "aa");
+    }
+    assert_eq!(signed.len(), SIGNED_CHECKPOINTS_KEPT);
+    assert_eq!(evicted, Some(1));
+    // what we signed for a dropped nonce is forgotten, so nothing is signed for it anymore
+    assert_eq!(signed_for(&signed, evicted, 1, "aa"), Signed::Forgotten);
+    assert_eq!(signed_for(&signed, evicted, 1, "bb"), Signed::Forgotten);
+    assert_eq!(signed_for(&signed, evicted, 0, "bb"), Signed::Forgotten);
+    assert_eq!(signed_for(&signed, evicted, 2, "aa"), Signed::Same);
+    assert_eq!(
+        signed_for(&signed, evicted, 2, "bb"),
+        Signed::Other("aa".to_string())
+    );
+    assert_eq!(
+        signed_for(&signed, evicted, SIGNED_CHECKPOINTS_KEPT as u64 + 2, "bb"),
+        Signed::Nothing
+    );
+
+    // and the guard refuses an old valset nonce after a restart
+    let mut guard = SigningGuard::load(Some(storage.clone()));
+    for nonce in 10..SIGNED_CHECKPOINTS_KEPT as u64 + 20 {
+        assert!(guard.allow_valset(nonce, "ee"));
+    }
+    let mut guard = SigningGuard::load(Some(storage.clone()));
+    assert!(!guard.allow_valset(5, "ff"));
+    assert!(!guard.allow_valset(12, "ff"));
+    assert!(guard.allow_valset(SIGNED_CHECKPOINTS_KEPT as u64 + 19, "ee"));
+    assert_eq!(
+        storage.state.load().unwrap().signed_valsets_evicted,
+        Some(19)
+    );
+
+    // as it does an old batch nonce, by token
+    for nonce in 10..SIGNED_CHECKPOINTS_KEPT as u64 + 20 {
+        assert!(guard.allow_batch(token, nonce, "ee"));
+    }
+    let mut guard = SigningGuard::load(Some(storage.clone()));
+    assert!(!guard.allow_batch(token, 5, "ff"));
+    assert!(!guard.allow_batch(token, 12, "ff"));
+    assert!(guard.allow_batch(token, SIGNED_CHECKPOINTS_KEPT as u64 + 19, "ee"));
+    assert!(guard.allow_batch(EthAddress::default(), 12, "ff"));
+    let state = storage.state.load().unwrap();
+    assert_eq!(
+        state.signed_batches_evicted.get(&token.to_string()),
+        Some(&19)
+    );
+    assert_eq!(
+        state
+            .signed_batches_evicted
+            .get(&EthAddress::default().to_string()),
+        None
+    );
+    assert_eq!(state.error_counts.get("batch_forgotten"), Some(&2));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
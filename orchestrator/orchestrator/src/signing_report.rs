@@ -0,0 +1,690 @@
+//! Tracks which validators keep missing valset and batch confirmations or lag behind on event nonces, so
+//! orchestrator problems can be spotted and raised with their operators before the Hub slashes them. Every
+//! valset and batch the Hub shows is followed until it drops off, a validator missed it if its signature
+//! still wasn't there `GRACE_BLOCKS` after the valset or batch was created. Only the last `TRACKED` of each
+//! are counted, so the report reflects recent behaviour. The leaderboard is published in the state store for
+//! the tui and summarized in the log every `SUMMARY_INTERVAL`.
+//!
+//! The last event nonce each orchestrator has claimed is kept as its watermark. Validators holding more than
+//! half the power agree on what happened on Ethereum, so when our own watermark stays away from theirs for
+//! `DIVERGENCE_SCANS` scans our view of Ethereum has diverged, most likely because our Ethereum endpoint is
+//! stale or on the wrong chain. That is alerted once until we are back in step.
+//!
+//! A batch needs the signatures of more than the contract's 66% power threshold to be relayed. Batches still
+//! short of that `GRACE_BLOCKS` after they were created are logged once with the validators withholding their
+//! signatures. After its `batch_timeout_blocks` param the Hub batches the token's pool again, the withheld batch
+//! is canceled once a newer batch executes.
+
+use crate::key_rotation::KeyRing;
+use crate::state_store::LocalStorage;
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use cosmos_peggy::query::{
+    get_all_valset_confirms, get_current_valset, get_last_event_nonce,
+    get_latest_transaction_batches, get_latest_valsets, get_peggy_params,
+    get_transaction_batch_signatures,
+};
+use deep_space::address::Address as CosmosAddress;
+use ethereum_peggy::utils::get_event_nonce;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::endpoints::{Endpoints, Purpose};
+use peggy_utils::error::PeggyError;
+use peggy_utils::types::{
+    passes_power_threshold, BatchConfirmResponse, TransactionBatch, Valset, ValsetConfirmResponse,
+    TOTAL_PEGGY_POWER,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+
+/// How often the Hub is polled for confirmations
+pub const REPORT_SPEED: Duration = Duration::from_secs(60);
+/// How often the leaderboard is summarized in the log
+pub const SUMMARY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Hub blocks a validator has to sign a valset or batch in before it counts as missed
+pub const GRACE_BLOCKS: u64 = 50;
+/// How many valsets and how many batches the report covers
+const TRACKED: usize = 100;
+/// Scans our event nonce has to differ from the majority's before we alert, orchestrators claim the same
+/// event a few blocks apart so a single scan out of step is normal
+pub const DIVERGENCE_SCANS: u32 = 3;
+
+/// One validator's place on the leaderboard
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct SignerRecord {
+    pub ethereum_address: EthAddress,
+    /// the orchestrator's Cosmos address, known once it has signed anything we have seen
+    pub orchestrator: Option<String>,
+    pub power: u64,
+    pub valsets_missed: usize,
+    pub valsets: usize,
+    pub batches_missed: usize,
+    pub batches: usize,
+    /// how many events it is behind the furthest orchestrator, None while its orchestrator is unknown
+    pub event_nonce_lag: Option<u64>,
+    /// the last event nonce its orchestrator claimed
+    #[serde(default)]
+    pub event_nonce: Option<u64>,
+}
+
+impl SignerRecord {
+    pub fn missed(&self) -> usize {
+        self.valsets_missed + self.batches_missed
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.missed() == 0 && self.event_nonce_lag.unwrap_or(0) == 0
+    }
+}
+
+/// A valset or batch and who has signed it so far
+#[derive(Debug, Clone)]
+struct Tracked {
+    /// the Hub height it was created at
+    created: u64,
+    expected: Vec<EthAddress>,
+    signers: HashSet<EthAddress>,
+}
+
+impl Tracked {
+    fn new(created: u64, expected: &Valset) -> Self {
+        Tracked {
+            created,
+            expected: expected
+                .members
+                .iter()
+                .filter_map(|member| member.eth_address)
+                .collect(),
+            signers: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SigningTracker {
+    valsets: HashMap<u64, Tracked>,
+    batches: HashMap<(EthAddress, u64), Tracked>,
+    orchestrators: HashMap<EthAddress, CosmosAddress>,
+    event_nonces: HashMap<EthAddress, u64>,
+    /// the validators on the leaderboard
+    current: Valset,
+    /// batches already logged as withheld
+    withheld: HashSet<(EthAddress, u64)>,
+    /// the Hub's `batch_timeout_blocks` param
+    batch_timeout_blocks: u64,
+}
+
+/// A batch that can't be relayed yet because validators with too much power haven't signed it
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct WithheldBatch {
+    pub token_contract: EthAddress,
+    pub nonce: u64,
+    pub created: u64,
+    /// the share of the power that has signed, in percent
+    pub signed_percent: u64,
+    pub withheld_by: Vec<EthAddress>,
+}
+
+/// Drops the oldest entries beyond `TRACKED`
+fn evict<K: Eq + Hash + Clone>(tracked: &mut HashMap<K, Tracked>) {
+    while tracked.len() > TRACKED {
+        let oldest = tracked
+            .iter()
+            .min_by_key(|(_, t)| t.created)
+            .map(|(key, _)| key.clone())
+            .unwrap();
+        tracked.remove(&oldest);
+    }
+}
+
+impl SigningTracker {
+    /// Valsets are signed by their own members, valset nonces are the height they were created at
+    pub fn observe_valset(&mut self, valset: &Valset, confirms: &[ValsetConfirmResponse]) {
+        let tracked = self
+            .valsets
+            .entry(valset.nonce)
+            .or_insert_with(|| Tracked::new(valset.nonce, valset));
+        for confirm in confirms {
+            tracked.signers.insert(confirm.eth_address);
+            self.orchestrators
+                .insert(confirm.eth_address, confirm.orchestrator);
+        }
+        evict(&mut self.valsets);
+    }
+
+    /// Batches are signed by the validator set of the time we first see them
+    pub fn observe_batch(
+        &mut self,
+        batch: &TransactionBatch,
+        signers: &Valset,
+        confirms: &[BatchConfirmResponse],
+    ) {
+        let tracked = self
+            .batches
+            .entry((batch.token_contract, batch.nonce))
+            .or_insert_with(|| Tracked::new(batch.block, signers));
+        for confirm in confirms {
+            tracked.signers.insert(confirm.ethereum_signer);
+            self.orchestrators
+                .insert(confirm.ethereum_signer, confirm.orchestrator);
+        }
+        evict(&mut self.batches);
+    }
+
+    pub fn observe_event_nonce(&mut self, ethereum_address: EthAddress, nonce: u64) {
+        self.event_nonces.insert(ethereum_address, nonce);
+    }
+
+    pub fn set_current_valset(&mut self, valset: Valset) {
+        self.current = valset;
+    }
+
+    /// The current validators, worst first
+    pub fn leaderboard(&self, current_height: u64) -> Vec<SignerRecord> {
+        let matured = |t: &&Tracked| t.created + GRACE_BLOCKS <= current_height;
+        let furthest = self.event_nonces.values().max().copied().unwrap_or(0);
+        let mut out: Vec<SignerRecord> = self
+            .current
+            .members
+            .iter()
+            .filter_map(|member| Some((member.eth_address?, member.power)))
+            .map(|(address, power)| {
+                let count = |tracked: Vec<&Tracked>| {
+                    let expected: Vec<&&Tracked> = tracked
+                        .iter()
+                        .filter(|t| t.expected.contains(&address))
+                        .collect();
+                    let missed = expected
+                        .iter()
+                        .filter(|t| !t.signers.contains(&address))
+                        .count();
+                    (missed, expected.len())
+                };
+                let (valsets_missed, valsets) =
+                    count(self.valsets.values().filter(matured).collect());
+                let (batches_missed, batches) =
+                    count(self.batches.values().filter(matured).collect());
+                SignerRecord {
+                    ethereum_address: address,
+                    orchestrator: self.orchestrators.get(&address).map(|a| a.to_string()),
+                    power,
+                    valsets_missed,
+                    valsets,
+                    batches_missed,
+                    batches,
+                    event_nonce_lag: self
+                        .event_nonces
+                        .get(&address)
+                        .map(|nonce| furthest - nonce),
+                    event_nonce: self.event_nonces.get(&address).copied(),
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| {
+            b.missed()
+                .cmp(&a.missed())
+                .then(b.event_nonce_lag.cmp(&a.event_nonce_lag))
+                .then(b.power.cmp(&a.power))
+        });
+        out
+    }
+
+    /// Batches that are still short of the contract's power threshold `GRACE_BLOCKS` after they were created,
+    /// each batch is only returned once
+    pub fn new_withheld_batches(&mut self, current_height: u64) -> Vec<WithheldBatch> {
+        let batches = &self.batches;
+        self.withheld.retain(|key| batches.contains_key(key));
+        let total: u64 = self.current.members.iter().map(|m| m.power).sum();
+        let mut out = Vec::new();
+        for ((token_contract, nonce), tracked) in self.batches.iter() {
+            if tracked.created + GRACE_BLOCKS > current_height
+                || self.withheld.contains(&(*token_contract, *nonce))
+            {
+                continue;
+            }
+            let mut signed = 0;
+            let mut withheld_by = Vec::new();
+            for member in self.current.members.iter() {
+                let address = match member.eth_address {
+                    Some(address) => address,
+                    None => continue,
+                };
+                if tracked.signers.contains(&address) {
+                    signed += member.power;
+                } else {
+                    withheld_by.push(address);
+                }
+            }
+            if total == 0 {
+                continue;
+            }
+            // normalized like the Hub does, the contract checks that against its threshold
+            let contract_power =
+                (signed as u128 * TOTAL_PEGGY_POWER as u128 / total as u128) as u64;
+            if passes_power_threshold(contract_power) {
+                continue;
+            }
+            self.withheld.insert((*token_contract, *nonce));
+            out.push(WithheldBatch {
+                token_contract: *token_contract,
+                nonce: *nonce,
+                created: tracked.created,
+                signed_percent: signed * 100 / total,
+                withheld_by,
+            });
+        }
+        out.sort_by_key(|batch| (batch.created, batch.nonce));
+        out
+    }
+
+    /// The highest event nonce reached by validators holding more than half the power, None until we know
+    /// the orchestrators of that much power
+    pub fn majority_event_nonce(&self) -> Option<u64> {
+        let total: u64 = self.current.members.iter().map(|m| m.power).sum();
+        let mut known: Vec<(u64, u64)> = self
+            .current
+            .members
+            .iter()
+            .filter_map(|m| Some((*self.event_nonces.get(&m.eth_address?)?, m.power)))
+            .collect();
+        known.sort_by(|a, b| b.0.cmp(&a.0));
+        let mut power = 0;
+        for (nonce, member_power) in known {
+            power += member_power;
+            if power * 2 > total {
+                return Some(nonce);
+            }
+        }
+        None
+    }
+
+    /// Polls the Hub for the confirmations of every valset and batch it shows and the event nonces of the
+    /// orchestrators we know
+    pub async fn refresh(
+        &mut self,
+        grpc_client: &mut PeggyQueryClient<Channel>,
+    ) -> Result<(), PeggyError> {
+        let current = get_current_valset(grpc_client).await?;
+        self.batch_timeout_blocks = get_peggy_params(grpc_client).await?.batch_timeout_blocks;
+        for valset in get_latest_valsets(grpc_client).await? {
+            let confirms = get_all_valset_confirms(grpc_client, valset.nonce).await?;
+            self.observe_valset(&valset, &confirms);
+        }
+        for batch in get_latest_transaction_batches(grpc_client).await? {
+            let confirms =
+                get_transaction_batch_signatures(grpc_client, batch.nonce, batch.token_contract)
+                    .await?;
+            self.observe_batch(&batch, &current, &confirms);
+        }
+        for (address, orchestrator) in self.orchestrators.clone() {
+            let nonce = get_last_event_nonce(grpc_client, orchestrator).await?;
+            self.observe_event_nonce(address, nonce);
+        }
+        self.set_current_valset(current);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DivergenceAlert {
+    Diverged {
+        ours: u64,
+        majority: u64,
+        /// the contract's event nonce as our Ethereum endpoint reports it, None if it couldn't be asked
+        ethereum: Option<u64>,
+    },
+    Recovered {
+        ours: u64,
+    },
+}
+
+impl fmt::Display for DivergenceAlert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DivergenceAlert::Diverged {
+                ours,
+                majority,
+                ethereum,
+            } => {
+                write!(
+                    f,
+                    "Our event nonce is {} but the majority of orchestrators is at {}",
+                    ours, majority
+                )?;
+                match ethereum {
+                    Some(ethereum) if ours > majority => write!(
+                        f,
+                        ", we claimed events they don't see and our Ethereum endpoint reports {}, it is likely on a fork or the wrong network",
+                        ethereum
+                    ),
+                    Some(ethereum) if ethereum < majority => write!(
+                        f,
+                        ", our Ethereum endpoint only reports {}, it is likely stale or on the wrong network",
+                        ethereum
+                    ),
+                    Some(ethereum) => write!(
+                        f,
+                        ", our Ethereum endpoint reports {} so our oracle is not claiming what it sees",
+                        ethereum
+                    ),
+                    None => write!(f, ", our Ethereum endpoint could not be asked for its nonce"),
+                }
+            }
+            DivergenceAlert::Recovered { ours } => write!(
+                f,
+                "Our event nonce {} is back in step with the majority of orchestrators",
+                ours
+            ),
+        }
+    }
+}
+
+/// Counts the scans our event nonce has been out of step so a divergence is alerted once per episode
+#[derive(Debug, Clone, Default)]
+pub struct DivergenceWatcher {
+    scans: u32,
+    alerted: bool,
+}
+
+impl DivergenceWatcher {
+    pub fn observe(
+        &mut self,
+        ours: u64,
+        majority: u64,
+        ethereum: Option<u64>,
+    ) -> Option<DivergenceAlert> {
+        if ours == majority {
+            self.scans = 0;
+            if self.alerted {
+                self.alerted = false;
+                return Some(DivergenceAlert::Recovered { ours });
+            }
+            return None;
+        }
+        self.scans += 1;
+        if self.scans >= DIVERGENCE_SCANS && !self.alerted {
+            self.alerted = true;
+            return Some(DivergenceAlert::Diverged {
+                ours,
+                majority,
+                ethereum,
+            });
+        }
+        None
+    }
+}
+
+/// Compares our own event nonce with the majority's
+async fn check_divergence(
+    watcher: &mut DivergenceWatcher,
+    tracker: &SigningTracker,
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    keys: &KeyRing,
+    ethereum_endpoints: &Endpoints,
+    peggy_contract_address: EthAddress,
+    storage: Option<&LocalStorage>,
+) -> Result<(), PeggyError> {
+    let majority = match tracker.majority_event_nonce() {
+        Some(majority) => majority,
+        None => return Ok(()),
+    };
+    let ours = get_last_event_nonce(grpc_client, keys.current().cosmos_address()).await?;
+    let ethereum = if ours == majority {
+        None
+    } else {
+        let web3 = ethereum_endpoints.web3(Purpose::TimeCritical);
+        get_event_nonce(peggy_contract_address, &web3).await.ok()
+    };
+    match watcher.observe(ours, majority, ethereum) {
+        Some(alert @ DivergenceAlert::Diverged { .. }) => {
+            error!("EVENT NONCE DIVERGENCE {}", alert);
+            if let Some(storage) = storage {
+                storage.count_error("event_nonce_divergence");
+            }
+        }
+        Some(alert) => info!("{}", alert),
+        None => {}
+    }
+    Ok(())
+}
+
+fn log_withheld_batch(batch: &WithheldBatch, current_height: u64, batch_timeout_blocks: u64) {
+    let withheld_by: Vec<String> = batch.withheld_by.iter().map(|a| a.to_string()).collect();
+    let replaced = if batch_timeout_blocks > 0 {
+        format!(
+            ", the Hub batches the token again at block {}",
+            batch.created + batch_timeout_blocks
+        )
+    } else {
+        String::new()
+    };
+    warn!(
+        "Batch {} for {} has waited {} blocks with {}% of the power signed, withheld by {}{}",
+        batch.nonce,
+        batch.token_contract,
+        current_height.saturating_sub(batch.created),
+        batch.signed_percent,
+        withheld_by.join(", "),
+        replaced
+    );
+}
+
+pub fn log_summary(leaderboard: &[SignerRecord]) {
+    let problems: Vec<&SignerRecord> = leaderboard.iter().filter(|r| !r.is_clean()).collect();
+    if problems.is_empty() {
+        info!(
+            "Signing report: all {} validators signed everything",
+            leaderboard.len()
+        );
+        return;
+    }
+    for record in problems {
+        warn!(
+            "Signing report: {} ({}) missed {}/{} valsets and {}/{} batches, {} events behind",
+            record.ethereum_address,
+            record
+                .orchestrator
+                .as_deref()
+                .unwrap_or("unknown orchestrator"),
+            record.valsets_missed,
+            record.valsets,
+            record.batches_missed,
+            record.batches,
+            record
+                .event_nonce_lag
+                .map(|lag| lag.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        );
+    }
+}
+
+/// Keeps the leaderboard up to date in the state store, summarizes it in the log and alerts when our event
+/// nonce diverges from the majority's
+pub async fn signing_report_loop(
+    contact: Contact,
+    grpc_client: PeggyQueryClient<Channel>,
+    keys: KeyRing,
+    ethereum_endpoints: Endpoints,
+    peggy_contract_address: EthAddress,
+    storage: Option<LocalStorage>,
+) {
+    let mut grpc_client = grpc_client;
+    let mut tracker = SigningTracker::default();
+    let mut watcher = DivergenceWatcher::default();
+    let mut last_summary = Instant::now();
+    loop {
+        let height = contact.get_latest_block_number().await;
+        match (height, tracker.refresh(&mut grpc_client).await) {
+            (Ok(height), Ok(())) => {
+                let leaderboard = tracker.leaderboard(height);
+                for batch in tracker.new_withheld_batches(height) {
+                    log_withheld_batch(&batch, height, tracker.batch_timeout_blocks);
+                    if let Some(storage) = storage.as_ref() {
+                        storage.count_error("batch_withheld");
+                    }
+                }
+                if last_summary.elapsed() >= SUMMARY_INTERVAL {
+                    log_summary(&leaderboard);
+                    last_summary = Instant::now();
+                }
+                if let Err(e) = check_divergence(
+                    &mut watcher,
+                    &tracker,
+                    &mut grpc_client,
+                    &keys,
+                    &ethereum_endpoints,
+                    peggy_contract_address,
+                    storage.as_ref(),
+                )
+                .await
+                {
+                    trace!("Failed to check our event nonce {:?}", e);
+                }
+                if let Some(storage) = storage.as_ref() {
+                    if let Err(e) = storage
+                        .state
+                        .update(|state| state.signing_report = leaderboard)
+                    {
+                        error!("Failed to update state store: {}", e);
+                    }
+                }
+            }
+            (Err(e), _) => trace!(
+                "Failed to get the Hub height for the signing report {:?}",
+                e
+            ),
+            (_, Err(e)) => trace!("Failed to refresh the signing report {:?}", e),
+        }
+        delay_for(REPORT_SPEED).await;
+    }
+}
+
+#[test]
+fn test_leaderboard() {
+    use clarity::PrivateKey as EthPrivateKey;
+    use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+    use peggy_utils::types::{ERC20Token, ValsetMember};
+
+    let address = |i: u8| EthAddress::from_slice(&[i; 20]).unwrap();
+    let orchestrator = |i: u8| {
+        CosmosPrivateKey::from_secret(&[i; 32])
+            .to_public_key()
+            .unwrap()
+            .to_address()
+    };
+    let valset = Valset {
+        nonce: 100,
+        members: (1..=3)
+            .map(|i| ValsetMember {
+                power: 1000 * i as u64,
+                eth_address: Some(address(i)),
+            })
+            .collect(),
+    };
+    let signature = EthPrivateKey::from_slice(&[1u8; 32])
+        .unwrap()
+        .sign_ethereum_msg(b"confirm");
+    let valset_confirm = |i: u8| ValsetConfirmResponse {
+        orchestrator: orchestrator(i),
+        eth_address: address(i),
+        nonce: 100,
+        eth_signature: signature.clone(),
+    };
+    let token = address(9);
+    let batch = |nonce: u64, block: u64| TransactionBatch {
+        nonce,
+        transactions: Vec::new(),
+        total_fee: ERC20Token {
+            amount: 0u8.into(),
+            token_contract_address: token,
+        },
+        token_contract: token,
+        block,
+    };
+    let batch_confirm = |i: u8, nonce: u64| BatchConfirmResponse {
+        nonce,
+        orchestrator: orchestrator(i),
+        token_contract: token,
+        ethereum_signer: address(i),
+        eth_signature: signature.clone(),
+    };
+
+    let mut tracker = SigningTracker::default();
+    tracker.set_current_valset(valset.clone());
+    tracker.observe_valset(&valset, &[valset_confirm(1), valset_confirm(2)]);
+    tracker.observe_batch(&batch(1, 100), &valset, &[batch_confirm(1, 1)]);
+    // too recent to count yet
+    tracker.observe_batch(&batch(2, 190), &valset, &[]);
+    tracker.observe_event_nonce(address(1), 10);
+    tracker.observe_event_nonce(address(2), 7);
+
+    let board = tracker.leaderboard(200);
+    let order: Vec<EthAddress> = board.iter().map(|r| r.ethereum_address).collect();
+    assert_eq!(order, vec![address(3), address(2), address(1)]);
+    assert_eq!(board[0].valsets_missed, 1);
+    assert_eq!(board[0].batches_missed, 1);
+    assert_eq!(board[0].batches, 1);
+    assert_eq!(board[0].orchestrator, None);
+    assert_eq!(board[0].event_nonce_lag, None);
+    assert_eq!(board[1].missed(), 1);
+    assert_eq!(board[1].event_nonce_lag, Some(3));
+    assert_eq!(board[1].event_nonce, Some(7));
+    assert!(board[2].is_clean());
+
+    // batch 1 only has address(1)'s 1000 of 6000 power, batch 2 is too recent
+    let withheld = tracker.new_withheld_batches(200);
+    assert_eq!(withheld.len(), 1);
+    assert_eq!(withheld[0].nonce, 1);
+    assert_eq!(withheld[0].signed_percent, 16);
+    assert_eq!(withheld[0].withheld_by, vec![address(2), address(3)]);
+    assert!(tracker.new_withheld_batches(200).is_empty());
+    tracker.observe_batch(
+        &batch(3, 100),
+        &valset,
+        &[batch_confirm(2, 3), batch_confirm(3, 3)],
+    );
+    assert!(tracker
+        .new_withheld_batches(300)
+        .iter()
+        .all(|b| b.nonce != 3));
+
+    // address(3) holds half the power, its nonce is needed for a majority
+    assert_eq!(tracker.majority_event_nonce(), None);
+    tracker.observe_event_nonce(address(3), 7);
+    assert_eq!(tracker.majority_event_nonce(), Some(7));
+    tracker.observe_event_nonce(address(3), 12);
+    assert_eq!(tracker.majority_event_nonce(), Some(10));
+}
+
+#[test]
+fn test_divergence_watcher() {
+    let mut watcher = DivergenceWatcher::default();
+    assert_eq!(watcher.observe(10, 10, None), None);
+    for _ in 1..DIVERGENCE_SCANS {
+        assert_eq!(watcher.observe(10, 12, Some(10)), None);
+    }
+    let alert = watcher.observe(10, 12, Some(10)).unwrap();
+    assert_eq!(
+        alert,
+        DivergenceAlert::Diverged {
+            ours: 10,
+            majority: 12,
+            ethereum: Some(10)
+        }
+    );
+    assert!(alert.to_string().contains("stale"));
+    // alerted once per episode
+    assert_eq!(watcher.observe(10, 13, Some(10)), None);
+    assert_eq!(
+        watcher.observe(13, 13, None),
+        Some(DivergenceAlert::Recovered { ours: 13 })
+    );
+    assert_eq!(watcher.observe(13, 13, None), None);
+    // a single scan out of step is not a divergence
+    assert_eq!(watcher.observe(13, 14, Some(14)), None);
+    assert_eq!(watcher.observe(14, 14, None), None);
+    assert_eq!(watcher.observe(14, 15, Some(15)), None);
+}
@@ -0,0 +1,323 @@
+//! Watches that every token bridged through the Hub stays fully backed. The Hub mints vouchers for deposits to
+//! the Peggy contract and to the Minter multisig and burns them for withdrawals, so its supply of a coin can
+//! never be more than what the contract and the multisig hold together. Withdrawals waiting to be relayed and
+//! deposits not claimed yet only add to the backing. A shortfall means tokens left the contract or the multisig
+//! without vouchers being burned, or vouchers were minted for tokens that were never locked, either way an
+//! exploit in progress.
+//!
+//! With `--watch-solvency` every coin on the Hub's coin list is checked every `SCAN_SPEED` instead of only when
+//! someone audits the bridge, and a shortfall raises an alert right away, again whenever it grows. Amounts are
+//! compared in Hub units, contract balances are converted from the token's Ethereum decimals like the Hub
+//! converts deposits.
+
+use crate::state_store::LocalStorage;
+use clarity::{Address as EthAddress, Uint256};
+use contact::client::Contact;
+use contact::types::{parse_val, ResponseWrapper};
+use cosmos_peggy::query::get_coins;
+use ethereum_peggy::utils::get_locked_balance;
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_utils::amount::{format_amount, pow10};
+use peggy_utils::endpoints::{Endpoints, Purpose};
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+
+/// How often the backing of every coin is checked
+pub const SCAN_SPEED: Duration = Duration::from_secs(60);
+/// Hub and Minter amounts have 18 decimals
+pub const HUB_DECIMALS: u64 = 18;
+const MINTER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where the Minter side of the bridge keeps its tokens
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MinterMultisig {
+    /// a Minter Gate v2 api, such as https://node-api.testnet.minter.network/v2
+    pub api: String,
+    pub address: String,
+}
+
+/// A coin's Hub supply and what backs it, all in Hub units
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Backing {
+    pub denom: String,
+    pub erc20: EthAddress,
+    pub hub_supply: Uint256,
+    pub locked_on_ethereum: Uint256,
+    pub locked_on_minter: Uint256,
+}
+
+impl Backing {
+    /// How many vouchers are more than the contract and multisig hold, None if the coin is fully backed
+    pub fn shortfall(&self) -> Option<Uint256> {
+        let backing = self.locked_on_ethereum.clone() + self.locked_on_minter.clone();
+        if self.hub_supply > backing {
+            Some(self.hub_supply.clone() - backing)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SolvencyAlert {
+    Shortfall {
+        backing: Backing,
+        shortfall: Uint256,
+    },
+    Restored {
+        denom: String,
+    },
+}
+
+impl fmt::Display for SolvencyAlert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolvencyAlert::Shortfall { backing, shortfall } => write!(
+                f,
+                "{} ({}) is short {}, the Hub supply is {} but the Peggy contract holds {} and the Minter multisig {}",
+                backing.denom,
+                explorer::address(Chain::Ethereum, backing.erc20),
+                format_hub_amount(shortfall),
+                format_hub_amount(&backing.hub_supply),
+                format_hub_amount(&backing.locked_on_ethereum),
+                format_hub_amount(&backing.locked_on_minter)
+            ),
+            SolvencyAlert::Restored { denom } => write!(f, "{} is fully backed again", denom),
+        }
+    }
+}
+
+/// An amount in Hub units, for display
+pub fn format_hub_amount(amount: &Uint256) -> String {
+    format_amount(amount, HUB_DECIMALS as usize)
+}
+
+/// Remembers the shortfall we last alerted on per coin so an unchanged one isn't reported every scan
+#[derive(Debug, Clone, Default)]
+pub struct SolvencyWatcher {
+    alerted: HashMap<String, Uint256>,
+}
+
+impl SolvencyWatcher {
+    pub fn observe(&mut self, backing: Backing) -> Option<SolvencyAlert> {
+        match backing.shortfall() {
+            Some(shortfall) => {
+                if let Some(alerted) = self.alerted.get(&backing.denom) {
+                    if shortfall <= *alerted {
+                        return None;
+                    }
+                }
+                self.alerted
+                    .insert(backing.denom.clone(), shortfall.clone());
+                Some(SolvencyAlert::Shortfall { backing, shortfall })
+            }
+            None => self
+                .alerted
+                .remove(&backing.denom)
+                .map(|_| SolvencyAlert::Restored {
+                    denom: backing.denom,
+                }),
+        }
+    }
+}
+
+/// Converts an amount with the token's Ethereum decimals to Hub units, rounding down like the Hub does
+pub fn from_eth_units(amount: Uint256, eth_decimals: u64) -> Uint256 {
+    if eth_decimals < HUB_DECIMALS {
+        amount * pow10(HUB_DECIMALS - eth_decimals)
+    } else {
+        amount / pow10(eth_decimals - HUB_DECIMALS)
+    }
+}
+
+/// The Hub's total supply of a denom, over the legacy REST api
+pub async fn get_hub_supply(contact: &Contact, denom: &str) -> Result<Uint256, PeggyError> {
+    let none: Option<bool> = None;
+    let supply: ResponseWrapper<String> = contact
+        .jsonrpc_client
+        .request_method(
+            &format!("bank/total/{}", denom),
+            none,
+            contact.timeout,
+            None,
+        )
+        .await?;
+    Ok(supply.result.parse()?)
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct MinterCoin {
+    #[serde(deserialize_with = "parse_val")]
+    id: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct MinterBalance {
+    coin: MinterCoin,
+    value: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct MinterAddress {
+    #[serde(default)]
+    balance: Vec<MinterBalance>,
+}
+
+/// The multisig's balance of every Minter coin by coin id
+pub async fn get_minter_balances(
+    multisig: &MinterMultisig,
+) -> Result<HashMap<u64, Uint256>, PeggyError> {
+    let url = format!(
+        "{}/address/{}",
+        multisig.api.trim_end_matches('/'),
+        multisig.address
+    );
+    let minter_error =
+        |e: String| PeggyError::InvalidBridgeStateError(format!("Failed to get {}: {}", url, e));
+    let mut res = actix_web::client::Client::default()
+        .get(&url)
+        .timeout(MINTER_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| minter_error(e.to_string()))?;
+    if !res.status().is_success() {
+        return Err(minter_error(res.status().to_string()));
+    }
+    let address: MinterAddress = res.json().await.map_err(|e| minter_error(e.to_string()))?;
+    let mut balances = HashMap::new();
+    for balance in address.balance {
+        balances.insert(balance.coin.id, balance.value.parse()?);
+    }
+    Ok(balances)
+}
+
+/// Checks every coin the Hub bridges to Ethereum
+pub async fn check_solvency(
+    contact: &Contact,
+    oracle_client: &mut OracleQueryClient<Channel>,
+    ethereum_endpoints: &Endpoints,
+    peggy_contract_address: EthAddress,
+    multisig: &MinterMultisig,
+) -> Result<Vec<Backing>, PeggyError> {
+    let coins = get_coins(oracle_client).await?;
+    let minter_balances = get_minter_balances(multisig).await?;
+    let web3 = ethereum_endpoints.web3(Purpose::TimeCritical);
+
+    let mut out = Vec::new();
+    for coin in coins {
+        // coins that only exist on Minter have nothing locked on Ethereum to check
+        let erc20: EthAddress = match coin.eth_addr.parse() {
+            Ok(erc20) => erc20,
+            Err(_) => continue,
+        };
+        let locked = get_locked_balance(peggy_contract_address, erc20, &web3).await?;
+        out.push(Backing {
+            hub_supply: get_hub_supply(contact, &coin.denom).await?,
+            locked_on_ethereum: from_eth_units(locked, coin.eth_decimals),
+            locked_on_minter: minter_balances
+                .get(&coin.minter_id)
+                .cloned()
+                .unwrap_or_else(|| 0u8.into()),
+            denom: coin.denom,
+            erc20,
+        });
+    }
+    Ok(out)
+}
+
+pub async fn solvency_loop(
+    contact: Contact,
+    watch: Option<(OracleQueryClient<Channel>, MinterMultisig)>,
+    ethereum_endpoints: Endpoints,
+    peggy_contract_address: EthAddress,
+    storage: Option<LocalStorage>,
+) {
+    let (mut oracle_client, multisig) = match watch {
+        Some(watch) => watch,
+        None => return,
+    };
+    let mut watcher = SolvencyWatcher::default();
+    loop {
+        match check_solvency(
+            &contact,
+            &mut oracle_client,
+            &ethereum_endpoints,
+            peggy_contract_address,
+            &multisig,
+        )
+        .await
+        {
+            Ok(backings) => {
+                for backing in backings {
+                    match watcher.observe(backing) {
+                        Some(alert @ SolvencyAlert::Shortfall { .. }) => {
+                            error!("BRIDGE SHORTFALL {}", alert);
+                            if let Some(storage) = storage.as_ref() {
+                                storage.count_error("bridge_shortfall");
+                            }
+                        }
+                        Some(alert) => info!("{}", alert),
+                        None => {}
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to check the bridge's backing {:?}", e),
+        }
+        delay_for(SCAN_SPEED).await;
+    }
+}
+
+#[test]
+fn test_from_eth_units() {
+    let usdt: Uint256 = 1_500_000u64.into();
+    assert_eq!(
+        from_eth_units(usdt, 6),
+        "1500000000000000000".parse().unwrap()
+    );
+    let weth: Uint256 = 42u8.into();
+    assert_eq!(from_eth_units(weth.clone(), 18), weth);
+    assert_eq!(from_eth_units(1999u64.into(), 21), 1u8.into());
+}
+
+#[test]
+fn test_solvency_watcher() {
+    let backing = |supply: u64, ethereum: u64, minter: u64| Backing {
+        denom: "usdt".to_string(),
+        erc20: EthAddress::from_slice(&[1; 20]).unwrap(),
+        hub_supply: supply.into(),
+        locked_on_ethereum: ethereum.into(),
+        locked_on_minter: minter.into(),
+    };
+    let mut watcher = SolvencyWatcher::default();
+
+    // pending withdrawals leave more locked than the supply
+    assert_eq!(backing(100, 60, 50).shortfall(), None);
+    assert_eq!(watcher.observe(backing(100, 60, 50)), None);
+
+    let short = backing(100, 60, 30);
+    assert_eq!(short.shortfall(), Some(10u8.into()));
+    assert_eq!(
+        watcher.observe(short.clone()),
+        Some(SolvencyAlert::Shortfall {
+            backing: short.clone(),
+            shortfall: 10u8.into()
+        })
+    );
+    // the same shortfall is only reported once, a growing one again
+    assert_eq!(watcher.observe(short), None);
+    assert!(watcher.observe(backing(100, 50, 30)).is_some());
+    assert_eq!(watcher.observe(backing(100, 55, 30)), None);
+
+    assert_eq!(
+        watcher.observe(backing(100, 70, 30)),
+        Some(SolvencyAlert::Restored {
+            denom: "usdt".to_string()
+        })
+    );
+    assert_eq!(watcher.observe(backing(100, 70, 30)), None);
+}
@@ -0,0 +1,427 @@
+//! Watches the Hub's outgoing pool for withdrawals that no batch picks up. A transfer to Ethereum waits in the
+//! pool until a batch includes it, and if that never happens the user's funds sit there silently. With
+//! `--stalled-transfer-alert=<minutes>` the pool is scanned every `SCAN_SPEED` and any transfer we have seen
+//! waiting longer than that raises an alert, once per transfer and again if its likely cause changes.
+//!
+//! The pool entries carry no timestamp, so waiting time counts from when we first saw a transfer and starts
+//! over when the orchestrator restarts. The cause is a best guess from what we can see:
+//!
+//! - our relayer has relaying disabled for the token
+//! - the transfer pays no fee at all
+//! - batches for the token were built since it entered the pool without it, so its fee is too low
+//! - a batch for the token has been waiting to be relayed for longer than the threshold, a relayer outage
+//! - otherwise nobody has requested a batch for the token
+//!
+//! Our own transfers stalled for their fee can be topped up automatically, see `fee_top_up`.
+
+use crate::claim_delay::unix_now;
+use crate::fee_top_up::{top_up_fee, FeeTopUp};
+use crate::key_rotation::KeyRing;
+use crate::solvency::format_hub_amount;
+use crate::state_store::LocalStorage;
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use contact::types::{parse_val, ResponseWrapper};
+use cosmos_peggy::query::get_latest_transaction_batches;
+use deep_space::coin::Coin;
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::types::{ERC20Token, TransactionBatch};
+use relayer::main_loop::RelayerControls;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::mem::discriminant;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+
+/// How often the outgoing pool is scanned
+pub const SCAN_SPEED: Duration = Duration::from_secs(5 * 60);
+/// The legacy REST route of the Hub's unbatched outgoing transfers
+const OUTGOING_POOL_ROUTE: &str = "peggy/outgoing_pool";
+
+/// A transfer waiting in the Hub's outgoing pool, as the legacy REST api returns it
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct PoolTransfer {
+    #[serde(deserialize_with = "parse_val")]
+    pub id: u64,
+    pub sender: String,
+    pub dest_address: String,
+    pub erc20_token: ERC20Token,
+    pub erc20_fee: ERC20Token,
+    #[serde(default)]
+    pub memo: String,
+}
+
+impl PoolTransfer {
+    pub fn token(&self) -> EthAddress {
+        self.erc20_token.token_contract_address
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StallCause {
+    TokenDisabled,
+    NoFee,
+    /// this many batches were built for the token without the transfer
+    FeeTooLow {
+        batches: usize,
+    },
+    /// the oldest batch of the token has been waiting longer than the threshold to be relayed
+    RelayerOutage {
+        batch_nonce: u64,
+        waiting_secs: u64,
+    },
+    NoBatchRequested,
+}
+
+impl fmt::Display for StallCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StallCause::TokenDisabled => write!(f, "relaying is disabled for the token"),
+            StallCause::NoFee => write!(f, "it pays no fee"),
+            StallCause::FeeTooLow { batches } => write!(
+                f,
+                "its fee is too low, {} batches were built without it",
+                batches
+            ),
+            StallCause::RelayerOutage {
+                batch_nonce,
+                waiting_secs,
+            } => write!(
+                f,
+                "batch {} has been waiting {} minutes to be relayed, are the relayers down?",
+                batch_nonce,
+                waiting_secs / 60
+            ),
+            StallCause::NoBatchRequested => write!(f, "nobody requested a batch for the token"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StalledTransfer {
+    pub transfer: PoolTransfer,
+    pub waiting_secs: u64,
+    pub cause: StallCause,
+}
+
+impl fmt::Display for StalledTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} of {} from {} to {} waiting {} minutes, {}",
+            self.transfer.id,
+            explorer::address(Chain::Ethereum, self.transfer.token()),
+            explorer::address(Chain::Hub, &self.transfer.sender),
+            self.transfer.dest_address,
+            self.waiting_secs / 60,
+            self.cause
+        )
+    }
+}
+
+/// When we first saw a transfer, and the token's highest batch nonce at the time
+#[derive(Debug, Clone, Copy)]
+struct Sighting {
+    since: u64,
+    batch_nonce: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StallDetector {
+    threshold_secs: u64,
+    transfers: HashMap<u64, Sighting>,
+    /// when we first saw each pending batch
+    batches: HashMap<(EthAddress, u64), u64>,
+    /// the highest batch nonce seen per token, batches drop off the Hub once executed
+    highest_batch: HashMap<EthAddress, u64>,
+    /// the batch nonces seen per token that a waiting transfer may have missed
+    batch_nonces: HashMap<EthAddress, HashSet<u64>>,
+    alerted: HashMap<u64, StallCause>,
+}
+
+impl StallDetector {
+    pub fn new(threshold_secs: u64) -> Self {
+        StallDetector {
+            threshold_secs,
+            ..Default::default()
+        }
+    }
+
+    /// Records the current pool and pending batches, returns the transfers to alert on
+    pub fn observe(
+        &mut self,
+        now: u64,
+        pool: &[PoolTransfer],
+        batches: &[TransactionBatch],
+        disabled_tokens: &[EthAddress],
+    ) -> Vec<StalledTransfer> {
+        let pending: HashSet<(EthAddress, u64)> = batches
+            .iter()
+            .map(|batch| (batch.token_contract, batch.nonce))
+            .collect();
+        self.batches.retain(|key, _| pending.contains(key));
+        for batch in batches {
+            self.batches
+                .entry((batch.token_contract, batch.nonce))
+                .or_insert(now);
+            let highest = self.highest_batch.entry(batch.token_contract).or_insert(0);
+            *highest = (*highest).max(batch.nonce);
+            self.batch_nonces
+                .entry(batch.token_contract)
+                .or_default()
+                .insert(batch.nonce);
+        }
+
+        let ids: HashSet<u64> = pool.iter().map(|transfer| transfer.id).collect();
+        self.transfers.retain(|id, _| ids.contains(id));
+        self.alerted.retain(|id, _| ids.contains(id));
+
+        let mut stalled = Vec::new();
+        for transfer in pool {
+            let batch_nonce = self.highest_batch(transfer.token());
+            let sighting = *self.transfers.entry(transfer.id).or_insert(Sighting {
+                since: now,
+                batch_nonce,
+            });
+            let waiting_secs = now.saturating_sub(sighting.since);
+            if waiting_secs < self.threshold_secs {
+                continue;
+            }
+            let cause = self.cause(now, transfer, sighting, disabled_tokens);
+            // the details of a cause change every scan, only a different cause is worth another alert
+            if let Some(alerted) = self.alerted.get(&transfer.id) {
+                if discriminant(alerted) == discriminant(&cause) {
+                    continue;
+                }
+            }
+            self.alerted.insert(transfer.id, cause);
+            stalled.push(StalledTransfer {
+                transfer: transfer.clone(),
+                waiting_secs,
+                cause,
+            });
+        }
+
+        // batches older than every waiting transfer of their token can't have missed any
+        let mut oldest: HashMap<EthAddress, u64> = HashMap::new();
+        for transfer in pool {
+            let seen = self.transfers[&transfer.id].batch_nonce;
+            let entry = oldest.entry(transfer.token()).or_insert(seen);
+            *entry = (*entry).min(seen);
+        }
+        self.batch_nonces
+            .retain(|token, nonces| match oldest.get(token) {
+                Some(oldest) => {
+                    nonces.retain(|nonce| nonce > oldest);
+                    true
+                }
+                None => false,
+            });
+        stalled
+    }
+
+    fn highest_batch(&self, token: EthAddress) -> u64 {
+        self.highest_batch.get(&token).copied().unwrap_or(0)
+    }
+
+    fn cause(
+        &self,
+        now: u64,
+        transfer: &PoolTransfer,
+        sighting: Sighting,
+        disabled_tokens: &[EthAddress],
+    ) -> StallCause {
+        let token = transfer.token();
+        if disabled_tokens.contains(&token) {
+            return StallCause::TokenDisabled;
+        }
+        if transfer.erc20_fee.amount == 0u8.into() {
+            return StallCause::NoFee;
+        }
+        let missed = self.batch_nonces.get(&token).map_or(0, |nonces| {
+            nonces
+                .iter()
+                .filter(|nonce| **nonce > sighting.batch_nonce)
+                .count()
+        });
+        if missed > 0 {
+            return StallCause::FeeTooLow { batches: missed };
+        }
+        let oldest = self
+            .batches
+            .iter()
+            .filter(|((batch_token, _), _)| *batch_token == token)
+            .min_by_key(|(_, since)| **since);
+        match oldest {
+            Some(((_, batch_nonce), since))
+                if now.saturating_sub(*since) >= self.threshold_secs =>
+            {
+                StallCause::RelayerOutage {
+                    batch_nonce: *batch_nonce,
+                    waiting_secs: now - since,
+                }
+            }
+            _ => StallCause::NoBatchRequested,
+        }
+    }
+}
+
+pub async fn get_outgoing_pool(contact: &Contact) -> Result<Vec<PoolTransfer>, PeggyError> {
+    let none: Option<bool> = None;
+    let pool: ResponseWrapper<Option<Vec<PoolTransfer>>> = contact
+        .jsonrpc_client
+        .request_method(OUTGOING_POOL_ROUTE, none, contact.timeout, None)
+        .await?;
+    Ok(pool.result.unwrap_or_default())
+}
+
+/// Scans the outgoing pool until the orchestrator stops, does nothing without a threshold
+#[allow(clippy::too_many_arguments)]
+pub async fn stalled_transfer_loop(
+    contact: Contact,
+    grpc_client: PeggyQueryClient<Channel>,
+    threshold_secs: Option<u64>,
+    fee_top_up: Option<(FeeTopUp, OracleQueryClient<Channel>)>,
+    keys: KeyRing,
+    fee: Coin,
+    relayer_controls: Arc<RwLock<RelayerControls>>,
+    storage: Option<LocalStorage>,
+) {
+    let threshold_secs = match threshold_secs {
+        Some(threshold_secs) => threshold_secs,
+        None => return,
+    };
+    let mut grpc_client = grpc_client;
+    let mut fee_top_up = fee_top_up;
+    let mut detector = StallDetector::new(threshold_secs);
+    loop {
+        let pool = get_outgoing_pool(&contact).await;
+        let batches = get_latest_transaction_batches(&mut grpc_client).await;
+        match (pool, batches) {
+            (Ok(pool), Ok(batches)) => {
+                let disabled_tokens = relayer_controls.read().unwrap().disabled_tokens.clone();
+                for stalled in detector.observe(unix_now(), &pool, &batches, &disabled_tokens) {
+                    error!("STALLED TRANSFER {}", stalled);
+                    if let Some(storage) = storage.as_ref() {
+                        storage.count_error("stalled_transfer");
+                    }
+                    let (top_up, oracle_client) = match fee_top_up.as_mut() {
+                        Some(fee_top_up) => fee_top_up,
+                        None => continue,
+                    };
+                    let our_keys = keys.current();
+                    let our_address = our_keys.cosmos_address().to_string();
+                    let new_fee = match top_up.new_fee(&stalled, &our_address) {
+                        Some(new_fee) => new_fee,
+                        None => continue,
+                    };
+                    let res = top_up_fee(
+                        &contact,
+                        oracle_client,
+                        our_keys.cosmos_key,
+                        fee.clone(),
+                        &stalled,
+                        new_fee.clone(),
+                    )
+                    .await;
+                    match res {
+                        Ok(()) => {
+                            info!(
+                                "Topped up the fee of our transfer {} from {} to {}",
+                                stalled.transfer.id,
+                                format_hub_amount(&stalled.transfer.erc20_fee.amount),
+                                format_hub_amount(&new_fee)
+                            );
+                            if let Some(storage) = storage.as_ref() {
+                                if let Err(e) =
+                                    storage.state.update(|state| state.count("fee_top_ups", 1))
+                                {
+                                    error!("Failed to update state store: {}", e);
+                                }
+                            }
+                        }
+                        Err(e) => warn!(
+                            "Failed to top up the fee of our transfer {}: {}",
+                            stalled.transfer.id, e
+                        ),
+                    }
+                }
+            }
+            (Err(e), _) => warn!("Failed to get the Hub's outgoing pool {:?}", e),
+            (_, Err(e)) => warn!("Failed to get the Hub's pending batches {:?}", e),
+        }
+        delay_for(SCAN_SPEED).await;
+    }
+}
+
+#[test]
+fn test_stall_detector() {
+    let erc20 = |byte: u8| EthAddress::from_slice(&[byte; 20]).unwrap();
+    let transfer = |id: u64, token: EthAddress, fee: u64| PoolTransfer {
+        id,
+        sender: "hub1sender".to_string(),
+        dest_address: "0x0000000000000000000000000000000000000001".to_string(),
+        erc20_token: ERC20Token {
+            amount: 100u64.into(),
+            token_contract_address: token,
+        },
+        erc20_fee: ERC20Token {
+            amount: fee.into(),
+            token_contract_address: token,
+        },
+        memo: String::new(),
+    };
+    let batch = |nonce: u64, token: EthAddress| TransactionBatch {
+        nonce,
+        token_contract: token,
+        ..Default::default()
+    };
+    let (a, b, c) = (erc20(1), erc20(2), erc20(3));
+    let pool = vec![
+        transfer(1, a, 10),
+        transfer(2, b, 0),
+        transfer(3, c, 10),
+        transfer(4, erc20(4), 10),
+    ];
+    let mut detector = StallDetector::new(600);
+
+    // nothing is stalled before the threshold
+    assert!(detector
+        .observe(1000, &pool, &[batch(5, c)], &[a])
+        .is_empty());
+
+    // a batch for a built after the transfer was seen, c's batch is stuck
+    let batches = vec![batch(1, a), batch(5, c)];
+    let stalled = detector.observe(1600, &pool, &batches, &[]);
+    let causes: Vec<(u64, StallCause)> = stalled.iter().map(|s| (s.transfer.id, s.cause)).collect();
+    assert_eq!(
+        causes,
+        vec![
+            (1, StallCause::FeeTooLow { batches: 1 }),
+            (2, StallCause::NoFee),
+            (
+                3,
+                StallCause::RelayerOutage {
+                    batch_nonce: 5,
+                    waiting_secs: 600
+                }
+            ),
+            (4, StallCause::NoBatchRequested),
+        ]
+    );
+
+    // alerts aren't repeated unless the cause changes
+    let stalled = detector.observe(1900, &pool, &batches, &[erc20(4)]);
+    assert_eq!(stalled.len(), 1);
+    assert_eq!(stalled[0].transfer.id, 4);
+    assert_eq!(stalled[0].cause, StallCause::TokenDisabled);
+
+    // transfers that left the pool are forgotten
+    detector.observe(2000, &pool[..1], &batches, &[]);
+    assert_eq!(detector.transfers.len(), 1);
+}
@@ -0,0 +1,212 @@
+//! Standby mode for running a backup orchestrator with the same delegate keys as the primary. With
+//! `--standby=<minutes>` the orchestrator connects to everything, checks its configuration and then only watches
+//! the Hub's record of what our keys have submitted, it doesn't sign or broadcast anything. Once we have had work
+//! pending, an Ethereum event left to claim or a valset or batch left to confirm, without the record moving for
+//! the standby period it assumes the primary is down and takes over.
+//!
+//! The main loops rebuild their progress from the Hub when they start, so nothing the primary already submitted
+//! is submitted again. The standby period must be longer than the primary legitimately holds work back, the
+//! claim delay and the Ethereum confirmations, and the primary must stay stopped once the backup took over or
+//! both will fight over the account sequence.
+
+use crate::claim_delay::unix_now;
+use crate::main_loop::LOOP_SPEED;
+use clarity::Address as EthAddress;
+use cosmos_peggy::query::{
+    get_last_event_nonce, get_oldest_unsigned_transaction_batch, get_oldest_unsigned_valset,
+};
+use deep_space::address::Address as CosmosAddress;
+use ethereum_peggy::utils::get_event_nonce;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// What the Hub has on record from our keys, and what is left for us to do
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HubRecord {
+    /// the last event nonce we claimed
+    pub last_event_nonce: u64,
+    /// the last event nonce issued by the contract
+    pub ethereum_event_nonce: u64,
+    pub oldest_unsigned_valset: Option<u64>,
+    pub oldest_unsigned_batch: Option<(EthAddress, u64)>,
+}
+
+impl HubRecord {
+    pub fn work_pending(&self) -> bool {
+        self.ethereum_event_nonce > self.last_event_nonce
+            || self.oldest_unsigned_valset.is_some()
+            || self.oldest_unsigned_batch.is_some()
+    }
+
+    /// Whether our keys submitted anything between `previous` and this record
+    pub fn progressed_since(&self, previous: &HubRecord) -> bool {
+        self.last_event_nonce != previous.last_event_nonce
+            || self.oldest_unsigned_valset != previous.oldest_unsigned_valset
+            || self.oldest_unsigned_batch != previous.oldest_unsigned_batch
+    }
+}
+
+/// Decides when the primary has been silent long enough to take over
+#[derive(Debug, Clone)]
+pub struct Standby {
+    /// seconds of silence with work pending before we take over
+    silence: u64,
+    last: Option<HubRecord>,
+    /// when the current stretch of pending work without progress started
+    pending_since: Option<u64>,
+}
+
+impl Standby {
+    pub fn new(silence: u64) -> Self {
+        Standby {
+            silence,
+            last: None,
+            pending_since: None,
+        }
+    }
+
+    /// Records what the Hub reports at unix time `now`, returns true once we should take over
+    pub fn observe(&mut self, now: u64, record: HubRecord) -> bool {
+        let progressed = self
+            .last
+            .map(|last| record.progressed_since(&last))
+            .unwrap_or(false);
+        self.last = Some(record);
+        if !record.work_pending() {
+            self.pending_since = None;
+            return false;
+        }
+        if progressed {
+            self.pending_since = Some(now);
+            return false;
+        }
+        let since = *self.pending_since.get_or_insert(now);
+        now.saturating_sub(since) >= self.silence
+    }
+
+    /// How long work has been pending without the primary making progress
+    pub fn silent_for(&self, now: u64) -> Option<u64> {
+        self.pending_since.map(|since| now.saturating_sub(since))
+    }
+}
+
+pub async fn get_hub_record(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    web3: &Web3,
+    peggy_contract_address: EthAddress,
+    our_cosmos_address: CosmosAddress,
+) -> Result<HubRecord, PeggyError> {
+    let last_event_nonce = get_last_event_nonce(grpc_client, our_cosmos_address).await?;
+    let ethereum_event_nonce = get_event_nonce(peggy_contract_address, web3).await?;
+    let oldest_unsigned_valset = get_oldest_unsigned_valset(grpc_client, our_cosmos_address)
+        .await?
+        .map(|valset| valset.nonce);
+    let oldest_unsigned_batch =
+        get_oldest_unsigned_transaction_batch(grpc_client, our_cosmos_address)
+            .await?
+            .map(|batch| (batch.token_contract, batch.nonce));
+    Ok(HubRecord {
+        last_event_nonce,
+        ethereum_event_nonce,
+        oldest_unsigned_valset,
+        oldest_unsigned_batch,
+    })
+}
+
+/// Watches the Hub until the primary orchestrator has been silent for `silence` seconds
+pub async fn wait_for_takeover(
+    mut grpc_client: PeggyQueryClient<Channel>,
+    web3: &Web3,
+    peggy_contract_address: EthAddress,
+    our_cosmos_address: CosmosAddress,
+    silence: u64,
+) {
+    info!(
+        "Standing by, taking over once {} has left work pending for {}s",
+        our_cosmos_address, silence
+    );
+    let mut standby = Standby::new(silence);
+    loop {
+        match get_hub_record(
+            &mut grpc_client,
+            web3,
+            peggy_contract_address,
+            our_cosmos_address,
+        )
+        .await
+        {
+            Ok(record) => {
+                let now = unix_now();
+                if standby.observe(now, record) {
+                    warn!(
+                        "The primary orchestrator left work pending for {}s, taking over",
+                        silence
+                    );
+                    return;
+                }
+                if let Some(secs) = standby.silent_for(now) {
+                    info!(
+                        "The primary orchestrator has left work pending for {}s, {:?}",
+                        secs, record
+                    );
+                }
+            }
+            // we can't tell whether the primary is working, better to stay in standby than to double submit
+            Err(e) => warn!("Failed to get our submissions from the Hub {:?}", e),
+        }
+        delay_for(LOOP_SPEED).await;
+    }
+}
+
+#[test]
+fn test_standby() {
+    let idle = HubRecord {
+        last_event_nonce: 5,
+        ethereum_event_nonce: 5,
+        oldest_unsigned_valset: None,
+        oldest_unsigned_batch: None,
+    };
+    let mut standby = Standby::new(600);
+    // nothing to do is never silence
+    assert!(!standby.observe(0, idle));
+    assert!(!standby.observe(10_000, idle));
+
+    let pending = HubRecord {
+        ethereum_event_nonce: 6,
+        ..idle
+    };
+    assert!(!standby.observe(10_000, pending));
+    assert!(!standby.observe(10_599, pending));
+    assert_eq!(standby.silent_for(10_599), Some(599));
+    assert!(standby.observe(10_600, pending));
+
+    // progress by the primary restarts the clock even while work stays pending
+    let mut standby = Standby::new(600);
+    assert!(!standby.observe(0, pending));
+    let claimed = HubRecord {
+        last_event_nonce: 6,
+        ethereum_event_nonce: 7,
+        ..pending
+    };
+    assert!(!standby.observe(500, claimed));
+    assert!(!standby.observe(1000, claimed));
+    assert!(standby.observe(1100, claimed));
+
+    let mut standby = Standby::new(600);
+    let unsigned = HubRecord {
+        oldest_unsigned_valset: Some(3),
+        ..idle
+    };
+    assert!(!standby.observe(0, unsigned));
+    assert!(!standby.observe(
+        300,
+        HubRecord {
+            oldest_unsigned_valset: Some(4),
+            ..idle
+        }
+    ));
+    assert!(!standby.observe(800, unsigned));
+}
@@ -0,0 +1,317 @@
+//! Optional encryption at rest of the state store, journal and rotated delegate keys, for relayers on shared
+//! or cloud hosts where the disk can't be trusted with transfer history. `--state-key` takes a secret, usually
+//! a reference to a secrets backend like `vault:<path>#<field>`, that has to be 32 random bytes in hex such as
+//! the output of `openssl rand -hex 32`. It is the AES-256-GCM key as it is, a passphrase is refused since
+//! nothing would slow down guessing it. The state and key files are encrypted as a whole and every journal
+//! line on its own so appending stays cheap, each as `ENCRYPTED_PREFIX` followed by the base64 of a random
+//! nonce, the ciphertext and the tag.
+//!
+//! Every encryption is bound to where the contents belong: the state and key files to their file name, so one
+//! can't be swapped for another, and every journal line to the line before it (see `journal_line_aad`), so
+//! lines can't be dropped, reordered or copied from elsewhere in the journal without failing authentication.
+//! Only cutting off the last lines of the journal goes unnoticed.
+//!
+//! Once a key is given anything in plaintext is refused, so a file can't be swapped for a plaintext one that
+//! goes unnoticed. Files written before the key was given are only read by `state migrate`, which encrypts
+//! them, and `state migrate --check` shows whether any are still in plaintext. An encrypted file can't be read
+//! without the key, and one that fails authentication is refused rather than overwritten.
+
+use crate::state_schema::{JsonCodec, StateCodec};
+use deep_space::utils::hex_str_to_bytes;
+use openssl::rand::rand_bytes;
+use openssl::sha::sha256;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use serde_json::Value;
+use std::fmt;
+
+/// Marks encrypted contents, plaintext json can never start with it
+pub const ENCRYPTED_PREFIX: &str = "peggy-encrypted-v2:";
+/// Why plaintext is refused with a key
+pub const PLAINTEXT_REFUSED: &str =
+    "it is in plaintext but --state-key is given, run `state migrate` to encrypt it";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Whether `contents` were written encrypted
+pub fn is_encrypted(contents: &[u8]) -> bool {
+    contents.starts_with(ENCRYPTED_PREFIX.as_bytes())
+}
+
+/// What a journal line is encrypted bound to, the hash of the line before it or the journal's file name for
+/// the first line
+pub fn journal_line_aad(first: &str, previous: Option<&str>) -> Vec<u8> {
+    match previous {
+        Some(line) => sha256(line.as_bytes()).to_vec(),
+        None => first.as_bytes().to_vec(),
+    }
+}
+
+/// The key the state store, journal and rotated keys are encrypted with
+#[derive(Clone, Eq, PartialEq)]
+pub struct StateKey {
+    key: [u8; KEY_LEN],
+}
+
+impl StateKey {
+    /// The key for a `secret` of 32 random bytes in hex
+    pub fn from_secret(secret: &str) -> Result<Self, String> {
+        let trimmed = secret.trim();
+        let hex = trimmed.strip_prefix("0x").unwrap_or(trimmed);
+        let bytes = if hex.len() == 2 * KEY_LEN {
+            hex_str_to_bytes(hex).ok()
+        } else {
+            None
+        };
+        let bytes = bytes.ok_or_else(|| {
+            "the state key has to be 32 random bytes in hex, such as the output of `openssl rand -hex 32`"
+                .to_string()
+        })?;
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(&bytes);
+        Ok(StateKey { key })
+    }
+
+    /// Encrypts `plaintext` bound to `aad`, it only decrypts with the same `aad`
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Result<String, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand_bytes(&mut nonce).map_err(|e| format!("Failed to generate a nonce: {}", e))?;
+        let mut tag = [0u8; TAG_LEN];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            aad,
+            plaintext,
+            &mut tag,
+        )
+        .map_err(|e| format!("Failed to encrypt: {}", e))?;
+        let mut sealed = nonce.to_vec();
+        sealed.extend(ciphertext);
+        sealed.extend(&tag);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, base64::encode(sealed)))
+    }
+
+    /// Decrypts contents encrypted bound to `aad`
+    pub fn decrypt(&self, contents: &[u8], aad: &[u8]) -> Result<Vec<u8>, String> {
+        if !is_encrypted(contents) {
+            return Err("not encrypted".to_string());
+        }
+        let body = &contents[ENCRYPTED_PREFIX.len()..];
+        let sealed = std::str::from_utf8(body)
+            .map_err(|e| e.to_string())
+            .and_then(|text| base64::decode(text.trim()).map_err(|e| e.to_string()))
+            .map_err(|e| format!("invalid encrypted contents: {}", e))?;
+        if sealed.len() < NONCE_LEN + TAG_LEN {
+            return Err("encrypted contents are truncated".to_string());
+        }
+        let (nonce, rest) = sealed.split_at(NONCE_LEN);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LEN);
+        decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(nonce),
+            aad,
+            ciphertext,
+            tag,
+        )
+        .map_err(|_| {
+            "failed to decrypt, the state key is wrong or the file was altered".to_string()
+        })
+    }
+}
+
+/// Never prints the key
+impl fmt::Debug for StateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<state key>")
+    }
+}
+
+/// Encrypts the json state
+#[derive(Debug, Clone)]
+pub struct EncryptedCodec {
+    pub key: StateKey,
+    /// the name of the file, the contents are bound to it
+    pub file: &'static str,
+    /// reads state written in plaintext before the key was given, only so `state migrate` can encrypt it
+    pub plaintext_allowed: bool,
+}
+
+impl StateCodec for EncryptedCodec {
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, String> {
+        let plaintext = JsonCodec.encode(value)?;
+        Ok(self
+            .key
+            .encrypt(&plaintext, self.file.as_bytes())?
+            .into_bytes())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        if is_encrypted(bytes) {
+            JsonCodec.decode(&self.key.decrypt(bytes, self.file.as_bytes())?)
+        } else if self.plaintext_allowed {
+            JsonCodec.decode(bytes)
+        } else {
+            Err(PLAINTEXT_REFUSED.to_string())
+        }
+    }
+
+    fn encrypted(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn test_state_encryption() {
+    use crate::journal::{JournalEntry, JOURNAL_FILE_NAME};
+    use crate::key_rotation::{
+        load_active_keys, read_keys, write_keys, StoredKeys, ACTIVE_KEYS_FILE,
+    };
+    use crate::state_schema::{migrate_storage, plan_migrations};
+    use crate::state_store::{LocalStorage, STATE_FILE_NAME};
+    use std::fs;
+
+    let secret = "5a".repeat(32);
+    let key = StateKey::from_secret(&secret).unwrap();
+    let sealed = key.encrypt(b"{}", b"state.json").unwrap();
+    assert!(is_encrypted(sealed.as_bytes()));
+    assert_eq!(
+        key.decrypt(sealed.as_bytes(), b"state.json").unwrap(),
+        b"{}".to_vec()
+    );
+    // every encryption has its own nonce and only decrypts where it belongs
+    assert_ne!(key.encrypt(b"{}", b"state.json").unwrap(), sealed);
+    assert!(key.decrypt(sealed.as_bytes(), b"journal.jsonl").is_err());
+    let wrong = StateKey::from_secret(&format!("0x{}", "a5".repeat(32))).unwrap();
+    assert!(wrong.decrypt(sealed.as_bytes(), b"state.json").is_err());
+    let mut altered = sealed.clone().into_bytes();
+    let last = altered.len() - 3;
+    altered[last] = if altered[last] == b'A' { b'B' } else { b'A' };
+    assert!(key.decrypt(&altered, b"state.json").is_err());
+    assert_eq!(format!("{:?}", key), "<state key>");
+
+    // a passphrase is no key
+    assert!(StateKey::from_secret("correct horse battery staple").is_err());
+    assert!(StateKey::from_secret(&"5a".repeat(31)).is_err());
+    assert!(StateKey::from_secret(&"zz".repeat(32)).is_err());
+
+    // a state dir written in plaintext is encrypted by the migration once a key is given
+    let dir = std::env::temp_dir().join(format!("peggy-encryption-test-{}", std::process::id()));
+    let plaintext = LocalStorage::open(&dir).unwrap();
+    plaintext
+        .state
+        .update(|state| state.count("claim_txs", 1))
+        .unwrap();
+    let confirm = JournalEntry::ValsetConfirm {
+        nonce: 3,
+        txhash: "AB".to_string(),
+    };
+    plaintext.journal.append(&confirm).unwrap();
+    let stored = StoredKeys::generate();
+    fs::write(
+        dir.join(ACTIVE_KEYS_FILE),
+        serde_json::to_string(&stored).unwrap(),
+    )
+    .unwrap();
+
+    // but nothing in plaintext is read with the key outside of the migration
+    let encrypted = LocalStorage::open_with_key(&dir, Some(key.clone())).unwrap();
+    assert!(encrypted.state.needs_encrypting().unwrap());
+    assert!(encrypted.journal.needs_encrypting().unwrap());
+    assert!(encrypted.state.load().is_err());
+    assert!(encrypted
+        .state
+        .update(|state| state.count("claim_txs", 1))
+        .is_err());
+    assert!(encrypted.journal.entries().is_err());
+    assert!(read_keys(&dir.join(ACTIVE_KEYS_FILE), Some(&key), false).is_err());
+    assert!(plan_migrations(&encrypted).is_err());
+    assert!(migrate_storage(&encrypted).is_err());
+    assert!(!is_encrypted(&fs::read(dir.join(STATE_FILE_NAME)).unwrap()));
+
+    let migrating = LocalStorage::open_for_migration(&dir, Some(key.clone())).unwrap();
+    assert!(plan_migrations(&migrating)
+        .unwrap()
+        .iter()
+        .any(|m| m.file.ends_with(ACTIVE_KEYS_FILE) && !m.steps.is_empty()));
+    migrate_storage(&migrating).unwrap();
+    assert!(!encrypted.state.needs_encrypting().unwrap());
+    assert!(!encrypted.journal.needs_encrypting().unwrap());
+    assert!(plan_migrations(&encrypted)
+        .unwrap()
+        .iter()
+        .all(|m| m.steps.is_empty()));
+    assert!(is_encrypted(&fs::read(dir.join(STATE_FILE_NAME)).unwrap()));
+    assert!(is_encrypted(&fs::read(dir.join(ACTIVE_KEYS_FILE)).unwrap()));
+    assert_eq!(
+        read_keys(&dir.join(ACTIVE_KEYS_FILE), Some(&key), false).unwrap(),
+        Some(stored.clone())
+    );
+    encrypted.journal.append(&confirm).unwrap();
+    let second = JournalEntry::ValsetConfirm {
+        nonce: 4,
+        txhash: "CD".to_string(),
+    };
+    encrypted.journal.append(&second).unwrap();
+    let journal = fs::read_to_string(dir.join(JOURNAL_FILE_NAME)).unwrap();
+    assert!(journal.lines().all(|line| is_encrypted(line.as_bytes())));
+    assert!(!journal.contains("AB"));
+    assert_eq!(
+        encrypted.journal.entries().unwrap(),
+        vec![confirm.clone(), confirm.clone(), second.clone()]
+    );
+    assert_eq!(
+        encrypted.state.load().unwrap().counters.get("claim_txs"),
+        Some(&1)
+    );
+
+    // journal lines can't be dropped or reordered
+    let lines: Vec<&str> = journal.lines().collect();
+    let dropped = format!("{}\n{}\n{}\n", lines[0], lines[1], lines[3]);
+    fs::write(dir.join(JOURNAL_FILE_NAME), dropped).unwrap();
+    assert!(encrypted.journal.entries().is_err());
+    let reordered = format!("{}\n{}\n{}\n{}\n", lines[0], lines[2], lines[1], lines[3]);
+    fs::write(dir.join(JOURNAL_FILE_NAME), reordered).unwrap();
+    assert!(encrypted.journal.entries().is_err());
+    fs::write(dir.join(JOURNAL_FILE_NAME), &journal).unwrap();
+    // nor can the state be swapped for an encrypted key file
+    let state = fs::read(dir.join(STATE_FILE_NAME)).unwrap();
+    fs::copy(dir.join(ACTIVE_KEYS_FILE), dir.join(STATE_FILE_NAME)).unwrap();
+    assert!(encrypted.state.load().is_err());
+    fs::write(dir.join(STATE_FILE_NAME), &state).unwrap();
+    // or for plaintext ones once everything is encrypted
+    fs::write(dir.join(STATE_FILE_NAME), b"{}").unwrap();
+    assert!(encrypted.state.load().is_err());
+    fs::write(dir.join(STATE_FILE_NAME), &state).unwrap();
+    let plain_line = serde_json::to_string(&second).unwrap();
+    fs::write(
+        dir.join(JOURNAL_FILE_NAME),
+        format!("{}{}\n", journal, plain_line),
+    )
+    .unwrap();
+    assert!(encrypted.journal.entries().is_err());
+    fs::write(dir.join(JOURNAL_FILE_NAME), &journal).unwrap();
+    fs::write(
+        dir.join(ACTIVE_KEYS_FILE),
+        serde_json::to_string(&stored).unwrap(),
+    )
+    .unwrap();
+    assert!(load_active_keys(&dir, Some(&key)).is_err());
+    write_keys(&dir.join(ACTIVE_KEYS_FILE), &stored, &key).unwrap();
+
+    // without the key nothing can be read, and nothing is overwritten
+    assert!(plaintext.state.load().is_err());
+    assert!(plaintext.journal.entries().is_err());
+    let wrong = LocalStorage::open_with_key(&dir, Some(wrong)).unwrap();
+    assert!(wrong.state.load().is_err());
+    assert!(wrong
+        .state
+        .update(|state| state.count("claim_txs", 1))
+        .is_err());
+    assert_eq!(
+        encrypted.journal.entries().unwrap(),
+        vec![confirm.clone(), confirm, second]
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,432 @@
+//! Schema versions and migrations for what the orchestrator persists in its state directory. The state file
+//! (cursors, counters, delayed claims) carries a `schema_version` field and the journal starts with a
+//! `{"schema_version": n}` header line, files written before versioning count as version 0. Whenever the
+//! persisted format changes the version is bumped and a migration from the previous version is added to
+//! `STATE_MIGRATIONS` or `JOURNAL_MIGRATIONS`, the journal's migrations apply to each entry.
+//!
+//! Loading migrates older files in memory. Files written by a newer orchestrator are refused, as are files
+//! with fields this version doesn't know, instead of silently dropping them on the next write. The
+//! orchestrator migrates the files on disk at startup, keeping a copy of the old ones, `state migrate
+//! --check` shows what would be migrated and whether it would work without changing anything. Encrypting
+//! files written before `--state-key` was given, the rotated delegate keys included, is planned and applied
+//! the same way without a plaintext backup, but only by `state migrate` since nothing else reads plaintext
+//! with a key, see `LocalStorage::open_for_migration`.
+
+use crate::journal::JOURNAL_FILE_NAME;
+use crate::key_rotation::{
+    keys_need_encrypting, read_keys, write_keys, ACTIVE_KEYS_FILE, PENDING_KEYS_FILE,
+};
+use crate::state_encryption::is_encrypted;
+use crate::state_store::{LocalStorage, STATE_FILE_NAME};
+use peggy_utils::error::PeggyError;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const STATE_SCHEMA_VERSION: u32 = 7;
+pub const JOURNAL_SCHEMA_VERSION: u32 = 2;
+const ENCRYPT_STEP: &str = "encrypt with --state-key, it was written in plaintext";
+
+/// Turns the persisted values into bytes and back, migrations work on the decoded values so they don't
+/// depend on the encoding
+pub trait StateCodec: fmt::Debug + Send + Sync {
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, String>;
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String>;
+    /// Whether what `encode` writes is encrypted, see `state_encryption`
+    fn encrypted(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonCodec;
+
+impl StateCodec for JsonCodec {
+    fn encode(&self, value: &Value) -> Result<Vec<u8>, String> {
+        serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, String> {
+        if is_encrypted(bytes) {
+            return Err("it is encrypted, --state-key is needed to read it".to_string());
+        }
+        serde_json::from_slice(bytes).map_err(|e| e.to_string())
+    }
+}
+
+/// Converts a persisted value from schema version `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(Value) -> Result<Value, String>,
+}
+
+pub const STATE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "stamp the schema version on a state file from before it was versioned",
+        apply: unchanged,
+    },
+    Migration {
+        from: 1,
+        description: "allow the pending claim submission, existing state is unchanged",
+        apply: unchanged,
+    },
+    Migration {
+        from: 2,
+        description: "allow the last seen Peggy params, existing state is unchanged",
+        apply: unchanged,
+    },
+    Migration {
+        from: 3,
+        description: "allow the signed valset checkpoints, existing state is unchanged",
+        apply: unchanged,
+    },
+    Migration {
+        from: 4,
+        description: "allow the signed batch checkpoints, existing state is unchanged",
+        apply: unchanged,
+    },
+    Migration {
+        from: 5,
+        description: "allow the highest forgotten valset nonce, existing state is unchanged",
+        apply: unchanged,
+    },
+    Migration {
+        from: 6,
+        description: "allow the highest forgotten batch nonces, existing state is unchanged",
+        apply: unchanged,
+    },
+];
+
+pub const JOURNAL_MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "add the schema version header to a journal from before it was versioned",
+        apply: unchanged,
+    },
+    Migration {
+        from: 1,
+        description: "allow the Ethereum relay entries, existing entries are unchanged",
+        apply: unchanged,
+    },
+];
+
+fn unchanged(value: Value) -> Result<Value, String> {
+    Ok(value)
+}
+
+/// The migrations that take a value from `version` to `current`, in order
+pub fn pending_migrations<'a>(
+    what: &str,
+    version: u32,
+    current: u32,
+    migrations: &'a [Migration],
+) -> Result<Vec<&'a Migration>, String> {
+    if version > current {
+        return Err(format!(
+            "{} has schema version {} but this orchestrator only knows up to {}, it was written by a newer orchestrator. Refusing to use it so it isn't overwritten",
+            what, version, current
+        ));
+    }
+    (version..current)
+        .map(|from| {
+            migrations
+                .iter()
+                .find(|m| m.from == from)
+                .ok_or_else(|| format!("No migration of {} from schema version {}", what, from))
+        })
+        .collect()
+}
+
+pub fn migrate(
+    what: &str,
+    mut value: Value,
+    version: u32,
+    current: u32,
+    migrations: &[Migration],
+) -> Result<Value, String> {
+    for migration in pending_migrations(what, version, current, migrations)? {
+        value = (migration.apply)(value).map_err(|e| {
+            format!(
+                "Failed to migrate {} from schema version {}: {}",
+                what, migration.from, e
+            )
+        })?;
+    }
+    Ok(value)
+}
+
+/// Fails if `stored` has fields that are missing once parsed and written back as `parsed`, those would be
+/// lost the next time the file is written. Null fields are the same as missing ones
+pub fn check_nothing_dropped(what: &str, stored: &Value, parsed: &Value) -> Result<(), String> {
+    let mut dropped = Vec::new();
+    find_dropped("", stored, parsed, &mut dropped);
+    if dropped.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} has fields this orchestrator doesn't know, {}. Refusing to use it so they aren't lost",
+            what,
+            dropped.join(", ")
+        ))
+    }
+}
+
+fn find_dropped(path: &str, stored: &Value, parsed: &Value, dropped: &mut Vec<String>) {
+    match (stored, parsed) {
+        (Value::Object(stored), Value::Object(parsed)) => {
+            for (key, value) in stored {
+                let field = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match parsed.get(key) {
+                    Some(parsed) => find_dropped(&field, value, parsed, dropped),
+                    None if value.is_null() => {}
+                    None => dropped.push(field),
+                }
+            }
+        }
+        (Value::Array(stored), Value::Array(parsed)) if stored.len() == parsed.len() => {
+            for (i, (stored, parsed)) in stored.iter().zip(parsed.iter()).enumerate() {
+                find_dropped(&format!("{}[{}]", path, i), stored, parsed, dropped);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// What migrating one file involves
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct FileMigration {
+    pub file: PathBuf,
+    pub version: u32,
+    pub current: u32,
+    pub steps: Vec<&'static str>,
+}
+
+impl fmt::Display for FileMigration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.steps.is_empty() {
+            return write!(
+                f,
+                "{} is at schema version {}, nothing to migrate",
+                self.file.display(),
+                self.current
+            );
+        }
+        if self.version == self.current {
+            write!(f, "{} needs migrating:", self.file.display())?;
+        } else {
+            write!(
+                f,
+                "{} migrates from schema version {} to {}:",
+                self.file.display(),
+                self.version,
+                self.current
+            )?;
+        }
+        for step in self.steps.iter() {
+            write!(f, "\n  - {}", step)?;
+        }
+        Ok(())
+    }
+}
+
+/// Works out the migrations of the state directory and checks that every file loads once migrated,
+/// without writing anything
+pub fn plan_migrations(storage: &LocalStorage) -> Result<Vec<FileMigration>, PeggyError> {
+    let state_version = storage.state.schema_version()?;
+    let journal_version = storage.journal.schema_version()?;
+    let mut plan = vec![
+        file_migration(
+            storage.dir.join(STATE_FILE_NAME),
+            state_version,
+            STATE_SCHEMA_VERSION,
+            STATE_MIGRATIONS,
+        )?,
+        file_migration(
+            storage.dir.join(JOURNAL_FILE_NAME),
+            journal_version,
+            JOURNAL_SCHEMA_VERSION,
+            JOURNAL_MIGRATIONS,
+        )?,
+    ];
+    if storage.state.needs_encrypting()? {
+        plan[0].steps.push(ENCRYPT_STEP);
+    }
+    if storage.journal.needs_encrypting()? {
+        plan[1].steps.push(ENCRYPT_STEP);
+    }
+    // the key files have no schema version, they only ever need encrypting
+    for keys_file in [ACTIVE_KEYS_FILE, PENDING_KEYS_FILE].iter() {
+        let file = storage.dir.join(keys_file);
+        if keys_need_encrypting(&file, storage.key.as_ref())? {
+            read_keys(&file, storage.key.as_ref(), storage.plaintext_allowed)?;
+            plan.push(FileMigration {
+                file,
+                version: 0,
+                current: 0,
+                steps: vec![ENCRYPT_STEP],
+            });
+        }
+    }
+    // loading migrates in memory and fails on anything that wouldn't survive
+    storage.state.load()?;
+    storage.journal.entries()?;
+    Ok(plan)
+}
+
+fn file_migration(
+    file: PathBuf,
+    version: u32,
+    current: u32,
+    migrations: &[Migration],
+) -> Result<FileMigration, PeggyError> {
+    let what = file.display().to_string();
+    let steps = pending_migrations(&what, version, current, migrations)
+        .map_err(PeggyError::StateStoreError)?
+        .iter()
+        .map(|m| m.description)
+        .collect();
+    Ok(FileMigration {
+        file,
+        version,
+        current,
+        steps,
+    })
+}
+
+/// Migrates the state directory on disk, each file migrated to a new schema version is first copied to
+/// `<file>.v<version>.bak`
+pub fn migrate_storage(storage: &LocalStorage) -> Result<Vec<FileMigration>, PeggyError> {
+    let plan = plan_migrations(storage)?;
+    for migration in plan.iter().filter(|m| !m.steps.is_empty()) {
+        if !migration.file.exists() {
+            continue;
+        }
+        if migration.version != migration.current {
+            backup(&migration.file, migration.version)?;
+        }
+        if migration.file.ends_with(STATE_FILE_NAME) {
+            storage.state.save(&storage.state.load()?)?;
+        } else if migration.file.ends_with(JOURNAL_FILE_NAME) {
+            storage.journal.rewrite(&storage.journal.entries()?)?;
+        } else if let (Some(key), Some(keys)) = (
+            storage.key.as_ref(),
+            read_keys(
+                &migration.file,
+                storage.key.as_ref(),
+                storage.plaintext_allowed,
+            )?,
+        ) {
+            write_keys(&migration.file, &keys, key)?;
+        }
+    }
+    Ok(plan)
+}
+
+fn backup(file: &Path, version: u32) -> Result<(), PeggyError> {
+    let mut name = file.as_os_str().to_owned();
+    name.push(format!(".v{}.bak", version));
+    fs::copy(file, &name).map(|_| ()).map_err(|e| {
+        PeggyError::StateStoreError(format!("Failed to back up {}: {}", file.display(), e))
+    })
+}
+
+#[test]
+fn test_migrations() {
+    fn bump(value: Value) -> Result<Value, String> {
+        let mut value = value;
+        value["bumped"] = Value::from(value["bumped"].as_u64().unwrap_or(0) + 1);
+        Ok(value)
+    }
+    let migrations = [
+        Migration {
+            from: 0,
+            description: "first",
+            apply: bump,
+        },
+        Migration {
+            from: 1,
+            description: "second",
+            apply: bump,
+        },
+    ];
+    let value = migrate("test", serde_json::json!({}), 0, 2, &migrations).unwrap();
+    assert_eq!(value["bumped"], 2);
+    let value = migrate("test", serde_json::json!({}), 1, 2, &migrations).unwrap();
+    assert_eq!(value["bumped"], 1);
+    assert!(migrate("test", serde_json::json!({}), 3, 2, &migrations)
+        .unwrap_err()
+        .contains("newer orchestrator"));
+    assert!(migrate("test", serde_json::json!({}), 0, 3, &migrations)
+        .unwrap_err()
+        .contains("No migration"));
+
+    let stored = serde_json::json!({"a": 1, "b": null, "c": {"d": 2, "e": 3}, "f": [{"g": 1}]});
+    let parsed = serde_json::json!({"a": 1, "c": {"d": 2}, "f": [{}]});
+    assert_eq!(
+        check_nothing_dropped("test", &stored, &parsed).unwrap_err(),
+        "test has fields this orchestrator doesn't know, c.e, f[0].g. Refusing to use it so they aren't lost"
+    );
+    assert!(check_nothing_dropped("test", &stored, &stored).is_ok());
+}
+
+#[test]
+fn test_migrate_storage() {
+    use crate::journal::JournalEntry;
+
+    let dir = std::env::temp_dir().join(format!("peggy-schema-test-{}", std::process::id()));
+    let storage = LocalStorage::open(&dir).unwrap();
+    // files from before versioning
+    fs::write(
+        dir.join(STATE_FILE_NAME),
+        r#"{"last_checked_block":null,"last_event_nonce":7,"last_valset_confirm_nonce":0,"last_batch_confirm_nonce":0}"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join(JOURNAL_FILE_NAME),
+        "{\"type\":\"ValsetConfirm\",\"nonce\":3,\"txhash\":\"AB\"}\n",
+    )
+    .unwrap();
+
+    let plan = plan_migrations(&storage).unwrap();
+    assert_eq!(plan[0].version, 0);
+    assert_eq!(plan[1].steps.len(), 2);
+    assert_eq!(storage.state.schema_version().unwrap(), 0);
+
+    migrate_storage(&storage).unwrap();
+    assert!(plan_migrations(&storage)
+        .unwrap()
+        .iter()
+        .all(|m| m.steps.is_empty()));
+    assert_eq!(storage.state.load().unwrap().last_event_nonce, 7);
+    assert_eq!(
+        storage.journal.entries().unwrap(),
+        vec![JournalEntry::ValsetConfirm {
+            nonce: 3,
+            txhash: "AB".to_string()
+        }]
+    );
+    assert!(dir.join("state.json.v0.bak").exists());
+    assert!(dir.join("journal.jsonl.v0.bak").exists());
+
+    // a newer orchestrator's state or fields we don't know are refused rather than dropped
+    let mut state: Value =
+        serde_json::from_slice(&fs::read(dir.join(STATE_FILE_NAME)).unwrap()).unwrap();
+    state["paused_until"] = Value::from(5);
+    fs::write(dir.join(STATE_FILE_NAME), state.to_string()).unwrap();
+    assert!(storage.state.load().is_err());
+    assert!(storage
+        .state
+        .update(|state| state.count("claims", 1))
+        .is_err());
+    state["schema_version"] = Value::from(STATE_SCHEMA_VERSION + 1);
+    fs::write(dir.join(STATE_FILE_NAME), state.to_string()).unwrap();
+    assert!(plan_migrations(&storage).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
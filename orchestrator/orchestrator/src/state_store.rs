@@ -0,0 +1,300 @@
+//! The state store persists the small amount of progress information the orchestrator needs to resume
+//! after a restart without rescanning history or resubmitting anything. It is a single json file that is
+//! rewritten atomically (write to a temporary file then rename) every time the state changes. The file is
+//! versioned, see `state_schema`, and can be encrypted at rest, see `state_encryption`.
+
+use crate::claim_delay::DelayedClaim;
+use crate::claim_resubmission::ClaimSubmission;
+use crate::downtime::DowntimeTolerance;
+use crate::journal::{Journal, JournalEntry};
+use crate::signing_report::SignerRecord;
+use crate::state_encryption::{is_encrypted, EncryptedCodec, StateKey};
+use crate::state_schema::{
+    check_nothing_dropped, migrate, JsonCodec, StateCodec, STATE_MIGRATIONS, STATE_SCHEMA_VERSION,
+};
+use clarity::Uint256;
+use peggy_utils::error::PeggyError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub const STATE_FILE_NAME: &str = "state.json";
+const SCHEMA_VERSION_FIELD: &str = "schema_version";
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq)]
+pub struct OrchestratorState {
+    /// the last Ethereum block the oracle has fully processed
+    pub last_checked_block: Option<Uint256>,
+    /// the highest event nonce we have had a claim accepted for
+    pub last_event_nonce: u64,
+    /// the highest valset nonce we have had a confirm accepted for
+    pub last_valset_confirm_nonce: u64,
+    /// the highest batch nonce we have had a confirm accepted for
+    pub last_batch_confirm_nonce: u64,
+    /// how many times each kind of failure has happened, shown by the tui
+    #[serde(default)]
+    pub error_counts: BTreeMap<String, u64>,
+    /// running totals of the work done, like claims and confirms sent. They only ever grow and are kept
+    /// here so they carry on from where they were after a restart instead of starting over at zero
+    #[serde(default)]
+    pub counters: BTreeMap<String, u64>,
+    /// the Cosmos delegate address the orchestrator is running with, used by rotate-keys
+    #[serde(default)]
+    pub orchestrator_address: Option<String>,
+    /// large deposits whose claims are being held back by the claim delay, shown by the tui
+    #[serde(default)]
+    pub delayed_claims: Vec<DelayedClaim>,
+    /// claims sent but not yet seen on the Hub when a claim TTL is set, shown by the tui
+    #[serde(default)]
+    pub claim_submission: Option<ClaimSubmission>,
+    /// how long the orchestrator can be offline before missing a signing window, shown by the tui
+    #[serde(default)]
+    pub downtime_tolerance: Option<DowntimeTolerance>,
+    /// the validators missing confirmations or lagging on event nonces, worst first, shown by the tui
+    #[serde(default)]
+    pub signing_report: Vec<SignerRecord>,
+    /// the Peggy module params as last seen on the Hub, to alert on governance changes across restarts
+    #[serde(default)]
+    pub peggy_params: BTreeMap<String, String>,
+    /// the checkpoint we signed for each recent valset nonce, never sign another one for the same nonce
+    #[serde(default)]
+    pub signed_valsets: BTreeMap<u64, String>,
+    /// the highest valset nonce dropped from `signed_valsets`, nothing at or below it is signed anymore
+    #[serde(default)]
+    pub signed_valsets_evicted: Option<u64>,
+    /// the checkpoint we signed for each recent batch nonce by token contract, never sign another one for them
+    #[serde(default)]
+    pub signed_batches: BTreeMap<String, BTreeMap<u64, String>>,
+    /// the highest batch nonce dropped from `signed_batches` by token contract, nothing at or below it is
+    /// signed anymore
+    #[serde(default)]
+    pub signed_batches_evicted: BTreeMap<String, u64>,
+}
+
+impl OrchestratorState {
+    /// Adds `amount` to a counter
+    pub fn count(&mut self, counter: &str, amount: u64) {
+        let total = self.counters.entry(counter.to_string()).or_insert(0);
+        *total = total.saturating_add(amount);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StateStore {
+    path: PathBuf,
+    codec: Arc<dyn StateCodec>,
+}
+
+impl StateStore {
+    /// Opens the state store in the given directory, creating the directory if needed
+    pub fn open(dir: &Path) -> Result<Self, PeggyError> {
+        Self::open_with_codec(dir, Arc::new(JsonCodec))
+    }
+
+    pub fn open_with_codec(dir: &Path, codec: Arc<dyn StateCodec>) -> Result<Self, PeggyError> {
+        fs::create_dir_all(dir).map_err(|e| {
+            PeggyError::StateStoreError(format!("Failed to create {}: {}", dir.display(), e))
+        })?;
+        Ok(StateStore {
+            path: dir.join(STATE_FILE_NAME),
+            codec,
+        })
+    }
+
+    /// The schema version of the stored state, a missing file will be written at the current version
+    pub fn schema_version(&self) -> Result<u32, PeggyError> {
+        Ok(match self.read()? {
+            Some(value) => stored_version(&value),
+            None => STATE_SCHEMA_VERSION,
+        })
+    }
+
+    /// Loads the stored state, a missing file is a fresh orchestrator and yields the default state. State
+    /// from an older orchestrator is migrated, state from a newer one or with fields we don't know is refused
+    pub fn load(&self) -> Result<OrchestratorState, PeggyError> {
+        let mut value = match self.read()? {
+            Some(value) => value,
+            None => return Ok(OrchestratorState::default()),
+        };
+        let what = self.path.display().to_string();
+        let version = stored_version(&value);
+        if let Some(object) = value.as_object_mut() {
+            object.remove(SCHEMA_VERSION_FIELD);
+        }
+        let value = migrate(
+            &what,
+            value,
+            version,
+            STATE_SCHEMA_VERSION,
+            STATE_MIGRATIONS,
+        )
+        .map_err(PeggyError::StateStoreError)?;
+        let state: OrchestratorState = serde_json::from_value(value.clone())
+            .map_err(|e| PeggyError::StateStoreError(format!("Failed to parse {}: {}", what, e)))?;
+        check_nothing_dropped(&what, &value, &serde_json::to_value(&state).unwrap())
+            .map_err(PeggyError::StateStoreError)?;
+        Ok(state)
+    }
+
+    /// Whether the codec encrypts but the stored state was written in plaintext before it did
+    pub fn needs_encrypting(&self) -> Result<bool, PeggyError> {
+        if !self.codec.encrypted() || !self.path.exists() {
+            return Ok(false);
+        }
+        let contents = fs::read(&self.path).map_err(|e| {
+            PeggyError::StateStoreError(format!("Failed to read {}: {}", self.path.display(), e))
+        })?;
+        Ok(!is_encrypted(&contents))
+    }
+
+    fn read(&self) -> Result<Option<Value>, PeggyError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read(&self.path).map_err(|e| {
+            PeggyError::StateStoreError(format!("Failed to read {}: {}", self.path.display(), e))
+        })?;
+        self.codec.decode(&contents).map(Some).map_err(|e| {
+            PeggyError::StateStoreError(format!("Failed to parse {}: {}", self.path.display(), e))
+        })
+    }
+
+    pub fn save(&self, state: &OrchestratorState) -> Result<(), PeggyError> {
+        let mut value = serde_json::to_value(state).unwrap();
+        value[SCHEMA_VERSION_FIELD] = Value::from(STATE_SCHEMA_VERSION);
+        let contents = self
+            .codec
+            .encode(&value)
+            .map_err(PeggyError::StateStoreError)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, contents)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| {
+                PeggyError::StateStoreError(format!(
+                    "Failed to write {}: {}",
+                    self.path.display(),
+                    e
+                ))
+            })
+    }
+
+    /// Loads, modifies and saves the state in one go
+    pub fn update<F: FnOnce(&mut OrchestratorState)>(&self, f: F) -> Result<(), PeggyError> {
+        let mut state = self.load()?;
+        f(&mut state);
+        self.save(&state)
+    }
+}
+
+fn stored_version(value: &Value) -> u32 {
+    value[SCHEMA_VERSION_FIELD].as_u64().unwrap_or(0) as u32
+}
+
+/// The journal and state store live side by side in the orchestrator's state directory
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    pub dir: PathBuf,
+    pub journal: Journal,
+    pub state: StateStore,
+    /// the key everything in the state dir is encrypted with, if it is
+    pub key: Option<StateKey>,
+    /// opened by `state migrate`, files in plaintext are read with the key to encrypt them
+    pub plaintext_allowed: bool,
+}
+
+impl LocalStorage {
+    pub fn open(dir: &Path) -> Result<Self, PeggyError> {
+        Self::open_with_key(dir, None)
+    }
+
+    /// Opens the state dir with the journal and state store encrypted with `key` if there is one, anything in
+    /// plaintext is refused with a key
+    pub fn open_with_key(dir: &Path, key: Option<StateKey>) -> Result<Self, PeggyError> {
+        Self::open_with(dir, key, false)
+    }
+
+    /// Opens the state dir for `state migrate`, which also reads files in plaintext to encrypt them with `key`
+    pub fn open_for_migration(dir: &Path, key: Option<StateKey>) -> Result<Self, PeggyError> {
+        Self::open_with(dir, key, true)
+    }
+
+    fn open_with(
+        dir: &Path,
+        key: Option<StateKey>,
+        plaintext_allowed: bool,
+    ) -> Result<Self, PeggyError> {
+        let state = match key.clone() {
+            Some(key) => StateStore::open_with_codec(
+                dir,
+                Arc::new(EncryptedCodec {
+                    key,
+                    file: STATE_FILE_NAME,
+                    plaintext_allowed,
+                }),
+            )?,
+            None => StateStore::open(dir)?,
+        };
+        let journal = if plaintext_allowed {
+            Journal::open_for_migration(dir, key.clone())?
+        } else {
+            Journal::open_with_key(dir, key.clone())?
+        };
+        Ok(LocalStorage {
+            dir: dir.to_path_buf(),
+            journal,
+            state,
+            key,
+            plaintext_allowed,
+        })
+    }
+
+    /// Records an accepted submission in the journal and applies the matching state change.
+    /// The submission already happened, so failures here are only logged
+    pub fn record<F: FnOnce(&mut OrchestratorState)>(&self, entry: JournalEntry, f: F) {
+        if let Err(e) = self.journal.append(&entry) {
+            error!("Failed to journal {:?}: {}", entry, e);
+        }
+        if let Err(e) = self.state.update(f) {
+            error!("Failed to update state store: {}", e);
+        }
+    }
+
+    pub fn count_error(&self, kind: &str) {
+        let res = self
+            .state
+            .update(|state| *state.error_counts.entry(kind.to_string()).or_insert(0) += 1);
+        if let Err(e) = res {
+            error!("Failed to update state store: {}", e);
+        }
+    }
+}
+
+#[test]
+fn test_counters_survive_restart() {
+    let dir = std::env::temp_dir().join(format!("peggy-state-test-{}", std::process::id()));
+    let store = StateStore::open(&dir).unwrap();
+    store
+        .update(|state| {
+            state.count("events_claimed", 3);
+            state.count("claim_txs", 1);
+        })
+        .unwrap();
+
+    let reopened = StateStore::open(&dir).unwrap();
+    reopened
+        .update(|state| state.count("events_claimed", 2))
+        .unwrap();
+    let state = reopened.load().unwrap();
+    assert_eq!(state.counters.get("events_claimed"), Some(&5));
+    assert_eq!(state.counters.get("claim_txs"), Some(&1));
+
+    // state files from before counters existed load with none
+    let old: OrchestratorState = serde_json::from_str(
+        r#"{"last_checked_block":null,"last_event_nonce":1,"last_valset_confirm_nonce":0,"last_batch_confirm_nonce":0}"#,
+    )
+    .unwrap();
+    assert!(old.counters.is_empty());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,379 @@
+//! Historical bridge statistics aggregated from the journal. For every day, token and direction we report how many
+//! transfers crossed the bridge and their volume, for withdrawals also the batch fees and the average time from our
+//! batch confirm to the batch executing on Ethereum. Only what this orchestrator itself submitted is counted, entries
+//! written before the journal recorded amounts are skipped. For accounting the same transfers can be exported one by
+//! one, along with the sender, destination, labels and USD price recorded by `enrichment`.
+
+use crate::journal::{Direction, JournalEntry, TransferMetadata};
+use clarity::{Address as EthAddress, Uint256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct DailyStats {
+    /// the UTC day, formatted as YYYY-MM-DD
+    pub day: String,
+    pub erc20: EthAddress,
+    pub direction: Direction,
+    /// transfers for deposits, executed batches for withdrawals
+    pub count: u64,
+    pub volume: Uint256,
+    /// batch fees paid to relayers, only for withdrawals
+    pub fees: Uint256,
+    /// average seconds from our batch confirm to the batch executing, only for withdrawals
+    pub average_latency_secs: Option<u64>,
+}
+
+#[derive(Default)]
+struct Totals {
+    erc20: EthAddress,
+    count: u64,
+    volume: Uint256,
+    fees: Uint256,
+    latency_sum: u64,
+    latency_count: u64,
+}
+
+pub fn compute_stats(entries: &[JournalEntry]) -> Vec<DailyStats> {
+    // what we signed for every batch, executed batch claims only carry the nonce
+    let mut confirms: HashMap<(EthAddress, u64), (Uint256, Uint256, u64)> = HashMap::new();
+    for entry in entries {
+        if let JournalEntry::BatchConfirm {
+            nonce,
+            token_contract,
+            total_amount,
+            total_fee,
+            timestamp,
+            ..
+        } = entry
+        {
+            confirms.insert(
+                (*token_contract, *nonce),
+                (total_amount.clone(), total_fee.clone(), *timestamp),
+            );
+        }
+    }
+
+    // keyed by the address string so rows come out sorted by day, token and direction
+    let mut totals: BTreeMap<(u64, String, Direction), Totals> = BTreeMap::new();
+    for entry in entries {
+        let (events, timestamp) = match entry {
+            JournalEntry::Claims {
+                events, timestamp, ..
+            } if *timestamp > 0 => (events, *timestamp),
+            _ => continue,
+        };
+        let day = timestamp / SECS_PER_DAY;
+        for event in events {
+            let totals = totals
+                .entry((day, event.erc20.to_string(), event.direction))
+                .or_default();
+            totals.erc20 = event.erc20;
+            totals.count += event.folded.unwrap_or(1);
+            totals.volume = totals.volume.clone() + event.amount.clone();
+            let confirm = event
+                .batch_nonce
+                .and_then(|nonce| confirms.get(&(event.erc20, nonce)));
+            if let Some((amount, fee, confirmed_at)) = confirm {
+                totals.volume = totals.volume.clone() + amount.clone();
+                totals.fees = totals.fees.clone() + fee.clone();
+                if *confirmed_at > 0 && *confirmed_at <= timestamp {
+                    totals.latency_sum += timestamp - confirmed_at;
+                    totals.latency_count += 1;
+                }
+            }
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|((day, _, direction), totals)| DailyStats {
+            day: format_day(day),
+            erc20: totals.erc20,
+            direction,
+            count: totals.count,
+            volume: totals.volume,
+            fees: totals.fees,
+            average_latency_secs: if totals.latency_count > 0 {
+                Some(totals.latency_sum / totals.latency_count)
+            } else {
+                None
+            },
+        })
+        .collect()
+}
+
+/// A single transfer we claimed
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TransferRecord {
+    /// unix time in seconds the Hub accepted our claim
+    pub timestamp: u64,
+    pub txhash: String,
+    pub direction: Direction,
+    pub erc20: EthAddress,
+    pub amount: Uint256,
+    #[serde(flatten)]
+    pub metadata: TransferMetadata,
+}
+
+pub fn list_transfers(entries: &[JournalEntry]) -> Vec<TransferRecord> {
+    let mut batches: HashMap<(EthAddress, u64), Uint256> = HashMap::new();
+    for entry in entries {
+        if let JournalEntry::BatchConfirm {
+            nonce,
+            token_contract,
+            total_amount,
+            ..
+        } = entry
+        {
+            batches.insert((*token_contract, *nonce), total_amount.clone());
+        }
+    }
+
+    let mut out = Vec::new();
+    for entry in entries {
+        if let JournalEntry::Claims {
+            events,
+            txhash,
+            timestamp,
+            ..
+        } = entry
+        {
+            for event in events {
+                let amount = event
+                    .batch_nonce
+                    .and_then(|nonce| batches.get(&(event.erc20, nonce)))
+                    .cloned()
+                    .unwrap_or_else(|| event.amount.clone());
+                out.push(TransferRecord {
+                    timestamp: *timestamp,
+                    txhash: txhash.clone(),
+                    direction: event.direction,
+                    erc20: event.erc20,
+                    amount,
+                    metadata: event.metadata.clone(),
+                });
+            }
+        }
+    }
+    out
+}
+
+pub fn transfers_to_csv(transfers: &[TransferRecord]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "timestamp,txhash,direction,token,amount,sender,sender_label,destination,destination_label,usd_price"
+    );
+    for row in transfers {
+        let metadata = &row.metadata;
+        let _ = writeln!(
+            out,
+            "{},{},{:?},{},{},{},{},{},{},{}",
+            row.timestamp,
+            row.txhash,
+            row.direction,
+            row.erc20,
+            row.amount,
+            metadata.sender.map(|s| s.to_string()).unwrap_or_default(),
+            csv_field(metadata.sender_label.as_deref()),
+            csv_field(metadata.destination.as_deref()),
+            csv_field(metadata.destination_label.as_deref()),
+            csv_field(metadata.usd_price.as_deref()),
+        );
+    }
+    out
+}
+
+/// Quotes free text fields, labels are operator supplied and may contain commas
+fn csv_field(value: Option<&str>) -> String {
+    match value {
+        Some(value) if value.contains(|c| c == ',' || c == '"' || c == '\n') => {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        }
+        Some(value) => value.to_string(),
+        None => String::new(),
+    }
+}
+
+pub fn to_csv(stats: &[DailyStats]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "day,token,direction,count,volume,fees,average_latency_secs"
+    );
+    for row in stats {
+        let _ = writeln!(
+            out,
+            "{},{},{:?},{},{},{},{}",
+            row.day,
+            row.erc20,
+            row.direction,
+            row.count,
+            row.volume,
+            row.fees,
+            row.average_latency_secs
+                .map(|l| l.to_string())
+                .unwrap_or_default()
+        );
+    }
+    out
+}
+
+/// The UTC date of a day number counted from the unix epoch
+pub fn format_day(days: u64) -> String {
+    // civil from days, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[test]
+fn test_format_day() {
+    assert_eq!(format_day(0), "1970-01-01");
+    assert_eq!(format_day(18_321), "2020-02-29");
+    assert_eq!(format_day(18_628), "2021-01-01");
+}
+
+#[test]
+fn test_compute_stats() {
+    use crate::journal::ClaimedEvent;
+
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let day = 18_628 * SECS_PER_DAY;
+    let entries = vec![
+        JournalEntry::Claims {
+            event_nonces: vec![1, 2],
+            txhash: "A".to_string(),
+            events: vec![
+                ClaimedEvent {
+                    direction: Direction::ToHub,
+                    erc20: token,
+                    amount: 100u64.into(),
+                    batch_nonce: None,
+                    metadata: Default::default(),
+                    folded: None,
+                },
+                ClaimedEvent {
+                    direction: Direction::ToHub,
+                    erc20: token,
+                    amount: 50u64.into(),
+                    batch_nonce: None,
+                    metadata: Default::default(),
+                    folded: None,
+                },
+            ],
+            timestamp: day + 10,
+        },
+        JournalEntry::BatchConfirm {
+            nonce: 7,
+            token_contract: token,
+            txhash: "B".to_string(),
+            total_amount: 1000u64.into(),
+            total_fee: 10u64.into(),
+            timestamp: day + 100,
+        },
+        JournalEntry::Claims {
+            event_nonces: vec![3],
+            txhash: "C".to_string(),
+            events: vec![ClaimedEvent {
+                direction: Direction::FromHub,
+                erc20: token,
+                amount: 0u64.into(),
+                batch_nonce: Some(7),
+                metadata: Default::default(),
+                folded: None,
+            }],
+            timestamp: day + 400,
+        },
+        // written before the journal had amounts
+        JournalEntry::Claims {
+            event_nonces: vec![4],
+            txhash: "D".to_string(),
+            events: Vec::new(),
+            timestamp: 0,
+        },
+    ];
+
+    let stats = compute_stats(&entries);
+    assert_eq!(stats.len(), 2);
+    assert_eq!(stats[0].day, "2021-01-01");
+    assert_eq!(stats[0].direction, Direction::ToHub);
+    assert_eq!(stats[0].count, 2);
+    assert_eq!(stats[0].volume, 150u64.into());
+    assert_eq!(stats[1].direction, Direction::FromHub);
+    assert_eq!(stats[1].volume, 1000u64.into());
+    assert_eq!(stats[1].fees, 10u64.into());
+    assert_eq!(stats[1].average_latency_secs, Some(300));
+
+    let csv = to_csv(&stats);
+    assert_eq!(csv.lines().count(), 3);
+}
+
+#[test]
+fn test_list_transfers() {
+    use crate::journal::ClaimedEvent;
+
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let entries = vec![
+        JournalEntry::BatchConfirm {
+            nonce: 7,
+            token_contract: token,
+            txhash: "A".to_string(),
+            total_amount: 1000u64.into(),
+            total_fee: 10u64.into(),
+            timestamp: 100,
+        },
+        JournalEntry::Claims {
+            event_nonces: vec![1, 2],
+            txhash: "B".to_string(),
+            events: vec![
+                ClaimedEvent {
+                    direction: Direction::ToHub,
+                    erc20: token,
+                    amount: 100u64.into(),
+                    batch_nonce: None,
+                    metadata: TransferMetadata {
+                        sender: Some(token),
+                        sender_label: Some("treasury, cold".to_string()),
+                        usd_price: Some("1.5".to_string()),
+                        ..Default::default()
+                    },
+                    folded: None,
+                },
+                ClaimedEvent {
+                    direction: Direction::FromHub,
+                    erc20: token,
+                    amount: 0u64.into(),
+                    batch_nonce: Some(7),
+                    metadata: Default::default(),
+                    folded: None,
+                },
+            ],
+            timestamp: 400,
+        },
+    ];
+
+    let transfers = list_transfers(&entries);
+    assert_eq!(transfers.len(), 2);
+    assert_eq!(transfers[0].txhash, "B");
+    assert_eq!(transfers[0].metadata.usd_price, Some("1.5".to_string()));
+    assert_eq!(transfers[1].amount, 1000u64.into());
+
+    let csv = transfers_to_csv(&transfers);
+    assert_eq!(csv.lines().count(), 3);
+    assert!(csv.contains("\"treasury, cold\""));
+}
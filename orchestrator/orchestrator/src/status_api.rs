@@ -0,0 +1,713 @@
+//! `orchestrator status-api` serves the bridge status the tui shows as json over HTTP, for status pages and
+//! monitoring. These routes are read only GETs:
+//!
+//! - `/status` the Ethereum block and gas price, the contract and Hub nonces, the pause state, the contract
+//!   version and the pending batches of every token
+//! - `/transfers` the latest deposits and transfers to Minter seen on Ethereum, with their Ethereum
+//!   confirmations and, with `--cosmos-legacy-rpc`, the stage they reached, see `confirmation`
+//! - `/admin` the pending admin instructions, the local state and today's statistics from `--state-dir`. The
+//!   local state includes the claims waiting to show up on the Hub and how often they were sent, see
+//!   `claim_resubmission`. Without `--api-tokens` anyone who can connect may read it, so it is then only served
+//!   when `--bind` is a loopback address
+//!
+//! With `--api-tokens` every request needs an `Authorization: Bearer <token>` header with a token from the json
+//! file of `{"<client>": {"token": "<token>", "scope": "read"}}`, where a token can also be a reference to a
+//! secret backend (see `peggy_utils::secrets`). A `read` token can use the routes above, an `admin` token can
+//! also give the running orchestrator the instructions of `orchestrator admin`:
+//!
+//! - `POST /admin/pause-relaying` and `POST /admin/resume-relaying` pause and resume the relayer
+//!
+//! Admin instructions are only served with tokens, the admin route on any `--bind`. With `--tls-cert` and
+//! `--tls-key` the API is served over HTTPS so the tokens can't be read off the wire.
+//!
+//! Amounts are integers of base units as strings, each with a `display_` twin in whole tokens for status pages.
+//!
+//! With `--public` the API can be exposed to the internet directly off an orchestrator node. The admin route is
+//! left out, every client IP gets `--rate-limit` requests a minute and responses are cached for
+//! `PUBLIC_CACHE_TTL`, so the Hub and Ethereum nodes see one status query per cache period however many
+//! clients there are. Clients are told apart by the address they connect from, behind a reverse proxy the
+//! proxy has to do the rate limiting. Builds without the default `admin-api` feature leave the admin route out
+//! entirely.
+
+use crate::claim_delay::unix_now;
+use crate::confirmation::{confirmations, get_confirmation_stage, ConfirmationStage};
+use crate::enrichment::connect_oracle;
+use crate::solvency::format_hub_amount;
+use crate::state_encryption::StateKey;
+use crate::tui::{get_bridge_status, BridgeStatus};
+use actix_web::http::header;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use futures::lock::Mutex as AsyncMutex;
+use openssl::ssl::SslAcceptorBuilder;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use peggy_utils::secrets::resolve_secret;
+use relayer::request_api::constant_time_eq;
+pub use relayer::request_api::tls_acceptor;
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use web30::client::Web3;
+
+/// How long a public API reuses a status
+pub const PUBLIC_CACHE_TTL: Duration = Duration::from_secs(15);
+/// Requests a client IP may make a minute on a public API, unless --rate-limit says otherwise
+pub const DEFAULT_RATE_LIMIT: u32 = 60;
+/// Tokens shorter than this are too easy to guess
+const MIN_TOKEN_LEN: usize = 16;
+/// Past this many tracked clients the ones from earlier minutes are forgotten, then the quietest
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct PendingBatchesView {
+    pub token_contract: EthAddress,
+    pub count: usize,
+    pub lowest_nonce: u64,
+    pub total_fees: String,
+    /// `total_fees` in whole tokens for display, like `1,234.5`
+    pub display_total_fees: String,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct StatusView {
+    pub eth_block: String,
+    pub gas_price: String,
+    pub eth_event_nonce: u64,
+    pub eth_valset_nonce: u64,
+    pub hub_valset_nonce: Option<u64>,
+    pub contract_halted: bool,
+    pub deposits_stopped: bool,
+    pub hub_stopped: bool,
+    pub contract: String,
+    pub pending_batches: Vec<PendingBatchesView>,
+    /// unix time the status was fetched
+    pub updated_at: u64,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct TransferView {
+    pub event_nonce: String,
+    pub sender: EthAddress,
+    pub destination: String,
+    pub erc20: EthAddress,
+    pub amount: String,
+    /// `amount` in whole tokens for display, in base units if the Hub doesn't know the token's decimals
+    pub display_amount: String,
+    pub tx_hash: String,
+    pub confirmations: u64,
+    /// None without the Hub's legacy rpc or when asking it failed
+    pub stage: Option<ConfirmationStage>,
+}
+
+/// What `/status` and `/transfers` serve, taken from one bridge status
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Snapshot {
+    pub status: StatusView,
+    pub transfers: Vec<TransferView>,
+}
+
+impl Snapshot {
+    pub fn new(status: &BridgeStatus, updated_at: u64) -> Self {
+        Snapshot {
+            status: StatusView {
+                eth_block: status.eth_block.to_string(),
+                gas_price: status.gas_price.to_string(),
+                eth_event_nonce: status.eth_event_nonce,
+                eth_valset_nonce: status.eth_valset_nonce,
+                hub_valset_nonce: status.hub_valset_nonce,
+                contract_halted: status.pause_state.contract_halted,
+                deposits_stopped: status.pause_state.deposits_stopped,
+                hub_stopped: status.pause_state.hub_stopped,
+                contract: status.capabilities.to_string(),
+                pending_batches: status
+                    .pending_batches
+                    .iter()
+                    .map(|(token, pending)| PendingBatchesView {
+                        token_contract: *token,
+                        count: pending.count,
+                        lowest_nonce: pending.lowest_nonce,
+                        total_fees: pending.total_fees.to_string(),
+                        display_total_fees: format_hub_amount(&pending.total_fees),
+                    })
+                    .collect(),
+                updated_at,
+            },
+            transfers: status
+                .recent_transfers
+                .iter()
+                .map(|transfer| TransferView {
+                    event_nonce: transfer.event_nonce.to_string(),
+                    sender: transfer.sender,
+                    destination: transfer.destination.clone(),
+                    erc20: transfer.erc20,
+                    amount: transfer.amount.to_string(),
+                    display_amount: status.format_token_amount(transfer.erc20, &transfer.amount),
+                    tx_hash: transfer.tx_hash.clone(),
+                    confirmations: confirmations(&transfer.block, &status.eth_block),
+                    stage: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Counts the requests of every client IP in fixed one minute windows
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    per_minute: u32,
+    /// the minute and the requests made in it by every client
+    clients: Arc<Mutex<HashMap<IpAddr, (u64, u32)>>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        RateLimiter {
+            per_minute,
+            clients: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records a request of `client` at unix time `now`, false if it is over its limit
+    pub fn allow(&self, client: IpAddr, now: u64) -> bool {
+        let minute = now / 60;
+        let mut clients = self.clients.lock().unwrap();
+        if clients.len() >= MAX_TRACKED_CLIENTS && !clients.contains_key(&client) {
+            clients.retain(|_, (window, _)| *window == minute);
+            // with that many clients in this very minute the quietest one is forgotten, so a flood of
+            // addresses can't grow the map without bound
+            if clients.len() >= MAX_TRACKED_CLIENTS {
+                let quietest = clients
+                    .iter()
+                    .min_by_key(|(_, (_, requests))| *requests)
+                    .map(|(address, _)| *address);
+                if let Some(address) = quietest {
+                    clients.remove(&address);
+                }
+            }
+        }
+        let entry = clients.entry(client).or_insert((minute, 0));
+        if entry.0 != minute {
+            *entry = (minute, 0);
+        }
+        entry.1 += 1;
+        entry.1 <= self.per_minute
+    }
+}
+
+/// What a token may do
+#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// the read only routes
+    Read,
+    /// the read only routes and the admin instructions
+    Admin,
+}
+
+impl Scope {
+    pub fn allows(self, needed: Scope) -> bool {
+        self == Scope::Admin || needed == Scope::Read
+    }
+}
+
+/// A client's entry in the `--api-tokens` file
+#[derive(Deserialize, Debug, Clone)]
+pub struct ApiToken {
+    pub token: String,
+    pub scope: Scope,
+}
+
+/// The clients allowed to use the API, with their scope and token
+#[derive(Debug, Clone, Default)]
+pub struct ApiTokens(Vec<(String, Scope, String)>);
+
+impl ApiTokens {
+    pub fn new(tokens: HashMap<String, ApiToken>) -> Result<Self, String> {
+        let mut out = Vec::new();
+        for (client, entry) in tokens {
+            if entry.token.len() < MIN_TOKEN_LEN {
+                return Err(format!(
+                    "The token of {} is shorter than {} characters",
+                    client, MIN_TOKEN_LEN
+                ));
+            }
+            out.push((client, entry.scope, entry.token));
+        }
+        Ok(ApiTokens(out))
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let tokens: HashMap<String, ApiToken> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        let mut resolved = HashMap::new();
+        for (client, entry) in tokens {
+            let token = resolve_secret(&entry.token)
+                .await
+                .map_err(|e| format!("The token of {}: {}", client, e))?;
+            resolved.insert(
+                client,
+                ApiToken {
+                    token,
+                    scope: entry.scope,
+                },
+            );
+        }
+        ApiTokens::new(resolved)
+    }
+
+    /// Returns the client an `Authorization` header belongs to and its scope
+    pub fn authorize(&self, authorization: Option<&str>) -> Option<(&str, Scope)> {
+        let token = authorization?.strip_prefix("Bearer ")?.trim();
+        // compare against every token in constant time so response times don't leak them
+        let mut client = None;
+        for (name, scope, expected) in self.0.iter() {
+            if constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+                client = Some((name.as_str(), *scope));
+            }
+        }
+        client
+    }
+}
+
+/// Everything the handlers need, the Hub and Ethereum connections are made per refresh since the handlers run
+/// on the server's own threads
+#[derive(Clone)]
+pub struct StatusApi {
+    pub cosmos_grpc: String,
+    /// the Hub's legacy rpc the transfer stages are asked from, if any
+    pub cosmos_legacy_rpc: Option<String>,
+    pub ethereum_rpc: String,
+    pub peggy_contract_address: EthAddress,
+    pub timeout: Duration,
+    pub state_dir: Option<PathBuf>,
+    /// the key the state dir is encrypted with, if it is
+    pub state_key: Option<StateKey>,
+    /// Some in public mode
+    pub rate_limiter: Option<RateLimiter>,
+    /// the tokens every request has to carry one of, if any
+    pub tokens: Option<ApiTokens>,
+    /// how long a snapshot is served before it is refreshed, zero fetches one for every request
+    pub cache_ttl: Duration,
+    cache: Arc<AsyncMutex<Option<(Instant, Snapshot)>>>,
+}
+
+impl StatusApi {
+    pub fn new(
+        cosmos_grpc: String,
+        cosmos_legacy_rpc: Option<String>,
+        ethereum_rpc: String,
+        peggy_contract_address: EthAddress,
+        timeout: Duration,
+        state_dir: Option<PathBuf>,
+        state_key: Option<StateKey>,
+        public_rate_limit: Option<u32>,
+    ) -> Self {
+        StatusApi {
+            cosmos_grpc,
+            cosmos_legacy_rpc,
+            ethereum_rpc,
+            peggy_contract_address,
+            timeout,
+            state_dir,
+            state_key,
+            rate_limiter: public_rate_limit.map(RateLimiter::new),
+            tokens: None,
+            cache_ttl: if public_rate_limit.is_some() {
+                PUBLIC_CACHE_TTL
+            } else {
+                Duration::from_secs(0)
+            },
+            cache: Arc::new(AsyncMutex::new(None)),
+        }
+    }
+
+    pub fn is_public(&self) -> bool {
+        self.rate_limiter.is_some()
+    }
+
+    /// The cached snapshot, or a fresh one once it is older than the cache period. Requests that come in
+    /// during a refresh wait for it instead of querying the nodes themselves
+    async fn snapshot(&self) -> Result<Snapshot, PeggyError> {
+        let mut cache = self.cache.lock().await;
+        if let Some((fetched, snapshot)) = cache.as_ref() {
+            if fetched.elapsed() < self.cache_ttl {
+                return Ok(snapshot.clone());
+            }
+        }
+        let mut grpc_client = PeggyQueryClient::connect(self.cosmos_grpc.clone())
+            .await
+            .map_err(|e| {
+                PeggyError::InvalidOptionsError(format!(
+                    "Failed to connect to {}: {}",
+                    self.cosmos_grpc, e
+                ))
+            })?;
+        let mut oracle_client = connect_oracle(&self.cosmos_grpc).await?;
+        let web3 = Web3::new(&self.ethereum_rpc, self.timeout);
+        let status = get_bridge_status(
+            &web3,
+            &mut grpc_client,
+            &mut oracle_client,
+            self.peggy_contract_address,
+            None,
+            None,
+            None,
+        )
+        .await?;
+        let mut snapshot = Snapshot::new(&status, unix_now());
+        if let Some(url) = self.cosmos_legacy_rpc.as_ref() {
+            let contact = Contact::new(url, self.timeout);
+            for transfer in snapshot.transfers.iter_mut() {
+                match get_confirmation_stage(&contact, &transfer.tx_hash, transfer.confirmations)
+                    .await
+                {
+                    Ok(stage) => transfer.stage = Some(stage),
+                    Err(e) => warn!(
+                        "Status API failed to get the stage of {} {:?}",
+                        transfer.tx_hash, e
+                    ),
+                }
+            }
+        }
+        *cache = Some((Instant::now(), snapshot.clone()));
+        Ok(snapshot)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ApiError {
+    error: String,
+}
+
+fn error(message: &str) -> ApiError {
+    ApiError {
+        error: message.to_string(),
+    }
+}
+
+/// None if the client may go ahead, the response to send otherwise
+fn limit(api: &StatusApi, req: &HttpRequest) -> Option<HttpResponse> {
+    let limiter = api.rate_limiter.as_ref()?;
+    let client = match req.peer_addr() {
+        Some(address) => address.ip(),
+        None => return Some(HttpResponse::BadRequest().json(error("Unknown client address"))),
+    };
+    if limiter.allow(client, unix_now()) {
+        None
+    } else {
+        Some(HttpResponse::TooManyRequests().json(error("Rate limit exceeded, try again later")))
+    }
+}
+
+/// None if the client's token allows `scope` or no tokens are configured, the response to send otherwise
+fn authorize(api: &StatusApi, req: &HttpRequest, scope: Scope) -> Option<HttpResponse> {
+    let tokens = api.tokens.as_ref()?;
+    let authorization = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    match tokens.authorize(authorization) {
+        None => Some(
+            HttpResponse::Unauthorized()
+                .header(header::WWW_AUTHENTICATE, "Bearer")
+                .json(error("Missing or unknown token")),
+        ),
+        Some((_, granted)) if !granted.allows(scope) => {
+            Some(HttpResponse::Forbidden().json(error("The token doesn't allow this")))
+        }
+        Some(_) => None,
+    }
+}
+
+fn cache_control(api: &StatusApi) -> String {
+    format!("public, max-age={}", api.cache_ttl.as_secs())
+}
+
+async fn get_status(api: web::Data<StatusApi>, req: HttpRequest) -> HttpResponse {
+    if let Some(response) = limit(&api, &req).or_else(|| authorize(&api, &req, Scope::Read)) {
+        return response;
+    }
+    match api.snapshot().await {
+        Ok(snapshot) => HttpResponse::Ok()
+            .header("Cache-Control", cache_control(&api))
+            .json(snapshot.status),
+        Err(e) => {
+            error!("Status API failed to get the bridge status {:?}", e);
+            HttpResponse::ServiceUnavailable().json(error("Bridge status unavailable"))
+        }
+    }
+}
+
+async fn get_transfers(api: web::Data<StatusApi>, req: HttpRequest) -> HttpResponse {
+    if let Some(response) = limit(&api, &req).or_else(|| authorize(&api, &req, Scope::Read)) {
+        return response;
+    }
+    match api.snapshot().await {
+        Ok(snapshot) => HttpResponse::Ok()
+            .header("Cache-Control", cache_control(&api))
+            .json(snapshot.transfers),
+        Err(e) => {
+            error!("Status API failed to get the bridge status {:?}", e);
+            HttpResponse::ServiceUnavailable().json(error("Bridge status unavailable"))
+        }
+    }
+}
+
+#[cfg(feature = "admin-api")]
+mod admin_route {
+    use super::{authorize, error, Scope, StatusApi};
+    use crate::admin::{read_admin, submit_admin_command, AdminCommand, AdminRequests};
+    use crate::claim_delay::unix_now;
+    use crate::state_encryption::StateKey;
+    use crate::state_store::{LocalStorage, OrchestratorState};
+    use crate::stats::{compute_stats, format_day, DailyStats};
+    use actix_web::{web, HttpRequest, HttpResponse};
+    use peggy_utils::error::PeggyError;
+    use std::path::Path;
+
+    #[derive(Serialize, Debug, Clone)]
+    struct AdminView {
+        admin: AdminRequests,
+        local_state: OrchestratorState,
+        today: Vec<DailyStats>,
+    }
+
+    fn admin_view(dir: &Path, key: Option<StateKey>) -> Result<AdminView, PeggyError> {
+        let storage = LocalStorage::open_with_key(dir, key)?;
+        let today = format_day(unix_now() / (24 * 60 * 60));
+        Ok(AdminView {
+            admin: read_admin(dir)?,
+            local_state: storage.state.load()?,
+            today: compute_stats(&storage.journal.entries()?)
+                .into_iter()
+                .filter(|row| row.day == today)
+                .collect(),
+        })
+    }
+
+    pub async fn get_admin(api: web::Data<StatusApi>, req: HttpRequest) -> HttpResponse {
+        if let Some(response) = authorize(&api, &req, Scope::Read) {
+            return response;
+        }
+        let dir = match api.state_dir.as_ref() {
+            Some(dir) => dir,
+            None => return HttpResponse::NotFound().json(error("No state dir configured")),
+        };
+        match admin_view(dir, api.state_key.clone()) {
+            Ok(view) => HttpResponse::Ok().json(view),
+            Err(e) => {
+                error!("Status API failed to read the state dir {:?}", e);
+                HttpResponse::ServiceUnavailable().json(error("State dir unavailable"))
+            }
+        }
+    }
+
+    async fn submit(api: &StatusApi, req: &HttpRequest, command: AdminCommand) -> HttpResponse {
+        if let Some(response) = authorize(api, req, Scope::Admin) {
+            return response;
+        }
+        let dir = match api.state_dir.as_ref() {
+            Some(dir) => dir,
+            None => return HttpResponse::NotFound().json(error("No state dir configured")),
+        };
+        info!("Status API client submitted {:?}", command);
+        match submit_admin_command(dir, command) {
+            Ok(()) => HttpResponse::Ok().json(read_admin(dir).unwrap_or_default()),
+            Err(e) => {
+                error!("Status API failed to write the admin instructions {:?}", e);
+                HttpResponse::ServiceUnavailable().json(error("State dir unavailable"))
+            }
+        }
+    }
+
+    pub async fn pause_relaying(api: web::Data<StatusApi>, req: HttpRequest) -> HttpResponse {
+        submit(&api, &req, AdminCommand::PauseRelaying).await
+    }
+
+    pub async fn resume_relaying(api: web::Data<StatusApi>, req: HttpRequest) -> HttpResponse {
+        submit(&api, &req, AdminCommand::ResumeRelaying).await
+    }
+}
+
+/// True if every address `bind` resolves to is a loopback address, false if any isn't or it doesn't resolve
+pub fn is_loopback(bind: &str) -> bool {
+    match bind.to_socket_addrs() {
+        Ok(addresses) => {
+            let addresses: Vec<_> = addresses.collect();
+            !addresses.is_empty() && addresses.iter().all(|address| address.ip().is_loopback())
+        }
+        Err(_) => false,
+    }
+}
+
+/// The admin route is only served to operators on this machine or with a token, never on a public API. The
+/// admin instructions need an admin token
+#[cfg(feature = "admin-api")]
+fn admin_routes(config: &mut web::ServiceConfig, admin: bool, instructions: bool) {
+    if admin {
+        config.route("/admin", web::get().to(admin_route::get_admin));
+    }
+    if instructions {
+        config
+            .route(
+                "/admin/pause-relaying",
+                web::post().to(admin_route::pause_relaying),
+            )
+            .route(
+                "/admin/resume-relaying",
+                web::post().to(admin_route::resume_relaying),
+            );
+    }
+}
+
+#[cfg(not(feature = "admin-api"))]
+fn admin_routes(_config: &mut web::ServiceConfig, _admin: bool, _instructions: bool) {}
+
+/// Serves the status API on `bind` until the process exits, over HTTPS with `tls`. Without tokens the admin
+/// route is only served if `bind` is a loopback address since it is served to anyone who can connect
+pub async fn run_status_api(
+    bind: String,
+    api: StatusApi,
+    tls: Option<SslAcceptorBuilder>,
+) -> std::io::Result<()> {
+    let authenticated = api.tokens.is_some();
+    let admin = !api.is_public() && (authenticated || is_loopback(&bind));
+    let instructions = admin && authenticated;
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    if api.is_public() {
+        info!("Public status API listening on {}://{}", scheme, bind);
+    } else if admin {
+        info!("Status API listening on {}://{}", scheme, bind);
+    } else {
+        warn!(
+            "Status API listening on {}://{} without the admin route, it needs --api-tokens or loopback",
+            scheme, bind
+        );
+    }
+    let server = HttpServer::new(move || {
+        App::new()
+            .data(api.clone())
+            .route("/status", web::get().to(get_status))
+            .route("/transfers", web::get().to(get_transfers))
+            .configure(|config| admin_routes(config, admin, instructions))
+    })
+    .workers(1);
+    match tls {
+        Some(acceptor) => server.bind_openssl(bind, acceptor)?,
+        None => server.bind(bind)?,
+    }
+    .run()
+    .await
+}
+
+#[test]
+fn test_rate_limiter() {
+    let limiter = RateLimiter::new(2);
+    let alice: IpAddr = "10.0.0.1".parse().unwrap();
+    let bob: IpAddr = "10.0.0.2".parse().unwrap();
+    assert!(limiter.allow(alice, 60));
+    assert!(limiter.allow(alice, 90));
+    assert!(!limiter.allow(alice, 119));
+    // every client has its own limit
+    assert!(limiter.allow(bob, 119));
+    // and it starts over every minute
+    assert!(limiter.allow(alice, 120));
+
+    // a full map forgets the quietest client of the minute to make room
+    let limiter = RateLimiter::new(DEFAULT_RATE_LIMIT);
+    for i in 0..MAX_TRACKED_CLIENTS as u32 {
+        assert!(limiter.allow(IpAddr::from(i.to_be_bytes()), 60));
+    }
+    assert!(limiter.allow(alice, 60));
+    assert!(limiter.allow(alice, 61));
+    assert!(limiter.allow(bob, 62));
+    assert_eq!(limiter.clients.lock().unwrap().len(), MAX_TRACKED_CLIENTS);
+    assert_eq!(limiter.clients.lock().unwrap()[&alice], (1, 2));
+
+    let private = StatusApi::new(
+        "http://localhost:9090".to_string(),
+        None,
+        "http://localhost:8545".to_string(),
+        EthAddress::default(),
+        Duration::from_secs(10),
+        None,
+        None,
+        None,
+    );
+    assert!(!private.is_public());
+    assert_eq!(private.cache_ttl, Duration::from_secs(0));
+    let public = StatusApi {
+        rate_limiter: Some(RateLimiter::new(DEFAULT_RATE_LIMIT)),
+        cache_ttl: PUBLIC_CACHE_TTL,
+        ..private
+    };
+    assert!(public.is_public());
+}
+
+#[test]
+fn test_api_tokens() {
+    let entry = |token: &str, scope: Scope| ApiToken {
+        token: token.to_string(),
+        scope,
+    };
+    let mut tokens = HashMap::new();
+    tokens.insert(
+        "dashboard".to_string(),
+        entry("dashboard-token-0123456789", Scope::Read),
+    );
+    tokens.insert(
+        "operator".to_string(),
+        entry("operator-token-0123456789", Scope::Admin),
+    );
+    let tokens = ApiTokens::new(tokens).unwrap();
+
+    assert_eq!(
+        tokens.authorize(Some("Bearer dashboard-token-0123456789")),
+        Some(("dashboard", Scope::Read))
+    );
+    assert_eq!(
+        tokens.authorize(Some("Bearer operator-token-0123456789")),
+        Some(("operator", Scope::Admin))
+    );
+    assert_eq!(
+        tokens.authorize(Some("Bearer unknown-token-0123456789")),
+        None
+    );
+    assert_eq!(tokens.authorize(Some("dashboard-token-0123456789")), None);
+    assert_eq!(tokens.authorize(None), None);
+
+    // a read token can't give instructions, an admin token can do everything
+    assert!(Scope::Read.allows(Scope::Read));
+    assert!(!Scope::Read.allows(Scope::Admin));
+    assert!(Scope::Admin.allows(Scope::Read));
+    assert!(Scope::Admin.allows(Scope::Admin));
+
+    let mut short = HashMap::new();
+    short.insert("dashboard".to_string(), entry("short", Scope::Read));
+    assert!(ApiTokens::new(short).is_err());
+
+    let parsed: HashMap<String, ApiToken> =
+        serde_json::from_str(r#"{"dashboard": {"token": "t", "scope": "read"}}"#).unwrap();
+    assert_eq!(parsed["dashboard"].scope, Scope::Read);
+    assert!(serde_json::from_str::<HashMap<String, ApiToken>>(
+        r#"{"d": {"token": "t", "scope": "root"}}"#
+    )
+    .is_err());
+}
+
+#[test]
+fn test_is_loopback() {
+    assert!(is_loopback("127.0.0.1:8080"));
+    assert!(is_loopback("[::1]:8080"));
+    assert!(!is_loopback("0.0.0.0:8080"));
+    assert!(!is_loopback("[::]:8080"));
+    assert!(!is_loopback("10.0.0.1:8080"));
+    assert!(!is_loopback("not an address"));
+}
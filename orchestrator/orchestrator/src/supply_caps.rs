@@ -0,0 +1,133 @@
+//! Per token caps on the supply bridged from Ethereum. A bug in the Peggy contract or a compromised token
+//! could let an attacker mint an unbounded amount on the Hub, capping what we are willing to attest to limits
+//! the damage. Once the amount of a token locked in the Peggy contract is over its cap we stop claiming
+//! deposits of it, and since claims must be made in event nonce order everything after the first refused
+//! deposit waits as well until the cap is raised or the locked supply drops again.
+
+use clarity::{Address as EthAddress, Uint256};
+use ethereum_peggy::utils::get_locked_balance;
+use peggy_utils::amount::format_token_amount;
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::types::{SendToCosmosEvent, SendToMinterEvent};
+use std::collections::HashMap;
+use web30::client::Web3;
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SupplyCaps(HashMap<EthAddress, Uint256>);
+
+impl SupplyCaps {
+    /// Parses comma separated `<erc20>:<cap>` pairs, caps are in the token's base units
+    pub fn parse(caps: Option<&str>) -> Result<Self, String> {
+        match caps {
+            Some(caps) => Ok(SupplyCaps(parse_token_amounts(caps)?)),
+            None => Ok(SupplyCaps::default()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn cap(&self, erc20: &EthAddress) -> Option<&Uint256> {
+        self.0.get(erc20)
+    }
+}
+
+/// Parses comma separated `<erc20>:<amount>` pairs, amounts are in the token's base units
+pub fn parse_token_amounts(pairs: &str) -> Result<HashMap<EthAddress, Uint256>, String> {
+    let mut parsed = HashMap::new();
+    for pair in pairs.split(',').map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let mut parts = pair.splitn(2, ':');
+        let (erc20, amount) = match (parts.next(), parts.next()) {
+            (Some(erc20), Some(amount)) => (erc20, amount),
+            _ => {
+                return Err(format!(
+                    "Invalid token amount {}, expected <erc20>:<amount>",
+                    pair
+                ))
+            }
+        };
+        let erc20: EthAddress = erc20
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid token address {}: {:?}", erc20, e))?;
+        let amount: Uint256 = amount
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid token amount {}: {:?}", amount, e))?;
+        parsed.insert(erc20, amount);
+    }
+    Ok(parsed)
+}
+
+/// Returns the event nonce of the first deposit we must not claim because the supply of its token locked in the
+/// Peggy contract is over the cap, each capped token is queried once per call
+pub async fn first_capped_deposit(
+    web3: &Web3,
+    peggy_contract_address: EthAddress,
+    caps: &SupplyCaps,
+    deposits: &[SendToCosmosEvent],
+    transfers: &[SendToMinterEvent],
+) -> Result<Option<Uint256>, PeggyError> {
+    if caps.is_empty() {
+        return Ok(None);
+    }
+
+    let events: Vec<(EthAddress, Uint256)> = deposits
+        .iter()
+        .map(|e| (e.erc20, e.event_nonce.clone()))
+        .chain(transfers.iter().map(|e| (e.erc20, e.event_nonce.clone())))
+        .collect();
+
+    let mut over_cap: HashMap<EthAddress, bool> = HashMap::new();
+    let mut first: Option<Uint256> = None;
+    for (erc20, event_nonce) in events {
+        let cap = match caps.cap(&erc20) {
+            Some(cap) => cap,
+            None => continue,
+        };
+        let over = match over_cap.get(&erc20) {
+            Some(over) => *over,
+            None => {
+                let locked = get_locked_balance(peggy_contract_address, erc20, web3).await?;
+                let over = locked > *cap;
+                if over {
+                    error!(
+                        "SUPPLY CAP EXCEEDED for {}, {} locked in the Peggy contract with a cap of {}, refusing to claim its deposits",
+                        explorer::address(Chain::Ethereum, erc20),
+                        format_token_amount(&locked, None),
+                        format_token_amount(cap, None)
+                    );
+                }
+                over_cap.insert(erc20, over);
+                over
+            }
+        };
+        if over && first.as_ref().map(|f| event_nonce < *f).unwrap_or(true) {
+            first = Some(event_nonce);
+        }
+    }
+    Ok(first)
+}
+
+#[test]
+fn test_parse_supply_caps() {
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    assert!(SupplyCaps::parse(None).unwrap().is_empty());
+
+    let caps = SupplyCaps::parse(Some(
+        "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:1000000000000000000000, ",
+    ))
+    .unwrap();
+    assert_eq!(
+        caps.cap(&token),
+        Some(&"1000000000000000000000".parse().unwrap())
+    );
+
+    assert!(SupplyCaps::parse(Some("0x7580bFE88Dd3d07947908FAE12d95872a260F2D8")).is_err());
+    assert!(SupplyCaps::parse(Some("not an address:100")).is_err());
+    assert!(SupplyCaps::parse(Some("0x7580bFE88Dd3d07947908FAE12d95872a260F2D8:ten")).is_err());
+}
@@ -0,0 +1,627 @@
+//! A read only terminal dashboard for operators, `orchestrator tui` redraws the current bridge status
+//! every few seconds. It only talks to the Hub gRPC, the Ethereum node and the orchestrator state dir, so
+//! it can run next to a live orchestrator without interfering with it. With a price feed batch fees are also
+//! shown in a single quote asset, so pending fees in different tokens can be compared and added up. The
+//! validators missing confirmations come from the signing report the orchestrator keeps in the state store.
+//! Whether relaying a batch pays off is judged on the averaged gas price rather than the spot price, so it
+//! doesn't flip with every gas spike. The contract's version and features are detected on every refresh.
+//! Amounts are shown in whole tokens, transfers in their token's Ethereum decimals from the Hub's coin list and
+//! fees in Hub units, see `peggy_utils::amount`.
+
+use crate::admin::{read_admin, AdminRequests};
+use crate::build_info::BuildInfo;
+use crate::claim_delay::unix_now;
+use crate::fee_value::{get_token_decimals, FeeQuoter, FeeValuer, ETH_DECIMALS};
+use crate::journal::Direction;
+use crate::pause::{get_pause_state, PauseState};
+use crate::solvency::format_hub_amount;
+use crate::state_store::{LocalStorage, OrchestratorState};
+use crate::stats::{compute_stats, format_day, DailyStats};
+use crate::version::Version;
+use clarity::{Address as EthAddress, Uint256};
+use cosmos_peggy::query::{get_latest_transaction_batches, get_latest_valsets};
+use ethereum_peggy::capabilities::{get_contract_capabilities, ContractCapabilities};
+use ethereum_peggy::submit_batch::BATCH_GAS_LIMIT;
+use ethereum_peggy::utils::{
+    get_event_nonce, get_relayer_authorization, get_valset_nonce, RelayerAuthorization,
+};
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::amount::{format_amount, format_decimal, format_token_amount};
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::gas_price::GasPriceAverage;
+use peggy_utils::types::{SendToCosmosEvent, SendToMinterEvent, TransactionBatch};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::ops::Sub;
+use std::time::Duration;
+use tokio::time::delay_for;
+use tonic::transport::Channel;
+use web30::client::Web3;
+use web30::types::Log;
+
+pub const TUI_REFRESH: Duration = Duration::from_secs(5);
+/// How far back to look for transfers to show
+const RECENT_BLOCKS: u64 = 1000;
+const RECENT_TRANSFERS: usize = 10;
+/// Gas prices are shown in gwei
+pub const GWEI_DECIMALS: usize = 9;
+/// How many validators of the signing report to show, worst first
+const NON_SIGNING_VALIDATORS: usize = 10;
+
+#[derive(Debug, Clone, Default)]
+pub struct PendingBatches {
+    pub count: usize,
+    pub lowest_nonce: u64,
+    pub total_fees: Uint256,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecentTransfer {
+    pub event_nonce: Uint256,
+    pub sender: EthAddress,
+    pub destination: String,
+    pub erc20: EthAddress,
+    pub amount: Uint256,
+    pub tx_hash: String,
+    /// the Ethereum block it is in
+    pub block: Uint256,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BridgeStatus {
+    pub eth_block: Uint256,
+    pub gas_price: Uint256,
+    /// the time weighted average of the gas prices seen by this tui, filled in by `run_tui`
+    pub average_gas_price: Option<Uint256>,
+    pub eth_event_nonce: u64,
+    pub eth_valset_nonce: u64,
+    pub hub_valset_nonce: Option<u64>,
+    pub pause_state: PauseState,
+    /// the version and features detected on the deployed contract
+    pub capabilities: ContractCapabilities,
+    pub pending_batches: Vec<(EthAddress, PendingBatches)>,
+    pub recent_transfers: Vec<RecentTransfer>,
+    pub relayer_balance: Option<Uint256>,
+    /// whether the relayer may submit batches, for deployments with a relayer whitelist
+    pub relayer_authorization: Option<RelayerAuthorization>,
+    pub local_state: Option<OrchestratorState>,
+    /// today's statistics from the journal
+    pub today: Vec<DailyStats>,
+    /// operator instructions given with the admin commands
+    pub admin: AdminRequests,
+    /// values fees in the quote asset, None without a price feed or when it failed
+    pub fee_valuer: Option<FeeValuer>,
+    /// the Ethereum decimals of the Hub's tokens, empty when asking the Hub failed
+    pub token_decimals: HashMap<EthAddress, u64>,
+}
+
+impl BridgeStatus {
+    /// An amount of `token` in its Ethereum decimals, for display
+    pub fn format_token_amount(&self, token: EthAddress, amount: &Uint256) -> String {
+        format_token_amount(amount, self.token_decimals.get(&token).copied())
+    }
+}
+
+pub async fn run_tui(
+    web3: Web3,
+    grpc_client: PeggyQueryClient<Channel>,
+    oracle_client: OracleQueryClient<Channel>,
+    peggy_contract_address: EthAddress,
+    relayer_address: Option<EthAddress>,
+    storage: Option<LocalStorage>,
+    fee_quoter: Option<FeeQuoter>,
+    gas_price_window: Duration,
+) {
+    let mut grpc_client = grpc_client;
+    let mut oracle_client = oracle_client;
+    let mut gas_price_average = GasPriceAverage::new(gas_price_window);
+    loop {
+        let status = get_bridge_status(
+            &web3,
+            &mut grpc_client,
+            &mut oracle_client,
+            peggy_contract_address,
+            relayer_address,
+            storage.as_ref(),
+            fee_quoter.as_ref(),
+        )
+        .await;
+        // clear the screen and move the cursor home before redrawing
+        print!("\x1B[2J\x1B[H");
+        match status {
+            Ok(mut status) => {
+                gas_price_average.observe(&status.gas_price);
+                status.average_gas_price = gas_price_average.average();
+                println!("{}", render(&status))
+            }
+            Err(e) => println!("Failed to get bridge status: {}", e),
+        }
+        delay_for(TUI_REFRESH).await;
+    }
+}
+
+pub async fn get_bridge_status(
+    web3: &Web3,
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    oracle_client: &mut OracleQueryClient<Channel>,
+    peggy_contract_address: EthAddress,
+    relayer_address: Option<EthAddress>,
+    storage: Option<&LocalStorage>,
+    fee_quoter: Option<&FeeQuoter>,
+) -> Result<BridgeStatus, PeggyError> {
+    let eth_block = web3.eth_block_number().await?;
+    let gas_price = web3.eth_gas_price().await?;
+    let eth_event_nonce = get_event_nonce(peggy_contract_address, web3).await?;
+    let eth_valset_nonce =
+        get_valset_nonce(peggy_contract_address, peggy_contract_address, web3).await?;
+    let hub_valset_nonce = get_latest_valsets(grpc_client)
+        .await?
+        .iter()
+        .map(|v| v.nonce)
+        .max();
+    let pending_batches = summarize_batches(&get_latest_transaction_batches(grpc_client).await?);
+    let pause_state = get_pause_state(web3, grpc_client, peggy_contract_address).await?;
+    let capabilities = get_contract_capabilities(peggy_contract_address, web3).await?;
+
+    let starting_block = if eth_block > RECENT_BLOCKS.into() {
+        eth_block.clone().sub(RECENT_BLOCKS.into())
+    } else {
+        0u8.into()
+    };
+    let deposits = web3
+        .check_for_events(
+            starting_block.clone(),
+            Some(eth_block.clone()),
+            vec![peggy_contract_address],
+            vec!["SendToHubEvent(address,address,bytes32,uint256,uint256)"],
+        )
+        .await?;
+    let transfers = web3
+        .check_for_events(
+            starting_block,
+            Some(eth_block.clone()),
+            vec![peggy_contract_address],
+            vec!["SendToMinterEvent(address,address,bytes32,uint256,uint256)"],
+        )
+        .await?;
+    // logs without a block are pending, they count as in the latest one
+    let block_of = |log: &Log| {
+        log.block_number
+            .clone()
+            .unwrap_or_else(|| eth_block.clone())
+    };
+    let mut recent_transfers = Vec::new();
+    for log in deposits.iter() {
+        let e = SendToCosmosEvent::from_log(log)?;
+        recent_transfers.push(RecentTransfer {
+            event_nonce: e.event_nonce,
+            sender: e.sender,
+            destination: e.destination.to_string(),
+            erc20: e.erc20,
+            amount: e.amount,
+            tx_hash: e.tx_hash,
+            block: block_of(log),
+        });
+    }
+    for log in transfers.iter() {
+        let e = SendToMinterEvent::from_log(log)?;
+        recent_transfers.push(RecentTransfer {
+            event_nonce: e.event_nonce,
+            sender: e.sender,
+            destination: e.destination,
+            erc20: e.erc20,
+            amount: e.amount,
+            tx_hash: e.tx_hash,
+            block: block_of(log),
+        });
+    }
+    recent_transfers.sort_by(|a, b| b.event_nonce.cmp(&a.event_nonce));
+    recent_transfers.truncate(RECENT_TRANSFERS);
+
+    let relayer_balance = match relayer_address {
+        Some(address) => Some(web3.eth_get_balance(address).await?),
+        None => None,
+    };
+    let relayer_authorization = match relayer_address {
+        Some(address) => {
+            Some(get_relayer_authorization(peggy_contract_address, address, web3).await?)
+        }
+        None => None,
+    };
+    let local_state = match storage {
+        Some(storage) => Some(storage.state.load()?),
+        None => None,
+    };
+    let today = match storage {
+        Some(storage) => {
+            let today = format_day(unix_now() / (24 * 60 * 60));
+            compute_stats(&storage.journal.entries()?)
+                .into_iter()
+                .filter(|row| row.day == today)
+                .collect()
+        }
+        None => Vec::new(),
+    };
+    let admin = match storage {
+        Some(storage) => read_admin(&storage.dir)?,
+        None => AdminRequests::default(),
+    };
+
+    // a price feed outage shouldn't take the rest of the dashboard with it
+    let fee_valuer = match fee_quoter {
+        Some(quoter) => quoter.valuer().await.ok(),
+        None => None,
+    };
+    let token_decimals = get_token_decimals(oracle_client).await.unwrap_or_default();
+
+    Ok(BridgeStatus {
+        eth_block,
+        gas_price,
+        average_gas_price: None,
+        eth_event_nonce,
+        eth_valset_nonce,
+        hub_valset_nonce,
+        pause_state,
+        capabilities,
+        pending_batches,
+        recent_transfers,
+        relayer_balance,
+        relayer_authorization,
+        local_state,
+        today,
+        admin,
+        fee_valuer,
+        token_decimals,
+    })
+}
+
+/// Groups the pending batches by token, in the order the tokens first appear
+fn summarize_batches(batches: &[TransactionBatch]) -> Vec<(EthAddress, PendingBatches)> {
+    let mut out: Vec<(EthAddress, PendingBatches)> = Vec::new();
+    for batch in batches {
+        let position = match out.iter().position(|(t, _)| *t == batch.token_contract) {
+            Some(position) => position,
+            None => {
+                out.push((batch.token_contract, PendingBatches::default()));
+                out.len() - 1
+            }
+        };
+        let entry = &mut out[position].1;
+        if entry.count == 0 || batch.nonce < entry.lowest_nonce {
+            entry.lowest_nonce = batch.nonce;
+        }
+        entry.count += 1;
+        entry.total_fees = entry.total_fees.clone() + batch.total_fee.amount.clone();
+    }
+    out
+}
+
+pub fn render(status: &BridgeStatus) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "Peggy bridge status");
+    let _ = writeln!(out, "===================");
+    let _ = writeln!(out, "Orchestrator build      {}", BuildInfo::current());
+    let _ = writeln!(out, "Ethereum block          {}", status.eth_block);
+    match &status.average_gas_price {
+        Some(average) => {
+            let _ = writeln!(
+                out,
+                "Gas price (gwei)        {}, average {}",
+                format_decimal(&status.gas_price, GWEI_DECIMALS),
+                format_decimal(average, GWEI_DECIMALS)
+            );
+        }
+        None => {
+            let _ = writeln!(
+                out,
+                "Gas price (gwei)        {}",
+                format_decimal(&status.gas_price, GWEI_DECIMALS)
+            );
+        }
+    }
+    let _ = writeln!(out, "Ethereum event nonce    {}", status.eth_event_nonce);
+    let _ = writeln!(out, "Ethereum valset nonce   {}", status.eth_valset_nonce);
+    let _ = writeln!(out, "Peggy contract          {}", status.capabilities);
+    if !status.capabilities.can_relay_batches() {
+        let _ = writeln!(
+            out,
+            "Batch relaying          UNSUPPORTED by this orchestrator"
+        );
+    }
+    match status.hub_valset_nonce {
+        Some(nonce) => {
+            let _ = writeln!(out, "Hub valset nonce        {}", nonce);
+        }
+        None => {
+            let _ = writeln!(out, "Hub valset nonce        none requested");
+        }
+    }
+    if status.pause_state.contract_halted {
+        let _ = writeln!(out, "Contract                HALTED");
+    }
+    if status.pause_state.deposits_stopped {
+        let _ = writeln!(out, "Deposits                STOPPED");
+    }
+    if status.pause_state.hub_stopped {
+        let _ = writeln!(out, "Hub bridge              STOPPED");
+    }
+    if let Some(halt_at) = status.pause_state.halt_at_event_nonce {
+        let _ = writeln!(
+            out,
+            "Bridge                  HALTED at event nonce {}",
+            halt_at
+        );
+    }
+    if let Some(min) = status.pause_state.min_orchestrator_version {
+        let outdated = if status.pause_state.version_outdated() {
+            ", OUTDATED"
+        } else {
+            ""
+        };
+        let _ = writeln!(
+            out,
+            "Orchestrator version    {} (at least {} required{})",
+            Version::ours(),
+            min,
+            outdated
+        );
+    }
+    if status.admin.relaying_paused {
+        let _ = writeln!(out, "Relaying                PAUSED by the operator");
+    }
+    for token in status.admin.disabled_tokens.iter() {
+        let _ = writeln!(out, "Relaying disabled for   {}", token);
+    }
+    for batch in status.admin.skipped_batches.iter() {
+        let _ = writeln!(
+            out,
+            "Skipped batch           {}:{}",
+            batch.token_contract, batch.nonce
+        );
+    }
+    if let Some(state) = &status.local_state {
+        let _ = writeln!(out, "Our last claimed nonce  {}", state.last_event_nonce);
+        if let Some(block) = &state.last_checked_block {
+            let _ = writeln!(out, "Our last checked block  {}", block);
+        }
+        if let Some(tolerance) = &state.downtime_tolerance {
+            match tolerance.secs {
+                Some(secs) => {
+                    let _ = writeln!(
+                        out,
+                        "Downtime tolerance      {} blocks, about {}h {}m",
+                        tolerance.blocks,
+                        secs / 3600,
+                        secs % 3600 / 60
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "Downtime tolerance      {} blocks", tolerance.blocks);
+                }
+            }
+        }
+    }
+    if let Some(balance) = &status.relayer_balance {
+        let _ = writeln!(
+            out,
+            "Relayer balance (ETH)   {}",
+            format_amount(balance, ETH_DECIMALS as usize)
+        );
+    }
+    match status.relayer_authorization {
+        Some(RelayerAuthorization::Unrestricted) => {
+            let _ = writeln!(out, "Relayer whitelist       none, anyone may relay");
+        }
+        Some(RelayerAuthorization::Authorized) => {
+            let _ = writeln!(out, "Relayer whitelist       authorized");
+        }
+        Some(RelayerAuthorization::NotAuthorized) => {
+            let _ = writeln!(
+                out,
+                "Relayer whitelist       NOT AUTHORIZED to relay batches"
+            );
+        }
+        None => {}
+    }
+
+    let _ = writeln!(out, "\nPending batches");
+    if status.pending_batches.is_empty() {
+        let _ = writeln!(out, "  none");
+    }
+    for (token, batches) in status.pending_batches.iter() {
+        let _ = writeln!(
+            out,
+            "  {} count {} oldest nonce {} total fees {}{}",
+            token,
+            batches.count,
+            batches.lowest_nonce,
+            format_hub_amount(&batches.total_fees),
+            fee_value(status, *token, &batches.total_fees)
+        );
+        if let Some(line) = relay_profitability(status, *token, &batches.total_fees) {
+            let _ = writeln!(out, "    {}", line);
+        }
+    }
+    if let Some(valuer) = &status.fee_valuer {
+        let total = valuer.total(
+            status
+                .pending_batches
+                .iter()
+                .map(|(token, batches)| (*token, &batches.total_fees)),
+        );
+        if let (false, Some(total)) = (status.pending_batches.is_empty(), total) {
+            let _ = writeln!(out, "  all tokens total fees {}", valuer.format(&total));
+        }
+    }
+
+    let _ = writeln!(out, "\nRecent transfers");
+    if status.recent_transfers.is_empty() {
+        let _ = writeln!(out, "  none");
+    }
+    for transfer in status.recent_transfers.iter() {
+        let _ = writeln!(
+            out,
+            "  #{} {} -> {} {} of {}",
+            transfer.event_nonce,
+            transfer.sender,
+            transfer.destination,
+            status.format_token_amount(transfer.erc20, &transfer.amount),
+            transfer.erc20
+        );
+        if let Some(url) = explorer::tx(Chain::Ethereum, &transfer.tx_hash).url() {
+            let _ = writeln!(out, "    {}", url);
+        }
+    }
+
+    if let Some(state) = &status.local_state {
+        if !state.delayed_claims.is_empty() {
+            let _ = writeln!(out, "\nDelayed claims");
+        }
+        let now = unix_now();
+        for claim in state.delayed_claims.iter() {
+            let _ = writeln!(
+                out,
+                "  #{} {} of {} -> {} signed in {}s",
+                claim.event_nonce,
+                status.format_token_amount(claim.erc20, &claim.amount),
+                claim.erc20,
+                claim.destination,
+                claim.release_at.saturating_sub(now)
+            );
+        }
+        if let Some(submission) = state.claim_submission.as_ref() {
+            let _ = writeln!(
+                out,
+                "\nClaims not yet on the Hub\n  {:?} sent {} times over {}s{}",
+                submission.event_nonces,
+                submission.attempts,
+                now.saturating_sub(submission.first_submitted_at),
+                if submission.expired { ", expired" } else { "" }
+            );
+        }
+
+        let _ = writeln!(out, "\nToday");
+        if status.today.is_empty() {
+            let _ = writeln!(out, "  none");
+        }
+        for row in status.today.iter() {
+            let direction = match row.direction {
+                Direction::ToHub => "to Hub",
+                Direction::ToMinter => "to Minter",
+                Direction::FromHub => "from Hub",
+            };
+            let _ = writeln!(
+                out,
+                "  {} {:<10} count {} volume {} fees {}{}",
+                row.erc20,
+                direction,
+                row.count,
+                status.format_token_amount(row.erc20, &row.volume),
+                format_hub_amount(&row.fees),
+                fee_value(status, row.erc20, &row.fees)
+            );
+        }
+
+        let _ = writeln!(out, "\nTotals");
+        if state.counters.is_empty() {
+            let _ = writeln!(out, "  none");
+        }
+        for (counter, total) in state.counters.iter() {
+            let _ = writeln!(out, "  {:<22} {}", counter, total);
+        }
+
+        let _ = writeln!(out, "\nError counters");
+        if state.error_counts.is_empty() {
+            let _ = writeln!(out, "  none");
+        }
+        for (kind, count) in state.error_counts.iter() {
+            let _ = writeln!(out, "  {:<22} {}", kind, count);
+        }
+
+        let _ = writeln!(out, "\nNon-signing validators");
+        let mut problems = state
+            .signing_report
+            .iter()
+            .filter(|record| !record.is_clean())
+            .peekable();
+        if problems.peek().is_none() {
+            let _ = writeln!(out, "  none");
+        }
+        for record in problems.take(NON_SIGNING_VALIDATORS) {
+            let lag = match record.event_nonce_lag {
+                Some(lag) => lag.to_string(),
+                None => "?".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "  {} valsets {}/{} batches {}/{} missed, {} events behind",
+                record.ethereum_address,
+                record.valsets_missed,
+                record.valsets,
+                record.batches_missed,
+                record.batches,
+                lag
+            );
+        }
+    }
+    out
+}
+
+/// The value of a fee in the quote asset for display after the fee, empty if it has none
+fn fee_value(status: &BridgeStatus, token: EthAddress, fee: &Uint256) -> String {
+    if *fee == Uint256::from(0u8) {
+        return String::new();
+    }
+    match status
+        .fee_valuer
+        .as_ref()
+        .and_then(|valuer| Some(valuer.format(&valuer.value(token, fee)?)))
+    {
+        Some(value) => format!(" ({})", value),
+        None => String::new(),
+    }
+}
+
+/// Compares the value of a token's pending fees to the cost of relaying a batch at the average gas price, the
+/// cost is an upper bound as it assumes the whole batch gas limit is used
+fn relay_profitability(status: &BridgeStatus, token: EthAddress, fees: &Uint256) -> Option<String> {
+    let valuer = status.fee_valuer.as_ref()?;
+    let gas_price = status.average_gas_price.as_ref()?;
+    let cost = valuer.eth_value(&(gas_price.clone() * Uint256::from(BATCH_GAS_LIMIT)))?;
+    let value = valuer.value(token, fees)?;
+    let verdict = if value >= cost {
+        "profitable"
+    } else {
+        "NOT profitable"
+    };
+    Some(format!(
+        "relay cost up to {} at the average gas price, {}",
+        valuer.format(&cost),
+        verdict
+    ))
+}
+
+#[test]
+fn test_summarize_batches() {
+    let token: EthAddress = "0x34Ac3eB6180FdD94043664C22043F004734Dc480"
+        .parse()
+        .unwrap();
+    let batch = |nonce: u64, fee: u64| TransactionBatch {
+        nonce,
+        transactions: Vec::new(),
+        total_fee: peggy_utils::types::ERC20Token {
+            amount: fee.into(),
+            token_contract_address: token,
+        },
+        token_contract: token,
+        block: 0,
+    };
+    let summary = summarize_batches(&[batch(7, 10), batch(5, 20)]);
+    assert_eq!(summary.len(), 1);
+    let pending = &summary[0].1;
+    assert_eq!(pending.count, 2);
+    assert_eq!(pending.lowest_nonce, 5);
+    assert_eq!(pending.total_fees, 30u64.into());
+}
@@ -0,0 +1,346 @@
+//! `orchestrator verify-batch` rebuilds a batch's submitBatch call from nothing but the Hub and the contract,
+//! so anyone can independently check what a relayer is about to submit or did submit. The batch and its
+//! confirms come from the Hub, the valset and PeggyID from the contract, and the calldata is built by the same
+//! code the relayer uses, so it matches a relayer's transaction byte for byte.
+//!
+//! Every confirm is checked against the checkpoint hash, not just the ones that end up in the calldata. A
+//! confirm from an address that isn't in the valset on Ethereum counts for nothing there.
+
+use crate::solvency::format_hub_amount;
+use clarity::Address as EthAddress;
+use clarity::Signature as EthSignature;
+use cosmos_peggy::query::{get_transaction_batch, get_transaction_batch_signatures};
+use deep_space::address::Address as CosmosAddress;
+use deep_space::utils::bytes_to_hex_str;
+use ethereum_peggy::checkpoint_cache::{batch_checkpoint, Checkpoint};
+use ethereum_peggy::submit_batch::{encode_submit_batch, SubmitBatchCall};
+use ethereum_peggy::utils::{
+    get_peggy_id_string, get_power_threshold, get_signature_encoding, get_tx_batch_nonce,
+};
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use peggy_utils::types::{BatchConfirmResponse, SignatureSelection, TransactionBatch, Valset};
+use relayer::find_latest_valset::find_latest_valset;
+use std::fmt::Write;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// The outcome of checking one confirm against the checkpoint
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ConfirmCheck {
+    pub orchestrator: CosmosAddress,
+    pub eth_address: EthAddress,
+    /// the signer's power in the valset on Ethereum, None if it isn't a member
+    pub power: Option<u64>,
+    pub valid: bool,
+}
+
+/// Checks every confirm's signature against `signed_hash` and looks up its power in `valset`
+pub fn check_confirms(
+    valset: &Valset,
+    confirms: &[BatchConfirmResponse],
+    signed_hash: &[u8],
+) -> Vec<ConfirmCheck> {
+    confirms
+        .iter()
+        .map(|confirm| {
+            let signature = EthSignature::new(
+                confirm.eth_signature.v.clone(),
+                confirm.eth_signature.r.clone(),
+                confirm.eth_signature.s.clone(),
+            );
+            let valid = match signature.recover(signed_hash) {
+                Ok(signer) => signer == confirm.ethereum_signer,
+                Err(_) => false,
+            };
+            let power = valset
+                .members
+                .iter()
+                .find(|member| member.eth_address == Some(confirm.ethereum_signer))
+                .map(|member| member.power);
+            ConfirmCheck {
+                orchestrator: confirm.orchestrator,
+                eth_address: confirm.ethereum_signer,
+                power,
+                valid,
+            }
+        })
+        .collect()
+}
+
+/// The valset power behind the valid confirms
+pub fn valid_power(checks: &[ConfirmCheck]) -> u64 {
+    checks
+        .iter()
+        .filter(|check| check.valid)
+        .filter_map(|check| check.power)
+        .sum()
+}
+
+#[derive(Debug)]
+pub struct BatchVerification {
+    pub batch: TransactionBatch,
+    pub valset: Valset,
+    pub peggy_id: String,
+    pub checkpoint: Checkpoint,
+    pub confirms: Vec<ConfirmCheck>,
+    /// the rebuilt call, or why a relayer couldn't submit the batch
+    pub call: Result<SubmitBatchCall, PeggyError>,
+    /// the contract's last batch nonce for the token
+    pub last_batch_nonce: u64,
+    /// the contract's power threshold, the valid confirms need more power than this
+    pub power_threshold: u64,
+}
+
+impl BatchVerification {
+    /// Whether every confirm is valid and the batch can be submitted
+    pub fn passed(&self) -> bool {
+        self.call.is_ok() && self.confirms.iter().all(|check| check.valid)
+    }
+
+    pub fn view(&self) -> BatchVerificationView {
+        let batch = &self.batch;
+        let call = self.call.as_ref().ok();
+        BatchVerificationView {
+            token_contract: batch.token_contract,
+            nonce: batch.nonce,
+            transactions: batch.transactions.len(),
+            total_fee: batch.total_fee.amount.to_string(),
+            contract_last_batch_nonce: self.last_batch_nonce,
+            submitted: self.last_batch_nonce >= batch.nonce,
+            peggy_id: self.peggy_id.clone(),
+            valset_nonce: self.valset.nonce,
+            checkpoint: format!("0x{}", bytes_to_hex_str(&self.checkpoint.message)),
+            signed_hash: format!("0x{}", bytes_to_hex_str(&self.checkpoint.signed_hash)),
+            confirms: self.confirms.clone(),
+            valid_power: valid_power(&self.confirms),
+            required_power: self.power_threshold,
+            calldata: call.map(|call| format!("0x{}", bytes_to_hex_str(&call.payload))),
+            submitted_signatures: call.map(|call| {
+                call.signatures
+                    .iter()
+                    .filter(|sig| sig.v != 0u8.into())
+                    .count()
+            }),
+            error: self.call.as_ref().err().map(|e| format!("{:?}", e)),
+            passed: self.passed(),
+        }
+    }
+}
+
+/// What `verify-batch --output json` prints
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct BatchVerificationView {
+    pub token_contract: EthAddress,
+    pub nonce: u64,
+    pub transactions: usize,
+    pub total_fee: String,
+    pub contract_last_batch_nonce: u64,
+    /// whether the contract has executed this batch or a later one
+    pub submitted: bool,
+    pub peggy_id: String,
+    pub valset_nonce: u64,
+    pub checkpoint: String,
+    pub signed_hash: String,
+    pub confirms: Vec<ConfirmCheck>,
+    pub valid_power: u64,
+    /// the power the valid confirms must exceed
+    pub required_power: u64,
+    /// the submitBatch calldata, None if the batch can't be submitted
+    pub calldata: Option<String>,
+    pub submitted_signatures: Option<usize>,
+    /// why the batch can't be submitted
+    pub error: Option<String>,
+    pub passed: bool,
+}
+
+pub async fn verify_batch(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    web3: &Web3,
+    peggy_contract_address: EthAddress,
+    token_contract: EthAddress,
+    nonce: u64,
+    signatures: SignatureSelection,
+) -> Result<BatchVerification, PeggyError> {
+    let batch = get_transaction_batch(grpc_client, nonce, token_contract, None)
+        .await?
+        .ok_or_else(|| {
+            PeggyError::InvalidBridgeStateError(format!(
+                "The Hub has no batch {} for {}",
+                nonce, token_contract
+            ))
+        })?;
+    let confirms = get_transaction_batch_signatures(grpc_client, nonce, token_contract).await?;
+    let valset = find_latest_valset(
+        grpc_client,
+        peggy_contract_address,
+        peggy_contract_address,
+        web3,
+    )
+    .await?;
+    let peggy_id =
+        get_peggy_id_string(peggy_contract_address, peggy_contract_address, web3).await?;
+    let last_batch_nonce = get_tx_batch_nonce(
+        peggy_contract_address,
+        token_contract,
+        peggy_contract_address,
+        web3,
+    )
+    .await?;
+
+    let checkpoint = batch_checkpoint(&peggy_id, &batch);
+    let checks = check_confirms(&valset, &confirms, &checkpoint.signed_hash);
+    let encoding = get_signature_encoding(peggy_contract_address, web3).await?;
+    let power_threshold = get_power_threshold(peggy_contract_address, web3).await?;
+    let call = encode_submit_batch(
+        &valset,
+        &batch,
+        &confirms,
+        &peggy_id,
+        signatures,
+        power_threshold,
+        encoding,
+    );
+    Ok(BatchVerification {
+        batch,
+        valset,
+        peggy_id,
+        checkpoint,
+        confirms: checks,
+        call,
+        last_batch_nonce,
+        power_threshold,
+    })
+}
+
+pub fn format_verification(verification: &BatchVerification) -> String {
+    let batch = &verification.batch;
+    let mut out = String::new();
+    writeln!(out, "Batch {}:{}", batch.token_contract, batch.nonce).unwrap();
+    writeln!(
+        out,
+        "  transactions: {} total fee: {}",
+        batch.transactions.len(),
+        format_hub_amount(&batch.total_fee.amount)
+    )
+    .unwrap();
+    let status = if verification.last_batch_nonce >= batch.nonce {
+        "submitted or superseded"
+    } else {
+        "not submitted"
+    };
+    writeln!(
+        out,
+        "  contract last batch nonce: {} ({})",
+        verification.last_batch_nonce, status
+    )
+    .unwrap();
+    writeln!(out, "  peggy id: {}", verification.peggy_id).unwrap();
+    writeln!(out, "  valset nonce: {}", verification.valset.nonce).unwrap();
+    writeln!(
+        out,
+        "  checkpoint: 0x{}",
+        bytes_to_hex_str(&verification.checkpoint.message)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "  signed hash: 0x{}",
+        bytes_to_hex_str(&verification.checkpoint.signed_hash)
+    )
+    .unwrap();
+
+    writeln!(out, "Confirms").unwrap();
+    for check in verification.confirms.iter() {
+        let power = match check.power {
+            Some(power) => power.to_string(),
+            None => "not in valset".to_string(),
+        };
+        let valid = if check.valid { "valid" } else { "INVALID" };
+        writeln!(
+            out,
+            "  {} {} power {} {}",
+            check.orchestrator, check.eth_address, power, valid
+        )
+        .unwrap();
+    }
+    let power = valid_power(&verification.confirms);
+    writeln!(
+        out,
+        "  valid power {} of more than {} required",
+        power, verification.power_threshold
+    )
+    .unwrap();
+
+    match &verification.call {
+        Ok(call) => {
+            let submitted = call
+                .signatures
+                .iter()
+                .filter(|sig| sig.v != 0u8.into())
+                .count();
+            writeln!(
+                out,
+                "Calldata with {} of {} signatures",
+                submitted,
+                call.signatures.len()
+            )
+            .unwrap();
+            writeln!(out, "0x{}", bytes_to_hex_str(&call.payload)).unwrap();
+        }
+        Err(e) => writeln!(out, "Can't be submitted: {:?}", e).unwrap(),
+    }
+    out
+}
+
+#[test]
+fn test_check_confirms() {
+    use clarity::PrivateKey as EthPrivateKey;
+    use deep_space::private_key::PrivateKey as CosmosPrivateKey;
+    use ethereum_peggy::utils::get_signed_message_hash;
+    use peggy_utils::types::ValsetMember;
+
+    let orchestrator = CosmosPrivateKey::from_secret(b"verify batch")
+        .to_public_key()
+        .unwrap()
+        .to_address();
+    let member_key = EthPrivateKey::from_slice(&[1u8; 32]).unwrap();
+    let outsider_key = EthPrivateKey::from_slice(&[2u8; 32]).unwrap();
+    let member = member_key.to_public_key().unwrap();
+    let outsider = outsider_key.to_public_key().unwrap();
+    let valset = Valset {
+        nonce: 1,
+        members: vec![ValsetMember {
+            power: 100,
+            eth_address: Some(member),
+        }],
+    };
+    let message = b"checkpoint";
+    let signed_hash = get_signed_message_hash(message);
+    let confirm = |signer: EthAddress, key: &EthPrivateKey| BatchConfirmResponse {
+        nonce: 1,
+        orchestrator,
+        token_contract: member,
+        ethereum_signer: signer,
+        eth_signature: key.sign_ethereum_msg(message),
+    };
+
+    let checks = check_confirms(
+        &valset,
+        &[
+            confirm(member, &member_key),
+            confirm(outsider, &outsider_key),
+            // signed by the wrong key
+            confirm(member, &outsider_key),
+        ],
+        &signed_hash,
+    );
+    assert_eq!(
+        checks
+            .iter()
+            .map(|check| (check.power, check.valid))
+            .collect::<Vec<_>>(),
+        vec![(Some(100), true), (None, true), (Some(100), false)]
+    );
+    assert_eq!(valid_power(&checks), 100);
+}
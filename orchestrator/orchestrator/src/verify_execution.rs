@@ -0,0 +1,255 @@
+//! `orchestrator verify-execution` is an after the fact fraud check for auditors. It decodes a submitBatch
+//! transaction from Ethereum and compares what it paid out, transfer by transfer, with the batch the Hub
+//! created for that nonce. Any difference means the contract released funds the Hub never agreed to.
+//!
+//! The Hub deletes a batch once its execution is observed, so the comparison usually needs the Hub state
+//! from before that, `--hub-height` queries an archive node at an earlier block.
+
+use clarity::{Address as EthAddress, Uint256};
+use cosmos_peggy::query::get_transaction_batch;
+use deep_space::utils::hex_str_to_bytes;
+use ethereum_peggy::abi_decode::{decode_submit_batch, SubmitBatchArgs};
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::types::TransactionBatch;
+use std::fmt::Write;
+use tonic::transport::Channel;
+use web30::client::Web3;
+
+/// Parses a 0x prefixed transaction hash
+pub fn parse_tx_hash(input: &str) -> Result<Uint256, String> {
+    match hex_str_to_bytes(input.trim()) {
+        Ok(bytes) if bytes.len() == 32 => Ok(Uint256::from_bytes_be(&bytes)),
+        _ => Err(format!("Invalid transaction hash {}", input)),
+    }
+}
+
+/// Lists every way the executed call differs from the Hub's batch, empty if they match
+pub fn compare_execution(executed: &SubmitBatchArgs, batch: &TransactionBatch) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    if executed.token_contract != batch.token_contract {
+        mismatches.push(format!(
+            "token {} but the Hub batch is for {}",
+            executed.token_contract, batch.token_contract
+        ));
+    }
+    if executed.batch_nonce != batch.nonce {
+        mismatches.push(format!(
+            "nonce {} but the Hub batch has nonce {}",
+            executed.batch_nonce, batch.nonce
+        ));
+    }
+    if executed.amounts.len() != batch.transactions.len() {
+        mismatches.push(format!(
+            "{} transfers but the Hub batch has {}",
+            executed.amounts.len(),
+            batch.transactions.len()
+        ));
+    }
+    let executed_transfers = executed.amounts.iter().zip(executed.destinations.iter());
+    for (i, ((amount, destination), tx)) in executed_transfers
+        .zip(batch.transactions.iter())
+        .enumerate()
+    {
+        if *destination != tx.destination {
+            mismatches.push(format!(
+                "transfer {} paid {} but the Hub batch pays {}",
+                i, destination, tx.destination
+            ));
+        }
+        if *amount != tx.erc20_token.amount {
+            mismatches.push(format!(
+                "transfer {} paid {} but the Hub batch pays {}",
+                i, amount, tx.erc20_token.amount
+            ));
+        }
+    }
+    mismatches
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionVerification {
+    pub executed: SubmitBatchArgs,
+    pub block: Option<Uint256>,
+    /// the Hub's batch for the executed nonce, None if the Hub doesn't have it at the queried height
+    pub batch: Option<TransactionBatch>,
+    pub mismatches: Vec<String>,
+}
+
+impl ExecutionVerification {
+    /// Whether the execution was checked against the Hub and matched
+    pub fn matches(&self) -> bool {
+        self.batch.is_some() && self.mismatches.is_empty()
+    }
+
+    pub fn view(&self) -> ExecutionView {
+        let executed = &self.executed;
+        ExecutionView {
+            token_contract: executed.token_contract,
+            batch_nonce: executed.batch_nonce,
+            valset_nonce: executed.current_valset_nonce,
+            block: self.block.as_ref().map(|block| block.to_string()),
+            transfers: executed
+                .destinations
+                .iter()
+                .zip(executed.amounts.iter())
+                .map(|(destination, amount)| ExecutedTransfer {
+                    destination: *destination,
+                    amount: amount.to_string(),
+                })
+                .collect(),
+            verified: self.batch.is_some(),
+            mismatches: self.mismatches.clone(),
+            matches: self.matches(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ExecutedTransfer {
+    pub destination: EthAddress,
+    pub amount: String,
+}
+
+/// What `verify-execution --output json` prints
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct ExecutionView {
+    pub token_contract: EthAddress,
+    pub batch_nonce: u64,
+    pub valset_nonce: u64,
+    /// None while the transaction is pending
+    pub block: Option<String>,
+    pub transfers: Vec<ExecutedTransfer>,
+    /// false if the Hub no longer has the batch to compare with
+    pub verified: bool,
+    pub mismatches: Vec<String>,
+    pub matches: bool,
+}
+
+pub async fn verify_execution(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    web3: &Web3,
+    peggy_contract_address: EthAddress,
+    tx_hash: Uint256,
+    hub_height: Option<u64>,
+) -> Result<ExecutionVerification, PeggyError> {
+    let tx = web3
+        .eth_get_transaction_by_hash(tx_hash.clone())
+        .await?
+        .ok_or_else(|| {
+            PeggyError::InvalidBridgeStateError(format!("Transaction {:#066x} not found", tx_hash))
+        })?;
+    if tx.to != Some(peggy_contract_address) {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "Transaction {} is not a call to the Peggy contract {}",
+            explorer::tx(Chain::Ethereum, format!("{:#066x}", tx_hash)),
+            peggy_contract_address
+        )));
+    }
+    let executed = decode_submit_batch(&tx.input.0)?;
+    let batch = get_transaction_batch(
+        grpc_client,
+        executed.batch_nonce,
+        executed.token_contract,
+        hub_height,
+    )
+    .await?;
+    let mismatches = match batch.as_ref() {
+        Some(batch) => compare_execution(&executed, batch),
+        None => Vec::new(),
+    };
+    Ok(ExecutionVerification {
+        executed,
+        block: tx.block_number,
+        batch,
+        mismatches,
+    })
+}
+
+pub fn format_execution(verification: &ExecutionVerification) -> String {
+    let executed = &verification.executed;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "Executed batch {}:{} against valset {}",
+        executed.token_contract, executed.batch_nonce, executed.current_valset_nonce
+    )
+    .unwrap();
+    match verification.block.as_ref() {
+        Some(block) => writeln!(out, "  block: {}", block).unwrap(),
+        None => writeln!(out, "  block: pending").unwrap(),
+    }
+    for (amount, destination) in executed.amounts.iter().zip(executed.destinations.iter()) {
+        writeln!(out, "  {} {}", destination, amount).unwrap();
+    }
+    if verification.batch.is_none() {
+        writeln!(
+            out,
+            "UNVERIFIED: the Hub has no batch {}:{}, it is deleted once executed so query an archive node \
+             with --hub-height set to a block before the execution was observed",
+            executed.token_contract, executed.batch_nonce
+        )
+        .unwrap();
+    } else if verification.mismatches.is_empty() {
+        writeln!(out, "OK: matches the Hub batch").unwrap();
+    } else {
+        writeln!(out, "MISMATCH:").unwrap();
+        for mismatch in verification.mismatches.iter() {
+            writeln!(out, "  {}", mismatch).unwrap();
+        }
+    }
+    out
+}
+
+#[test]
+fn test_compare_execution() {
+    use peggy_utils::types::{BatchTransaction, ERC20Token};
+
+    let token = EthAddress::from_slice(&[0x44; 20]).unwrap();
+    let alice = EthAddress::from_slice(&[0x22; 20]).unwrap();
+    let mallory = EthAddress::from_slice(&[0x66; 20]).unwrap();
+    let transfer = |destination: EthAddress, amount: u64| BatchTransaction {
+        destination,
+        erc20_token: ERC20Token {
+            amount: amount.into(),
+            token_contract_address: token,
+        },
+        ..Default::default()
+    };
+    let batch = TransactionBatch {
+        nonce: 9,
+        transactions: vec![transfer(alice, 5)],
+        token_contract: token,
+        ..Default::default()
+    };
+    let executed = SubmitBatchArgs {
+        current_validators: Vec::new(),
+        current_valset_nonce: 3,
+        amounts: vec![5u64.into()],
+        destinations: vec![alice],
+        batch_nonce: 9,
+        token_contract: token,
+    };
+    assert!(compare_execution(&executed, &batch).is_empty());
+
+    let redirected = SubmitBatchArgs {
+        amounts: vec![500u64.into()],
+        destinations: vec![mallory],
+        ..executed.clone()
+    };
+    assert_eq!(compare_execution(&redirected, &batch).len(), 2);
+
+    let extra = SubmitBatchArgs {
+        amounts: vec![5u64.into(), 7u64.into()],
+        destinations: vec![alice, mallory],
+        ..executed
+    };
+    assert_eq!(compare_execution(&extra, &batch).len(), 1);
+
+    assert_eq!(
+        parse_tx_hash(&format!("0x{}", "ab".repeat(32))).unwrap(),
+        Uint256::from_bytes_be(&[0xab; 32])
+    );
+    assert!(parse_tx_hash("0xabcd").is_err());
+}
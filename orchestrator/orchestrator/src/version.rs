@@ -0,0 +1,90 @@
+//! Governance can publish the lowest orchestrator version validators should run in the `min_orchestrator_version`
+//! Hub param, ahead of an upgrade that changes what orchestrators sign. We compare our own version against it
+//! every loop, an outdated orchestrator always warns and with --enforce-min-version it also stops signing until
+//! it is upgraded, rather than submitting claims and confirms the rest of the validator set disagrees with.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// Parses major.minor.patch, pre release and build suffixes like 1.2.3-rc1 are ignored
+    pub fn parse(input: &str) -> Result<Version, String> {
+        let core = input
+            .trim()
+            .trim_start_matches('v')
+            .split(|c| c == '-' || c == '+')
+            .next()
+            .unwrap_or_default();
+        let parts: Vec<&str> = core.split('.').collect();
+        if parts.len() != 3 {
+            return Err(format!("Version {} is not major.minor.patch", input));
+        }
+        let mut numbers = [0u64; 3];
+        for (number, part) in numbers.iter_mut().zip(parts) {
+            *number = part
+                .parse()
+                .map_err(|_| format!("Version {} is not major.minor.patch", input))?;
+        }
+        Ok(Version {
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers[2],
+        })
+    }
+
+    /// The version of this orchestrator binary
+    pub fn ours() -> Version {
+        Version::parse(env!("CARGO_PKG_VERSION")).expect("Invalid crate version")
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The minimum version from the Hub param, which is empty when governance hasn't set one
+pub fn parse_min_version(param: &str) -> Option<Version> {
+    if param.is_empty() {
+        return None;
+    }
+    match Version::parse(param) {
+        Ok(version) => Some(version),
+        Err(e) => {
+            // the Hub validates the param, so this is a newer format we don't understand
+            warn!("Ignoring the Hub min orchestrator version: {}", e);
+            None
+        }
+    }
+}
+
+#[test]
+fn test_version() {
+    let v = |s: &str| Version::parse(s).unwrap();
+    assert_eq!(
+        v("1.2.3"),
+        Version {
+            major: 1,
+            minor: 2,
+            patch: 3
+        }
+    );
+    assert_eq!(v("v1.2.3-rc1"), v("1.2.3"));
+    assert!(v("0.10.0") > v("0.9.9"));
+    assert!(v("1.0.0") > v("0.99.99"));
+    assert!(v("0.1.1") > v("0.1.0"));
+    assert!(Version::parse("1.2").is_err());
+    assert!(Version::parse("1.x.3").is_err());
+    assert_eq!(v("1.2.3").to_string(), "1.2.3");
+
+    assert_eq!(parse_min_version(""), None);
+    assert_eq!(parse_min_version("0.2.0"), Some(v("0.2.0")));
+    assert!(Version::ours() >= v("0.1.0"));
+}
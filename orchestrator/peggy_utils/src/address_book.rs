@@ -0,0 +1,161 @@
+//! An address book for the CLIs, so destinations can be given by name instead of pasted. The book is a json
+//! file of `{"<label>": {"chain": "ethereum" | "hub" | "minter", "address": "<address>"}}`, every address is
+//! checked against its chain when the book is loaded. Wherever a command takes an address it also takes a
+//! label, which must be for the chain the command needs, so a Hub label can never end up as an Ethereum
+//! destination.
+
+use clarity::Address as EthAddress;
+use deep_space::address::Address as CosmosAddress;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressChain {
+    Ethereum,
+    Hub,
+    Minter,
+}
+
+impl fmt::Display for AddressChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressChain::Ethereum => write!(f, "Ethereum"),
+            AddressChain::Hub => write!(f, "Hub"),
+            AddressChain::Minter => write!(f, "Minter"),
+        }
+    }
+}
+
+impl AddressChain {
+    /// Checks that `address` is an address of this chain
+    fn check(self, address: &str) -> Result<(), String> {
+        let valid = match self {
+            // both parse from bare hex too, only the prefixed forms tell the chains apart
+            AddressChain::Ethereum => {
+                address.starts_with("0x") && address.parse::<EthAddress>().is_ok()
+            }
+            AddressChain::Hub => {
+                address.starts_with("hub1") && address.parse::<CosmosAddress>().is_ok()
+            }
+            AddressChain::Minter => is_minter_address(address),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("{} is not a {} address", address, self))
+        }
+    }
+}
+
+/// Minter addresses are `Mx` followed by 20 hex encoded bytes
+fn is_minter_address(address: &str) -> bool {
+    address.len() == 42
+        && address.starts_with("Mx")
+        && address[2..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[derive(Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct AddressEntry {
+    pub chain: AddressChain,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct AddressBook(HashMap<String, AddressEntry>);
+
+impl AddressBook {
+    pub fn new(entries: HashMap<String, AddressEntry>) -> Result<Self, String> {
+        for (label, entry) in entries.iter() {
+            entry
+                .chain
+                .check(&entry.address)
+                .map_err(|e| format!("Address book entry {}: {}", label, e))?;
+        }
+        Ok(AddressBook(entries))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let entries = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        AddressBook::new(entries)
+    }
+
+    /// The address behind `input` if it's a label, else `input` itself, checked against `chain` either way
+    pub fn resolve(&self, input: &str, chain: AddressChain) -> Result<String, String> {
+        let input = input.trim();
+        match self.0.get(input) {
+            Some(entry) if entry.chain != chain => Err(format!(
+                "{} is a {} address, a {} address is needed here",
+                input, entry.chain, chain
+            )),
+            Some(entry) => Ok(entry.address.clone()),
+            None => {
+                chain.check(input)?;
+                Ok(input.to_string())
+            }
+        }
+    }
+
+    pub fn ethereum(&self, input: &str) -> Result<EthAddress, String> {
+        let address = self.resolve(input, AddressChain::Ethereum)?;
+        address
+            .parse()
+            .map_err(|e| format!("Invalid Ethereum address {}: {}", address, e))
+    }
+
+    pub fn hub(&self, input: &str) -> Result<CosmosAddress, String> {
+        let address = self.resolve(input, AddressChain::Hub)?;
+        address
+            .parse()
+            .map_err(|e| format!("Invalid Hub address {}: {:?}", address, e))
+    }
+
+    pub fn minter(&self, input: &str) -> Result<String, String> {
+        self.resolve(input, AddressChain::Minter)
+    }
+}
+
+#[test]
+fn test_address_book() {
+    let hub = CosmosAddress::from_bytes([7; 20]).to_string();
+    let entries: HashMap<String, AddressEntry> = serde_json::from_str(&format!(
+        r#"{{
+            "exchange-hot-wallet": {{"chain": "ethereum", "address": "0x0707070707070707070707070707070707070707"}},
+            "treasury": {{"chain": "hub", "address": "{}"}},
+            "minter-wallet": {{"chain": "minter", "address": "Mx0707070707070707070707070707070707070707"}}
+        }}"#,
+        hub
+    ))
+    .unwrap();
+    let book = AddressBook::new(entries).unwrap();
+
+    assert_eq!(
+        book.ethereum("exchange-hot-wallet").unwrap(),
+        EthAddress::from_slice(&[7; 20]).unwrap()
+    );
+    assert_eq!(book.hub(" treasury ").unwrap().to_string(), hub);
+    assert_eq!(
+        book.minter("minter-wallet").unwrap(),
+        "Mx0707070707070707070707070707070707070707"
+    );
+    // a label of another chain is refused
+    assert!(book.ethereum("treasury").is_err());
+    assert!(book.hub("minter-wallet").is_err());
+    // plain addresses pass through, anything else is refused
+    assert!(book
+        .ethereum("0x0101010101010101010101010101010101010101")
+        .is_ok());
+    assert!(book.ethereum("exchange-cold-wallet").is_err());
+    assert!(book.minter("Mx01").is_err());
+
+    let mismatched: HashMap<String, AddressEntry> = serde_json::from_str(
+        r#"{"typo": {"chain": "hub", "address": "0x0707070707070707070707070707070707070707"}}"#,
+    )
+    .unwrap();
+    assert!(AddressBook::new(mismatched).is_err());
+}
@@ -0,0 +1,126 @@
+//! Formatting of token amounts. Amounts are integers of base units, wei for ether and the smallest unit of
+//! an ERC20, and at 18 decimals raw numbers are close to unreadable. Everything here works on the integers
+//! themselves, an amount never passes through a float and is never rounded. The separators are fixed, `.`
+//! before the decimals and `,` between thousands, whatever the locale, so output reads the same on every
+//! machine and scripts can parse it.
+//!
+//! `format_decimal` is the plain form for config and machines, `format_amount` groups the whole part by
+//! thousands for people.
+
+use num256::Uint256;
+
+pub fn pow10(exponent: u64) -> Uint256 {
+    let mut out: Uint256 = 1u8.into();
+    for _ in 0..exponent {
+        out = out * Uint256::from(10u8);
+    }
+    out
+}
+
+/// Parses a decimal string like `1.25` into an integer of `decimals` decimals, extra decimals are cut off
+pub fn parse_decimal(input: &str, decimals: usize) -> Option<Uint256> {
+    let input = input.trim();
+    let (whole, fraction) = match input.find('.') {
+        Some(i) => (&input[..i], &input[i + 1..]),
+        None => (input, ""),
+    };
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(decimals)
+        .collect();
+    let mut out: Uint256 = 0u8.into();
+    for c in whole.chars().chain(fraction.chars()) {
+        let digit = c.to_digit(10)?;
+        out = out * Uint256::from(10u8) + Uint256::from(digit);
+    }
+    Some(out)
+}
+
+/// Splits an integer of `decimals` decimals into its whole part and its decimals without trailing zeros
+fn split_decimal(value: &Uint256, decimals: usize) -> (String, String) {
+    let padded = format!("{:0>width$}", value.to_string(), width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    (
+        whole.to_string(),
+        fraction.trim_end_matches('0').to_string(),
+    )
+}
+
+/// Formats an integer of `decimals` decimals as a decimal string
+pub fn format_decimal(value: &Uint256, decimals: usize) -> String {
+    match split_decimal(value, decimals) {
+        (whole, fraction) if fraction.is_empty() => whole,
+        (whole, fraction) => format!("{}.{}", whole, fraction),
+    }
+}
+
+/// Formats an integer of `decimals` decimals for people, like `1,234,567.89`
+pub fn format_amount(value: &Uint256, decimals: usize) -> String {
+    let (whole, fraction) = split_decimal(value, decimals);
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if fraction.is_empty() {
+        grouped
+    } else {
+        format!("{}.{}", grouped, fraction)
+    }
+}
+
+/// Formats an amount of a token for people, in base units if its decimals aren't known
+pub fn format_token_amount(value: &Uint256, decimals: Option<u64>) -> String {
+    match decimals {
+        Some(decimals) => format_amount(value, decimals as usize),
+        None => format!("{} base units", format_amount(value, 0)),
+    }
+}
+
+#[test]
+fn test_format_amount() {
+    let amount = |s: &str| s.parse::<Uint256>().unwrap();
+
+    assert_eq!(pow10(3), amount("1000"));
+    assert_eq!(parse_decimal("1.25", 2), Some(amount("125")));
+    assert_eq!(parse_decimal("1.255", 2), Some(amount("125")));
+    assert_eq!(parse_decimal(".5", 1), Some(amount("5")));
+    assert_eq!(parse_decimal("", 2), None);
+    assert_eq!(parse_decimal("abc", 2), None);
+    assert_eq!(parse_decimal("1,000", 0), None);
+
+    assert_eq!(format_decimal(&amount("5"), 3), "0.005");
+    assert_eq!(format_decimal(&amount("1500"), 3), "1.5");
+    assert_eq!(format_decimal(&amount("1234567"), 0), "1234567");
+
+    assert_eq!(format_amount(&amount("0"), 18), "0");
+    assert_eq!(format_amount(&amount("999"), 0), "999");
+    assert_eq!(format_amount(&amount("1000"), 0), "1,000");
+    assert_eq!(format_amount(&amount("123456789"), 2), "1,234,567.89");
+    // a whole uint256 of wei, far past what a float keeps exact
+    assert_eq!(
+        format_amount(
+            &amount(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            ),
+            18
+        ),
+        "115,792,089,237,316,195,423,570,985,008,687,907,853,269,984,665,640,564,039,457.584007913129639935"
+    );
+    assert_eq!(
+        format_amount(&amount("1000000000000000001"), 18),
+        "1.000000000000000001"
+    );
+
+    assert_eq!(format_token_amount(&amount("2500000"), Some(6)), "2.5");
+    assert_eq!(
+        format_token_amount(&amount("2500000"), None),
+        "2,500,000 base units"
+    );
+}
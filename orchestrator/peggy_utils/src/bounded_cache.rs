@@ -0,0 +1,179 @@
+//! A size bounded cache shared by everything that memoizes data across loop iterations. Relayers and
+//! orchestrators run for months, so an unbounded map that gains an entry per valset, batch or token slowly
+//! grows until the process is killed for running out of memory. Every cache here holds at most `capacity`
+//! entries and evicts the least recently used one to make room, with hit, miss and eviction counts kept so
+//! the caches can be sized from what the logs show.
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::ops::Add;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// entries dropped to make room for new ones
+    pub evictions: u64,
+    /// entries currently held
+    pub len: usize,
+    pub capacity: usize,
+}
+
+impl CacheStats {
+    /// The share of lookups served from the cache, in percent
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64 * 100.0,
+        }
+    }
+}
+
+/// Combines the stats of several caches reported as one
+impl Add for CacheStats {
+    type Output = CacheStats;
+
+    fn add(self, other: CacheStats) -> CacheStats {
+        CacheStats {
+            hits: self.hits + other.hits,
+            misses: self.misses + other.misses,
+            evictions: self.evictions + other.evictions,
+            len: self.len + other.len,
+            capacity: self.capacity + other.capacity,
+        }
+    }
+}
+
+struct Inner<K, V> {
+    /// the value and the tick it was last used at
+    entries: HashMap<K, (V, u64)>,
+    /// keys by the tick they were last used at, the first is the least recently used
+    recency: BTreeMap<u64, K>,
+    tick: u64,
+    stats: CacheStats,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> Inner<K, V> {
+    fn touch(&mut self, key: &K) -> Option<V> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (value, used) = self.entries.get_mut(key)?;
+        self.recency.remove(used);
+        *used = tick;
+        self.recency.insert(tick, key.clone());
+        Some(value.clone())
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(self.tick, key);
+        while self.entries.len() > self.stats.capacity {
+            let oldest = *self.recency.keys().next().unwrap();
+            let key = self.recency.remove(&oldest).unwrap();
+            self.entries.remove(&key);
+            self.stats.evictions += 1;
+        }
+        self.stats.len = self.entries.len();
+    }
+}
+
+/// A thread safe cache of at most `capacity` entries with least recently used eviction
+pub struct BoundedCache<K, V> {
+    inner: Mutex<Inner<K, V>>,
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> BoundedCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "a cache needs room for at least one entry");
+        BoundedCache {
+            inner: Mutex::new(Inner {
+                entries: HashMap::new(),
+                recency: BTreeMap::new(),
+                tick: 0,
+                stats: CacheStats {
+                    capacity,
+                    ..Default::default()
+                },
+            }),
+        }
+    }
+
+    /// The cached value of `key`, counted as a hit or a miss
+    pub fn get(&self, key: &K) -> Option<V> {
+        let mut inner = self.inner.lock().unwrap();
+        let value = inner.touch(key);
+        match value {
+            Some(_) => inner.stats.hits += 1,
+            None => inner.stats.misses += 1,
+        }
+        value
+    }
+
+    /// Caches `value` under `key`, evicting the least recently used entry when full
+    pub fn insert(&self, key: K, value: V) {
+        self.inner.lock().unwrap().insert(key, value);
+    }
+
+    /// The cached value of `key`, computing and caching it on a miss. `compute` runs with the cache locked so
+    /// it must not use the cache itself.
+    pub fn get_or_insert_with(&self, key: K, compute: impl FnOnce() -> V) -> V {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(value) = inner.touch(&key) {
+            inner.stats.hits += 1;
+            return value;
+        }
+        inner.stats.misses += 1;
+        let value = compute();
+        inner.insert(key, value.clone());
+        value
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.inner.lock().unwrap().stats
+    }
+}
+
+#[test]
+fn test_bounded_cache() {
+    let cache = BoundedCache::new(2);
+    assert!(cache.is_empty());
+    assert_eq!(cache.get_or_insert_with(1, || "one"), "one");
+    assert_eq!(cache.get_or_insert_with(1, || "uno"), "one");
+    cache.insert(2, "two");
+    // 1 was used last so 2 is the one to go
+    assert_eq!(cache.get(&1), Some("one"));
+    cache.insert(3, "three");
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get(&2), None);
+    assert_eq!(cache.get(&1), Some("one"));
+    assert_eq!(cache.get(&3), Some("three"));
+
+    // replacing a value is not an eviction
+    cache.insert(3, "drei");
+    assert_eq!(cache.get(&3), Some("drei"));
+    assert_eq!(
+        cache.stats(),
+        CacheStats {
+            hits: 5,
+            misses: 2,
+            evictions: 1,
+            len: 2,
+            capacity: 2,
+        }
+    );
+    assert!((cache.stats().hit_rate() - 500.0 / 7.0).abs() < 1e-9);
+    assert_eq!(CacheStats::default().hit_rate(), 0.0);
+    assert_eq!((cache.stats() + cache.stats()).capacity, 4);
+}
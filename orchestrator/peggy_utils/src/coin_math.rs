@@ -0,0 +1,208 @@
+//! Checked arithmetic for amounts that carry their denom, Cosmos `Coin`s and `ERC20Token`s. Adding or
+//! comparing amounts of different denoms is always a bug, so every operation checks the denoms and errors
+//! on a mismatch instead of silently mixing up tokens.
+
+use crate::error::PeggyError;
+use crate::types::ERC20Token;
+use clarity::Address as EthAddress;
+use deep_space::coin::Coin;
+use num256::Uint256;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CoinMathError {
+    /// the two amounts are of different denoms
+    DenomMismatch(String, String),
+    /// subtracting would go below zero
+    Underflow,
+    /// adding would go above the largest uint256
+    Overflow,
+    /// the ratio of an amount to zero
+    DivisionByZero,
+}
+
+impl Display for CoinMathError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoinMathError::DenomMismatch(a, b) => write!(f, "Denom mismatch {} and {}", a, b),
+            CoinMathError::Underflow => write!(f, "Coin amount underflow"),
+            CoinMathError::Overflow => write!(f, "Coin amount overflow"),
+            CoinMathError::DivisionByZero => write!(f, "Ratio to a zero amount"),
+        }
+    }
+}
+
+impl std::error::Error for CoinMathError {}
+
+impl From<CoinMathError> for PeggyError {
+    fn from(error: CoinMathError) -> Self {
+        PeggyError::InvalidBridgeStateError(error.to_string())
+    }
+}
+
+lazy_static! {
+    static ref MAX_UINT256: Uint256 =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            .parse()
+            .unwrap();
+}
+
+/// The largest amount a contract or the Hub can hold, Uint256 arithmetic past it panics
+pub fn max_uint256() -> Uint256 {
+    MAX_UINT256.clone()
+}
+
+/// An amount of one denom
+pub trait CoinMath: Sized + Clone {
+    type Denom: Eq + Display;
+
+    fn denom(&self) -> &Self::Denom;
+    fn amount(&self) -> &Uint256;
+    fn with_amount(&self, amount: Uint256) -> Self;
+
+    fn same_denom(&self, other: &Self) -> Result<(), CoinMathError> {
+        if self.denom() == other.denom() {
+            Ok(())
+        } else {
+            Err(CoinMathError::DenomMismatch(
+                self.denom().to_string(),
+                other.denom().to_string(),
+            ))
+        }
+    }
+
+    fn checked_add(&self, other: &Self) -> Result<Self, CoinMathError> {
+        self.same_denom(other)?;
+        let (a, b) = (self.amount(), other.amount());
+        if *a > *MAX_UINT256 || *b > *MAX_UINT256 || MAX_UINT256.clone() - a.clone() < *b {
+            return Err(CoinMathError::Overflow);
+        }
+        Ok(self.with_amount(a.clone() + b.clone()))
+    }
+
+    fn checked_sub(&self, other: &Self) -> Result<Self, CoinMathError> {
+        self.same_denom(other)?;
+        if other.amount() > self.amount() {
+            return Err(CoinMathError::Underflow);
+        }
+        Ok(self.with_amount(self.amount().clone() - other.amount().clone()))
+    }
+
+    /// This amount divided by the other, for example the share of a fee in a batch's total fee
+    fn ratio(&self, other: &Self) -> Result<f64, CoinMathError> {
+        self.same_denom(other)?;
+        if *other.amount() == Uint256::from(0u8) {
+            return Err(CoinMathError::DivisionByZero);
+        }
+        Ok(to_f64(self.amount()) / to_f64(other.amount()))
+    }
+
+    /// True if this amount is at most `budget`, for example if a fee can be paid out of a balance
+    fn fits_in(&self, budget: &Self) -> Result<bool, CoinMathError> {
+        self.same_denom(budget)?;
+        Ok(self.amount() <= budget.amount())
+    }
+
+    /// The total of some amounts of one denom, None if there are none
+    fn checked_sum<'a, I>(coins: I) -> Result<Option<Self>, CoinMathError>
+    where
+        Self: 'a,
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let mut total: Option<Self> = None;
+        for coin in coins {
+            total = Some(match total {
+                Some(total) => total.checked_add(coin)?,
+                None => coin.clone(),
+            });
+        }
+        Ok(total)
+    }
+}
+
+fn to_f64(amount: &Uint256) -> f64 {
+    amount.to_string().parse().unwrap_or(f64::MAX)
+}
+
+impl CoinMath for Coin {
+    type Denom = String;
+
+    fn denom(&self) -> &String {
+        &self.denom
+    }
+
+    fn amount(&self) -> &Uint256 {
+        &self.amount
+    }
+
+    fn with_amount(&self, amount: Uint256) -> Self {
+        Coin {
+            amount,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+impl CoinMath for ERC20Token {
+    type Denom = EthAddress;
+
+    fn denom(&self) -> &EthAddress {
+        &self.token_contract_address
+    }
+
+    fn amount(&self) -> &Uint256 {
+        &self.amount
+    }
+
+    fn with_amount(&self, amount: Uint256) -> Self {
+        ERC20Token {
+            amount,
+            token_contract_address: self.token_contract_address,
+        }
+    }
+}
+
+#[test]
+fn test_coin_math() {
+    let hub = |amount: u64| Coin {
+        amount: amount.into(),
+        denom: "hub".to_string(),
+    };
+    let other = Coin {
+        amount: 1u64.into(),
+        denom: "peggy/0x00".to_string(),
+    };
+
+    assert_eq!(hub(2).checked_add(&hub(3)), Ok(hub(5)));
+    assert_eq!(hub(5).checked_sub(&hub(3)), Ok(hub(2)));
+    assert_eq!(hub(3).checked_sub(&hub(5)), Err(CoinMathError::Underflow));
+    let max = Coin {
+        amount: max_uint256(),
+        denom: "hub".to_string(),
+    };
+    assert_eq!(max.checked_add(&hub(0)), Ok(max.clone()));
+    assert_eq!(max.checked_add(&hub(1)), Err(CoinMathError::Overflow));
+    assert!((hub(1).ratio(&hub(4)).unwrap() - 0.25).abs() < f64::EPSILON);
+    assert_eq!(hub(1).ratio(&hub(0)), Err(CoinMathError::DivisionByZero));
+    assert_eq!(hub(1).fits_in(&hub(1)), Ok(true));
+    assert_eq!(hub(2).fits_in(&hub(1)), Ok(false));
+    assert_eq!(
+        hub(1).checked_add(&other),
+        Err(CoinMathError::DenomMismatch(
+            "hub".to_string(),
+            "peggy/0x00".to_string()
+        ))
+    );
+    assert!(hub(1).fits_in(&other).is_err());
+
+    let token = |byte: u8, amount: u64| ERC20Token {
+        amount: amount.into(),
+        token_contract_address: EthAddress::from_slice(&[byte; 20]).unwrap(),
+    };
+    assert_eq!(
+        ERC20Token::checked_sum(&[token(1, 1), token(1, 2)]),
+        Ok(Some(token(1, 3)))
+    );
+    assert!(ERC20Token::checked_sum(&[token(1, 1), token(2, 2)]).is_err());
+    assert_eq!(ERC20Token::checked_sum(&[]), Ok(None));
+}
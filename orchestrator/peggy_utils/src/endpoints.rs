@@ -0,0 +1,216 @@
+//! Picking between several Ethereum RPC endpoints. Every endpoint is probed every `PROBE_SPEED` for its
+//! response time and latest block. Time critical work, scanning for new events and submitting transactions,
+//! goes to the fastest endpoint that isn't lagging behind the others. Bulk backfill, rescanning old blocks,
+//! goes to the fastest of the endpoints configured for backfill, usually cheaper or rate limited ones we don't
+//! want on the critical path, and to the time critical choice when there are none.
+//!
+//! An endpoint that fails a probe is avoided until it answers again. Before the first probe, and when every
+//! endpoint is failing, the first configured endpoint is used.
+
+use num256::Uint256;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+use web30::client::Web3;
+
+/// How often every endpoint is probed
+pub const PROBE_SPEED: Duration = Duration::from_secs(30);
+/// An endpoint this many blocks behind the latest block any endpoint reported is stale
+pub const MAX_BLOCK_LAG: u64 = 3;
+/// How much of a new latency sample goes into the average
+const LATENCY_WEIGHT: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Purpose {
+    /// event scanning near the chain head and submitting transactions
+    TimeCritical,
+    /// rescanning old blocks
+    Backfill,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Endpoint {
+    url: String,
+    backfill: bool,
+    /// moving average of the probe response time in seconds
+    latency: Option<f64>,
+    latest_block: Option<u64>,
+    failing: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+    endpoints: Arc<RwLock<Vec<Endpoint>>>,
+    timeout: Duration,
+}
+
+/// Splits a comma separated list of urls
+pub fn parse_urls(urls: &str) -> Vec<String> {
+    urls.split(',')
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+impl Endpoints {
+    /// `urls` serve everything, `backfill_urls` only backfill. `timeout` is the request timeout of the
+    /// `Web3` clients handed out
+    pub fn new(urls: &[String], backfill_urls: &[String], timeout: Duration) -> Self {
+        assert!(!urls.is_empty(), "At least one Ethereum RPC url is needed");
+        let endpoint = |url: &String, backfill: bool| Endpoint {
+            url: url.clone(),
+            backfill,
+            latency: None,
+            latest_block: None,
+            failing: false,
+        };
+        let endpoints = urls
+            .iter()
+            .map(|url| endpoint(url, false))
+            .chain(backfill_urls.iter().map(|url| endpoint(url, true)))
+            .collect();
+        Endpoints {
+            endpoints: Arc::new(RwLock::new(endpoints)),
+            timeout,
+        }
+    }
+
+    pub fn urls(&self) -> Vec<String> {
+        let endpoints = self.endpoints.read().unwrap();
+        endpoints.iter().map(|e| e.url.clone()).collect()
+    }
+
+    /// The latest block any endpoint reported
+    pub fn latest_block(&self) -> Option<u64> {
+        let endpoints = self.endpoints.read().unwrap();
+        endpoints.iter().filter_map(|e| e.latest_block).max()
+    }
+
+    pub fn record(&self, url: &str, latency: Duration, latest_block: u64) {
+        let mut endpoints = self.endpoints.write().unwrap();
+        if let Some(endpoint) = endpoints.iter_mut().find(|e| e.url == url) {
+            let sample = latency.as_secs_f64();
+            endpoint.latency = Some(match endpoint.latency {
+                Some(average) => average * (1.0 - LATENCY_WEIGHT) + sample * LATENCY_WEIGHT,
+                None => sample,
+            });
+            endpoint.latest_block = Some(latest_block);
+            endpoint.failing = false;
+        }
+    }
+
+    pub fn record_failure(&self, url: &str) {
+        let mut endpoints = self.endpoints.write().unwrap();
+        if let Some(endpoint) = endpoints.iter_mut().find(|e| e.url == url) {
+            endpoint.failing = true;
+        }
+    }
+
+    /// The url to use for `purpose`
+    pub fn select(&self, purpose: Purpose) -> String {
+        let endpoints = self.endpoints.read().unwrap();
+        let latest = endpoints.iter().filter_map(|e| e.latest_block).max();
+        let fresh = |e: &Endpoint| match (e.latest_block, latest) {
+            (Some(block), Some(latest)) => block + MAX_BLOCK_LAG >= latest,
+            _ => false,
+        };
+        // the probed endpoints by latency
+        let fastest = |backfill: bool, fresh_only: bool| {
+            endpoints
+                .iter()
+                .filter(|e| e.backfill == backfill && !e.failing && e.latency.is_some())
+                .filter(|e| !fresh_only || fresh(e))
+                .min_by(|a, b| a.latency.partial_cmp(&b.latency).unwrap())
+                .map(|e| e.url.clone())
+        };
+        let time_critical = || {
+            fastest(false, true)
+                .or_else(|| fastest(false, false))
+                .unwrap_or_else(|| endpoints[0].url.clone())
+        };
+        match purpose {
+            Purpose::TimeCritical => time_critical(),
+            // old blocks don't need the chain head, only an endpoint that answers
+            Purpose::Backfill => fastest(true, false).unwrap_or_else(time_critical),
+        }
+    }
+
+    pub fn web3(&self, purpose: Purpose) -> Web3 {
+        self.web3_with_timeout(purpose, self.timeout)
+    }
+
+    pub fn web3_with_timeout(&self, purpose: Purpose, timeout: Duration) -> Web3 {
+        Web3::new(&self.select(purpose), timeout)
+    }
+
+    /// Probes every endpoint once
+    pub async fn probe(&self) {
+        for url in self.urls() {
+            let web3 = Web3::new(&url, self.timeout);
+            let start = Instant::now();
+            match web3.eth_block_number().await {
+                Ok(block) => self.record(&url, start.elapsed(), to_block(&block)),
+                Err(e) => {
+                    warn!("Ethereum RPC {} failed its probe {:?}", url, e);
+                    self.record_failure(&url);
+                }
+            }
+        }
+        trace!(
+            "Ethereum RPC probe done, using {} for time critical work and {} for backfill",
+            self.select(Purpose::TimeCritical),
+            self.select(Purpose::Backfill)
+        );
+    }
+}
+
+fn to_block(block: &Uint256) -> u64 {
+    block.to_string().parse().unwrap_or(u64::MAX)
+}
+
+/// Probes the endpoints until the process stops, does nothing with a single endpoint
+pub async fn probe_loop(endpoints: Endpoints) {
+    if endpoints.urls().len() < 2 {
+        return;
+    }
+    loop {
+        endpoints.probe().await;
+        delay_for(PROBE_SPEED).await;
+    }
+}
+
+#[test]
+fn test_endpoint_selection() {
+    let urls = parse_urls("http://fast/, http://slow,");
+    assert_eq!(urls, vec!["http://fast", "http://slow"]);
+    let endpoints = Endpoints::new(
+        &urls,
+        &["http://archive".to_string()],
+        Duration::from_secs(10),
+    );
+    // before any probe the first endpoint serves everything
+    assert_eq!(endpoints.select(Purpose::TimeCritical), "http://fast");
+    assert_eq!(endpoints.select(Purpose::Backfill), "http://fast");
+
+    endpoints.record("http://fast", Duration::from_millis(50), 100);
+    endpoints.record("http://slow", Duration::from_millis(400), 100);
+    endpoints.record("http://archive", Duration::from_millis(900), 90);
+    assert_eq!(endpoints.select(Purpose::TimeCritical), "http://fast");
+    // backfill goes to the backfill endpoint even though it is slower and behind
+    assert_eq!(endpoints.select(Purpose::Backfill), "http://archive");
+    assert_eq!(endpoints.latest_block(), Some(100));
+
+    // the fast endpoint falls behind, the slow one is preferred until it catches up
+    endpoints.record("http://slow", Duration::from_millis(400), 110);
+    assert_eq!(endpoints.select(Purpose::TimeCritical), "http://slow");
+    endpoints.record("http://fast", Duration::from_millis(50), 109);
+    assert_eq!(endpoints.select(Purpose::TimeCritical), "http://fast");
+
+    // failing endpoints are avoided
+    endpoints.record_failure("http://fast");
+    endpoints.record_failure("http://archive");
+    assert_eq!(endpoints.select(Purpose::TimeCritical), "http://slow");
+    assert_eq!(endpoints.select(Purpose::Backfill), "http://slow");
+    endpoints.record_failure("http://slow");
+    assert_eq!(endpoints.select(Purpose::TimeCritical), "http://fast");
+}
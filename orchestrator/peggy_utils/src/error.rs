@@ -25,6 +25,100 @@ pub enum PeggyError {
     InvalidEventLogError(String),
     CosmosgRPCError(Status),
     InsufficientVotingPowerToPass(String),
+    CosmosTxRejected(TxRejection),
+    StateStoreError(String),
+    SecretError(String),
+}
+
+/// The reasons the Peggy module may reject a claim or confirm that we need to handle
+/// differently, everything we don't know how to react to ends up in Other
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TxRejection {
+    /// the event nonce we submitted is not the next one the chain expects from us
+    NonContiguousEventNonce(String),
+    /// we have already submitted this claim or signature
+    Duplicate(String),
+    /// our validator is unknown or not in the active set
+    NotValidator(String),
+    /// the bridge is paused by governance
+    ServiceStopped(String),
+    /// governance halted the bridge at an event nonce below the one we claimed
+    BridgeHalted(String),
+    /// our account sequence is out of date, usually a concurrent tx from the same key
+    SequenceMismatch(String),
+    Other {
+        codespace: String,
+        code: u32,
+        raw_log: String,
+    },
+}
+
+/// What the orchestrator should do about a given rejection
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RejectionAction {
+    /// look up our last event nonce again and rescan from there
+    Resync,
+    /// the work is already done, move on
+    Skip,
+    /// nothing we send will be accepted, stop submitting
+    Halt,
+    /// transient, try again next loop
+    Retry,
+}
+
+impl TxRejection {
+    /// Parses the codespace, code and raw log of a failed Cosmos tx, see
+    /// x/peggy/types/errors.go and the Cosmos SDK's types/errors for the codes
+    pub fn from_tx_result(codespace: &str, code: u32, raw_log: &str) -> Self {
+        let raw_log = raw_log.to_string();
+        match (codespace, code) {
+            ("peggy", 9) => TxRejection::NonContiguousEventNonce(raw_log),
+            ("peggy", 2) => TxRejection::Duplicate(raw_log),
+            ("peggy", 10) | ("minter", 10) => TxRejection::ServiceStopped(raw_log),
+            ("peggy", 11) => TxRejection::BridgeHalted(raw_log),
+            ("peggy", 5) | ("staking", 4) | ("sdk", 24) if raw_log.contains("validator") => {
+                TxRejection::NotValidator(raw_log)
+            }
+            ("sdk", 3) => TxRejection::SequenceMismatch(raw_log),
+            _ => TxRejection::Other {
+                codespace: codespace.to_string(),
+                code,
+                raw_log,
+            },
+        }
+    }
+
+    pub fn action(&self) -> RejectionAction {
+        match self {
+            TxRejection::NonContiguousEventNonce(_) => RejectionAction::Resync,
+            TxRejection::Duplicate(_) => RejectionAction::Skip,
+            TxRejection::NotValidator(_) => RejectionAction::Halt,
+            TxRejection::ServiceStopped(_)
+            | TxRejection::BridgeHalted(_)
+            | TxRejection::SequenceMismatch(_)
+            | TxRejection::Other { .. } => RejectionAction::Retry,
+        }
+    }
+}
+
+impl fmt::Display for TxRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TxRejection::NonContiguousEventNonce(val) => {
+                write!(f, "Non contiguous event nonce: {}", val)
+            }
+            TxRejection::Duplicate(val) => write!(f, "Duplicate: {}", val),
+            TxRejection::NotValidator(val) => write!(f, "Not a validator: {}", val),
+            TxRejection::ServiceStopped(val) => write!(f, "Bridge stopped: {}", val),
+            TxRejection::BridgeHalted(val) => write!(f, "Bridge halted: {}", val),
+            TxRejection::SequenceMismatch(val) => write!(f, "Sequence mismatch: {}", val),
+            TxRejection::Other {
+                codespace,
+                code,
+                raw_log,
+            } => write!(f, "Code {} in {}: {}", code, codespace, raw_log),
+        }
+    }
 }
 
 impl fmt::Display for PeggyError {
@@ -51,6 +145,9 @@ impl fmt::Display for PeggyError {
             PeggyError::InsufficientVotingPowerToPass(val) => {
                 write!(f, "{}", val)
             }
+            PeggyError::CosmosTxRejected(val) => write!(f, "Cosmos tx rejected! {}", val),
+            PeggyError::StateStoreError(val) => write!(f, "Local state error: {}", val),
+            PeggyError::SecretError(val) => write!(f, "Secret error: {}", val),
         }
     }
 }
@@ -95,3 +192,27 @@ impl From<ParseBigIntError> for PeggyError {
         PeggyError::InvalidBigInt(error)
     }
 }
+
+#[test]
+fn test_tx_rejection_parsing() {
+    let rejection =
+        TxRejection::from_tx_result("peggy", 9, "claim: got 5: non contiguous event nonce");
+    assert_eq!(rejection.action(), RejectionAction::Resync);
+
+    let rejection = TxRejection::from_tx_result("peggy", 2, "duplicate signature: duplicate");
+    assert_eq!(rejection.action(), RejectionAction::Skip);
+
+    let rejection = TxRejection::from_tx_result("sdk", 24, "validator not in active set");
+    assert_eq!(
+        rejection,
+        TxRejection::NotValidator("validator not in active set".to_string())
+    );
+    assert_eq!(rejection.action(), RejectionAction::Halt);
+
+    let rejection =
+        TxRejection::from_tx_result("peggy", 11, "at event nonce 10, got 11: bridge halted");
+    assert_eq!(rejection.action(), RejectionAction::Retry);
+
+    let rejection = TxRejection::from_tx_result("sdk", 5, "insufficient funds");
+    assert_eq!(rejection.action(), RejectionAction::Retry);
+}
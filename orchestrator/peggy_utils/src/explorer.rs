@@ -0,0 +1,112 @@
+//! Block explorer links in logs and alerts. An explorer is configured per chain with `--hub-explorer` and
+//! `--ethereum-explorer` as a url template, `{kind}` is replaced with `tx` or `address` and `{id}` with the
+//! hash or address, for example `https://etherscan.io/{kind}/{id}`. Wrap hashes and addresses in `tx` or
+//! `address` when logging them, they are printed followed by their link if the chain has an explorer.
+
+use std::fmt::{self, Display};
+use std::sync::RwLock;
+
+lazy_static! {
+    static ref HUB_EXPLORER: RwLock<Option<String>> = RwLock::new(None);
+    static ref ETHEREUM_EXPLORER: RwLock<Option<String>> = RwLock::new(None);
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Chain {
+    Hub,
+    Ethereum,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Kind {
+    Tx,
+    Address,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Kind::Tx => "tx",
+            Kind::Address => "address",
+        }
+    }
+}
+
+fn explorer(chain: Chain) -> &'static RwLock<Option<String>> {
+    match chain {
+        Chain::Hub => &*HUB_EXPLORER,
+        Chain::Ethereum => &*ETHEREUM_EXPLORER,
+    }
+}
+
+/// Sets the url template of a chain's explorer, should be called once at startup
+pub fn set_explorer(chain: Chain, template: Option<String>) {
+    *explorer(chain).write().unwrap() = template;
+}
+
+fn link(chain: Chain, kind: Kind, id: &str) -> Option<String> {
+    explorer(chain).read().unwrap().as_ref().map(|template| {
+        template
+            .replace("{kind}", kind.as_str())
+            .replace("{id}", id)
+    })
+}
+
+/// A tx hash or address that is displayed with its explorer link
+pub struct Linked {
+    chain: Chain,
+    kind: Kind,
+    id: String,
+}
+
+impl Linked {
+    /// The explorer link, None if the chain has no explorer
+    pub fn url(&self) -> Option<String> {
+        link(self.chain, self.kind, &self.id)
+    }
+}
+
+impl Display for Linked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.url() {
+            Some(url) => write!(f, "{} ({})", self.id, url),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
+pub fn tx(chain: Chain, hash: impl Display) -> Linked {
+    Linked {
+        chain,
+        kind: Kind::Tx,
+        id: hash.to_string(),
+    }
+}
+
+pub fn address(chain: Chain, address: impl Display) -> Linked {
+    Linked {
+        chain,
+        kind: Kind::Address,
+        id: address.to_string(),
+    }
+}
+
+#[test]
+fn test_links() {
+    set_explorer(Chain::Hub, None);
+    assert_eq!(tx(Chain::Hub, "ABCD").to_string(), "ABCD");
+    set_explorer(
+        Chain::Hub,
+        Some("https://explorer.minter.network/hub/{kind}/{id}".to_string()),
+    );
+    assert_eq!(
+        tx(Chain::Hub, "ABCD").to_string(),
+        "ABCD (https://explorer.minter.network/hub/tx/ABCD)"
+    );
+    assert_eq!(
+        address(Chain::Hub, "hub1xyz").url(),
+        Some("https://explorer.minter.network/hub/address/hub1xyz".to_string())
+    );
+    assert_eq!(address(Chain::Ethereum, "0x11").url(), None);
+    set_explorer(Chain::Hub, None);
+}
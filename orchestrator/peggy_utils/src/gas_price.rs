@@ -0,0 +1,84 @@
+//! A time weighted average of the gas price. Deciding whether relaying is worth it on the spot gas price makes
+//! the answer flip with every gas spike, so estimates use an exponentially weighted moving average of the
+//! observed prices instead. An observation's weight halves with every `window` that passed since, so the average
+//! follows a lasting change within a few windows and mostly ignores spikes shorter than one. Transactions are
+//! still sent at the spot price, the average is only for estimates.
+
+use num256::Uint256;
+use std::time::{Duration, Instant};
+
+/// The default averaging window
+pub const DEFAULT_GAS_PRICE_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct GasPriceAverage {
+    window: Duration,
+    average: Option<f64>,
+    last_observed: Option<Instant>,
+}
+
+impl GasPriceAverage {
+    pub fn new(window: Duration) -> Self {
+        GasPriceAverage {
+            window,
+            average: None,
+            last_observed: None,
+        }
+    }
+
+    /// Adds a gas price observed now
+    pub fn observe(&mut self, price: &Uint256) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_observed
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        self.observe_after(price, elapsed);
+        self.last_observed = Some(now);
+    }
+
+    /// Adds a gas price observed `elapsed` after the previous one
+    pub fn observe_after(&mut self, price: &Uint256, elapsed: Duration) {
+        let price: f64 = price.to_string().parse().unwrap_or(f64::MAX);
+        self.average = Some(match self.average {
+            Some(average) => {
+                let window = self.window.as_secs_f64().max(1.0);
+                let kept = 0.5f64.powf(elapsed.as_secs_f64() / window);
+                average * kept + price * (1.0 - kept)
+            }
+            None => price,
+        });
+    }
+
+    /// The average gas price in wei, None before the first observation
+    pub fn average(&self) -> Option<Uint256> {
+        self.average.map(|average| (average.round() as u128).into())
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+}
+
+#[test]
+fn test_gas_price_average() {
+    let window = Duration::from_secs(60);
+    let mut average = GasPriceAverage::new(window);
+    assert_eq!(average.average(), None);
+
+    average.observe_after(&100u64.into(), Duration::from_secs(0));
+    assert_eq!(average.average(), Some(100u64.into()));
+
+    // a spike shortly after the previous observation barely moves the average
+    let mut spiked = average.clone();
+    spiked.observe_after(&1000u64.into(), Duration::from_secs(1));
+    assert!(spiked.average().unwrap() < 120u64.into());
+
+    // one window later the new price has half the weight
+    average.observe_after(&300u64.into(), window);
+    assert_eq!(average.average(), Some(200u64.into()));
+
+    // and after many windows the old prices are forgotten
+    average.observe_after(&50u64.into(), window * 20);
+    assert_eq!(average.average(), Some(50u64.into()));
+}
@@ -4,6 +4,18 @@
 extern crate serde_derive;
 #[macro_use]
 extern crate log;
+#[macro_use]
+extern crate lazy_static;
 
+pub mod address_book;
+pub mod amount;
+pub mod bounded_cache;
+pub mod coin_math;
+pub mod endpoints;
 pub mod error;
+pub mod explorer;
+pub mod gas_price;
+pub mod redact;
+pub mod secrets;
+pub mod timings;
 pub mod types;
@@ -0,0 +1,50 @@
+//! Log redaction for sensitive data. Raw signed transactions, signatures and full contract calldata are
+//! useful when debugging but should not end up in log aggregation by default. Wrap such values in
+//! `Sensitive` when logging them, they will only be printed in full if `--log-sensitive` was passed.
+
+use std::fmt::{self, Debug, Display};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static LOG_SENSITIVE: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables printing of sensitive values, should be called once at startup
+pub fn set_log_sensitive(enabled: bool) {
+    LOG_SENSITIVE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn log_sensitive() -> bool {
+    LOG_SENSITIVE.load(Ordering::Relaxed)
+}
+
+/// A value that is only shown in logs if sensitive logging is enabled
+pub struct Sensitive<T>(pub T);
+
+impl<T: Display> Display for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if log_sensitive() {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "<redacted>")
+        }
+    }
+}
+
+impl<T: Debug> Debug for Sensitive<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if log_sensitive() {
+            write!(f, "{:?}", self.0)
+        } else {
+            write!(f, "<redacted>")
+        }
+    }
+}
+
+#[test]
+fn test_redaction() {
+    set_log_sensitive(false);
+    assert_eq!(format!("{}", Sensitive("0xdeadbeef")), "<redacted>");
+    assert_eq!(format!("{:?}", Sensitive(vec![1u8, 2])), "<redacted>");
+    set_log_sensitive(true);
+    assert_eq!(format!("{}", Sensitive("0xdeadbeef")), "0xdeadbeef");
+    set_log_sensitive(false);
+}
@@ -0,0 +1,266 @@
+//! Private keys, phrases and API tokens can be given as they are or as a reference to where they are kept,
+//! so they never have to sit in a plaintext file or on the command line of a relayer host. References are
+//! resolved once at startup:
+//!
+//! - `env:<VAR>` the environment variable `VAR`
+//! - `file:<path>` the contents of a file, for example one a secrets manager mounts on a tmpfs
+//! - `gpg:<path>` a file encrypted with gpg, decrypted through the local gpg agent
+//! - `vault:<path>#<field>` a field of a HashiCorp Vault secret. `path` is the API path after `/v1/`, like
+//!   `secret/data/orchestrator` for a KV version 2 engine mounted at `secret`
+//! - `aws-sm:<secret id>` an AWS Secrets Manager secret
+//! - `aws-kms:<path>` a file encrypted with AWS KMS
+//!
+//! The Vault and AWS backends run the vault and aws clis, so they use their usual configuration, `VAULT_ADDR`,
+//! `VAULT_TOKEN` and `VAULT_NAMESPACE` for Vault and the credentials and region for AWS. The clis run on the
+//! async runtime's process driver and are killed if they take longer than `BACKEND_TIMEOUT`. Anything without
+//! one of these prefixes is the secret itself. Surrounding whitespace is trimmed from every secret.
+
+use crate::error::PeggyError;
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+/// How long to wait for a backend's cli
+const BACKEND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where a secret is kept
+#[derive(Clone, Eq, PartialEq)]
+pub enum SecretSource {
+    /// the secret itself
+    Plain(String),
+    Env(String),
+    File(PathBuf),
+    Gpg(PathBuf),
+    Vault {
+        path: String,
+        field: String,
+    },
+    AwsSecretsManager(String),
+    AwsKms(PathBuf),
+}
+
+impl SecretSource {
+    pub fn is_plain(&self) -> bool {
+        matches!(self, SecretSource::Plain(_))
+    }
+
+    pub async fn resolve(&self) -> Result<String, PeggyError> {
+        let secret = match self {
+            SecretSource::Plain(secret) => secret.clone(),
+            SecretSource::Env(var) => std::env::var(var)
+                .map_err(|e| secret_error(format!("Failed to read {}: {}", var, e)))?,
+            SecretSource::File(path) => fs::read_to_string(path)
+                .map_err(|e| secret_error(format!("Failed to read {}: {}", path.display(), e)))?,
+            SecretSource::Gpg(path) => {
+                run(
+                    "gpg",
+                    Command::new("gpg")
+                        .args(&["--quiet", "--batch", "--decrypt"])
+                        .arg(path),
+                )
+                .await?
+            }
+            SecretSource::Vault { path, field } => read_vault(path, field).await?,
+            SecretSource::AwsSecretsManager(id) => {
+                run(
+                    "aws secretsmanager",
+                    Command::new("aws")
+                        .args(&["secretsmanager", "get-secret-value", "--secret-id"])
+                        .arg(id)
+                        .args(&["--query", "SecretString", "--output", "text"]),
+                )
+                .await?
+            }
+            SecretSource::AwsKms(path) => {
+                // the cli prints the plaintext base64 encoded
+                let plaintext = run(
+                    "aws kms",
+                    Command::new("aws")
+                        .args(&["kms", "decrypt", "--ciphertext-blob"])
+                        .arg(format!("fileb://{}", path.display()))
+                        .args(&["--query", "Plaintext", "--output", "text"]),
+                )
+                .await?;
+                let plaintext = base64::decode(plaintext.trim()).map_err(|e| {
+                    secret_error(format!(
+                        "Invalid KMS plaintext for {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                String::from_utf8(plaintext).map_err(|_| {
+                    secret_error(format!("{} doesn't decrypt to text", path.display()))
+                })?
+            }
+        };
+        Ok(secret.trim().to_string())
+    }
+}
+
+impl FromStr for SecretSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (scheme, target) = match s.find(':') {
+            Some(i) => (&s[..i], s[i + 1..].trim()),
+            None => return Ok(SecretSource::Plain(s.to_string())),
+        };
+        let source = match scheme {
+            "env" => SecretSource::Env(target.to_string()),
+            "file" => SecretSource::File(target.into()),
+            "gpg" => SecretSource::Gpg(target.into()),
+            "vault" => {
+                let mut parts = target.rsplitn(2, '#');
+                match (parts.next(), parts.next()) {
+                    (Some(field), Some(path)) if !field.is_empty() && !path.is_empty() => {
+                        SecretSource::Vault {
+                            path: path.to_string(),
+                            field: field.to_string(),
+                        }
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Invalid Vault secret {}, expected vault:<path>#<field>",
+                            s
+                        ))
+                    }
+                }
+            }
+            "aws-sm" => SecretSource::AwsSecretsManager(target.to_string()),
+            "aws-kms" => SecretSource::AwsKms(target.into()),
+            _ => return Ok(SecretSource::Plain(s.to_string())),
+        };
+        if target.is_empty() {
+            return Err(format!("Secret reference {} doesn't say where to look", s));
+        }
+        Ok(source)
+    }
+}
+
+/// Never prints a plaintext secret, only where a secret is kept
+impl fmt::Debug for SecretSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecretSource::Plain(_) => write!(f, "<plaintext secret>"),
+            SecretSource::Env(var) => write!(f, "env:{}", var),
+            SecretSource::File(path) => write!(f, "file:{}", path.display()),
+            SecretSource::Gpg(path) => write!(f, "gpg:{}", path.display()),
+            SecretSource::Vault { path, field } => write!(f, "vault:{}#{}", path, field),
+            SecretSource::AwsSecretsManager(id) => write!(f, "aws-sm:{}", id),
+            SecretSource::AwsKms(path) => write!(f, "aws-kms:{}", path.display()),
+        }
+    }
+}
+
+/// Resolves a secret given as a reference or as it is
+pub async fn resolve_secret(input: &str) -> Result<String, PeggyError> {
+    let source: SecretSource = input.parse().map_err(PeggyError::SecretError)?;
+    let secret = source.resolve().await?;
+    if secret.is_empty() {
+        return Err(secret_error(format!("The secret in {:?} is empty", source)));
+    }
+    Ok(secret)
+}
+
+fn secret_error(message: String) -> PeggyError {
+    PeggyError::SecretError(message)
+}
+
+/// Runs a backend's cli, `name` stands for it in errors. Errors carry its stderr but never its stdout,
+/// which holds the secret
+async fn run(name: &str, command: &mut Command) -> Result<String, PeggyError> {
+    let output = timeout(BACKEND_TIMEOUT, command.kill_on_drop(true).output())
+        .await
+        .map_err(|_| {
+            secret_error(format!(
+                "{} didn't finish within {}s",
+                name,
+                BACKEND_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| secret_error(format!("Failed to run {}: {}", name, e)))?;
+    if !output.status.success() {
+        return Err(secret_error(format!(
+            "{} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| secret_error(format!("{} didn't print text", name)))
+}
+
+/// Reads the secret with `vault read`, its json output is the API response
+async fn read_vault(path: &str, field: &str) -> Result<String, PeggyError> {
+    let output = run(
+        "vault read",
+        Command::new("vault")
+            .args(&["read", "-format=json"])
+            .arg(path.trim_start_matches('/')),
+    )
+    .await?;
+    let body: Value = serde_json::from_str(&output)
+        .map_err(|e| secret_error(format!("Invalid Vault response for {}: {}", path, e)))?;
+    vault_field(&body, field)
+        .ok_or_else(|| secret_error(format!("Vault secret {} has no field {}", path, field)))
+}
+
+/// KV version 2 secrets are nested one level deeper than version 1 secrets
+fn vault_field(body: &Value, field: &str) -> Option<String> {
+    let data = &body["data"];
+    data["data"][field]
+        .as_str()
+        .or_else(|| data[field].as_str())
+        .map(|secret| secret.to_string())
+}
+
+#[test]
+fn test_secret_sources() {
+    let parse = |s: &str| s.parse::<SecretSource>();
+    let phrase = "follow sister blossom hire";
+    assert_eq!(parse(phrase), Ok(SecretSource::Plain(phrase.to_string())));
+    assert_eq!(
+        parse("0xe108657c5d7bb1b1b4a6d8bcda6ca16b8ba0d8a6d0e7cbb2b2a2ee0d9a1fa5e1"),
+        Ok(SecretSource::Plain(
+            "0xe108657c5d7bb1b1b4a6d8bcda6ca16b8ba0d8a6d0e7cbb2b2a2ee0d9a1fa5e1".to_string()
+        ))
+    );
+    assert_eq!(
+        parse("env:ETH_KEY"),
+        Ok(SecretSource::Env("ETH_KEY".to_string()))
+    );
+    assert_eq!(
+        parse("file:/run/secrets/phrase"),
+        Ok(SecretSource::File("/run/secrets/phrase".into()))
+    );
+    assert_eq!(
+        parse("vault:secret/data/orchestrator#ethereum_key"),
+        Ok(SecretSource::Vault {
+            path: "secret/data/orchestrator".to_string(),
+            field: "ethereum_key".to_string(),
+        })
+    );
+    assert!(parse("vault:secret/data/orchestrator").is_err());
+    assert!(parse("aws-sm:").is_err());
+    // a colon alone doesn't make a reference
+    assert!(parse("user:password").unwrap().is_plain());
+    assert_eq!(
+        format!("{:?}", parse(phrase).unwrap()),
+        "<plaintext secret>"
+    );
+    assert_eq!(
+        format!("{:?}", parse("aws-kms: /etc/key.enc").unwrap()),
+        "aws-kms:/etc/key.enc"
+    );
+
+    let v1 = serde_json::json!({"data": {"ethereum_key": "0x01"}});
+    let v2 = serde_json::json!({"data": {"data": {"ethereum_key": "0x02"}, "metadata": {}}});
+    assert_eq!(vault_field(&v1, "ethereum_key"), Some("0x01".to_string()));
+    assert_eq!(vault_field(&v2, "ethereum_key"), Some("0x02".to_string()));
+    assert_eq!(vault_field(&v2, "cosmos_phrase"), None);
+}
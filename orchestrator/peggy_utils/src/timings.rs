@@ -0,0 +1,213 @@
+//! Loop intervals, timeouts and confirmation depths used by the orchestrator and relayer. The defaults
+//! suit Ethereum mainnet, a json file with any of the `TimingsConfig` fields overrides them, in seconds.
+//! Values that can't work are rejected, values that work but are risky are warned about.
+
+use crate::error::PeggyError;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Timings {
+    /// how often the oracle, signer and relayer loops run
+    pub loop_speed: Duration,
+    /// timeout for Ethereum and Cosmos RPC requests
+    pub rpc_timeout: Duration,
+    /// how long the relayer waits for a submitted Ethereum tx
+    pub eth_tx_timeout: Duration,
+    /// the expected Ethereum block time
+    pub eth_block_time: Duration,
+    /// how many blocks deep Ethereum events must be before the oracle claims them
+    pub eth_confirmations: u64,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Timings {
+            loop_speed: Duration::from_secs(10),
+            rpc_timeout: Duration::from_secs(10),
+            eth_tx_timeout: Duration::from_secs(120),
+            eth_block_time: Duration::from_secs(13),
+            eth_confirmations: 5,
+        }
+    }
+}
+
+/// The timings as written in a config file, every field is optional
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TimingsConfig {
+    #[serde(default)]
+    pub loop_secs: Option<u64>,
+    #[serde(default)]
+    pub rpc_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub eth_tx_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub eth_block_secs: Option<u64>,
+    #[serde(default)]
+    pub eth_confirmations: Option<u64>,
+}
+
+impl Timings {
+    pub fn from_config(config: TimingsConfig) -> Result<Timings, PeggyError> {
+        Timings::from_config_over(config, Timings::default())
+    }
+
+    /// Like `from_config` but the fields the config leaves out come from `defaults`, a profile's timings
+    pub fn from_config_over(
+        config: TimingsConfig,
+        defaults: Timings,
+    ) -> Result<Timings, PeggyError> {
+        let secs =
+            |value: Option<u64>, default: Duration| value.map_or(default, Duration::from_secs);
+        let timings = Timings {
+            loop_speed: secs(config.loop_secs, defaults.loop_speed),
+            rpc_timeout: secs(config.rpc_timeout_secs, defaults.rpc_timeout),
+            eth_tx_timeout: secs(config.eth_tx_timeout_secs, defaults.eth_tx_timeout),
+            eth_block_time: secs(config.eth_block_secs, defaults.eth_block_time),
+            eth_confirmations: config
+                .eth_confirmations
+                .unwrap_or(defaults.eth_confirmations),
+        };
+        timings.validate()?;
+        Ok(timings)
+    }
+
+    /// Loads the timings from a json file, logging a warning for every risky value
+    pub fn load(path: &Path) -> Result<Timings, PeggyError> {
+        Timings::load_over(path, Timings::default())
+    }
+
+    /// Loads the timings from a json file on top of `defaults`
+    pub fn load_over(path: &Path, defaults: Timings) -> Result<Timings, PeggyError> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            PeggyError::InvalidBridgeStateError(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let config: TimingsConfig = serde_json::from_str(&contents).map_err(|e| {
+            PeggyError::InvalidBridgeStateError(format!(
+                "Failed to parse {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        let timings = Timings::from_config_over(config, defaults)?;
+        for warning in timings.warnings() {
+            warn!("{}", warning);
+        }
+        Ok(timings)
+    }
+
+    /// Rejects values the loops can't work with
+    pub fn validate(&self) -> Result<(), PeggyError> {
+        let zero = Duration::from_secs(0);
+        for (name, value) in [
+            ("loop_secs", self.loop_speed),
+            ("rpc_timeout_secs", self.rpc_timeout),
+            ("eth_tx_timeout_secs", self.eth_tx_timeout),
+            ("eth_block_secs", self.eth_block_time),
+        ]
+        .iter()
+        {
+            if *value == zero {
+                return Err(PeggyError::InvalidBridgeStateError(format!(
+                    "Timing {} must not be zero",
+                    name
+                )));
+            }
+        }
+        if self.eth_tx_timeout < self.eth_block_time {
+            return Err(PeggyError::InvalidBridgeStateError(format!(
+                "The Ethereum tx timeout of {}s is shorter than a single {}s block",
+                self.eth_tx_timeout.as_secs(),
+                self.eth_block_time.as_secs()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Values that work but are likely a mistake
+    pub fn warnings(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        let confirmed_after = self.eth_block_time * self.eth_confirmations as u32;
+        if self.eth_tx_timeout < confirmed_after {
+            out.push(format!(
+                "The Ethereum tx timeout of {}s is shorter than {} confirmations take, the relayer will often give up on txs that are still pending",
+                self.eth_tx_timeout.as_secs(),
+                self.eth_confirmations
+            ));
+        }
+        if self.eth_confirmations < 3 {
+            out.push(format!(
+                "Only {} Ethereum confirmations, a reorg could make the oracle claim events that never happened",
+                self.eth_confirmations
+            ));
+        }
+        if self.loop_speed < Duration::from_secs(2) {
+            out.push(format!(
+                "A loop interval of {}ms will put heavy load on the RPC endpoints",
+                self.loop_speed.as_millis()
+            ));
+        }
+        if self.rpc_timeout > self.loop_speed * 6 {
+            out.push(format!(
+                "An RPC timeout of {}s lets a single hanging request stall the loops for many intervals",
+                self.rpc_timeout.as_secs()
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings() {
+        let defaults = Timings::default();
+        assert!(defaults.validate().is_ok());
+        assert!(defaults.warnings().is_empty());
+        assert_eq!(
+            Timings::from_config(TimingsConfig::default()).unwrap(),
+            defaults
+        );
+
+        let config: TimingsConfig =
+            serde_json::from_str(r#"{"loop_secs": 5, "eth_confirmations": 12}"#).unwrap();
+        let timings = Timings::from_config(config).unwrap();
+        assert_eq!(timings.loop_speed, Duration::from_secs(5));
+        assert_eq!(timings.eth_confirmations, 12);
+        assert_eq!(timings.rpc_timeout, defaults.rpc_timeout);
+        // 12 confirmations of 13 seconds take longer than the tx timeout
+        assert_eq!(timings.warnings().len(), 1);
+
+        // the fields the file leaves out come from the given defaults instead
+        let fast = Timings {
+            eth_block_time: Duration::from_secs(1),
+            ..defaults
+        };
+        let timings = Timings::from_config_over(config, fast).unwrap();
+        assert_eq!(timings.eth_confirmations, 12);
+        assert_eq!(timings.eth_block_time, Duration::from_secs(1));
+
+        assert!(Timings::from_config(TimingsConfig {
+            loop_secs: Some(0),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(Timings::from_config(TimingsConfig {
+            eth_tx_timeout_secs: Some(5),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(serde_json::from_str::<TimingsConfig>(r#"{"loop": 5}"#).is_err());
+
+        let risky = Timings::from_config(TimingsConfig {
+            eth_confirmations: Some(1),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(risky.warnings().len(), 1);
+    }
+}
@@ -1,12 +1,16 @@
 use super::*;
+use crate::coin_math::{CoinMath, CoinMathError};
 use crate::error::PeggyError;
 use clarity::{abi::Token, Address as EthAddress};
 use deep_space::address::Address as CosmosAddress;
 use std::cmp::Ordering;
 
+/// The most transactions a batch from the Hub may have, the Hub builds batches of at most 100
+pub const MAX_BATCH_TRANSACTIONS: usize = 1000;
+
 /// This represents an individual transaction being bridged over to Ethereum
 /// parallel is the OutgoingTransferTx in x/peggy/types/batch.go
-#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialOrd, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub struct BatchTransaction {
     pub id: u64,
     pub sender: CosmosAddress,
@@ -33,12 +37,15 @@ impl BatchTransaction {
 }
 
 /// the response we get when querying for a valset confirmation
-#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialOrd, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub struct TransactionBatch {
     pub nonce: u64,
     pub transactions: Vec<BatchTransaction>,
     pub total_fee: ERC20Token,
     pub token_contract: EthAddress,
+    /// the Hub block the batch was created in
+    #[serde(default)]
+    pub block: u64,
 }
 
 impl Ord for TransactionBatch {
@@ -48,6 +55,14 @@ impl Ord for TransactionBatch {
 }
 
 impl TransactionBatch {
+    /// The total amount the batch sends out, in the batch's token
+    pub fn total_amount(&self) -> Result<ERC20Token, CoinMathError> {
+        let total = ERC20Token::checked_sum(self.transactions.iter().map(|tx| &tx.erc20_token))?;
+        let total = total.unwrap_or_else(|| self.total_fee.with_amount(0u8.into()));
+        total.same_denom(&self.total_fee)?;
+        Ok(total)
+    }
+
     /// extracts the amounts, destinations and fees as submitted to the Ethereum contract
     /// and used for signatures
     pub fn get_checkpoint_values(&self) -> (Token, Token) {
@@ -62,31 +77,33 @@ impl TransactionBatch {
     }
 
     pub fn from_proto(input: peggy_proto::peggy::OutgoingTxBatch) -> Result<Self, PeggyError> {
+        check_response_len(
+            "batch transactions",
+            input.transactions.len(),
+            MAX_BATCH_TRANSACTIONS,
+        )?;
         let mut transactions = Vec::new();
-        let mut running_total_fee: Option<ERC20Token> = None;
         for tx in input.transactions {
-            let tx = BatchTransaction::from_proto(tx)?;
-            if let Some(total_fee) = running_total_fee {
-                running_total_fee = Some(ERC20Token {
-                    token_contract_address: total_fee.token_contract_address,
-                    amount: total_fee.amount + tx.erc20_fee.amount.clone(),
-                });
-            } else {
-                running_total_fee = Some(tx.erc20_fee.clone())
-            }
-            transactions.push(tx);
-        }
-        if let Some(total_fee) = running_total_fee {
-            Ok(TransactionBatch {
-                nonce: input.batch_nonce,
-                transactions,
-                token_contract: total_fee.token_contract_address,
-                total_fee,
-            })
-        } else {
-            Err(PeggyError::InvalidBridgeStateError(
-                "Transaction batch containing no transactions!".to_string(),
-            ))
+            transactions.push(BatchTransaction::from_proto(tx)?);
         }
+        // a batch only holds one token, a fee in another token means the batch is corrupt
+        let total_fee = ERC20Token::checked_sum(transactions.iter().map(|tx| &tx.erc20_fee))?;
+        let total_fee = match total_fee {
+            Some(total_fee) => total_fee,
+            // the Hub supersedes a withheld batch with an empty one when the token's pool can't be batched
+            None => ERC20Token {
+                amount: 0u8.into(),
+                token_contract_address: input.token_contract.parse()?,
+            },
+        };
+        let batch = TransactionBatch {
+            nonce: input.batch_nonce,
+            transactions,
+            token_contract: total_fee.token_contract_address,
+            total_fee,
+            block: input.block,
+        };
+        batch.total_amount()?;
+        Ok(batch)
     }
 }
@@ -7,6 +7,7 @@ pub struct Coin {
     pub denom: String,
     pub minter_id: u64,
     pub eth_addr: String,
+    pub eth_decimals: u64,
 }
 
 impl Coin {
@@ -15,6 +16,7 @@ impl Coin {
             denom: input.denom,
             minter_id: input.minter_id,
             eth_addr: input.eth_addr,
+            eth_decimals: input.eth_decimals,
         })
     }
 }
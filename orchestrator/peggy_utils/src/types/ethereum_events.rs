@@ -7,6 +7,15 @@ use num256::Uint256;
 use serde::Serialize;
 use web30::types::Log;
 
+/// Deposit destinations are bytes32 but Cosmos and Minter addresses are 20 bytes, anything in the
+/// upper 12 bytes means the user passed something that isn't an address. Taking the lower 20 bytes
+/// of it would credit an arbitrary account, as would the zero address, so these deposits are refunded.
+pub fn is_invalid_destination(destination: &[u8]) -> bool {
+    destination.len() != 32
+        || destination[..12].iter().any(|b| *b != 0)
+        || destination[12..].iter().all(|b| *b == 0)
+}
+
 /// A parsed struct representing the Ethereum event fired by the Peggy contract
 /// when the validator set is updated.
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, Hash)]
@@ -156,6 +165,7 @@ impl TransactionBatchExecutedEvent {
                         bytes_to_hex_str(input.transaction_hash.as_deref().unwrap())
                     )
                     .into(),
+                    invalid_destination: is_invalid_destination(destination_data),
                 })
             }
         } else {
@@ -200,6 +210,8 @@ pub struct SendToCosmosEvent {
     pub event_nonce: Uint256,
 
     pub tx_hash: String,
+    /// the destination isn't an address, the Hub refunds the deposit to `sender`
+    pub invalid_destination: bool,
 }
 
 impl SendToCosmosEvent {
@@ -236,6 +248,7 @@ impl SendToCosmosEvent {
                         bytes_to_hex_str(input.transaction_hash.as_deref().unwrap())
                     )
                     .into(),
+                    invalid_destination: is_invalid_destination(destination_data),
                 })
             }
         } else {
@@ -279,6 +292,8 @@ pub struct SendToMinterEvent {
     /// The transaction's nonce, used to make sure there can be no accidntal duplication
     pub event_nonce: Uint256,
     pub tx_hash: String,
+    /// the destination isn't an address, the Hub refunds the deposit to `sender`
+    pub invalid_destination: bool,
 }
 
 impl SendToMinterEvent {
@@ -340,3 +355,19 @@ impl SendToMinterEvent {
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_invalid_destination() {
+        let mut destination = [0u8; 32];
+        assert!(is_invalid_destination(&destination));
+        destination[31] = 1;
+        assert!(!is_invalid_destination(&destination));
+        destination[11] = 1;
+        assert!(is_invalid_destination(&destination));
+        assert!(is_invalid_destination(&destination[12..]));
+    }
+}
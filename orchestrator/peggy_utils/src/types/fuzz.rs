@@ -0,0 +1,366 @@
+//! Feeds malformed and adversarial Hub responses into the parsing of valsets, batches and confirms. The Hub
+//! node we query is not necessarily honest, a compromised or buggy endpoint can send huge numbers, wrong
+//! types, invalid addresses and signatures or absurdly many items. All of that has to end in an error the
+//! loops can log and retry on, never in a panic that takes the orchestrator down. Every parsed value is also
+//! run through the code that consumes it, since that is where overflows used to hide. The inputs are random
+//! but seeded, so a failure reproduces.
+
+use super::*;
+use clarity::abi::encode_tokens;
+use deep_space::address::Address as CosmosAddress;
+use peggy_proto::peggy as proto;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ROUNDS: usize = 2000;
+
+const MAX_UINT256: &str =
+    "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+const MAX_UINT256_PLUS_ONE: &str =
+    "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+
+fn rng() -> StdRng {
+    StdRng::seed_from_u64(0x9e55)
+}
+
+fn random_hex(rng: &mut StdRng, len: usize) -> String {
+    (0..len)
+        .map(|_| std::char::from_digit(rng.gen_range(0..16), 16).unwrap())
+        .collect()
+}
+
+fn random_digits(rng: &mut StdRng, len: usize) -> String {
+    (0..len)
+        .map(|_| std::char::from_digit(rng.gen_range(0..10), 10).unwrap())
+        .collect()
+}
+
+fn number(rng: &mut StdRng) -> String {
+    match rng.gen_range(0..12) {
+        0 => "0".to_string(),
+        1 => u64::MAX.to_string(),
+        2 => "18446744073709551616".to_string(),
+        3 => MAX_UINT256.to_string(),
+        4 => MAX_UINT256_PLUS_ONE.to_string(),
+        5 => "9".repeat(rng.gen_range(79..5000)),
+        6 => "-1".to_string(),
+        7 => "1e18".to_string(),
+        8 => format!("0x{}", random_hex(rng, 64)),
+        9 => String::new(),
+        10 => " 1".to_string(),
+        _ => {
+            let len = rng.gen_range(1..80);
+            random_digits(rng, len)
+        }
+    }
+}
+
+fn power(rng: &mut StdRng) -> u64 {
+    match rng.gen_range(0..4) {
+        0 => u64::MAX,
+        1 => u64::MAX - rng.gen_range(0..1000),
+        2 => 0,
+        _ => rng.gen_range(0..=TOTAL_PEGGY_POWER),
+    }
+}
+
+fn eth_address(rng: &mut StdRng, pool: &[String]) -> String {
+    match rng.gen_range(0..8) {
+        0 => String::new(),
+        1 => "0x".to_string(),
+        2 => {
+            let len = rng.gen_range(0..80);
+            format!("0x{}", random_hex(rng, len))
+        }
+        3 => format!("0x{}zz", random_hex(rng, 38)),
+        4 => random_hex(rng, 40),
+        _ => pool[rng.gen_range(0..pool.len())].clone(),
+    }
+}
+
+fn valid_cosmos_address() -> String {
+    CosmosAddress::from_bytes([7; 20]).to_string()
+}
+
+fn cosmos_address(rng: &mut StdRng) -> String {
+    match rng.gen_range(0..4) {
+        0 => String::new(),
+        1 => "hub1".to_string(),
+        2 => {
+            let len = rng.gen_range(0..100);
+            format!("hub1{}", random_hex(rng, len))
+        }
+        _ => valid_cosmos_address(),
+    }
+}
+
+fn signature(rng: &mut StdRng) -> String {
+    match rng.gen_range(0..5) {
+        0 => String::new(),
+        1 => random_hex(rng, 129),
+        2 => format!("0x{}", random_hex(rng, 128)),
+        3 => format!("0x{}", random_hex(rng, 130)),
+        _ => {
+            let len = rng.gen_range(0..400);
+            random_hex(rng, len)
+        }
+    }
+}
+
+/// Valid addresses for most members and signers, so that parsing succeeds often enough to reach the
+/// consuming code
+fn address_pool(rng: &mut StdRng) -> Vec<String> {
+    (0..8)
+        .map(|_| format!("0x{}", random_hex(rng, 40)))
+        .collect()
+}
+
+fn proto_token(rng: &mut StdRng, pool: &[String]) -> Option<proto::Erc20Token> {
+    if rng.gen_range(0..20) == 0 {
+        return None;
+    }
+    Some(proto::Erc20Token {
+        contract: if rng.gen_bool(0.9) {
+            pool[0].clone()
+        } else {
+            eth_address(rng, pool)
+        },
+        amount: number(rng),
+    })
+}
+
+fn proto_batch(rng: &mut StdRng, pool: &[String], len: usize) -> proto::OutgoingTxBatch {
+    proto::OutgoingTxBatch {
+        batch_nonce: rng.gen(),
+        transactions: (0..len)
+            .map(|_| proto::OutgoingTransferTx {
+                id: rng.gen(),
+                sender: cosmos_address(rng),
+                dest_address: eth_address(rng, pool),
+                erc20_token: proto_token(rng, pool),
+                erc20_fee: proto_token(rng, pool),
+                tx_hash: random_hex(rng, 64),
+                memo: String::new(),
+            })
+            .collect(),
+        token_contract: eth_address(rng, pool),
+        block: rng.gen(),
+    }
+}
+
+fn proto_valset(rng: &mut StdRng, pool: &[String], len: usize) -> proto::Valset {
+    proto::Valset {
+        nonce: rng.gen(),
+        members: (0..len)
+            .map(|_| proto::BridgeValidator {
+                power: power(rng),
+                ethereum_address: eth_address(rng, pool),
+            })
+            .collect(),
+        height: rng.gen(),
+    }
+}
+
+fn exercise_valset(valset: &Valset, other: &Valset, rng: &mut StdRng) {
+    let _ = valset.power_check(rng.gen());
+    let _ = valset.power_check(ETHEREUM_POWER_THRESHOLD);
+    let diff = valset.power_diff(other);
+    assert!(diff.is_finite());
+    let _ = valset.overlap_power(other);
+    let (addresses, powers) = valset.filter_empty_addresses();
+    let _ = encode_tokens(&[addresses.into(), powers.into()]);
+}
+
+#[test]
+fn test_fuzz_batch_from_proto() {
+    let mut rng = rng();
+    let pool = address_pool(&mut rng);
+    for _ in 0..ROUNDS {
+        let len = rng.gen_range(0..6);
+        let batch = match TransactionBatch::from_proto(proto_batch(&mut rng, &pool, len)) {
+            Ok(batch) => batch,
+            Err(_) => continue,
+        };
+        // a batch that parses is one the signer and relayer will encode
+        batch.total_amount().unwrap();
+        let (amounts, destinations) = batch.get_checkpoint_values();
+        let _ = encode_tokens(&[amounts, destinations]);
+    }
+
+    // amounts that only overflow once added up
+    let mut batch = proto_batch(&mut rng, &pool, 2);
+    for tx in batch.transactions.iter_mut() {
+        let token = proto::Erc20Token {
+            contract: pool[0].clone(),
+            amount: MAX_UINT256.to_string(),
+        };
+        tx.dest_address = pool[1].clone();
+        tx.sender = valid_cosmos_address();
+        tx.erc20_token = Some(token.clone());
+        tx.erc20_fee = Some(token);
+    }
+    assert!(TransactionBatch::from_proto(batch).is_err());
+
+    let absurd = proto_batch(&mut rng, &pool, MAX_BATCH_TRANSACTIONS + 1);
+    assert!(TransactionBatch::from_proto(absurd).is_err());
+}
+
+#[test]
+fn test_fuzz_valset_and_confirms_from_proto() {
+    let mut rng = rng();
+    let pool = address_pool(&mut rng);
+    for _ in 0..ROUNDS {
+        let len = rng.gen_range(0..12);
+        let valset = Valset::from_proto(proto_valset(&mut rng, &pool, len)).unwrap();
+        let other = Valset::from_proto(proto_valset(&mut rng, &pool, len)).unwrap();
+        exercise_valset(&valset, &other, &mut rng);
+
+        let valset_confirms: Vec<ValsetConfirmResponse> = (0..rng.gen_range(0..12))
+            .filter_map(|_| {
+                ValsetConfirmResponse::from_proto(proto::MsgValsetConfirm {
+                    nonce: rng.gen(),
+                    orchestrator: cosmos_address(&mut rng),
+                    eth_address: eth_address(&mut rng, &pool),
+                    signature: signature(&mut rng),
+                })
+                .ok()
+            })
+            .collect();
+        let _ = valset.signed_power(&valset_confirms);
+        if let Ok(signatures) = valset.order_valset_sigs(&valset_confirms) {
+            let _ = select_signatures(
+                signatures,
+                &[0x11; 32],
+                SignatureSelection::Minimal,
+                ETHEREUM_POWER_THRESHOLD,
+            );
+        }
+
+        let batch_confirms: Vec<BatchConfirmResponse> = (0..rng.gen_range(0..12))
+            .filter_map(|_| {
+                BatchConfirmResponse::from_proto(proto::MsgConfirmBatch {
+                    nonce: rng.gen(),
+                    token_contract: eth_address(&mut rng, &pool),
+                    eth_signer: eth_address(&mut rng, &pool),
+                    orchestrator: cosmos_address(&mut rng),
+                    signature: signature(&mut rng),
+                })
+                .ok()
+            })
+            .collect();
+        if let Ok(signatures) = valset.order_batch_sigs(&batch_confirms) {
+            let arrays = to_arrays(signatures, SignatureEncoding::Compact);
+            let _ = encode_tokens(&[arrays.addresses.into(), arrays.powers.into()]);
+        }
+    }
+
+    let absurd = proto_valset(&mut rng, &pool, MAX_VALSET_MEMBERS + 1);
+    assert!(Valset::from_proto(absurd).is_err());
+}
+
+/// A random json value shaped roughly like the responses, with the wrong types mixed in
+fn json_value(rng: &mut StdRng, depth: usize) -> String {
+    let choice = if depth > 3 {
+        rng.gen_range(0..6)
+    } else {
+        rng.gen_range(0..9)
+    };
+    match choice {
+        0 => "null".to_string(),
+        1 => "true".to_string(),
+        2 => number(rng).trim_start_matches("0x").to_string(),
+        3 => format!("\"{}\"", number(rng)),
+        4 => {
+            let pool = address_pool(rng);
+            format!("\"{}\"", eth_address(rng, &pool))
+        }
+        5 => format!("-{}e{}", random_digits(rng, 3), rng.gen_range(0..400)),
+        6 => {
+            let items: Vec<String> = (0..rng.gen_range(0..5))
+                .map(|_| json_value(rng, depth + 1))
+                .collect();
+            format!("[{}]", items.join(","))
+        }
+        _ => json_object(rng, depth + 1),
+    }
+}
+
+const JSON_KEYS: [&str; 14] = [
+    "nonce",
+    "members",
+    "power",
+    "eth_address",
+    "transactions",
+    "total_fee",
+    "token_contract",
+    "amount",
+    "contract",
+    "destination",
+    "orchestrator",
+    "eth_signature",
+    "ethereum_signer",
+    "erc20_token",
+];
+
+fn json_object(rng: &mut StdRng, depth: usize) -> String {
+    let fields: Vec<String> = (0..rng.gen_range(0..JSON_KEYS.len()))
+        .map(|_| {
+            format!(
+                "\"{}\":{}",
+                JSON_KEYS[rng.gen_range(0..JSON_KEYS.len())],
+                json_value(rng, depth)
+            )
+        })
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+#[test]
+fn test_fuzz_json_responses() {
+    let mut rng = rng();
+    let member = |power: &str| format!("{{\"power\":{},\"eth_address\":null}}", power);
+    let mut documents = vec![
+        String::new(),
+        "{".to_string(),
+        "[]".to_string(),
+        "{\"nonce\":-1,\"members\":[]}".to_string(),
+        format!("{{\"nonce\":{},\"members\":[]}}", MAX_UINT256),
+        format!("{{\"nonce\":1,\"members\":[{}]}}", member("1e400")),
+        format!("{{\"nonce\":1,\"members\":[{}]}}", member("\"5\"")),
+        format!(
+            "{{\"nonce\":1,\"members\":[{},{}]}}",
+            member(&u64::MAX.to_string()),
+            member(&u64::MAX.to_string())
+        ),
+        format!("{}{}", "[".repeat(10_000), "]".repeat(10_000)),
+        format!(
+            "{{\"nonce\":1,\"transactions\":[],\"total_fee\":{{\"amount\":\"{}\",\"contract\":\"{}\"}},\"token_contract\":\"0x\"}}",
+            MAX_UINT256_PLUS_ONE, "0x"
+        ),
+    ];
+    for _ in 0..ROUNDS {
+        documents.push(json_object(&mut rng, 0));
+    }
+
+    let empty = Valset::default();
+    for document in documents.iter() {
+        if let Ok(valset) = serde_json::from_str::<Valset>(document) {
+            exercise_valset(&valset, &empty, &mut rng);
+        }
+        if let Ok(batch) = serde_json::from_str::<TransactionBatch>(document) {
+            let _ = batch.total_amount();
+        }
+        let _ = serde_json::from_str::<ValsetConfirmResponse>(document);
+        let _ = serde_json::from_str::<BatchConfirmResponse>(document);
+    }
+}
+
+#[test]
+fn test_parse_amount() {
+    assert_eq!(parse_amount("5").unwrap(), Uint256::from(5u8));
+    assert_eq!(parse_amount(MAX_UINT256).unwrap().to_string(), MAX_UINT256);
+    assert!(parse_amount(MAX_UINT256_PLUS_ONE).is_err());
+    assert!(parse_amount(&"9".repeat(100_000)).is_err());
+    assert!(parse_amount("-1").is_err());
+    assert!(parse_amount("").is_err());
+}
@@ -4,8 +4,11 @@ use num256::Uint256;
 mod batches;
 mod coins;
 mod ethereum_events;
+#[cfg(test)]
+mod fuzz;
 mod signatures;
 mod valsets;
+use crate::coin_math::max_uint256;
 use crate::error::PeggyError;
 
 pub use batches::*;
@@ -24,7 +27,7 @@ pub struct ERC20Token {
 impl ERC20Token {
     pub fn from_proto(input: peggy_proto::peggy::Erc20Token) -> Result<Self, PeggyError> {
         Ok(ERC20Token {
-            amount: input.amount.parse()?,
+            amount: parse_amount(&input.amount)?,
             token_contract_address: input.contract.parse()?,
         })
     }
@@ -37,3 +40,33 @@ pub struct ERC20Denominator {
     pub symbol: String,
     pub cosmos_voucher_denom: String,
 }
+
+/// Parses an amount from the Hub, refusing what doesn't fit in a uint256 since arithmetic on it would panic
+pub fn parse_amount(input: &str) -> Result<Uint256, PeggyError> {
+    // a uint256 has at most 78 digits, don't parse anything much longer
+    if input.len() > 78 {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "Amount of {} digits is above the largest uint256",
+            input.len()
+        )));
+    }
+    let amount: Uint256 = input.parse()?;
+    if amount > max_uint256() {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "Amount {} is above the largest uint256",
+            amount
+        )));
+    }
+    Ok(amount)
+}
+
+/// Refuses a Hub response listing more items than the Hub could ever produce, the Hub we query may not be honest
+pub fn check_response_len(what: &str, len: usize, max: usize) -> Result<(), PeggyError> {
+    if len > max {
+        return Err(PeggyError::InvalidBridgeStateError(format!(
+            "The Hub returned {} {}, more than the {} possible",
+            len, what, max
+        )));
+    }
+    Ok(())
+}
@@ -1,7 +1,14 @@
+use crate::error::PeggyError;
+use clarity::utils::hex_str_to_bytes;
 use clarity::Signature as EthSignature;
 use clarity::{abi::Token, Address as EthAddress};
 use num256::Uint256;
 use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// The power threshold the Peggy contract is deployed with, see contract-deployer.ts and the Hub's
+/// `power.ContractThreshold`. The contract needs strictly more than this much power to have signed
+pub const ETHEREUM_POWER_THRESHOLD: u64 = 2_834_678_415;
 
 /// A sortable struct of a validator and it's signatures
 /// this can be used for either transaction batch or validator
@@ -39,52 +46,226 @@ impl PartialOrd for PeggySignature {
     }
 }
 
+/// How signatures are encoded in confirms and contract calls. Newer contract versions also take EIP-2098
+/// compact signatures, which saves a calldata array per call.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SignatureEncoding {
+    /// 65 bytes of r, s and v, submitted as v, r and s arrays
+    Standard,
+    /// 64 bytes of r and s with the parity of v in the top bit of s, submitted as r and vs arrays
+    Compact,
+}
+
+impl Default for SignatureEncoding {
+    fn default() -> Self {
+        SignatureEncoding::Standard
+    }
+}
+
+impl SignatureEncoding {
+    /// The ABI types of the signature arrays in the contract functions
+    pub fn abi_types(self) -> &'static str {
+        match self {
+            SignatureEncoding::Standard => "uint8[],bytes32[],bytes32[]",
+            SignatureEncoding::Compact => "bytes32[],bytes32[]",
+        }
+    }
+
+    /// How many signature arrays the contract functions take
+    pub fn array_count(self) -> usize {
+        match self {
+            SignatureEncoding::Standard => 3,
+            SignatureEncoding::Compact => 2,
+        }
+    }
+}
+
+fn to_bytes32(input: &Uint256) -> Vec<u8> {
+    let bytes = input.to_bytes_be();
+    let mut out = vec![0; 32 - bytes.len()];
+    out.extend(bytes);
+    out
+}
+
+/// The `vs` word of a compact signature, s with the parity of v in its top bit
+fn compact_vs(signature: &EthSignature) -> Vec<u8> {
+    let mut vs = to_bytes32(&signature.s);
+    if signature.v == 28u8.into() {
+        vs[0] |= 0x80;
+    }
+    vs
+}
+
+/// Encodes a signature for a confirm message
+pub fn encode_signature(signature: &EthSignature, encoding: SignatureEncoding) -> Vec<u8> {
+    match encoding {
+        SignatureEncoding::Standard => signature.to_bytes().to_vec(),
+        SignatureEncoding::Compact => {
+            let mut out = to_bytes32(&signature.r);
+            out.extend(compact_vs(signature));
+            out
+        }
+    }
+}
+
+/// Decodes a hex encoded signature of a confirm message in either encoding
+pub fn decode_signature(input: &str) -> Result<EthSignature, PeggyError> {
+    let bytes = hex_str_to_bytes(input)
+        .map_err(|e| PeggyError::InvalidBridgeStateError(format!("Invalid signature {:?}", e)))?;
+    match bytes.len() {
+        65 => Ok(EthSignature::new(
+            bytes[64].into(),
+            Uint256::from_bytes_be(&bytes[0..32]),
+            Uint256::from_bytes_be(&bytes[32..64]),
+        )),
+        64 => {
+            let mut s = bytes[32..64].to_vec();
+            let parity = s[0] >> 7;
+            s[0] &= 0x7f;
+            Ok(EthSignature::new(
+                (27 + parity).into(),
+                Uint256::from_bytes_be(&bytes[0..32]),
+                Uint256::from_bytes_be(&s),
+            ))
+        }
+        other => Err(PeggyError::InvalidBridgeStateError(format!(
+            "Invalid signature length {}, expected 65 or 64 bytes",
+            other
+        ))),
+    }
+}
+
 /// signatures in array formats ready to be
 /// submitted to the Peggy Ethereum Contract
 pub struct PeggySignatureArrays {
     pub addresses: Vec<EthAddress>,
     pub powers: Vec<u64>,
-    pub v: Token,
-    pub r: Token,
-    pub s: Token,
+    /// v, r and s or r and vs depending on the encoding, in the order the contract takes them
+    pub signatures: Vec<Token>,
 }
 
 /// This function handles converting the PeggySignature type into an Ethereum
 /// submittable arrays, including the finicky token encoding tricks you need to
 /// perform in order to distinguish between a uint8[] and bytes32[]
-pub fn to_arrays(input: Vec<PeggySignature>) -> PeggySignatureArrays {
+pub fn to_arrays(input: Vec<PeggySignature>, encoding: SignatureEncoding) -> PeggySignatureArrays {
     let mut addresses = Vec::new();
     let mut powers = Vec::new();
     let mut v = Vec::new();
     let mut r = Vec::new();
     let mut s = Vec::new();
     for val in input {
-        let mut r_val = val.r.to_bytes_be();
-        let mut s_val = val.s.to_bytes_be();
-
-        r_val.reverse();
-        r_val.append(&mut vec![0; 32-r_val.len() as usize]);
-        r_val.reverse();
-
-        s_val.reverse();
-        s_val.append(&mut vec![0; 32-s_val.len() as usize]);
-        s_val.reverse();
-
         addresses.push(val.eth_address);
         powers.push(val.power);
-        v.push(val.v);
-        r.push(Token::Bytes(r_val));
-        s.push(Token::Bytes(s_val));
+        r.push(Token::Bytes(to_bytes32(&val.r)));
+        match encoding {
+            SignatureEncoding::Standard => {
+                s.push(Token::Bytes(to_bytes32(&val.s)));
+                v.push(val.v);
+            }
+            SignatureEncoding::Compact => {
+                let signature = EthSignature::new(val.v, val.r, val.s);
+                s.push(Token::Bytes(compact_vs(&signature)));
+            }
+        }
     }
+    let signatures = match encoding {
+        SignatureEncoding::Standard => vec![v.into(), Token::Dynamic(r), Token::Dynamic(s)],
+        SignatureEncoding::Compact => vec![Token::Dynamic(r), Token::Dynamic(s)],
+    };
     PeggySignatureArrays {
         addresses,
         powers,
-        v: v.into(),
-        r: Token::Dynamic(r),
-        s: Token::Dynamic(s),
+        signatures,
+    }
+}
+
+/// Which of the available signatures are submitted to the contract
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SignatureSelection {
+    /// the fewest validators whose power crosses the threshold, every signature takes the same
+    /// calldata so this is also the fewest bytes
+    Minimal,
+    /// every valid signature, for contracts that don't skip zeroed signatures
+    Full,
+}
+
+impl Default for SignatureSelection {
+    fn default() -> Self {
+        SignatureSelection::Minimal
     }
 }
 
+impl FromStr for SignatureSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "minimal" => Ok(SignatureSelection::Minimal),
+            "full" => Ok(SignatureSelection::Full),
+            other => Err(format!(
+                "Invalid signature selection {}, expected minimal or full",
+                other
+            )),
+        }
+    }
+}
+
+/// Trims ordered signatures down to the ones the contract needs. Signatures are verified against
+/// `signed_hash` from the most to the least powerful validator and with `Minimal` we stop as soon
+/// as the verified power crosses `power_threshold`, the contract's `state_powerThreshold`, everything
+/// else is zeroed. The contract skips zeroed signatures, so the arrays keep the shape of the valset
+/// while the calldata and the signature checks shrink. A signature that doesn't verify would revert the whole submission, so
+/// it's dropped with either selection.
+pub fn select_signatures(
+    mut input: Vec<PeggySignature>,
+    signed_hash: &[u8],
+    selection: SignatureSelection,
+    power_threshold: u64,
+) -> Result<Vec<PeggySignature>, PeggyError> {
+    let mut by_power: Vec<usize> = (0..input.len()).collect();
+    by_power.sort_by(|a, b| input[*b].power.cmp(&input[*a].power));
+
+    let mut verified_power = 0u64;
+    for i in by_power {
+        let sig = &mut input[i];
+        if sig.v == 0u8.into() {
+            continue;
+        }
+        let needed = selection == SignatureSelection::Full || verified_power <= power_threshold;
+        let valid = needed && {
+            let signature = EthSignature::new(sig.v.clone(), sig.r.clone(), sig.s.clone());
+            // ecrecover in the contract only takes 27 and 28, anything else from the Hub can't be valid
+            let recovered = if sig.v == 27u8.into() || sig.v == 28u8.into() {
+                signature.recover(signed_hash).ok()
+            } else {
+                None
+            };
+            match recovered {
+                Some(signer) if signer == sig.eth_address => true,
+                _ => {
+                    warn!("Dropping invalid signature from {}", sig.eth_address);
+                    false
+                }
+            }
+        };
+        if valid {
+            verified_power = verified_power.saturating_add(sig.power);
+        } else {
+            sig.v = 0u8.into();
+            sig.r = 0u8.into();
+            sig.s = 0u8.into();
+        }
+    }
+
+    if verified_power <= power_threshold {
+        return Err(PeggyError::InsufficientVotingPowerToPass(format!(
+            "Only {} power has valid signatures, more than {} is required",
+            verified_power, power_threshold
+        )));
+    }
+    Ok(input)
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone, Eq, PartialEq, Hash)]
 pub struct SigWithAddress {
     pub eth_address: EthAddress,
@@ -183,4 +364,137 @@ mod tests {
         incorrect.reverse();
         assert_eq!(incorrect, correct);
     }
+
+    #[test]
+    fn test_select_signatures() {
+        use clarity::PrivateKey as EthPrivateKey;
+
+        let hash = [7u8; 32];
+        let signed = |key: u8, power: u64| {
+            let key: EthPrivateKey = format!("0x{}", format!("{:02x}", key).repeat(32))
+                .parse()
+                .unwrap();
+            let signature = key.sign_hash(&hash);
+            PeggySignature {
+                power,
+                eth_address: key.to_public_key().unwrap(),
+                v: signature.v,
+                r: signature.r,
+                s: signature.s,
+            }
+        };
+        let unsigned = |key: u8, power: u64| PeggySignature {
+            v: 0u8.into(),
+            r: 0u8.into(),
+            s: 0u8.into(),
+            ..signed(key, power)
+        };
+        let total = crate::types::TOTAL_PEGGY_POWER;
+        let input = vec![
+            signed(1, total / 10),
+            signed(2, total / 2),
+            unsigned(3, total / 10),
+            signed(4, total / 5),
+            signed(5, total / 10),
+        ];
+
+        // 50% + 20% crosses the threshold, the 10% signatures are not needed
+        let selected = select_signatures(
+            input.clone(),
+            &hash,
+            SignatureSelection::Minimal,
+            ETHEREUM_POWER_THRESHOLD,
+        )
+        .unwrap();
+        let kept: Vec<bool> = selected.iter().map(|s| s.v != 0u8.into()).collect();
+        assert_eq!(kept, vec![false, true, false, true, false]);
+        assert_eq!(selected[1], input[1]);
+        assert_eq!(selected[0].eth_address, input[0].eth_address);
+
+        // a bad signature is dropped and the next one is used instead
+        let mut tampered = input.clone();
+        tampered[3].r = 1u8.into();
+        let selected = select_signatures(
+            tampered.clone(),
+            &hash,
+            SignatureSelection::Minimal,
+            ETHEREUM_POWER_THRESHOLD,
+        )
+        .unwrap();
+        let kept: Vec<bool> = selected.iter().map(|s| s.v != 0u8.into()).collect();
+        assert_eq!(kept, vec![true, true, false, false, true]);
+
+        // full arrays keep every valid signature
+        let selected = select_signatures(
+            input.clone(),
+            &hash,
+            SignatureSelection::Full,
+            ETHEREUM_POWER_THRESHOLD,
+        )
+        .unwrap();
+        assert_eq!(selected, input);
+        let selected = select_signatures(
+            tampered,
+            &hash,
+            SignatureSelection::Full,
+            ETHEREUM_POWER_THRESHOLD,
+        )
+        .unwrap();
+        let kept: Vec<bool> = selected.iter().map(|s| s.v != 0u8.into()).collect();
+        assert_eq!(kept, vec![true, true, false, false, true]);
+
+        assert!(select_signatures(
+            input[..2].to_vec(),
+            &hash,
+            SignatureSelection::Full,
+            ETHEREUM_POWER_THRESHOLD
+        )
+        .is_err());
+
+        // a contract deployed with a higher threshold needs the 10% signatures too
+        let threshold = total / 100 * 75;
+        let selected =
+            select_signatures(input.clone(), &hash, SignatureSelection::Minimal, threshold)
+                .unwrap();
+        let kept: Vec<bool> = selected.iter().map(|s| s.v != 0u8.into()).collect();
+        assert_eq!(kept, vec![true, true, false, true, false]);
+        assert!(
+            select_signatures(input[..4].to_vec(), &hash, SignatureSelection::Full, total).is_err()
+        );
+        assert_eq!("full".parse(), Ok(SignatureSelection::Full));
+        assert!("all".parse::<SignatureSelection>().is_err());
+    }
+
+    #[test]
+    fn test_signature_encoding() {
+        use clarity::utils::bytes_to_hex_str;
+        use clarity::PrivateKey as EthPrivateKey;
+
+        let hash = [7u8; 32];
+        let mut parities = Vec::new();
+        for key in 1u8..=8 {
+            let key: EthPrivateKey = format!("0x{}", format!("{:02x}", key).repeat(32))
+                .parse()
+                .unwrap();
+            let signature = key.sign_hash(&hash);
+            parities.push(signature.v.clone());
+
+            let standard = encode_signature(&signature, SignatureEncoding::Standard);
+            let compact = encode_signature(&signature, SignatureEncoding::Compact);
+            assert_eq!(standard.len(), 65);
+            assert_eq!(compact.len(), 64);
+            assert_eq!(standard[..32], compact[..32]);
+            for encoded in [standard, compact].iter() {
+                let decoded = decode_signature(&bytes_to_hex_str(encoded)).unwrap();
+                assert_eq!(decoded, signature);
+                assert_eq!(
+                    decoded.recover(&hash).unwrap(),
+                    key.to_public_key().unwrap()
+                );
+            }
+        }
+        // both parities of v were round tripped
+        assert!(parities.contains(&27u8.into()) && parities.contains(&28u8.into()));
+        assert!(decode_signature("0x1234").is_err());
+    }
 }
@@ -12,14 +12,46 @@ use std::{
 
 /// The total power in the Peggy bridge is normalized to u32 max every
 /// time a validator set is created. This value of up to u32 max is then
-/// stored in a u64 to prevent overflow during computation.
+/// stored in a u64 to prevent overflow during computation. It is the Hub's
+/// `power.ContractTotal`, which the normalization is defined with.
 pub const TOTAL_PEGGY_POWER: u64 = u32::MAX as u64;
 
+/// The most members a valset from the Hub may have, far more than the contract could verify the
+/// signatures of in one transaction
+pub const MAX_VALSET_MEMBERS: usize = 1000;
+
 /// takes in an amount of power in the peggy bridge, returns a percentage of total
-fn peggy_power_to_percent(input: u64) -> f32 {
+pub fn peggy_power_to_percent(input: u64) -> f32 {
     (input as f32 / TOTAL_PEGGY_POWER as f32) * 100f32
 }
 
+/// true if this much power is enough for the contract to accept a signed update or batch, checked
+/// in integers like the contract does since the percentage rounds the threshold itself up to passing
+pub fn passes_power_threshold(input: u64) -> bool {
+    input > ETHEREUM_POWER_THRESHOLD
+}
+
+/// How a valset's powers hold up against the contract's power threshold
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PowerCheck {
+    /// the contract's state_powerThreshold, signatures need strictly more power than this
+    pub threshold: u64,
+    /// the power of all members as the contract sees it
+    pub total_power: u64,
+    /// the power of members with an Ethereum address, the only ones that can ever sign
+    pub signing_power: u64,
+    /// everything about the powers that diverges from what the contract and the Hub's normalization expect
+    pub issues: Vec<String>,
+}
+
+impl PowerCheck {
+    /// Whether the valset can still pass the contract's threshold once it is relayed, if it can't the
+    /// contract would never accept another valset or batch
+    pub fn passes(&self) -> bool {
+        self.signing_power > self.threshold
+    }
+}
+
 /// the response we get when querying for a valset confirmation
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct ValsetConfirmResponse {
@@ -35,7 +67,7 @@ impl ValsetConfirmResponse {
             orchestrator: input.orchestrator.parse()?,
             eth_address: input.eth_address.parse()?,
             nonce: input.nonce,
-            eth_signature: input.signature.parse()?,
+            eth_signature: decode_signature(&input.signature)?,
         })
     }
 }
@@ -57,7 +89,7 @@ impl BatchConfirmResponse {
             orchestrator: input.orchestrator.parse()?,
             token_contract: input.token_contract.parse()?,
             ethereum_signer: input.eth_signer.parse()?,
-            eth_signature: input.signature.parse()?,
+            eth_signature: decode_signature(&input.signature)?,
         })
     }
 }
@@ -83,7 +115,7 @@ fn batch_confirms_to_hashmap(
 }
 
 /// a list of validators, powers, and eth addresses at a given block height
-#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub struct Valset {
     pub nonce: u64,
     pub members: Vec<ValsetMember>,
@@ -111,6 +143,75 @@ impl Valset {
         (addresses, powers)
     }
 
+    /// Checks the valset against the contract's power `threshold` as the contract would, summing the powers
+    /// as uint256, and against the Hub's normalization of every valset's powers to `TOTAL_PEGGY_POWER`. The
+    /// Hub truncates each member's power, so up to one unit per member goes missing from the total but
+    /// never more, and no power can be above the total.
+    pub fn power_check(&self, threshold: u64) -> PowerCheck {
+        let mut issues = Vec::new();
+        let mut total_power: u128 = 0;
+        let mut signing_power: u128 = 0;
+        for member in self.members.iter() {
+            total_power += member.power as u128;
+            match member.eth_address {
+                Some(address) => {
+                    signing_power += member.power as u128;
+                    if member.power > TOTAL_PEGGY_POWER {
+                        issues.push(format!(
+                            "{} has power {} above the normalized total {}",
+                            address, member.power, TOTAL_PEGGY_POWER
+                        ));
+                    }
+                    if member.power == 0 {
+                        issues.push(format!("{} has no power", address));
+                    }
+                }
+                None => issues.push(format!(
+                    "a member with power {} has no Ethereum address, it is relayed as the zero address and can't sign",
+                    member.power
+                )),
+            }
+        }
+        let members = self.members.len() as u128;
+        let total = TOTAL_PEGGY_POWER as u128;
+        if total_power > total {
+            issues.push(format!(
+                "total power {} is above the normalized total {}",
+                total_power, total
+            ));
+        } else if total_power + members < total {
+            issues.push(format!(
+                "total power {} is more than {} below the normalized total {}, the truncation of {} members",
+                total_power, members, total, members
+            ));
+        }
+        if threshold as u128 >= total {
+            issues.push(format!(
+                "the contract threshold {} can't be passed with the normalized total {}",
+                threshold, total
+            ));
+        }
+        if threshold != ETHEREUM_POWER_THRESHOLD {
+            issues.push(format!(
+                "the contract threshold {} differs from the {} we sign and relay for",
+                threshold, ETHEREUM_POWER_THRESHOLD
+            ));
+        }
+        let mut check = PowerCheck {
+            threshold,
+            total_power: total_power.min(u64::MAX as u128) as u64,
+            signing_power: signing_power.min(u64::MAX as u128) as u64,
+            issues,
+        };
+        if !check.passes() {
+            check.issues.push(format!(
+                "members with an Ethereum address hold {} power, not more than the contract threshold {}",
+                check.signing_power, threshold
+            ));
+        }
+        check
+    }
+
     pub fn get_power(&self, address: EthAddress) -> Result<u64, JsonRpcError> {
         for val in self.members.iter() {
             if val.eth_address == Some(address) {
@@ -142,10 +243,10 @@ impl Valset {
 
         let mut out = Vec::new();
         let signatures_hashmap = valset_confirms_to_hashmap(signatures);
-        let mut power_of_good_sigs = 0;
-        let mut power_of_unset_keys = 0;
+        let mut power_of_good_sigs = 0u64;
+        let mut power_of_unset_keys = 0u64;
         let mut number_of_unset_key_validators = 0;
-        let mut power_of_nonvoters = 0;
+        let mut power_of_nonvoters = 0u64;
         let mut number_of_nonvoters = 0;
         for member in self.members.iter() {
             if let Some(eth_address) = member.eth_address {
@@ -158,7 +259,7 @@ impl Valset {
                         r: sig.eth_signature.r.clone(),
                         s: sig.eth_signature.s.clone(),
                     });
-                    power_of_good_sigs += member.power;
+                    power_of_good_sigs = power_of_good_sigs.saturating_add(member.power);
                 } else {
                     out.push(PeggySignature {
                         power: member.power,
@@ -167,7 +268,7 @@ impl Valset {
                         r: 0u8.into(),
                         s: 0u8.into(),
                     });
-                    power_of_nonvoters += member.power;
+                    power_of_nonvoters = power_of_nonvoters.saturating_add(member.power);
                     number_of_nonvoters += 1;
                 }
             } else {
@@ -178,7 +279,7 @@ impl Valset {
                     r: 0u8.into(),
                     s: 0u8.into(),
                 });
-                power_of_unset_keys += member.power;
+                power_of_unset_keys = power_of_unset_keys.saturating_add(member.power);
                 number_of_unset_key_validators += 1;
             }
         }
@@ -187,7 +288,7 @@ impl Valset {
         // now that we have collected the signatures we can determine if the measure has the votes to pass
         // and error early if it does not, otherwise the user will pay fees for a transaction that will
         // just throw
-        if peggy_power_to_percent(power_of_good_sigs) < 66f32 {
+        if !passes_power_threshold(power_of_good_sigs) {
             let message = format!(
                 "
                 Valset {} has {}/{} or {:.2}% power voting! Can not execute on Ethereum!
@@ -233,10 +334,10 @@ impl Valset {
 
         let mut out = Vec::new();
         let signatures_hashmap = batch_confirms_to_hashmap(signatures);
-        let mut power_of_good_sigs = 0;
-        let mut power_of_unset_keys = 0;
+        let mut power_of_good_sigs = 0u64;
+        let mut power_of_unset_keys = 0u64;
         let mut number_of_unset_key_validators = 0;
-        let mut power_of_nonvoters = 0;
+        let mut power_of_nonvoters = 0u64;
         let mut number_of_nonvoters = 0;
         for member in self.members.iter() {
             if let Some(eth_address) = member.eth_address {
@@ -249,7 +350,7 @@ impl Valset {
                         r: sig.eth_signature.r.clone(),
                         s: sig.eth_signature.s.clone(),
                     });
-                    power_of_good_sigs += member.power;
+                    power_of_good_sigs = power_of_good_sigs.saturating_add(member.power);
                 } else {
                     out.push(PeggySignature {
                         power: member.power,
@@ -258,7 +359,7 @@ impl Valset {
                         r: 0u8.into(),
                         s: 0u8.into(),
                     });
-                    power_of_nonvoters += member.power;
+                    power_of_nonvoters = power_of_nonvoters.saturating_add(member.power);
                     number_of_nonvoters += 1;
                 }
             } else {
@@ -269,7 +370,7 @@ impl Valset {
                     r: 0u8.into(),
                     s: 0u8.into(),
                 });
-                power_of_unset_keys += member.power;
+                power_of_unset_keys = power_of_unset_keys.saturating_add(member.power);
                 number_of_unset_key_validators += 1;
             }
         }
@@ -278,7 +379,7 @@ impl Valset {
         // now that we have collected the signatures we can determine if the measure has the votes to pass
         // and error early if it does not, otherwise the user will pay fees for a transaction that will
         // just throw
-        if peggy_power_to_percent(power_of_good_sigs) < 66f32 {
+        if !passes_power_threshold(power_of_good_sigs) {
             let message = format!(
                 "
                 Batch {} for token {} has {}/{} or {:.2}% power voting! Can not execute on Ethereum!
@@ -308,6 +409,26 @@ impl Valset {
         }
     }
 
+    /// The power of our members that are also members of `other`, that is how much of this
+    /// valset's power is still held by validators of the other valset
+    pub fn overlap_power(&self, other: &Valset) -> u64 {
+        let others = other.to_hashset();
+        self.members
+            .iter()
+            .filter(|m| m.eth_address.map(|a| others.contains(&a)).unwrap_or(false))
+            .fold(0u64, |total, m| total.saturating_add(m.power))
+    }
+
+    /// The power of our members that signed these confirms of a newer valset. While we are the
+    /// valset in the contract this has to pass the threshold for the newer valset to be accepted
+    pub fn signed_power(&self, confirms: &[ValsetConfirmResponse]) -> u64 {
+        let signers: HashSet<EthAddress> = confirms.iter().map(|c| c.eth_address).collect();
+        self.members
+            .iter()
+            .filter(|m| m.eth_address.map(|a| signers.contains(&a)).unwrap_or(false))
+            .fold(0u64, |total, m| total.saturating_add(m.power))
+    }
+
     /// A utility function to provide a HashMap of members for easy lookups
     pub fn to_hashmap(&self) -> HashMap<EthAddress, u64> {
         let mut res = HashMap::new();
@@ -364,20 +485,28 @@ impl Valset {
             // impossible for this to panic without a failure in the logic
             // of the symmetric difference function
             let power = power.unwrap();
-            total_power_diff += power;
+            total_power_diff = total_power_diff.saturating_add(*power);
         }
         for item in intersection {
             // can't panic since there must be an entry for both.
             let power_a = a[item];
             let power_b = b[item];
             if power_a > power_b {
-                total_power_diff += power_a - power_b;
+                total_power_diff = total_power_diff.saturating_add(power_a - power_b);
             } else {
-                total_power_diff += power_b - power_a;
+                total_power_diff = total_power_diff.saturating_add(power_b - power_a);
             }
         }
 
-        (total_power_diff as f32) / (u32::MAX as f32)
+        (total_power_diff as f32) / (TOTAL_PEGGY_POWER as f32)
+    }
+}
+
+impl Valset {
+    /// A valset from a Hub query, refusing one with more members than any real valset
+    pub fn from_proto(input: peggy_proto::peggy::Valset) -> Result<Self, PeggyError> {
+        check_response_len("valset members", input.members.len(), MAX_VALSET_MEMBERS)?;
+        Ok(input.into())
     }
 }
 
@@ -475,3 +604,123 @@ impl From<&peggy_proto::peggy::BridgeValidator> for ValsetMember {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overlap_and_signed_power() {
+        let a: EthAddress = "0x479FFc856Cdfa0f5D1AE6Fa61915b01351A7773D"
+            .parse()
+            .unwrap();
+        let b: EthAddress = "0x6db48cBBCeD754bDc760720e38E456144e83269b"
+            .parse()
+            .unwrap();
+        let c: EthAddress = "0x0A7254b318dd742A3086882321C27779B4B642a6"
+            .parse()
+            .unwrap();
+        let member = |power: u64, address: EthAddress| ValsetMember {
+            power,
+            eth_address: Some(address),
+        };
+        let third = TOTAL_PEGGY_POWER / 3;
+        let old = Valset {
+            nonce: 1,
+            members: vec![member(third, a), member(third, b), member(third, c)],
+        };
+        let new = Valset {
+            nonce: 2,
+            members: vec![
+                member(TOTAL_PEGGY_POWER / 2, a),
+                member(TOTAL_PEGGY_POWER / 2, b),
+            ],
+        };
+        assert_eq!(old.overlap_power(&new), 2 * third);
+        assert!(passes_power_threshold(old.overlap_power(&new)));
+
+        let confirm = |eth_address: EthAddress| ValsetConfirmResponse {
+            eth_address,
+            nonce: 2,
+            ..Default::default()
+        };
+        assert!(!passes_power_threshold(old.signed_power(&[confirm(a)])));
+        assert!(passes_power_threshold(
+            old.signed_power(&[confirm(a), confirm(b)])
+        ));
+        assert!(!passes_power_threshold(ETHEREUM_POWER_THRESHOLD));
+        assert!(passes_power_threshold(ETHEREUM_POWER_THRESHOLD + 1));
+    }
+
+    #[test]
+    fn test_power_check() {
+        let a: EthAddress = "0x479FFc856Cdfa0f5D1AE6Fa61915b01351A7773D"
+            .parse()
+            .unwrap();
+        let b: EthAddress = "0x6db48cBBCeD754bDc760720e38E456144e83269b"
+            .parse()
+            .unwrap();
+        let third = TOTAL_PEGGY_POWER / 3;
+        let valset = |members: Vec<(u64, Option<EthAddress>)>| Valset {
+            nonce: 1,
+            members: members
+                .into_iter()
+                .map(|(power, eth_address)| ValsetMember { power, eth_address })
+                .collect(),
+        };
+
+        // truncated by the Hub's normalization, still fine
+        let check = valset(vec![(third, Some(a)), (third, Some(b)), (third, Some(a))])
+            .power_check(ETHEREUM_POWER_THRESHOLD);
+        assert!(check.passes());
+        assert!(check.issues.is_empty());
+
+        // two thirds of the power can never sign
+        let check = valset(vec![(third, Some(a)), (third, None), (third, None)])
+            .power_check(ETHEREUM_POWER_THRESHOLD);
+        assert!(!check.passes());
+        assert_eq!(check.issues.len(), 3);
+
+        // powers that weren't normalized
+        let check = valset(vec![(100, Some(a)), (200, Some(b))]).power_check(100);
+        assert!(check.passes());
+        assert_eq!(check.issues.len(), 2);
+        let check = valset(vec![(TOTAL_PEGGY_POWER, Some(a)), (1, Some(b))])
+            .power_check(ETHEREUM_POWER_THRESHOLD);
+        assert_eq!(check.issues.len(), 1);
+    }
+
+    /// The Hub's power tests check the same vectors, so both sides agree on when signatures are enough
+    #[test]
+    fn test_power_vectors() {
+        let vectors: serde_json::Value = serde_json::from_str(include_str!(
+            "../../../../chain/power/testdata/vectors.json"
+        ))
+        .unwrap();
+        assert_eq!(vectors["contract_total"], TOTAL_PEGGY_POWER);
+        assert_eq!(vectors["contract_threshold"], ETHEREUM_POWER_THRESHOLD);
+
+        for valset in vectors["valsets"].as_array().unwrap() {
+            let powers: Vec<u64> = valset["contract_powers"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|power| power.as_u64().unwrap())
+                .collect();
+            assert!(powers.iter().sum::<u64>() <= TOTAL_PEGGY_POWER);
+
+            let signed: u64 = valset["signers"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|signer| powers[signer.as_u64().unwrap() as usize])
+                .sum();
+            assert_eq!(
+                passes_power_threshold(signed),
+                valset["passes_contract"].as_bool().unwrap(),
+                "{}",
+                valset
+            );
+        }
+    }
+}
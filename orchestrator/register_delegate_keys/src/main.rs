@@ -9,11 +9,13 @@ use std::time::Duration;
 
 use clarity::PrivateKey as EthPrivateKey;
 use contact::client::Contact;
+use cosmos_peggy::eth_signer::{EthSigner, ThresholdSigner};
 use cosmos_peggy::send::update_peggy_delegate_addresses;
 use deep_space::{
     coin::Coin, private_key::PrivateKey as CosmosPrivateKey, utils::bytes_to_hex_str,
 };
 use docopt::Docopt;
+use peggy_utils::secrets::resolve_secret;
 use rand::{thread_rng, Rng};
 use url::Url;
 
@@ -22,17 +24,19 @@ struct Args {
     flag_validator_phrase: String,
     flag_cosmos_phrase: Option<String>,
     flag_ethereum_key: Option<String>,
+    flag_threshold_signer: Option<String>,
     flag_cosmos_rpc: String,
     flag_fees: String,
 }
 
 lazy_static! {
     pub static ref USAGE: String = format!(
-        "Usage: {} --validator-phrase=<key> [--ethereum-key=<key>] --cosmos-phrase=<key> --cosmos-rpc=<url> --fees=<denom>
+        "Usage: {} --validator-phrase=<key> [--ethereum-key=<key> | --threshold-signer=<url>] --cosmos-phrase=<key> --cosmos-rpc=<url> --fees=<denom>
         Options:
             -h --help                     Show this screen.
             --validator-phrase=<vkey>    The Cosmos private key of the validator. Must be saved when you generate your key
             --ethereum-key=<ekey>     (Optional) The Ethereum private key to register, will be generated if not provided
+            --threshold-signer=<url>  (Optional) Register the address of the key shared by this threshold signer instead
             --cosmos-phrase=<ckey>    (Optional) The phrase for the Cosmos key to register, will be generated if not provided.
             --cosmos-rpc=<curl>       The Cosmos Legacy RPC url, usually the validator. This will need to be manually enabled
             --fees=<denom>            The Cosmos Denom in which to pay Cosmos chain fees
@@ -41,6 +45,8 @@ lazy_static! {
             generate an Ethereum key that will be used to sign messages on behalf of your Validator
             on the Cosmos blockchain running the Peggy module. Be aware this Ethereum key must be kept
             safe as you can be slashed for losing it.
+            Keys and phrases can be given as they are or as env:<var>, file:<path>, gpg:<path>,
+            vault:<path>#<field>, aws-sm:<secret id> or aws-kms:<path> references to where they are kept.
             Written By: {}
             Version {}",
         env!("CARGO_PKG_NAME"),
@@ -51,6 +57,13 @@ lazy_static! {
 
 const TIMEOUT: Duration = Duration::from_secs(60);
 
+/// Resolves a key or phrase that may be a reference to a secret backend
+async fn secret(value: &str) -> String {
+    resolve_secret(value)
+        .await
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
 #[actix_rt::main]
 async fn main() {
     env_logger::init();
@@ -61,9 +74,11 @@ async fn main() {
     let args: Args = Docopt::new(USAGE.as_str())
         .and_then(|d| d.deserialize())
         .unwrap_or_else(|e| e.exit());
-    let validator_key = CosmosPrivateKey::from_phrase(&args.flag_validator_phrase, "")
+    let validator_phrase = secret(&args.flag_validator_phrase).await;
+    let validator_key = CosmosPrivateKey::from_phrase(&validator_phrase, "")
         .expect("Failed to parse validator key");
     let cosmos_key = if let Some(cosmos_phrase) = args.flag_cosmos_phrase {
+        let cosmos_phrase = secret(&cosmos_phrase).await;
         CosmosPrivateKey::from_phrase(&cosmos_phrase, "").expect("Failed to parse cosmos key")
     } else {
         let mut rng = thread_rng();
@@ -76,8 +91,18 @@ async fn main() {
         );
         key
     };
-    let ethereum_key = if let Some(key) = args.flag_ethereum_key {
-        key.parse().expect("Invalid Ethereum Private key!")
+    let ethereum_address = if let Some(url) = args.flag_threshold_signer {
+        let _ = Url::parse(&url).expect("Invalid threshold signer url");
+        ThresholdSigner::connect(url.trim_end_matches('/'), TIMEOUT)
+            .await
+            .expect("Failed to get the address of the threshold signer")
+            .address()
+    } else if let Some(key) = args.flag_ethereum_key {
+        let key: EthPrivateKey = secret(&key)
+            .await
+            .parse()
+            .expect("Invalid Ethereum Private key!");
+        key.to_public_key().unwrap()
     } else {
         let mut rng = thread_rng();
         let key: [u8; 32] = rng.gen();
@@ -87,7 +112,7 @@ async fn main() {
             key,
             key.to_public_key().unwrap()
         );
-        key
+        key.to_public_key().unwrap()
     };
     let cosmos_url = Url::parse(&args.flag_cosmos_rpc).expect("Invalid Cosmos RPC url");
     let cosmos_url = cosmos_url.to_string();
@@ -100,7 +125,6 @@ async fn main() {
         amount: 1u64.into(),
     };
 
-    let ethereum_address = ethereum_key.to_public_key().unwrap();
     let cosmos_address = cosmos_key.to_public_key().unwrap().to_address();
     update_peggy_delegate_addresses(
         &contact,
@@ -112,10 +136,8 @@ async fn main() {
     .await
     .expect("Failed to update Eth address");
 
-    let eth_address = ethereum_key.to_public_key().unwrap();
-    let cosmos_address = cosmos_key.to_public_key().unwrap().to_address();
     println!(
         "Registered Ethereum address {} for validator address {}",
-        eth_address, cosmos_address
+        ethereum_address, cosmos_address
     )
 }
@@ -1,102 +1,198 @@
 //! This module contains code for the batch update lifecycle. Functioning as a way for this validator to observe
 //! the state of both chains and perform the required operations.
 
+use crate::destination_check::DestinationCheck;
 use crate::find_latest_valset::find_latest_valset;
+use crate::main_loop::{RelayedTx, RelayerControls};
+use crate::profitability::ProfitCheck;
+use crate::sponsorship::Sponsors;
 use clarity::address::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
+use clarity::Uint256;
 use cosmos_peggy::query::get_latest_transaction_batches;
 use cosmos_peggy::query::get_transaction_batch_signatures;
+use ethereum_peggy::dry_run::DryRunOutput;
 use ethereum_peggy::submit_batch::send_eth_transaction_batch;
 use ethereum_peggy::utils::get_tx_batch_nonce;
+use futures::future::join_all;
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::redact::Sensitive;
+use peggy_utils::types::{SignatureSelection, TransactionBatch};
+use std::collections::HashMap;
 use std::ops::Add;
 use std::time::Duration;
 use tonic::transport::Channel;
 use web30::client::Web3;
 
 /// Check the last validator set on Ethereum, if it's lower than our latest validator
-/// set then we should package and submit the update as an Ethereum transaction
+/// set then we should package and submit the update as an Ethereum transaction. Batches the `controls`
+/// skip, or whose token they disable, are never submitted, the ones still waiting on the Hub are returned
+/// as (token contract, nonce) so they can be reported. In marketplace mode only requested batches are
+/// considered at all, and unless they are sponsored only while `profit_check` finds them worth the gas, the
+/// others are returned with the skipped ones. With a `dry_run` the batches are written there instead of sent.
+/// The batches we send are added to `relayed`, with the transfers `destinations` finds risky
+#[allow(clippy::too_many_arguments)]
 pub async fn relay_batches(
     ethereum_key: EthPrivateKey,
     web3: &Web3,
     mut grpc_client: &mut PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
     timeout: Duration,
-) {
+    controls: &RelayerControls,
+    profit_check: Option<&ProfitCheck>,
+    destinations: &DestinationCheck,
+    signatures: SignatureSelection,
+    mut dry_run: Option<&mut DryRunOutput>,
+    relayed: &mut Vec<RelayedTx>,
+) -> Vec<(EthAddress, u64)> {
     let our_ethereum_address = ethereum_key.to_public_key().unwrap();
+    let mut skipped = Vec::new();
 
     let latest_batches = get_latest_transaction_batches(grpc_client).await;
     trace!("Latest batches {:?}", latest_batches);
     if latest_batches.is_err() {
-        return;
+        return skipped;
     }
     let mut latest_batches = latest_batches.unwrap();
-    latest_batches.sort();
-    //latest_batches.reverse();
 
     let nonce = web3.eth_get_transaction_count(our_ethereum_address).await;
     if nonce.is_err() {
-        return;
+        return skipped;
     }
     let nonce = nonce.unwrap();
 
     let mut i = 0u32;
 
-    for batch in latest_batches {
-        let sigs =
-            get_transaction_batch_signatures(grpc_client, batch.nonce, batch.token_contract).await;
-        trace!("Got sigs {:?}", sigs);
-        if let Ok(sigs) = sigs {
-            // todo check that enough people have signed
+    if let Some(requested) = controls.requested_batches.as_ref() {
+        latest_batches.retain(|batch| {
+            requested.contains(&(batch.token_contract, batch.nonce))
+                || controls.sponsors.covers(batch)
+        });
+        if let Some(profit_check) = profit_check {
+            let mut profitable = Vec::new();
+            for batch in latest_batches {
+                if controls.sponsors.covers(&batch) {
+                    profitable.push(batch);
+                    continue;
+                }
+                match profit_check.is_profitable(&batch).await {
+                    Ok(true) => profitable.push(batch),
+                    Ok(false) => skipped.push((batch.token_contract, batch.nonce)),
+                    Err(e) => warn!(
+                        "Not relaying requested batch {}:{}, failed to check its fees with {:?}",
+                        batch.token_contract, batch.nonce, e
+                    ),
+                }
+            }
+            latest_batches = profitable;
+        }
+    }
 
-            let erc20_contract = batch.token_contract;
-            let latest_ethereum_batch = get_tx_batch_nonce(
-                peggy_contract_address,
-                erc20_contract,
+    let (skipped_here, latest_batches): (Vec<_>, Vec<_>) =
+        latest_batches.into_iter().partition(|batch| {
+            (controls.disabled_tokens.contains(&batch.token_contract)
+                && !controls.sponsors.covers(batch))
+                || controls
+                    .skipped_batches
+                    .contains(&(batch.token_contract, batch.nonce))
+        });
+    skipped.extend(skipped_here.iter().map(|b| (b.token_contract, b.nonce)));
+
+    let mut ethereum_nonces = HashMap::new();
+    for batch in latest_batches.iter() {
+        if ethereum_nonces.contains_key(&batch.token_contract) {
+            continue;
+        }
+        match get_tx_batch_nonce(
+            peggy_contract_address,
+            batch.token_contract,
+            our_ethereum_address,
+            web3,
+        )
+        .await
+        {
+            Ok(nonce) => {
+                ethereum_nonces.insert(batch.token_contract, nonce);
+            }
+            Err(e) => error!(
+                "Failed to get latest Ethereum batch for {} with {:?}",
+                batch.token_contract, e
+            ),
+        }
+    }
+    let latest_batches = batches_to_relay(latest_batches, &ethereum_nonces);
+
+    // fetch the confirms for every batch at once rather than one round trip after another
+    let all_sigs =
+        join_all(latest_batches.iter().map(|batch| {
+            let mut grpc_client = grpc_client.clone();
+            let (nonce, token_contract) = (batch.nonce, batch.token_contract);
+            async move {
+                get_transaction_batch_signatures(&mut grpc_client, nonce, token_contract).await
+            }
+        }))
+        .await;
+
+    for (batch, sigs) in latest_batches.into_iter().zip(all_sigs) {
+        trace!("Got sigs {:?}", Sensitive(&sigs));
+        if let Ok(sigs) = sigs {
+            // whether enough of the valset has signed is checked when the call is encoded, a batch
+            // without enough signatures fails there and isn't sent
+            info!(
+                "We have detected latest batch {} but latest on Ethereum is {} sending an update!",
+                batch.nonce, ethereum_nonces[&batch.token_contract]
+            );
+            let current_valset = find_latest_valset(
+                &mut grpc_client,
                 our_ethereum_address,
+                peggy_contract_address,
                 web3,
             )
             .await;
-            if latest_ethereum_batch.is_err() {
-                error!(
-                    "Failed to get latest Ethereum batch with {:?}",
-                    latest_ethereum_batch
-                );
-            }
-            let latest_ethereum_batch = latest_ethereum_batch.unwrap();
-
-            if batch.clone().nonce > latest_ethereum_batch {
-                info!(
-                    "We have detected latest batch {} but latest on Ethereum is {} sending an update!",
-                    batch.clone().nonce, latest_ethereum_batch
-                );
-                let current_valset = find_latest_valset(
-                    &mut grpc_client,
-                    our_ethereum_address,
-                    peggy_contract_address,
+            if let Ok(current_valset) = current_valset {
+                let current_nonce = nonce.clone().add(i.clone().into());
+                info!("Sending eth tx with nonce {}", current_nonce);
+
+                let sponsored = controls.sponsors.covers(&batch);
+                let risky_destinations = destinations.check(&batch).await;
+                destinations.warn(&batch, &risky_destinations);
+                let res = send_eth_transaction_batch(
+                    current_valset,
+                    batch.clone(),
+                    &sigs,
                     web3,
+                    timeout,
+                    peggy_contract_address,
+                    ethereum_key,
+                    current_nonce,
+                    signatures,
+                    dry_run.as_deref_mut(),
                 )
                 .await;
-                if let Ok(current_valset) = current_valset {
-                    let current_nonce = nonce.clone().add(i.clone().into());
-                    info!("Sending eth tx with nonce {}", current_nonce);
-
-                    let _res = send_eth_transaction_batch(
-                        current_valset,
-                        batch,
-                        &sigs,
-                        web3,
-                        timeout,
-                        peggy_contract_address,
-                        ethereum_key,
-                        current_nonce,
-                    )
-                    .await;
-
-                    i += 1;
-                } else {
-                    error!("Failed to find latest valset with {:?}", current_valset);
+                match res {
+                    Ok(Some(tx_hash)) => {
+                        if sponsored {
+                            record_sponsored(web3, &controls.sponsors, &batch, tx_hash.clone())
+                                .await;
+                        }
+                        relayed.push(RelayedTx::Batch {
+                            token_contract: batch.token_contract,
+                            nonce: batch.nonce,
+                            tx_hash,
+                            risky_destinations,
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => error!(
+                        "Failed to relay batch {}:{} with {:?}",
+                        batch.token_contract, batch.nonce, e
+                    ),
                 }
+
+                i += 1;
+            } else {
+                error!("Failed to find latest valset with {:?}", current_valset);
             }
         } else {
             error!(
@@ -105,4 +201,175 @@ pub async fn relay_batches(
             );
         }
     }
+    skipped
+}
+
+/// The batches the contract would still accept, oldest first so a newer batch of a token doesn't make an older
+/// one revert. `ethereum_nonces` are the contract's last batch nonce per token, batches of tokens missing there
+/// are left out
+pub fn batches_to_relay(
+    mut batches: Vec<TransactionBatch>,
+    ethereum_nonces: &HashMap<EthAddress, u64>,
+) -> Vec<TransactionBatch> {
+    batches.retain(|batch| match ethereum_nonces.get(&batch.token_contract) {
+        Some(nonce) => batch.nonce > *nonce,
+        None => false,
+    });
+    batches.sort();
+    batches
+}
+
+/// Parses comma separated `<erc20>:<batch nonce>` pairs of batches that must not be relayed
+pub fn parse_skipped_batches(input: &str) -> Result<Vec<(EthAddress, u64)>, String> {
+    let mut out = Vec::new();
+    for entry in input.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let mut parts = entry.splitn(2, ':');
+        let (token, nonce) = match (parts.next(), parts.next()) {
+            (Some(token), Some(nonce)) => (token, nonce),
+            _ => return Err(format!("Invalid batch {}, expected <erc20>:<nonce>", entry)),
+        };
+        let token: EthAddress = token
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid token address {}: {:?}", token, e))?;
+        let nonce: u64 = nonce
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid batch nonce {}: {:?}", nonce, e))?;
+        out.push((token, nonce));
+    }
+    Ok(out)
+}
+
+/// Parses a comma separated list of token contracts
+pub fn parse_tokens(input: &str) -> Result<Vec<EthAddress>, String> {
+    input
+        .split(',')
+        .map(|token| token.trim())
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            token
+                .parse()
+                .map_err(|e| format!("Invalid token address {}: {:?}", token, e))
+        })
+        .collect()
+}
+
+/// Writes a sponsored batch we relayed to the sponsors' ledger, the gas price is taken from the mined
+/// transaction since it's set by our node
+async fn record_sponsored(
+    web3: &Web3,
+    sponsors: &Sponsors,
+    batch: &TransactionBatch,
+    tx_hash: Uint256,
+) {
+    let (gas_price, gas_limit) = match web3.eth_get_transaction_by_hash(tx_hash.clone()).await {
+        Ok(Some(tx)) => (tx.gas_price, tx.gas),
+        res => {
+            error!(
+                "Failed to get sponsored tx {}, it's not in the ledger {:?}",
+                explorer::tx(Chain::Ethereum, format!("{:#066x}", tx_hash)),
+                res
+            );
+            return;
+        }
+    };
+    let relays = sponsors.relays(batch, &tx_hash, &gas_price, &gas_limit);
+    for relay in relays.iter() {
+        info!(
+            "Relayed batch {}:{} sponsored by {} for {} of {} transfers",
+            relay.token_contract,
+            relay.batch_nonce,
+            relay.sponsor,
+            relay.transfers,
+            relay.batch_transfers
+        );
+    }
+    if let Err(e) = sponsors.record(&relays) {
+        error!(
+            "Failed to record sponsored batch {}:{} {}",
+            batch.token_contract, batch.nonce, e
+        );
+    }
+}
+
+#[test]
+fn test_batches_to_relay() {
+    use clarity::PrivateKey as EthPrivateKey;
+    use deep_space::address::Address as CosmosAddress;
+    use ethereum_peggy::contract_model::{PeggyContractModel, Revert};
+    use peggy_utils::types::{
+        BatchConfirmResponse, ERC20Token, Valset, ValsetMember, ETHEREUM_POWER_THRESHOLD,
+    };
+
+    let address = |i: u8| EthAddress::from_slice(&[i; 20]).unwrap();
+    let valset = Valset {
+        nonce: 1,
+        members: (1..=4)
+            .map(|i| ValsetMember {
+                power: 1_000_000_000,
+                eth_address: Some(address(i)),
+            })
+            .collect(),
+    };
+    let signature = EthPrivateKey::from_slice(&[1u8; 32])
+        .unwrap()
+        .sign_ethereum_msg(b"confirm");
+    let batch = |token: EthAddress, nonce: u64| TransactionBatch {
+        nonce,
+        transactions: Vec::new(),
+        total_fee: ERC20Token {
+            amount: 0u8.into(),
+            token_contract_address: token,
+        },
+        token_contract: token,
+        block: 0,
+    };
+    let confirms = |batch: &TransactionBatch| -> Vec<BatchConfirmResponse> {
+        (1..=4)
+            .map(|i| BatchConfirmResponse {
+                nonce: batch.nonce,
+                orchestrator: CosmosAddress::from_bytes([i; 20]),
+                token_contract: batch.token_contract,
+                ethereum_signer: address(i),
+                eth_signature: signature.clone(),
+            })
+            .collect()
+    };
+    let (a, b, unknown) = (address(10), address(11), address(12));
+    let hub = vec![
+        batch(a, 5),
+        batch(b, 2),
+        batch(a, 3),
+        batch(a, 4),
+        batch(unknown, 1),
+    ];
+
+    // for every state of the contract the planned batches all go through in order and the rest would revert
+    for nonce_a in 0..=6 {
+        for nonce_b in 0..=3 {
+            let ethereum_nonces: HashMap<EthAddress, u64> =
+                vec![(a, nonce_a), (b, nonce_b)].into_iter().collect();
+            let mut contract = PeggyContractModel::new(valset.clone(), ETHEREUM_POWER_THRESHOLD);
+            contract.batch_nonces = ethereum_nonces.clone();
+
+            let planned = batches_to_relay(hub.clone(), &ethereum_nonces);
+            assert!(planned.iter().all(|batch| batch.token_contract != unknown));
+            for batch in hub.iter().filter(|b| b.token_contract != unknown) {
+                if !planned.contains(batch) {
+                    assert_eq!(
+                        contract
+                            .clone()
+                            .submit_batch(batch, &valset, &confirms(batch)),
+                        Err(Revert::BatchNonceNotIncreasing)
+                    );
+                }
+            }
+            for batch in planned.iter() {
+                contract
+                    .submit_batch(batch, &valset, &confirms(batch))
+                    .unwrap();
+            }
+        }
+    }
 }
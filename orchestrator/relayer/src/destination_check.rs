@@ -0,0 +1,220 @@
+//! Spots withdrawals whose tokens would be lost on arrival. The Hub only checks that a destination is a well
+//! formed address, so a batch pays out to whatever users typed. Tokens sent to a burn address, to the token
+//! contract itself or to the Peggy contract can never be moved again, and a contract destination only gets
+//! them back out if it was written to handle that token. Before a batch is relayed its destinations are
+//! checked, each risky one is logged once per batch and journaled with the relay so operators can reach the
+//! user before they come asking. The batch is still relayed, the Hub burned the vouchers when it was built and
+//! relaying is the only way to deliver the rest of it.
+//!
+//! Whether an address holds code is cached, it would be an `eth_getCode` round trip per transfer otherwise.
+
+use clarity::Address as EthAddress;
+use ethereum_peggy::abi_drift::get_code;
+use peggy_utils::bounded_cache::BoundedCache;
+use peggy_utils::endpoints::{Endpoints, Purpose};
+use peggy_utils::error::PeggyError;
+use peggy_utils::explorer::{self, Chain};
+use peggy_utils::types::TransactionBatch;
+use std::fmt;
+use std::time::Duration;
+
+/// Addresses tokens are commonly sent to in order to burn them
+pub const BURN_ADDRESSES: [&str; 2] = [
+    "0x0000000000000000000000000000000000000000",
+    "0x000000000000000000000000000000000000dEaD",
+];
+/// How many addresses we remember holding code or not
+const CODE_CACHE_ENTRIES: usize = 4096;
+/// How many batches we remember having warned about
+const WARNED_BATCHES: usize = 256;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum DestinationRisk {
+    BurnAddress,
+    /// the batch's token contract, which can't move tokens it holds itself
+    TokenContract,
+    /// tokens sent to the Peggy contract are locked without a deposit event to claim them
+    PeggyContract,
+    /// any other contract, the tokens are stuck unless it can transfer them
+    Contract,
+}
+
+impl DestinationRisk {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DestinationRisk::BurnAddress => "burn_address",
+            DestinationRisk::TokenContract => "token_contract",
+            DestinationRisk::PeggyContract => "peggy_contract",
+            DestinationRisk::Contract => "contract",
+        }
+    }
+}
+
+impl fmt::Display for DestinationRisk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DestinationRisk::BurnAddress => write!(f, "a burn address"),
+            DestinationRisk::TokenContract => {
+                write!(f, "the token contract, which can't move its own tokens")
+            }
+            DestinationRisk::PeggyContract => {
+                write!(f, "the Peggy contract, which locks them without a deposit")
+            }
+            DestinationRisk::Contract => write!(
+                f,
+                "a contract, the tokens are stuck unless it can transfer them"
+            ),
+        }
+    }
+}
+
+/// A transfer of a batch to a destination that likely can't use the tokens
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RiskyDestination {
+    pub transfer_id: u64,
+    pub destination: EthAddress,
+    pub risk: DestinationRisk,
+}
+
+/// The risk of `destination` that is known without asking a node
+pub fn known_risk(
+    destination: EthAddress,
+    token_contract: EthAddress,
+    peggy_contract_address: EthAddress,
+) -> Option<DestinationRisk> {
+    if BURN_ADDRESSES
+        .iter()
+        .any(|burn| burn.parse::<EthAddress>().ok() == Some(destination))
+    {
+        Some(DestinationRisk::BurnAddress)
+    } else if destination == token_contract {
+        Some(DestinationRisk::TokenContract)
+    } else if destination == peggy_contract_address {
+        Some(DestinationRisk::PeggyContract)
+    } else {
+        None
+    }
+}
+
+pub struct DestinationCheck {
+    ethereum_endpoints: Endpoints,
+    peggy_contract_address: EthAddress,
+    timeout: Duration,
+    has_code: BoundedCache<EthAddress, bool>,
+    warned: BoundedCache<(EthAddress, u64), ()>,
+}
+
+impl DestinationCheck {
+    pub fn new(
+        ethereum_endpoints: Endpoints,
+        peggy_contract_address: EthAddress,
+        timeout: Duration,
+    ) -> Self {
+        DestinationCheck {
+            ethereum_endpoints,
+            peggy_contract_address,
+            timeout,
+            has_code: BoundedCache::new(CODE_CACHE_ENTRIES),
+            warned: BoundedCache::new(WARNED_BATCHES),
+        }
+    }
+
+    /// The risky destinations of `batch`, destinations we failed to check are left out
+    pub async fn check(&self, batch: &TransactionBatch) -> Vec<RiskyDestination> {
+        let mut risky = Vec::new();
+        for tx in batch.transactions.iter() {
+            let risk = match known_risk(
+                tx.destination,
+                batch.token_contract,
+                self.peggy_contract_address,
+            ) {
+                Some(risk) => Some(risk),
+                None => match self.has_code(tx.destination).await {
+                    Ok(true) => Some(DestinationRisk::Contract),
+                    Ok(false) => None,
+                    Err(e) => {
+                        warn!(
+                            "Failed to check if {} is a contract {:?}",
+                            tx.destination, e
+                        );
+                        None
+                    }
+                },
+            };
+            if let Some(risk) = risk {
+                risky.push(RiskyDestination {
+                    transfer_id: tx.id,
+                    destination: tx.destination,
+                    risk,
+                });
+            }
+        }
+        risky
+    }
+
+    async fn has_code(&self, address: EthAddress) -> Result<bool, PeggyError> {
+        if let Some(has_code) = self.has_code.get(&address) {
+            return Ok(has_code);
+        }
+        let eth_url = self.ethereum_endpoints.select(Purpose::TimeCritical);
+        let has_code = !get_code(&eth_url, address, self.timeout).await?.is_empty();
+        self.has_code.insert(address, has_code);
+        Ok(has_code)
+    }
+
+    /// Logs the risky destinations of `batch`, only the first time the batch is seen
+    pub fn warn(&self, batch: &TransactionBatch, risky: &[RiskyDestination]) {
+        let key = (batch.token_contract, batch.nonce);
+        if risky.is_empty() || self.warned.get(&key).is_some() {
+            return;
+        }
+        self.warned.insert(key, ());
+        for destination in risky {
+            warn!(
+                "Batch {}:{} sends transfer {} to {}, {}",
+                batch.token_contract,
+                batch.nonce,
+                destination.transfer_id,
+                explorer::address(Chain::Ethereum, destination.destination),
+                destination.risk
+            );
+        }
+    }
+}
+
+#[test]
+fn test_known_risk() {
+    let token: EthAddress = "0x0101010101010101010101010101010101010101"
+        .parse()
+        .unwrap();
+    let peggy: EthAddress = "0x0202020202020202020202020202020202020202"
+        .parse()
+        .unwrap();
+    let user: EthAddress = "0x0303030303030303030303030303030303030303"
+        .parse()
+        .unwrap();
+    let zero: EthAddress = "0x0000000000000000000000000000000000000000"
+        .parse()
+        .unwrap();
+    let dead: EthAddress = "0x000000000000000000000000000000000000dead"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        known_risk(dead, token, peggy),
+        Some(DestinationRisk::BurnAddress)
+    );
+    assert_eq!(
+        known_risk(zero, token, peggy),
+        Some(DestinationRisk::BurnAddress)
+    );
+    assert_eq!(
+        known_risk(token, token, peggy),
+        Some(DestinationRisk::TokenContract)
+    );
+    assert_eq!(
+        known_risk(peggy, token, peggy),
+        Some(DestinationRisk::PeggyContract)
+    );
+    // whether an ordinary address is a contract takes a node to tell
+    assert_eq!(known_risk(user, token, peggy), None);
+}
@@ -1,6 +1,10 @@
 pub mod batch_relaying;
+pub mod destination_check;
 pub mod find_latest_valset;
 pub mod main_loop;
+pub mod profitability;
+pub mod request_api;
+pub mod sponsorship;
 pub mod valset_relaying;
 
 #[macro_use]
@@ -1,15 +1,35 @@
+use crate::batch_relaying::{parse_skipped_batches, parse_tokens};
 use crate::main_loop::relayer_main_loop;
-use crate::main_loop::LOOP_SPEED;
+use crate::main_loop::RelayerControls;
+use crate::profitability::ProfitCheck;
+use crate::request_api::{run_request_api, tls_acceptor, ApiKeys, RequestApi, RequestBook};
+use crate::sponsorship::Sponsors;
+use crate::valset_relaying::parse_overlap_percent;
 use clarity::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
+use contact::client::Contact;
 use docopt::Docopt;
+use ethereum_peggy::abi_drift::check_contract_abi;
+use futures::future::join;
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::endpoints::{parse_urls, probe_loop, Endpoints, Purpose};
+use peggy_utils::explorer::{set_explorer, Chain};
+use peggy_utils::redact::set_log_sensitive;
+use peggy_utils::secrets::resolve_secret;
+use peggy_utils::timings::Timings;
+use peggy_utils::types::SignatureSelection;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
 use url::Url;
-use web30::client::Web3;
 
 pub mod batch_relaying;
+pub mod destination_check;
 pub mod find_latest_valset;
 pub mod main_loop;
+pub mod profitability;
+pub mod request_api;
+pub mod sponsorship;
 pub mod valset_relaying;
 
 #[macro_use]
@@ -26,23 +46,61 @@ struct Args {
     flag_cosmos_grpc: String,
     flag_ethereum_rpc: String,
     flag_contract_address: String,
+    flag_log_sensitive: bool,
+    flag_ethereum_explorer: Option<String>,
+    flag_skip_batches: Option<String>,
+    flag_disable_tokens: Option<String>,
+    flag_sponsors: Option<String>,
+    flag_request_api: Option<String>,
+    flag_request_api_keys: Option<String>,
+    flag_tls_cert: Option<String>,
+    flag_tls_key: Option<String>,
+    flag_signatures: Option<String>,
+    flag_timings: Option<String>,
+    flag_force_valset_relay_below: Option<String>,
+    flag_dry_run_output: Option<String>,
 }
 
 lazy_static! {
     pub static ref USAGE: String = format!(
-    "Usage: {} --ethereum-key=<key> --cosmos-legacy-rpc=<url> --cosmos-grpc=<url> --ethereum-rpc=<url> --fees=<denom> --contract-address=<addr>
+    "Usage: {} --ethereum-key=<key> --cosmos-legacy-rpc=<url> --cosmos-grpc=<url> --ethereum-rpc=<url> --fees=<denom> --contract-address=<addr> [--skip-batches=<batches>] [--disable-tokens=<erc20s>] [--sponsors=<file>] [--request-api=<addr> --request-api-keys=<file> [--tls-cert=<file> --tls-key=<file>]] [--signatures=<selection>] [--timings=<file>] [--force-valset-relay-below=<percent>] [--dry-run-output=<file>] [--log-sensitive] [--ethereum-explorer=<url>]
         Options:
             -h --help                    Show this screen.
             --ethereum-key=<ekey>        An Ethereum private key containing non-trivial funds
             --cosmos-legacy-rpc=<curl>   The Cosmos RPC url
             --cosmos-grpc=<gurl>         The Cosmos gRPC url
-            --ethereum-rpc=<eurl>        The Ethereum RPC url, Geth light clients work and sync fast
+            --ethereum-rpc=<eurl>        The Ethereum RPC url, Geth light clients work and sync fast. With several
+                                         comma separated urls the fastest one that is up to date is used
             --contract-address=<addr>    The Ethereum contract address for Peggy
+            --skip-batches=<batches>     (Optional) Comma separated <erc20>:<nonce> pairs of batches to never relay
+            --disable-tokens=<erc20s>    (Optional) Comma separated ERC20 contracts whose batches are not relayed
+            --sponsors=<file>            (Optional) Json file of sponsors paying the gas for withdrawals to their
+                                         users, those batches are always relayed and logged to a ledger
+            --request-api=<addr>         (Optional) Only relay batches requested through an HTTP API on this
+                                         host:port, to relay for third parties as a service. Requested batches
+                                         are relayed while their fees are worth the Hub oracle's withdrawal fee
+            --request-api-keys=<file>    Json file of the request API clients and their bearer tokens, tokens can
+                                         be secret references like the Ethereum key
+            --tls-cert=<file>            (Optional) PEM certificate chain to serve the request API over HTTPS with
+            --tls-key=<file>             The PEM private key of --tls-cert
+            --signatures=<selection>     (Optional) minimal to submit just enough signatures to pass, the default,
+                                         or full for contracts that need every signature
+            --timings=<file>             (Optional) Json file overriding loop intervals, timeouts and confirmations
+            --force-valset-relay-below=<percent> (Optional) Relay valset updates regardless of gas once less than
+                                         this percentage of the Ethereum valset's power is held by current Hub
+                                         validators, for example 75
+            --dry-run-output=<file>      (Optional) Make one relaying pass that writes the signed valset and batch
+                                         transactions to this file as raw hex, one per line, without sending them
+            --log-sensitive              Log raw signed transactions, signatures and calldata, for debugging only
+            --ethereum-explorer=<url>    (Optional) Ethereum explorer url template for links in logs, {{kind}} is
+                                         replaced with tx or address and {{id}} with the hash or address
         About:
             The Peggy relayer component, responsible for relaying data from the Cosmos blockchain
             to the Ethereum blockchain, cosmos key and fees are optional since they are only used
             to request the creation of batches or validator sets to relay.
             for Althea-Peggy.
+            The Ethereum key can be given as it is or as an env:<var>, file:<path>, gpg:<path>,
+            vault:<path>#<field>, aws-sm:<secret id> or aws-kms:<path> reference to where it is kept.
             Written By: {}
             Version {}",
             env!("CARGO_PKG_NAME"),
@@ -61,29 +119,163 @@ async fn main() {
     let args: Args = Docopt::new(USAGE.as_str())
         .and_then(|d| d.deserialize())
         .unwrap_or_else(|e| e.exit());
-    let ethereum_key: EthPrivateKey = args
-        .flag_ethereum_key
+    set_log_sensitive(args.flag_log_sensitive);
+    set_explorer(Chain::Ethereum, args.flag_ethereum_explorer);
+    let ethereum_key: EthPrivateKey = resolve_secret(&args.flag_ethereum_key)
+        .await
+        .unwrap_or_else(|e| panic!("{}", e))
         .parse()
         .expect("Invalid Ethereum private key!");
     let peggy_contract_address: EthAddress = args
         .flag_contract_address
         .parse()
         .expect("Invalid contract address!");
+    let controls = if args.flag_skip_batches.is_some()
+        || args.flag_disable_tokens.is_some()
+        || args.flag_sponsors.is_some()
+        || args.flag_request_api.is_some()
+    {
+        Some(Arc::new(RwLock::new(RelayerControls {
+            paused: false,
+            skipped_batches: parse_skipped_batches(args.flag_skip_batches.as_deref().unwrap_or(""))
+                .unwrap_or_else(|e| panic!("{}", e)),
+            disabled_tokens: parse_tokens(args.flag_disable_tokens.as_deref().unwrap_or(""))
+                .unwrap_or_else(|e| panic!("{}", e)),
+            requested_batches: None,
+            sponsors: match args.flag_sponsors.as_deref() {
+                Some(file) => Sponsors::load(Path::new(file)).unwrap_or_else(|e| panic!("{}", e)),
+                None => Sponsors::default(),
+            },
+        })))
+    } else {
+        None
+    };
+
+    let signatures: SignatureSelection = match args.flag_signatures {
+        Some(selection) => selection.parse().unwrap_or_else(|e| panic!("{}", e)),
+        None => SignatureSelection::default(),
+    };
+    let timings = match args.flag_timings {
+        Some(file) => Timings::load(Path::new(&file)).unwrap_or_else(|e| panic!("{}", e)),
+        None => Timings::default(),
+    };
+    let force_valset_relay_below = args
+        .flag_force_valset_relay_below
+        .map(|percent| parse_overlap_percent(&percent).unwrap_or_else(|e| panic!("{}", e)));
 
     let _ = Url::parse(&args.flag_cosmos_grpc).expect("Invalid Cosmos gRPC url");
     let cosmos_grpc_url = args.flag_cosmos_grpc.trim_end_matches('/').to_string();
 
-    let _ = Url::parse(&args.flag_ethereum_rpc).expect("Invalid Ethereum RPC url");
-    let eth_url = args.flag_ethereum_rpc.trim_end_matches('/');
+    let eth_urls = parse_urls(&args.flag_ethereum_rpc);
+    for url in eth_urls.iter() {
+        let _ = Url::parse(url).expect("Invalid Ethereum RPC url");
+    }
+
+    let request_api = match args.flag_request_api {
+        Some(bind) => {
+            let keys = args
+                .flag_request_api_keys
+                .as_deref()
+                .expect("--request-api requires --request-api-keys");
+            let api = RequestApi {
+                keys: ApiKeys::load(Path::new(keys))
+                    .await
+                    .unwrap_or_else(|e| panic!("{}", e)),
+                book: RequestBook::new(controls.clone().unwrap()),
+                cosmos_grpc: cosmos_grpc_url.clone(),
+                ethereum_rpc: eth_urls[0].clone(),
+                peggy_contract_address,
+                timeout: timings.rpc_timeout,
+            };
+            let tls = match (args.flag_tls_cert, args.flag_tls_key) {
+                (Some(cert), Some(key)) => Some(
+                    tls_acceptor(Path::new(&cert), Path::new(&key))
+                        .unwrap_or_else(|e| panic!("{}", e)),
+                ),
+                _ => None,
+            };
+            Some((bind, api, tls))
+        }
+        None => None,
+    };
 
+    // requested batches are relayed at our expense, they must still be worth the gas
+    let profit_check = if request_api.is_some() {
+        let contact = Contact::new(
+            args.flag_cosmos_legacy_rpc.trim_end_matches('/'),
+            timings.rpc_timeout,
+        );
+        let oracle_client = OracleQueryClient::connect(cosmos_grpc_url.clone())
+            .await
+            .expect("Failed to connect to the Hub oracle");
+        Some(ProfitCheck::new(contact, oracle_client))
+    } else {
+        None
+    };
     let grpc_client = PeggyQueryClient::connect(cosmos_grpc_url).await.unwrap();
-    let web3 = Web3::new(&eth_url, LOOP_SPEED);
+    let ethereum_endpoints = Endpoints::new(&eth_urls, &[], timings.rpc_timeout);
 
     let public_eth_key = ethereum_key
         .to_public_key()
         .expect("Invalid Ethereum Private Key!");
     info!("Starting Peggy Relayer");
     info!("Ethereum Address: {}", public_eth_key);
+    check_contract_abi(
+        &ethereum_endpoints.select(Purpose::TimeCritical),
+        peggy_contract_address,
+        timings.rpc_timeout,
+    )
+    .await
+    .unwrap_or_else(|e| panic!("{}", e));
+
+    if let Some(output) = args.flag_dry_run_output {
+        // a single pass, no endpoint probing or request API to keep running
+        relayer_main_loop(
+            ethereum_key,
+            ethereum_endpoints,
+            grpc_client,
+            peggy_contract_address,
+            signatures,
+            timings,
+            None,
+            controls,
+            force_valset_relay_below,
+            profit_check,
+            Some(PathBuf::from(output)),
+            None,
+        )
+        .await;
+        return;
+    }
 
-    relayer_main_loop(ethereum_key, web3, grpc_client, peggy_contract_address).await
+    let relaying = join(
+        relayer_main_loop(
+            ethereum_key,
+            ethereum_endpoints.clone(),
+            grpc_client,
+            peggy_contract_address,
+            signatures,
+            timings,
+            None,
+            controls,
+            force_valset_relay_below,
+            profit_check,
+            None,
+            None,
+        ),
+        probe_loop(ethereum_endpoints),
+    );
+    match request_api {
+        Some((bind, api, tls)) => {
+            let serving = async move {
+                if let Err(e) = run_request_api(bind, api, tls).await {
+                    panic!("Failed to run the request API: {}", e);
+                }
+            };
+            join(relaying, serving).await;
+        }
+        None => {
+            relaying.await;
+        }
+    }
 }
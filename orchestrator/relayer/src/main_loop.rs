@@ -1,49 +1,256 @@
+use crate::destination_check::{DestinationCheck, RiskyDestination};
+use crate::profitability::ProfitCheck;
+use crate::sponsorship::Sponsors;
 use crate::{batch_relaying::relay_batches, valset_relaying::relay_valsets};
 use clarity::address::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
+use clarity::Uint256;
+use ethereum_peggy::checkpoint_cache::checkpoint_cache_stats;
+use ethereum_peggy::dry_run::DryRunOutput;
+use ethereum_peggy::utils::{get_halted, get_relayer_authorization, RelayerAuthorization};
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
-use std::time::{Duration, Instant};
+use peggy_utils::endpoints::{Endpoints, Purpose};
+use peggy_utils::timings::Timings;
+use peggy_utils::types::SignatureSelection;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use tokio::time::delay_for;
 use tonic::transport::Channel;
-use web30::client::Web3;
 
-pub const LOOP_SPEED: Duration = Duration::from_secs(10);
+/// Operator overrides for the relayer, the orchestrator updates these from its admin commands
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct RelayerControls {
+    pub paused: bool,
+    /// (token contract, batch nonce) of batches we must not relay
+    pub skipped_batches: Vec<(EthAddress, u64)>,
+    /// token contracts whose batches we don't relay at all, for tokens that aren't worth the gas. Valsets
+    /// are still relayed and our orchestrator still signs these batches
+    pub disabled_tokens: Vec<EthAddress>,
+    /// in marketplace mode the (token contract, batch nonce) of the only batches we relay, the ones
+    /// requested through the request API
+    pub requested_batches: Option<Vec<(EthAddress, u64)>>,
+    /// batches to sponsored destinations are relayed even if their token is disabled or nobody requested them
+    pub sponsors: Sponsors,
+}
+
+/// An Ethereum transaction the relayer sent, it may not have been mined yet
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum RelayedTx {
+    Valset {
+        nonce: u64,
+        tx_hash: Uint256,
+    },
+    Batch {
+        token_contract: EthAddress,
+        nonce: u64,
+        tx_hash: Uint256,
+        /// transfers to destinations that likely can't use the tokens, see `destination_check`
+        risky_destinations: Vec<RiskyDestination>,
+    },
+}
+
+/// Called with every Ethereum transaction the relayer sends, the orchestrator journals them
+pub type RelayRecorder = Arc<dyn Fn(RelayedTx) + Send + Sync>;
 
 /// This function contains the orchestrator primary loop, it is broken out of the main loop so that
-/// it can be called in the test runner for easier orchestration of multi-node tests
+/// it can be called in the test runner for easier orchestration of multi-node tests. If `key_updates`
+/// is provided the key is re-read from it every loop, this is how the orchestrator rotates the relayer key.
+/// `controls` are re-read every loop the same way. `signatures` selects which confirms are submitted,
+/// `timings` how often we loop and how long we wait for our Ethereum txs. Valset updates are relayed
+/// regardless of gas once the overlap with the Hub valset is below `force_valset_relay_below` percent.
+/// Every loop relays through the fastest up to date of the `ethereum_endpoints`. With a `dry_run_output`
+/// a single pass is made that writes the signed transactions to that file instead of sending them.
+/// Every transaction sent is passed to `on_relay`. In marketplace mode requested batches are only relayed
+/// while `profit_check` finds them worth the gas.
+#[allow(clippy::too_many_arguments)]
 pub async fn relayer_main_loop(
     ethereum_key: EthPrivateKey,
-    web3: Web3,
+    ethereum_endpoints: Endpoints,
     grpc_client: PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
+    signatures: SignatureSelection,
+    timings: Timings,
+    key_updates: Option<Arc<RwLock<EthPrivateKey>>>,
+    controls: Option<Arc<RwLock<RelayerControls>>>,
+    force_valset_relay_below: Option<f32>,
+    profit_check: Option<ProfitCheck>,
+    dry_run_output: Option<PathBuf>,
+    on_relay: Option<RelayRecorder>,
 ) {
+    let mut dry_run = dry_run_output.map(|path| {
+        DryRunOutput::create(&path).unwrap_or_else(|e| panic!("Invalid dry run output {:?}", e))
+    });
+    let mut ethereum_key = ethereum_key;
     let mut grpc_client = grpc_client;
+    let mut halted = false;
+    let mut authorization = RelayerAuthorization::Unrestricted;
+    let mut paused = false;
+    let mut reported_skips = HashSet::new();
+    let mut relayed = Vec::new();
+    let destinations = DestinationCheck::new(
+        ethereum_endpoints.clone(),
+        peggy_contract_address,
+        timings.rpc_timeout,
+    );
     loop {
         let loop_start = Instant::now();
+        if let Some(key_updates) = key_updates.as_ref() {
+            ethereum_key = *key_updates.read().unwrap();
+        }
+        let current_controls = match controls.as_ref() {
+            Some(controls) => controls.read().unwrap().clone(),
+            None => RelayerControls::default(),
+        };
+        if current_controls.paused != paused {
+            if current_controls.paused {
+                warn!("Relaying paused by the operator");
+            } else {
+                info!("Relaying resumed by the operator");
+            }
+            paused = current_controls.paused;
+        }
+        if paused {
+            delay_for(timings.loop_speed).await;
+            continue;
+        }
+        let web3 = ethereum_endpoints.web3(Purpose::TimeCritical);
+
+        // updates and batches revert while the contract is halted, don't burn gas on them
+        match get_halted(peggy_contract_address, &web3).await {
+            Ok(val) => {
+                if val != halted {
+                    if val {
+                        warn!("Peggy contract is halted, pausing relaying");
+                    } else {
+                        info!("Peggy contract is no longer halted, resuming relaying");
+                    }
+                }
+                halted = val;
+            }
+            Err(e) => warn!("Failed to check if the Peggy contract is halted {:?}", e),
+        }
+        if halted {
+            if dry_run.is_some() {
+                warn!("Dry run ended, nothing is relayed while the Peggy contract is halted");
+                return;
+            }
+            delay_for(timings.loop_speed).await;
+            continue;
+        }
+
         relay_valsets(
             ethereum_key,
             &web3,
             &mut grpc_client,
             peggy_contract_address,
-            LOOP_SPEED,
+            timings.eth_tx_timeout,
+            signatures,
+            force_valset_relay_below,
+            dry_run.as_mut(),
+            &mut relayed,
         )
         .await;
 
-        relay_batches(
+        // permissioned deployments revert batches from relayers outside their whitelist
+        let our_address = ethereum_key.to_public_key().unwrap();
+        match get_relayer_authorization(peggy_contract_address, our_address, &web3).await {
+            Ok(val) => {
+                if val != authorization {
+                    match val {
+                        RelayerAuthorization::NotAuthorized => warn!(
+                            "{} is not on the contract's batch relayer whitelist, not relaying batches",
+                            our_address
+                        ),
+                        RelayerAuthorization::Authorized => {
+                            info!("{} is on the batch relayer whitelist", our_address)
+                        }
+                        RelayerAuthorization::Unrestricted => {}
+                    }
+                }
+                authorization = val;
+            }
+            Err(e) => warn!("Failed to check the batch relayer whitelist {:?}", e),
+        }
+        if authorization == RelayerAuthorization::NotAuthorized {
+            if let Some(dry_run) = dry_run.as_ref() {
+                log_dry_run(dry_run);
+                return;
+            }
+            delay_for(timings.loop_speed).await;
+            continue;
+        }
+
+        let skipped = relay_batches(
             ethereum_key,
             &web3,
             &mut grpc_client,
             peggy_contract_address,
-            LOOP_SPEED,
+            timings.eth_tx_timeout,
+            &current_controls,
+            profit_check.as_ref(),
+            &destinations,
+            signatures,
+            dry_run.as_mut(),
+            &mut relayed,
         )
         .await;
+        for tx in relayed.drain(..) {
+            if let Some(on_relay) = on_relay.as_ref() {
+                on_relay(tx);
+            }
+        }
+        for (token, nonce) in skipped {
+            if !reported_skips.insert((token, nonce)) {
+                continue;
+            }
+            if current_controls.disabled_tokens.contains(&token) {
+                info!(
+                    "Not relaying batch {}:{}, relaying is disabled for its token",
+                    token, nonce
+                );
+            } else if current_controls.skipped_batches.contains(&(token, nonce)) {
+                warn!(
+                    "Not relaying batch {}:{}, it is on the skip list",
+                    token, nonce
+                );
+            } else {
+                info!(
+                    "Not relaying requested batch {}:{}, its fees don't cover the gas at the oracle's prices",
+                    token, nonce
+                );
+            }
+        }
+        if let Some(dry_run) = dry_run.as_ref() {
+            log_dry_run(dry_run);
+            return;
+        }
+        let cache = checkpoint_cache_stats();
+        debug!(
+            "Checkpoint cache {} hits {} misses, {:.1}% hit rate, {}/{} entries {} evictions",
+            cache.hits,
+            cache.misses,
+            cache.hit_rate(),
+            cache.len,
+            cache.capacity,
+            cache.evictions
+        );
 
-        // a bit of logic that tires to keep things running every 5 seconds exactly
+        // a bit of logic that tires to keep things running every loop_speed seconds exactly
         // this is not required for any specific reason. In fact we expect and plan for
         // the timing being off significantly
         let elapsed = Instant::now() - loop_start;
-        if elapsed < LOOP_SPEED {
-            delay_for(LOOP_SPEED - elapsed).await;
+        if elapsed < timings.loop_speed {
+            delay_for(timings.loop_speed - elapsed).await;
         }
     }
 }
+
+fn log_dry_run(dry_run: &DryRunOutput) {
+    info!(
+        "Dry run done, wrote {} transactions to {}",
+        dry_run.written,
+        dry_run.path().display()
+    );
+}
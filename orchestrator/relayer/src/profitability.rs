@@ -0,0 +1,136 @@
+//! The Hub only builds a batch if its fees at the oracle price of the token are worth the oracle's fast
+//! withdrawal fee, the oracle's USD cost of relaying a batch. In marketplace mode we relay requested batches at
+//! our own expense, so they are checked against the same rule with the oracle's current numbers: a batch built
+//! when gas was cheap may no longer be worth relaying when it's requested. Sponsored batches are relayed
+//! regardless, their gas is paid by the sponsor.
+
+use clarity::Address as EthAddress;
+use contact::client::Contact;
+use cosmos_peggy::query::{get_coins, get_eth_fee, get_oracle_prices};
+use num256::Uint256;
+use peggy_proto::oracle::query_client::QueryClient as OracleQueryClient;
+use peggy_utils::amount::parse_decimal;
+use peggy_utils::error::PeggyError;
+use peggy_utils::types::TransactionBatch;
+use tonic::transport::Channel;
+
+/// The Hub values fees as if every token had this many decimals
+pub const HUB_FEE_DECIMALS: usize = 18;
+
+/// The Hub's own rule for building a batch, prices and values in the oracle's integer USD
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct HubCheck {
+    pub total_fee: Uint256,
+    /// the oracle price of the token
+    pub price: Uint256,
+    /// the batch's fees at that price
+    pub value: Uint256,
+    /// the oracle's fast withdrawal fee, the least a batch must be worth
+    pub threshold: Uint256,
+}
+
+impl HubCheck {
+    pub fn new(total_fee: &Uint256, price: Uint256, threshold: Uint256) -> Self {
+        HubCheck {
+            total_fee: total_fee.clone(),
+            value: total_fee.clone() * price.clone() / hub_fee_unit(),
+            price,
+            threshold,
+        }
+    }
+
+    pub fn accepts(&self) -> bool {
+        self.value >= self.threshold
+    }
+
+    /// How much more fee in the token the batch needs before the Hub builds it
+    pub fn shortfall(&self) -> Option<Uint256> {
+        if self.accepts() || self.price == 0u8.into() {
+            return None;
+        }
+        let one: Uint256 = 1u8.into();
+        let needed = (self.threshold.clone() * hub_fee_unit() + self.price.clone() - one)
+            / self.price.clone();
+        Some(needed - self.total_fee.clone())
+    }
+}
+
+fn hub_fee_unit() -> Uint256 {
+    parse_decimal("1", HUB_FEE_DECIMALS).unwrap()
+}
+
+/// Looks up the oracle numbers the Hub checks a batch of `token` against
+pub async fn hub_check(
+    contact: &Contact,
+    oracle_client: &mut OracleQueryClient<Channel>,
+    token: EthAddress,
+    total_fee: &Uint256,
+) -> Result<HubCheck, PeggyError> {
+    let coin = get_coins(oracle_client)
+        .await?
+        .into_iter()
+        .find(|coin| coin.eth_addr.parse::<EthAddress>().ok() == Some(token))
+        .ok_or_else(|| {
+            PeggyError::InvalidBridgeStateError(format!("{} is not a Hub coin", token))
+        })?;
+    let prices = get_oracle_prices(contact).await?;
+    let price = prices
+        .get(&format!("minter/{}", coin.minter_id))
+        .and_then(|price| parse_decimal(price, 0))
+        .ok_or_else(|| {
+            PeggyError::InvalidBridgeStateError(format!(
+                "The oracle has no price of {}",
+                coin.denom
+            ))
+        })?;
+    let fast = get_eth_fee(oracle_client).await?.fast;
+    let threshold = parse_decimal(&fast, 0).ok_or_else(|| {
+        PeggyError::InvalidBridgeStateError(format!("Invalid oracle withdrawal fee {}", fast))
+    })?;
+    Ok(HubCheck::new(total_fee, price, threshold))
+}
+
+/// Checks requested batches against the Hub oracle
+#[derive(Clone)]
+pub struct ProfitCheck {
+    contact: Contact,
+    oracle_client: OracleQueryClient<Channel>,
+}
+
+impl ProfitCheck {
+    pub fn new(contact: Contact, oracle_client: OracleQueryClient<Channel>) -> Self {
+        ProfitCheck {
+            contact,
+            oracle_client,
+        }
+    }
+
+    /// Whether the fees of `batch` are still worth relaying it
+    pub async fn is_profitable(&self, batch: &TransactionBatch) -> Result<bool, PeggyError> {
+        let mut oracle_client = self.oracle_client.clone();
+        let check = hub_check(
+            &self.contact,
+            &mut oracle_client,
+            batch.token_contract,
+            &batch.total_fee.amount,
+        )
+        .await?;
+        Ok(check.accepts())
+    }
+}
+
+#[test]
+fn test_hub_check() {
+    // 30 tokens at 2 USD are worth 60 USD, 100 USD needs 20 more
+    let whole = |amount: &str| parse_decimal(amount, HUB_FEE_DECIMALS).unwrap();
+    let usd = |amount: &str| parse_decimal(amount, 10).unwrap();
+    let check = HubCheck::new(&whole("30"), usd("2"), usd("100"));
+    assert!(!check.accepts());
+    assert_eq!(check.value, usd("60"));
+    assert_eq!(check.shortfall(), Some(whole("20")));
+    assert!(HubCheck::new(&whole("50"), usd("2"), usd("100")).accepts());
+    assert_eq!(
+        HubCheck::new(&whole("50"), 0u8.into(), usd("100")).shortfall(),
+        None
+    );
+}
@@ -0,0 +1,423 @@
+//! Marketplace mode, where this relayer submits batches on request instead of every batch it sees. With
+//! `--request-api=<host:port>` the relayer only relays the batches that third parties asked for over an
+//! authenticated HTTP endpoint, so a relayer can be run as a service for anyone who finds a batch worth
+//! submitting, validator or not. Valsets are still relayed as usual. A requested batch is only relayed while its
+//! fees are worth the gas by the Hub oracle, see `profitability`, unless a sponsor pays for it.
+//!
+//! Clients authenticate with a bearer token from the `--request-api-keys` json file of `{"<client>": "<token>"}`,
+//! where a token can also be a reference to a secret backend (see `peggy_utils::secrets`),
+//! and can attach a `tip`, free form proof of an out of band payment, which is only recorded for the operator:
+//!
+//! - `POST /batches` with `{"token_contract": "0x..", "nonce": 12, "tip": ".."}` requests a batch, it must exist
+//!   on the Hub and not have been submitted yet
+//! - `GET /batches` lists the open requests
+//!
+//! Requests expire after a day, whether or not the batch was submitted. Expired requests are dropped every
+//! `PRUNE_INTERVAL` and before the open requests are listed, so the relayer stops relaying them even when no
+//! new requests come in.
+//!
+//! With `--tls-cert` and `--tls-key` the API is served over HTTPS so the tokens can't be read off the wire, with
+//! the same TLS setup as the orchestrator's status API.
+
+use crate::main_loop::RelayerControls;
+use actix_web::http::header;
+use actix_web::{web, App, HttpRequest, HttpResponse, HttpServer};
+use clarity::Address as EthAddress;
+use cosmos_peggy::query::get_transaction_batch;
+use ethereum_peggy::utils::get_tx_batch_nonce;
+use futures::future::{select, Either};
+use openssl::ssl::{SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod};
+use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::secrets::resolve_secret;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::delay_for;
+use web30::client::Web3;
+
+/// How long a request stays open
+pub const REQUEST_TTL: u64 = 24 * 60 * 60;
+/// How often expired requests are dropped
+pub const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+/// Tokens shorter than this are too easy to guess
+const MIN_TOKEN_LEN: usize = 16;
+
+/// The clients allowed to make requests, by token
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys(Vec<(String, String)>);
+
+impl ApiKeys {
+    pub fn new(keys: HashMap<String, String>) -> Result<Self, String> {
+        let mut out = Vec::new();
+        for (client, token) in keys {
+            if token.len() < MIN_TOKEN_LEN {
+                return Err(format!(
+                    "The token of {} is shorter than {} characters",
+                    client, MIN_TOKEN_LEN
+                ));
+            }
+            out.push((client, token));
+        }
+        Ok(ApiKeys(out))
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let keys: HashMap<String, String> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        let mut resolved = HashMap::new();
+        for (client, token) in keys {
+            let token = resolve_secret(&token)
+                .await
+                .map_err(|e| format!("The token of {}: {}", client, e))?;
+            resolved.insert(client, token);
+        }
+        ApiKeys::new(resolved)
+    }
+
+    /// Returns the client an `Authorization` header belongs to
+    pub fn authorize(&self, authorization: Option<&str>) -> Option<&str> {
+        let token = authorization?.strip_prefix("Bearer ")?.trim();
+        // compare against every token in constant time so response times don't leak them
+        let mut client = None;
+        for (name, expected) in self.0.iter() {
+            if constant_time_eq(token.as_bytes(), expected.as_bytes()) {
+                client = Some(name.as_str());
+            }
+        }
+        client
+    }
+}
+
+/// Compares two tokens in time that depends only on their length
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter()
+        .zip(b.iter())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BatchRequest {
+    pub token_contract: EthAddress,
+    pub nonce: u64,
+    #[serde(default)]
+    pub tip: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct RequestedBatch {
+    pub token_contract: EthAddress,
+    pub nonce: u64,
+    pub requested_by: String,
+    pub tip: Option<String>,
+    /// unix time of the request
+    pub requested_at: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ApiError {
+    error: String,
+}
+
+fn error(message: String) -> ApiError {
+    ApiError { error: message }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// The open requests, mirrored into the relayer controls
+#[derive(Clone)]
+pub struct RequestBook {
+    requests: Arc<RwLock<Vec<RequestedBatch>>>,
+    controls: Arc<RwLock<RelayerControls>>,
+}
+
+impl RequestBook {
+    /// Puts the relayer behind `controls` into marketplace mode
+    pub fn new(controls: Arc<RwLock<RelayerControls>>) -> Self {
+        controls.write().unwrap().requested_batches = Some(Vec::new());
+        RequestBook {
+            requests: Arc::new(RwLock::new(Vec::new())),
+            controls,
+        }
+    }
+
+    /// Drops expired requests and records `request`, a batch that is already requested keeps its first request
+    pub fn add(&self, now: u64, request: RequestedBatch) -> bool {
+        let mut requests = self.requests.write().unwrap();
+        retain_open(&mut requests, now);
+        let added = !requests
+            .iter()
+            .any(|r| r.token_contract == request.token_contract && r.nonce == request.nonce);
+        if added {
+            requests.push(request);
+        }
+        self.mirror(&requests);
+        added
+    }
+
+    /// Drops expired requests
+    pub fn prune(&self, now: u64) {
+        let mut requests = self.requests.write().unwrap();
+        retain_open(&mut requests, now);
+        self.mirror(&requests);
+    }
+
+    /// The requests that are still open at `now`
+    pub fn list(&self, now: u64) -> Vec<RequestedBatch> {
+        self.prune(now);
+        self.requests.read().unwrap().clone()
+    }
+
+    fn mirror(&self, requests: &[RequestedBatch]) {
+        self.controls.write().unwrap().requested_batches = Some(
+            requests
+                .iter()
+                .map(|r| (r.token_contract, r.nonce))
+                .collect(),
+        );
+    }
+}
+
+fn retain_open(requests: &mut Vec<RequestedBatch>, now: u64) {
+    requests.retain(|r| now.saturating_sub(r.requested_at) < REQUEST_TTL);
+}
+
+/// Drops expired requests every `PRUNE_INTERVAL`
+async fn prune_loop(book: RequestBook) {
+    loop {
+        delay_for(PRUNE_INTERVAL).await;
+        book.prune(unix_now());
+    }
+}
+
+/// Everything the request handlers need, the Hub and Ethereum connections are made per request since the
+/// handlers run on the server's own threads
+#[derive(Clone)]
+pub struct RequestApi {
+    pub keys: ApiKeys,
+    pub book: RequestBook,
+    pub cosmos_grpc: String,
+    pub ethereum_rpc: String,
+    pub peggy_contract_address: EthAddress,
+    pub timeout: Duration,
+}
+
+fn authorize<'a>(api: &'a RequestApi, req: &HttpRequest) -> Option<&'a str> {
+    let authorization = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+    api.keys.authorize(authorization)
+}
+
+async fn request_batch(
+    api: web::Data<RequestApi>,
+    req: HttpRequest,
+    body: web::Json<BatchRequest>,
+) -> HttpResponse {
+    let client = match authorize(&api, &req) {
+        Some(client) => client.to_string(),
+        None => return HttpResponse::Unauthorized().json(error("Invalid token".to_string())),
+    };
+    let request = body.into_inner();
+
+    let mut grpc_client = match PeggyQueryClient::connect(api.cosmos_grpc.clone()).await {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Request API failed to connect to the Hub {:?}", e);
+            return HttpResponse::ServiceUnavailable().json(error("Hub unavailable".to_string()));
+        }
+    };
+    match get_transaction_batch(
+        &mut grpc_client,
+        request.nonce,
+        request.token_contract,
+        None,
+    )
+    .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return HttpResponse::NotFound().json(error(format!(
+                "The Hub has no batch {}:{}",
+                request.token_contract, request.nonce
+            )))
+        }
+        Err(e) => {
+            error!("Request API failed to get a batch from the Hub {:?}", e);
+            return HttpResponse::ServiceUnavailable().json(error("Hub unavailable".to_string()));
+        }
+    }
+    let web3 = Web3::new(&api.ethereum_rpc, api.timeout);
+    match get_tx_batch_nonce(
+        api.peggy_contract_address,
+        request.token_contract,
+        api.peggy_contract_address,
+        &web3,
+    )
+    .await
+    {
+        Ok(nonce) if nonce >= request.nonce => {
+            return HttpResponse::Conflict().json(error(format!(
+                "Batch {}:{} was already submitted",
+                request.token_contract, request.nonce
+            )))
+        }
+        Ok(_) => {}
+        Err(e) => {
+            error!("Request API failed to get the last batch nonce {:?}", e);
+            return HttpResponse::ServiceUnavailable()
+                .json(error("Ethereum node unavailable".to_string()));
+        }
+    }
+
+    let requested = RequestedBatch {
+        token_contract: request.token_contract,
+        nonce: request.nonce,
+        requested_by: client,
+        tip: request.tip,
+        requested_at: unix_now(),
+    };
+    if api.book.add(requested.requested_at, requested.clone()) {
+        info!(
+            "{} requested batch {}:{} with tip {:?}",
+            requested.requested_by, requested.token_contract, requested.nonce, requested.tip
+        );
+    }
+    HttpResponse::Accepted().json(requested)
+}
+
+async fn list_requests(api: web::Data<RequestApi>, req: HttpRequest) -> HttpResponse {
+    if authorize(&api, &req).is_none() {
+        return HttpResponse::Unauthorized().json(error("Invalid token".to_string()));
+    }
+    HttpResponse::Ok().json(api.book.list(unix_now()))
+}
+
+/// The TLS setup for serving the API over HTTPS with the PEM certificate chain and private key at these paths
+pub fn tls_acceptor(cert: &Path, key: &Path) -> Result<SslAcceptorBuilder, String> {
+    let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls())
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    acceptor
+        .set_private_key_file(key, SslFiletype::PEM)
+        .map_err(|e| format!("Failed to load {}: {}", key.display(), e))?;
+    acceptor
+        .set_certificate_chain_file(cert)
+        .map_err(|e| format!("Failed to load {}: {}", cert.display(), e))?;
+    acceptor
+        .check_private_key()
+        .map_err(|e| format!("{} doesn't match {}: {}", key.display(), cert.display(), e))?;
+    Ok(acceptor)
+}
+
+/// Serves the request API on `bind` until the process exits, over HTTPS with `tls`
+pub async fn run_request_api(
+    bind: String,
+    api: RequestApi,
+    tls: Option<SslAcceptorBuilder>,
+) -> std::io::Result<()> {
+    let scheme = if tls.is_some() { "https" } else { "http" };
+    info!(
+        "Relaying batches on request, request API listening on {}://{}",
+        scheme, bind
+    );
+    let pruning = prune_loop(api.book.clone());
+    let server = HttpServer::new(move || {
+        App::new()
+            .data(api.clone())
+            .route("/batches", web::get().to(list_requests))
+            .route("/batches", web::post().to(request_batch))
+    })
+    .workers(1);
+    let server = match tls {
+        Some(acceptor) => server.bind_openssl(bind, acceptor)?,
+        None => server.bind(bind)?,
+    }
+    .run();
+    // the pruning loop never ends, the server only when it fails
+    match select(Box::pin(server), Box::pin(pruning)).await {
+        Either::Left((res, _)) => res,
+        Either::Right(_) => Ok(()),
+    }
+}
+
+#[test]
+fn test_authorize() {
+    let mut keys = HashMap::new();
+    keys.insert("alice".to_string(), "alice-token-0123456789".to_string());
+    keys.insert("bob".to_string(), "bob-token-0123456789".to_string());
+    let keys = ApiKeys::new(keys).unwrap();
+
+    assert_eq!(
+        keys.authorize(Some("Bearer alice-token-0123456789")),
+        Some("alice")
+    );
+    assert_eq!(
+        keys.authorize(Some("Bearer  bob-token-0123456789 ")),
+        Some("bob")
+    );
+    assert_eq!(keys.authorize(Some("Bearer alice-token-012345678")), None);
+    assert_eq!(keys.authorize(Some("alice-token-0123456789")), None);
+    assert_eq!(keys.authorize(Some("Bearer ")), None);
+    assert_eq!(keys.authorize(None), None);
+
+    let mut short = HashMap::new();
+    short.insert("carol".to_string(), "too short".to_string());
+    assert!(ApiKeys::new(short).is_err());
+}
+
+#[test]
+fn test_constant_time_eq() {
+    assert!(constant_time_eq(b"", b""));
+    assert!(constant_time_eq(b"token", b"token"));
+    assert!(!constant_time_eq(b"token", b"tokem"));
+    assert!(!constant_time_eq(b"token", b"token "));
+    assert!(!constant_time_eq(b"", b"token"));
+}
+
+#[test]
+fn test_request_book() {
+    let token: EthAddress = "0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"
+        .parse()
+        .unwrap();
+    let request = |nonce: u64, requested_by: &str, requested_at: u64| RequestedBatch {
+        token_contract: token,
+        nonce,
+        requested_by: requested_by.to_string(),
+        tip: None,
+        requested_at,
+    };
+    let controls = Arc::new(RwLock::new(RelayerControls::default()));
+    let book = RequestBook::new(controls.clone());
+    let requested = || controls.read().unwrap().requested_batches.clone();
+    assert_eq!(requested(), Some(Vec::new()));
+
+    assert!(book.add(100, request(1, "alice", 100)));
+    assert!(book.add(200, request(2, "bob", 200)));
+    // the first request of a batch is kept
+    assert!(!book.add(300, request(1, "bob", 300)));
+    assert_eq!(book.list(300)[0].requested_by, "alice");
+    assert_eq!(requested(), Some(vec![(token, 1), (token, 2)]));
+
+    // expired requests are dropped without a new request coming in
+    book.prune(100 + REQUEST_TTL);
+    assert_eq!(requested(), Some(vec![(token, 2)]));
+    assert_eq!(book.list(200 + REQUEST_TTL), Vec::new());
+    assert_eq!(requested(), Some(Vec::new()));
+
+    // an expired batch can be requested again
+    assert!(book.add(200 + REQUEST_TTL, request(1, "carol", 200 + REQUEST_TTL)));
+}
@@ -0,0 +1,133 @@
+//! Gas sponsorship, where a sponsor pays the Ethereum gas for withdrawals to its users. With `--sponsors=<file>`
+//! batches with a transfer to a sponsored destination are always relayed, even if their token is disabled or, in
+//! marketplace mode, nobody requested them. Batches on the skip list are still skipped.
+//!
+//! We pay the gas as usual and the sponsors settle with us off chain, every sponsored batch we relay is appended
+//! to the ledger as a json line per sponsor with our transaction and the share of the batch's transfers that went
+//! to its users, the gas used can be looked up by the transaction hash.
+//!
+//! ```json
+//! {
+//!     "ledger": "/var/lib/relayer/sponsored.jsonl",
+//!     "sponsors": {
+//!         "acme": ["0x7580bFE88Dd3d07947908FAE12d95872a260F2D8"]
+//!     }
+//! }
+//! ```
+
+use clarity::{Address as EthAddress, Uint256};
+use peggy_utils::types::TransactionBatch;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize, Debug, Clone)]
+struct SponsorsFile {
+    ledger: PathBuf,
+    sponsors: HashMap<String, Vec<EthAddress>>,
+}
+
+/// The sponsors of withdrawals by destination
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Sponsors {
+    by_destination: HashMap<EthAddress, String>,
+    ledger: Option<PathBuf>,
+}
+
+/// One sponsor's share of a relayed batch
+#[derive(Serialize, Debug, Clone, Eq, PartialEq)]
+pub struct SponsoredRelay {
+    pub sponsor: String,
+    pub token_contract: EthAddress,
+    pub batch_nonce: u64,
+    pub tx_hash: String,
+    pub gas_price: Uint256,
+    pub gas_limit: Uint256,
+    /// the transfers to the sponsor's users out of all transfers in the batch
+    pub transfers: usize,
+    pub batch_transfers: usize,
+}
+
+impl Sponsors {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let file: SponsorsFile = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+        let mut by_destination = HashMap::new();
+        for (sponsor, destinations) in file.sponsors {
+            for destination in destinations {
+                if let Some(other) = by_destination.insert(destination, sponsor.clone()) {
+                    return Err(format!(
+                        "{} is sponsored by both {} and {}",
+                        destination, other, sponsor
+                    ));
+                }
+            }
+        }
+        Ok(Sponsors {
+            by_destination,
+            ledger: Some(file.ledger),
+        })
+    }
+
+    /// The sponsors of a batch and how many of its transfers each one covers
+    pub fn sponsors_of(&self, batch: &TransactionBatch) -> BTreeMap<String, usize> {
+        let mut out = BTreeMap::new();
+        for tx in batch.transactions.iter() {
+            if let Some(sponsor) = self.by_destination.get(&tx.destination) {
+                *out.entry(sponsor.clone()).or_insert(0) += 1;
+            }
+        }
+        out
+    }
+
+    pub fn covers(&self, batch: &TransactionBatch) -> bool {
+        batch
+            .transactions
+            .iter()
+            .any(|tx| self.by_destination.contains_key(&tx.destination))
+    }
+
+    /// The ledger entries for relaying `batch` with the given transaction
+    pub fn relays(
+        &self,
+        batch: &TransactionBatch,
+        tx_hash: &Uint256,
+        gas_price: &Uint256,
+        gas_limit: &Uint256,
+    ) -> Vec<SponsoredRelay> {
+        self.sponsors_of(batch)
+            .into_iter()
+            .map(|(sponsor, transfers)| SponsoredRelay {
+                sponsor,
+                token_contract: batch.token_contract,
+                batch_nonce: batch.nonce,
+                tx_hash: format!("{:#066x}", tx_hash),
+                gas_price: gas_price.clone(),
+                gas_limit: gas_limit.clone(),
+                transfers,
+                batch_transfers: batch.transactions.len(),
+            })
+            .collect()
+    }
+
+    pub fn record(&self, relays: &[SponsoredRelay]) -> Result<(), String> {
+        let path = match self.ledger.as_ref() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut lines = String::new();
+        for relay in relays {
+            lines.push_str(&serde_json::to_string(relay).unwrap());
+            lines.push('\n');
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
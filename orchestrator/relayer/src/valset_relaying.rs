@@ -6,22 +6,60 @@ use std::time::Duration;
 use clarity::address::Address as EthAddress;
 use clarity::PrivateKey as EthPrivateKey;
 use cosmos_peggy::query::get_all_valset_confirms;
+use cosmos_peggy::query::get_current_valset;
 use cosmos_peggy::query::get_latest_valsets;
+use ethereum_peggy::dry_run::DryRunOutput;
+use ethereum_peggy::utils::get_power_threshold;
 use ethereum_peggy::valset_update::send_eth_valset_update;
 use peggy_proto::peggy::query_client::QueryClient as PeggyQueryClient;
+use peggy_utils::types::{
+    passes_power_threshold, peggy_power_to_percent, SignatureSelection, Valset,
+    ValsetConfirmResponse,
+};
 use tonic::transport::Channel;
 use web30::client::Web3;
 
 use crate::find_latest_valset::find_latest_valset;
+use crate::main_loop::RelayedTx;
+
+/// Below this share of the Ethereum valset's power held by current Hub validators the overlap is warned about
+const OVERLAP_WARN_PERCENT: f32 = 80.0;
+/// Below this it's an error, a few more validator changes could take it across the 66% boundary
+const OVERLAP_CRITICAL_PERCENT: f32 = 72.0;
+
+/// Parses the overlap percentage below which valset updates are relayed regardless of gas
+pub fn parse_overlap_percent(input: &str) -> Result<f32, String> {
+    match input.trim().parse::<f32>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+        _ => Err(format!(
+            "Invalid valset overlap percentage {}, expected 0 to 100",
+            input
+        )),
+    }
+}
 
 /// Check the last validator set on Ethereum, if it's lower than our latest validator
-/// set then we should package and submit the update as an Ethereum transaction
+/// set then we should package and submit the update as an Ethereum transaction.
+///
+/// The contract only accepts a new valset signed by enough of the power of the valset it currently
+/// holds. After a large validator set change the newest Hub valset may not have that, in which case
+/// we hand off through the newest intermediate valset that does, one step per call.
+///
+/// Updates that change less than 1% of the power are normally skipped to save gas. With
+/// `force_relay_below` set they are relayed anyway once the overlap with the Hub valset drops
+/// below that percentage. With a `dry_run` the update is written there instead of sent. The update we
+/// send is added to `relayed`.
+#[allow(clippy::too_many_arguments)]
 pub async fn relay_valsets(
     ethereum_key: EthPrivateKey,
     web3: &Web3,
     grpc_client: &mut PeggyQueryClient<Channel>,
     peggy_contract_address: EthAddress,
     timeout: Duration,
+    signatures: SignatureSelection,
+    force_relay_below: Option<f32>,
+    dry_run: Option<&mut DryRunOutput>,
+    relayed: &mut Vec<RelayedTx>,
 ) {
     let our_ethereum_address = ethereum_key.to_public_key().unwrap();
 
@@ -35,24 +73,6 @@ pub async fn relay_valsets(
     }
     let latest_valsets = latest_valsets.unwrap();
 
-    let mut latest_confirmed = None;
-    let mut latest_valset = None;
-    for set in latest_valsets {
-        let confirms = get_all_valset_confirms(grpc_client, set.nonce).await;
-        if let Ok(confirms) = confirms {
-            latest_confirmed = Some(confirms);
-            latest_valset = Some(set);
-            break;
-        }
-    }
-
-    if latest_confirmed.is_none() {
-        error!("We don't have a latest confirmed valset?");
-        return;
-    }
-    let latest_cosmos_confirmed = latest_confirmed.unwrap();
-    let latest_cosmos_valset = latest_valset.unwrap();
-
     let current_valset = find_latest_valset(
         grpc_client,
         our_ethereum_address,
@@ -65,35 +85,262 @@ pub async fn relay_valsets(
         return;
     }
     let current_valset = current_valset.unwrap();
-    let latest_cosmos_valset_nonce = latest_cosmos_valset.nonce;
-    if latest_cosmos_valset_nonce > current_valset.nonce {
+    let overlap = check_valset_overlap(grpc_client, &current_valset).await;
+    let forced = match (force_relay_below, overlap) {
+        (Some(limit), Some(overlap)) => overlap < limit,
+        _ => false,
+    };
+
+    let mut newer_valsets: Vec<Valset> = latest_valsets
+        .into_iter()
+        .filter(|v| v.nonce > current_valset.nonce)
+        .collect();
+    newer_valsets.sort_by(|a, b| b.nonce.cmp(&a.nonce));
+    let target_nonce = match newer_valsets.first() {
+        Some(valset) => valset.nonce,
+        None => return,
+    };
+
+    let mut candidates = Vec::new();
+    for set in newer_valsets {
+        match get_all_valset_confirms(grpc_client, set.nonce).await {
+            Ok(confirms) => candidates.push((set, confirms)),
+            Err(e) => trace!("Failed to get confirms for valset {} {:?}", set.nonce, e),
+        }
+    }
+    let latest_confirmed = pick_valset_update(&current_valset, candidates);
+    let (latest_cosmos_valset, latest_cosmos_confirmed, signed_power) = match latest_confirmed {
+        Some(confirmed) => confirmed,
+        None => {
+            warn!(
+                "Valset handoff waiting, no Hub valset up to {} is signed by enough of the power of Ethereum valset {} yet",
+                target_nonce, current_valset.nonce
+            );
+            return;
+        }
+    };
+    let handoff = latest_cosmos_valset.nonce != target_nonce;
+
+    info!(
+        "We have detected latest valset {} but latest on Ethereum is {} sending an update!",
+        latest_cosmos_valset.nonce, current_valset.nonce
+    );
+    if handoff {
         info!(
-            "We have detected latest valset {} but latest on Ethereum is {} sending an update!",
-            latest_cosmos_valset.nonce, current_valset.nonce
+            "Valset handoff in progress, relaying intermediate valset {} on the way to {}, it is signed by {:.2}% of the power of Ethereum valset {}",
+            latest_cosmos_valset.nonce,
+            target_nonce,
+            peggy_power_to_percent(signed_power),
+            current_valset.nonce
         );
+    }
+
+    if forced {
+        warn!(
+            "Relaying valset {} regardless of gas, the overlap with the Hub valset is below {:.2}%",
+            latest_cosmos_valset.nonce,
+            force_relay_below.unwrap_or_default()
+        );
+    }
 
-        // If the ENV var NO_GAS_OPT is not set at compile time then the resulting binary will not
-        // have gas optimizations. In this case if we exit early if gas optimizations are enabled
-        // (the default value)
-        if option_env!("NO_GAS_OPT").is_none() {
-            let diff = current_valset.power_diff(&latest_cosmos_valset);
-            // if the power difference is less than one percent, skip updating
-            // the validator set
-            if diff < 0.01 {
-                info!("Difference in power between valset {} and {} is less than 1% skipping update to save gas", current_valset.nonce, latest_cosmos_valset.nonce);
+    // If the ENV var NO_GAS_OPT is not set at compile time then the resulting binary will not
+    // have gas optimizations. In this case if we exit early if gas optimizations are enabled
+    // (the default value). Handoff steps and forced updates are always relayed
+    if option_env!("NO_GAS_OPT").is_none() && !handoff && !forced {
+        let diff = current_valset.power_diff(&latest_cosmos_valset);
+        // if the power difference is less than one percent, skip updating
+        // the validator set
+        if diff < 0.01 {
+            info!("Difference in power between valset {} and {} is less than 1% skipping update to save gas", current_valset.nonce, latest_cosmos_valset.nonce);
+            return;
+        }
+    }
+
+    // a valset that can't pass the contract's threshold would be the last one the contract ever accepts
+    match get_power_threshold(peggy_contract_address, web3).await {
+        Ok(threshold) => {
+            let check = latest_cosmos_valset.power_check(threshold);
+            for issue in check.issues.iter() {
+                warn!("Valset {} power: {}", latest_cosmos_valset.nonce, issue);
+            }
+            if !check.passes() {
+                error!(
+                    "Not relaying valset {}, its signing power {} of total {} doesn't pass the contract threshold {}, the contract would never accept another update",
+                    latest_cosmos_valset.nonce, check.signing_power, check.total_power, threshold
+                );
                 return;
             }
         }
+        Err(e) => warn!(
+            "Failed to get the contract power threshold, relaying valset {} unchecked {:?}",
+            latest_cosmos_valset.nonce, e
+        ),
+    }
+
+    let nonce = latest_cosmos_valset.nonce;
+    let res = send_eth_valset_update(
+        latest_cosmos_valset,
+        current_valset,
+        &latest_cosmos_confirmed,
+        web3,
+        timeout,
+        peggy_contract_address,
+        ethereum_key,
+        signatures,
+        dry_run,
+    )
+    .await;
+    if let Ok(Some(tx_hash)) = res {
+        relayed.push(RelayedTx::Valset { nonce, tx_hash });
+    }
+}
+
+/// The newest of the `candidates`, sorted newest first, that enough of the power of `current_valset` has
+/// signed for the contract to accept it, with its confirms and the signed power
+pub fn pick_valset_update(
+    current_valset: &Valset,
+    candidates: Vec<(Valset, Vec<ValsetConfirmResponse>)>,
+) -> Option<(Valset, Vec<ValsetConfirmResponse>, u64)> {
+    for (set, confirms) in candidates {
+        let signed_power = current_valset.signed_power(&confirms);
+        if passes_power_threshold(signed_power) {
+            return Some((set, confirms, signed_power));
+        }
+        trace!(
+            "Valset {} is signed by {:.2}% of the power of Ethereum valset {}",
+            set.nonce,
+            peggy_power_to_percent(signed_power),
+            current_valset.nonce
+        );
+    }
+    None
+}
+
+/// Alerts when the validators of the Ethereum valset no longer hold a threshold of the power on the Hub.
+/// At that point the bridge funds are controlled by validators that may have unbonded and can't be slashed.
+/// The alerts escalate from a warning to an error as the overlap approaches the threshold. Returns the
+/// overlap as a percentage of the power, None if the Hub valset couldn't be read
+async fn check_valset_overlap(
+    grpc_client: &mut PeggyQueryClient<Channel>,
+    ethereum_valset: &Valset,
+) -> Option<f32> {
+    let hub_valset = match get_current_valset(grpc_client).await {
+        Ok(valset) => valset,
+        Err(e) => {
+            trace!("Failed to get the current Hub valset {:?}", e);
+            return None;
+        }
+    };
+    let overlap = ethereum_valset.overlap_power(&hub_valset);
+    let percent = peggy_power_to_percent(overlap);
+    if !passes_power_threshold(overlap) {
+        error!(
+            "VALSET OVERLAP BELOW THRESHOLD only {:.2}% of the power of Ethereum valset {} is held by current Hub validators. Validators that left the Hub still control the bridge funds and can't be slashed, the valset handoff must be relayed as soon as possible",
+            percent,
+            ethereum_valset.nonce
+        );
+    } else if percent < OVERLAP_CRITICAL_PERCENT {
+        error!(
+            "VALSET OVERLAP CRITICAL only {:.2}% of the power of Ethereum valset {} is held by current Hub validators, close to the 66% threshold. Relay the next valset soon",
+            percent,
+            ethereum_valset.nonce
+        );
+    } else if percent < OVERLAP_WARN_PERCENT {
+        warn!(
+            "Valset overlap {:.2}% of the power of Ethereum valset {} is held by current Hub validators, it is approaching the 66% threshold",
+            percent,
+            ethereum_valset.nonce
+        );
+    }
+    Some(percent)
+}
+
+#[test]
+fn test_pick_valset_update() {
+    use clarity::PrivateKey as EthPrivateKey;
+    use deep_space::address::Address as CosmosAddress;
+    use ethereum_peggy::contract_model::{PeggyContractModel, Revert};
+    use peggy_utils::types::{ValsetMember, ETHEREUM_POWER_THRESHOLD, TOTAL_PEGGY_POWER};
+
+    let address = |i: u8| EthAddress::from_slice(&[i; 20]).unwrap();
+    let valset = |nonce: u64, members: &[u8]| Valset {
+        nonce,
+        members: members
+            .iter()
+            .map(|i| ValsetMember {
+                power: TOTAL_PEGGY_POWER / members.len() as u64,
+                eth_address: Some(address(*i)),
+            })
+            .collect(),
+    };
+    let signature = EthPrivateKey::from_slice(&[1u8; 32])
+        .unwrap()
+        .sign_ethereum_msg(b"confirm");
+    let confirms = |nonce: u64, signers: &[u8]| -> Vec<ValsetConfirmResponse> {
+        signers
+            .iter()
+            .map(|i| ValsetConfirmResponse {
+                orchestrator: CosmosAddress::from_bytes([*i; 20]),
+                eth_address: address(*i),
+                nonce,
+                eth_signature: signature.clone(),
+            })
+            .collect()
+    };
+
+    // every Hub valset is signed by its own members, the last one shares a single validator with the first
+    let members: [&[u8]; 4] = [&[1, 2, 3, 4], &[1, 2, 3, 5], &[1, 2, 5, 6], &[1, 5, 6, 7]];
+    let hub: Vec<(Valset, Vec<ValsetConfirmResponse>)> = members
+        .iter()
+        .enumerate()
+        .map(|(nonce, members)| {
+            (
+                valset(nonce as u64, members),
+                confirms(nonce as u64, members),
+            )
+        })
+        .collect();
+    let mut contract = PeggyContractModel::new(hub[0].0.clone(), ETHEREUM_POWER_THRESHOLD);
+
+    let (newest, newest_confirms) = hub[3].clone();
+    assert_eq!(
+        contract
+            .clone()
+            .update_valset(&newest, &contract.valset.clone(), &newest_confirms),
+        Err(Revert::NotEnoughPower)
+    );
+
+    // the handoff goes through every intermediate valset and never sends an update the contract rejects
+    let mut relayed = Vec::new();
+    while contract.valset_nonce() < newest.nonce {
+        let current = contract.valset.clone();
+        let mut candidates: Vec<(Valset, Vec<ValsetConfirmResponse>)> = hub
+            .iter()
+            .filter(|(set, _)| set.nonce > current.nonce)
+            .cloned()
+            .collect();
+        candidates.reverse();
+        let (set, set_confirms, _) = pick_valset_update(&current, candidates).unwrap();
+        contract
+            .update_valset(&set, &current, &set_confirms)
+            .unwrap();
+        relayed.push(set.nonce);
+    }
+    assert_eq!(relayed, vec![1, 2, 3]);
 
-        let _res = send_eth_valset_update(
-            latest_cosmos_valset,
-            current_valset,
-            &latest_cosmos_confirmed,
-            web3,
-            timeout,
-            peggy_contract_address,
-            ethereum_key,
-        )
-        .await;
+    // every combination of signers is picked exactly when the contract accepts it
+    let current = valset(0, &[1, 2, 3, 4]);
+    let next = valset(1, &[1, 2, 3, 4]);
+    for mask in 0u8..16 {
+        let signers: Vec<u8> = (0..4)
+            .filter(|i| mask & (1 << i) != 0)
+            .map(|i| i + 1)
+            .collect();
+        let candidate = vec![(next.clone(), confirms(1, &signers))];
+        let picked = pick_valset_update(&current, candidate).is_some();
+        let accepted = PeggyContractModel::new(current.clone(), ETHEREUM_POWER_THRESHOLD)
+            .update_valset(&next, &current, &confirms(1, &signers))
+            .is_ok();
+        assert_eq!(picked, accepted, "signers {:?}", signers);
     }
 }